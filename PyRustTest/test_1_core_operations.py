@@ -100,6 +100,31 @@ def run_tests():
         })
         assert node_id is not None
     
+    def test_nested_properties_round_trip():
+        """Test list and dict properties round-trip through the bindings"""
+        storage = deepgraph.GraphStorage()
+        properties = {
+            "tags": ["a", "b", 3],
+            "address": {"city": "Paris", "geo": {"lat": 48.85, "lon": 2.35}},
+            "matrix": [[1, 2], [3, 4]],
+            "empty": [],
+        }
+        node_id = storage.add_node(["Test"], dict(properties, pair=(1, True)))
+        node = storage.get_node(node_id)
+        for key, value in properties.items():
+            assert node["properties"][key] == value
+        assert node["properties"]["pair"] == [1, True]
+        
+        other = storage.add_node(["Test"], {})
+        edge_id = storage.add_edge(node_id, other, "LINKS", {"weights": {"a": [0.5]}})
+        assert storage.get_edge(edge_id)["properties"]["weights"] == {"a": [0.5]}
+        
+        try:
+            storage.add_node(["Test"], {"bad": {1: "x"}})
+            assert False, "Expected ValueError for a non-string map key"
+        except ValueError:
+            pass
+    
     def test_add_node_unicode_properties():
         """Test node with Unicode characters"""
         storage = deepgraph.GraphStorage()
@@ -801,6 +826,88 @@ def run_tests():
         assert node is not None
         assert storage.node_count() == 1
     
+    # =============================================================================
+    # FEATURE 19: add_nodes() / add_edges() - Bulk insert
+    # =============================================================================
+    
+    def test_add_nodes_and_edges_bulk():
+        """Test bulk insert returning IDs in input order"""
+        storage = deepgraph.GraphStorage()
+        ids = storage.add_nodes([
+            {"labels": ["Person"], "properties": {"name": "Alice", "age": 30}},
+            {"labels": ["Person"], "properties": {"name": "Bob"}},
+            {},
+        ])
+        assert len(ids) == 3
+        assert storage.get_node(ids[0])["properties"]["name"] == "Alice"
+        
+        edge_ids = storage.add_edges([
+            {"from_id": ids[0], "to_id": ids[1], "label": "KNOWS", "properties": {"since": 2020}},
+            {"from_id": ids[1], "to_id": ids[2], "label": "KNOWS"},
+        ])
+        assert len(edge_ids) == 2
+        assert storage.node_count() == 3
+        assert storage.edge_count() == 2
+    
+    def test_add_edges_bulk_is_atomic():
+        """Test a rejected edge leaves the whole batch unapplied"""
+        storage = deepgraph.GraphStorage()
+        a, b = storage.add_nodes([{"labels": ["Person"]}, {"labels": ["Person"]}])
+        missing = "00000000-0000-0000-0000-000000000000"
+        try:
+            storage.add_edges([
+                {"from_id": a, "to_id": b, "label": "KNOWS"},
+                {"from_id": a, "to_id": missing, "label": "KNOWS"},
+            ])
+            assert False, "Expected an error for a missing endpoint"
+        except RuntimeError:
+            pass
+        assert storage.edge_count() == 0
+    
+    # =============================================================================
+    # FEATURE 20: nodes() / edges() / neighbors() - Lazy iteration
+    # =============================================================================
+    
+    def test_iterate_nodes_and_edges():
+        """Test lazy node and edge iterators with filters"""
+        storage = deepgraph.GraphStorage()
+        alice = storage.add_node(["Person"], {"name": "Alice"})
+        bob = storage.add_node(["Person"], {"name": "Bob"})
+        acme = storage.add_node(["Company"], {"name": "Acme"})
+        storage.add_edge(alice, bob, "KNOWS", {})
+        storage.add_edge(alice, acme, "WORKS_AT", {})
+        
+        people = iter(storage.nodes(label="Person"))
+        assert iter(people) is people
+        assert {node["properties"]["name"] for node in people} == {"Alice", "Bob"}
+        assert len(list(storage.nodes())) == 3
+        assert [edge["label"] for edge in storage.edges(relationship_type="KNOWS")] == ["KNOWS"]
+    
+    def test_iterate_neighbors():
+        """Test neighbors() in each direction"""
+        storage = deepgraph.GraphStorage()
+        alice = storage.add_node(["Person"], {"name": "Alice"})
+        bob = storage.add_node(["Person"], {"name": "Bob"})
+        carol = storage.add_node(["Person"], {"name": "Carol"})
+        storage.add_edge(alice, bob, "KNOWS", {})
+        storage.add_edge(alice, bob, "LIKES", {})
+        storage.add_edge(carol, alice, "KNOWS", {})
+        
+        assert [n["id"] for n in storage.neighbors(alice)] == [bob]
+        assert [n["id"] for n in storage.neighbors(alice, direction="in")] == [carol]
+        assert {n["id"] for n in storage.neighbors(alice, direction="both")} == {bob, carol}
+        
+        # Nodes deleted during iteration are skipped
+        neighbors = storage.neighbors(alice, direction="both")
+        storage.delete_node(carol)
+        assert [n["id"] for n in neighbors] == [bob]
+        
+        try:
+            storage.neighbors(alice, direction="sideways")
+            assert False, "Expected ValueError for an invalid direction"
+        except ValueError:
+            pass
+    
     # =============================================================================
     # STRESS TESTS
     # =============================================================================
@@ -850,6 +957,7 @@ def run_tests():
     run_test("test_add_node_no_properties", test_add_node_no_properties)
     run_test("test_add_node_empty", test_add_node_empty)
     run_test("test_add_node_all_property_types", test_add_node_all_property_types)
+    run_test("test_nested_properties_round_trip", test_nested_properties_round_trip)
     run_test("test_add_node_unicode_properties", test_add_node_unicode_properties)
     run_test("test_add_node_large_properties", test_add_node_large_properties)
     run_test("test_add_node_many_properties", test_add_node_many_properties)
@@ -944,6 +1052,12 @@ def run_tests():
     run_test("test_clear_empty", test_clear_empty)
     run_test("test_clear_and_reuse", test_clear_and_reuse)
     
+    run_test("test_add_nodes_and_edges_bulk", test_add_nodes_and_edges_bulk)
+    run_test("test_add_edges_bulk_is_atomic", test_add_edges_bulk_is_atomic)
+    
+    run_test("test_iterate_nodes_and_edges", test_iterate_nodes_and_edges)
+    run_test("test_iterate_neighbors", test_iterate_neighbors)
+    
     print()
     print("### Stress Tests")
     print()
@@ -6,6 +6,7 @@ Tests cover:
 - WAL.__init__() - Initialize Write-Ahead Log
 - WAL.flush() - Flush WAL to disk
 - WALRecovery.recover() - Recover from crash
+- GraphStorage.open() / open_with_config() - Persistent storage
 
 Each test includes data persistence, crash scenarios, and recovery validation.
 """
@@ -485,6 +486,75 @@ def run_tests():
         shutil.rmtree("./test_wal_trailing")
         shutil.rmtree("./test_wal_no_trailing")
     
+    # =============================================================================
+    # PERSISTENT STORAGE
+    # =============================================================================
+    
+    def test_open_persists_across_reopen():
+        """Test GraphStorage.open() keeps the graph after reopening"""
+        setup_test_dir()
+        
+        storage = deepgraph.GraphStorage.open(TEST_WAL_DIR)
+        alice = storage.add_node(["Person"], {"name": "Alice"})
+        storage.flush()
+        del storage
+        
+        reopened = deepgraph.GraphStorage.open(TEST_WAL_DIR)
+        assert reopened.node_count() == 1, "Node should survive reopening"
+        assert reopened.get_node(alice)["properties"]["name"] == "Alice"
+        del reopened
+        
+        cleanup_test_dir()
+    
+    def test_open_with_config_recovers_from_wal():
+        """Test GraphStorage.open_with_config() replays the WAL of a memory backend"""
+        setup_test_dir()
+        
+        config_path = os.path.join(TEST_WAL_DIR, "deepgraph.toml")
+        with open(config_path, "w") as f:
+            f.write(f"""
+[storage]
+storage_type = "memory"
+data_dir = "{TEST_WAL_DIR}"
+enable_cache = false
+cache_size_mb = 0
+
+[wal]
+enabled = true
+wal_dir = "wal"
+segment_size_mb = 64
+sync_on_write = true
+checkpoint_threshold = 1000
+
+[index]
+index_dir = "indices"
+auto_index = false
+default_index_type = "hash"
+
+[algorithm]
+pagerank_damping = 0.85
+pagerank_max_iterations = 100
+pagerank_tolerance = 0.000001
+node2vec_walk_length = 80
+node2vec_walks_per_node = 10
+louvain_max_iterations = 100
+
+[logging]
+level = "info"
+log_to_file = false
+log_to_console = true
+""")
+        
+        storage = deepgraph.GraphStorage.open_with_config(config_path)
+        storage.add_node(["Person"], {"name": "Alice"})
+        storage.flush()
+        del storage
+        
+        reopened = deepgraph.GraphStorage.open_with_config(config_path)
+        assert reopened.node_count() == 1, "WAL replay should restore the node"
+        
+        cleanup_test_dir()
+    
     # =============================================================================
     # RUN ALL TESTS
     # =============================================================================
@@ -540,6 +610,12 @@ def run_tests():
     run_test("test_recovery_then_continue", test_recovery_then_continue)
     run_test("test_wal_path_normalization", test_wal_path_normalization)
     
+    print()
+    print("### GraphStorage.open() - Persistent storage")
+    print()
+    run_test("test_open_persists_across_reopen", test_open_persists_across_reopen)
+    run_test("test_open_with_config_recovers_from_wal", test_open_with_config_recovers_from_wal)
+    
     # Final cleanup
     cleanup_test_dir()
     
@@ -14,6 +14,7 @@ Test Coverage:
 6. Comparison operators (=, !=, <, >, <=, >=)
 7. Empty results
 8. Error handling
+9. Query parameters
 """
 
 import sys
@@ -243,6 +244,39 @@ class TestCypherExecution(unittest.TestCase):
         result3 = self.storage.execute_cypher("MATCH (n:Company) RETURN n;")
         self.assertEqual(result3['row_count'], 1)
     
+    def test_execute_with_parameters(self):
+        """Test execute() binding $name parameters"""
+        result = self.storage.execute(
+            "MATCH (n:Person) WHERE n.age > $min_age AND n.city = $city RETURN n;",
+            {"min_age": 25, "city": "NYC"}
+        )
+        
+        self.assertEqual(result['row_count'], 2)
+        self.assertIn('columns', result)
+        names = {row['name'] for row in result['rows']}
+        self.assertEqual(names, {"Alice", "Charlie"})
+        
+        # Unbound parameters are rejected before the query runs
+        with self.assertRaises(RuntimeError) as context:
+            self.storage.execute("MATCH (n:Person) WHERE n.age > $min_age RETURN n;")
+        self.assertIn("Missing parameter: $min_age", str(context.exception))
+    
+    def test_execute_df(self):
+        """Test execute_df() returning a typed pyarrow Table"""
+        try:
+            import pyarrow as pa
+        except ImportError:
+            self.skipTest("pyarrow is not installed")
+        
+        table = self.storage.execute_df(
+            "MATCH (n:Person) WHERE n.city = $city RETURN n;", {"city": "NYC"}
+        )
+        
+        self.assertIsInstance(table, pa.Table)
+        self.assertEqual(table.num_rows, 2)
+        self.assertEqual(table.schema.field("age").type, pa.int64())
+        self.assertEqual(sorted(table.column("name").to_pylist()), ["Alice", "Charlie"])
+    
     def test_query_with_no_where_clause(self):
         """Test MATCH with label but no WHERE clause"""
         result = self.storage.execute_cypher("MATCH (n:Person) RETURN n;")
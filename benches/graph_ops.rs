@@ -1,10 +1,13 @@
 //! Performance benchmarks for DeepGraph operations
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use deepgraph::{GraphStorage, Node, Edge, PropertyValue};
-use deepgraph::index::IndexManager;
+use deepgraph::{GraphStorage, Node, NodeId, Edge, PropertyValue};
+use deepgraph::algorithms::{connected_components, triangle_count, triangle_count_streaming, weakly_connected_components};
+use deepgraph::import::CsvImporter;
+use deepgraph::index::{IndexConfig, IndexManager, IndexType};
 use deepgraph::mvcc::TransactionManager;
 use deepgraph::wal::{WAL, WALConfig, WALOperation};
+use rand::prelude::*;
 use tempfile::tempdir;
 
 fn bench_node_creation(c: &mut Criterion) {
@@ -191,42 +194,41 @@ fn bench_property_operations(c: &mut Criterion) {
 // Phase 2 benchmarks
 
 fn bench_hash_index_lookup(c: &mut Criterion) {
-    let dir = tempdir().unwrap();
-    let manager = IndexManager::new(dir.path().to_string_lossy().to_string()).unwrap();
+    let manager = IndexManager::new();
+    manager
+        .create_index(IndexConfig::property_index("person_name".to_string(), IndexType::Hash, "name".to_string()))
+        .unwrap();
     
     // Populate index
     for i in 0..10000 {
-        let key = format!("Person:{}", i);
-        manager.insert_hash("person_label", key.as_bytes(), i as u64).unwrap();
+        let key = PropertyValue::String(format!("Person:{}", i));
+        manager.insert_property("name", &key, NodeId::new()).unwrap();
     }
     
     c.bench_function("hash_index_lookup_10k", |b| {
+        let key = PropertyValue::String("Person:5000".to_string());
         b.iter(|| {
-            let key = "Person:5000";
-            manager.lookup_hash(black_box("person_label"), black_box(key.as_bytes())).unwrap();
+            manager.lookup_property(black_box("name"), black_box(&key)).unwrap();
         });
     });
 }
 
 fn bench_btree_range_query(c: &mut Criterion) {
-    let dir = tempdir().unwrap();
-    let manager = IndexManager::new(dir.path().to_string_lossy().to_string()).unwrap();
+    let manager = IndexManager::new();
+    manager
+        .create_index(IndexConfig::property_index("age_index".to_string(), IndexType::BTree, "age".to_string()))
+        .unwrap();
     
     // Populate B-tree
     for i in 0..10000 {
-        let key = format!("{:08}", i); // Zero-padded for lexicographic order
-        manager.insert_btree("age_index", key.as_bytes(), i as u64).unwrap();
+        manager.insert_property("age", &PropertyValue::Integer(i), NodeId::new()).unwrap();
     }
     
     c.bench_function("btree_range_query_10k", |b| {
+        let start = PropertyValue::Integer(2500);
+        let end = PropertyValue::Integer(7500);
         b.iter(|| {
-            let start = format!("{:08}", 2500);
-            let end = format!("{:08}", 7500);
-            manager.range_btree(
-                black_box("age_index"),
-                black_box(start.as_bytes()),
-                black_box(end.as_bytes())
-            ).unwrap();
+            manager.range_property(black_box("age"), black_box(&start), black_box(&end)).unwrap();
         });
     });
 }
@@ -306,12 +308,13 @@ fn bench_index_vs_scan(c: &mut Criterion) {
     }
     
     // Setup index
-    let dir = tempdir().unwrap();
-    let manager = IndexManager::new(dir.path().to_string_lossy().to_string()).unwrap();
+    let manager = IndexManager::new();
+    manager
+        .create_index(IndexConfig::property_index("person_id".to_string(), IndexType::Hash, "id".to_string()))
+        .unwrap();
     
-    for (i, node) in storage.get_nodes_by_label("Person").iter().enumerate() {
-        let key = format!("Person:{}", i);
-        manager.insert_hash("person_id", key.as_bytes(), node.id().0).unwrap();
+    for node in storage.get_nodes_by_label("Person") {
+        manager.insert_property("id", node.get_property("id").unwrap(), node.id()).unwrap();
     }
     
     // Benchmark table scan
@@ -323,15 +326,94 @@ fn bench_index_vs_scan(c: &mut Criterion) {
     
     // Benchmark index lookup
     group.bench_function("index_lookup_1000", |b| {
+        let key = PropertyValue::Integer(500);
         b.iter(|| {
-            let key = "Person:500";
-            manager.lookup_hash(black_box("person_id"), black_box(key.as_bytes())).unwrap();
+            manager.lookup_property(black_box("id"), black_box(&key)).unwrap();
         });
     });
     
     group.finish();
 }
 
+fn bench_connected_components(c: &mut Criterion) {
+    let mut group = c.benchmark_group("connected_components");
+    group.sample_size(10);
+
+    // 250k nodes and 1M random edges
+    let storage = GraphStorage::new();
+    let ids: Vec<_> = (0..250_000)
+        .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+        .collect();
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..1_000_000 {
+        let from = ids[rng.gen_range(0..ids.len())];
+        let to = ids[rng.gen_range(0..ids.len())];
+        storage.add_edge(Edge::new(from, to, "LINKS".to_string())).unwrap();
+    }
+
+    group.bench_function("bfs_1m_edges", |b| {
+        b.iter(|| connected_components(black_box(&storage)).unwrap());
+    });
+
+    group.bench_function("union_find_1m_edges", |b| {
+        b.iter(|| weakly_connected_components(black_box(&storage)).unwrap());
+    });
+
+    group.finish();
+}
+
+fn bench_triangle_count(c: &mut Criterion) {
+    let mut group = c.benchmark_group("triangle_count");
+    group.sample_size(10);
+
+    // 20k nodes and 200k random edges
+    let storage = GraphStorage::new();
+    let ids: Vec<_> = (0..20_000)
+        .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+        .collect();
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..200_000 {
+        let from = ids[rng.gen_range(0..ids.len())];
+        let to = ids[rng.gen_range(0..ids.len())];
+        storage.add_edge(Edge::new(from, to, "LINKS".to_string())).unwrap();
+    }
+
+    group.bench_function("csr_200k_edges", |b| {
+        b.iter(|| triangle_count(black_box(&storage)).unwrap());
+    });
+
+    group.bench_function("streaming_200k_edges", |b| {
+        b.iter(|| triangle_count_streaming(black_box(&storage)).unwrap());
+    });
+
+    group.finish();
+}
+
+fn bench_csv_import(c: &mut Criterion) {
+    let mut group = c.benchmark_group("csv_import");
+    group.sample_size(10);
+
+    // 200k nodes with four inferred properties each
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("nodes.csv");
+    let mut csv = String::from("id,labels,name,age,score,active\n");
+    for i in 0..200_000 {
+        csv.push_str(&format!("{},Person,user{},{},{}.25,{}\n", i, i, i % 90, i, i % 2 == 0));
+    }
+    std::fs::write(&path, csv).unwrap();
+    let importer = CsvImporter::new();
+
+    group.bench_function("sequential_200k_nodes", |b| {
+        b.iter(|| importer.import_nodes(&GraphStorage::new(), black_box(&path)).unwrap());
+    });
+
+    group.bench_function("parallel_200k_nodes", |b| {
+        b.iter(|| importer.import_nodes_parallel(&GraphStorage::new(), black_box(&path)).unwrap());
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_node_creation,
@@ -351,6 +433,9 @@ criterion_group!(
     bench_mvcc_transaction,
     bench_mvcc_concurrent_transactions,
     bench_index_vs_scan,
+    bench_connected_components,
+    bench_triangle_count,
+    bench_csv_import,
 );
 
 criterion_main!(benches);
@@ -1,9 +1,28 @@
-//! Centrality algorithms (PageRank, etc.)
+//! Centrality algorithms (PageRank, HITS, eigenvector, Katz, betweenness,
+//! closeness)
+//!
+//! Betweenness and closeness need a breadth-first search per source node,
+//! which is quadratic on large graphs. Both accept a [`CentralityConfig`]
+//! whose `sample_size` runs the searches from that many random sources
+//! only and extrapolates, trading accuracy for time.
 
-use crate::error::Result;
+use crate::algorithms::csr::CsrGraph;
+use crate::config::AlgorithmConfig;
+use crate::error::{DeepGraphError, Result};
 use crate::graph::NodeId;
-use crate::storage::GraphStorage;
-use std::collections::HashMap;
+use crate::storage::StorageBackend;
+use rand::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+/// Sort `scores` descending and keep the first `n`
+///
+/// Uses the IEEE total order, so a NaN score sorts first instead of
+/// panicking.
+fn top_scores(scores: &HashMap<NodeId, f64>, n: usize) -> Vec<(NodeId, f64)> {
+    let mut scores: Vec<_> = scores.iter().map(|(&k, &v)| (k, v)).collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scores.into_iter().take(n).collect()
+}
 
 /// Result of PageRank algorithm
 #[derive(Debug, Clone)]
@@ -19,9 +38,7 @@ pub struct PageRankResult {
 impl PageRankResult {
     /// Get top N nodes by PageRank score
     pub fn top_nodes(&self, n: usize) -> Vec<(NodeId, f64)> {
-        let mut scores: Vec<_> = self.scores.iter().map(|(&k, &v)| (k, v)).collect();
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        scores.into_iter().take(n).collect()
+        top_scores(&self.scores, n)
     }
 }
 
@@ -50,94 +67,657 @@ impl PageRankResult {
 /// let top10 = result.top_nodes(10);
 /// println!("Top 10 nodes by PageRank: {:?}", top10);
 /// ```
-pub fn pagerank(
-    storage: &GraphStorage,
+pub fn pagerank<S: StorageBackend + ?Sized>(
+    storage: &S,
     damping_factor: f64,
     max_iterations: usize,
     tolerance: f64,
 ) -> Result<PageRankResult> {
-    let all_nodes = storage.get_all_nodes();
-    let num_nodes = all_nodes.len();
+    let csr = CsrGraph::from_storage(storage);
+    Ok(pagerank_csr(&csr, damping_factor, max_iterations, tolerance))
+}
+
+/// PageRank over a prebuilt [`CsrGraph`] snapshot
+///
+/// Use this to run several analytics passes over one snapshot without
+/// re-reading the graph from storage.
+pub fn pagerank_csr(
+    csr: &CsrGraph,
+    damping_factor: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> PageRankResult {
+    let num_nodes = csr.node_count();
 
     if num_nodes == 0 {
-        return Ok(PageRankResult {
+        return PageRankResult {
             scores: HashMap::new(),
             iterations: 0,
             converged: true,
-        });
+        };
     }
 
-    let initial_rank = 1.0 / num_nodes as f64;
-    let mut ranks: HashMap<NodeId, f64> = all_nodes
-        .iter()
-        .map(|node| (node.id(), initial_rank))
-        .collect();
-
-    let mut new_ranks = ranks.clone();
+    let mut ranks = vec![1.0 / num_nodes as f64; num_nodes];
+    let mut new_ranks = vec![0.0; num_nodes];
+    let base = (1.0 - damping_factor) / num_nodes as f64;
+    let mut iterations = max_iterations;
     let mut converged = false;
 
-    // Precompute outgoing edge counts
-    let mut out_degrees: HashMap<NodeId, usize> = HashMap::new();
-    for node in &all_nodes {
-        let node_id = node.id();
-        let out_edges = storage.get_outgoing_edges(node_id).unwrap_or_default();
-        out_degrees.insert(node_id, out_edges.len());
-    }
-
     for iteration in 0..max_iterations {
         let mut max_diff: f64 = 0.0;
 
-        // Calculate new PageRank for each node
-        for node in &all_nodes {
-            let node_id = node.id();
-            let mut rank_sum = 0.0;
-
-            // Sum contributions from incoming edges
-            if let Ok(incoming_edges) = storage.get_incoming_edges(node_id) {
-                for edge in incoming_edges {
-                    let from_node = edge.from();
-                    let from_rank = ranks.get(&from_node).copied().unwrap_or(0.0);
-                    let from_out_degree = out_degrees.get(&from_node).copied().unwrap_or(1);
-
-                    if from_out_degree > 0 {
-                        rank_sum += from_rank / from_out_degree as f64;
-                    }
-                }
-            }
+        // Sum contributions from incoming edges
+        for (node, new_rank) in new_ranks.iter_mut().enumerate() {
+            let rank_sum: f64 = csr
+                .in_neighbors(node as u32)
+                .iter()
+                .map(|&from| ranks[from as usize] / csr.out_degree(from) as f64)
+                .sum();
+            *new_rank = base + damping_factor * rank_sum;
+            max_diff = max_diff.max((*new_rank - ranks[node]).abs());
+        }
 
-            // Apply PageRank formula
-            let new_rank = (1.0 - damping_factor) / num_nodes as f64 + damping_factor * rank_sum;
-            new_ranks.insert(node_id, new_rank);
+        std::mem::swap(&mut ranks, &mut new_ranks);
 
-            // Track convergence
-            let diff = (new_rank - ranks.get(&node_id).unwrap()).abs();
-            max_diff = max_diff.max(diff);
+        if max_diff < tolerance {
+            iterations = iteration + 1;
+            converged = true;
+            break;
         }
+    }
+
+    PageRankResult {
+        scores: csr.node_ids().iter().copied().zip(ranks).collect(),
+        iterations,
+        converged,
+    }
+}
+
+/// Result of the HITS algorithm
+#[derive(Debug, Clone)]
+pub struct HitsResult {
+    /// Hub score for each node: how well it points at good authorities
+    pub hubs: HashMap<NodeId, f64>,
+    /// Authority score for each node: how well it is pointed at by good hubs
+    pub authorities: HashMap<NodeId, f64>,
+    /// Number of iterations performed
+    pub iterations: usize,
+    /// Whether the algorithm converged
+    pub converged: bool,
+}
+
+impl HitsResult {
+    /// Get top N nodes by hub score
+    pub fn top_hubs(&self, n: usize) -> Vec<(NodeId, f64)> {
+        top_scores(&self.hubs, n)
+    }
+
+    /// Get top N nodes by authority score
+    pub fn top_authorities(&self, n: usize) -> Vec<(NodeId, f64)> {
+        top_scores(&self.authorities, n)
+    }
+}
 
-        // Update ranks
-        ranks = new_ranks.clone();
+/// HITS (hubs and authorities) algorithm
+///
+/// Iterates authority scores as the sum of the hub scores of a node's
+/// predecessors and hub scores as the sum of the authority scores of its
+/// successors, normalizing both to unit length each round.
+///
+/// # Arguments
+/// * `storage` - Graph storage
+/// * `max_iterations` - Maximum number of iterations
+/// * `tolerance` - Convergence tolerance
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::hits;
+///
+/// let result = hits(&storage, 100, 1e-8)?;
+/// println!("Top authorities: {:?}", result.top_authorities(10));
+/// ```
+pub fn hits<S: StorageBackend + ?Sized>(storage: &S, max_iterations: usize, tolerance: f64) -> Result<HitsResult> {
+    let csr = CsrGraph::from_storage(storage);
+    Ok(hits_csr(&csr, max_iterations, tolerance))
+}
+
+/// HITS over a prebuilt [`CsrGraph`] snapshot
+pub fn hits_csr(csr: &CsrGraph, max_iterations: usize, tolerance: f64) -> HitsResult {
+    let num_nodes = csr.node_count();
+
+    if num_nodes == 0 {
+        return HitsResult {
+            hubs: HashMap::new(),
+            authorities: HashMap::new(),
+            iterations: 0,
+            converged: true,
+        };
+    }
+
+    let initial = 1.0 / (num_nodes as f64).sqrt();
+    let mut hubs = vec![initial; num_nodes];
+    let mut authorities = vec![initial; num_nodes];
+    let mut iterations = max_iterations;
+    let mut converged = false;
+
+    for iteration in 0..max_iterations {
+        let mut new_authorities: Vec<f64> = (0..num_nodes as u32)
+            .map(|node| csr.in_neighbors(node).iter().map(|&from| hubs[from as usize]).sum())
+            .collect();
+        normalize_l2(&mut new_authorities);
+        let mut new_hubs: Vec<f64> = (0..num_nodes as u32)
+            .map(|node| csr.out_neighbors(node).iter().map(|&to| new_authorities[to as usize]).sum())
+            .collect();
+        normalize_l2(&mut new_hubs);
+
+        let max_diff = hubs
+            .iter()
+            .zip(&new_hubs)
+            .chain(authorities.iter().zip(&new_authorities))
+            .map(|(old, new)| (old - new).abs())
+            .fold(0.0, f64::max);
+        hubs = new_hubs;
+        authorities = new_authorities;
 
-        // Check convergence
         if max_diff < tolerance {
+            iterations = iteration + 1;
             converged = true;
-            return Ok(PageRankResult {
-                scores: ranks,
-                iterations: iteration + 1,
-                converged,
-            });
+            break;
         }
     }
 
-    Ok(PageRankResult {
-        scores: ranks,
-        iterations: max_iterations,
+    HitsResult {
+        hubs: csr.node_ids().iter().copied().zip(hubs).collect(),
+        authorities: csr.node_ids().iter().copied().zip(authorities).collect(),
+        iterations,
+        converged,
+    }
+}
+
+/// Result of eigenvector centrality
+#[derive(Debug, Clone)]
+pub struct EigenvectorResult {
+    /// Eigenvector centrality score for each node, unit length overall
+    pub scores: HashMap<NodeId, f64>,
+    /// Number of iterations performed
+    pub iterations: usize,
+    /// Whether the algorithm converged
+    pub converged: bool,
+}
+
+impl EigenvectorResult {
+    /// Get top N nodes by eigenvector centrality
+    pub fn top_nodes(&self, n: usize) -> Vec<(NodeId, f64)> {
+        top_scores(&self.scores, n)
+    }
+}
+
+/// Result of Katz centrality
+#[derive(Debug, Clone)]
+pub struct KatzResult {
+    /// Katz centrality score for each node, unit length overall
+    pub scores: HashMap<NodeId, f64>,
+    /// Number of iterations performed
+    pub iterations: usize,
+    /// Whether the algorithm converged
+    pub converged: bool,
+}
+
+impl KatzResult {
+    /// Get top N nodes by Katz centrality
+    pub fn top_nodes(&self, n: usize) -> Vec<(NodeId, f64)> {
+        top_scores(&self.scores, n)
+    }
+}
+
+/// Scale `scores` to unit Euclidean length
+fn normalize_l2(scores: &mut [f64]) {
+    let norm = scores.iter().map(|s| s * s).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        scores.iter_mut().for_each(|s| *s /= norm);
+    }
+}
+
+/// Iterate `step` from `initial` until no score moves by `tolerance`,
+/// returning the scores, iterations and whether they converged
+fn power_iterate(
+    initial: Vec<f64>,
+    max_iterations: usize,
+    tolerance: f64,
+    mut step: impl FnMut(&[f64]) -> Vec<f64>,
+) -> (Vec<f64>, usize, bool) {
+    let mut scores = initial;
+    for iteration in 0..max_iterations {
+        let next = step(&scores);
+        let max_diff = max_change(&scores, &next);
+        scores = next;
+        if max_diff < tolerance {
+            return (scores, iteration + 1, true);
+        }
+    }
+    (scores, max_iterations, false)
+}
+
+/// Largest absolute difference between two score vectors
+fn max_change(old: &[f64], new: &[f64]) -> f64 {
+    old.iter()
+        .zip(new)
+        .map(|(old, new)| (old - new).abs())
+        .fold(0.0, f64::max)
+}
+
+/// Eigenvector centrality
+///
+/// Scores each node in proportion to the sum of the scores of its
+/// predecessors, i.e. the principal eigenvector of the transposed adjacency
+/// matrix, found by power iteration. Iterating `x + Aᵀx` rather than `Aᵀx`
+/// keeps the same eigenvector but also converges on bipartite graphs.
+///
+/// # Arguments
+/// * `storage` - Graph storage
+/// * `max_iterations` - Maximum number of iterations
+/// * `tolerance` - Convergence tolerance
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::eigenvector_centrality;
+///
+/// let result = eigenvector_centrality(&storage, 100, 1e-6)?;
+/// println!("Most influential: {:?}", result.top_nodes(10));
+/// ```
+pub fn eigenvector_centrality<S: StorageBackend + ?Sized>(
+    storage: &S,
+    max_iterations: usize,
+    tolerance: f64,
+) -> Result<EigenvectorResult> {
+    let csr = CsrGraph::from_storage(storage);
+    Ok(eigenvector_centrality_csr(&csr, max_iterations, tolerance))
+}
+
+/// Eigenvector centrality over a prebuilt [`CsrGraph`] snapshot
+pub fn eigenvector_centrality_csr(csr: &CsrGraph, max_iterations: usize, tolerance: f64) -> EigenvectorResult {
+    let num_nodes = csr.node_count();
+    let initial = vec![1.0 / (num_nodes.max(1) as f64).sqrt(); num_nodes];
+    let (scores, iterations, converged) = power_iterate(initial, max_iterations, tolerance, |scores| {
+        let mut next: Vec<f64> = (0..num_nodes as u32)
+            .map(|node| {
+                let incoming: f64 = csr
+                    .in_neighbors(node)
+                    .iter()
+                    .zip(csr.in_weights(node))
+                    .map(|(&from, &weight)| scores[from as usize] * weight)
+                    .sum();
+                scores[node as usize] + incoming
+            })
+            .collect();
+        normalize_l2(&mut next);
+        next
+    });
+
+    EigenvectorResult {
+        scores: csr.node_ids().iter().copied().zip(scores).collect(),
+        iterations,
+        converged,
+    }
+}
+
+/// Katz centrality
+///
+/// Solves `x = alpha * Aᵀx + beta` by power iteration, so every node gets
+/// `beta` for free plus `alpha`-attenuated credit for paths leading to it.
+/// `alpha` must be below the reciprocal of the largest eigenvalue of the
+/// adjacency matrix for the iteration to converge; otherwise the scores
+/// grow without bound and an error is returned. The scores are scaled to
+/// unit length at the end.
+///
+/// # Arguments
+/// * `storage` - Graph storage
+/// * `alpha` - Attenuation factor (typically 0.1)
+/// * `beta` - Base score of every node (typically 1.0)
+/// * `max_iterations` - Maximum number of iterations
+/// * `tolerance` - Convergence tolerance
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::katz_centrality;
+///
+/// let result = katz_centrality(&storage, 0.1, 1.0, 1000, 1e-6)?;
+/// ```
+pub fn katz_centrality<S: StorageBackend + ?Sized>(
+    storage: &S,
+    alpha: f64,
+    beta: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> Result<KatzResult> {
+    let csr = CsrGraph::from_storage(storage);
+    katz_centrality_csr(&csr, alpha, beta, max_iterations, tolerance)
+}
+
+/// Katz centrality over a prebuilt [`CsrGraph`] snapshot
+pub fn katz_centrality_csr(
+    csr: &CsrGraph,
+    alpha: f64,
+    beta: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> Result<KatzResult> {
+    let num_nodes = csr.node_count();
+    let step = |scores: &[f64]| -> Vec<f64> {
+        (0..num_nodes as u32)
+            .map(|node| {
+                let incoming: f64 = csr
+                    .in_neighbors(node)
+                    .iter()
+                    .zip(csr.in_weights(node))
+                    .map(|(&from, &weight)| scores[from as usize] * weight)
+                    .sum();
+                alpha * incoming + beta
+            })
+            .collect()
+    };
+    let (mut scores, iterations, converged) = power_iterate(vec![0.0; num_nodes], max_iterations, tolerance, step);
+
+    // Each step shrinks the change by about alpha * λ, so a change that
+    // no longer shrinks means the series diverges
+    let diverged = !scores.iter().all(|s| s.is_finite()) || (!converged && {
+        let once = step(&scores);
+        let change = max_change(&scores, &once);
+        change > 0.0 && max_change(&once, &step(&once)) >= change
+    });
+    if diverged {
+        return Err(DeepGraphError::InvalidOperation(format!(
+            "Katz centrality diverged: alpha {} is not below 1 / the largest eigenvalue of the graph",
+            alpha
+        )));
+    }
+    normalize_l2(&mut scores);
+
+    Ok(KatzResult {
+        scores: csr.node_ids().iter().copied().zip(scores).collect(),
+        iterations,
         converged,
     })
 }
 
+/// Configuration for betweenness and closeness centrality
+#[derive(Debug, Clone)]
+pub struct CentralityConfig {
+    /// Number of random source nodes to search from (`None` = all nodes)
+    pub sample_size: Option<usize>,
+    /// Random seed for reproducible sampling
+    pub seed: Option<u64>,
+    /// Scale betweenness scores into [0, 1]
+    pub normalized: bool,
+}
+
+impl Default for CentralityConfig {
+    fn default() -> Self {
+        Self {
+            sample_size: None,
+            seed: None,
+            normalized: true,
+        }
+    }
+}
+
+impl CentralityConfig {
+    /// Default settings seeded from the `[algorithm]` configuration section
+    pub fn from_config(config: &AlgorithmConfig) -> Self {
+        Self {
+            seed: config.seed,
+            ..Self::default()
+        }
+    }
+
+    /// Create a configuration computing exact, normalized scores
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Search from `sample_size` random sources only
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = Some(sample_size);
+        self
+    }
+
+    /// Seed the source sampling
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Whether to normalize betweenness scores
+    pub fn with_normalized(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        self
+    }
+
+    /// Dense indices of the source nodes to search from
+    fn sources(&self, num_nodes: usize) -> Vec<u32> {
+        match self.sample_size {
+            Some(k) if k < num_nodes => {
+                let mut rng: StdRng = match self.seed {
+                    Some(seed) => StdRng::seed_from_u64(seed),
+                    None => StdRng::from_entropy(),
+                };
+                rand::seq::index::sample(&mut rng, num_nodes, k)
+                    .into_iter()
+                    .map(|i| i as u32)
+                    .collect()
+            }
+            _ => (0..num_nodes as u32).collect(),
+        }
+    }
+}
+
+/// Result of betweenness centrality
+#[derive(Debug, Clone)]
+pub struct BetweennessResult {
+    /// Betweenness score for each node
+    pub scores: HashMap<NodeId, f64>,
+    /// Number of source nodes searched from
+    pub sources: usize,
+    /// Whether the scores are estimated from a sample of sources
+    pub sampled: bool,
+}
+
+impl BetweennessResult {
+    /// Get top N nodes by betweenness score
+    pub fn top_nodes(&self, n: usize) -> Vec<(NodeId, f64)> {
+        top_scores(&self.scores, n)
+    }
+}
+
+/// Result of closeness centrality
+#[derive(Debug, Clone)]
+pub struct ClosenessResult {
+    /// Closeness score for each node
+    pub scores: HashMap<NodeId, f64>,
+    /// Number of source nodes searched from
+    pub sources: usize,
+    /// Whether the scores are estimated from a sample of sources
+    pub sampled: bool,
+}
+
+impl ClosenessResult {
+    /// Get top N nodes by closeness score
+    pub fn top_nodes(&self, n: usize) -> Vec<(NodeId, f64)> {
+        top_scores(&self.scores, n)
+    }
+}
+
+/// Betweenness centrality (Brandes' algorithm)
+///
+/// Scores each node by the shortest directed paths between other nodes
+/// that pass through it. Edges are unweighted. With `config.sample_size`
+/// set, paths are counted from a random sample of sources and the scores
+/// scaled up by `n / sample_size`.
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::{betweenness_centrality, CentralityConfig};
+///
+/// let config = CentralityConfig::new().with_sample_size(256).with_seed(42);
+/// let result = betweenness_centrality(&storage, &config)?;
+/// println!("Brokers: {:?}", result.top_nodes(10));
+/// ```
+pub fn betweenness_centrality<S: StorageBackend + ?Sized>(storage: &S, config: &CentralityConfig) -> Result<BetweennessResult> {
+    let csr = CsrGraph::from_storage(storage);
+    Ok(betweenness_centrality_csr(&csr, config))
+}
+
+/// Betweenness centrality over a prebuilt [`CsrGraph`] snapshot
+pub fn betweenness_centrality_csr(csr: &CsrGraph, config: &CentralityConfig) -> BetweennessResult {
+    let num_nodes = csr.node_count();
+    let sources = config.sources(num_nodes);
+    let mut centrality = vec![0.0; num_nodes];
+
+    let mut order = Vec::with_capacity(num_nodes);
+    let mut queue = VecDeque::new();
+    let mut predecessors: Vec<Vec<u32>> = vec![Vec::new(); num_nodes];
+    let mut paths = vec![0.0f64; num_nodes];
+    let mut distance = vec![-1i64; num_nodes];
+    let mut dependency = vec![0.0f64; num_nodes];
+
+    for &source in &sources {
+        order.clear();
+        predecessors.iter_mut().for_each(Vec::clear);
+        paths.iter_mut().for_each(|p| *p = 0.0);
+        distance.iter_mut().for_each(|d| *d = -1);
+        dependency.iter_mut().for_each(|d| *d = 0.0);
+
+        // Count shortest paths from the source
+        paths[source as usize] = 1.0;
+        distance[source as usize] = 0;
+        queue.push_back(source);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            let n = node as usize;
+            for &next in csr.out_neighbors(node) {
+                let v = next as usize;
+                if distance[v] < 0 {
+                    distance[v] = distance[n] + 1;
+                    queue.push_back(next);
+                }
+                if distance[v] == distance[n] + 1 {
+                    paths[v] += paths[n];
+                    predecessors[v].push(node);
+                }
+            }
+        }
+
+        // Accumulate dependencies in order of decreasing distance
+        for &node in order.iter().rev() {
+            let w = node as usize;
+            for &pred in &predecessors[w] {
+                let v = pred as usize;
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if node != source {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+
+    let sampled = sources.len() < num_nodes;
+    let mut scale = if sampled && !sources.is_empty() {
+        num_nodes as f64 / sources.len() as f64
+    } else {
+        1.0
+    };
+    if config.normalized && num_nodes > 2 {
+        scale /= ((num_nodes - 1) * (num_nodes - 2)) as f64;
+    }
+
+    BetweennessResult {
+        scores: csr
+            .node_ids()
+            .iter()
+            .copied()
+            .zip(centrality.into_iter().map(|c| c * scale))
+            .collect(),
+        sources: sources.len(),
+        sampled,
+    }
+}
+
+/// Closeness centrality
+///
+/// Scores each node by how close it is to the nodes it can reach along
+/// outgoing edges. The Wasserman-Faust formula `(r / (n - 1)) * (r / d)`,
+/// for a node reaching `r` other nodes at total distance `d`, keeps scores
+/// comparable on disconnected graphs; a node reaching nothing scores 0.
+/// With `config.sample_size` set, distances are measured to a random
+/// sample of target nodes only.
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::{closeness_centrality, CentralityConfig};
+///
+/// let result = closeness_centrality(&storage, &CentralityConfig::new())?;
+/// println!("Most central: {:?}", result.top_nodes(10));
+/// ```
+pub fn closeness_centrality<S: StorageBackend + ?Sized>(storage: &S, config: &CentralityConfig) -> Result<ClosenessResult> {
+    let csr = CsrGraph::from_storage(storage);
+    Ok(closeness_centrality_csr(&csr, config))
+}
+
+/// Closeness centrality over a prebuilt [`CsrGraph`] snapshot
+pub fn closeness_centrality_csr(csr: &CsrGraph, config: &CentralityConfig) -> ClosenessResult {
+    let num_nodes = csr.node_count();
+    let sources = config.sources(num_nodes);
+    let mut total_distance = vec![0u64; num_nodes];
+    let mut reached = vec![0u64; num_nodes];
+    let mut is_source = vec![false; num_nodes];
+
+    // Searching backwards along incoming edges from each sampled node gives
+    // every node's distance to it
+    let mut distance = vec![u64::MAX; num_nodes];
+    let mut queue = VecDeque::new();
+    for &source in &sources {
+        is_source[source as usize] = true;
+        distance.iter_mut().for_each(|d| *d = u64::MAX);
+        distance[source as usize] = 0;
+        queue.push_back(source);
+        while let Some(node) = queue.pop_front() {
+            let d = distance[node as usize] + 1;
+            for &prev in csr.in_neighbors(node) {
+                let v = prev as usize;
+                if distance[v] == u64::MAX {
+                    distance[v] = d;
+                    total_distance[v] += d;
+                    reached[v] += 1;
+                    queue.push_back(prev);
+                }
+            }
+        }
+    }
+
+    let scores = (0..num_nodes)
+        .map(|v| {
+            let targets = sources.len() - usize::from(is_source[v]);
+            let score = if total_distance[v] == 0 || targets == 0 {
+                0.0
+            } else {
+                let r = reached[v] as f64;
+                (r / targets as f64) * (r / total_distance[v] as f64)
+            };
+            (csr.node_id(v as u32), score)
+        })
+        .collect();
+
+    ClosenessResult {
+        scores,
+        sources: sources.len(),
+        sampled: sources.len() < num_nodes,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::GraphStorage;
     use crate::graph::Node;
 
     #[test]
@@ -174,5 +754,137 @@ mod tests {
         assert!(rank3 > rank2);
         assert!(result.converged);
     }
-}
 
+    fn path_graph(len: usize) -> (GraphStorage, Vec<NodeId>) {
+        let storage = GraphStorage::new();
+        let ids: Vec<_> = (0..len)
+            .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+            .collect();
+        for pair in ids.windows(2) {
+            storage
+                .add_edge_simple(pair[0], pair[1], "LINKS".to_string())
+                .unwrap();
+        }
+        (storage, ids)
+    }
+
+    #[test]
+    fn test_betweenness_path() {
+        // 0 -> 1 -> 2 -> 3: node 1 lies on 0->2 and 0->3, node 2 on 0->3 and 1->3
+        let (storage, ids) = path_graph(4);
+        let config = CentralityConfig::new().with_normalized(false);
+        let result = betweenness_centrality(&storage, &config).unwrap();
+        assert!(!result.sampled);
+        assert_eq!(result.scores[&ids[0]], 0.0);
+        assert_eq!(result.scores[&ids[1]], 2.0);
+        assert_eq!(result.scores[&ids[2]], 2.0);
+        assert_eq!(result.scores[&ids[3]], 0.0);
+
+        let normalized = betweenness_centrality(&storage, &CentralityConfig::new()).unwrap();
+        assert!((normalized.scores[&ids[1]] - 2.0 / 6.0).abs() < 1e-9);
+
+        let sampled = betweenness_centrality(&storage, &config.with_sample_size(2).with_seed(7)).unwrap();
+        assert!(sampled.sampled);
+        assert_eq!(sampled.sources, 2);
+        assert_eq!(sampled.scores.len(), 4);
+    }
+
+    #[test]
+    fn test_closeness_path() {
+        let (storage, ids) = path_graph(3);
+        let result = closeness_centrality(&storage, &CentralityConfig::new()).unwrap();
+        // Node 0 reaches 2 nodes at distance 1 + 2, node 1 reaches 1 at distance 1
+        assert!((result.scores[&ids[0]] - 2.0 / 3.0).abs() < 1e-9);
+        assert!((result.scores[&ids[1]] - 0.5).abs() < 1e-9);
+        assert_eq!(result.scores[&ids[2]], 0.0);
+        assert_eq!(result.top_nodes(1)[0].0, ids[0]);
+    }
+
+    #[test]
+    fn test_hits_star() {
+        // Hubs 0 and 1 both point at 2 and 3; 1 also points at 4
+        let storage = GraphStorage::new();
+        let ids: Vec<_> = (0..5)
+            .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+            .collect();
+        for (from, to) in [(0, 2), (0, 3), (1, 2), (1, 3), (1, 4)] {
+            storage
+                .add_edge_simple(ids[from], ids[to], "LINKS".to_string())
+                .unwrap();
+        }
+
+        let result = hits(&storage, 100, 1e-10).unwrap();
+        assert!(result.converged);
+        assert_eq!(result.top_hubs(1)[0].0, ids[1]);
+        assert!(result.hubs[&ids[0]] > result.hubs[&ids[2]]);
+        assert!(result.authorities[&ids[2]] > result.authorities[&ids[4]]);
+        assert!(result.authorities[&ids[4]] > result.authorities[&ids[0]]);
+        assert_eq!(result.authorities[&ids[0]], 0.0);
+    }
+
+    #[test]
+    fn test_eigenvector_and_katz() {
+        // 1, 2 and 3 all point at 0, which points back at 1
+        let storage = GraphStorage::new();
+        let ids: Vec<_> = (0..4)
+            .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+            .collect();
+        for (from, to) in [(1, 0), (2, 0), (3, 0), (0, 1)] {
+            storage
+                .add_edge_simple(ids[from], ids[to], "LINKS".to_string())
+                .unwrap();
+        }
+
+        let eigen = eigenvector_centrality(&storage, 1000, 1e-9).unwrap();
+        assert!(eigen.converged);
+        // The 0 <-> 1 cycle carries all the weight in the limit
+        let mut top: Vec<_> = eigen.top_nodes(2).into_iter().map(|(id, _)| id).collect();
+        top.sort();
+        let mut cycle = vec![ids[0], ids[1]];
+        cycle.sort();
+        assert_eq!(top, cycle);
+        assert!(eigen.scores[&ids[2]] < 1e-3);
+
+        let katz = katz_centrality(&storage, 0.1, 1.0, 1000, 1e-9).unwrap();
+        assert!(katz.converged);
+        assert_eq!(katz.top_nodes(1)[0].0, ids[0]);
+        // Sources without predecessors keep the base score only
+        assert!((katz.scores[&ids[2]] - katz.scores[&ids[3]]).abs() < 1e-12);
+        assert!(katz.scores[&ids[2]] > 0.0);
+        let norm: f64 = katz.scores.values().map(|s| s * s).sum();
+        assert!((norm - 1.0).abs() < 1e-9);
+
+        // The 0 <-> 1 cycle has eigenvalue above 1, so alpha 1 diverges
+        assert!(katz_centrality(&storage, 1.0, 1.0, 100, 1e-9).is_err());
+        assert!(katz_centrality(&storage, 1.0, 1.0, 5000, 1e-9).is_err());
+        // Running out of iterations while still converging is not an error
+        assert!(!katz_centrality(&storage, 0.1, 1.0, 2, 1e-9).unwrap().converged);
+    }
+
+    #[test]
+    fn test_top_scores_tolerates_nan() {
+        let (a, b, c) = (NodeId::new(), NodeId::new(), NodeId::new());
+        let scores = HashMap::from([(a, 0.5), (b, f64::NAN), (c, 1.0)]);
+        let top = top_scores(&scores, 3);
+        assert_eq!(top[0].0, b);
+        assert_eq!(top[1], (c, 1.0));
+        assert_eq!(top[2], (a, 0.5));
+    }
+
+    #[test]
+    fn test_pagerank_on_other_backends() {
+        use crate::graph::Edge;
+        use crate::storage::{ColumnarStorage, StorageBackend};
+
+        let storage = ColumnarStorage::new();
+        let a = storage.add_node(Node::new(vec!["Node".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Node".to_string()])).unwrap();
+        storage.add_edge(Edge::new(a, b, "LINKS".to_string())).unwrap();
+
+        let result = pagerank(&storage, 0.85, 100, 1e-6).unwrap();
+        assert!(result.scores[&b] > result.scores[&a]);
+
+        let dynamic: &dyn StorageBackend = &storage;
+        assert_eq!(pagerank(dynamic, 0.85, 100, 1e-6).unwrap().scores, result.scores);
+    }
+}
@@ -1,9 +1,77 @@
 //! Community detection algorithms
-
+//!
+//! Louvain alternates two phases. Local moving sweeps over the nodes,
+//! moving each into the neighbouring community with the largest modularity
+//! gain, computed incrementally from the node's edge weight into that
+//! community and the community's total degree. Aggregation then collapses
+//! every community into a single node and repeats on the smaller graph,
+//! until no move improves modularity.
+//!
+//! With [`LouvainConfig::leiden`] set, a refinement phase (Traag et al.,
+//! 2019) splits each community into well-connected sub-communities before
+//! aggregation, which guarantees that no returned community is internally
+//! disconnected. Edges are treated as undirected.
+
+use crate::algorithms::csr::CsrGraph;
 use crate::error::Result;
 use crate::graph::NodeId;
-use crate::storage::GraphStorage;
-use std::collections::{HashMap, HashSet};
+use crate::storage::StorageBackend;
+use std::collections::HashMap;
+
+/// Configuration for Louvain community detection
+#[derive(Debug, Clone)]
+pub struct LouvainConfig {
+    /// Maximum local moving sweeps per level
+    pub max_iterations: usize,
+    /// Stop a level once a sweep gains less modularity than this
+    pub min_improvement: f64,
+    /// Maximum number of aggregation levels
+    pub max_levels: usize,
+    /// Refine communities before aggregating (Leiden)
+    pub leiden: bool,
+}
+
+impl Default for LouvainConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 100,
+            min_improvement: 1e-4,
+            max_levels: 10,
+            leiden: false,
+        }
+    }
+}
+
+impl LouvainConfig {
+    /// Create a configuration with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum local moving sweeps per level
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Set the minimum modularity gain per sweep
+    pub fn with_min_improvement(mut self, min_improvement: f64) -> Self {
+        self.min_improvement = min_improvement;
+        self
+    }
+
+    /// Set the maximum number of aggregation levels
+    pub fn with_max_levels(mut self, max_levels: usize) -> Self {
+        self.max_levels = max_levels;
+        self
+    }
+
+    /// Enable the Leiden refinement phase
+    pub fn with_leiden(mut self, leiden: bool) -> Self {
+        self.leiden = leiden;
+        self
+    }
+}
 
 /// Result of Louvain community detection
 #[derive(Debug, Clone)]
@@ -14,8 +82,10 @@ pub struct LouvainResult {
     pub modularity: f64,
     /// Number of communities found
     pub num_communities: usize,
-    /// Number of iterations performed
+    /// Number of local moving sweeps performed across all levels
     pub iterations: usize,
+    /// Number of levels (local moving passes) performed
+    pub levels: usize,
 }
 
 impl LouvainResult {
@@ -45,7 +115,7 @@ impl LouvainResult {
 ///
 /// # Arguments
 /// * `storage` - Graph storage
-/// * `max_iterations` - Maximum number of iterations (typically 10-100)
+/// * `max_iterations` - Maximum local moving sweeps per level (typically 10-100)
 /// * `min_improvement` - Minimum modularity improvement to continue (typically 1e-4)
 ///
 /// # Returns
@@ -62,158 +132,353 @@ impl LouvainResult {
 /// println!("Found {} communities with modularity {}", 
 ///          result.num_communities, result.modularity);
 /// ```
-pub fn louvain(
-    storage: &GraphStorage,
+pub fn louvain<S: StorageBackend + ?Sized>(
+    storage: &S,
     max_iterations: usize,
-    _min_improvement: f64,
+    min_improvement: f64,
 ) -> Result<LouvainResult> {
-    let all_nodes = storage.get_all_nodes();
-    let num_nodes = all_nodes.len();
-
-    if num_nodes == 0 {
-        return Ok(LouvainResult {
-            communities: HashMap::new(),
-            modularity: 0.0,
-            num_communities: 0,
-            iterations: 0,
-        });
-    }
+    let config = LouvainConfig::new()
+        .with_max_iterations(max_iterations)
+        .with_min_improvement(min_improvement);
+    louvain_with_config(storage, &config)
+}
 
-    // Initialize: each node in its own community
-    let mut communities: HashMap<NodeId, usize> = all_nodes
-        .iter()
-        .enumerate()
-        .map(|(i, node)| (node.id(), i))
-        .collect();
-
-    // Build adjacency and degree information
-    let mut adjacency: HashMap<NodeId, HashMap<NodeId, f64>> = HashMap::new();
-    let mut total_weight = 0.0;
-    let mut node_degrees: HashMap<NodeId, f64> = HashMap::new();
-
-    for node in &all_nodes {
-        let node_id = node.id();
-        let mut neighbors = HashMap::new();
-        let mut degree = 0.0;
-
-        // Process outgoing edges
-        if let Ok(edges) = storage.get_outgoing_edges(node_id) {
-            for edge in edges {
-                let weight = 1.0; // Could extract from edge properties
-                neighbors.insert(edge.to(), weight);
-                degree += weight;
-                total_weight += weight;
-            }
+/// Louvain (or Leiden) community detection with full configuration
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::{louvain_with_config, LouvainConfig};
+///
+/// let result = louvain_with_config(&storage, &LouvainConfig::new().with_leiden(true))?;
+/// ```
+pub fn louvain_with_config<S: StorageBackend + ?Sized>(storage: &S, config: &LouvainConfig) -> Result<LouvainResult> {
+    let csr = CsrGraph::from_storage(storage);
+    Ok(louvain_csr(&csr, config))
+}
+
+/// Louvain (or Leiden) community detection over a prebuilt [`CsrGraph`]
+///
+/// Edge weights of the snapshot are used, so a snapshot from
+/// [`CsrGraph::from_storage_weighted`] detects weighted communities.
+pub fn louvain_csr(csr: &CsrGraph, config: &LouvainConfig) -> LouvainResult {
+    let num_nodes = csr.node_count();
+    let base = WeightedGraph::from_csr(csr);
+    let mut graph = base.clone();
+
+    // Original node -> node of the current aggregated graph
+    let mut membership: Vec<usize> = (0..num_nodes).collect();
+    // Node of the current graph -> community
+    let mut partition: Vec<usize> = (0..num_nodes).collect();
+    let mut iterations = 0;
+    let mut levels = 0;
+
+    while levels < config.max_levels {
+        let (moved, sweeps) = move_nodes(&graph, &mut partition, config);
+        iterations += sweeps;
+        levels += 1;
+        if !moved {
+            break;
+        }
+
+        let aggregates = if config.leiden {
+            renumber(&refine(&graph, &partition))
+        } else {
+            renumber(&partition)
+        };
+        let count = aggregates.iter().max().map_or(0, |&max| max + 1);
+        if count == graph.len() {
+            break;
         }
 
-        adjacency.insert(node_id, neighbors);
-        node_degrees.insert(node_id, degree);
+        // Louvain restarts from singletons; Leiden starts the aggregate
+        // graph from the unrefined communities
+        let next_partition = if config.leiden {
+            let mut next = vec![0; count];
+            for (node, &aggregate) in aggregates.iter().enumerate() {
+                next[aggregate] = partition[node];
+            }
+            renumber(&next)
+        } else {
+            (0..count).collect()
+        };
+
+        for member in membership.iter_mut() {
+            *member = aggregates[*member];
+        }
+        graph = graph.aggregate(&aggregates, count);
+        partition = next_partition;
     }
 
-    let m = total_weight;
-    let mut current_modularity = calculate_modularity(&communities, &adjacency, &node_degrees, m);
-    let mut iterations = 0;
+    let final_partition = renumber(&membership.iter().map(|&m| partition[m]).collect::<Vec<_>>());
+    let num_communities = final_partition.iter().max().map_or(0, |&max| max + 1);
 
-    // Phase 1: Optimize communities
-    for iteration in 0..max_iterations {
-        let mut improved = false;
-        iterations = iteration + 1;
+    LouvainResult {
+        modularity: base.modularity(&final_partition),
+        communities: csr.node_ids().iter().copied().zip(final_partition).collect(),
+        num_communities,
+        iterations,
+        levels,
+    }
+}
 
-        for node in &all_nodes {
-            let node_id = node.id();
-            let current_comm = communities[&node_id];
+/// Undirected weighted graph for one Louvain level
+#[derive(Debug, Clone)]
+struct WeightedGraph {
+    /// Neighbours and edge weights, excluding self-loops
+    adjacency: Vec<Vec<(usize, f64)>>,
+    /// Self-loop weight of each node
+    self_loops: Vec<f64>,
+    /// Weighted degree, self-loops counted twice
+    degrees: Vec<f64>,
+    /// Total edge weight `m`
+    total_weight: f64,
+}
 
-            // Try moving node to neighboring communities
-            let mut neighbor_communities = HashSet::new();
-            if let Some(neighbors) = adjacency.get(&node_id) {
-                for &neighbor_id in neighbors.keys() {
-                    neighbor_communities.insert(communities[&neighbor_id]);
+impl WeightedGraph {
+    fn from_csr(csr: &CsrGraph) -> Self {
+        let n = csr.node_count();
+        let mut adjacency = vec![Vec::new(); n];
+        let mut self_loops = vec![0.0; n];
+        let mut total_weight = 0.0;
+        for from in 0..n {
+            let targets = csr.out_neighbors(from as u32);
+            let weights = csr.out_weights(from as u32);
+            for (&to, &weight) in targets.iter().zip(weights) {
+                let to = to as usize;
+                if to == from {
+                    self_loops[from] += weight;
+                } else {
+                    adjacency[from].push((to, weight));
+                    adjacency[to].push((from, weight));
                 }
+                total_weight += weight;
             }
+        }
+        Self::new(adjacency, self_loops, total_weight)
+    }
+
+    fn new(adjacency: Vec<Vec<(usize, f64)>>, self_loops: Vec<f64>, total_weight: f64) -> Self {
+        let degrees = adjacency
+            .iter()
+            .zip(&self_loops)
+            .map(|(neighbors, &self_loop)| neighbors.iter().map(|&(_, w)| w).sum::<f64>() + 2.0 * self_loop)
+            .collect();
+        Self {
+            adjacency,
+            self_loops,
+            degrees,
+            total_weight,
+        }
+    }
 
-            let mut best_comm = current_comm;
-            let mut best_modularity = current_modularity;
+    fn len(&self) -> usize {
+        self.adjacency.len()
+    }
 
-            for &target_comm in &neighbor_communities {
-                if target_comm == current_comm {
-                    continue;
+    /// Collapse every group of `aggregates` into one node
+    fn aggregate(&self, aggregates: &[usize], count: usize) -> Self {
+        let mut merged: Vec<HashMap<usize, f64>> = vec![HashMap::new(); count];
+        let mut self_loops = vec![0.0; count];
+        for (node, neighbors) in self.adjacency.iter().enumerate() {
+            let a = aggregates[node];
+            self_loops[a] += self.self_loops[node];
+            for &(neighbor, weight) in neighbors {
+                let b = aggregates[neighbor];
+                if a == b {
+                    // Seen once from each endpoint
+                    self_loops[a] += weight / 2.0;
+                } else {
+                    *merged[a].entry(b).or_insert(0.0) += weight;
                 }
+            }
+        }
+        let adjacency = merged.into_iter().map(|neighbors| neighbors.into_iter().collect()).collect();
+        Self::new(adjacency, self_loops, self.total_weight)
+    }
 
-                // Try moving node to target community
-                communities.insert(node_id, target_comm);
-                let new_modularity =
-                    calculate_modularity(&communities, &adjacency, &node_degrees, m);
-
-                if new_modularity > best_modularity {
-                    best_modularity = new_modularity;
-                    best_comm = target_comm;
-                    improved = true;
+    /// Modularity of `partition`, whose values are below `self.len()`
+    fn modularity(&self, partition: &[usize]) -> f64 {
+        if self.total_weight == 0.0 {
+            return 0.0;
+        }
+        let two_m = 2.0 * self.total_weight;
+        let mut internal = vec![0.0; self.len()];
+        let mut totals = vec![0.0; self.len()];
+        for (node, neighbors) in self.adjacency.iter().enumerate() {
+            let c = partition[node];
+            totals[c] += self.degrees[node];
+            internal[c] += 2.0 * self.self_loops[node];
+            for &(neighbor, weight) in neighbors {
+                if partition[neighbor] == c {
+                    internal[c] += weight;
                 }
             }
+        }
+        internal
+            .iter()
+            .zip(&totals)
+            .map(|(&inside, &total)| inside / two_m - (total / two_m).powi(2))
+            .sum()
+    }
+}
 
-            // Apply best move
-            communities.insert(node_id, best_comm);
+/// Edge weight from a node to each neighbouring group, reusing one dense
+/// buffer across nodes
+struct NeighborWeights {
+    weights: Vec<f64>,
+    touched: Vec<usize>,
+}
+
+impl NeighborWeights {
+    fn new(len: usize) -> Self {
+        Self {
+            weights: vec![0.0; len],
+            touched: Vec::new(),
         }
+    }
 
-        current_modularity = calculate_modularity(&communities, &adjacency, &node_degrees, m);
+    fn add(&mut self, group: usize, weight: f64) {
+        if self.weights[group] == 0.0 {
+            self.touched.push(group);
+        }
+        self.weights[group] += weight;
+    }
 
-        // Check for convergence
-        if !improved {
-            break;
+    fn clear(&mut self) {
+        for group in self.touched.drain(..) {
+            self.weights[group] = 0.0;
         }
     }
+}
 
-    // Renumber communities to be consecutive
-    let unique_comms: HashSet<_> = communities.values().copied().collect();
-    let mut comm_map: HashMap<usize, usize> = HashMap::new();
-    for (new_id, &old_id) in unique_comms.iter().enumerate() {
-        comm_map.insert(old_id, new_id);
+/// Local moving phase: move nodes between communities while modularity
+/// improves, returning whether any node moved and the sweeps performed
+fn move_nodes(graph: &WeightedGraph, partition: &mut [usize], config: &LouvainConfig) -> (bool, usize) {
+    let two_m = 2.0 * graph.total_weight;
+    if two_m == 0.0 {
+        return (false, 0);
     }
 
-    for (_node, comm) in communities.iter_mut() {
-        *comm = comm_map[comm];
+    let mut totals = vec![0.0; graph.len()];
+    for (node, &community) in partition.iter().enumerate() {
+        totals[community] += graph.degrees[node];
     }
+    let mut neighbor_weights = NeighborWeights::new(graph.len());
+    let mut quality = graph.modularity(partition);
+    let mut moved = false;
+    let mut sweeps = 0;
+
+    for _ in 0..config.max_iterations {
+        sweeps += 1;
+        let mut moves = 0;
+
+        for node in 0..graph.len() {
+            let current = partition[node];
+            let degree = graph.degrees[node];
+            for &(neighbor, weight) in &graph.adjacency[node] {
+                neighbor_weights.add(partition[neighbor], weight);
+            }
 
-    let num_communities = unique_comms.len();
+            // The gain of joining community c is proportional to
+            // k_i,in(c) - tot(c) * k_i / 2m with the node taken out
+            totals[current] -= degree;
+            let gain = |c: usize| neighbor_weights.weights[c] - totals[c] * degree / two_m;
+            let mut best = current;
+            let mut best_gain = gain(current);
+            for &community in &neighbor_weights.touched {
+                let candidate = gain(community);
+                if candidate > best_gain {
+                    best = community;
+                    best_gain = candidate;
+                }
+            }
+            totals[best] += degree;
+            neighbor_weights.clear();
 
-    Ok(LouvainResult {
-        communities,
-        modularity: current_modularity,
-        num_communities,
-        iterations,
-    })
-}
+            if best != current {
+                partition[node] = best;
+                moves += 1;
+            }
+        }
 
-/// Calculate modularity of a community assignment
-fn calculate_modularity(
-    communities: &HashMap<NodeId, usize>,
-    adjacency: &HashMap<NodeId, HashMap<NodeId, f64>>,
-    node_degrees: &HashMap<NodeId, f64>,
-    total_weight: f64,
-) -> f64 {
-    let mut modularity = 0.0;
+        if moves == 0 {
+            break;
+        }
+        moved = true;
+        let next_quality = graph.modularity(partition);
+        let improvement = next_quality - quality;
+        quality = next_quality;
+        if improvement < config.min_improvement {
+            break;
+        }
+    }
 
-    for (node_i, neighbors) in adjacency.iter() {
-        let comm_i = communities[node_i];
-        let degree_i = node_degrees[node_i];
+    (moved, sweeps)
+}
 
-        for (node_j, &weight) in neighbors.iter() {
-            let comm_j = communities[node_j];
+/// Leiden refinement: split each community of `partition` into
+/// sub-communities grown by merging singleton nodes along edges, so every
+/// sub-community is connected
+fn refine(graph: &WeightedGraph, partition: &[usize]) -> Vec<usize> {
+    let two_m = 2.0 * graph.total_weight;
+    // Sub-communities are named after the node that founded them
+    let mut refined: Vec<usize> = (0..graph.len()).collect();
+    let mut singleton = vec![true; graph.len()];
+    let mut totals = graph.degrees.clone();
+    let mut neighbor_weights = NeighborWeights::new(graph.len());
+
+    for node in 0..graph.len() {
+        if !singleton[node] {
+            continue;
+        }
+        let community = partition[node];
+        for &(neighbor, weight) in &graph.adjacency[node] {
+            if partition[neighbor] == community {
+                neighbor_weights.add(refined[neighbor], weight);
+            }
+        }
 
-            if comm_i == comm_j {
-                let degree_j = node_degrees[node_j];
-                let expected = (degree_i * degree_j) / (2.0 * total_weight);
-                modularity += weight - expected;
+        // Staying alone gains nothing
+        let degree = graph.degrees[node];
+        let mut best = node;
+        let mut best_gain = 0.0;
+        for &sub in &neighbor_weights.touched {
+            let gain = neighbor_weights.weights[sub] - totals[sub] * degree / two_m;
+            if gain > best_gain {
+                best = sub;
+                best_gain = gain;
             }
         }
+        neighbor_weights.clear();
+
+        if best != node {
+            refined[node] = best;
+            totals[node] -= degree;
+            totals[best] += degree;
+            singleton[node] = false;
+            singleton[best] = false;
+        }
     }
 
-    modularity / (2.0 * total_weight)
+    refined
+}
+
+/// Renumber group labels densely in order of first appearance
+fn renumber(labels: &[usize]) -> Vec<usize> {
+    let mut ids: HashMap<usize, usize> = HashMap::new();
+    labels
+        .iter()
+        .map(|&label| {
+            let next = ids.len();
+            *ids.entry(label).or_insert(next)
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::GraphStorage;
     use crate::graph::Node;
 
     #[test]
@@ -264,5 +529,39 @@ mod tests {
         assert!(result.num_communities >= 2);
         assert!(result.modularity > 0.0);
     }
-}
 
+    #[test]
+    fn test_louvain_aggregates_clique_ring() {
+        // Six 4-cliques joined in a ring by single edges
+        let storage = GraphStorage::new();
+        let ids: Vec<_> = (0..24)
+            .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+            .collect();
+        for clique in 0..6 {
+            let base = clique * 4;
+            for i in 0..4 {
+                for j in i + 1..4 {
+                    storage
+                        .add_edge_simple(ids[base + i], ids[base + j], "CONNECTS".to_string())
+                        .unwrap();
+                }
+            }
+            storage
+                .add_edge_simple(ids[base + 3], ids[(base + 4) % 24], "CONNECTS".to_string())
+                .unwrap();
+        }
+
+        for leiden in [false, true] {
+            let config = LouvainConfig::new().with_leiden(leiden);
+            let result = louvain_with_config(&storage, &config).unwrap();
+            assert_eq!(result.num_communities, 6, "leiden: {}", leiden);
+            assert!(result.levels >= 2);
+            for clique in ids.chunks(4) {
+                let community = result.communities[&clique[0]];
+                assert!(clique.iter().all(|id| result.communities[id] == community));
+            }
+            // One community per clique scores 6 * (12/84 - (14/84)^2) ~ 0.69
+            assert!(result.modularity > 0.6);
+        }
+    }
+}
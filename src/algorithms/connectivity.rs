@@ -1,8 +1,15 @@
 //! Graph connectivity algorithms
+//!
+//! [`connected_components`] searches breadth-first through the storage
+//! layer, fetching the edges of every node it visits.
+//! [`weakly_connected_components`] computes the same components with a
+//! union-find over a [`CsrGraph`] snapshot, touching each edge once with no
+//! per-node lookups, and is the one to use on large graphs.
 
+use crate::algorithms::csr::CsrGraph;
 use crate::error::Result;
 use crate::graph::NodeId;
-use crate::storage::GraphStorage;
+use crate::storage::StorageBackend;
 use std::collections::{HashMap, HashSet, VecDeque};
 
 /// Result of connected components analysis
@@ -59,7 +66,7 @@ impl ConnectedComponentsResult {
 /// let result = connected_components(&storage)?;
 /// println!("Found {} components", result.num_components);
 /// ```
-pub fn connected_components(storage: &GraphStorage) -> Result<ConnectedComponentsResult> {
+pub fn connected_components<S: StorageBackend + ?Sized>(storage: &S) -> Result<ConnectedComponentsResult> {
     let mut component_map = HashMap::new();
     let mut component_sizes = HashMap::new();
     let mut visited = HashSet::new();
@@ -120,9 +127,93 @@ pub fn connected_components(storage: &GraphStorage) -> Result<ConnectedComponent
     })
 }
 
+/// Disjoint-set forest with path halving and union by size
+struct UnionFind {
+    parent: Vec<u32>,
+    size: Vec<u32>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len as u32).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, mut x: u32) -> u32 {
+        while self.parent[x as usize] != x {
+            let grandparent = self.parent[self.parent[x as usize] as usize];
+            self.parent[x as usize] = grandparent;
+            x = grandparent;
+        }
+        x
+    }
+
+    fn union(&mut self, a: u32, b: u32) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a as usize] < self.size[b as usize] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b as usize] = a;
+        self.size[a as usize] += self.size[b as usize];
+    }
+}
+
+/// Find weakly connected components with union-find
+///
+/// Produces the same components as [`connected_components`], but reads the
+/// graph once into a CSR snapshot instead of querying storage per node.
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::weakly_connected_components;
+///
+/// let result = weakly_connected_components(&storage)?;
+/// println!("Found {} components", result.num_components);
+/// ```
+pub fn weakly_connected_components<S: StorageBackend + ?Sized>(storage: &S) -> Result<ConnectedComponentsResult> {
+    let csr = CsrGraph::from_storage(storage);
+    Ok(weakly_connected_components_csr(&csr))
+}
+
+/// Weakly connected components over a prebuilt [`CsrGraph`] snapshot
+///
+/// Component IDs are assigned in node order, starting at 0.
+pub fn weakly_connected_components_csr(csr: &CsrGraph) -> ConnectedComponentsResult {
+    let num_nodes = csr.node_count();
+    let mut forest = UnionFind::new(num_nodes);
+    for node in 0..num_nodes as u32 {
+        for &next in csr.out_neighbors(node) {
+            forest.union(node, next);
+        }
+    }
+
+    let mut root_component: HashMap<u32, usize> = HashMap::new();
+    let mut component_map = HashMap::with_capacity(num_nodes);
+    let mut component_sizes = HashMap::new();
+    for node in 0..num_nodes as u32 {
+        let root = forest.find(node);
+        let next_id = root_component.len();
+        let component = *root_component.entry(root).or_insert(next_id);
+        component_map.insert(csr.node_id(node), component);
+        *component_sizes.entry(component).or_insert(0) += 1;
+    }
+
+    ConnectedComponentsResult {
+        component_map,
+        num_components: root_component.len(),
+        component_sizes,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::GraphStorage;
     use crate::graph::Node;
 
     #[test]
@@ -153,5 +244,31 @@ mod tests {
         assert!(!result.are_connected(id1, id3));
         assert!(result.are_connected(id1, id2));
     }
-}
 
+    #[test]
+    fn test_union_find_matches_bfs() {
+        let storage = GraphStorage::new();
+        let ids: Vec<_> = (0..7)
+            .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+            .collect();
+        // {0, 1, 2} joined through edges in both directions, {3, 4}, {5}, {6}
+        for (from, to) in [(0, 1), (2, 1), (4, 3), (6, 6)] {
+            storage
+                .add_edge_simple(ids[from], ids[to], "CONNECTS".to_string())
+                .unwrap();
+        }
+
+        let bfs = connected_components(&storage).unwrap();
+        let union_find = weakly_connected_components(&storage).unwrap();
+        assert_eq!(union_find.num_components, 4);
+        assert_eq!(union_find.num_components, bfs.num_components);
+        for &a in &ids {
+            for &b in &ids {
+                assert_eq!(union_find.are_connected(a, b), bfs.are_connected(a, b));
+            }
+        }
+        let mut sizes: Vec<_> = union_find.component_sizes.values().copied().collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 1, 2, 3]);
+    }
+}
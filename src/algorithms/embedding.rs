@@ -1,8 +1,9 @@
 //! Graph embedding algorithms (Node2Vec, etc.)
 
+use crate::config::AlgorithmConfig;
 use crate::error::Result;
 use crate::graph::NodeId;
-use crate::storage::GraphStorage;
+use crate::storage::StorageBackend;
 use rand::prelude::*;
 use std::collections::HashMap;
 
@@ -33,6 +34,24 @@ impl Default for Node2VecConfig {
     }
 }
 
+impl Node2VecConfig {
+    /// Walk settings and seed from the `[algorithm]` configuration section
+    pub fn from_config(config: &AlgorithmConfig) -> Self {
+        Self {
+            walk_length: config.node2vec_walk_length,
+            walks_per_node: config.node2vec_walks_per_node,
+            seed: config.seed,
+            ..Self::default()
+        }
+    }
+
+    /// Seed the walks for reproducible output
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
 /// Result of Node2Vec sampling
 #[derive(Debug, Clone)]
 pub struct Node2VecResult {
@@ -66,6 +85,8 @@ impl Node2VecResult {
 ///
 /// Generates random walks with configurable exploration-exploitation tradeoff.
 /// These walks can be used as input to Word2Vec-style embedding algorithms.
+/// With a seed, nodes and neighbours are visited in [`NodeId`] order so the
+/// walks are identical on every run over the same graph.
 ///
 /// # Arguments
 /// * `storage` - Graph storage
@@ -93,8 +114,9 @@ impl Node2VecResult {
 /// let result = node2vec(&storage, config)?;
 /// println!("Generated {} walks", result.num_walks());
 /// ```
-pub fn node2vec(storage: &GraphStorage, config: Node2VecConfig) -> Result<Node2VecResult> {
-    let all_nodes = storage.get_all_nodes();
+pub fn node2vec<S: StorageBackend + ?Sized>(storage: &S, config: Node2VecConfig) -> Result<Node2VecResult> {
+    let mut all_nodes = storage.get_all_nodes();
+    all_nodes.sort_unstable_by_key(|node| node.id());
 
     if all_nodes.is_empty() {
         return Ok(Node2VecResult {
@@ -123,6 +145,7 @@ pub fn node2vec(storage: &GraphStorage, config: Node2VecConfig) -> Result<Node2V
                 neighbors.push(edge.to());
             }
         }
+        neighbors.sort_unstable();
 
         neighbors_map.insert(node_id, neighbors);
     }
@@ -241,6 +264,7 @@ fn is_neighbor(node1: NodeId, node2: NodeId, neighbors_map: &HashMap<NodeId, Vec
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::GraphStorage;
     use crate::graph::Node;
 
     #[test]
@@ -283,5 +307,32 @@ mod tests {
             assert!(!walk.is_empty());
         }
     }
-}
 
+    #[test]
+    fn test_seed_from_algorithm_config() {
+        let storage = GraphStorage::new();
+        let ids: Vec<_> = (0..6)
+            .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+            .collect();
+        for i in 0..6 {
+            for j in [1, 2] {
+                storage
+                    .add_edge_simple(ids[i], ids[(i + j) % 6], "LINKS".to_string())
+                    .unwrap();
+            }
+        }
+
+        let algorithm = AlgorithmConfig {
+            node2vec_walk_length: 12,
+            node2vec_walks_per_node: 3,
+            seed: Some(7),
+            ..AlgorithmConfig::default()
+        };
+        let config = Node2VecConfig::from_config(&algorithm);
+        assert_eq!(config.seed, Some(7));
+        let first = node2vec(&storage, config.clone()).unwrap();
+        let second = node2vec(&storage, config).unwrap();
+        assert_eq!(first.walks, second.walks);
+        assert_eq!(first.num_walks(), 18);
+    }
+}
@@ -0,0 +1,328 @@
+//! Graph hashing and isomorphism
+//!
+//! Weisfeiler-Lehman (WL) hashing repeatedly relabels every node with a
+//! hash of its own label and the multiset of its neighbours' labels, then
+//! hashes the resulting label multisets. Isomorphic graphs always hash
+//! equal, so differing hashes prove two graphs or patterns differ; equal
+//! hashes are a strong hint but not a proof. Node labels and relationship
+//! types take part in the hash, and edge direction is respected.
+//!
+//! [`find_isomorphism`] settles the question exactly by backtracking over
+//! node mappings, pruned by WL colours. Its worst case is exponential, so
+//! it is meant for small graphs such as query patterns.
+//!
+//! Hashes use FNV-1a and are stable across runs and platforms, so they can
+//! be stored to deduplicate patterns over time.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use deepgraph::algorithms::{is_isomorphic, wl_subgraph_hash};
+//!
+//! let key = wl_subgraph_hash(&storage, &pattern_nodes, 3)?;
+//! if seen.insert(key) {
+//!     // first time this pattern shape appears
+//! }
+//! assert!(is_isomorphic(&left, &right)?);
+//! ```
+
+use crate::error::Result;
+use crate::graph::NodeId;
+use crate::storage::StorageBackend;
+use std::collections::{HashMap, HashSet};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a over a sequence of words
+fn fnv(words: impl IntoIterator<Item = u64>) -> u64 {
+    words.into_iter().fold(FNV_OFFSET, |hash, word| {
+        word.to_le_bytes()
+            .iter()
+            .fold(hash, |hash, &byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+    })
+}
+
+/// FNV-1a over a string
+fn fnv_str(s: &str) -> u64 {
+    s.bytes()
+        .fold(FNV_OFFSET, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// Hash a multiset of words independently of order
+fn fnv_multiset(mut words: Vec<u64>) -> u64 {
+    words.sort_unstable();
+    fnv(std::iter::once(words.len() as u64).chain(words))
+}
+
+/// Nodes and edges reduced to label and relationship type hashes
+struct LabeledGraph {
+    ids: Vec<NodeId>,
+    labels: Vec<u64>,
+    /// `(neighbour, relationship type hash)` per node
+    outgoing: Vec<Vec<(usize, u64)>>,
+    incoming: Vec<Vec<(usize, u64)>>,
+    /// `(from, to)` -> sorted relationship type hashes of the parallel edges
+    edges: HashMap<(usize, usize), Vec<u64>>,
+}
+
+impl LabeledGraph {
+    /// The subgraph induced by `nodes`, or the whole graph
+    fn load<S: StorageBackend + ?Sized>(storage: &S, nodes: Option<&[NodeId]>) -> Result<Self> {
+        let nodes = match nodes {
+            Some(ids) => {
+                let mut seen = HashSet::new();
+                let mut loaded = Vec::with_capacity(ids.len());
+                for &id in ids {
+                    if seen.insert(id) {
+                        loaded.push(storage.get_node(id)?);
+                    }
+                }
+                loaded
+            }
+            None => storage.iter_nodes().collect(),
+        };
+
+        let ids: Vec<NodeId> = nodes.iter().map(|node| node.id()).collect();
+        let index: HashMap<NodeId, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let labels = nodes
+            .iter()
+            .map(|node| fnv_multiset(node.labels().iter().map(|label| fnv_str(label)).collect()))
+            .collect();
+
+        let mut graph = Self {
+            ids,
+            labels,
+            outgoing: vec![Vec::new(); nodes.len()],
+            incoming: vec![Vec::new(); nodes.len()],
+            edges: HashMap::new(),
+        };
+        for node in &nodes {
+            for edge in storage.get_outgoing_edges(node.id())? {
+                if let (Some(&from), Some(&to)) = (index.get(&edge.from()), index.get(&edge.to())) {
+                    let kind = fnv_str(edge.relationship_type());
+                    graph.outgoing[from].push((to, kind));
+                    graph.incoming[to].push((from, kind));
+                    graph.edges.entry((from, to)).or_default().push(kind);
+                }
+            }
+        }
+        graph.edges.values_mut().for_each(|kinds| kinds.sort_unstable());
+        Ok(graph)
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.outgoing.iter().map(Vec::len).sum()
+    }
+
+    /// One round of WL relabelling
+    fn refine(&self, colors: &[u64]) -> Vec<u64> {
+        (0..self.len())
+            .map(|node| {
+                let neighborhood = |edges: &[(usize, u64)]| {
+                    fnv_multiset(
+                        edges
+                            .iter()
+                            .map(|&(neighbor, kind)| fnv([kind, colors[neighbor]]))
+                            .collect(),
+                    )
+                };
+                fnv([colors[node], neighborhood(&self.outgoing[node]), neighborhood(&self.incoming[node])])
+            })
+            .collect()
+    }
+
+    /// Node colours after `iterations` rounds, and the graph hash over
+    /// every round
+    fn wl(&self, iterations: usize) -> (Vec<u64>, u64) {
+        let mut colors = self.labels.clone();
+        let mut rounds = vec![fnv_multiset(colors.clone())];
+        for _ in 0..iterations {
+            colors = self.refine(&colors);
+            rounds.push(fnv_multiset(colors.clone()));
+        }
+        (colors, fnv(rounds))
+    }
+
+    fn edge_kinds(&self, from: usize, to: usize) -> &[u64] {
+        self.edges.get(&(from, to)).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// WL hash of a whole graph after `iterations` rounds of relabelling
+///
+/// Two or three rounds distinguish most non-isomorphic graphs in practice.
+pub fn wl_graph_hash<S: StorageBackend + ?Sized>(storage: &S, iterations: usize) -> Result<u64> {
+    Ok(LabeledGraph::load(storage, None)?.wl(iterations).1)
+}
+
+/// WL hash of the subgraph induced by `nodes`
+///
+/// Only edges between the given nodes count. Fails if a node is missing.
+pub fn wl_subgraph_hash<S: StorageBackend + ?Sized>(storage: &S, nodes: &[NodeId], iterations: usize) -> Result<u64> {
+    Ok(LabeledGraph::load(storage, Some(nodes))?.wl(iterations).1)
+}
+
+/// WL colour of every node after `iterations` rounds
+///
+/// Nodes with different colours cannot be mapped onto each other by any
+/// isomorphism; nodes in similar structural positions share a colour.
+pub fn wl_node_hashes<S: StorageBackend + ?Sized>(storage: &S, iterations: usize) -> Result<HashMap<NodeId, u64>> {
+    let graph = LabeledGraph::load(storage, None)?;
+    let (colors, _) = graph.wl(iterations);
+    Ok(graph.ids.iter().copied().zip(colors).collect())
+}
+
+/// Whether two graphs are isomorphic, respecting labels, relationship types
+/// and direction
+pub fn is_isomorphic<A, B>(a: &A, b: &B) -> Result<bool>
+where
+    A: StorageBackend + ?Sized,
+    B: StorageBackend + ?Sized,
+{
+    Ok(find_isomorphism(a, b)?.is_some())
+}
+
+/// A node mapping from `a` onto `b` preserving labels, relationship types
+/// and direction, if one exists
+pub fn find_isomorphism<A, B>(a: &A, b: &B) -> Result<Option<HashMap<NodeId, NodeId>>>
+where
+    A: StorageBackend + ?Sized,
+    B: StorageBackend + ?Sized,
+{
+    let a = LabeledGraph::load(a, None)?;
+    let b = LabeledGraph::load(b, None)?;
+    Ok(match_graphs(&a, &b))
+}
+
+/// Whether the subgraphs induced by `nodes_a` in `a` and `nodes_b` in `b`
+/// are isomorphic
+pub fn are_subgraphs_isomorphic<A, B>(a: &A, nodes_a: &[NodeId], b: &B, nodes_b: &[NodeId]) -> Result<bool>
+where
+    A: StorageBackend + ?Sized,
+    B: StorageBackend + ?Sized,
+{
+    let a = LabeledGraph::load(a, Some(nodes_a))?;
+    let b = LabeledGraph::load(b, Some(nodes_b))?;
+    Ok(match_graphs(&a, &b).is_some())
+}
+
+fn match_graphs(a: &LabeledGraph, b: &LabeledGraph) -> Option<HashMap<NodeId, NodeId>> {
+    if a.len() != b.len() || a.edge_count() != b.edge_count() {
+        return None;
+    }
+    // n rounds reach the stable colouring
+    let (colors_a, hash_a) = a.wl(a.len());
+    let (colors_b, hash_b) = b.wl(b.len());
+    if hash_a != hash_b {
+        return None;
+    }
+
+    // Map the rarest colours first to prune early
+    let mut class_size: HashMap<u64, usize> = HashMap::new();
+    for &color in &colors_a {
+        *class_size.entry(color).or_insert(0) += 1;
+    }
+    let mut order: Vec<usize> = (0..a.len()).collect();
+    order.sort_by_key(|&node| (class_size[&colors_a[node]], colors_a[node]));
+
+    let mut mapping = vec![usize::MAX; a.len()];
+    let mut used = vec![false; b.len()];
+    if extend(a, b, &colors_a, &colors_b, &order, 0, &mut mapping, &mut used) {
+        Some(
+            mapping
+                .iter()
+                .enumerate()
+                .map(|(from, &to)| (a.ids[from], b.ids[to]))
+                .collect(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Map `order[depth..]` consistently with the mapping so far
+#[allow(clippy::too_many_arguments)]
+fn extend(
+    a: &LabeledGraph,
+    b: &LabeledGraph,
+    colors_a: &[u64],
+    colors_b: &[u64],
+    order: &[usize],
+    depth: usize,
+    mapping: &mut [usize],
+    used: &mut [bool],
+) -> bool {
+    let Some(&v) = order.get(depth) else {
+        return true;
+    };
+    for w in 0..b.len() {
+        if used[w] || colors_a[v] != colors_b[w] || a.edge_kinds(v, v) != b.edge_kinds(w, w) {
+            continue;
+        }
+        let consistent = order[..depth].iter().all(|&u| {
+            let mapped = mapping[u];
+            a.edge_kinds(v, u) == b.edge_kinds(w, mapped) && a.edge_kinds(u, v) == b.edge_kinds(mapped, w)
+        });
+        if !consistent {
+            continue;
+        }
+        mapping[v] = w;
+        used[w] = true;
+        if extend(a, b, colors_a, colors_b, order, depth + 1, mapping, used) {
+            return true;
+        }
+        mapping[v] = usize::MAX;
+        used[w] = false;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+    use crate::storage::GraphStorage;
+
+    /// A directed cycle over nodes labelled `labels`
+    fn cycle(labels: &[&str]) -> (GraphStorage, Vec<NodeId>) {
+        let storage = GraphStorage::new();
+        let ids: Vec<_> = labels
+            .iter()
+            .map(|label| storage.add_node(Node::new(vec![label.to_string()])).unwrap())
+            .collect();
+        for i in 0..ids.len() {
+            storage
+                .add_edge_simple(ids[i], ids[(i + 1) % ids.len()], "NEXT".to_string())
+                .unwrap();
+        }
+        (storage, ids)
+    }
+
+    #[test]
+    fn test_wl_hash_and_isomorphism() {
+        let (a, a_ids) = cycle(&["A", "B", "B", "C"]);
+        let (b, b_ids) = cycle(&["B", "C", "A", "B"]);
+        let (c, _) = cycle(&["A", "B", "C", "B"]);
+
+        assert_eq!(wl_graph_hash(&a, 3).unwrap(), wl_graph_hash(&b, 3).unwrap());
+        let mapping = find_isomorphism(&a, &b).unwrap().unwrap();
+        assert_eq!(mapping[&a_ids[0]], b_ids[2]);
+        assert_eq!(mapping[&a_ids[3]], b_ids[1]);
+
+        // Same labels and shape, different order around the cycle
+        assert!(!is_isomorphic(&a, &c).unwrap());
+
+        // The A -> B edge of a appears in b, but B -> C does not match it
+        assert!(are_subgraphs_isomorphic(&a, &a_ids[..2], &b, &b_ids[2..]).unwrap());
+        assert!(!are_subgraphs_isomorphic(&a, &a_ids[..2], &b, &b_ids[..2]).unwrap());
+        assert_ne!(
+            wl_subgraph_hash(&a, &a_ids[..2], 2).unwrap(),
+            wl_subgraph_hash(&a, &a_ids[1..3], 2).unwrap()
+        );
+    }
+}
@@ -1,27 +1,63 @@
 //! Graph algorithms module
 //!
 //! This module provides implementations of common graph algorithms optimized for DeepGraph:
-//! - **Traversal**: BFS, DFS
+//! - **Traversal**: BFS, DFS, configurable Traversal builder
 //! - **Shortest Path**: Dijkstra
-//! - **Connectivity**: Connected Components
-//! - **Centrality**: PageRank
-//! - **Structural**: Triangle Counting
-//! - **Community**: Louvain Community Detection
+//! - **Temporal**: time-respecting BFS and shortest path over timestamped edges
+//! - **Connectivity**: Connected Components (BFS and union-find)
+//! - **Centrality**: PageRank, HITS, Eigenvector, Katz, Betweenness, Closeness
+//! - **Structural**: Triangle Counting, Clustering Coefficient, Motif Counting
+//! - **Community**: Louvain Community Detection (multi-level, optional Leiden refinement)
+//! - **Similarity**: Common Neighbours, Jaccard, Adamic-Adar, Link Prediction
+//! - **Isomorphism**: Weisfeiler-Lehman hashing, exact isomorphism for small graphs
+//! - **Profile**: density, degree distribution, assortativity, diameter estimate
 //! - **Embedding**: Node2Vec (Biased Random Walk)
+//! - **CSR**: contiguous adjacency snapshots for iterative algorithms
+//!
+//! Every algorithm accepts any [`StorageBackend`](crate::storage::StorageBackend),
+//! including `&dyn StorageBackend`, so it runs directly on disk, columnar
+//! or durable storage without copying the graph into memory first.
 
 pub mod traversal;
 pub mod shortest_path;
+pub mod temporal;
 pub mod connectivity;
 pub mod centrality;
 pub mod structural;
 pub mod community;
+pub mod similarity;
+pub mod profile;
+pub mod isomorphism;
 pub mod embedding;
+pub mod csr;
 
-pub use traversal::{bfs, dfs, BFSResult, DFSResult};
+pub use traversal::{
+    bfs, dfs, BFSResult, DFSResult, Traversal, TraversalDirection, TraversalIter, TraversalOrder, TraversalStep,
+    VisitControl,
+};
 pub use shortest_path::{dijkstra, DijkstraResult};
-pub use connectivity::{connected_components, ConnectedComponentsResult};
-pub use centrality::{pagerank, PageRankResult};
-pub use structural::{triangle_count, TriangleCountResult};
-pub use community::{louvain, LouvainResult};
+pub use temporal::{
+    temporal_bfs, temporal_shortest_path, TemporalBfsResult, TemporalConfig, TemporalPath, TimeWindow,
+};
+pub use connectivity::{
+    connected_components, weakly_connected_components, weakly_connected_components_csr, ConnectedComponentsResult,
+};
+pub use centrality::{
+    betweenness_centrality, betweenness_centrality_csr, closeness_centrality, closeness_centrality_csr,
+    eigenvector_centrality, eigenvector_centrality_csr, hits, hits_csr, katz_centrality, katz_centrality_csr,
+    pagerank, pagerank_csr, BetweennessResult, CentralityConfig, ClosenessResult, EigenvectorResult, HitsResult,
+    KatzResult, PageRankResult,
+};
+pub use structural::{
+    clustering_coefficient, clustering_coefficient_csr, motif_count, motif_count_csr, triangle_count,
+    triangle_count_csr, triangle_count_streaming, ClusteringCoefficientResult, MotifCountResult, MotifCounts, TriangleCountResult,
+};
+pub use community::{louvain, louvain_csr, louvain_with_config, LouvainConfig, LouvainResult};
+pub use similarity::{node_similarity, predict_links, LinkPrediction, SimilarityMetric};
+pub use profile::{graph_profile, graph_profile_csr, GraphProfile, ProfileConfig};
+pub use isomorphism::{
+    are_subgraphs_isomorphic, find_isomorphism, is_isomorphic, wl_graph_hash, wl_node_hashes, wl_subgraph_hash,
+};
 pub use embedding::{node2vec, Node2VecConfig, Node2VecResult};
+pub use csr::CsrGraph;
 
@@ -2,7 +2,7 @@
 
 use crate::error::{DeepGraphError, Result};
 use crate::graph::{NodeId, PropertyValue};
-use crate::storage::GraphStorage;
+use crate::storage::StorageBackend;
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
 
@@ -97,8 +97,8 @@ impl Ord for State {
 ///     println!("Shortest path: {:?}", path);
 /// }
 /// ```
-pub fn dijkstra(
-    storage: &GraphStorage,
+pub fn dijkstra<S: StorageBackend + ?Sized>(
+    storage: &S,
     source: NodeId,
     weight_property: Option<&str>,
 ) -> Result<DijkstraResult> {
@@ -182,6 +182,7 @@ pub fn dijkstra(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::GraphStorage;
     use crate::graph::{Edge, Node};
     use std::collections::HashMap;
 
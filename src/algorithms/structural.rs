@@ -1,9 +1,11 @@
-//! Structural graph algorithms (Triangle Counting, etc.)
+//! Structural graph algorithms (Triangle Counting, Clustering Coefficient,
+//! Motif Counting)
 
+use crate::algorithms::csr::CsrGraph;
 use crate::error::Result;
 use crate::graph::NodeId;
-use crate::storage::GraphStorage;
-use std::collections::{HashMap, HashSet};
+use crate::storage::StorageBackend;
+use std::collections::{BTreeSet, HashMap};
 
 /// Result of triangle counting
 #[derive(Debug, Clone)]
@@ -39,107 +41,368 @@ pub struct TriangleCountResult {
 /// let result = triangle_count(&storage)?;
 /// println!("Found {} triangles", result.total_triangles);
 /// ```
-pub fn triangle_count(storage: &GraphStorage) -> Result<TriangleCountResult> {
-    let all_nodes = storage.get_all_nodes();
-    let mut triangles_per_node: HashMap<NodeId, usize> = HashMap::new();
-    let mut total_triangles = 0;
+pub fn triangle_count<S: StorageBackend + ?Sized>(storage: &S) -> Result<TriangleCountResult> {
+    let csr = CsrGraph::from_storage(storage);
+    Ok(triangle_count_csr(&csr))
+}
 
-    // Build adjacency sets for each node (treat as undirected)
-    let mut adjacency: HashMap<NodeId, HashSet<NodeId>> = HashMap::new();
+/// Triangle counting over a prebuilt [`CsrGraph`] snapshot
+pub fn triangle_count_csr(csr: &CsrGraph) -> TriangleCountResult {
+    let adjacency = undirected_adjacency(csr);
+    let (per_node, total_triangles) = count_triangles(&adjacency);
+    triangle_result(
+        total_triangles,
+        adjacency
+            .iter()
+            .zip(per_node)
+            .enumerate()
+            .map(|(index, (neighbors, triangles))| (csr.node_id(index as u32), neighbors.len(), triangles)),
+    )
+}
 
-    for node in &all_nodes {
-        let node_id = node.id();
-        let mut neighbors = HashSet::new();
+/// Triangle counting straight from storage, without a CSR snapshot
+///
+/// Holds only one neighbourhood pair in memory at a time besides the
+/// per-node counts, at the cost of one storage query per edge endpoint.
+/// Use it when a snapshot of the whole graph would not fit in memory;
+/// otherwise [`triangle_count`] is much faster.
+pub fn triangle_count_streaming<S: StorageBackend + ?Sized>(storage: &S) -> Result<TriangleCountResult> {
+    let neighbors = |node: NodeId| -> Result<BTreeSet<NodeId>> {
+        let mut neighbors: BTreeSet<NodeId> = storage
+            .get_outgoing_edges(node)?
+            .iter()
+            .map(|edge| edge.to())
+            .chain(storage.get_incoming_edges(node)?.iter().map(|edge| edge.from()))
+            .collect();
+        neighbors.remove(&node);
+        Ok(neighbors)
+    };
 
-        // Add outgoing neighbors
-        if let Ok(edges) = storage.get_outgoing_edges(node_id) {
-            for edge in edges {
-                neighbors.insert(edge.to());
+    let mut degrees = HashMap::new();
+    let mut per_node: HashMap<NodeId, usize> = HashMap::new();
+    let mut total_triangles = 0;
+    for node in storage.get_all_nodes() {
+        let u = node.id();
+        let neighbors_u = neighbors(u)?;
+        degrees.insert(u, neighbors_u.len());
+        // Count each triangle u < v < w once, from u
+        for &v in neighbors_u.range(u..) {
+            for &w in neighbors(v)?.range(v..) {
+                if neighbors_u.contains(&w) {
+                    for corner in [u, v, w] {
+                        *per_node.entry(corner).or_insert(0) += 1;
+                    }
+                    total_triangles += 1;
+                }
             }
         }
+    }
 
-        // Add incoming neighbors (treat as undirected)
-        if let Ok(edges) = storage.get_incoming_edges(node_id) {
-            for edge in edges {
-                neighbors.insert(edge.from());
-            }
+    Ok(triangle_result(
+        total_triangles,
+        degrees
+            .into_iter()
+            .map(|(node, degree)| (node, degree, per_node.get(&node).copied().unwrap_or(0))),
+    ))
+}
+
+/// Assemble a [`TriangleCountResult`] from `(node, degree, triangles)`
+fn triangle_result(
+    total_triangles: usize,
+    nodes: impl Iterator<Item = (NodeId, usize, usize)>,
+) -> TriangleCountResult {
+    let mut node_triangles = HashMap::new();
+    let mut clustering_coefficients = HashMap::new();
+    let mut total_coefficient_sum = 0.0;
+    let mut nodes_with_coefficient = 0;
+
+    for (node, degree, triangles) in nodes {
+        let coefficient = local_coefficient(triangles, degree);
+        node_triangles.insert(node, triangles);
+        clustering_coefficients.insert(node, coefficient);
+        if degree >= 2 {
+            total_coefficient_sum += coefficient;
+            nodes_with_coefficient += 1;
         }
+    }
+
+    let global_clustering_coefficient = if nodes_with_coefficient > 0 {
+        total_coefficient_sum / nodes_with_coefficient as f64
+    } else {
+        0.0
+    };
 
-        adjacency.insert(node_id, neighbors);
-        triangles_per_node.insert(node_id, 0);
+    TriangleCountResult {
+        total_triangles,
+        node_triangles,
+        clustering_coefficients,
+        global_clustering_coefficient,
     }
+}
 
-    // Count triangles
-    let node_ids: Vec<NodeId> = all_nodes.iter().map(|n| n.id()).collect();
+/// Result of clustering coefficient computation
+#[derive(Debug, Clone)]
+pub struct ClusteringCoefficientResult {
+    /// Local clustering coefficient of each node: the fraction of pairs of
+    /// its neighbours that are themselves connected
+    pub local_coefficients: HashMap<NodeId, f64>,
+    /// Mean of the local coefficients over all nodes, counting nodes with
+    /// fewer than two neighbours as 0 (Watts-Strogatz)
+    pub average_coefficient: f64,
+    /// Transitivity: three times the triangles over the connected triples
+    pub global_coefficient: f64,
+}
 
-    for (i, &node1) in node_ids.iter().enumerate() {
-        let neighbors1 = adjacency.get(&node1).unwrap();
+/// Compute local, average and global clustering coefficients
+///
+/// Treats the graph as undirected, ignoring self-loops and parallel edges.
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::clustering_coefficient;
+///
+/// let result = clustering_coefficient(&storage)?;
+/// println!("Transitivity {}, average {}", result.global_coefficient, result.average_coefficient);
+/// ```
+pub fn clustering_coefficient<S: StorageBackend + ?Sized>(storage: &S) -> Result<ClusteringCoefficientResult> {
+    let csr = CsrGraph::from_storage(storage);
+    Ok(clustering_coefficient_csr(&csr))
+}
 
-        for (_j, &node2) in node_ids.iter().enumerate().skip(i + 1) {
-            // Check if node1 and node2 are connected
-            if !neighbors1.contains(&node2) {
-                continue;
-            }
+/// Clustering coefficients over a prebuilt [`CsrGraph`] snapshot
+pub fn clustering_coefficient_csr(csr: &CsrGraph) -> ClusteringCoefficientResult {
+    let adjacency = undirected_adjacency(csr);
+    let (per_node, total_triangles) = count_triangles(&adjacency);
 
-            let neighbors2 = adjacency.get(&node2).unwrap();
+    let local: Vec<f64> = adjacency
+        .iter()
+        .zip(&per_node)
+        .map(|(neighbors, &triangles)| local_coefficient(triangles, neighbors.len()))
+        .collect();
+    let average_coefficient = if local.is_empty() {
+        0.0
+    } else {
+        local.iter().sum::<f64>() / local.len() as f64
+    };
+    let triples: usize = adjacency
+        .iter()
+        .map(|neighbors| neighbors.len() * neighbors.len().saturating_sub(1) / 2)
+        .sum();
+    let global_coefficient = if triples > 0 {
+        3.0 * total_triangles as f64 / triples as f64
+    } else {
+        0.0
+    };
+
+    ClusteringCoefficientResult {
+        local_coefficients: csr.node_ids().iter().copied().zip(local).collect(),
+        average_coefficient,
+        global_coefficient,
+    }
+}
 
-            // Find common neighbors (potential third vertex of triangle)
-            for &node3 in neighbors1.iter() {
-                if node3 != node2 && neighbors2.contains(&node3) {
-                    // Found a triangle: node1-node2-node3
-                    *triangles_per_node.get_mut(&node1).unwrap() += 1;
-                    *triangles_per_node.get_mut(&node2).unwrap() += 1;
-                    *triangles_per_node.get_mut(&node3).unwrap() += 1;
+/// Counts of each motif
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MotifCounts {
+    /// Open two-edge paths `a - v - b` with `a` and `b` not linked; per node,
+    /// the wedges centred on it
+    pub wedges: usize,
+    /// Three mutually linked nodes
+    pub triangles: usize,
+    /// Four-cycles `a - b - c - d - a`, chords allowed
+    pub squares: usize,
+    /// Four mutually linked nodes
+    pub four_cliques: usize,
+}
+
+/// Result of motif counting
+#[derive(Debug, Clone)]
+pub struct MotifCountResult {
+    /// Motifs in the whole graph
+    pub totals: MotifCounts,
+    /// Motifs each node takes part in
+    pub node_counts: HashMap<NodeId, MotifCounts>,
+}
 
-                    // Only count once (avoid triple counting)
-                    if node1 < node2 && node2 < node3 {
-                        total_triangles += 1;
+/// Count wedges, triangles, squares and 4-cliques
+///
+/// Treats the graph as undirected, ignoring self-loops and parallel edges.
+/// Dense local structure (many squares or cliques around few accounts) is
+/// a common fraud signal.
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::motif_count;
+///
+/// let result = motif_count(&storage)?;
+/// let suspicious = result.node_counts.iter().filter(|(_, counts)| counts.four_cliques > 10);
+/// ```
+pub fn motif_count<S: StorageBackend + ?Sized>(storage: &S) -> Result<MotifCountResult> {
+    let csr = CsrGraph::from_storage(storage);
+    Ok(motif_count_csr(&csr))
+}
+
+/// Motif counting over a prebuilt [`CsrGraph`] snapshot
+pub fn motif_count_csr(csr: &CsrGraph) -> MotifCountResult {
+    let adjacency = undirected_adjacency(csr);
+    let n = adjacency.len();
+    let (node_triangles, triangles) = count_triangles(&adjacency);
+    let mut counts: Vec<MotifCounts> = node_triangles
+        .iter()
+        .zip(&adjacency)
+        .map(|(&triangles, neighbors)| MotifCounts {
+            wedges: neighbors.len() * neighbors.len().saturating_sub(1) / 2 - triangles,
+            triangles,
+            ..MotifCounts::default()
+        })
+        .collect();
+
+    // Every square through u has exactly one node w opposite u, and is
+    // formed by two of the common neighbours of u and w
+    let mut common = vec![0usize; n];
+    let mut touched = Vec::new();
+    let mut square_corners = 0;
+    for u in 0..n {
+        for &v in &adjacency[u] {
+            for &w in &adjacency[v as usize] {
+                if w as usize != u {
+                    if common[w as usize] == 0 {
+                        touched.push(w);
                     }
+                    common[w as usize] += 1;
                 }
             }
         }
+        for w in touched.drain(..) {
+            let c = common[w as usize];
+            counts[u].squares += c * c.saturating_sub(1) / 2;
+            common[w as usize] = 0;
+        }
+        square_corners += counts[u].squares;
     }
 
-    // Calculate clustering coefficients
-    let mut clustering_coefficients = HashMap::new();
-    let mut total_coefficient_sum = 0.0;
-    let mut nodes_with_coefficient = 0;
-
-    for node in &all_nodes {
-        let node_id = node.id();
-        let degree = adjacency.get(&node_id).unwrap().len();
-
-        if degree < 2 {
-            clustering_coefficients.insert(node_id, 0.0);
-            continue;
+    // Extend each triangle u < v < w by a fourth node x > w linked to all
+    let mut four_cliques = 0;
+    for (u, neighbors_u) in adjacency.iter().enumerate() {
+        for &v in neighbors_u.iter().filter(|&&v| v as usize > u) {
+            let neighbors_v = &adjacency[v as usize];
+            for &w in neighbors_v.iter().filter(|&&w| w > v) {
+                if neighbors_u.binary_search(&w).is_err() {
+                    continue;
+                }
+                let neighbors_w = &adjacency[w as usize];
+                for &x in neighbors_w.iter().filter(|&&x| x > w) {
+                    if neighbors_u.binary_search(&x).is_ok() && neighbors_v.binary_search(&x).is_ok() {
+                        for node in [u, v as usize, w as usize, x as usize] {
+                            counts[node].four_cliques += 1;
+                        }
+                        four_cliques += 1;
+                    }
+                }
+            }
         }
+    }
 
-        let triangles = *triangles_per_node.get(&node_id).unwrap() as f64;
-        let max_possible = (degree * (degree - 1)) as f64 / 2.0;
-        let coefficient = triangles / max_possible;
+    MotifCountResult {
+        totals: MotifCounts {
+            wedges: counts.iter().map(|c| c.wedges).sum(),
+            triangles,
+            squares: square_corners / 4,
+            four_cliques,
+        },
+        node_counts: csr.node_ids().iter().copied().zip(counts).collect(),
+    }
+}
+
+/// Sorted, deduplicated undirected neighbours of each node, without
+/// self-loops
+fn undirected_adjacency(csr: &CsrGraph) -> Vec<Vec<u32>> {
+    (0..csr.node_count() as u32)
+        .map(|node| {
+            let mut neighbors: Vec<u32> = csr
+                .out_neighbors(node)
+                .iter()
+                .chain(csr.in_neighbors(node))
+                .copied()
+                .filter(|&neighbor| neighbor != node)
+                .collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            neighbors
+        })
+        .collect()
+}
 
-        clustering_coefficients.insert(node_id, coefficient);
-        total_coefficient_sum += coefficient;
-        nodes_with_coefficient += 1;
+/// Triangles each node is part of, and the total
+///
+/// Orients every edge from the endpoint of lower degree to the higher one
+/// (ties by index), so each node keeps at most `O(sqrt(m))` forward
+/// neighbours, and counts every triangle once by intersecting the sorted
+/// forward lists of its two lowest-ranked corners. `O(m^1.5)` overall, and
+/// hubs never have their full neighbourhood scanned.
+fn count_triangles(adjacency: &[Vec<u32>]) -> (Vec<usize>, usize) {
+    let n = adjacency.len();
+    let mut order: Vec<u32> = (0..n as u32).collect();
+    order.sort_unstable_by_key(|&node| (adjacency[node as usize].len(), node));
+    let mut rank = vec![0u32; n];
+    for (position, &node) in order.iter().enumerate() {
+        rank[node as usize] = position as u32;
     }
 
-    let global_clustering_coefficient = if nodes_with_coefficient > 0 {
-        total_coefficient_sum / nodes_with_coefficient as f64
-    } else {
-        0.0
-    };
+    // Higher-ranked neighbours of each node, by rank and in rank order
+    let forward: Vec<Vec<u32>> = order
+        .iter()
+        .enumerate()
+        .map(|(position, &node)| {
+            let mut higher: Vec<u32> = adjacency[node as usize]
+                .iter()
+                .map(|&neighbor| rank[neighbor as usize])
+                .filter(|&r| r as usize > position)
+                .collect();
+            higher.sort_unstable();
+            higher
+        })
+        .collect();
 
-    Ok(TriangleCountResult {
-        total_triangles,
-        node_triangles: triangles_per_node,
-        clustering_coefficients,
-        global_clustering_coefficient,
-    })
+    let mut per_node = vec![0; n];
+    let mut total = 0;
+    for (r, forward_r) in forward.iter().enumerate() {
+        for &s in forward_r {
+            let forward_s = &forward[s as usize];
+            let (mut i, mut j) = (0, 0);
+            while i < forward_r.len() && j < forward_s.len() {
+                let (a, b) = (forward_r[i], forward_s[j]);
+                if a < b {
+                    i += 1;
+                } else if b < a {
+                    j += 1;
+                } else {
+                    for corner in [r as u32, s, a] {
+                        per_node[order[corner as usize] as usize] += 1;
+                    }
+                    total += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+    }
+    (per_node, total)
+}
+
+/// Local clustering coefficient of a node in `triangles` triangles with
+/// `degree` distinct neighbours
+fn local_coefficient(triangles: usize, degree: usize) -> f64 {
+    if degree < 2 {
+        return 0.0;
+    }
+    triangles as f64 / (degree * (degree - 1) / 2) as f64
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::GraphStorage;
     use crate::graph::Node;
 
     #[test]
@@ -172,5 +435,87 @@ mod tests {
         assert_eq!(*result.node_triangles.get(&id2).unwrap(), 1);
         assert_eq!(*result.node_triangles.get(&id3).unwrap(), 1);
     }
-}
 
+    #[test]
+    fn test_triangle_count_matches_streaming() {
+        // A 4-clique, plus a hub linked to every node and a pendant leaf:
+        // 4 + 6 triangles, and the hub ranks last in degree order
+        let storage = GraphStorage::new();
+        let clique = [(); 4].map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap());
+        let [hub, leaf] = [(); 2].map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap());
+        for (i, &a) in clique.iter().enumerate() {
+            for &b in &clique[i + 1..] {
+                storage.add_edge_simple(a, b, "LINKS".to_string()).unwrap();
+            }
+            storage.add_edge_simple(a, hub, "LINKS".to_string()).unwrap();
+        }
+        storage.add_edge_simple(leaf, hub, "LINKS".to_string()).unwrap();
+        // A reciprocal edge must not create extra triangles
+        storage.add_edge_simple(hub, clique[0], "LINKS".to_string()).unwrap();
+
+        let fast = triangle_count(&storage).unwrap();
+        let streaming = triangle_count_streaming(&storage).unwrap();
+        assert_eq!(fast.total_triangles, 10);
+        assert_eq!(fast.node_triangles[&hub], 6);
+        assert_eq!(fast.node_triangles[&clique[0]], 6);
+        assert_eq!(fast.node_triangles[&leaf], 0);
+        assert_eq!(fast.total_triangles, streaming.total_triangles);
+        assert_eq!(fast.node_triangles, streaming.node_triangles);
+        assert_eq!(fast.clustering_coefficients, streaming.clustering_coefficients);
+    }
+
+    #[test]
+    fn test_clustering_coefficient() {
+        // Triangle 0-1-2 with a pendant 3 attached to 2
+        let storage = GraphStorage::new();
+        let ids: Vec<_> = (0..4)
+            .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+            .collect();
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (2, 3)] {
+            storage
+                .add_edge_simple(ids[from], ids[to], "CONNECTS".to_string())
+                .unwrap();
+        }
+
+        let result = clustering_coefficient(&storage).unwrap();
+        assert_eq!(result.local_coefficients[&ids[0]], 1.0);
+        assert!((result.local_coefficients[&ids[2]] - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(result.local_coefficients[&ids[3]], 0.0);
+        assert!((result.average_coefficient - (2.0 + 1.0 / 3.0) / 4.0).abs() < 1e-9);
+        // One triangle over 1 + 1 + 3 connected triples
+        assert!((result.global_coefficient - 3.0 / 5.0).abs() < 1e-9);
+        assert_eq!(triangle_count(&storage).unwrap().node_triangles[&ids[2]], 1);
+    }
+
+    #[test]
+    fn test_motif_count() {
+        // A 4-clique {0, 1, 2, 3} plus a square 3 - 4 - 5 - 6 - 3
+        let storage = GraphStorage::new();
+        let ids: Vec<_> = (0..7)
+            .map(|_| storage.add_node(Node::new(vec!["Node".to_string()])).unwrap())
+            .collect();
+        let mut edges = vec![(3, 4), (4, 5), (5, 6), (6, 3)];
+        for i in 0..4 {
+            for j in i + 1..4 {
+                edges.push((i, j));
+            }
+        }
+        for (from, to) in edges {
+            storage
+                .add_edge_simple(ids[from], ids[to], "CONNECTS".to_string())
+                .unwrap();
+        }
+
+        let result = motif_count(&storage).unwrap();
+        assert_eq!(result.totals.four_cliques, 1);
+        assert_eq!(result.totals.triangles, 4);
+        // Three squares inside the clique and the outer one
+        assert_eq!(result.totals.squares, 4);
+        assert_eq!(result.node_counts[&ids[0]].four_cliques, 1);
+        assert_eq!(result.node_counts[&ids[5]].squares, 1);
+        assert_eq!(result.node_counts[&ids[3]].squares, 4);
+        // Node 3 has degree 5: 10 pairs, 3 of them closed
+        assert_eq!(result.node_counts[&ids[3]].wedges, 7);
+        assert_eq!(result.node_counts[&ids[0]].wedges, 0);
+    }
+}
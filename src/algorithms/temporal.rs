@@ -0,0 +1,282 @@
+//! Time-respecting traversal
+//!
+//! In event and transaction graphs an edge only exists at the moment in its
+//! timestamp property, so a path is only meaningful if its timestamps never
+//! decrease: money cannot be forwarded before it arrives. These variants of
+//! BFS and shortest path follow only edges whose timestamp falls inside a
+//! [`TimeWindow`] and is no earlier than the edge before it.
+//!
+//! Timestamps are Unix milliseconds stored as integers, floats, or RFC 3339
+//! strings. Edges without a readable timestamp are never followed.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use deepgraph::algorithms::{temporal_bfs, temporal_shortest_path, TemporalConfig, TimeWindow};
+//!
+//! let config = TemporalConfig::new("sent_at").with_window(TimeWindow::new(day_start, day_end));
+//! let reach = temporal_bfs(&storage, account, &config)?;
+//! if let Some(path) = temporal_shortest_path(&storage, account, suspect, &config)? {
+//!     println!("{} hops, arriving at {}", path.edges.len(), path.arrival_time);
+//! }
+//! ```
+
+use crate::error::Result;
+use crate::graph::{Edge, EdgeId, NodeId, PropertyValue};
+use crate::storage::StorageBackend;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Inclusive range of timestamps in Unix milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl TimeWindow {
+    /// Timestamps from `start` to `end`, inclusive
+    pub fn new(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+
+    /// Every timestamp
+    pub fn unbounded() -> Self {
+        Self::new(i64::MIN, i64::MAX)
+    }
+
+    /// Whether `timestamp` lies in the window
+    pub fn contains(&self, timestamp: i64) -> bool {
+        self.start <= timestamp && timestamp <= self.end
+    }
+}
+
+impl Default for TimeWindow {
+    fn default() -> Self {
+        Self::unbounded()
+    }
+}
+
+/// Configuration for temporal traversal
+#[derive(Debug, Clone)]
+pub struct TemporalConfig {
+    /// Edge property holding the timestamp
+    pub timestamp_property: String,
+    /// Edges outside this window are ignored
+    pub window: TimeWindow,
+}
+
+impl TemporalConfig {
+    /// Read timestamps from `timestamp_property`, with no window
+    pub fn new(timestamp_property: impl Into<String>) -> Self {
+        Self {
+            timestamp_property: timestamp_property.into(),
+            window: TimeWindow::unbounded(),
+        }
+    }
+
+    /// Only follow edges inside `window`
+    pub fn with_window(mut self, window: TimeWindow) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Timestamp of `edge` if it can be followed
+    fn timestamp(&self, edge: &Edge) -> Option<i64> {
+        let timestamp = timestamp_millis(edge.get_property(&self.timestamp_property)?)?;
+        self.window.contains(timestamp).then_some(timestamp)
+    }
+}
+
+/// Unix milliseconds of a timestamp property value
+pub(crate) fn timestamp_millis(value: &PropertyValue) -> Option<i64> {
+    match value {
+        PropertyValue::Integer(ms) => Some(*ms),
+        PropertyValue::Float(ms) => Some(*ms as i64),
+        PropertyValue::String(s) => Some(chrono::DateTime::parse_from_rfc3339(s).ok()?.timestamp_millis()),
+        _ => None,
+    }
+}
+
+/// Result of a temporal BFS
+#[derive(Debug, Clone)]
+pub struct TemporalBfsResult {
+    /// Earliest time each reachable node can be reached; the source maps to
+    /// the window start
+    pub arrival_times: HashMap<NodeId, i64>,
+    /// Edges on the earliest-arrival path to each node
+    pub hops: HashMap<NodeId, usize>,
+    /// Node and edge each node is reached through, `None` for the source
+    pub parents: HashMap<NodeId, Option<(NodeId, EdgeId)>>,
+}
+
+impl TemporalBfsResult {
+    /// Nodes reachable from the source, in order of arrival
+    pub fn reached(&self) -> Vec<NodeId> {
+        let mut nodes: Vec<_> = self.arrival_times.iter().map(|(&node, &time)| (time, node)).collect();
+        nodes.sort();
+        nodes.into_iter().map(|(_, node)| node).collect()
+    }
+}
+
+/// A time-respecting path
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemporalPath {
+    /// Nodes from source to target
+    pub nodes: Vec<NodeId>,
+    /// Edges between consecutive nodes
+    pub edges: Vec<EdgeId>,
+    /// Timestamp of each edge, non-decreasing
+    pub timestamps: Vec<i64>,
+    /// Timestamp of the last edge, or the window start for an empty path
+    pub arrival_time: i64,
+}
+
+/// Temporal breadth-first search from `source`
+///
+/// Finds every node reachable along outgoing edges with non-decreasing
+/// timestamps inside the window, together with its earliest arrival time.
+/// Fails if `source` does not exist.
+pub fn temporal_bfs<S: StorageBackend + ?Sized>(
+    storage: &S,
+    source: NodeId,
+    config: &TemporalConfig,
+) -> Result<TemporalBfsResult> {
+    storage.get_node(source)?;
+    let mut result = TemporalBfsResult {
+        arrival_times: HashMap::from([(source, config.window.start)]),
+        hops: HashMap::from([(source, 0)]),
+        parents: HashMap::from([(source, None)]),
+    };
+
+    // Settle nodes in order of arrival, as in Dijkstra
+    let mut queue = BinaryHeap::from([Reverse((config.window.start, source))]);
+    while let Some(Reverse((time, node))) = queue.pop() {
+        if result.arrival_times[&node] < time {
+            continue;
+        }
+        let hops = result.hops[&node];
+        for edge in storage.get_outgoing_edges(node)? {
+            let Some(timestamp) = config.timestamp(&edge).filter(|&ts| ts >= time) else {
+                continue;
+            };
+            let next = edge.to();
+            if result.arrival_times.get(&next).map_or(true, |&best| timestamp < best) {
+                result.arrival_times.insert(next, timestamp);
+                result.hops.insert(next, hops + 1);
+                result.parents.insert(next, Some((node, edge.id())));
+                queue.push(Reverse((timestamp, next)));
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Fewest-hop time-respecting path from `source` to `target`
+///
+/// Among the paths with fewest edges, returns one arriving earliest, or
+/// `None` if `target` cannot be reached within the window. Fails if either
+/// node does not exist.
+pub fn temporal_shortest_path<S: StorageBackend + ?Sized>(
+    storage: &S,
+    source: NodeId,
+    target: NodeId,
+    config: &TemporalConfig,
+) -> Result<Option<TemporalPath>> {
+    storage.get_node(source)?;
+    storage.get_node(target)?;
+
+    // Earliest arrival at each node using at most the hops explored so far;
+    // a later layer only revisits a node if it gets there strictly earlier
+    let mut best: HashMap<NodeId, i64> = HashMap::from([(source, config.window.start)]);
+    let mut frontier: HashMap<NodeId, i64> = best.clone();
+    // Per layer: node -> (previous node, edge, timestamp)
+    let mut layers: Vec<HashMap<NodeId, (NodeId, EdgeId, i64)>> = Vec::new();
+
+    while !frontier.contains_key(&target) {
+        if frontier.is_empty() {
+            return Ok(None);
+        }
+        let mut layer: HashMap<NodeId, (NodeId, EdgeId, i64)> = HashMap::new();
+        for (&node, &time) in &frontier {
+            for edge in storage.get_outgoing_edges(node)? {
+                let Some(timestamp) = config.timestamp(&edge).filter(|&ts| ts >= time) else {
+                    continue;
+                };
+                let next = edge.to();
+                let improves = best.get(&next).map_or(true, |&t| timestamp < t)
+                    && layer.get(&next).map_or(true, |&(_, _, t)| timestamp < t);
+                if improves {
+                    layer.insert(next, (node, edge.id(), timestamp));
+                }
+            }
+        }
+        frontier = layer.iter().map(|(&node, &(_, _, time))| (node, time)).collect();
+        best.extend(frontier.iter().map(|(&node, &time)| (node, time)));
+        layers.push(layer);
+    }
+
+    // Walk back through the layers
+    let mut nodes = vec![target];
+    let mut edges = Vec::new();
+    let mut timestamps = Vec::new();
+    for layer in layers.iter().rev() {
+        let (previous, edge, timestamp) = layer[nodes.last().unwrap()];
+        nodes.push(previous);
+        edges.push(edge);
+        timestamps.push(timestamp);
+    }
+    nodes.reverse();
+    edges.reverse();
+    timestamps.reverse();
+    Ok(Some(TemporalPath {
+        arrival_time: timestamps.last().copied().unwrap_or(config.window.start),
+        nodes,
+        edges,
+        timestamps,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+    use crate::storage::GraphStorage;
+
+    fn transfer(storage: &GraphStorage, from: NodeId, to: NodeId, at: i64) {
+        let mut edge = Edge::new(from, to, "TRANSFER".to_string());
+        edge.set_property("at".to_string(), PropertyValue::Integer(at));
+        storage.add_edge(edge).unwrap();
+    }
+
+    #[test]
+    fn test_time_respecting_paths() {
+        // a -10-> b -5-> c is not time-respecting; a -10-> b -20-> d -30-> c is,
+        // and a -1-> e -2-> c arrives earliest but lies outside the window
+        let storage = GraphStorage::new();
+        let [a, b, c, d, e] = [(); 5].map(|_| storage.add_node(Node::new(vec!["Account".to_string()])).unwrap());
+        transfer(&storage, a, b, 10);
+        transfer(&storage, b, c, 5);
+        transfer(&storage, b, d, 20);
+        transfer(&storage, d, c, 30);
+        transfer(&storage, a, e, 1);
+        transfer(&storage, e, c, 2);
+
+        let config = TemporalConfig::new("at").with_window(TimeWindow::new(5, 100));
+        let reach = temporal_bfs(&storage, a, &config).unwrap();
+        assert_eq!(reach.arrival_times[&c], 30);
+        assert_eq!(reach.hops[&c], 3);
+        assert!(!reach.arrival_times.contains_key(&e));
+        assert_eq!(reach.reached(), vec![a, b, d, c]);
+
+        let path = temporal_shortest_path(&storage, a, c, &config).unwrap().unwrap();
+        assert_eq!(path.nodes, vec![a, b, d, c]);
+        assert_eq!(path.timestamps, vec![10, 20, 30]);
+
+        let unbounded = TemporalConfig::new("at");
+        let path = temporal_shortest_path(&storage, a, c, &unbounded).unwrap().unwrap();
+        assert_eq!(path.nodes, vec![a, e, c]);
+        assert_eq!(path.arrival_time, 2);
+        assert!(temporal_shortest_path(&storage, c, a, &unbounded).unwrap().is_none());
+    }
+}
@@ -1,8 +1,8 @@
-//! Graph traversal algorithms (BFS, DFS)
+//! Graph traversal algorithms (BFS, DFS, configurable [`Traversal`])
 
 use crate::error::Result;
-use crate::graph::NodeId;
-use crate::storage::GraphStorage;
+use crate::graph::{Edge, Node, NodeId};
+use crate::storage::StorageBackend;
 use log::info;
 use std::collections::{HashMap, HashSet, VecDeque};
 
@@ -50,8 +50,8 @@ pub struct DFSResult {
 /// let result = bfs(&storage, start_id, None)?;
 /// println!("Visited {} nodes", result.visited.len());
 /// ```
-pub fn bfs(
-    storage: &GraphStorage,
+pub fn bfs<S: StorageBackend + ?Sized>(
+    storage: &S,
     start_node: NodeId,
     max_depth: Option<usize>,
 ) -> Result<BFSResult> {
@@ -126,7 +126,7 @@ pub fn bfs(
 /// let result = dfs(&storage, start_id)?;
 /// println!("Visited {} nodes", result.visited.len());
 /// ```
-pub fn dfs(storage: &GraphStorage, start_node: NodeId) -> Result<DFSResult> {
+pub fn dfs<S: StorageBackend + ?Sized>(storage: &S, start_node: NodeId) -> Result<DFSResult> {
     info!("Starting DFS from node {}", start_node);
     
     // Verify start node exists
@@ -140,8 +140,8 @@ pub fn dfs(storage: &GraphStorage, start_node: NodeId) -> Result<DFSResult> {
     let mut time = 0;
 
     // DFS recursive helper
-    fn dfs_visit(
-        storage: &GraphStorage,
+    fn dfs_visit<S: StorageBackend + ?Sized>(
+        storage: &S,
         node: NodeId,
         visited: &mut Vec<NodeId>,
         discovery_time: &mut HashMap<NodeId, usize>,
@@ -203,9 +203,287 @@ pub fn dfs(storage: &GraphStorage, start_node: NodeId) -> Result<DFSResult> {
     Ok(result)
 }
 
+/// Order in which a [`Traversal`] visits nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalOrder {
+    /// Nearest nodes first
+    BreadthFirst,
+    /// Follow each branch as deep as possible first
+    DepthFirst,
+}
+
+/// Which edges a [`Traversal`] follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalDirection {
+    /// From source to target
+    Outgoing,
+    /// From target to source
+    Incoming,
+    /// Either way
+    Both,
+}
+
+/// A node reached by a [`Traversal`]
+#[derive(Debug, Clone)]
+pub struct TraversalStep {
+    /// The node reached
+    pub node: Node,
+    /// Number of edges from the start node
+    pub depth: usize,
+    /// The node it was reached from, `None` for the start node
+    pub parent: Option<NodeId>,
+    /// The edge it was reached through, `None` for the start node
+    pub edge: Option<Edge>,
+}
+
+/// What a visitor tells [`Traversal::visit`] to do next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitControl {
+    /// Keep going, expanding the node just visited
+    Continue,
+    /// Keep going, but do not expand the node just visited
+    Prune,
+    /// End the traversal
+    Stop,
+}
+
+type NodeFilter<'a> = Box<dyn Fn(&Node) -> bool + 'a>;
+
+/// Configurable graph traversal
+///
+/// Generalizes [`bfs`] and [`dfs`] with a depth limit, a direction, edge
+/// type and node filters, and either an iterator or a visitor callback as
+/// output. Nodes rejected by the node filter are neither returned nor
+/// expanded; the start node is always returned. Every node is visited at
+/// most once.
+///
+/// # Example
+/// ```rust,ignore
+/// use deepgraph::algorithms::{Traversal, TraversalDirection, VisitControl};
+///
+/// let friends_of_friends: Vec<_> = Traversal::new(&storage, alice)
+///     .with_max_depth(2)
+///     .with_direction(TraversalDirection::Both)
+///     .with_edge_types(["KNOWS"])
+///     .with_node_filter(|node| node.has_label("Person"))
+///     .iter()
+///     .collect::<Result<_>>()?;
+///
+/// Traversal::new(&storage, root).depth_first().visit(|step| {
+///     println!("{}{}", "  ".repeat(step.depth), step.node.id());
+///     VisitControl::Continue
+/// })?;
+/// ```
+pub struct Traversal<'a, S: StorageBackend + ?Sized> {
+    storage: &'a S,
+    start: NodeId,
+    order: TraversalOrder,
+    direction: TraversalDirection,
+    max_depth: Option<usize>,
+    edge_types: Option<HashSet<String>>,
+    node_filter: Option<NodeFilter<'a>>,
+}
+
+impl<'a, S: StorageBackend + ?Sized> Traversal<'a, S> {
+    /// Traverse `storage` breadth-first along outgoing edges from `start`
+    pub fn new(storage: &'a S, start: NodeId) -> Self {
+        Self {
+            storage,
+            start,
+            order: TraversalOrder::BreadthFirst,
+            direction: TraversalDirection::Outgoing,
+            max_depth: None,
+            edge_types: None,
+            node_filter: None,
+        }
+    }
+
+    /// Visit nearest nodes first (the default)
+    pub fn breadth_first(mut self) -> Self {
+        self.order = TraversalOrder::BreadthFirst;
+        self
+    }
+
+    /// Follow each branch as deep as possible first
+    pub fn depth_first(mut self) -> Self {
+        self.order = TraversalOrder::DepthFirst;
+        self
+    }
+
+    /// Follow edges in `direction`
+    pub fn with_direction(mut self, direction: TraversalDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Stop expanding at `max_depth` edges from the start node
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Follow only edges of these relationship types
+    pub fn with_edge_types<I, T>(mut self, edge_types: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.edge_types = Some(edge_types.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Visit only nodes for which `filter` returns true
+    pub fn with_node_filter(mut self, filter: impl Fn(&Node) -> bool + 'a) -> Self {
+        self.node_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Iterate over the nodes reached
+    pub fn iter(self) -> TraversalIter<'a, S> {
+        let mut frontier = VecDeque::new();
+        frontier.push_back(Pending {
+            node: self.start,
+            depth: 0,
+            parent: None,
+            edge: None,
+        });
+        TraversalIter {
+            traversal: self,
+            frontier,
+            visited: HashSet::new(),
+            expand: None,
+        }
+    }
+
+    /// Call `visitor` on every node reached, returning how many were visited
+    pub fn visit(self, mut visitor: impl FnMut(&TraversalStep) -> VisitControl) -> Result<usize> {
+        let mut iter = self.iter();
+        let mut count = 0;
+        while let Some(step) = iter.next() {
+            let step = step?;
+            count += 1;
+            match visitor(&step) {
+                VisitControl::Continue => {}
+                VisitControl::Prune => iter.prune(),
+                VisitControl::Stop => break,
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// A node waiting to be visited
+struct Pending {
+    node: NodeId,
+    depth: usize,
+    parent: Option<NodeId>,
+    edge: Option<Edge>,
+}
+
+/// Iterator over the nodes reached by a [`Traversal`]
+///
+/// A node's neighbours are fetched only when the iterator advances past
+/// it, so [`TraversalIter::prune`] can skip them.
+pub struct TraversalIter<'a, S: StorageBackend + ?Sized> {
+    traversal: Traversal<'a, S>,
+    frontier: VecDeque<Pending>,
+    visited: HashSet<NodeId>,
+    /// Last node returned, to expand on the next call
+    expand: Option<(NodeId, usize)>,
+}
+
+impl<'a, S: StorageBackend + ?Sized> TraversalIter<'a, S> {
+    /// Do not expand the node returned last
+    pub fn prune(&mut self) {
+        self.expand = None;
+    }
+
+    fn follows(&self, edge: &Edge) -> bool {
+        self.traversal
+            .edge_types
+            .as_ref()
+            .map_or(true, |types| types.contains(edge.relationship_type()))
+    }
+
+    fn expand(&mut self, node: NodeId, depth: usize) -> Result<()> {
+        if self.traversal.max_depth.is_some_and(|max| depth >= max) {
+            return Ok(());
+        }
+        let storage = self.traversal.storage;
+        let mut next = Vec::new();
+        if self.traversal.direction != TraversalDirection::Incoming {
+            for edge in storage.get_outgoing_edges(node)? {
+                next.push((edge.to(), edge));
+            }
+        }
+        if self.traversal.direction != TraversalDirection::Outgoing {
+            for edge in storage.get_incoming_edges(node)? {
+                next.push((edge.from(), edge));
+            }
+        }
+        let next: Vec<Pending> = next
+            .into_iter()
+            .filter(|(neighbor, edge)| !self.visited.contains(neighbor) && self.follows(edge))
+            .map(|(neighbor, edge)| Pending {
+                node: neighbor,
+                depth: depth + 1,
+                parent: Some(node),
+                edge: Some(edge),
+            })
+            .collect();
+        match self.traversal.order {
+            TraversalOrder::BreadthFirst => self.frontier.extend(next),
+            // Pushed in reverse so the first neighbour is popped first
+            TraversalOrder::DepthFirst => self.frontier.extend(next.into_iter().rev()),
+        }
+        Ok(())
+    }
+}
+
+impl<'a, S: StorageBackend + ?Sized> Iterator for TraversalIter<'a, S> {
+    type Item = Result<TraversalStep>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((node, depth)) = self.expand.take() {
+            if let Err(e) = self.expand(node, depth) {
+                return Some(Err(e));
+            }
+        }
+
+        loop {
+            let pending = match self.traversal.order {
+                TraversalOrder::BreadthFirst => self.frontier.pop_front()?,
+                TraversalOrder::DepthFirst => self.frontier.pop_back()?,
+            };
+            if !self.visited.insert(pending.node) {
+                continue;
+            }
+            let node = match self.traversal.storage.get_node(pending.node) {
+                Ok(node) => node,
+                Err(e) => return Some(Err(e)),
+            };
+            if pending.parent.is_some() {
+                if let Some(filter) = &self.traversal.node_filter {
+                    if !filter(&node) {
+                        continue;
+                    }
+                }
+            }
+            self.expand = Some((pending.node, pending.depth));
+            return Some(Ok(TraversalStep {
+                node,
+                depth: pending.depth,
+                parent: pending.parent,
+                edge: pending.edge,
+            }));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::GraphStorage;
     use crate::graph::Node;
 
     #[test]
@@ -260,5 +538,44 @@ mod tests {
         assert!(result.discovery_time.contains_key(&id1));
         assert!(result.finish_time.contains_key(&id1));
     }
-}
 
+    #[test]
+    fn test_traversal_constraints() {
+        // root -KNOWS-> a -KNOWS-> b, root -OWNS-> car, c -KNOWS-> root
+        let storage = GraphStorage::new();
+        let [root, a, b, c] = [(); 4].map(|_| storage.add_node(Node::new(vec!["Person".to_string()])).unwrap());
+        let car = storage.add_node(Node::new(vec!["Car".to_string()])).unwrap();
+        for (from, to, kind) in [(root, a, "KNOWS"), (a, b, "KNOWS"), (root, car, "OWNS"), (c, root, "KNOWS")] {
+            storage.add_edge_simple(from, to, kind.to_string()).unwrap();
+        }
+
+        let ids = |traversal: Traversal<'_, GraphStorage>| -> Vec<NodeId> {
+            traversal.iter().map(|step| step.unwrap().node.id()).collect()
+        };
+        assert_eq!(ids(Traversal::new(&storage, root).with_edge_types(["KNOWS"])), vec![root, a, b]);
+        assert_eq!(ids(Traversal::new(&storage, root).with_max_depth(1)).len(), 3);
+        assert_eq!(
+            ids(Traversal::new(&storage, root)
+                .with_direction(TraversalDirection::Both)
+                .with_node_filter(|node| !node.has_label("Car"))),
+            vec![root, a, c, b]
+        );
+
+        // Pruning a stops its subtree; depth-first reaches b via a
+        let mut seen = Vec::new();
+        let count = Traversal::new(&storage, root)
+            .depth_first()
+            .with_edge_types(["KNOWS"])
+            .visit(|step| {
+                seen.push((step.node.id(), step.depth, step.parent));
+                if step.node.id() == a {
+                    VisitControl::Prune
+                } else {
+                    VisitControl::Continue
+                }
+            })
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(seen, vec![(root, 0, None), (a, 1, Some(root))]);
+    }
+}
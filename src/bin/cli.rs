@@ -3,7 +3,7 @@
 use clap::Parser;
 use deepgraph::{
     storage::{DiskStorage, MemoryStorage, StorageBackend},
-    query::{CypherParser, QueryPlanner, QueryExecutor},
+    query::{rewrite::prepare, CypherParser, GraphView, QueryPlanner, QueryExecutor},
     import::{CsvImporter, JsonImporter},
 };
 use prettytable::{Table, Row, Cell, format};
@@ -30,7 +30,10 @@ struct Cli {
     #[arg(short, long)]
     file: Option<String>,
     
-    /// Output format: table, json, csv
+    /// Output format: table, json, csv, d3, cytoscape
+    ///
+    /// `d3` and `cytoscape` print the returned nodes and the edges between
+    /// them as graph JSON for visualization.
     #[arg(long, default_value = "table")]
     output: String,
     
@@ -185,6 +188,7 @@ fn handle_single_query(cli: &Cli, query: &str) {
                     Ok(result) => {
                         match cli.output.as_str() {
                             "json" => print_json_output(&result),
+                            "d3" | "cytoscape" => print_graph_output(&result, storage.as_ref(), &cli.output),
                             "csv" => print_csv_output(&result),
                             _ => print_table_output(&result),
                         }
@@ -207,6 +211,7 @@ fn handle_single_query(cli: &Cli, query: &str) {
             Ok(result) => {
                 match cli.output.as_str() {
                     "json" => print_json_output(&result),
+                    "d3" | "cytoscape" => print_graph_output(&result, storage.as_ref(), &cli.output),
                     "csv" => print_csv_output(&result),
                     _ => print_table_output(&result),
                 }
@@ -392,6 +397,8 @@ fn execute_cypher_query_memory(query: &str, storage: &Arc<MemoryStorage>) -> Res
     
     let ast = CypherParser::parse(query)
         .map_err(|e| format!("Parse error: {}", e))?;
+    let ast = prepare(&ast, &Default::default())
+        .map_err(|e| format!("Preparation error: {}", e))?;
     
     let query_ast = match ast {
         deepgraph::query::ast::Statement::Query(q) => q,
@@ -422,6 +429,8 @@ fn execute_cypher_query_disk(query: &str, storage: &Arc<DiskStorage>) -> Result<
     
     let ast = CypherParser::parse(query)
         .map_err(|e| format!("Parse error: {}", e))?;
+    let ast = prepare(&ast, &Default::default())
+        .map_err(|e| format!("Preparation error: {}", e))?;
     
     let query_ast = match ast {
         deepgraph::query::ast::Statement::Query(q) => q,
@@ -502,6 +511,23 @@ fn print_json_output(result: &QueryResult) {
     println!("{}", serde_json::to_string_pretty(&json_rows).unwrap());
 }
 
+fn print_graph_output<S: StorageBackend>(result: &QueryResult, storage: &S, format: &str) {
+    match GraphView::from_rows(storage, &result.rows) {
+        Ok(view) => {
+            let json = if format == "cytoscape" {
+                view.to_cytoscape_json()
+            } else {
+                view.to_d3_json()
+            };
+            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        }
+        Err(e) => {
+            eprintln!("❌ Failed to build graph view: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn print_csv_output(result: &QueryResult) {
     // Print header
     println!("{}", result.columns.join(","));
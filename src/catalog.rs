@@ -0,0 +1,250 @@
+//! Catalog of named, isolated graphs
+//!
+//! A [`Catalog`] lets one process manage several datasets side by side. Each
+//! graph is its own storage backend, so data, indexes and statistics never
+//! mix. Queries run in a [`Session`], one per connection, and pick their
+//! graph with `USE`:
+//!
+//! ```text
+//! USE tenant_a MATCH (n:Person) RETURN n.name
+//! USE tenant_b                 -- select tenant_b for this session
+//! MATCH (n) RETURN n.name      -- runs against tenant_b
+//! ```
+//!
+//! A session starts in the catalog's default graph, the first one added.
+//!
+//! `FROM GRAPH` federated queries run across all graphs in the catalog.
+
+use crate::error::{DeepGraphError, Result};
+use crate::query::ast::{Query, Statement};
+use crate::query::executor::{QueryExecutor, QueryResult};
+use crate::query::federation::FederatedExecutor;
+use crate::query::rewrite::prepare;
+use crate::query::{CypherParser, QueryPlanner};
+use crate::storage::StorageBackend;
+use log::info;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Named graphs, each backed by its own storage
+pub struct Catalog<S: StorageBackend> {
+    graphs: RwLock<HashMap<String, Arc<S>>>,
+    /// Graph new sessions start in
+    default: RwLock<Option<String>>,
+}
+
+impl<S: StorageBackend> Catalog<S> {
+    /// Create an empty catalog
+    pub fn new() -> Self {
+        Self {
+            graphs: RwLock::new(HashMap::new()),
+            default: RwLock::new(None),
+        }
+    }
+
+    /// Add a graph; the first graph added becomes the default one
+    pub fn with_graph(self, name: impl Into<String>, storage: Arc<S>) -> Self {
+        let name = name.into();
+        self.graphs.write().insert(name.clone(), storage);
+        self.default.write().get_or_insert(name);
+        self
+    }
+
+    /// Register a graph under a new name
+    pub fn create_graph(&self, name: &str, storage: Arc<S>) -> Result<Arc<S>> {
+        let mut graphs = self.graphs.write();
+        if graphs.contains_key(name) {
+            return Err(DeepGraphError::InvalidOperation(format!("Graph {} already exists", name)));
+        }
+        info!("Creating graph {}", name);
+        graphs.insert(name.to_string(), storage.clone());
+        self.default.write().get_or_insert_with(|| name.to_string());
+        Ok(storage)
+    }
+
+    /// Remove a graph from the catalog, returning its storage
+    pub fn drop_graph(&self, name: &str) -> Result<Arc<S>> {
+        let storage = self
+            .graphs
+            .write()
+            .remove(name)
+            .ok_or_else(|| DeepGraphError::NotFound(format!("Graph {} not found", name)))?;
+        info!("Dropped graph {}", name);
+        let mut default = self.default.write();
+        if default.as_deref() == Some(name) {
+            *default = None;
+        }
+        Ok(storage)
+    }
+
+    /// Storage of a graph
+    pub fn graph(&self, name: &str) -> Result<Arc<S>> {
+        self.graphs
+            .read()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| DeepGraphError::NotFound(format!("Graph {} not found", name)))
+    }
+
+    /// Whether a graph is registered
+    pub fn contains(&self, name: &str) -> bool {
+        self.graphs.read().contains_key(name)
+    }
+
+    /// Names of all graphs, sorted
+    pub fn graph_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.graphs.read().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Number of graphs
+    pub fn len(&self) -> usize {
+        self.graphs.read().len()
+    }
+
+    /// Whether the catalog has no graphs
+    pub fn is_empty(&self) -> bool {
+        self.graphs.read().is_empty()
+    }
+
+    /// Name of the graph new sessions start in
+    pub fn default_graph(&self) -> Option<String> {
+        self.default.read().clone()
+    }
+
+    /// Open a session starting in the default graph
+    pub fn session(self: &Arc<Self>) -> Session<S> {
+        Session {
+            catalog: self.clone(),
+            current: self.default_graph(),
+        }
+    }
+}
+
+/// One connection's view of a [`Catalog`]
+///
+/// Each session tracks its own current graph, so `USE` in one session never
+/// changes where another session's queries run.
+pub struct Session<S: StorageBackend> {
+    catalog: Arc<Catalog<S>>,
+    /// Graph that queries without `USE` run against
+    current: Option<String>,
+}
+
+impl<S: StorageBackend> Session<S> {
+    /// Catalog the session runs against
+    pub fn catalog(&self) -> &Arc<Catalog<S>> {
+        &self.catalog
+    }
+
+    /// Select the graph that queries without `USE` run against
+    pub fn use_graph(&mut self, name: &str) -> Result<()> {
+        if !self.catalog.contains(name) {
+            return Err(DeepGraphError::NotFound(format!("Graph {} not found", name)));
+        }
+        self.current = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Name of the current graph
+    pub fn current_graph(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Parse and execute a Cypher query
+    pub fn execute(&mut self, query: &str) -> Result<QueryResult> {
+        let Statement::Query(query) = prepare(&CypherParser::parse(query)?, &HashMap::new())?;
+        self.execute_query(&query)
+    }
+
+    /// Execute a parsed query against the graph it selects
+    ///
+    /// A bare `USE graph` changes the session's current graph and returns
+    /// an empty result.
+    pub fn execute_query(&mut self, query: &Query) -> Result<QueryResult> {
+        match query {
+            Query::Use(use_query) => match &use_query.query {
+                Some(inner) => self.run(&self.catalog.graph(&use_query.graph)?, inner),
+                None => {
+                    self.use_graph(&use_query.graph)?;
+                    Ok(QueryResult::empty())
+                }
+            },
+            Query::Federated(federated) => {
+                let executor = self
+                    .catalog
+                    .graphs
+                    .read()
+                    .iter()
+                    .fold(FederatedExecutor::new(), |executor, (name, storage)| {
+                        executor.with_graph(name.clone(), storage.clone())
+                    });
+                executor.execute(federated)
+            }
+            other => {
+                let name = self.current.as_deref().ok_or_else(|| {
+                    DeepGraphError::InvalidOperation("No graph selected; run USE <graph> first".to_string())
+                })?;
+                self.run(&self.catalog.graph(name)?, other)
+            }
+        }
+    }
+
+    fn run(&self, storage: &Arc<S>, query: &Query) -> Result<QueryResult> {
+        let planner = QueryPlanner::new();
+        let plan = planner.physical_plan(&planner.logical_plan(query)?)?;
+        QueryExecutor::new(storage.clone()).execute(&plan)
+    }
+}
+
+impl<S: StorageBackend> Default for Catalog<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Node, PropertyValue};
+    use crate::storage::MemoryStorage;
+
+    fn graph_with(name: &str) -> Arc<MemoryStorage> {
+        let storage = Arc::new(MemoryStorage::new());
+        let mut node = Node::new(vec!["Person".to_string()]);
+        node.set_property("name".to_string(), PropertyValue::String(name.to_string()));
+        storage.add_node(node).unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_use_selects_graph() {
+        let catalog = Arc::new(
+            Catalog::new()
+                .with_graph("a", graph_with("Ann"))
+                .with_graph("b", graph_with("Bo")),
+        );
+        assert_eq!(catalog.graph_names(), vec!["a", "b"]);
+        let mut session = catalog.session();
+        assert_eq!(session.current_graph(), Some("a"));
+
+        let name = |result: QueryResult| result.rows[0]["name"].clone();
+        let result = session.execute("USE b MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(name(result), PropertyValue::String("Bo".to_string()));
+
+        // A bare USE switches the graph for later queries of this session only
+        session.execute("USE b").unwrap();
+        let result = session.execute("MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(name(result), PropertyValue::String("Bo".to_string()));
+        let mut other = catalog.session();
+        let result = other.execute("MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(name(result), PropertyValue::String("Ann".to_string()));
+
+        assert!(session.execute("USE missing MATCH (n) RETURN n").is_err());
+        catalog.drop_graph("b").unwrap();
+        assert!(session.execute("MATCH (n) RETURN n").is_err());
+        assert!(other.execute("MATCH (n) RETURN n").is_ok());
+    }
+}
@@ -9,6 +9,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::fs;
+use crate::encryption::EncryptionKey;
 use crate::error::{DeepGraphError, Result};
 use log::{info, warn, debug};
 
@@ -29,6 +30,14 @@ pub struct DeepGraphConfig {
     
     /// Logging configuration
     pub logging: LoggingConfig,
+    
+    /// Scheduled snapshot configuration
+    #[serde(default)]
+    pub snapshot: SnapshotConfig,
+    
+    /// Encryption of data at rest
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
 }
 
 /// Storage configuration
@@ -58,6 +67,14 @@ pub struct StorageConfig {
     /// Auto-compact interval in seconds (0 = disabled)
     #[serde(default)]
     pub auto_compact_interval_secs: u64,
+    
+    /// Compress stored records of at least this many bytes (0 = disabled)
+    #[serde(default)]
+    pub compression_threshold_bytes: usize,
+    
+    /// zstd compression level (1-22)
+    #[serde(default = "default_compression_level")]
+    pub compression_level: i32,
 }
 
 fn default_storage_type() -> String {
@@ -72,6 +89,10 @@ fn default_flush_interval_ms() -> u64 {
     100 // Flush every 100ms
 }
 
+fn default_compression_level() -> i32 {
+    3
+}
+
 /// WAL configuration options
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WALConfigOptions {
@@ -91,6 +112,55 @@ pub struct WALConfigOptions {
     pub checkpoint_threshold: usize,
 }
 
+/// Scheduled snapshot configuration
+///
+/// See [`SnapshotScheduler`](crate::persistence::SnapshotScheduler). A
+/// snapshot is taken when either trigger fires; with both at zero the
+/// scheduler only snapshots on request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    /// Run the snapshot scheduler
+    pub enabled: bool,
+    
+    /// Snapshot directory (relative to data_dir or absolute)
+    pub snapshot_dir: String,
+    
+    /// Seconds between snapshots (0 = no time trigger)
+    pub interval_secs: u64,
+    
+    /// Snapshot once the WAL grew by this many MB since the last one
+    /// (0 = no WAL size trigger)
+    pub wal_size_mb: u64,
+    
+    /// Snapshots to keep; older ones are deleted
+    pub keep_count: usize,
+}
+
+/// Encryption of data at rest
+///
+/// See [`EncryptionKey`](crate::encryption::EncryptionKey). The key is
+/// read from `key` if set, otherwise from the environment variable named
+/// by `key_env`; prefer the environment so the key stays out of files.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncryptionConfig {
+    /// Encrypt data at rest
+    pub enabled: bool,
+    
+    /// Key as 64 hex digits
+    pub key: Option<String>,
+    
+    /// Environment variable holding the key
+    pub key_env: String,
+    
+    /// Encrypt WAL segments
+    pub wal: bool,
+    
+    /// Encrypt snapshot files
+    pub snapshots: bool,
+}
+
 /// Index configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexConfig {
@@ -124,6 +194,11 @@ pub struct AlgorithmConfig {
     
     /// Louvain max iterations
     pub louvain_max_iterations: usize,
+
+    /// Seed for every randomized algorithm (Node2Vec walks, sampled
+    /// centrality, profile sampling); `None` seeds from entropy
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 /// Logging configuration
@@ -150,6 +225,8 @@ impl Default for DeepGraphConfig {
             index: IndexConfig::default(),
             algorithm: AlgorithmConfig::default(),
             logging: LoggingConfig::default(),
+            snapshot: SnapshotConfig::default(),
+            encryption: EncryptionConfig::default(),
         }
     }
 }
@@ -164,6 +241,8 @@ impl Default for StorageConfig {
             cache_size_mb: 512,
             flush_interval_ms: default_flush_interval_ms(),
             auto_compact_interval_secs: 0, // Disabled by default
+            compression_threshold_bytes: 0, // Disabled by default
+            compression_level: default_compression_level(),
         }
     }
 }
@@ -180,6 +259,42 @@ impl Default for WALConfigOptions {
     }
 }
 
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            snapshot_dir: "snapshots".to_string(),
+            interval_secs: 3600,
+            wal_size_mb: 256,
+            keep_count: 5,
+        }
+    }
+}
+
+impl std::fmt::Debug for EncryptionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionConfig")
+            .field("enabled", &self.enabled)
+            .field("key", &self.key.as_ref().map(|_| "<redacted>"))
+            .field("key_env", &self.key_env)
+            .field("wal", &self.wal)
+            .field("snapshots", &self.snapshots)
+            .finish()
+    }
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key: None,
+            key_env: crate::encryption::DEFAULT_KEY_ENV.to_string(),
+            wal: true,
+            snapshots: true,
+        }
+    }
+}
+
 impl Default for IndexConfig {
     fn default() -> Self {
         Self {
@@ -199,6 +314,7 @@ impl Default for AlgorithmConfig {
             node2vec_walk_length: 80,
             node2vec_walks_per_node: 10,
             louvain_max_iterations: 100,
+            seed: None,
         }
     }
 }
@@ -317,6 +433,45 @@ impl DeepGraphConfig {
         }
     }
     
+    /// Get full snapshot directory path
+    pub fn snapshot_path(&self) -> PathBuf {
+        let snapshot_dir = PathBuf::from(&self.snapshot.snapshot_dir);
+        if snapshot_dir.is_absolute() {
+            snapshot_dir
+        } else {
+            PathBuf::from(&self.storage.data_dir).join(snapshot_dir)
+        }
+    }
+    
+    /// The encryption key, or `None` if encryption is disabled
+    ///
+    /// Fails if encryption is enabled but no valid key is configured.
+    pub fn encryption_key(&self) -> Result<Option<EncryptionKey>> {
+        if !self.encryption.enabled {
+            return Ok(None);
+        }
+        let key = match &self.encryption.key {
+            Some(hex) => Some(EncryptionKey::from_hex(hex)?),
+            None => EncryptionKey::from_env(&self.encryption.key_env)?,
+        };
+        key.map(Some).ok_or_else(|| {
+            DeepGraphError::InvalidOperation(format!(
+                "Encryption is enabled but no key is configured; set {}",
+                self.encryption.key_env
+            ))
+        })
+    }
+    
+    /// Key for WAL segments, if they are encrypted
+    pub fn wal_encryption_key(&self) -> Result<Option<EncryptionKey>> {
+        Ok(self.encryption_key()?.filter(|_| self.encryption.wal))
+    }
+    
+    /// Key for snapshot files, if they are encrypted
+    pub fn snapshot_encryption_key(&self) -> Result<Option<EncryptionKey>> {
+        Ok(self.encryption_key()?.filter(|_| self.encryption.snapshots))
+    }
+    
     /// Get full index directory path
     pub fn index_path(&self) -> PathBuf {
         let index_dir = PathBuf::from(&self.index.index_dir);
@@ -389,6 +544,24 @@ mod tests {
         let config = DeepGraphConfig::default();
         assert_eq!(config.wal_path(), PathBuf::from("./data/wal"));
         assert_eq!(config.index_path(), PathBuf::from("./data/indices"));
+        assert_eq!(config.snapshot_path(), PathBuf::from("./data/snapshots"));
+    }
+    
+    #[test]
+    fn test_encryption_key() {
+        let mut config = DeepGraphConfig::default();
+        assert!(config.encryption_key().unwrap().is_none());
+        
+        config.encryption.enabled = true;
+        config.encryption.key_env = "DEEPGRAPH_TEST_UNSET_ENCRYPTION_KEY".to_string();
+        assert!(config.encryption_key().is_err());
+        
+        let key = EncryptionKey::generate_hex();
+        config.encryption.key = Some(key.clone());
+        assert!(!format!("{:?}", config).contains(&key));
+        config.encryption.wal = false;
+        assert!(config.snapshot_encryption_key().unwrap().is_some());
+        assert!(config.wal_encryption_key().unwrap().is_none());
     }
 }
 
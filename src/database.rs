@@ -0,0 +1,362 @@
+//! Database entry point assembled from configuration
+//!
+//! [`DeepGraph::open`] reads a [`DeepGraphConfig`] and builds the storage
+//! backend it names, the write-ahead log, the index manager and the MVCC
+//! transaction manager, so callers do not wire the subsystems by hand.
+//!
+//! Opening recovers in a fixed order, so no step works on state a later
+//! step would change:
+//!
+//! 1. In-memory backends load the newest snapshot under
+//!    [`DeepGraphConfig::snapshot_path`], if any.
+//! 2. Committed WAL entries from the snapshot's checkpoint on are
+//!    replayed. If the log no longer reaches back that far, because it was
+//!    truncated after a snapshot that is now missing, opening fails rather
+//!    than silently dropping the truncated writes.
+//! 3. Persistent indexes are verified against the recovered storage and
+//!    rebuilt if they disagree.
+//! 4. Only then is storage wrapped to log new writes and handed out.
+//!
+//! [`DeepGraph::recovery`] reports what each step did.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let config = DeepGraphConfig::from_file("deepgraph.toml")?;
+//! let db = DeepGraph::open(config)?;
+//! db.storage().add_node(Node::new(vec!["Person".to_string()]))?;
+//! ```
+
+use crate::config::DeepGraphConfig;
+use crate::error::{DeepGraphError, Result};
+use crate::index::IndexManager;
+use crate::mvcc::TransactionManager;
+use crate::persistence::SnapshotManager;
+use crate::wal::log::LSN;
+use crate::storage::{CachedStorage, ColumnarStorage, DiskStorage, DurableStorage, MemoryStorage, RecordCompression, StorageBackend};
+use crate::wal::{WALConfig, WALRecovery, WAL};
+use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// A storage backend together with the subsystems configured around it
+pub struct DeepGraph {
+    config: DeepGraphConfig,
+    storage: Arc<dyn StorageBackend>,
+    wal: Option<Arc<WAL>>,
+    indexes: Arc<IndexManager>,
+    transactions: Arc<TransactionManager>,
+    recovery: RecoveryReport,
+}
+
+/// What [`DeepGraph::open`] recovered
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Snapshot loaded, if any
+    pub snapshot_id: Option<String>,
+    /// Nodes loaded from the snapshot
+    pub snapshot_nodes: usize,
+    /// Edges loaded from the snapshot
+    pub snapshot_edges: usize,
+    /// LSN the WAL was replayed from
+    pub wal_from_lsn: LSN,
+    /// Committed WAL operations replayed
+    pub wal_operations: u64,
+    /// Persistent indexes verified against storage
+    pub indexes_verified: usize,
+    /// Indexes rebuilt because they disagreed with storage
+    pub indexes_rebuilt: Vec<String>,
+}
+
+impl DeepGraph {
+    /// Open a database as described by `config`
+    ///
+    /// `storage.storage_type` selects the backend: `"memory"`, `"columnar"`
+    /// or `"disk"`. Disk storage lives at `storage.disk_path` behind a read
+    /// cache sized by `enable_cache`/`cache_size_mb`, compresses records as
+    /// set by `compression_threshold_bytes`, and keeps its indexes
+    /// under [`DeepGraphConfig::index_path`]. When the WAL is enabled, the
+    /// in-memory backends are rebuilt from the committed log entries before
+    /// new entries are appended, and every write through
+    /// [`DeepGraph::storage`] is logged before it is applied, encrypted if
+    /// `encryption` says so. See the [module docs](self) for the order of
+    /// the recovery steps.
+    pub fn open(config: DeepGraphConfig) -> Result<Self> {
+        info!("Opening DeepGraph ({} storage)", config.storage.storage_type);
+
+        let wal_encryption = config.wal_encryption_key()?;
+        let wal_config = config.wal.enabled.then(|| WALConfig {
+            wal_dir: config.wal_path().to_string_lossy().into_owned(),
+            segment_size: config.wal.segment_size_mb.saturating_mul(1024 * 1024),
+            sync_on_write: config.wal.sync_on_write,
+            checkpoint_threshold: config.wal.checkpoint_threshold,
+            throttle: None,
+            retention: Default::default(),
+            encryption: wal_encryption,
+        });
+
+        // A reopened WAL appends to a fresh segment, after what recovery replays
+        let wal = wal_config.clone().map(WAL::new).transpose()?.map(Arc::new);
+
+        let mut recovery = RecoveryReport::default();
+        let (storage, indexes): (Arc<dyn StorageBackend>, IndexManager) =
+            match config.storage.storage_type.to_lowercase().as_str() {
+                "memory" => (
+                    durable(recover(MemoryStorage::new(), &config, wal_config.as_ref(), &mut recovery)?, &wal),
+                    IndexManager::new(),
+                ),
+                "columnar" => (
+                    durable(recover(ColumnarStorage::new(), &config, wal_config.as_ref(), &mut recovery)?, &wal),
+                    IndexManager::new(),
+                ),
+                "disk" => {
+                    let mut disk = DiskStorage::new(&config.storage.disk_path)?;
+                    if let Some(compression) = RecordCompression::from_config(&config.storage) {
+                        disk = disk.with_compression(compression);
+                    }
+                    // sled persists applied writes itself, so the WAL is
+                    // truncated whenever the storage is flushed
+                    (
+                        Arc::new(
+                            DurableStorage::new(CachedStorage::from_config(disk, &config.storage), wal.clone())
+                                .with_truncate_on_flush(),
+                        ),
+                        IndexManager::with_persistence(config.index_path())?,
+                    )
+                }
+                other => {
+                    return Err(DeepGraphError::InvalidOperation(format!(
+                        "Unknown storage type: {} (expected memory, columnar or disk)",
+                        other
+                    )))
+                }
+            };
+
+        verify_indexes(&indexes, storage.as_ref(), &mut recovery)?;
+
+        info!("DeepGraph opened: {} nodes, {} edges", storage.node_count(), storage.edge_count());
+        Ok(Self {
+            config,
+            storage,
+            wal,
+            indexes: Arc::new(indexes),
+            transactions: Arc::new(TransactionManager::new()),
+            recovery,
+        })
+    }
+
+    /// Open an in-memory database with default settings and no WAL
+    pub fn in_memory() -> Result<Self> {
+        let mut config = DeepGraphConfig::default();
+        config.wal.enabled = false;
+        Self::open(config)
+    }
+
+    /// Configuration the database was opened with
+    pub fn config(&self) -> &DeepGraphConfig {
+        &self.config
+    }
+
+    /// What opening the database recovered
+    pub fn recovery(&self) -> &RecoveryReport {
+        &self.recovery
+    }
+
+    /// Storage backend
+    pub fn storage(&self) -> Arc<dyn StorageBackend> {
+        self.storage.clone()
+    }
+
+    /// Write-ahead log, if enabled
+    pub fn wal(&self) -> Option<Arc<WAL>> {
+        self.wal.clone()
+    }
+
+    /// Directory of the write-ahead log, if enabled
+    pub fn wal_path(&self) -> Option<PathBuf> {
+        self.wal.as_ref().map(|_| self.config.wal_path())
+    }
+
+    /// Index manager
+    pub fn indexes(&self) -> Arc<IndexManager> {
+        self.indexes.clone()
+    }
+
+    /// MVCC transaction manager
+    pub fn transactions(&self) -> Arc<TransactionManager> {
+        self.transactions.clone()
+    }
+
+    /// Flush the write-ahead log and storage, and save persistent indexes
+    ///
+    /// For disk storage this also truncates the WAL up to what the flush
+    /// made durable.
+    pub fn flush(&self) -> Result<()> {
+        self.storage.flush()?;
+        self.indexes.flush()
+    }
+}
+
+/// Load the newest snapshot into a fresh in-memory backend, then replay
+/// the committed WAL entries logged since
+fn recover<S: StorageBackend>(
+    storage: S,
+    config: &DeepGraphConfig,
+    wal_config: Option<&WALConfig>,
+    report: &mut RecoveryReport,
+) -> Result<S> {
+    let snapshot_dir = config.snapshot_path();
+    if snapshot_dir.is_dir() {
+        let mut manager = SnapshotManager::new(snapshot_dir)?;
+        if let Some(key) = config.snapshot_encryption_key()? {
+            manager = manager.with_encryption(key);
+        }
+        if let Some(snapshot) = manager.list_snapshots()?.into_iter().next() {
+            info!("Loading snapshot {}", snapshot.id);
+            let nodes = manager.read_nodes(&snapshot)?;
+            let edges = manager.read_edges(&snapshot)?;
+            report.snapshot_nodes = nodes.len();
+            report.snapshot_edges = edges.len();
+            for node in nodes {
+                storage.add_node(node)?;
+            }
+            for edge in edges {
+                storage.add_edge(edge)?;
+            }
+            report.wal_from_lsn = snapshot.wal_lsn.unwrap_or(0);
+            report.snapshot_id = Some(snapshot.id);
+        }
+    }
+
+    if let Some(wal_config) = wal_config {
+        let recovery = WALRecovery::new(wal_config.clone());
+        if let Some(first) = recovery.first_available_lsn()? {
+            if first > report.wal_from_lsn {
+                return Err(DeepGraphError::StorageError(format!(
+                    "WAL starts at LSN {} but recovery needs entries from LSN {}; \
+                     the snapshot covering the truncated entries is missing",
+                    first, report.wal_from_lsn
+                )));
+            }
+        }
+        report.wal_operations = recovery.recover_from(&storage, report.wal_from_lsn)?;
+        if report.wal_operations > 0 {
+            info!("Recovered {} operations from WAL", report.wal_operations);
+        }
+    }
+    Ok(storage)
+}
+
+/// Check persistent indexes against recovered storage, rebuilding any that
+/// disagree
+fn verify_indexes(indexes: &IndexManager, storage: &dyn StorageBackend, report: &mut RecoveryReport) -> Result<()> {
+    // Vector indexes are not backed by a storage scan
+    for info in indexes.list_indices().into_iter().filter(|info| info.index_type.is_some()) {
+        report.indexes_verified += 1;
+        if !indexes.verify(&info.name, storage)?.is_consistent() {
+            warn!("Rebuilding index {} after recovery", info.name);
+            indexes.rebuild(&info.name, storage)?;
+            report.indexes_rebuilt.push(info.name);
+        }
+    }
+    Ok(())
+}
+
+/// Log writes to the WAL, if enabled, before they reach the backend
+fn durable<S: StorageBackend + 'static>(storage: S, wal: &Option<Arc<WAL>>) -> Arc<dyn StorageBackend> {
+    Arc::new(DurableStorage::new(storage, wal.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+
+    #[test]
+    fn test_open_from_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = DeepGraphConfig::default();
+        config.storage.data_dir = dir.path().to_string_lossy().into_owned();
+
+        let db = DeepGraph::open(config.clone()).unwrap();
+        assert!(db.wal().is_some());
+        assert!(db.wal_path().unwrap().starts_with(dir.path()));
+        let id = db.storage().add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        assert!(db.storage().get_node(id).is_ok());
+        assert!(db.transactions().begin_transaction().is_ok());
+
+        config.storage.storage_type = "tape".to_string();
+        assert!(DeepGraph::open(config).is_err());
+    }
+
+    #[test]
+    fn test_open_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = DeepGraphConfig::default();
+        config.storage.storage_type = "disk".to_string();
+        config.storage.data_dir = dir.path().to_string_lossy().into_owned();
+        config.storage.disk_path = dir.path().join("graph.db").to_string_lossy().into_owned();
+        config.wal.enabled = false;
+
+        let id = {
+            let db = DeepGraph::open(config.clone()).unwrap();
+            assert!(db.wal().is_none());
+            db.storage().add_node(Node::new(vec!["Person".to_string()])).unwrap()
+        };
+        let db = DeepGraph::open(config).unwrap();
+        assert!(db.storage().get_node(id).is_ok());
+    }
+    #[test]
+    fn test_memory_writes_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = DeepGraphConfig::default();
+        config.storage.data_dir = dir.path().to_string_lossy().into_owned();
+
+        let id = {
+            let db = DeepGraph::open(config.clone()).unwrap();
+            db.storage().add_node(Node::new(vec!["Person".to_string()])).unwrap()
+        };
+        let db = DeepGraph::open(config.clone()).unwrap();
+        assert!(db.storage().get_node(id).is_ok());
+        db.storage().delete_node(id).unwrap();
+        drop(db);
+
+        let db = DeepGraph::open(config).unwrap();
+        assert_eq!(db.storage().node_count(), 0);
+    }
+
+    #[test]
+    fn test_open_loads_snapshot_then_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = DeepGraphConfig::default();
+        config.storage.data_dir = dir.path().to_string_lossy().into_owned();
+
+        let first = {
+            let db = DeepGraph::open(config.clone()).unwrap();
+            db.storage().add_node(Node::new(vec!["Person".to_string()])).unwrap()
+        };
+        let second = {
+            let db = DeepGraph::open(config.clone()).unwrap();
+            let wal = db.wal().unwrap();
+            let lsn = wal.checkpoint().unwrap();
+            let manager = SnapshotManager::new(config.snapshot_path()).unwrap();
+            manager.create_snapshot(db.storage().as_ref(), Some(lsn)).unwrap();
+            assert!(wal.truncate(lsn).unwrap().deleted > 0);
+            db.storage().add_node(Node::new(vec!["Person".to_string()])).unwrap()
+        };
+
+        let db = DeepGraph::open(config.clone()).unwrap();
+        let recovery = db.recovery();
+        assert!(recovery.snapshot_id.is_some());
+        assert_eq!(recovery.snapshot_nodes, 1);
+        assert!(recovery.wal_operations > 0);
+        assert_eq!(db.storage().node_count(), 2);
+        assert!(db.storage().get_node(first).is_ok());
+        assert!(db.storage().get_node(second).is_ok());
+        drop(db);
+
+        // Without the snapshot the truncated WAL cannot be replayed alone
+        std::fs::remove_dir_all(config.snapshot_path()).unwrap();
+        assert!(DeepGraph::open(config).is_err());
+    }
+}
@@ -35,6 +35,12 @@ pub enum DeepGraphError {
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
 
+    #[error("Write throttled: {0}")]
+    Throttled(String),
+
+    #[error("Constraint violation: {0}")]
+    ConstraintViolation(String),
+
     #[error("Invalid property type: expected {expected}, got {actual}")]
     InvalidPropertyType { expected: String, actual: String },
 
@@ -51,6 +57,28 @@ pub enum DeepGraphError {
     Unknown(String),
 }
 
+impl DeepGraphError {
+    /// Whether this error means the requested entity does not exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            DeepGraphError::NodeNotFound(_)
+                | DeepGraphError::EdgeNotFound(_)
+                | DeepGraphError::NotFound(_)
+        )
+    }
+
+    /// Whether this error is backpressure that a writer may retry after a delay
+    pub fn is_throttled(&self) -> bool {
+        matches!(self, DeepGraphError::Throttled(_))
+    }
+
+    /// Whether this error is a write rejected by a uniqueness or other constraint
+    pub fn is_constraint_violation(&self) -> bool {
+        matches!(self, DeepGraphError::ConstraintViolation(_))
+    }
+}
+
 /// Result type alias for DeepGraph operations
 pub type Result<T> = std::result::Result<T, DeepGraphError>;
 
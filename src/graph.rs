@@ -134,6 +134,79 @@ impl PropertyValue {
             _ => None,
         }
     }
+
+    /// Byte encoding for use as an index key
+    ///
+    /// Equal values encode identically: map entries are written in key order
+    /// and `-0.0` is folded into `0.0`, which a plain serde encoding does not
+    /// guarantee.
+    pub fn index_key(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_index_key(&mut out);
+        out
+    }
+
+    fn write_index_key(&self, out: &mut Vec<u8>) {
+        let write_str = |out: &mut Vec<u8>, s: &str| {
+            out.extend_from_slice(&(s.len() as u64).to_be_bytes());
+            out.extend_from_slice(s.as_bytes());
+        };
+        match self {
+            PropertyValue::String(s) => {
+                out.push(0);
+                write_str(out, s);
+            }
+            PropertyValue::Integer(i) => {
+                out.push(1);
+                out.extend_from_slice(&i.to_be_bytes());
+            }
+            PropertyValue::Float(f) => {
+                out.push(2);
+                let f = if *f == 0.0 { 0.0f64 } else { *f };
+                out.extend_from_slice(&f.to_bits().to_be_bytes());
+            }
+            PropertyValue::Boolean(b) => out.extend_from_slice(&[3, *b as u8]),
+            PropertyValue::Null => out.push(4),
+            PropertyValue::List(list) => {
+                out.push(5);
+                out.extend_from_slice(&(list.len() as u64).to_be_bytes());
+                for item in list {
+                    item.write_index_key(out);
+                }
+            }
+            PropertyValue::Map(map) => {
+                out.push(6);
+                out.extend_from_slice(&(map.len() as u64).to_be_bytes());
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                for (key, value) in entries {
+                    write_str(out, key);
+                    value.write_index_key(out);
+                }
+            }
+        }
+    }
+
+    /// Convert to a plain JSON value (untagged, unlike the serde encoding)
+    ///
+    /// Non-finite floats have no JSON representation and become `null`.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            PropertyValue::String(s) => serde_json::Value::String(s.clone()),
+            PropertyValue::Integer(i) => serde_json::Value::from(*i),
+            PropertyValue::Float(f) => serde_json::Number::from_f64(*f)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            PropertyValue::Boolean(b) => serde_json::Value::Bool(*b),
+            PropertyValue::Null => serde_json::Value::Null,
+            PropertyValue::List(list) => {
+                serde_json::Value::Array(list.iter().map(PropertyValue::to_json).collect())
+            }
+            PropertyValue::Map(map) => serde_json::Value::Object(
+                map.iter().map(|(k, v)| (k.clone(), v.to_json())).collect(),
+            ),
+        }
+    }
 }
 
 impl From<String> for PropertyValue {
@@ -169,6 +242,23 @@ impl From<bool> for PropertyValue {
 /// A key-value property
 pub type Property = (String, PropertyValue);
 
+/// Reserved property holding an element's expiry time (Unix milliseconds)
+///
+/// Kept as a property rather than a struct field so stored nodes and
+/// edges keep their serialized layout.
+pub const EXPIRES_AT_PROPERTY: &str = "_expires_at";
+
+fn expiry_of(properties: &HashMap<String, PropertyValue>) -> Option<i64> {
+    match properties.get(EXPIRES_AT_PROPERTY) {
+        Some(PropertyValue::Integer(ms)) => Some(*ms),
+        _ => None,
+    }
+}
+
+fn ttl_deadline(ttl: std::time::Duration) -> i64 {
+    chrono::Utc::now().timestamp_millis().saturating_add(ttl.as_millis().min(i64::MAX as u128) as i64)
+}
+
 /// A node (vertex) in the graph
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Node {
@@ -260,6 +350,31 @@ impl Node {
     pub fn has_property(&self, key: &str) -> bool {
         self.properties.contains_key(key)
     }
+
+    /// Expiry time in Unix milliseconds, if one is set
+    pub fn expires_at(&self) -> Option<i64> {
+        expiry_of(&self.properties)
+    }
+
+    /// Expire the node at a Unix timestamp in milliseconds
+    pub fn set_expires_at(&mut self, unix_ms: i64) {
+        self.properties.insert(EXPIRES_AT_PROPERTY.to_string(), PropertyValue::Integer(unix_ms));
+    }
+
+    /// Expire the node `ttl` from now
+    pub fn set_ttl(&mut self, ttl: std::time::Duration) {
+        self.set_expires_at(ttl_deadline(ttl));
+    }
+
+    /// Remove any expiry, making the node permanent
+    pub fn clear_expiry(&mut self) {
+        self.properties.remove(EXPIRES_AT_PROPERTY);
+    }
+
+    /// Whether the node has expired as of `now_ms`
+    pub fn is_expired_at(&self, now_ms: i64) -> bool {
+        self.expires_at().is_some_and(|expires_at| expires_at <= now_ms)
+    }
 }
 
 /// An edge (relationship) in the graph
@@ -349,6 +464,31 @@ impl Edge {
     pub fn has_property(&self, key: &str) -> bool {
         self.properties.contains_key(key)
     }
+
+    /// Expiry time in Unix milliseconds, if one is set
+    pub fn expires_at(&self) -> Option<i64> {
+        expiry_of(&self.properties)
+    }
+
+    /// Expire the edge at a Unix timestamp in milliseconds
+    pub fn set_expires_at(&mut self, unix_ms: i64) {
+        self.properties.insert(EXPIRES_AT_PROPERTY.to_string(), PropertyValue::Integer(unix_ms));
+    }
+
+    /// Expire the edge `ttl` from now
+    pub fn set_ttl(&mut self, ttl: std::time::Duration) {
+        self.set_expires_at(ttl_deadline(ttl));
+    }
+
+    /// Remove any expiry, making the edge permanent
+    pub fn clear_expiry(&mut self) {
+        self.properties.remove(EXPIRES_AT_PROPERTY);
+    }
+
+    /// Whether the edge has expired as of `now_ms`
+    pub fn is_expired_at(&self, now_ms: i64) -> bool {
+        self.expires_at().is_some_and(|expires_at| expires_at <= now_ms)
+    }
 }
 
 #[cfg(test)]
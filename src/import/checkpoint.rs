@@ -0,0 +1,408 @@
+//! Transactional import batches with resumable checkpoints
+//!
+//! Each batch of imported records is written to the WAL as a single
+//! transaction and applied to storage as one atomic batch; the transaction
+//! is committed in the log only once storage accepted the whole batch, and
+//! aborted otherwise. It carries a [`WALOperation::ImportBatch`] record with
+//! the batch's progress, so the batch and its resume position become
+//! durable together. An
+//! [`ImportCheckpoint`] is also persisted next to the import after every
+//! batch; if a crash leaves that file behind the WAL, reopening catches it
+//! up from the log, so the next run resumes exactly at the last committed
+//! batch boundary and never re-imports a batch.
+//!
+//! Every import run is tagged with its own run ID, so independent imports
+//! can share one WAL and storage concurrently, and re-importing a file
+//! after deleting its checkpoint starts from scratch instead of picking up
+//! the batches of an earlier run.
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::{Edge, Node, NodeId};
+use crate::import::ImportStats;
+use crate::storage::{GraphOp, StorageBackend};
+use crate::wal::{WALConfig, WALOperation, WALRecovery, WAL};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Batch number, record count and node IDs of a logged import batch
+type LoggedBatch = (u64, u64, Vec<(String, NodeId)>);
+
+/// Progress of a resumable import, persisted after every committed batch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportCheckpoint {
+    /// Source the checkpoint belongs to (usually the input file path)
+    pub source: String,
+    /// Import run the checkpoint tracks; WAL batches of other runs are ignored
+    pub run_id: Uuid,
+    /// Number of input records covered by committed batches
+    pub records_committed: u64,
+    /// Number of committed batches
+    pub batches_committed: u64,
+    /// Node ID mapping (external ID → internal NodeId) of committed nodes
+    pub node_id_map: HashMap<String, String>,
+}
+
+impl ImportCheckpoint {
+    /// Create an empty checkpoint for a new run over a source
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            run_id: Uuid::new_v4(),
+            ..Default::default()
+        }
+    }
+
+    /// Load a checkpoint, returning `None` if the file does not exist
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    /// Load the checkpoint at `path` for `source`, or start a new one
+    ///
+    /// A checkpoint written for a different source is rejected rather than
+    /// silently skipping records of the wrong file.
+    pub fn load_for(path: impl AsRef<Path>, source: &str) -> Result<Self> {
+        let path = path.as_ref();
+        match Self::load(path)? {
+            Some(checkpoint) if checkpoint.source == source => {
+                info!(
+                    "Resuming import of {} after {} committed records ({} batches)",
+                    source, checkpoint.records_committed, checkpoint.batches_committed
+                );
+                Ok(checkpoint)
+            }
+            Some(checkpoint) => Err(DeepGraphError::InvalidOperation(format!(
+                "Checkpoint {:?} belongs to {}, not {}",
+                path, checkpoint.source, source
+            ))),
+            None => Ok(Self::new(source)),
+        }
+    }
+
+    /// Persist the checkpoint atomically (write to a temp file, then rename)
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("tmp");
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Fold in batches of this run the WAL committed after the checkpoint
+    /// was saved
+    ///
+    /// Returns whether anything was added. Only batches whose commit record
+    /// made it to the log count; a torn transaction is ignored.
+    pub fn catch_up(&mut self, config: &WALConfig) -> Result<bool> {
+        let mut logged: HashMap<u64, LoggedBatch> = HashMap::new();
+        let mut caught_up = false;
+        for entry in WALRecovery::new(config.clone()).entries()? {
+            match entry.operation {
+                // A transaction ID reused by a later process starts afresh
+                WALOperation::BeginTxn => {
+                    logged.remove(&entry.txn_id);
+                }
+                WALOperation::ImportBatch { run_id, batch, records, node_ids } if run_id == self.run_id => {
+                    logged.insert(entry.txn_id, (batch, records, node_ids));
+                }
+                WALOperation::CommitTxn => {
+                    if let Some((batch, records, node_ids)) = logged.remove(&entry.txn_id) {
+                        if batch == self.batches_committed {
+                            self.absorb(records, node_ids);
+                            caught_up = true;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(caught_up)
+    }
+
+    /// Record one committed batch
+    fn absorb(&mut self, records: u64, node_ids: Vec<(String, NodeId)>) {
+        self.records_committed += records;
+        self.batches_committed += 1;
+        self.node_id_map
+            .extend(node_ids.into_iter().map(|(external_id, id)| (external_id, id.to_string())));
+    }
+}
+
+/// Accumulates imported records and commits them as WAL transactions
+pub struct TransactionalBatch<'a> {
+    wal: &'a WAL,
+    checkpoint_path: PathBuf,
+    checkpoint: ImportCheckpoint,
+    nodes: Vec<(String, Node)>,
+    edges: Vec<Edge>,
+    pending_records: u64,
+}
+
+impl<'a> TransactionalBatch<'a> {
+    /// Open a batch writer, resuming from an existing checkpoint for `source`
+    ///
+    /// A checkpoint written for a different source is rejected rather than
+    /// silently skipping records of the wrong file. Batches `wal` committed
+    /// for this run after the checkpoint file was last saved are folded in
+    /// first, and the checkpoint is saved before any new batch is logged so
+    /// the run ID survives a crash.
+    pub fn open(wal: &'a WAL, checkpoint_path: impl AsRef<Path>, source: &str) -> Result<Self> {
+        let checkpoint_path = checkpoint_path.as_ref().to_path_buf();
+        let mut checkpoint = ImportCheckpoint::load_for(&checkpoint_path, source)?;
+        if checkpoint.catch_up(wal.config())? {
+            info!("Checkpoint for {} was behind the WAL", source);
+        }
+        checkpoint.save(&checkpoint_path)?;
+
+        Ok(Self {
+            wal,
+            checkpoint_path,
+            checkpoint,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            pending_records: 0,
+        })
+    }
+
+    /// Number of input records already covered by committed batches
+    pub fn resume_from(&self) -> u64 {
+        self.checkpoint.records_committed
+    }
+
+    /// Node ID mapping of all committed nodes, including earlier runs
+    pub fn node_id_map(&self) -> &HashMap<String, String> {
+        &self.checkpoint.node_id_map
+    }
+
+    /// Stage a node for the current batch
+    pub fn stage_node(&mut self, external_id: String, node: Node) {
+        self.nodes.push((external_id, node));
+        self.pending_records += 1;
+    }
+
+    /// Stage an edge for the current batch
+    pub fn stage_edge(&mut self, edge: Edge) {
+        self.edges.push(edge);
+        self.pending_records += 1;
+    }
+
+    /// Count a record that produced nothing (e.g. skipped as invalid)
+    ///
+    /// It still advances the resume position so it is not re-read.
+    pub fn skip_record(&mut self) {
+        self.pending_records += 1;
+    }
+
+    /// Number of records in the current, uncommitted batch
+    pub fn pending(&self) -> u64 {
+        self.pending_records
+    }
+
+    /// Log the current batch as one WAL transaction, apply it and checkpoint
+    ///
+    /// If storage rejects any record, none of the batch is applied, its WAL
+    /// transaction is aborted and the records stay staged.
+    pub fn commit<S: StorageBackend>(&mut self, storage: &S, stats: &mut ImportStats) -> Result<()> {
+        if self.pending_records == 0 {
+            return Ok(());
+        }
+
+        let node_ids: Vec<(String, NodeId)> = self
+            .nodes
+            .iter()
+            .map(|(external_id, node)| (external_id.clone(), node.id()))
+            .collect();
+
+        let txn_id = self.wal.allocate_txn_id();
+        self.wal.append(txn_id, WALOperation::BeginTxn)?;
+        for (_, node) in &self.nodes {
+            self.wal.append(txn_id, WALOperation::InsertNode { node: node.clone() })?;
+        }
+        for edge in &self.edges {
+            self.wal.append(txn_id, WALOperation::InsertEdge { edge: edge.clone() })?;
+        }
+        self.wal.append(
+            txn_id,
+            WALOperation::ImportBatch {
+                run_id: self.checkpoint.run_id,
+                batch: self.checkpoint.batches_committed,
+                records: self.pending_records,
+                node_ids: node_ids.clone(),
+            },
+        )?;
+
+        let ops = self
+            .nodes
+            .iter()
+            .map(|(_, node)| GraphOp::AddNode(node.clone()))
+            .chain(self.edges.iter().cloned().map(GraphOp::AddEdge))
+            .collect();
+        if let Err(e) = storage.apply_batch(ops) {
+            // Without a commit record recovery skips the batch anyway
+            if let Err(abort) = self.wal.append(txn_id, WALOperation::AbortTxn) {
+                warn!("Failed to log abort of import batch txn {}: {}", txn_id, abort);
+            }
+            return Err(e);
+        }
+        self.wal.append(txn_id, WALOperation::CommitTxn)?;
+        self.wal.flush()?;
+
+        // The batch and its progress are durable from here on: recovery
+        // replays the batch and `open` skips past it if we crash
+        for (external_id, node) in self.nodes.drain(..) {
+            stats.record_node(external_id, node.id().to_string());
+        }
+        for _ in self.edges.drain(..) {
+            stats.record_edge();
+        }
+
+        self.checkpoint.absorb(self.pending_records, node_ids);
+        self.pending_records = 0;
+        self.checkpoint.save(&self.checkpoint_path)?;
+
+        debug!(
+            "Committed import batch {} (txn {}, {} records total)",
+            self.checkpoint.batches_committed, txn_id, self.checkpoint.records_committed
+        );
+        Ok(())
+    }
+
+    /// Commit any remaining records and return the final checkpoint
+    pub fn finish<S: StorageBackend>(mut self, storage: &S, stats: &mut ImportStats) -> Result<ImportCheckpoint> {
+        self.commit(storage, stats)?;
+        Ok(self.checkpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use crate::wal::WALConfig;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("import.ckpt");
+
+        let mut checkpoint = ImportCheckpoint::new("nodes.csv");
+        checkpoint.records_committed = 42;
+        checkpoint.node_id_map.insert("1".to_string(), "abc".to_string());
+        checkpoint.save(&path).unwrap();
+
+        let loaded = ImportCheckpoint::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.records_committed, 42);
+        assert_eq!(loaded.node_id_map.get("1").map(String::as_str), Some("abc"));
+        assert!(ImportCheckpoint::load(dir.path().join("missing")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_batch_commit_advances_checkpoint() {
+        let dir = tempdir().unwrap();
+        let wal_dir = dir.path().join("wal");
+        let wal = WAL::new(WALConfig::new().with_dir(wal_dir.to_string_lossy().to_string())).unwrap();
+        let ckpt = dir.path().join("import.ckpt");
+        let storage = MemoryStorage::new();
+        let mut stats = ImportStats::new();
+
+        let mut batch = TransactionalBatch::open(&wal, &ckpt, "nodes.csv").unwrap();
+        batch.stage_node("1".to_string(), Node::new(vec!["Person".to_string()]));
+        batch.skip_record();
+        batch.commit(&storage, &mut stats).unwrap();
+
+        assert_eq!(storage.node_count(), 1);
+        let reopened = TransactionalBatch::open(&wal, &ckpt, "nodes.csv").unwrap();
+        assert_eq!(reopened.resume_from(), 2);
+        assert!(reopened.node_id_map().contains_key("1"));
+        assert!(TransactionalBatch::open(&wal, &ckpt, "other.csv").is_err());
+    }
+
+    #[test]
+    fn test_resume_after_crash_before_checkpoint_save() {
+        let dir = tempdir().unwrap();
+        let wal_dir = dir.path().join("wal");
+        let wal = WAL::new(WALConfig::new().with_dir(wal_dir.to_string_lossy().to_string())).unwrap();
+        let ckpt = dir.path().join("import.ckpt");
+        let storage = MemoryStorage::new();
+        let mut stats = ImportStats::new();
+
+        let mut batch = TransactionalBatch::open(&wal, &ckpt, "nodes.csv").unwrap();
+        batch.stage_node("1".to_string(), Node::new(vec!["Person".to_string()]));
+        batch.stage_node("2".to_string(), Node::new(vec!["Person".to_string()]));
+        batch.commit(&storage, &mut stats).unwrap();
+        let saved = fs::read(&ckpt).unwrap();
+
+        batch.stage_node("3".to_string(), Node::new(vec!["Person".to_string()]));
+        batch.skip_record();
+        batch.commit(&storage, &mut stats).unwrap();
+
+        // Crash after the second batch reached the WAL but before its
+        // checkpoint was saved: the file still describes the first batch
+        fs::write(&ckpt, saved).unwrap();
+
+        let reopened = TransactionalBatch::open(&wal, &ckpt, "nodes.csv").unwrap();
+        assert_eq!(reopened.resume_from(), 4);
+        assert_eq!(reopened.node_id_map().len(), 3);
+        assert_eq!(ImportCheckpoint::load(&ckpt).unwrap().unwrap().batches_committed, 2);
+
+        // Catching up again finds nothing new
+        let again = TransactionalBatch::open(&wal, &ckpt, "nodes.csv").unwrap();
+        assert_eq!(again.resume_from(), 4);
+        assert_eq!(storage.node_count(), 3);
+    }
+
+    #[test]
+    fn test_rejected_batch_is_aborted_and_not_applied() {
+        let dir = tempdir().unwrap();
+        let wal_dir = dir.path().join("wal");
+        let wal = WAL::new(WALConfig::new().with_dir(wal_dir.to_string_lossy().to_string())).unwrap();
+        let ckpt = dir.path().join("import.ckpt");
+        let storage = MemoryStorage::new();
+        let mut stats = ImportStats::new();
+
+        let mut batch = TransactionalBatch::open(&wal, &ckpt, "nodes.csv").unwrap();
+        let node = Node::new(vec!["Person".to_string()]);
+        let dangling = Edge::new(node.id(), NodeId::new(), "KNOWS".to_string());
+        batch.stage_node("1".to_string(), node);
+        batch.stage_edge(dangling);
+        assert!(batch.commit(&storage, &mut stats).is_err());
+
+        // Storage rolled back, the log holds no committed data and the
+        // checkpoint did not move
+        assert_eq!(storage.node_count(), 0);
+        assert_eq!(batch.pending(), 2);
+        assert!(WALRecovery::new(wal.config().clone()).committed_tail(0).unwrap().is_empty());
+        let reopened = TransactionalBatch::open(&wal, &ckpt, "nodes.csv").unwrap();
+        assert_eq!(reopened.resume_from(), 0);
+    }
+
+    #[test]
+    fn test_new_run_ignores_batches_of_earlier_run() {
+        let dir = tempdir().unwrap();
+        let wal_dir = dir.path().join("wal");
+        let wal = WAL::new(WALConfig::new().with_dir(wal_dir.to_string_lossy().to_string())).unwrap();
+        let ckpt = dir.path().join("import.ckpt");
+        let storage = MemoryStorage::new();
+
+        let mut first = TransactionalBatch::open(&wal, &ckpt, "nodes.csv").unwrap();
+        first.stage_node("1".to_string(), Node::new(vec!["Person".to_string()]));
+        first.finish(&storage, &mut ImportStats::new()).unwrap();
+
+        // Deleting the checkpoint starts a fresh run of the same file
+        fs::remove_file(&ckpt).unwrap();
+        let second = TransactionalBatch::open(&wal, &ckpt, "nodes.csv").unwrap();
+        assert_eq!(second.resume_from(), 0);
+        assert!(second.node_id_map().is_empty());
+    }
+}
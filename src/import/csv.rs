@@ -1,15 +1,30 @@
 //! CSV import functionality
+//!
+//! Besides the sequential and transactional paths, `import_nodes_parallel`
+//! and `import_edges_parallel` split the file into chunks of
+//! `ImportConfig::batch_size` records. The calling thread only splits
+//! records; worker threads infer property types, build the nodes or edges
+//! and write each chunk with [`StorageBackend::apply_batch`].
 
 use crate::error::{DeepGraphError, Result};
-use crate::graph::{Node, Edge, PropertyValue, NodeId};
-use crate::storage::StorageBackend;
-use crate::import::{ImportStats, ImportConfig};
+use crate::graph::{Node, Edge, PropertyValue};
+use crate::storage::{ExternalIdRegistry, GraphOp, StorageBackend};
+use crate::import::progress::ProgressTracker;
+use crate::import::{
+    import_edge, import_node, plain_input_size, resolve_node_id, store_edge, store_node, ColumnMapping, IdMapStore,
+    ImportConfig, ImportProgress, ImportStats, ProgressCallback, TransactionalBatch,
+};
+use crate::wal::WAL;
 use csv::StringRecord;
 use log::{debug, info, warn};
+use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::fs::File;
-use std::path::Path;
-use uuid::Uuid;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
 
 /// CSV importer for nodes and edges
 pub struct CsvImporter {
@@ -17,6 +32,10 @@ pub struct CsvImporter {
     delimiter: u8,
     has_header: bool,
     label_separator: char,
+    mapping: ColumnMapping,
+    id_map: Option<Arc<dyn IdMapStore>>,
+    progress: Option<ProgressCallback>,
+    checkpoint_path: Option<PathBuf>,
 }
 
 impl CsvImporter {
@@ -27,6 +46,10 @@ impl CsvImporter {
             delimiter: b',',
             has_header: true,
             label_separator: ';',
+            mapping: ColumnMapping::new(),
+            id_map: None,
+            progress: None,
+            checkpoint_path: None,
         }
     }
     
@@ -54,6 +77,50 @@ impl CsvImporter {
         self
     }
     
+    /// Rename, type, ignore and locate columns as `mapping` specifies
+    /// instead of relying on header names and type inference alone
+    pub fn with_mapping(mut self, mapping: ColumnMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+    
+    /// Record imported nodes in an external ID registry
+    ///
+    /// Re-importing a record whose ID is already registered updates that
+    /// node instead of creating a duplicate, and edge endpoints missing from
+    /// the ID map passed to `import_edges` are resolved through the registry.
+    pub fn with_id_registry(mut self, registry: Arc<ExternalIdRegistry>) -> Self {
+        self.id_map = Some(registry);
+        self
+    }
+    
+    /// Record imported nodes in any [`IdMapStore`], like
+    /// [`with_id_registry`](Self::with_id_registry); node imports flush it
+    /// when they finish
+    pub fn with_id_map(mut self, id_map: Arc<dyn IdMapStore>) -> Self {
+        self.id_map = Some(id_map);
+        self
+    }
+    
+    /// Call `callback` every `flush_interval` records and when an import
+    /// finishes
+    ///
+    /// Applies to `import_nodes` and `import_edges`.
+    pub fn with_progress(mut self, callback: impl Fn(&ImportProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+    
+    /// Save progress to `path` every `flush_interval` records, and resume
+    /// after the saved position when importing the same file again
+    ///
+    /// Applies to `import_nodes` and `import_edges`; see the
+    /// [`progress`](crate::import::progress) module.
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint_path = Some(path.into());
+        self
+    }
+    
     /// Import nodes from a CSV file
     ///
     /// # CSV Format
@@ -86,48 +153,51 @@ impl CsvImporter {
     ) -> Result<ImportStats> {
         let path = path.as_ref();
         info!("Importing nodes from CSV: {:?}", path);
-        
+        let (reader, headers) = self.open_reader(path)?;
+        let progress = self.progress_tracker(path, storage)?;
+        self.read_nodes(storage, reader, headers, progress, plain_input_size(path))
+    }
+    
+    /// Import nodes from CSV read from `input`, such as stdin or an HTTP
+    /// request body
+    ///
+    /// Progress is reported without an ETA and no checkpoint is kept, since
+    /// a stream cannot be re-read to resume.
+    pub fn import_nodes_from_reader<S: StorageBackend, R: Read>(
+        &self,
+        storage: &S,
+        input: R,
+    ) -> Result<ImportStats> {
+        info!("Importing nodes from CSV stream");
+        let (reader, headers) = self.csv_reader(input)?;
+        let progress = ProgressTracker::for_stream(self.progress.clone(), self.config.flush_interval);
+        self.read_nodes(storage, reader, headers, progress, None)
+    }
+    
+    /// Import the node records of `reader`, `total_bytes` long if known
+    fn read_nodes<S: StorageBackend, R: Read>(
+        &self,
+        storage: &S,
+        mut reader: csv::Reader<R>,
+        headers: StringRecord,
+        mut progress: ProgressTracker<'_>,
+        total_bytes: Option<u64>,
+    ) -> Result<ImportStats> {
         let mut stats = ImportStats::new();
         let timer = stats.start_timer();
         
-        // Open CSV file
-        let file = File::open(path)
-            .map_err(|e| DeepGraphError::IoError(e))?;
-        
-        let mut reader = csv::ReaderBuilder::new()
-            .delimiter(self.delimiter)
-            .has_headers(self.has_header)
-            .from_reader(file);
-        
-        // Get headers
-        let headers = if self.has_header {
-            reader.headers()
-                .map_err(|e| DeepGraphError::StorageError(format!("CSV header error: {}", e)))?
-                .clone()
-        } else {
-            // Generate default headers
-            let first_record = reader.records().next()
-                .ok_or_else(|| DeepGraphError::StorageError("Empty CSV file".to_string()))?
-                .map_err(|e| DeepGraphError::StorageError(format!("CSV read error: {}", e)))?;
-            StringRecord::from(
-                (0..first_record.len())
-                    .map(|i| format!("col{}", i))
-                    .collect::<Vec<_>>()
-            )
-        };
-        
-        debug!("CSV headers: {:?}", headers);
-        
-        // Find special columns
-        let id_col = headers.iter().position(|h| h.eq_ignore_ascii_case("id"));
-        let labels_col = headers.iter().position(|h| h.eq_ignore_ascii_case("labels") || h.eq_ignore_ascii_case("label"));
+        let (id_col, labels_col) = self.node_columns(&headers);
+        progress.restore(&mut stats);
         
-        // Process records
+        // Process records, skipping those a previous run applied
         let mut record_count = 0;
         for result in reader.records() {
             match result {
                 Ok(record) => {
                     record_count += 1;
+                    if record_count as u64 <= progress.resume_from() {
+                        continue;
+                    }
                     
                     match self.import_node_record(&headers, &record, id_col, labels_col, storage, &mut stats) {
                         Ok(_) => {},
@@ -147,6 +217,8 @@ impl CsvImporter {
                     if record_count % self.config.flush_interval == 0 {
                         debug!("Processed {} records", record_count);
                     }
+                    let fraction = Self::fraction_read(&record, total_bytes);
+                    progress.advance(record_count as u64, fraction, &mut stats)?;
                 }
                 Err(e) => {
                     stats.add_error(format!("CSV parse error: {}", e));
@@ -157,6 +229,7 @@ impl CsvImporter {
             }
         }
         
+        progress.finish(&mut stats)?;
         stats.stop_timer(timer);
         info!("Import complete: {} nodes imported in {}ms", stats.nodes_imported, stats.duration_ms);
         
@@ -164,6 +237,7 @@ impl CsvImporter {
             warn!("Import completed with {} errors", stats.errors.len());
         }
         
+        self.flush_id_map()?;
         Ok(stats)
     }
     
@@ -177,14 +251,29 @@ impl CsvImporter {
         storage: &S,
         stats: &mut ImportStats,
     ) -> Result<()> {
+        let fallback_id = format!("node_{}", stats.nodes_imported);
+        let (external_id, node) = self.build_node_record(headers, record, id_col, labels_col, fallback_id)?;
+        
+        // Add to storage, merging with an earlier import of the same record
+        import_node(storage, self.id_map.as_deref(), &self.config, stats, external_id, node)
+    }
+    
+    /// Build a node from a record, returning it with its external ID
+    fn build_node_record(
+        &self,
+        headers: &StringRecord,
+        record: &StringRecord,
+        id_col: Option<usize>,
+        labels_col: Option<usize>,
+        fallback_id: String,
+    ) -> Result<(String, Node)> {
         // Get or generate external ID
         let external_id = if let Some(col) = id_col {
             record.get(col)
                 .ok_or_else(|| DeepGraphError::StorageError("Missing ID column".to_string()))?
                 .to_string()
         } else {
-            // Auto-generate ID
-            format!("node_{}", stats.nodes_imported)
+            fallback_id
         };
         
         // Get labels
@@ -206,19 +295,504 @@ impl CsvImporter {
                 continue;
             }
             
-            if let Some(value) = record.get(i) {
-                if !value.is_empty() {
-                    let prop_value = self.infer_type(value);
-                    node.set_property(header.to_string(), prop_value);
+            let value = record.get(i).unwrap_or("");
+            if let Some((name, prop_value)) = self.mapping.property(header, value, |v| self.infer_type(v))? {
+                node.set_property(name, prop_value);
+            }
+        }
+        
+        Ok((external_id, node))
+    }
+    
+    /// Open a CSV file and resolve its headers
+    fn open_reader(&self, path: &Path) -> Result<(csv::Reader<File>, StringRecord)> {
+        let file = File::open(path)
+            .map_err(DeepGraphError::IoError)?;
+        self.csv_reader(file)
+    }
+    
+    /// Wrap `input` in a CSV reader and resolve its headers
+    fn csv_reader<R: Read>(&self, input: R) -> Result<(csv::Reader<R>, StringRecord)> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(self.has_header)
+            .from_reader(input);
+        
+        let headers = if self.has_header {
+            reader.headers()
+                .map_err(|e| DeepGraphError::StorageError(format!("CSV header error: {}", e)))?
+                .clone()
+        } else {
+            // Generate default headers
+            let first_record = reader.records().next()
+                .ok_or_else(|| DeepGraphError::StorageError("Empty CSV file".to_string()))?
+                .map_err(|e| DeepGraphError::StorageError(format!("CSV read error: {}", e)))?;
+            StringRecord::from(
+                (0..first_record.len())
+                    .map(|i| format!("col{}", i))
+                    .collect::<Vec<_>>()
+            )
+        };
+        
+        debug!("CSV headers: {:?}", headers);
+        Ok((reader, headers))
+    }
+    
+    /// Find the id and labels columns of a nodes CSV
+    fn node_columns(&self, headers: &StringRecord) -> (Option<usize>, Option<usize>) {
+        let mapping = &self.mapping;
+        let id_col = ColumnMapping::find_column(headers, mapping.id_column.as_deref(), &["id"]);
+        let labels_col = ColumnMapping::find_column(headers, mapping.labels_column.as_deref(), &["labels", "label"]);
+        (id_col, labels_col)
+    }
+    
+    /// Find the required from/to/type columns of an edges CSV
+    fn edge_columns(&self, headers: &StringRecord) -> Result<(usize, usize, usize)> {
+        let mapping = &self.mapping;
+        let from_col = ColumnMapping::find_column(headers, mapping.from_column.as_deref(), &["from", "source", "src"])
+            .ok_or_else(|| DeepGraphError::StorageError("Missing 'from' column in edges CSV".to_string()))?;
+        
+        let to_col = ColumnMapping::find_column(headers, mapping.to_column.as_deref(), &["to", "target", "dst"])
+            .ok_or_else(|| DeepGraphError::StorageError("Missing 'to' column in edges CSV".to_string()))?;
+        
+        let type_col = ColumnMapping::find_column(headers, mapping.type_column.as_deref(), &["type", "relationship", "label"])
+            .ok_or_else(|| DeepGraphError::StorageError("Missing 'type' column in edges CSV".to_string()))?;
+        
+        Ok((from_col, to_col, type_col))
+    }
+    
+    /// Import nodes in WAL-logged batches of `config.batch_size` records
+    ///
+    /// Each batch is committed as one WAL transaction and recorded in the
+    /// checkpoint file. Re-running after a crash skips every record covered
+    /// by a committed batch, so no node is imported twice. The returned
+    /// `node_id_map` includes nodes committed by earlier runs.
+    ///
+    /// Imports of different files may run concurrently against the same
+    /// `wal` and storage, each with its own checkpoint file.
+    pub fn import_nodes_transactional<S: StorageBackend>(
+        &self,
+        storage: &S,
+        path: impl AsRef<Path>,
+        wal: &WAL,
+        checkpoint_path: impl AsRef<Path>,
+    ) -> Result<ImportStats> {
+        let path = path.as_ref();
+        if self.config.validate_only {
+            // Nothing is written, so the write strategy does not matter
+            return self.import_nodes(storage, path);
+        }
+        info!("Importing nodes transactionally from CSV: {:?}", path);
+        
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        
+        let mut batch = TransactionalBatch::open(wal, checkpoint_path, &path.to_string_lossy())?;
+        let (mut reader, headers) = self.open_reader(path)?;
+        let (id_col, labels_col) = self.node_columns(&headers);
+        let skip = batch.resume_from();
+        
+        for (row, result) in reader.records().enumerate() {
+            if (row as u64) < skip {
+                continue;
+            }
+            
+            let built = result
+                .map_err(|e| DeepGraphError::StorageError(format!("CSV parse error: {}", e)))
+                .and_then(|record| {
+                    self.build_node_record(&headers, &record, id_col, labels_col, format!("node_{}", row))
+                });
+            
+            match built {
+                Ok((external_id, node)) => batch.stage_node(external_id, node),
+                Err(e) => {
+                    stats.add_error(format!("Row {}: {}", row + 1, e));
+                    if !self.config.skip_invalid {
+                        return Err(e);
+                    }
+                    batch.skip_record();
+                    if self.config.max_errors > 0 && stats.errors.len() >= self.config.max_errors {
+                        warn!("Max errors ({}) reached, aborting import", self.config.max_errors);
+                        break;
+                    }
                 }
             }
+            
+            if batch.pending() >= self.config.batch_size as u64 {
+                batch.commit(storage, &mut stats)?;
+            }
         }
         
-        // Add to storage
-        let internal_id = storage.add_node(node)?;
-        stats.record_node(external_id, internal_id.to_string());
+        let checkpoint = batch.finish(storage, &mut stats)?;
+        stats.node_id_map = checkpoint.node_id_map;
+        
+        stats.stop_timer(timer);
+        info!("Import complete: {} nodes imported in {} batches", stats.nodes_imported, checkpoint.batches_committed);
+        
+        Ok(stats)
+    }
+    
+    /// Import edges in WAL-logged batches of `config.batch_size` records
+    ///
+    /// Resumes from `checkpoint_path` the same way as
+    /// [`CsvImporter::import_nodes_transactional`].
+    pub fn import_edges_transactional<S: StorageBackend>(
+        &self,
+        storage: &S,
+        path: impl AsRef<Path>,
+        node_id_map: &HashMap<String, String>,
+        wal: &WAL,
+        checkpoint_path: impl AsRef<Path>,
+    ) -> Result<ImportStats> {
+        let path = path.as_ref();
+        if self.config.validate_only {
+            // Nothing is written, so the write strategy does not matter
+            return self.import_edges(storage, path, node_id_map);
+        }
+        info!("Importing edges transactionally from CSV: {:?}", path);
+        
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        
+        let mut batch = TransactionalBatch::open(wal, checkpoint_path, &path.to_string_lossy())?;
+        let (mut reader, headers) = self.open_reader(path)?;
+        let (from_col, to_col, type_col) = self.edge_columns(&headers)?;
+        let skip = batch.resume_from();
+        
+        for (row, result) in reader.records().enumerate() {
+            if (row as u64) < skip {
+                continue;
+            }
+            
+            let built = result
+                .map_err(|e| DeepGraphError::StorageError(format!("CSV parse error: {}", e)))
+                .and_then(|record| {
+                    self.build_edge_record(storage, &headers, &record, from_col, to_col, type_col, node_id_map)
+                });
+            
+            match built {
+                Ok(edge) => batch.stage_edge(edge),
+                Err(e) => {
+                    stats.add_error(format!("Row {}: {}", row + 1, e));
+                    if !self.config.skip_invalid {
+                        return Err(e);
+                    }
+                    batch.skip_record();
+                    if self.config.max_errors > 0 && stats.errors.len() >= self.config.max_errors {
+                        warn!("Max errors ({}) reached, aborting import", self.config.max_errors);
+                        break;
+                    }
+                }
+            }
+            
+            if batch.pending() >= self.config.batch_size as u64 {
+                batch.commit(storage, &mut stats)?;
+            }
+        }
+        
+        let checkpoint = batch.finish(storage, &mut stats)?;
+        
+        stats.stop_timer(timer);
+        info!("Import complete: {} edges imported in {} batches", stats.edges_imported, checkpoint.batches_committed);
+        
+        Ok(stats)
+    }
+    
+    /// Import nodes from a CSV file using `config.workers` threads
+    ///
+    /// Produces the same nodes as [`CsvImporter::import_nodes`], in chunks
+    /// of `config.batch_size` records each written as one batch. If the
+    /// storage rejects a chunk, none of its nodes are imported; with
+    /// `skip_invalid` its rows are retried one by one and only the rejected
+    /// ones are skipped, otherwise one error covering the chunk is
+    /// recorded. Errors are collected in no particular order.
+    ///
+    /// With an ID registry or a merge key, nodes are merged one at a time
+    /// instead of written in batches. Records sharing a key in different
+    /// chunks may then race and both create a node.
+    pub fn import_nodes_parallel<S: StorageBackend>(
+        &self,
+        storage: &S,
+        path: impl AsRef<Path>,
+    ) -> Result<ImportStats> {
+        let path = path.as_ref();
+        if self.config.validate_only {
+            // Nothing is written, so the write strategy does not matter
+            return self.import_nodes(storage, path);
+        }
+        if self.config.duplicate_policy.is_some() {
+            // Repeated IDs are only recognized when records arrive in file order
+            return self.import_nodes(storage, path);
+        }
+        
+        let (reader, headers) = self.open_reader(path)?;
+        let (id_col, labels_col) = self.node_columns(&headers);
+        let Some(id_col) = id_col else {
+            // Generated `node_<n>` IDs count the nodes imported before each
+            // record, which only a pass in file order knows
+            return self.import_nodes(storage, path);
+        };
+        let id_map = self.id_map.as_deref();
+        info!("Importing nodes from CSV with {} workers: {:?}", self.config.workers, path);
+        
+        let stats = self.import_parallel(
+            reader,
+            |record, _| self.build_node_record(&headers, record, Some(id_col), labels_col, String::new()),
+            |chunk, stats| {
+                if id_map.is_some() || self.config.merge_key.is_some() {
+                    for (row, (external_id, node)) in chunk {
+                        match store_node(storage, id_map, &self.config, &external_id, node) {
+                            Ok(id) => stats.record_node(external_id, id.to_string()),
+                            Err(e) => return Err((row, e)),
+                        }
+                    }
+                    return Ok(());
+                }
+                let ids: Vec<_> = chunk.iter().map(|(_, (external_id, node))| (external_id.clone(), node.id())).collect();
+                let first = chunk.first().map_or(0, |(row, _)| *row);
+                let ops = chunk
+                    .into_iter()
+                    .map(|(_, (external_id, mut node))| {
+                        if let Some(key) = &self.config.id_property {
+                            node.properties_mut()
+                                .entry(key.clone())
+                                .or_insert(PropertyValue::String(external_id));
+                        }
+                        GraphOp::AddNode(node)
+                    })
+                    .collect();
+                storage.apply_batch(ops).map_err(|e| (first, e))?;
+                for (external_id, id) in ids {
+                    stats.record_node(external_id, id.to_string());
+                }
+                Ok(())
+            },
+        )?;
+        
+        info!("Import complete: {} nodes imported in {}ms", stats.nodes_imported, stats.duration_ms);
+        self.flush_id_map()?;
+        Ok(stats)
+    }
+    
+    /// Import edges from a CSV file using `config.workers` threads
+    ///
+    /// Produces the same edges as [`CsvImporter::import_edges`], chunked
+    /// and written like [`CsvImporter::import_nodes_parallel`].
+    pub fn import_edges_parallel<S: StorageBackend>(
+        &self,
+        storage: &S,
+        path: impl AsRef<Path>,
+        node_id_map: &HashMap<String, String>,
+    ) -> Result<ImportStats> {
+        let path = path.as_ref();
+        if self.config.validate_only {
+            // Nothing is written, so the write strategy does not matter
+            return self.import_edges(storage, path, node_id_map);
+        }
+        info!("Importing edges from CSV with {} workers: {:?}", self.config.workers, path);
+        
+        let (reader, headers) = self.open_reader(path)?;
+        let (from_col, to_col, type_col) = self.edge_columns(&headers)?;
+        
+        let stats = self.import_parallel(
+            reader,
+            |record, _| self.build_edge_record(storage, &headers, record, from_col, to_col, type_col, node_id_map),
+            |chunk, stats| {
+                if self.config.merge_key.is_some() {
+                    for (row, edge) in chunk {
+                        store_edge(storage, true, edge).map_err(|e| (row, e))?;
+                        stats.record_edge();
+                    }
+                    return Ok(());
+                }
+                let count = chunk.len();
+                let first = chunk.first().map_or(0, |(row, _)| *row);
+                storage
+                    .apply_batch(chunk.into_iter().map(|(_, edge)| GraphOp::AddEdge(edge)).collect())
+                    .map_err(|e| (first, e))?;
+                stats.edges_imported += count;
+                Ok(())
+            },
+        )?;
+        
+        info!("Import complete: {} edges imported in {}ms", stats.edges_imported, stats.duration_ms);
+        Ok(stats)
+    }
+    
+    /// Split `reader` into chunks on the calling thread and have
+    /// `config.workers` threads `build` each record and `write` each chunk
+    ///
+    /// `write` receives `(row, item)` pairs and reports a failure with the
+    /// row it starts from; nothing from that row on may have been written.
+    /// With `skip_invalid` those rows are then written one at a time, so
+    /// each bad row gets its own error; otherwise one error covers them.
+    fn import_parallel<T, B, W>(
+        &self,
+        mut reader: csv::Reader<File>,
+        build: B,
+        write: W,
+    ) -> Result<ImportStats>
+    where
+        T: Clone,
+        B: Fn(&StringRecord, usize) -> Result<T> + Sync,
+        W: Fn(Vec<(usize, T)>, &mut ImportStats) -> std::result::Result<(), (usize, DeepGraphError)> + Sync,
+    {
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        let workers = self.config.workers.max(1);
+        let chunk_size = self.config.batch_size.max(1);
         
-        Ok(())
+        let stop = AtomicBool::new(false);
+        let error_count = AtomicUsize::new(0);
+        // Record an error, deciding whether the import must stop
+        let fail = |stats: &mut ImportStats, message: String, e: DeepGraphError| -> Result<()> {
+            stats.add_error(message);
+            if !self.config.skip_invalid {
+                stop.store(true, Ordering::Relaxed);
+                return Err(e);
+            }
+            let errors = error_count.fetch_add(1, Ordering::Relaxed) + 1;
+            if self.config.max_errors > 0 && errors >= self.config.max_errors && !stop.swap(true, Ordering::Relaxed) {
+                warn!("Max errors ({}) reached, aborting import", self.config.max_errors);
+            }
+            Ok(())
+        };
+        
+        let (sender, receiver) = mpsc::sync_channel::<Vec<(usize, StringRecord)>>(workers * 2);
+        let receiver = Mutex::new(receiver);
+        
+        let (read_result, results) = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    let (receiver, stop, build, write, fail) = (&receiver, &stop, &build, &write, &fail);
+                    scope.spawn(move || -> Result<ImportStats> {
+                        let mut stats = ImportStats::new();
+                        let mut first_error = None;
+                        // Keep draining after a stop so the reader never
+                        // blocks on a full channel
+                        loop {
+                            let next = receiver.lock().recv();
+                            let Ok(chunk) = next else { break };
+                            if stop.load(Ordering::Relaxed) {
+                                continue;
+                            }
+                            let last = chunk.last().map_or(0, |(row, _)| *row);
+                            let mut built = Vec::with_capacity(chunk.len());
+                            for (row, record) in chunk {
+                                match build(&record, row) {
+                                    Ok(item) => built.push((row, item)),
+                                    Err(e) => {
+                                        if let Err(e) = fail(&mut stats, format!("Row {}: {}", row + 1, e), e) {
+                                            first_error.get_or_insert(e);
+                                        }
+                                    }
+                                }
+                            }
+                            if built.is_empty() {
+                                continue;
+                            }
+                            let retry = self.config.skip_invalid.then(|| built.clone());
+                            let (row, e) = match write(built, &mut stats) {
+                                Ok(()) => continue,
+                                Err(failed) => failed,
+                            };
+                            match retry {
+                                // Write the unwritten rows one by one so only
+                                // the bad ones are skipped
+                                Some(items) if items.len() > 1 => {
+                                    debug!("Rows {}-{} rejected ({}), retrying them one by one", row + 1, last + 1, e);
+                                    for item in items.into_iter().filter(|(r, _)| *r >= row) {
+                                        if stop.load(Ordering::Relaxed) {
+                                            break;
+                                        }
+                                        let row = item.0;
+                                        if let Err((_, e)) = write(vec![item], &mut stats) {
+                                            // Skipping, so this never stops the import
+                                            let _ = fail(&mut stats, format!("Row {}: {}", row + 1, e), e);
+                                        }
+                                    }
+                                }
+                                _ => {
+                                    let message = format!("Rows {}-{}: {}", row + 1, last + 1, e);
+                                    if let Err(e) = fail(&mut stats, message, e) {
+                                        first_error.get_or_insert(e);
+                                    }
+                                }
+                            }
+                        }
+                        match first_error {
+                            Some(e) => Err(e),
+                            None => Ok(stats),
+                        }
+                    })
+                })
+                .collect();
+            
+            let read_result = (|| -> Result<()> {
+                let mut chunk = Vec::with_capacity(chunk_size);
+                for (row, result) in reader.records().enumerate() {
+                    if stop.load(Ordering::Relaxed) {
+                        return Ok(());
+                    }
+                    match result {
+                        Ok(record) => chunk.push((row, record)),
+                        Err(e) => {
+                            let message = format!("CSV parse error: {}", e);
+                            fail(&mut stats, message.clone(), DeepGraphError::StorageError(message))?;
+                        }
+                    }
+                    if chunk.len() == chunk_size {
+                        debug!("Queued {} records", row + 1);
+                        // Workers only hang up by panicking, which join reports
+                        let _ = sender.send(std::mem::replace(&mut chunk, Vec::with_capacity(chunk_size)));
+                    }
+                }
+                if !chunk.is_empty() {
+                    let _ = sender.send(chunk);
+                }
+                Ok(())
+            })();
+            drop(sender);
+            
+            let results: Vec<Result<ImportStats>> = handles
+                .into_iter()
+                .map(|h| h.join().expect("import worker panicked"))
+                .collect();
+            (read_result, results)
+        });
+        
+        read_result?;
+        for result in results {
+            stats.merge(result?);
+        }
+        stats.stop_timer(timer);
+        
+        if !stats.errors.is_empty() {
+            warn!("Import completed with {} errors", stats.errors.len());
+        }
+        
+        Ok(stats)
+    }
+    
+    /// Progress tracking for an import of `path`
+    fn progress_tracker<'a>(&self, path: &Path, storage: &'a dyn StorageBackend) -> Result<ProgressTracker<'a>> {
+        // A validation run must not make a later import skip records
+        let checkpoint_path = self.checkpoint_path.as_deref().filter(|_| !self.config.validate_only);
+        ProgressTracker::open(path, storage, self.progress.clone(), checkpoint_path, self.config.flush_interval)
+    }
+    
+    /// Fraction of a `total_bytes` file read up to the end of `record`
+    fn fraction_read(record: &StringRecord, total_bytes: Option<u64>) -> Option<f64> {
+        let offset = record.position()?.byte() + record.as_slice().len() as u64;
+        total_bytes.filter(|&total| total > 0).map(|total| (offset as f64 / total as f64).min(1.0))
+    }
+    
+    /// Make the node mappings of a finished import durable
+    fn flush_id_map(&self) -> Result<()> {
+        match &self.id_map {
+            Some(id_map) => id_map.flush(),
+            None => Ok(()),
+        }
     }
     
     /// Parse labels from a string (semicolon-separated)
@@ -295,53 +869,49 @@ impl CsvImporter {
     ) -> Result<ImportStats> {
         let path = path.as_ref();
         info!("Importing edges from CSV: {:?}", path);
-        
+        let (reader, headers) = self.open_reader(path)?;
+        let progress = self.progress_tracker(path, storage)?;
+        self.read_edges(storage, reader, headers, node_id_map, progress, plain_input_size(path))
+    }
+    
+    /// Import edges from CSV read from `input`, streamed like
+    /// [`import_nodes_from_reader`](Self::import_nodes_from_reader)
+    pub fn import_edges_from_reader<S: StorageBackend, R: Read>(
+        &self,
+        storage: &S,
+        input: R,
+        node_id_map: &HashMap<String, String>,
+    ) -> Result<ImportStats> {
+        info!("Importing edges from CSV stream");
+        let (reader, headers) = self.csv_reader(input)?;
+        let progress = ProgressTracker::for_stream(self.progress.clone(), self.config.flush_interval);
+        self.read_edges(storage, reader, headers, node_id_map, progress, None)
+    }
+    
+    /// Import the edge records of `reader`, `total_bytes` long if known
+    fn read_edges<S: StorageBackend, R: Read>(
+        &self,
+        storage: &S,
+        mut reader: csv::Reader<R>,
+        headers: StringRecord,
+        node_id_map: &HashMap<String, String>,
+        mut progress: ProgressTracker<'_>,
+        total_bytes: Option<u64>,
+    ) -> Result<ImportStats> {
         let mut stats = ImportStats::new();
         let timer = stats.start_timer();
         
-        // Open CSV file
-        let file = File::open(path)
-            .map_err(|e| DeepGraphError::IoError(e))?;
-        
-        let mut reader = csv::ReaderBuilder::new()
-            .delimiter(self.delimiter)
-            .has_headers(self.has_header)
-            .from_reader(file);
-        
-        // Get headers
-        let headers = if self.has_header {
-            reader.headers()
-                .map_err(|e| DeepGraphError::StorageError(format!("CSV header error: {}", e)))?
-                .clone()
-        } else {
-            let first_record = reader.records().next()
-                .ok_or_else(|| DeepGraphError::StorageError("Empty CSV file".to_string()))?
-                .map_err(|e| DeepGraphError::StorageError(format!("CSV read error: {}", e)))?;
-            StringRecord::from(
-                (0..first_record.len())
-                    .map(|i| format!("col{}", i))
-                    .collect::<Vec<_>>()
-            )
-        };
-        
-        debug!("CSV headers: {:?}", headers);
+        let (from_col, to_col, type_col) = self.edge_columns(&headers)?;
         
-        // Find required columns
-        let from_col = headers.iter().position(|h| h.eq_ignore_ascii_case("from") || h.eq_ignore_ascii_case("source") || h.eq_ignore_ascii_case("src"))
-            .ok_or_else(|| DeepGraphError::StorageError("Missing 'from' column in edges CSV".to_string()))?;
-        
-        let to_col = headers.iter().position(|h| h.eq_ignore_ascii_case("to") || h.eq_ignore_ascii_case("target") || h.eq_ignore_ascii_case("dst"))
-            .ok_or_else(|| DeepGraphError::StorageError("Missing 'to' column in edges CSV".to_string()))?;
-        
-        let type_col = headers.iter().position(|h| h.eq_ignore_ascii_case("type") || h.eq_ignore_ascii_case("relationship") || h.eq_ignore_ascii_case("label"))
-            .ok_or_else(|| DeepGraphError::StorageError("Missing 'type' column in edges CSV".to_string()))?;
-        
-        // Process records
+        // Process records, skipping those a previous run applied
         let mut record_count = 0;
         for result in reader.records() {
             match result {
                 Ok(record) => {
                     record_count += 1;
+                    if record_count as u64 <= progress.resume_from() {
+                        continue;
+                    }
                     
                     match self.import_edge_record(&headers, &record, from_col, to_col, type_col, node_id_map, storage, &mut stats) {
                         Ok(_) => {},
@@ -360,6 +930,8 @@ impl CsvImporter {
                     if record_count % self.config.flush_interval == 0 {
                         debug!("Processed {} edge records", record_count);
                     }
+                    let fraction = Self::fraction_read(&record, total_bytes);
+                    progress.advance(record_count as u64, fraction, &mut stats)?;
                 }
                 Err(e) => {
                     stats.add_error(format!("CSV parse error: {}", e));
@@ -370,6 +942,7 @@ impl CsvImporter {
             }
         }
         
+        progress.finish(&mut stats)?;
         stats.stop_timer(timer);
         info!("Import complete: {} edges imported in {}ms", stats.edges_imported, stats.duration_ms);
         
@@ -392,6 +965,24 @@ impl CsvImporter {
         storage: &S,
         stats: &mut ImportStats,
     ) -> Result<()> {
+        let edge = self.build_edge_record(storage, headers, record, from_col, to_col, type_col, node_id_map)?;
+        
+        // Add to storage
+        import_edge(storage, &self.config, stats, edge)
+    }
+    
+    /// Build an edge from a record, resolving endpoints through the node ID map
+    #[allow(clippy::too_many_arguments)]
+    fn build_edge_record<S: StorageBackend + ?Sized>(
+        &self,
+        storage: &S,
+        headers: &StringRecord,
+        record: &StringRecord,
+        from_col: usize,
+        to_col: usize,
+        type_col: usize,
+        node_id_map: &HashMap<String, String>,
+    ) -> Result<Edge> {
         // Get from/to external IDs
         let from_external = record.get(from_col)
             .ok_or_else(|| DeepGraphError::StorageError("Missing 'from' value".to_string()))?;
@@ -400,17 +991,9 @@ impl CsvImporter {
             .ok_or_else(|| DeepGraphError::StorageError("Missing 'to' value".to_string()))?;
         
         // Map to internal IDs
-        let from_internal = node_id_map.get(from_external)
-            .ok_or_else(|| DeepGraphError::StorageError(format!("Node '{}' not found in ID map", from_external)))?;
-        
-        let to_internal = node_id_map.get(to_external)
-            .ok_or_else(|| DeepGraphError::StorageError(format!("Node '{}' not found in ID map", to_external)))?;
-        
-        let from_id = NodeId::from_uuid(Uuid::parse_str(from_internal)
-            .map_err(|e| DeepGraphError::StorageError(format!("Invalid node ID: {}", e)))?);
-        
-        let to_id = NodeId::from_uuid(Uuid::parse_str(to_internal)
-            .map_err(|e| DeepGraphError::StorageError(format!("Invalid node ID: {}", e)))?);
+        let id_map = self.id_map.as_deref();
+        let from_id = resolve_node_id(storage, node_id_map, id_map, &self.config, from_external)?;
+        let to_id = resolve_node_id(storage, node_id_map, id_map, &self.config, to_external)?;
         
         // Get relationship type
         let rel_type = record.get(type_col)
@@ -427,19 +1010,13 @@ impl CsvImporter {
                 continue;
             }
             
-            if let Some(value) = record.get(i) {
-                if !value.is_empty() {
-                    let prop_value = self.infer_type(value);
-                    edge.set_property(header.to_string(), prop_value);
-                }
+            let value = record.get(i).unwrap_or("");
+            if let Some((name, prop_value)) = self.mapping.property(header, value, |v| self.infer_type(v))? {
+                edge.set_property(name, prop_value);
             }
         }
         
-        // Add to storage
-        storage.add_edge(edge)?;
-        stats.record_edge();
-        
-        Ok(())
+        Ok(edge)
     }
 }
 
@@ -452,7 +1029,11 @@ impl Default for CsvImporter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graph::NodeId;
+    use crate::import::{ColumnType, DuplicatePolicy, ImportCheckpoint};
     use crate::storage::MemoryStorage;
+    use std::collections::BTreeSet;
+    use std::time::Duration;
     use std::io::Write;
     use tempfile::NamedTempFile;
     
@@ -509,4 +1090,389 @@ mod tests {
         assert_eq!(stats.errors.len(), 0);
         assert_eq!(stats.node_id_map.len(), 2);
     }
+    
+    #[test]
+    fn test_import_from_reader() {
+        let storage = MemoryStorage::new();
+        let importer = CsvImporter::new();
+        let nodes = importer.import_nodes_from_reader(&storage, "id,name\n1,Alice\n2,Bob\n".as_bytes()).unwrap();
+        assert_eq!(nodes.nodes_imported, 2);
+        
+        let edges = importer
+            .import_edges_from_reader(&storage, "from,to,type\n1,2,KNOWS\n".as_bytes(), &nodes.node_id_map)
+            .unwrap();
+        assert_eq!(edges.edges_imported, 1);
+        assert_eq!(storage.edge_count(), 1);
+    }
+    
+    #[test]
+    fn test_import_nodes_transactional_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("nodes.csv");
+        let ckpt_path = dir.path().join("nodes.ckpt");
+        std::fs::write(&csv_path, "id,labels,name\n1,Person,Alice\n2,Person,Bob\n3,Person,Carol\n").unwrap();
+        
+        let wal_dir = dir.path().join("wal").to_string_lossy().to_string();
+        let wal = WAL::new(crate::wal::WALConfig::new().with_dir(wal_dir)).unwrap();
+        let importer = CsvImporter::new().with_config(ImportConfig::new().with_batch_size(2));
+        
+        // Pretend an earlier run committed the first batch before crashing
+        let storage = MemoryStorage::new();
+        let mut first = TransactionalBatch::open(&wal, &ckpt_path, &csv_path.to_string_lossy()).unwrap();
+        first.stage_node("1".to_string(), Node::new(vec!["Person".to_string()]));
+        first.stage_node("2".to_string(), Node::new(vec!["Person".to_string()]));
+        first.commit(&storage, &mut ImportStats::new()).unwrap();
+        
+        let stats = importer.import_nodes_transactional(&storage, &csv_path, &wal, &ckpt_path).unwrap();
+        assert_eq!(stats.nodes_imported, 1);
+        assert_eq!(stats.node_id_map.len(), 3);
+        assert_eq!(storage.node_count(), 3);
+    }
+    
+    #[test]
+    fn test_concurrent_transactional_imports_share_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_dir = dir.path().join("wal").to_string_lossy().to_string();
+        let wal = WAL::new(crate::wal::WALConfig::new().with_dir(wal_dir)).unwrap();
+        let storage = MemoryStorage::new();
+        let importer = CsvImporter::new().with_config(ImportConfig::new().with_batch_size(3));
+        
+        let files: Vec<_> = (0..2)
+            .map(|i| {
+                let csv_path = dir.path().join(format!("nodes{}.csv", i));
+                let rows: String = (0..10).map(|row| format!("{}-{},Person\n", i, row)).collect();
+                std::fs::write(&csv_path, format!("id,labels\n{}", rows)).unwrap();
+                (csv_path, dir.path().join(format!("nodes{}.ckpt", i)))
+            })
+            .collect();
+        
+        std::thread::scope(|scope| {
+            for (csv_path, ckpt_path) in &files {
+                let (importer, storage, wal) = (&importer, &storage, &wal);
+                scope.spawn(move || {
+                    let stats = importer.import_nodes_transactional(storage, csv_path, wal, ckpt_path).unwrap();
+                    assert_eq!(stats.nodes_imported, 10);
+                });
+            }
+        });
+        assert_eq!(storage.node_count(), 20);
+        
+        // Each run only catches up on its own batches from the shared log
+        for (csv_path, ckpt_path) in &files {
+            let batch = TransactionalBatch::open(&wal, ckpt_path, &csv_path.to_string_lossy()).unwrap();
+            assert_eq!(batch.resume_from(), 10);
+            assert_eq!(batch.node_id_map().len(), 10);
+        }
+    }
+    
+    #[test]
+    fn test_import_resumes_from_checkpoint_with_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("nodes.csv");
+        let ckpt_path = dir.path().join("nodes.ckpt");
+        std::fs::write(&csv_path, "id,name\n1,Alice\n2,Bob\n3,Carol\n4,Dave\n5,Eve\n").unwrap();
+        
+        // Pretend an earlier run was interrupted after checkpointing two rows
+        let mut checkpoint = ImportCheckpoint::new(csv_path.to_string_lossy());
+        checkpoint.records_committed = 2;
+        checkpoint.node_id_map.insert("1".to_string(), "earlier".to_string());
+        checkpoint.save(&ckpt_path).unwrap();
+        
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let importer = CsvImporter::new()
+            .with_config(ImportConfig::new().with_flush_interval(2))
+            .with_checkpoint(&ckpt_path)
+            .with_progress(move |p| sink.lock().push(p.clone()));
+        let storage = MemoryStorage::new();
+        let stats = importer.import_nodes(&storage, &csv_path).unwrap();
+        
+        assert_eq!(stats.nodes_imported, 3);
+        assert_eq!(stats.node_id_map.len(), 4);
+        {
+            let reports = reports.lock();
+            assert_eq!(reports.iter().map(|p| p.records).collect::<Vec<_>>(), vec![4, 5]);
+            assert_eq!(reports[1].eta, Some(Duration::ZERO));
+        }
+        
+        // Mappings live in the sidecar, not the checkpoint file
+        let saved = ImportCheckpoint::load(&ckpt_path).unwrap().unwrap();
+        assert!(saved.node_id_map.is_empty());
+        assert!(ckpt_path.with_extension("ids").exists());
+        
+        // A finished checkpoint makes a re-run a no-op
+        let again = importer.import_nodes(&storage, &csv_path).unwrap();
+        assert_eq!(again.nodes_imported, 0);
+        assert_eq!(again.node_id_map, stats.node_id_map);
+        assert_eq!(storage.node_count(), 3);
+    }
+    
+    #[test]
+    fn test_validate_only_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodes_path = dir.path().join("nodes.csv");
+        let edges_path = dir.path().join("edges.csv");
+        std::fs::write(&nodes_path, "id,name,age\n1,Alice,30\n2,Bob,unknown\n3,Carol,41\n").unwrap();
+        std::fs::write(&edges_path, "from,to,type\n1,3,KNOWS\n1,9,KNOWS\n").unwrap();
+        
+        let storage = MemoryStorage::new();
+        let importer = CsvImporter::new()
+            .with_config(ImportConfig::new().with_validate_only(true))
+            .with_mapping(ColumnMapping::new().with_type("age", ColumnType::Integer));
+        let nodes = importer.import_nodes_parallel(&storage, &nodes_path).unwrap();
+        assert_eq!(nodes.nodes_imported, 2);
+        assert_eq!(nodes.errors.len(), 1);
+        assert!(nodes.errors[0].starts_with("Row 2"));
+        assert_eq!(nodes.schema["age"], BTreeSet::from(["integer"]));
+        assert_eq!(nodes.schema["name"], BTreeSet::from(["string"]));
+        
+        let edges = importer.import_edges(&storage, &edges_path, &nodes.node_id_map).unwrap();
+        assert_eq!(edges.edges_imported, 1);
+        assert_eq!(edges.errors.len(), 1);
+        assert_eq!((storage.node_count(), storage.edge_count()), (0, 0));
+    }
+    
+    #[test]
+    fn test_duplicate_policies() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "id,labels,name,age").unwrap();
+        writeln!(file, "1,Person,Alice,30").unwrap();
+        writeln!(file, "2,Person,Bob,25").unwrap();
+        writeln!(file, "1,Employee,Alicia,").unwrap();
+        file.flush().unwrap();
+        
+        let import = |policy| {
+            let storage = MemoryStorage::new();
+            let config = ImportConfig::new().with_duplicate_policy(policy);
+            let stats = CsvImporter::new().with_config(config).import_nodes(&storage, file.path()).unwrap();
+            assert_eq!((stats.nodes_imported, stats.duplicates, storage.node_count()), (2, 1, 2));
+            let id = NodeId::from_uuid(uuid::Uuid::parse_str(&stats.node_id_map["1"]).unwrap());
+            (storage.get_node(id).unwrap(), stats)
+        };
+        
+        let (alice, _) = import(DuplicatePolicy::Skip);
+        assert_eq!(alice.get_property("name"), Some(&PropertyValue::String("Alice".to_string())));
+        
+        let (alice, _) = import(DuplicatePolicy::Overwrite);
+        assert_eq!(alice.labels().to_vec(), vec!["Employee".to_string()]);
+        assert_eq!(alice.get_property("age"), None);
+        
+        let (alice, _) = import(DuplicatePolicy::MergeProperties);
+        assert!(alice.has_label("Person") && alice.has_label("Employee"));
+        assert_eq!(alice.get_property("name"), Some(&PropertyValue::String("Alicia".to_string())));
+        assert_eq!(alice.get_property("age"), Some(&PropertyValue::Integer(30)));
+        
+        let (_, stats) = import(DuplicatePolicy::Error);
+        assert!(stats.errors[0].starts_with("Row 3: ") && stats.errors[0].contains("Duplicate external ID '1'"));
+    }
+    
+    #[test]
+    fn test_reimport_with_registry_merges() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodes_path = dir.path().join("nodes.csv");
+        let edges_path = dir.path().join("edges.csv");
+        std::fs::write(&nodes_path, "id,labels,name\nuser:1,Person,Alice\nuser:2,Person,Bob\n").unwrap();
+        std::fs::write(&edges_path, "from,to,type\nuser:1,user:2,KNOWS\n").unwrap();
+        
+        let storage = MemoryStorage::new();
+        let registry = Arc::new(ExternalIdRegistry::temporary().unwrap());
+        let importer = CsvImporter::new().with_id_registry(registry.clone());
+        importer.import_nodes(&storage, &nodes_path).unwrap();
+        
+        std::fs::write(&nodes_path, "id,labels,name\nuser:1,Person,Alicia\n").unwrap();
+        importer.import_nodes(&storage, &nodes_path).unwrap();
+        assert_eq!(storage.node_count(), 2);
+        let alice = storage.get_node(registry.get("user:1").unwrap().unwrap()).unwrap();
+        assert_eq!(alice.get_property("name"), Some(&PropertyValue::String("Alicia".to_string())));
+        
+        // Endpoints resolve through the registry without the earlier ID map
+        let stats = importer.import_edges(&storage, &edges_path, &HashMap::new()).unwrap();
+        assert_eq!(stats.edges_imported, 1);
+    }
+    
+    #[test]
+    fn test_parallel_import_matches_sequential() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodes_path = dir.path().join("nodes.csv");
+        let edges_path = dir.path().join("edges.csv");
+        let mut nodes = String::from("id,labels,name,score\n");
+        let mut edges = String::from("from,to,type\n");
+        for i in 0..50 {
+            nodes.push_str(&format!("{},Person,user{},{}.5\n", i, i, i));
+            if i > 0 {
+                edges.push_str(&format!("{},{},NEXT\n", i - 1, i));
+            }
+        }
+        edges.push_str("49,missing,NEXT\n");
+        std::fs::write(&nodes_path, nodes).unwrap();
+        std::fs::write(&edges_path, edges).unwrap();
+        
+        let storage = MemoryStorage::new();
+        let importer = CsvImporter::new().with_config(ImportConfig::new().with_batch_size(7).with_workers(4));
+        let node_stats = importer.import_nodes_parallel(&storage, &nodes_path).unwrap();
+        assert_eq!(node_stats.nodes_imported, 50);
+        assert_eq!(node_stats.node_id_map.len(), 50);
+        assert_eq!(storage.node_count(), 50);
+        
+        let edge_stats = importer.import_edges_parallel(&storage, &edges_path, &node_stats.node_id_map).unwrap();
+        assert_eq!(edge_stats.edges_imported, 49);
+        assert_eq!(edge_stats.errors, vec!["Row 50: Storage error: Node 'missing' not found in ID map".to_string()]);
+        assert_eq!(storage.edge_count(), 49);
+        
+        let user7 = storage.get_nodes_by_property("name", &PropertyValue::String("user7".to_string()));
+        assert_eq!(user7[0].get_property("score"), Some(&PropertyValue::Float(7.5)));
+        
+        let strict = CsvImporter::new().with_config(ImportConfig::new().with_skip_invalid(false).with_workers(2));
+        assert!(strict.import_edges_parallel(&MemoryStorage::new(), &edges_path, &node_stats.node_id_map).is_err());
+    }
+    
+    #[test]
+    fn test_parallel_import_skips_only_rejected_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodes_path = dir.path().join("nodes.csv");
+        let edges_path = dir.path().join("edges.csv");
+        let mut nodes = String::from("id,labels,name\n");
+        let mut edges = String::from("from,to,type\n");
+        for i in 0..20 {
+            nodes.push_str(&format!("{},Person,user{}\n", i, i));
+            edges.push_str(&format!("{},{},NEXT\n", i, if i == 9 { "ghost".to_string() } else { ((i + 1) % 20).to_string() }));
+        }
+        std::fs::write(&nodes_path, nodes).unwrap();
+        std::fs::write(&edges_path, edges).unwrap();
+        
+        let storage = MemoryStorage::new();
+        let importer = CsvImporter::new().with_config(ImportConfig::new().with_batch_size(5).with_workers(2));
+        let mut id_map = importer.import_nodes_parallel(&storage, &nodes_path).unwrap().node_id_map;
+        // Resolves, but names a node the storage does not have
+        id_map.insert("ghost".to_string(), crate::graph::NodeId::new().to_string());
+        
+        let stats = importer.import_edges_parallel(&storage, &edges_path, &id_map).unwrap();
+        assert_eq!(stats.edges_imported, 19);
+        assert_eq!(stats.errors.len(), 1);
+        assert!(stats.errors[0].starts_with("Row 10: "), "{}", stats.errors[0]);
+        assert_eq!(storage.edge_count(), 19);
+    }
+    
+    #[test]
+    fn test_parallel_import_without_id_column_numbers_like_sequential() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nodes.csv");
+        std::fs::write(&path, "labels,age\nPerson,1\nPerson,oops\nPerson,3\n").unwrap();
+        let mapping = ColumnMapping::new().with_type("age", crate::import::ColumnType::Integer);
+        
+        let importer = CsvImporter::new()
+            .with_mapping(mapping)
+            .with_config(ImportConfig::new().with_batch_size(1).with_workers(2));
+        let parallel = importer.import_nodes_parallel(&MemoryStorage::new(), &path).unwrap();
+        let sequential = importer.import_nodes(&MemoryStorage::new(), &path).unwrap();
+        let mut keys: Vec<_> = parallel.node_id_map.keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["node_0".to_string(), "node_1".to_string()]);
+        let mut expected: Vec<_> = sequential.node_id_map.keys().cloned().collect();
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+    
+    #[test]
+    fn test_merge_key_makes_reimport_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodes_path = dir.path().join("nodes.csv");
+        let edges_path = dir.path().join("edges.csv");
+        std::fs::write(&nodes_path, "id,labels,name\nu1,Person,Alice\nu2,Person,Bob\n").unwrap();
+        std::fs::write(&edges_path, "from,to,type,since\nu1,u2,KNOWS,2020\n").unwrap();
+        
+        let storage = MemoryStorage::new();
+        let importer = CsvImporter::new().with_config(ImportConfig::new().with_merge_key("external_id"));
+        let first = importer.import_nodes(&storage, &nodes_path).unwrap();
+        importer.import_edges(&storage, &edges_path, &first.node_id_map).unwrap();
+        
+        std::fs::write(&nodes_path, "id,labels,name\nu1,Employee,Alicia\nu2,Person,Bob\n").unwrap();
+        std::fs::write(&edges_path, "from,to,type,since\nu1,u2,KNOWS,2021\n").unwrap();
+        let second = importer.import_nodes(&storage, &nodes_path).unwrap();
+        importer.import_edges(&storage, &edges_path, &second.node_id_map).unwrap();
+        
+        assert_eq!(first.node_id_map, second.node_id_map);
+        assert_eq!(storage.node_count(), 2);
+        assert_eq!(storage.edge_count(), 1);
+        let alice = storage.get_nodes_by_property("external_id", &PropertyValue::String("u1".to_string()));
+        assert_eq!(alice[0].get_property("name"), Some(&PropertyValue::String("Alicia".to_string())));
+        assert!(alice[0].has_label("Person") && alice[0].has_label("Employee"));
+        let edges = storage.get_all_edges();
+        assert_eq!(edges[0].get_property("since"), Some(&PropertyValue::Integer(2021)));
+        
+        // Without a merge key the same file is imported again
+        CsvImporter::new().import_nodes(&storage, &nodes_path).unwrap();
+        assert_eq!(storage.node_count(), 4);
+    }
+    
+    #[test]
+    fn test_edges_in_later_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodes_path = dir.path().join("nodes.csv");
+        let edges_path = dir.path().join("edges.csv");
+        let ids_path = dir.path().join("ids.json");
+        std::fs::write(&nodes_path, "id,labels\na,Person\nb,Person\nc,Person\n").unwrap();
+        std::fs::write(&edges_path, "from,to,type\na,b,KNOWS\nb,c,KNOWS\n").unwrap();
+        
+        let storage = MemoryStorage::new();
+        let ids = Arc::new(crate::import::FileIdMapStore::open(&ids_path).unwrap());
+        let config = ImportConfig::new().with_id_property("source_id");
+        CsvImporter::new().with_config(config.clone()).with_id_map(ids).import_nodes(&storage, &nodes_path).unwrap();
+        
+        // A later session with only the saved file and no ID map
+        let ids = Arc::new(crate::import::FileIdMapStore::open(&ids_path).unwrap());
+        let stats = CsvImporter::new().with_id_map(ids).import_edges(&storage, &edges_path, &HashMap::new()).unwrap();
+        assert_eq!(stats.edges_imported, 2);
+        
+        // Or resolve lazily through the id property alone
+        let stats = CsvImporter::new().with_config(config).import_edges(&storage, &edges_path, &HashMap::new()).unwrap();
+        assert_eq!(stats.edges_imported, 2);
+        assert_eq!(storage.edge_count(), 4);
+        
+        let stats = CsvImporter::new().import_edges(&storage, &edges_path, &HashMap::new()).unwrap();
+        assert_eq!(stats.edges_imported, 0);
+        assert_eq!(stats.errors.len(), 2);
+    }
+    
+    #[test]
+    fn test_import_with_column_mapping() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodes_path = dir.path().join("customers.csv");
+        let edges_path = dir.path().join("referrals.csv");
+        std::fs::write(
+            &nodes_path,
+            "customer_no,kind,Full Name,zip,joined,notes\nC1,Customer,Ada,00501,15/03/2021,vip\nC2,Customer,Bob,NA,bad-date,\n",
+        )
+        .unwrap();
+        std::fs::write(&edges_path, "referrer,referee,rel\nC1,C2,REFERRED\n").unwrap();
+        
+        let mapping = ColumnMapping::new()
+            .with_id_column("customer_no")
+            .with_labels_column("kind")
+            .with_rename("Full Name", "name")
+            .with_type("zip", ColumnType::String)
+            .with_type("joined", ColumnType::Timestamp("%d/%m/%Y".to_string()))
+            .with_ignored("notes")
+            .with_null_value("NA")
+            .with_edge_columns("referrer", "referee", "rel");
+        let storage = MemoryStorage::new();
+        let importer = CsvImporter::new().with_mapping(mapping);
+        let stats = importer.import_nodes(&storage, &nodes_path).unwrap();
+        
+        // Bob's unparseable date rejects his row
+        assert_eq!(stats.nodes_imported, 1);
+        assert_eq!(stats.errors.len(), 1);
+        let ada = &storage.get_nodes_by_label("Customer")[0];
+        assert_eq!(ada.get_property("name"), Some(&PropertyValue::String("Ada".to_string())));
+        assert_eq!(ada.get_property("zip"), Some(&PropertyValue::String("00501".to_string())));
+        assert_eq!(ada.get_property("joined"), Some(&PropertyValue::Integer(1_615_766_400_000)));
+        assert!(!ada.has_property("notes") && !ada.has_property("customer_no"));
+        
+        std::fs::write(&nodes_path, "customer_no,kind,zip\nC2,Customer,NA\n").unwrap();
+        let more = importer.import_nodes(&storage, &nodes_path).unwrap();
+        let mut ids = stats.node_id_map;
+        ids.extend(more.node_id_map);
+        let edges = importer.import_edges(&storage, &edges_path, &ids).unwrap();
+        assert_eq!(edges.edges_imported, 1);
+    }
 }
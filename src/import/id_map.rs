@@ -0,0 +1,140 @@
+//! Persistent external-ID mappings for imports
+//!
+//! [`ImportStats::node_id_map`](crate::import::ImportStats) only lives as
+//! long as the import that produced it. An [`IdMapStore`] keeps the mapping
+//! from source-system IDs to [`NodeId`]s between runs, so edges can be
+//! imported in a later session and re-imported nodes update the node they
+//! were first imported as. Two stores are provided:
+//! - [`ExternalIdRegistry`]: Sled-backed, durable on every write
+//! - [`FileIdMapStore`]: an in-memory map saved to a JSON file on flush
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use deepgraph::import::{CsvImporter, FileIdMapStore};
+//!
+//! let ids = Arc::new(FileIdMapStore::open("import_ids.json")?);
+//! CsvImporter::new().with_id_map(ids.clone()).import_nodes(&storage, "nodes.csv")?;
+//!
+//! // Later, in another process
+//! let ids = Arc::new(FileIdMapStore::open("import_ids.json")?);
+//! CsvImporter::new().with_id_map(ids).import_edges(&storage, "edges.csv", &HashMap::new())?;
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::NodeId;
+use crate::storage::ExternalIdRegistry;
+use log::info;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// External ID -> [`NodeId`] mapping shared by import runs
+pub trait IdMapStore: Send + Sync {
+    /// Look up the node an external ID was imported as
+    fn get(&self, external_id: &str) -> Result<Option<NodeId>>;
+
+    /// Map an external ID to a node, returning the previous mapping
+    fn insert(&self, external_id: &str, node_id: NodeId) -> Result<Option<NodeId>>;
+
+    /// Make every mapping inserted so far durable
+    fn flush(&self) -> Result<()>;
+}
+
+impl IdMapStore for ExternalIdRegistry {
+    fn get(&self, external_id: &str) -> Result<Option<NodeId>> {
+        ExternalIdRegistry::get(self, external_id)
+    }
+
+    fn insert(&self, external_id: &str, node_id: NodeId) -> Result<Option<NodeId>> {
+        ExternalIdRegistry::insert(self, external_id, node_id)
+    }
+
+    fn flush(&self) -> Result<()> {
+        ExternalIdRegistry::flush(self)
+    }
+}
+
+/// ID mappings held in memory and saved to a JSON file
+///
+/// The file is a single object of external ID to node UUID, readable by
+/// other tools. Mappings inserted since the last [`flush`](IdMapStore::flush)
+/// are lost if the process exits first.
+#[derive(Debug)]
+pub struct FileIdMapStore {
+    path: PathBuf,
+    ids: RwLock<HashMap<String, NodeId>>,
+}
+
+impl FileIdMapStore {
+    /// Load the mappings saved at `path`, or start empty if it does not exist
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let ids = if path.exists() {
+            serde_json::from_slice(&fs::read(&path)?)?
+        } else {
+            HashMap::new()
+        };
+        info!("Opened ID map {:?} with {} entries", path, ids.len());
+        Ok(Self {
+            path,
+            ids: RwLock::new(ids),
+        })
+    }
+
+    /// Number of stored mappings
+    pub fn len(&self) -> usize {
+        self.ids.read().len()
+    }
+
+    /// Whether no mappings are stored
+    pub fn is_empty(&self) -> bool {
+        self.ids.read().is_empty()
+    }
+}
+
+impl IdMapStore for FileIdMapStore {
+    fn get(&self, external_id: &str) -> Result<Option<NodeId>> {
+        Ok(self.ids.read().get(external_id).copied())
+    }
+
+    fn insert(&self, external_id: &str, node_id: NodeId) -> Result<Option<NodeId>> {
+        Ok(self.ids.write().insert(external_id.to_string(), node_id))
+    }
+
+    /// Write the mappings to a temporary file and rename it over the old
+    /// one, so a crash mid-write leaves the previous version intact
+    fn flush(&self) -> Result<()> {
+        let json = serde_json::to_vec(&*self.ids.read())?;
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &self.path)
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to save ID map {:?}: {}", self.path, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_file_store_persists_after_flush() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ids.json");
+        let node_id = NodeId::new();
+        {
+            let store = FileIdMapStore::open(&path).unwrap();
+            assert!(store.is_empty());
+            assert_eq!(store.insert("user:1", node_id).unwrap(), None);
+            store.flush().unwrap();
+            store.insert("user:2", NodeId::new()).unwrap();
+        }
+
+        let store = FileIdMapStore::open(&path).unwrap();
+        assert_eq!(store.get("user:1").unwrap(), Some(node_id));
+        assert_eq!(store.get("user:2").unwrap(), None);
+        assert_eq!(store.len(), 1);
+    }
+}
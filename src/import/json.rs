@@ -1,20 +1,32 @@
 //! JSON import functionality
+//!
+//! Reads either a single JSON array of records or newline-delimited JSON
+//! (JSONL, one record per line). JSONL is streamed line by line, so only one
+//! record is held in memory at a time. Both accept gzip-compressed input,
+//! from a file or from any reader such as stdin or an HTTP request body.
 
 use crate::error::{DeepGraphError, Result};
-use crate::graph::{Node, Edge, PropertyValue, NodeId};
-use crate::storage::StorageBackend;
-use crate::import::{ImportStats, ImportConfig};
+use crate::graph::{Node, Edge, PropertyValue};
+use crate::storage::{ExternalIdRegistry, StorageBackend};
+use crate::import::progress::ProgressTracker;
+use crate::import::{
+    decode_input, import_edge, import_node, json_records, open_input, plain_input_size, resolve_node_id, IdMapStore,
+    ImportConfig, ImportProgress, ImportStats, ProgressCallback, TransactionalBatch,
+};
+use crate::wal::WAL;
 use log::{debug, info, warn};
 use serde_json::{Value, Map};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
-use uuid::Uuid;
+use std::io::{BufRead, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// JSON importer for nodes and edges
 pub struct JsonImporter {
     config: ImportConfig,
+    id_map: Option<Arc<dyn IdMapStore>>,
+    progress: Option<ProgressCallback>,
+    checkpoint_path: Option<PathBuf>,
 }
 
 impl JsonImporter {
@@ -22,6 +34,9 @@ impl JsonImporter {
     pub fn new() -> Self {
         Self {
             config: ImportConfig::new(),
+            id_map: None,
+            progress: None,
+            checkpoint_path: None,
         }
     }
     
@@ -31,6 +46,40 @@ impl JsonImporter {
         self
     }
     
+    /// Record imported nodes in an external ID registry
+    ///
+    /// Re-importing a record whose ID is already registered updates that
+    /// node instead of creating a duplicate, and edge endpoints missing from
+    /// the ID map passed to `import_edges` are resolved through the registry.
+    pub fn with_id_registry(mut self, registry: Arc<ExternalIdRegistry>) -> Self {
+        self.id_map = Some(registry);
+        self
+    }
+    
+    /// Record imported nodes in any [`IdMapStore`], like
+    /// [`with_id_registry`](Self::with_id_registry); node imports flush it
+    /// when they finish
+    pub fn with_id_map(mut self, id_map: Arc<dyn IdMapStore>) -> Self {
+        self.id_map = Some(id_map);
+        self
+    }
+    
+    /// Call `callback` every `flush_interval` records and when an import
+    /// finishes
+    pub fn with_progress(mut self, callback: impl Fn(&ImportProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+    
+    /// Save progress to `path` every `flush_interval` records, and resume
+    /// after the saved position when importing the same file again
+    ///
+    /// See the [`progress`](crate::import::progress) module.
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint_path = Some(path.into());
+        self
+    }
+    
     /// Import nodes from a JSON file
     ///
     /// # JSON Format
@@ -79,19 +128,14 @@ impl JsonImporter {
         let mut stats = ImportStats::new();
         let timer = stats.start_timer();
         
-        // Open JSON file
-        let file = File::open(path)
-            .map_err(|e| DeepGraphError::IoError(e))?;
-        let reader = BufReader::new(file);
-        
-        // Parse JSON array
-        let nodes: Vec<Value> = serde_json::from_reader(reader)
-            .map_err(|e| DeepGraphError::JsonError(e))?;
+        let nodes = Self::read_records(path)?;
         
         debug!("Parsed {} node records", nodes.len());
+        let mut progress = self.progress_tracker(path, storage)?;
+        progress.restore(&mut stats);
         
-        // Process each node
-        for (i, node_value) in nodes.iter().enumerate() {
+        // Process each node, skipping those a previous run applied
+        for (i, node_value) in nodes.iter().enumerate().skip(progress.resume_from() as usize) {
             match self.import_node_value(node_value, storage, &mut stats) {
                 Ok(_) => {},
                 Err(e) => {
@@ -109,8 +153,10 @@ impl JsonImporter {
             if (i + 1) % self.config.flush_interval == 0 {
                 debug!("Processed {} nodes", i + 1);
             }
+            progress.advance((i + 1) as u64, Some((i + 1) as f64 / nodes.len() as f64), &mut stats)?;
         }
         
+        progress.finish(&mut stats)?;
         stats.stop_timer(timer);
         info!("Import complete: {} nodes imported in {}ms", stats.nodes_imported, stats.duration_ms);
         
@@ -118,16 +164,246 @@ impl JsonImporter {
             warn!("Import completed with {} errors", stats.errors.len());
         }
         
+        self.flush_id_map()?;
         Ok(stats)
     }
     
+    /// Import nodes from a newline-delimited JSON file
+    ///
+    /// Each non-blank line holds one node record in the same format as the
+    /// array elements accepted by [`import_nodes`](Self::import_nodes).
+    /// Records are parsed and stored one line at a time, so memory use does
+    /// not grow with the file size beyond the returned ID map. Gzip input is
+    /// detected and decompressed on the fly.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let stats = importer.import_nodes_jsonl(&storage, "nodes.jsonl.gz")?;
+    /// let edges = importer.import_edges_jsonl(&storage, "edges.jsonl.gz", &stats.node_id_map)?;
+    /// ```
+    pub fn import_nodes_jsonl<S: StorageBackend>(
+        &self,
+        storage: &S,
+        path: impl AsRef<Path>,
+    ) -> Result<ImportStats> {
+        let path = path.as_ref();
+        info!("Importing nodes from JSONL: {:?}", path);
+
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        self.import_lines(storage, path, "nodes", &mut stats, |value, stats| {
+            self.import_node_value(value, storage, stats)
+        })?;
+        stats.stop_timer(timer);
+        info!("Import complete: {} nodes imported in {}ms", stats.nodes_imported, stats.duration_ms);
+
+        if !stats.errors.is_empty() {
+            warn!("Import completed with {} errors", stats.errors.len());
+        }
+
+        self.flush_id_map()?;
+        Ok(stats)
+    }
+
+    /// Import edges from a newline-delimited JSON file
+    ///
+    /// Each non-blank line holds one edge record in the same format as the
+    /// array elements accepted by [`import_edges`](Self::import_edges),
+    /// streamed like [`import_nodes_jsonl`](Self::import_nodes_jsonl).
+    pub fn import_edges_jsonl<S: StorageBackend>(
+        &self,
+        storage: &S,
+        path: impl AsRef<Path>,
+        node_id_map: &HashMap<String, String>,
+    ) -> Result<ImportStats> {
+        let path = path.as_ref();
+        info!("Importing edges from JSONL: {:?}", path);
+
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        self.import_lines(storage, path, "edges", &mut stats, |value, stats| {
+            self.import_edge_value(value, node_id_map, storage, stats)
+        })?;
+        stats.stop_timer(timer);
+        info!("Import complete: {} edges imported in {}ms", stats.edges_imported, stats.duration_ms);
+
+        if !stats.errors.is_empty() {
+            warn!("Import completed with {} errors", stats.errors.len());
+        }
+
+        Ok(stats)
+    }
+
+    /// Import nodes read from `input`, such as stdin or an HTTP request body
+    ///
+    /// The input may be a JSON array or JSONL, either optionally gzipped;
+    /// JSONL is streamed record by record. Progress is reported without an
+    /// ETA and no checkpoint is kept, since a stream cannot be re-read to
+    /// resume.
+    pub fn import_nodes_from_reader<S: StorageBackend, R: Read>(
+        &self,
+        storage: &S,
+        input: R,
+    ) -> Result<ImportStats> {
+        info!("Importing nodes from JSON stream");
+
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        self.import_stream(input, "nodes", &mut stats, |value, stats| {
+            self.import_node_value(value, storage, stats)
+        })?;
+        stats.stop_timer(timer);
+        info!("Import complete: {} nodes imported in {}ms", stats.nodes_imported, stats.duration_ms);
+
+        if !stats.errors.is_empty() {
+            warn!("Import completed with {} errors", stats.errors.len());
+        }
+
+        self.flush_id_map()?;
+        Ok(stats)
+    }
+
+    /// Import edges read from `input`, streamed like
+    /// [`import_nodes_from_reader`](Self::import_nodes_from_reader)
+    pub fn import_edges_from_reader<S: StorageBackend, R: Read>(
+        &self,
+        storage: &S,
+        input: R,
+        node_id_map: &HashMap<String, String>,
+    ) -> Result<ImportStats> {
+        info!("Importing edges from JSON stream");
+
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        self.import_stream(input, "edges", &mut stats, |value, stats| {
+            self.import_edge_value(value, node_id_map, storage, stats)
+        })?;
+        stats.stop_timer(timer);
+        info!("Import complete: {} edges imported in {}ms", stats.edges_imported, stats.duration_ms);
+
+        if !stats.errors.is_empty() {
+            warn!("Import completed with {} errors", stats.errors.len());
+        }
+
+        Ok(stats)
+    }
+
+    /// Hand each JSON record of `input` to `import`, applying the error
+    /// policy per record
+    fn import_stream<R: Read>(
+        &self,
+        input: R,
+        what: &str,
+        stats: &mut ImportStats,
+        mut import: impl FnMut(&Value, &mut ImportStats) -> Result<()>,
+    ) -> Result<()> {
+        let mut progress = ProgressTracker::for_stream(self.progress.clone(), self.config.flush_interval);
+        let mut records = 0;
+        for record in json_records(decode_input(input)?)? {
+            records += 1;
+            if let Err(e) = record.and_then(|value| import(&value, stats)) {
+                stats.add_error(format!("Record {}: {}", records, e));
+                if !self.config.skip_invalid {
+                    return Err(e);
+                }
+                if self.config.max_errors > 0 && stats.errors.len() >= self.config.max_errors {
+                    warn!("Max errors ({}) reached, aborting import", self.config.max_errors);
+                    break;
+                }
+            }
+
+            if records % self.config.flush_interval == 0 {
+                debug!("Processed {} {}", records, what);
+            }
+            progress.advance(records as u64, None, stats)?;
+        }
+        progress.finish(stats)
+    }
+
+    /// Parse each non-blank line of `path` as a JSON record and hand it to
+    /// `import`, applying the error policy per line
+    ///
+    /// Records a previous run applied, according to the checkpoint, are
+    /// skipped.
+    fn import_lines(
+        &self,
+        storage: &dyn StorageBackend,
+        path: &Path,
+        what: &str,
+        stats: &mut ImportStats,
+        mut import: impl FnMut(&Value, &mut ImportStats) -> Result<()>,
+    ) -> Result<()> {
+        let reader = open_input(path)?;
+        let mut progress = self.progress_tracker(path, storage)?;
+        progress.restore(stats);
+        let total_bytes = plain_input_size(path).filter(|&total| total > 0);
+        let mut bytes_read = 0u64;
+        let mut records = 0;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            bytes_read += line.len() as u64 + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records += 1;
+            if records as u64 <= progress.resume_from() {
+                continue;
+            }
+            let result = serde_json::from_str::<Value>(&line)
+                .map_err(DeepGraphError::JsonError)
+                .and_then(|value| import(&value, stats));
+            if let Err(e) = result {
+                stats.add_error(format!("Line {}: {}", i + 1, e));
+                if !self.config.skip_invalid {
+                    return Err(e);
+                }
+                if self.config.max_errors > 0 && stats.errors.len() >= self.config.max_errors {
+                    warn!("Max errors ({}) reached, aborting import", self.config.max_errors);
+                    break;
+                }
+            }
+
+            if records % self.config.flush_interval == 0 {
+                debug!("Processed {} {}", records, what);
+            }
+            let fraction = total_bytes.map(|total| (bytes_read as f64 / total as f64).min(1.0));
+            progress.advance(records as u64, fraction, stats)?;
+        }
+        progress.finish(stats)
+    }
+
     /// Import a single node from JSON value
-    fn import_node_value<S: StorageBackend>(
+    pub(crate) fn import_node_value<S: StorageBackend>(
         &self,
         value: &Value,
         storage: &S,
         stats: &mut ImportStats,
     ) -> Result<()> {
+        let fallback_id = format!("node_{}", stats.nodes_imported);
+        let (external_id, node) = self.build_node_value(value, fallback_id)?;
+        
+        // Add to storage, merging with an earlier import of the same record
+        import_node(storage, self.id_map.as_deref(), &self.config, stats, external_id, node)
+    }
+    
+    /// Progress tracking for an import of `path`
+    fn progress_tracker<'a>(&self, path: &Path, storage: &'a dyn StorageBackend) -> Result<ProgressTracker<'a>> {
+        // A validation run must not make a later import skip records
+        let checkpoint_path = self.checkpoint_path.as_deref().filter(|_| !self.config.validate_only);
+        ProgressTracker::open(path, storage, self.progress.clone(), checkpoint_path, self.config.flush_interval)
+    }
+    
+    /// Make the node mappings of a finished import durable
+    fn flush_id_map(&self) -> Result<()> {
+        match &self.id_map {
+            Some(id_map) => id_map.flush(),
+            None => Ok(()),
+        }
+    }
+    
+    /// Build a node from a JSON value, returning it with its external ID
+    fn build_node_value(&self, value: &Value, fallback_id: String) -> Result<(String, Node)> {
         let obj = value.as_object()
             .ok_or_else(|| DeepGraphError::StorageError("Expected JSON object".to_string()))?;
         
@@ -135,7 +411,7 @@ impl JsonImporter {
         let external_id = obj.get("id")
             .and_then(|v| v.as_str())
             .map(|s| s.to_string())
-            .unwrap_or_else(|| format!("node_{}", stats.nodes_imported));
+            .unwrap_or(fallback_id);
         
         // Get labels
         let labels = self.parse_labels(obj)?;
@@ -153,11 +429,134 @@ impl JsonImporter {
             }
         }
         
-        // Add to storage
-        let internal_id = storage.add_node(node)?;
-        stats.record_node(external_id, internal_id.to_string());
+        Ok((external_id, node))
+    }
+    
+    /// Read a JSON file holding an array of records, decompressing gzip input
+    fn read_records(path: &Path) -> Result<Vec<Value>> {
+        let reader = open_input(path)?;
+        serde_json::from_reader(reader)
+            .map_err(DeepGraphError::JsonError)
+    }
+    
+    /// Import nodes in WAL-logged batches of `config.batch_size` records
+    ///
+    /// Behaves like
+    /// [`CsvImporter::import_nodes_transactional`](crate::import::CsvImporter::import_nodes_transactional):
+    /// each batch is one WAL transaction, and re-running after a crash skips
+    /// every record covered by a committed batch.
+    pub fn import_nodes_transactional<S: StorageBackend>(
+        &self,
+        storage: &S,
+        path: impl AsRef<Path>,
+        wal: &WAL,
+        checkpoint_path: impl AsRef<Path>,
+    ) -> Result<ImportStats> {
+        let path = path.as_ref();
+        if self.config.validate_only {
+            // Nothing is written, so the write strategy does not matter
+            return self.import_nodes(storage, path);
+        }
+        info!("Importing nodes transactionally from JSON: {:?}", path);
+        
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        
+        let mut batch = TransactionalBatch::open(wal, checkpoint_path, &path.to_string_lossy())?;
+        let nodes = Self::read_records(path)?;
+        let skip = batch.resume_from();
+        
+        for (i, node_value) in nodes.iter().enumerate() {
+            if (i as u64) < skip {
+                continue;
+            }
+            
+            match self.build_node_value(node_value, format!("node_{}", i)) {
+                Ok((external_id, node)) => batch.stage_node(external_id, node),
+                Err(e) => {
+                    stats.add_error(format!("Node {}: {}", i, e));
+                    if !self.config.skip_invalid {
+                        return Err(e);
+                    }
+                    batch.skip_record();
+                    if self.config.max_errors > 0 && stats.errors.len() >= self.config.max_errors {
+                        warn!("Max errors ({}) reached, aborting import", self.config.max_errors);
+                        break;
+                    }
+                }
+            }
+            
+            if batch.pending() >= self.config.batch_size as u64 {
+                batch.commit(storage, &mut stats)?;
+            }
+        }
+        
+        let checkpoint = batch.finish(storage, &mut stats)?;
+        stats.node_id_map = checkpoint.node_id_map;
+        
+        stats.stop_timer(timer);
+        info!("Import complete: {} nodes imported in {} batches", stats.nodes_imported, checkpoint.batches_committed);
+        
+        Ok(stats)
+    }
+    
+    /// Import edges in WAL-logged batches of `config.batch_size` records
+    ///
+    /// Resumes from `checkpoint_path` the same way as
+    /// [`JsonImporter::import_nodes_transactional`].
+    pub fn import_edges_transactional<S: StorageBackend>(
+        &self,
+        storage: &S,
+        path: impl AsRef<Path>,
+        node_id_map: &HashMap<String, String>,
+        wal: &WAL,
+        checkpoint_path: impl AsRef<Path>,
+    ) -> Result<ImportStats> {
+        let path = path.as_ref();
+        if self.config.validate_only {
+            // Nothing is written, so the write strategy does not matter
+            return self.import_edges(storage, path, node_id_map);
+        }
+        info!("Importing edges transactionally from JSON: {:?}", path);
+        
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        
+        let mut batch = TransactionalBatch::open(wal, checkpoint_path, &path.to_string_lossy())?;
+        let edges = Self::read_records(path)?;
+        let skip = batch.resume_from();
+        
+        for (i, edge_value) in edges.iter().enumerate() {
+            if (i as u64) < skip {
+                continue;
+            }
+            
+            match self.build_edge_value(storage, edge_value, node_id_map) {
+                Ok(edge) => batch.stage_edge(edge),
+                Err(e) => {
+                    stats.add_error(format!("Edge {}: {}", i, e));
+                    if !self.config.skip_invalid {
+                        return Err(e);
+                    }
+                    batch.skip_record();
+                    if self.config.max_errors > 0 && stats.errors.len() >= self.config.max_errors {
+                        warn!("Max errors ({}) reached, aborting import", self.config.max_errors);
+                        break;
+                    }
+                }
+            }
+            
+            if batch.pending() >= self.config.batch_size as u64 {
+                batch.commit(storage, &mut stats)?;
+            }
+        }
+        
+        let checkpoint = batch.finish(storage, &mut stats)?;
+        
+        stats.stop_timer(timer);
+        info!("Import complete: {} edges imported in {} batches", stats.edges_imported, checkpoint.batches_committed);
         
-        Ok(())
+        Ok(stats)
     }
     
     /// Parse labels from JSON object
@@ -259,19 +658,13 @@ impl JsonImporter {
         let mut stats = ImportStats::new();
         let timer = stats.start_timer();
         
-        // Open JSON file
-        let file = File::open(path)
-            .map_err(|e| DeepGraphError::IoError(e))?;
-        let reader = BufReader::new(file);
-        
-        // Parse JSON array
-        let edges: Vec<Value> = serde_json::from_reader(reader)
-            .map_err(|e| DeepGraphError::JsonError(e))?;
+        let edges = Self::read_records(path)?;
         
         debug!("Parsed {} edge records", edges.len());
+        let mut progress = self.progress_tracker(path, storage)?;
         
-        // Process each edge
-        for (i, edge_value) in edges.iter().enumerate() {
+        // Process each edge, skipping those a previous run applied
+        for (i, edge_value) in edges.iter().enumerate().skip(progress.resume_from() as usize) {
             match self.import_edge_value(edge_value, node_id_map, storage, &mut stats) {
                 Ok(_) => {},
                 Err(e) => {
@@ -289,8 +682,10 @@ impl JsonImporter {
             if (i + 1) % self.config.flush_interval == 0 {
                 debug!("Processed {} edges", i + 1);
             }
+            progress.advance((i + 1) as u64, Some((i + 1) as f64 / edges.len() as f64), &mut stats)?;
         }
         
+        progress.finish(&mut stats)?;
         stats.stop_timer(timer);
         info!("Import complete: {} edges imported in {}ms", stats.edges_imported, stats.duration_ms);
         
@@ -302,13 +697,26 @@ impl JsonImporter {
     }
     
     /// Import a single edge from JSON value
-    fn import_edge_value<S: StorageBackend>(
+    pub(crate) fn import_edge_value<S: StorageBackend>(
         &self,
         value: &Value,
         node_id_map: &HashMap<String, String>,
         storage: &S,
         stats: &mut ImportStats,
     ) -> Result<()> {
+        let edge = self.build_edge_value(storage, value, node_id_map)?;
+        
+        // Add to storage
+        import_edge(storage, &self.config, stats, edge)
+    }
+    
+    /// Build an edge from a JSON value, resolving endpoints through the node ID map
+    fn build_edge_value<S: StorageBackend + ?Sized>(
+        &self,
+        storage: &S,
+        value: &Value,
+        node_id_map: &HashMap<String, String>,
+    ) -> Result<Edge> {
         let obj = value.as_object()
             .ok_or_else(|| DeepGraphError::StorageError("Expected JSON object".to_string()))?;
         
@@ -322,17 +730,9 @@ impl JsonImporter {
             .ok_or_else(|| DeepGraphError::StorageError("Missing 'to' field".to_string()))?;
         
         // Map to internal IDs
-        let from_internal = node_id_map.get(from_external)
-            .ok_or_else(|| DeepGraphError::StorageError(format!("Node '{}' not found in ID map", from_external)))?;
-        
-        let to_internal = node_id_map.get(to_external)
-            .ok_or_else(|| DeepGraphError::StorageError(format!("Node '{}' not found in ID map", to_external)))?;
-        
-        let from_id = NodeId::from_uuid(Uuid::parse_str(from_internal)
-            .map_err(|e| DeepGraphError::StorageError(format!("Invalid node ID: {}", e)))?);
-        
-        let to_id = NodeId::from_uuid(Uuid::parse_str(to_internal)
-            .map_err(|e| DeepGraphError::StorageError(format!("Invalid node ID: {}", e)))?);
+        let id_map = self.id_map.as_deref();
+        let from_id = resolve_node_id(storage, node_id_map, id_map, &self.config, from_external)?;
+        let to_id = resolve_node_id(storage, node_id_map, id_map, &self.config, to_external)?;
         
         // Get relationship type
         let rel_type = obj.get("type")
@@ -353,11 +753,7 @@ impl JsonImporter {
             }
         }
         
-        // Add to storage
-        storage.add_edge(edge)?;
-        stats.record_edge();
-        
-        Ok(())
+        Ok(edge)
     }
 }
 
@@ -412,4 +808,85 @@ mod tests {
         assert_eq!(stats.errors.len(), 0);
         assert_eq!(stats.node_id_map.len(), 2);
     }
+    
+    #[test]
+    fn test_import_nodes_transactional_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_path = dir.path().join("nodes.json");
+        let ckpt_path = dir.path().join("nodes.ckpt");
+        std::fs::write(&json_path, r#"[
+            {"id": "1", "labels": ["Person"]},
+            {"id": "2", "labels": ["Person"]},
+            {"id": "3", "labels": ["Person"]}
+        ]"#).unwrap();
+        
+        let wal_dir = dir.path().join("wal").to_string_lossy().to_string();
+        let wal = WAL::new(crate::wal::WALConfig::new().with_dir(wal_dir)).unwrap();
+        let importer = JsonImporter::new().with_config(ImportConfig::new().with_batch_size(2));
+        
+        // Pretend an earlier run committed the first batch before crashing
+        let storage = MemoryStorage::new();
+        let mut first = TransactionalBatch::open(&wal, &ckpt_path, &json_path.to_string_lossy()).unwrap();
+        first.stage_node("1".to_string(), Node::new(vec!["Person".to_string()]));
+        first.stage_node("2".to_string(), Node::new(vec!["Person".to_string()]));
+        first.commit(&storage, &mut ImportStats::new()).unwrap();
+        
+        let stats = importer.import_nodes_transactional(&storage, &json_path, &wal, &ckpt_path).unwrap();
+        assert_eq!(stats.nodes_imported, 1);
+        assert_eq!(stats.node_id_map.len(), 3);
+        assert_eq!(storage.node_count(), 3);
+    }
+    
+    #[test]
+    fn test_import_jsonl_gzip() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let mut nodes = GzEncoder::new(NamedTempFile::new().unwrap(), Compression::default());
+        writeln!(nodes, r#"{{"id": "a", "labels": ["Person"], "properties": {{"name": "Alice"}}}}"#).unwrap();
+        writeln!(nodes).unwrap();
+        writeln!(nodes, "not json").unwrap();
+        writeln!(nodes, r#"{{"id": "b", "labels": "Person"}}"#).unwrap();
+        let nodes = nodes.finish().unwrap();
+
+        let mut edges = NamedTempFile::new().unwrap();
+        writeln!(edges, r#"{{"from": "a", "to": "b", "type": "KNOWS", "properties": {{"since": 2020}}}}"#).unwrap();
+        writeln!(edges, r#"{{"from": "a", "to": "missing", "type": "KNOWS"}}"#).unwrap();
+
+        let storage = MemoryStorage::new();
+        let importer = JsonImporter::new();
+        let node_stats = importer.import_nodes_jsonl(&storage, nodes.path()).unwrap();
+        assert_eq!(node_stats.nodes_imported, 2);
+        assert_eq!(node_stats.errors.len(), 1);
+        assert!(node_stats.errors[0].starts_with("Line 3:"));
+
+        let edge_stats = importer
+            .import_edges_jsonl(&storage, edges.path(), &node_stats.node_id_map)
+            .unwrap();
+        assert_eq!(edge_stats.edges_imported, 1);
+        assert_eq!(edge_stats.errors.len(), 1);
+        assert_eq!(storage.edge_count(), 1);
+
+        let strict = JsonImporter::new().with_config(ImportConfig::new().with_skip_invalid(false));
+        assert!(strict.import_nodes_jsonl(&MemoryStorage::new(), nodes.path()).is_err());
+    }
+
+    #[test]
+    fn test_import_from_reader() {
+        let nodes = br#"[{"id": "a", "labels": ["Person"]}, {"id": "b", "labels": ["Person"]}]"#;
+        let edges = "{\"from\": \"a\", \"to\": \"b\", \"type\": \"KNOWS\"}\n\nnot json\n";
+
+        let storage = MemoryStorage::new();
+        let importer = JsonImporter::new();
+        let node_stats = importer.import_nodes_from_reader(&storage, &nodes[..]).unwrap();
+        assert_eq!(node_stats.nodes_imported, 2);
+
+        let edge_stats = importer
+            .import_edges_from_reader(&storage, edges.as_bytes(), &node_stats.node_id_map)
+            .unwrap();
+        assert_eq!(edge_stats.edges_imported, 1);
+        assert_eq!(edge_stats.errors.len(), 1);
+        assert!(edge_stats.errors[0].starts_with("Record 2:"));
+        assert_eq!(storage.edge_count(), 1);
+    }
 }
@@ -1,15 +1,38 @@
 //! Data import module for DeepGraph
 //!
-//! Supports importing graph data from CSV and JSON files.
+//! Supports importing graph data from CSV, JSON and newline-delimited JSON
+//! files, optionally gzip-compressed, from Arrow record batches, and from
+//! Neo4j exports.
 
+pub mod arrow;
+pub mod checkpoint;
 pub mod csv;
+pub mod id_map;
 pub mod json;
+pub mod mapping;
+pub mod neo4j;
+pub mod progress;
 
+pub use self::arrow::ArrowImporter;
+pub use checkpoint::{ImportCheckpoint, TransactionalBatch};
 pub use csv::CsvImporter;
+pub use id_map::{FileIdMapStore, IdMapStore};
 pub use json::JsonImporter;
+pub use mapping::{ColumnMapping, ColumnType};
+pub use neo4j::Neo4jImporter;
+pub use progress::{ImportProgress, ProgressCallback};
 
-use std::collections::HashMap;
+use crate::error::{DeepGraphError, Result};
+use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
+use crate::storage::StorageBackend;
+use flate2::read::MultiGzDecoder;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
 use std::time::Instant;
+use uuid::Uuid;
 
 /// Statistics from an import operation
 #[derive(Debug, Clone)]
@@ -28,6 +51,17 @@ pub struct ImportStats {
     
     /// Node ID mapping (external ID → internal NodeId)
     pub node_id_map: HashMap<String, String>,
+    
+    /// Property name → value types seen, collected by validation runs
+    pub schema: BTreeMap<String, BTreeSet<&'static str>>,
+    
+    /// Records repeating an external ID seen earlier in the same import,
+    /// counted when a [`DuplicatePolicy`] is set
+    pub duplicates: usize,
+    
+    /// Node mappings recorded since the last checkpoint, kept only while
+    /// an import is checkpointed
+    pub(crate) unsaved_ids: Option<Vec<(String, String)>>,
 }
 
 impl ImportStats {
@@ -39,6 +73,9 @@ impl ImportStats {
             errors: Vec::new(),
             duration_ms: 0,
             node_id_map: HashMap::new(),
+            schema: BTreeMap::new(),
+            duplicates: 0,
+            unsaved_ids: None,
         }
     }
     
@@ -60,6 +97,9 @@ impl ImportStats {
     /// Record node import
     pub fn record_node(&mut self, external_id: String, internal_id: String) {
         self.nodes_imported += 1;
+        if let Some(unsaved) = &mut self.unsaved_ids {
+            unsaved.push((external_id.clone(), internal_id.clone()));
+        }
         self.node_id_map.insert(external_id, internal_id);
     }
     
@@ -67,6 +107,42 @@ impl ImportStats {
     pub fn record_edge(&mut self) {
         self.edges_imported += 1;
     }
+    
+    /// Record the value types of a record's properties in `schema`
+    pub fn record_schema(&mut self, properties: &HashMap<String, PropertyValue>) {
+        for (key, value) in properties {
+            let type_name = match value {
+                PropertyValue::String(_) => "string",
+                PropertyValue::Integer(_) => "integer",
+                PropertyValue::Float(_) => "float",
+                PropertyValue::Boolean(_) => "boolean",
+                PropertyValue::Null => "null",
+                PropertyValue::List(_) => "list",
+                PropertyValue::Map(_) => "map",
+            };
+            if let Some(types) = self.schema.get_mut(key) {
+                types.insert(type_name);
+            } else {
+                self.schema.insert(key.clone(), BTreeSet::from([type_name]));
+            }
+        }
+    }
+    
+    /// Add the counts, errors and ID mappings of another import, such as
+    /// one worker's share of a parallel import
+    pub fn merge(&mut self, other: ImportStats) {
+        self.nodes_imported += other.nodes_imported;
+        self.edges_imported += other.edges_imported;
+        self.errors.extend(other.errors);
+        if let Some(unsaved) = &mut self.unsaved_ids {
+            unsaved.extend(other.node_id_map.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        self.node_id_map.extend(other.node_id_map);
+        self.duplicates += other.duplicates;
+        for (key, types) in other.schema {
+            self.schema.entry(key).or_default().extend(types);
+        }
+    }
 }
 
 impl Default for ImportStats {
@@ -75,10 +151,25 @@ impl Default for ImportStats {
     }
 }
 
+/// What a node import does with a record whose external ID already appeared
+/// earlier in the same import
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Reject the record, reporting an error
+    Error,
+    /// Keep the first record and ignore the repeat
+    Skip,
+    /// Replace the earlier node's labels and properties with the repeat's
+    Overwrite,
+    /// Union labels and overwrite only the properties the repeat sets
+    MergeProperties,
+}
+
 /// Configuration for import operations
 #[derive(Debug, Clone)]
 pub struct ImportConfig {
-    /// Batch size for bulk operations
+    /// Batch size for bulk operations (records per transaction for
+    /// transactional imports, records per chunk for parallel imports)
     pub batch_size: usize,
     
     /// Flush to disk after every N records
@@ -89,6 +180,43 @@ pub struct ImportConfig {
     
     /// Maximum errors before aborting (0 = unlimited)
     pub max_errors: usize,
+    
+    /// Worker threads for parallel imports
+    pub workers: usize,
+    
+    /// Property that identifies a node across imports (MERGE semantics)
+    ///
+    /// When set, a record whose key matches an existing node updates that
+    /// node instead of creating another, and an edge repeating the type and
+    /// endpoints of an existing edge updates it. Records without the key
+    /// property get their external ID stored under it. The transactional
+    /// CSV imports ignore it; they rely on their checkpoint instead.
+    pub merge_key: Option<String>,
+    
+    /// Property holding each node's external ID
+    ///
+    /// Node imports store the external ID under it when the record has no
+    /// such property, and edge endpoints missing from the ID map and ID
+    /// store are looked up by it in storage, so edges can be imported in a
+    /// later session without either.
+    pub id_property: Option<String>,
+    
+    /// Parse and type-check the whole input without writing anything
+    ///
+    /// Errors are reported per record as usual, counts are of the records
+    /// that would be imported, and `ImportStats::schema` lists the property
+    /// types found. Node IDs in `node_id_map` are placeholders, but can be
+    /// passed to a validation run over the edges to check their endpoints.
+    /// Checkpoints are neither read nor written.
+    pub validate_only: bool,
+    
+    /// Handling of external IDs repeated within one import
+    ///
+    /// Without a policy every record creates a node and the last one wins
+    /// the ID mapping. Parallel imports with a policy run sequentially, since
+    /// duplicates must be seen in file order; the transactional CSV imports
+    /// ignore it.
+    pub duplicate_policy: Option<DuplicatePolicy>,
 }
 
 impl ImportConfig {
@@ -99,6 +227,11 @@ impl ImportConfig {
             flush_interval: 5000,
             skip_invalid: true,
             max_errors: 100,
+            workers: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            merge_key: None,
+            id_property: None,
+            validate_only: false,
+            duplicate_policy: None,
         }
     }
     
@@ -125,6 +258,37 @@ impl ImportConfig {
         self.max_errors = max;
         self
     }
+    
+    /// Set the number of worker threads for parallel imports
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+    
+    /// Match existing nodes on property `key` and update them instead of
+    /// creating duplicates, so re-running an import is idempotent
+    pub fn with_merge_key(mut self, key: impl Into<String>) -> Self {
+        self.merge_key = Some(key.into());
+        self
+    }
+    
+    /// Store external IDs in property `key` and resolve edge endpoints by it
+    pub fn with_id_property(mut self, key: impl Into<String>) -> Self {
+        self.id_property = Some(key.into());
+        self
+    }
+    
+    /// Set whether to only validate the input, writing nothing
+    pub fn with_validate_only(mut self, validate_only: bool) -> Self {
+        self.validate_only = validate_only;
+        self
+    }
+    
+    /// Set how external IDs repeated within one import are handled
+    pub fn with_duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = Some(policy);
+        self
+    }
 }
 
 impl Default for ImportConfig {
@@ -132,3 +296,249 @@ impl Default for ImportConfig {
         Self::new()
     }
 }
+
+/// Store an imported node and record it in `stats`; validation runs only
+/// record it
+pub(crate) fn import_node<S: StorageBackend + ?Sized>(
+    storage: &S,
+    id_map: Option<&dyn IdMapStore>,
+    config: &ImportConfig,
+    stats: &mut ImportStats,
+    external_id: String,
+    node: Node,
+) -> Result<()> {
+    if let Some(policy) = config.duplicate_policy {
+        if let Some(previous) = stats.node_id_map.get(&external_id) {
+            let previous = parse_node_id(previous)?;
+            stats.duplicates += 1;
+            return import_duplicate(storage, config, stats, policy, previous, &external_id, node);
+        }
+    }
+    let node_id = if config.validate_only {
+        stats.record_schema(node.properties());
+        node.id()
+    } else {
+        store_node(storage, id_map, config, &external_id, node)?
+    };
+    stats.record_node(external_id, node_id.to_string());
+    Ok(())
+}
+
+/// Apply `policy` to a record repeating the external ID of node `previous`
+fn import_duplicate<S: StorageBackend + ?Sized>(
+    storage: &S,
+    config: &ImportConfig,
+    stats: &mut ImportStats,
+    policy: DuplicatePolicy,
+    previous: NodeId,
+    external_id: &str,
+    mut node: Node,
+) -> Result<()> {
+    match policy {
+        DuplicatePolicy::Error => {
+            return Err(DeepGraphError::StorageError(format!("Duplicate external ID '{}'", external_id)));
+        }
+        DuplicatePolicy::Skip => return Ok(()),
+        _ if config.validate_only => stats.record_schema(node.properties()),
+        DuplicatePolicy::Overwrite => {
+            set_key_properties(config, external_id, &mut node);
+            let mut replacement = Node::with_id(previous, node.labels().to_vec());
+            *replacement.properties_mut() = std::mem::take(node.properties_mut());
+            storage.update_node(replacement)?;
+        }
+        DuplicatePolicy::MergeProperties => {
+            set_key_properties(config, external_id, &mut node);
+            merge_node(storage, storage.get_node(previous)?, node)?;
+        }
+    }
+    Ok(())
+}
+
+/// Store an imported edge and record it in `stats`; validation runs only
+/// record it
+pub(crate) fn import_edge<S: StorageBackend + ?Sized>(
+    storage: &S,
+    config: &ImportConfig,
+    stats: &mut ImportStats,
+    edge: Edge,
+) -> Result<()> {
+    if config.validate_only {
+        stats.record_schema(edge.properties());
+    } else {
+        store_edge(storage, config.merge_key.is_some(), edge)?;
+    }
+    stats.record_edge();
+    Ok(())
+}
+
+/// Store an imported node, merging it into the node it was imported as
+/// earlier
+///
+/// An earlier node is found through the ID store by external ID, or else by
+/// the `merge_key` property, which defaults to the external ID when the
+/// record has no such property. Labels are unioned and incoming properties
+/// overwrite existing ones. If the stored node has since been deleted, a
+/// new node is created and the ID store entry is repointed at it.
+pub(crate) fn store_node<S: StorageBackend + ?Sized>(
+    storage: &S,
+    id_map: Option<&dyn IdMapStore>,
+    config: &ImportConfig,
+    external_id: &str,
+    mut node: Node,
+) -> Result<NodeId> {
+    let merge_value = set_key_properties(config, external_id, &mut node);
+
+    if let Some(existing_id) = id_map.map(|ids| ids.get(external_id)).transpose()?.flatten() {
+        match storage.get_node(existing_id) {
+            Ok(existing) => return merge_node(storage, existing, node),
+            Err(e) if e.is_not_found() => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let existing = merge_value
+        .and_then(|(key, value)| storage.get_nodes_by_property(key, &value).into_iter().next());
+    let node_id = match existing {
+        Some(existing) => merge_node(storage, existing, node)?,
+        None => storage.add_node(node)?,
+    };
+    if let Some(ids) = id_map {
+        ids.insert(external_id, node_id)?;
+    }
+    Ok(node_id)
+}
+
+/// Store the external ID under the `id_property` and `merge_key` properties
+/// where the record has no value of its own, returning the merge key and
+/// value
+fn set_key_properties<'a>(
+    config: &'a ImportConfig,
+    external_id: &str,
+    node: &mut Node,
+) -> Option<(&'a str, PropertyValue)> {
+    let mut key_value = |key: &str| {
+        node.properties_mut()
+            .entry(key.to_string())
+            .or_insert_with(|| PropertyValue::String(external_id.to_string()))
+            .clone()
+    };
+    if let Some(key) = config.id_property.as_deref() {
+        key_value(key);
+    }
+    config.merge_key.as_deref().map(|key| (key, key_value(key)))
+}
+
+/// Union `node`'s labels into `existing` and overwrite its properties
+fn merge_node<S: StorageBackend + ?Sized>(storage: &S, mut existing: Node, node: Node) -> Result<NodeId> {
+    for label in node.labels() {
+        existing.add_label(label.clone());
+    }
+    for (key, value) in node.properties() {
+        existing.set_property(key.clone(), value.clone());
+    }
+    let id = existing.id();
+    storage.update_node(existing)?;
+    Ok(id)
+}
+
+/// Store an imported edge, or with `merge` update the existing edge of the
+/// same type between the same nodes by overwriting its properties
+pub(crate) fn store_edge<S: StorageBackend + ?Sized>(storage: &S, merge: bool, edge: Edge) -> Result<EdgeId> {
+    if merge {
+        let existing = storage
+            .get_outgoing_edges(edge.from())?
+            .into_iter()
+            .find(|e| e.to() == edge.to() && e.relationship_type() == edge.relationship_type());
+        if let Some(mut existing) = existing {
+            for (key, value) in edge.properties() {
+                existing.set_property(key.clone(), value.clone());
+            }
+            let id = existing.id();
+            storage.update_edge(existing)?;
+            return Ok(id);
+        }
+    }
+    storage.add_edge(edge)
+}
+
+/// Resolve an edge endpoint through this import's ID map, falling back to
+/// the ID store and then the `id_property` for nodes imported by earlier
+/// runs
+pub(crate) fn resolve_node_id<S: StorageBackend + ?Sized>(
+    storage: &S,
+    node_id_map: &HashMap<String, String>,
+    id_map: Option<&dyn IdMapStore>,
+    config: &ImportConfig,
+    external_id: &str,
+) -> Result<NodeId> {
+    if let Some(internal) = node_id_map.get(external_id) {
+        return parse_node_id(internal);
+    }
+    if let Some(ids) = id_map {
+        if let Some(node_id) = ids.get(external_id)? {
+            return Ok(node_id);
+        }
+    }
+    if let Some(key) = config.id_property.as_deref() {
+        let value = PropertyValue::String(external_id.to_string());
+        if let Some(node) = storage.get_nodes_by_property(key, &value).into_iter().next() {
+            return Ok(node.id());
+        }
+    }
+    Err(DeepGraphError::StorageError(format!("Node '{}' not found in ID map", external_id)))
+}
+
+/// Parse an internal node ID as stored in `ImportStats::node_id_map`
+fn parse_node_id(internal: &str) -> Result<NodeId> {
+    Uuid::parse_str(internal)
+        .map(NodeId::from_uuid)
+        .map_err(|e| DeepGraphError::StorageError(format!("Invalid node ID: {}", e)))
+}
+
+/// Open an import file for buffered reading, transparently decompressing it
+/// if it starts with the gzip magic bytes
+pub(crate) fn open_input(path: &Path) -> Result<Box<dyn BufRead>> {
+    decode_input(File::open(path)?)
+}
+
+/// Buffer an import stream, transparently decompressing it if it starts
+/// with the gzip magic bytes
+pub(crate) fn decode_input<'a, R: Read + 'a>(input: R) -> Result<Box<dyn BufRead + 'a>> {
+    let mut reader = BufReader::new(input);
+    if reader.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        // Multi-member so concatenated gzip files (as written by
+        // `cat a.gz b.gz` or parallel gzip) decode in full
+        return Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))));
+    }
+    Ok(Box::new(reader))
+}
+
+/// The JSON records of `reader`: the elements of a JSON array, which is
+/// parsed whole, or else one record per non-blank line
+pub(crate) fn json_records<'a>(
+    mut reader: Box<dyn BufRead + 'a>,
+) -> Result<Box<dyn Iterator<Item = Result<Value>> + 'a>> {
+    let is_array = reader.fill_buf()?.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'[');
+    if is_array {
+        let values: Vec<Value> = serde_json::from_reader(reader)?;
+        return Ok(Box::new(values.into_iter().map(Ok)));
+    }
+    Ok(Box::new(
+        reader
+            .lines()
+            .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+            .map(|line| Ok(serde_json::from_str(&line?)?)),
+    ))
+}
+
+/// Size in bytes of an uncompressed import file, used to estimate progress;
+/// `None` for gzip input, whose decompressed size is unknown up front
+pub(crate) fn plain_input_size(path: &Path) -> Option<u64> {
+    let mut file = File::open(path).ok()?;
+    let mut magic = [0u8; 2];
+    let read = file.read(&mut magic).ok()?;
+    if read == 2 && magic == [0x1f, 0x8b] {
+        return None;
+    }
+    Some(file.metadata().ok()?.len())
+}
@@ -0,0 +1,301 @@
+//! Import of Neo4j exports
+//!
+//! Eases migrating a Neo4j database to DeepGraph. Two export formats are
+//! understood:
+//! - `apoc.export.json.all` output, either one record per line (the APOC
+//!   default) or a single JSON array (`jsonFormat: 'ARRAY_JSON'`), optionally
+//!   gzip-compressed
+//! - CSV files in the `neo4j-admin database import` layout, whose headers
+//!   carry the column roles and types (`userId:ID`, `:LABEL`, `age:int`,
+//!   `:START_ID`, `:END_ID`, `:TYPE`)
+//!
+//! Neo4j's internal node IDs become the external IDs of the import, so they
+//! land in [`ImportStats::node_id_map`] and any configured [`IdMapStore`].
+//! ID spaces such as `:ID(User)` are not distinguished; IDs must be unique
+//! across the whole export.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use deepgraph::import::Neo4jImporter;
+//!
+//! let stats = Neo4jImporter::new().import_apoc_json(&storage, "export.json")?;
+//! println!("{} nodes, {} relationships", stats.nodes_imported, stats.edges_imported);
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use crate::import::{
+    json_records, open_input, ColumnMapping, ColumnType, CsvImporter, IdMapStore, ImportConfig, ImportStats,
+    JsonImporter,
+};
+use crate::storage::StorageBackend;
+use csv::StringRecord;
+use log::{info, warn};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Importer for Neo4j APOC JSON and neo4j-admin CSV exports
+pub struct Neo4jImporter {
+    config: ImportConfig,
+    delimiter: u8,
+    id_map: Option<Arc<dyn IdMapStore>>,
+}
+
+impl Neo4jImporter {
+    /// Create a new Neo4j importer with default configuration
+    pub fn new() -> Self {
+        Self {
+            config: ImportConfig::new(),
+            delimiter: b',',
+            id_map: None,
+        }
+    }
+
+    /// Set the configuration
+    pub fn with_config(mut self, config: ImportConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set the delimiter of neo4j-admin CSV files (default: comma)
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Record imported nodes in an [`IdMapStore`], keyed by Neo4j node ID
+    pub fn with_id_map(mut self, id_map: Arc<dyn IdMapStore>) -> Self {
+        self.id_map = Some(id_map);
+        self
+    }
+
+    /// Import the nodes and relationships of an `apoc.export.json` file
+    ///
+    /// Relationships are resolved against the nodes imported earlier in the
+    /// same file (APOC writes all nodes first), then against the ID map.
+    pub fn import_apoc_json<S: StorageBackend>(&self, storage: &S, path: impl AsRef<Path>) -> Result<ImportStats> {
+        let path = path.as_ref();
+        info!("Importing Neo4j APOC export: {:?}", path);
+
+        let mut stats = ImportStats::new();
+        let timer = stats.start_timer();
+        let importer = self.json_importer();
+
+        for (i, record) in json_records(open_input(path)?)?.enumerate() {
+            let result = record.and_then(|value| self.import_apoc_record(&importer, storage, &value, &mut stats));
+            if let Err(e) = result {
+                stats.add_error(format!("Record {}: {}", i + 1, e));
+                if !self.config.skip_invalid {
+                    return Err(e);
+                }
+                if self.config.max_errors > 0 && stats.errors.len() >= self.config.max_errors {
+                    warn!("Max errors ({}) reached, aborting import", self.config.max_errors);
+                    break;
+                }
+            }
+        }
+
+        stats.stop_timer(timer);
+        info!(
+            "Import complete: {} nodes and {} relationships imported in {}ms",
+            stats.nodes_imported, stats.edges_imported, stats.duration_ms
+        );
+        if !stats.errors.is_empty() {
+            warn!("Import completed with {} errors", stats.errors.len());
+        }
+
+        if let Some(id_map) = &self.id_map {
+            id_map.flush()?;
+        }
+        Ok(stats)
+    }
+
+    /// Import a neo4j-admin node CSV file
+    ///
+    /// The `:ID` column supplies external IDs and `:LABEL` the labels
+    /// (`;`-separated). Typed headers such as `age:int` or `born:date` fix
+    /// the property type and name; array types are kept as text, and
+    /// `:IGNORE` columns are skipped.
+    pub fn import_admin_csv_nodes<S: StorageBackend>(&self, storage: &S, path: impl AsRef<Path>) -> Result<ImportStats> {
+        let path = path.as_ref();
+        self.csv_importer(path)?.import_nodes(storage, path)
+    }
+
+    /// Import a neo4j-admin relationship CSV file, resolving `:START_ID` and
+    /// `:END_ID` through `node_id_map` and the ID map
+    pub fn import_admin_csv_edges<S: StorageBackend>(
+        &self,
+        storage: &S,
+        path: impl AsRef<Path>,
+        node_id_map: &HashMap<String, String>,
+    ) -> Result<ImportStats> {
+        let path = path.as_ref();
+        self.csv_importer(path)?.import_edges(storage, path, node_id_map)
+    }
+
+    /// Import one APOC record, rewritten into the [`JsonImporter`] formats
+    fn import_apoc_record<S: StorageBackend>(
+        &self,
+        importer: &JsonImporter,
+        storage: &S,
+        value: &Value,
+        stats: &mut ImportStats,
+    ) -> Result<()> {
+        let properties = value.get("properties").cloned().unwrap_or(Value::Null);
+        match value.get("type").and_then(Value::as_str) {
+            Some("node") => {
+                let node = json!({
+                    "id": apoc_id(value.get("id"))?,
+                    "labels": value.get("labels").cloned().unwrap_or_else(|| json!([])),
+                    "properties": properties,
+                });
+                importer.import_node_value(&node, storage, stats)
+            }
+            Some("relationship") => {
+                let edge = json!({
+                    "from": apoc_id(value.get("start").and_then(|start| start.get("id")))?,
+                    "to": apoc_id(value.get("end").and_then(|end| end.get("id")))?,
+                    "type": value.get("label").cloned().unwrap_or(Value::Null),
+                    "properties": properties,
+                });
+                // Relationships resolve against this run's nodes
+                let node_id_map = std::mem::take(&mut stats.node_id_map);
+                let result = importer.import_edge_value(&edge, &node_id_map, storage, stats);
+                stats.node_id_map = node_id_map;
+                result
+            }
+            other => Err(DeepGraphError::StorageError(format!("Unknown APOC record type {:?}", other))),
+        }
+    }
+
+    /// JSON importer sharing this importer's configuration
+    fn json_importer(&self) -> JsonImporter {
+        let importer = JsonImporter::new().with_config(self.config.clone());
+        match &self.id_map {
+            Some(id_map) => importer.with_id_map(id_map.clone()),
+            None => importer,
+        }
+    }
+
+    /// CSV importer configured from the neo4j-admin header of `path`
+    fn csv_importer(&self, path: &Path) -> Result<CsvImporter> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(true)
+            .from_path(path)
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to open CSV: {}", e)))?;
+        let headers = reader
+            .headers()
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to read headers: {}", e)))?
+            .clone();
+
+        let importer = CsvImporter::new()
+            .with_config(self.config.clone())
+            .with_delimiter(self.delimiter)
+            .with_mapping(admin_mapping(&headers));
+        Ok(match &self.id_map {
+            Some(id_map) => importer.with_id_map(id_map.clone()),
+            None => importer,
+        })
+    }
+}
+
+impl Default for Neo4jImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An APOC ID, written as a string or (by older APOC versions) a number
+fn apoc_id(id: Option<&Value>) -> Result<String> {
+    match id {
+        Some(Value::String(id)) => Ok(id.clone()),
+        Some(Value::Number(id)) => Ok(id.to_string()),
+        _ => Err(DeepGraphError::StorageError("Missing 'id' field".to_string())),
+    }
+}
+
+/// Column mapping for a neo4j-admin CSV header
+fn admin_mapping(headers: &StringRecord) -> ColumnMapping {
+    let mut mapping = ColumnMapping::new();
+    for header in headers {
+        let Some((name, kind)) = header.rsplit_once(':') else {
+            continue;
+        };
+        // Drop the ID space of `:ID(User)` and friends
+        let kind = kind.split('(').next().unwrap_or(kind);
+        match kind {
+            "ID" => mapping.id_column = Some(header.to_string()),
+            "LABEL" => mapping.labels_column = Some(header.to_string()),
+            "START_ID" => mapping.from_column = Some(header.to_string()),
+            "END_ID" => mapping.to_column = Some(header.to_string()),
+            "TYPE" => mapping.type_column = Some(header.to_string()),
+            "IGNORE" => mapping = mapping.with_ignored(header),
+            _ => {
+                let column_type = match kind.to_ascii_lowercase().as_str() {
+                    "int" | "long" | "short" | "byte" => ColumnType::Integer,
+                    "float" | "double" => ColumnType::Float,
+                    "boolean" => ColumnType::Boolean,
+                    "date" => ColumnType::Timestamp("%Y-%m-%d".to_string()),
+                    _ => ColumnType::String,
+                };
+                mapping = mapping.with_type(header, column_type).with_rename(header, name);
+            }
+        }
+    }
+    mapping
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::PropertyValue;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_import_apoc_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.json");
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"type":"node","id":"0","labels":["User"],"properties":{"name":"Ada","tags":["a","b"]}}"#, "\n",
+                r#"{"type":"node","id":"1","labels":["User","Admin"],"properties":{"name":"Bob"}}"#, "\n",
+                r#"{"type":"relationship","id":"0","label":"KNOWS","properties":{"since":2020},"start":{"id":"0","labels":["User"]},"end":{"id":"1","labels":["User"]}}"#, "\n",
+            ),
+        )
+        .unwrap();
+
+        let storage = MemoryStorage::new();
+        let stats = Neo4jImporter::new().import_apoc_json(&storage, &path).unwrap();
+        assert_eq!((stats.nodes_imported, stats.edges_imported), (2, 1));
+        assert!(stats.errors.is_empty());
+        assert_eq!(storage.get_nodes_by_label("Admin").len(), 1);
+        let edge = &storage.get_edges_by_type("KNOWS")[0];
+        assert_eq!(edge.get_property("since"), Some(&PropertyValue::Integer(2020)));
+    }
+
+    #[test]
+    fn test_import_admin_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodes_path = dir.path().join("users.csv");
+        let edges_path = dir.path().join("follows.csv");
+        std::fs::write(&nodes_path, "userId:ID(User),name,zip:string,age:int,:LABEL\nu1,Ada,00501,36,User;Admin\nu2,Bob,,41,User\n").unwrap();
+        std::fs::write(&edges_path, ":START_ID(User),:END_ID(User),:TYPE,weight:double\nu1,u2,FOLLOWS,0.5\n").unwrap();
+
+        let storage = MemoryStorage::new();
+        let importer = Neo4jImporter::new();
+        let nodes = importer.import_admin_csv_nodes(&storage, &nodes_path).unwrap();
+        assert_eq!(nodes.nodes_imported, 2);
+        let ada = &storage.get_nodes_by_label("Admin")[0];
+        assert_eq!(ada.get_property("zip"), Some(&PropertyValue::String("00501".to_string())));
+        assert_eq!(ada.get_property("age"), Some(&PropertyValue::Integer(36)));
+
+        let edges = importer.import_admin_csv_edges(&storage, &edges_path, &nodes.node_id_map).unwrap();
+        assert_eq!(edges.edges_imported, 1);
+        let edge = &storage.get_edges_by_type("FOLLOWS")[0];
+        assert_eq!(edge.get_property("weight"), Some(&PropertyValue::Float(0.5)));
+    }
+}
@@ -0,0 +1,238 @@
+//! Import progress reporting and resumable checkpoints
+//!
+//! Importers report an [`ImportProgress`] to a callback every
+//! `ImportConfig::flush_interval` records and once at the end. With a
+//! checkpoint file, the same points save an [`ImportCheckpoint`] recording
+//! how many input records have been applied to storage, and a re-run of the
+//! same file skips them instead of starting over.
+//!
+//! Records applied after the last checkpoint are applied again on resume;
+//! combine checkpoints with `ImportConfig::with_merge_key` to make that
+//! replay idempotent.
+//!
+//! Node ID mappings are not part of the checkpoint file itself: each
+//! checkpoint appends the mappings recorded since the previous one to a
+//! sidecar file (the checkpoint path with an `ids` extension), so saving
+//! stays cheap however many nodes were imported. Storage is flushed before
+//! a checkpoint is saved, so the checkpoint never covers lost writes.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let importer = CsvImporter::new()
+//!     .with_checkpoint("nodes.ckpt")
+//!     .with_progress(|p| println!("{} records, {:.0}/s, eta {:?}", p.records, p.records_per_sec, p.eta));
+//! let stats = importer.import_nodes(&storage, "nodes.csv")?;
+//! ```
+
+use crate::error::Result;
+use crate::import::{ImportCheckpoint, ImportStats};
+use crate::storage::StorageBackend;
+use log::warn;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Callback receiving import progress
+pub type ProgressCallback = Arc<dyn Fn(&ImportProgress) + Send + Sync>;
+
+/// Snapshot of a running import
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportProgress {
+    /// Input records read so far, including records skipped on resume
+    pub records: u64,
+    /// Nodes and edges imported by this run
+    pub imported: usize,
+    /// Errors recorded by this run
+    pub errors: usize,
+    /// Time since this run started
+    pub elapsed: Duration,
+    /// Records per second processed by this run
+    pub records_per_sec: f64,
+    /// Estimated time remaining, when the input size is known
+    pub eta: Option<Duration>,
+}
+
+/// Drives progress callbacks and checkpoint saves for one import
+pub(crate) struct ProgressTracker<'a> {
+    callback: Option<ProgressCallback>,
+    checkpoint_path: Option<PathBuf>,
+    checkpoint: ImportCheckpoint,
+    /// Flushed before each checkpoint is saved
+    storage: Option<&'a dyn StorageBackend>,
+    interval: u64,
+    start: Instant,
+    resumed_from: u64,
+    /// Input fraction already consumed when this run started, estimated
+    /// from the first reported fraction
+    start_fraction: Option<f64>,
+    records: u64,
+}
+
+impl<'a> ProgressTracker<'a> {
+    /// Track an import of `source` into `storage`, resuming from
+    /// `checkpoint_path` if it holds a checkpoint for the same source
+    pub(crate) fn open(
+        source: &Path,
+        storage: &'a dyn StorageBackend,
+        callback: Option<ProgressCallback>,
+        checkpoint_path: Option<&Path>,
+        interval: usize,
+    ) -> Result<Self> {
+        let source = source.to_string_lossy();
+        let checkpoint = match checkpoint_path {
+            Some(path) => {
+                let resuming = path.exists();
+                let mut checkpoint = ImportCheckpoint::load_for(path, &source)?;
+                let ids_path = ids_path(path);
+                if !resuming {
+                    // Mappings of an abandoned run do not apply
+                    if ids_path.exists() {
+                        fs::remove_file(&ids_path)?;
+                    }
+                } else {
+                    checkpoint.node_id_map.extend(read_ids(&ids_path)?);
+                    // Compact the sidecar, folding in any map the checkpoint
+                    // file itself carried
+                    write_ids(&ids_path, &checkpoint.node_id_map, false)?;
+                }
+                checkpoint
+            }
+            None => ImportCheckpoint::new(source),
+        };
+        Ok(Self {
+            callback,
+            checkpoint_path: checkpoint_path.map(Path::to_path_buf),
+            storage: Some(storage),
+            resumed_from: checkpoint.records_committed,
+            records: checkpoint.records_committed,
+            checkpoint,
+            interval: interval.max(1) as u64,
+            start: Instant::now(),
+            start_fraction: None,
+        })
+    }
+
+    /// Track an import of a stream, which cannot be re-read and so is never
+    /// checkpointed
+    pub(crate) fn for_stream(callback: Option<ProgressCallback>, interval: usize) -> Self {
+        Self {
+            callback,
+            checkpoint_path: None,
+            checkpoint: ImportCheckpoint::new("<stream>"),
+            storage: None,
+            interval: interval.max(1) as u64,
+            start: Instant::now(),
+            resumed_from: 0,
+            start_fraction: None,
+            records: 0,
+        }
+    }
+
+    /// Input records a previous run already applied
+    pub(crate) fn resume_from(&self) -> u64 {
+        self.resumed_from
+    }
+
+    /// Seed `stats` with the node mappings of previous runs and, when
+    /// checkpointing, start collecting the mappings still to be saved
+    pub(crate) fn restore(&mut self, stats: &mut ImportStats) {
+        stats.node_id_map.extend(std::mem::take(&mut self.checkpoint.node_id_map));
+        if self.checkpoint_path.is_some() {
+            stats.unsaved_ids = Some(Vec::new());
+        }
+    }
+
+    /// Note that the first `records` input records have been applied, with
+    /// `fraction` of the input consumed if known; reports and checkpoints
+    /// every interval
+    pub(crate) fn advance(&mut self, records: u64, fraction: Option<f64>, stats: &mut ImportStats) -> Result<()> {
+        self.records = records;
+        if records % self.interval == 0 {
+            self.report(fraction, stats)?;
+        }
+        Ok(())
+    }
+
+    /// Report and checkpoint the end of the input
+    pub(crate) fn finish(&mut self, stats: &mut ImportStats) -> Result<()> {
+        self.report(Some(1.0), stats)
+    }
+
+    fn report(&mut self, fraction: Option<f64>, stats: &mut ImportStats) -> Result<()> {
+        if let Some(path) = &self.checkpoint_path {
+            if let Some(storage) = self.storage {
+                storage.flush()?;
+            }
+            if let Some(unsaved) = stats.unsaved_ids.as_mut().filter(|unsaved| !unsaved.is_empty()) {
+                write_ids(&ids_path(path), unsaved.drain(..), true)?;
+            }
+            self.checkpoint.records_committed = self.records;
+            self.checkpoint.batches_committed += 1;
+            self.checkpoint.save(path)?;
+        }
+        if let Some(callback) = &self.callback {
+            let elapsed = self.start.elapsed();
+            let seconds = elapsed.as_secs_f64();
+            let processed = (self.records - self.resumed_from) as f64;
+            // Only the part of the input read by this run took `elapsed`
+            let resumed_from = self.resumed_from;
+            let records = self.records;
+            let start_fraction = *self.start_fraction.get_or_insert_with(|| match fraction {
+                Some(f) if records > 0 => f * resumed_from as f64 / records as f64,
+                _ => 0.0,
+            });
+            callback(&ImportProgress {
+                records: self.records,
+                imported: stats.nodes_imported + stats.edges_imported,
+                errors: stats.errors.len(),
+                elapsed,
+                records_per_sec: if seconds > 0.0 { processed / seconds } else { 0.0 },
+                eta: fraction
+                    .filter(|&f| f > start_fraction && f <= 1.0)
+                    .map(|f| elapsed.mul_f64((1.0 - f) / (f - start_fraction))),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Sidecar file holding the node mappings of a checkpoint
+fn ids_path(checkpoint_path: &Path) -> PathBuf {
+    checkpoint_path.with_extension("ids")
+}
+
+/// Write mappings as JSON lines, appending or replacing the file
+fn write_ids<K: AsRef<str>, V: AsRef<str>>(path: &Path, ids: impl IntoIterator<Item = (K, V)>, append: bool) -> Result<()> {
+    let file = OpenOptions::new().create(true).write(true).append(append).truncate(!append).open(path)?;
+    let mut writer = BufWriter::new(file);
+    for (external_id, internal_id) in ids {
+        serde_json::to_writer(&mut writer, &(external_id.as_ref(), internal_id.as_ref()))?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read a mapping sidecar, ignoring a line torn by a crash
+fn read_ids(path: &Path) -> Result<HashMap<String, String>> {
+    let mut ids = HashMap::new();
+    if !path.exists() {
+        return Ok(ids);
+    }
+    for line in BufReader::new(File::open(path)?).lines() {
+        match serde_json::from_str::<(String, String)>(&line?) {
+            Ok((external_id, internal_id)) => {
+                ids.insert(external_id, internal_id);
+            }
+            Err(e) => {
+                warn!("Ignoring unreadable line of {:?}: {}", path, e);
+                break;
+            }
+        }
+    }
+    Ok(ids)
+}
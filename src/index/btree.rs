@@ -6,31 +6,30 @@
 
 use crate::error::{DeepGraphError, Result};
 use crate::graph::NodeId;
-use crate::index::Index;
+use crate::index::{Index, IndexId};
 use sled::Db;
+use std::marker::PhantomData;
 use std::path::Path;
 
 /// Persistent B-tree index using Sled
 ///
 /// Provides O(log n) operations with disk persistence.
-/// Supports efficient range queries.
-pub struct BTreeIndex {
+/// Supports efficient range queries. Sled trees are lock-free, so
+/// concurrent writers do not block each other.
+pub struct BTreeIndex<Id: IndexId = NodeId> {
     /// Sled database instance
     db: Db,
-    /// Tree name for this index
-    tree_name: String,
+    /// Tree holding this index, opened once
+    tree: sled::Tree,
+    _id: PhantomData<Id>,
 }
 
-impl BTreeIndex {
+impl<Id: IndexId> BTreeIndex<Id> {
     /// Create a new B-tree index with persistence
     pub fn new(path: &Path, tree_name: &str) -> Result<Self> {
         let db = sled::open(path)
             .map_err(|e| DeepGraphError::StorageError(format!("Failed to open sled: {}", e)))?;
-        
-        Ok(Self {
-            db,
-            tree_name: tree_name.to_string(),
-        })
+        Self::with_tree(db, tree_name)
     }
     
     /// Create a new in-memory B-tree index (for testing)
@@ -39,42 +38,42 @@ impl BTreeIndex {
             .temporary(true)
             .open()
             .map_err(|e| DeepGraphError::StorageError(format!("Failed to open temp sled: {}", e)))?;
-        
+        Self::with_tree(db, "temp")
+    }
+    
+    fn with_tree(db: Db, tree_name: &str) -> Result<Self> {
+        let tree = db
+            .open_tree(tree_name)
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to open tree: {}", e)))?;
         Ok(Self {
             db,
-            tree_name: "temp".to_string(),
+            tree,
+            _id: PhantomData,
         })
     }
     
-    /// Get the tree for this index
-    fn tree(&self) -> Result<sled::Tree> {
-        self.db
-            .open_tree(&self.tree_name)
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to open tree: {}", e)))
-    }
-    
-    /// Encode a NodeId as bytes
-    fn encode_node_id(node_id: &NodeId) -> Vec<u8> {
-        node_id.as_uuid().as_bytes().to_vec()
+    /// Encode an ID as bytes
+    fn encode_id(id: &Id) -> Vec<u8> {
+        id.uuid().as_bytes().to_vec()
     }
     
-    /// Decode bytes to NodeId
-    fn decode_node_id(bytes: &[u8]) -> Result<NodeId> {
+    /// Decode bytes to an ID
+    fn decode_id(bytes: &[u8]) -> Result<Id> {
         let uuid = uuid::Uuid::from_slice(bytes)
             .map_err(|e| DeepGraphError::InvalidNodeId(e.to_string()))?;
-        Ok(NodeId::from_uuid(uuid))
+        Ok(Id::from_uuid(uuid))
     }
     
-    /// Create a composite key (index_key + node_id for uniqueness)
-    fn make_key(key: &[u8], node_id: &NodeId) -> Vec<u8> {
+    /// Create a composite key (index_key + id for uniqueness)
+    fn make_key(key: &[u8], id: &Id) -> Vec<u8> {
         let mut composite = key.to_vec();
-        composite.extend_from_slice(&Self::encode_node_id(node_id));
+        composite.extend_from_slice(&Self::encode_id(id));
         composite
     }
     
     /// Get statistics about the index
     pub fn stats(&self) -> Result<BTreeIndexStats> {
-        let tree = self.tree()?;
+        let tree = &self.tree;
         
         // Get approximate size from database
         let size_on_disk = self.db.size_on_disk()
@@ -95,9 +94,9 @@ impl BTreeIndex {
     }
 }
 
-impl Index for BTreeIndex {
-    fn insert(&mut self, key: Vec<u8>, value: NodeId) -> Result<()> {
-        let tree = self.tree()?;
+impl<Id: IndexId> Index<Id> for BTreeIndex<Id> {
+    fn insert(&self, key: Vec<u8>, value: Id) -> Result<()> {
+        let tree = &self.tree;
         let composite_key = Self::make_key(&key, &value);
         
         tree.insert(composite_key, &[])
@@ -106,8 +105,8 @@ impl Index for BTreeIndex {
         Ok(())
     }
     
-    fn remove(&mut self, key: &[u8], value: NodeId) -> Result<()> {
-        let tree = self.tree()?;
+    fn remove(&self, key: &[u8], value: Id) -> Result<()> {
+        let tree = &self.tree;
         let composite_key = Self::make_key(key, &value);
         
         tree.remove(composite_key)
@@ -116,8 +115,8 @@ impl Index for BTreeIndex {
         Ok(())
     }
     
-    fn lookup(&self, key: &[u8]) -> Result<Vec<NodeId>> {
-        let tree = self.tree()?;
+    fn lookup(&self, key: &[u8]) -> Result<Vec<Id>> {
+        let tree = &self.tree;
         let mut results = Vec::new();
         
         // Scan all keys with this prefix
@@ -128,7 +127,7 @@ impl Index for BTreeIndex {
             // Extract the node ID from the composite key
             if composite_key.len() > key.len() {
                 let node_id_bytes = &composite_key[key.len()..];
-                if let Ok(node_id) = Self::decode_node_id(node_id_bytes) {
+                if let Ok(node_id) = Self::decode_id(node_id_bytes) {
                     results.push(node_id);
                 }
             }
@@ -137,8 +136,8 @@ impl Index for BTreeIndex {
         Ok(results)
     }
     
-    fn range(&self, start: &[u8], end: &[u8]) -> Result<Vec<NodeId>> {
-        let tree = self.tree()?;
+    fn range(&self, start: &[u8], end: &[u8]) -> Result<Vec<Id>> {
+        let tree = &self.tree;
         let mut results = Vec::new();
         
         // Range scan from start to end
@@ -151,7 +150,7 @@ impl Index for BTreeIndex {
             if composite_key.len() >= 16 {
                 let node_id_start = composite_key.len() - 16;
                 let node_id_bytes = &composite_key[node_id_start..];
-                if let Ok(node_id) = Self::decode_node_id(node_id_bytes) {
+                if let Ok(node_id) = Self::decode_id(node_id_bytes) {
                     results.push(node_id);
                 }
             }
@@ -161,7 +160,7 @@ impl Index for BTreeIndex {
     }
     
     fn keys(&self) -> Result<Vec<Vec<u8>>> {
-        let tree = self.tree()?;
+        let tree = &self.tree;
         let mut keys = Vec::new();
         
         for item in tree.iter() {
@@ -173,17 +172,32 @@ impl Index for BTreeIndex {
         Ok(keys)
     }
     
-    fn clear(&mut self) -> Result<()> {
-        let tree = self.tree()?;
+    fn entries(&self) -> Result<Vec<(Vec<u8>, Id)>> {
+        let tree = &self.tree;
+        let mut entries = Vec::new();
+        
+        // Composite keys end with the 16-byte ID
+        for item in tree.iter() {
+            let (composite_key, _) = item
+                .map_err(|e| DeepGraphError::StorageError(e.to_string()))?;
+            if composite_key.len() >= 16 {
+                let (key, id_bytes) = composite_key.split_at(composite_key.len() - 16);
+                entries.push((key.to_vec(), Self::decode_id(id_bytes)?));
+            }
+        }
+        
+        Ok(entries)
+    }
+    
+    fn clear(&self) -> Result<()> {
+        let tree = &self.tree;
         tree.clear()
             .map_err(|e| DeepGraphError::StorageError(format!("Failed to clear: {}", e)))?;
         Ok(())
     }
     
     fn len(&self) -> usize {
-        self.tree()
-            .map(|t| t.len())
-            .unwrap_or(0)
+        self.tree.len()
     }
 }
 
@@ -202,7 +216,7 @@ mod tests {
 
     #[test]
     fn test_btree_index_insert_lookup() {
-        let mut index = BTreeIndex::new_temp().unwrap();
+        let index = BTreeIndex::new_temp().unwrap();
         let node_id = NodeId::new();
         
         index.insert(b"key1".to_vec(), node_id).unwrap();
@@ -214,7 +228,7 @@ mod tests {
 
     #[test]
     fn test_btree_index_range_query() {
-        let mut index = BTreeIndex::new_temp().unwrap();
+        let index = BTreeIndex::new_temp().unwrap();
         
         // Insert values with integer keys
         let node1 = NodeId::new();
@@ -237,7 +251,7 @@ mod tests {
 
     #[test]
     fn test_btree_index_remove() {
-        let mut index = BTreeIndex::new_temp().unwrap();
+        let index = BTreeIndex::new_temp().unwrap();
         let node_id = NodeId::new();
         
         index.insert(b"key1".to_vec(), node_id).unwrap();
@@ -249,7 +263,7 @@ mod tests {
 
     #[test]
     fn test_btree_index_stats() {
-        let mut index = BTreeIndex::new_temp().unwrap();
+        let index = BTreeIndex::new_temp().unwrap();
         
         index.insert(b"key1".to_vec(), NodeId::new()).unwrap();
         index.insert(b"key2".to_vec(), NodeId::new()).unwrap();
@@ -260,7 +274,7 @@ mod tests {
 
     #[test]
     fn test_btree_index_clear() {
-        let mut index = BTreeIndex::new_temp().unwrap();
+        let index = BTreeIndex::new_temp().unwrap();
         
         index.insert(b"key1".to_vec(), NodeId::new()).unwrap();
         assert_eq!(index.len(), 1);
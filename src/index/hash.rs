@@ -3,30 +3,267 @@
 //! Efficient for queries like:
 //! - MATCH (n:Person {name: "Alice"})
 //! - WHERE n.age = 30
+//!
+//! The index lives in memory; [`HashIndex::save`] and [`HashIndex::load`]
+//! write it to and read it from a file so it survives restarts.
+//!
+//! With [`IndexLimits`] the index behaves like a cache: least recently used
+//! entries are evicted beyond an entry count or memory budget, and entries
+//! past their TTL are dropped.
 
-use crate::error::Result;
+use crate::error::{DeepGraphError, Result};
 use crate::graph::NodeId;
-use crate::index::Index;
+use crate::index::{Index, IndexId};
+use crate::metrics::{self, OperatorEvent};
 use dashmap::DashMap;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Bounds that turn a hash index into a cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexLimits {
+    /// Maximum number of (key, ID) entries
+    pub max_entries: Option<usize>,
+    /// Approximate memory budget: key bytes plus ID size per entry
+    pub max_memory_bytes: Option<usize>,
+    /// Milliseconds an entry stays valid after it was inserted
+    pub entry_ttl_ms: Option<u64>,
+}
+
+impl IndexLimits {
+    /// Whether no bound is set
+    pub fn is_unbounded(&self) -> bool {
+        self.max_entries.is_none() && self.max_memory_bytes.is_none() && self.entry_ttl_ms.is_none()
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// LRU and TTL bookkeeping of a bounded index
+struct Eviction<Id> {
+    limits: IndexLimits,
+    /// Advances on every insert and lookup
+    clock: u64,
+    /// Last use -> entry, least recently used first
+    order: BTreeMap<u64, (Vec<u8>, Id)>,
+    /// Entry -> (last use, insertion time in Unix ms)
+    entries: HashMap<(Vec<u8>, Id), (u64, i64)>,
+    /// Approximate bytes held
+    bytes: usize,
+    evicted: u64,
+    expired: u64,
+}
+
+impl<Id: IndexId> Eviction<Id> {
+    fn new(limits: IndexLimits) -> Self {
+        Self {
+            limits,
+            clock: 0,
+            order: BTreeMap::new(),
+            entries: HashMap::new(),
+            bytes: 0,
+            evicted: 0,
+            expired: 0,
+        }
+    }
+    
+    fn entry_bytes(key: &[u8]) -> usize {
+        key.len() + std::mem::size_of::<Id>()
+    }
+    
+    /// Track an inserted entry; returns false if it was already present
+    fn record(&mut self, key: &[u8], id: Id, now_ms: i64) -> bool {
+        if self.entries.contains_key(&(key.to_vec(), id)) {
+            self.touch(key, id);
+            return false;
+        }
+        self.clock += 1;
+        self.entries.insert((key.to_vec(), id), (self.clock, now_ms));
+        self.order.insert(self.clock, (key.to_vec(), id));
+        self.bytes += Self::entry_bytes(key);
+        true
+    }
+    
+    /// Mark an entry as just used
+    fn touch(&mut self, key: &[u8], id: Id) {
+        let Some((last_used, _)) = self.entries.get_mut(&(key.to_vec(), id)) else {
+            return;
+        };
+        self.clock += 1;
+        if let Some(entry) = self.order.remove(last_used) {
+            self.order.insert(self.clock, entry);
+        }
+        *last_used = self.clock;
+    }
+    
+    fn forget(&mut self, key: &[u8], id: Id) {
+        if let Some((last_used, _)) = self.entries.remove(&(key.to_vec(), id)) {
+            self.order.remove(&last_used);
+            self.bytes -= Self::entry_bytes(key);
+        }
+    }
+    
+    fn over_limit(&self) -> bool {
+        self.limits.max_entries.is_some_and(|max| self.entries.len() > max)
+            || self.limits.max_memory_bytes.is_some_and(|max| self.bytes > max)
+    }
+    
+    /// Untrack least recently used entries until within bounds and return them
+    fn evict(&mut self) -> Vec<(Vec<u8>, Id)> {
+        let mut victims = Vec::new();
+        while self.over_limit() {
+            let Some((_, (key, id))) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&(key.clone(), id));
+            self.bytes -= Self::entry_bytes(&key);
+            victims.push((key, id));
+        }
+        self.evicted += victims.len() as u64;
+        victims
+    }
+    
+    fn is_expired(&self, key: &[u8], id: Id, now_ms: i64) -> bool {
+        match (self.limits.entry_ttl_ms, self.entries.get(&(key.to_vec(), id))) {
+            (Some(ttl), Some((_, inserted))) => now_ms.saturating_sub(*inserted) >= ttl as i64,
+            _ => false,
+        }
+    }
+    
+    /// Untrack entries past their TTL and return them
+    fn expire(&mut self, now_ms: i64) -> Vec<(Vec<u8>, Id)> {
+        let Some(ttl) = self.limits.entry_ttl_ms else {
+            return Vec::new();
+        };
+        let expired: Vec<(Vec<u8>, Id)> = self
+            .entries
+            .iter()
+            .filter(|(_, (_, inserted))| now_ms.saturating_sub(*inserted) >= ttl as i64)
+            .map(|(entry, _)| entry.clone())
+            .collect();
+        for (key, id) in &expired {
+            self.forget(key, *id);
+        }
+        self.expired += expired.len() as u64;
+        expired
+    }
+    
+    fn clear(&mut self) {
+        self.order.clear();
+        self.entries.clear();
+        self.bytes = 0;
+    }
+}
 
 /// In-memory hash index using DashMap
 ///
-/// Provides O(1) lookup time for exact matches.
-/// Thread-safe and lock-free for reads.
-pub struct HashIndex {
-    /// Map from key to list of node IDs
-    data: Arc<DashMap<Vec<u8>, Vec<NodeId>>>,
+/// Provides O(1) lookup time for exact matches. Writers only lock the
+/// DashMap shard of their key, so parallel inserts scale; a bounded index
+/// additionally serializes writes through its LRU tracker.
+pub struct HashIndex<Id: IndexId = NodeId> {
+    /// Map from key to list of node (or edge) IDs
+    data: Arc<DashMap<Vec<u8>, Vec<Id>>>,
+    /// LRU/TTL state when the index is bounded
+    eviction: Option<Mutex<Eviction<Id>>>,
 }
 
-impl HashIndex {
+impl<Id: IndexId> HashIndex<Id> {
     /// Create a new hash index
     pub fn new() -> Self {
         Self {
             data: Arc::new(DashMap::new()),
+            eviction: None,
+        }
+    }
+    
+    /// Bound the index
+    ///
+    /// Entries already present count as inserted now; any beyond the
+    /// bounds are evicted right away.
+    pub fn with_limits(mut self, limits: IndexLimits) -> Self {
+        if limits.is_unbounded() {
+            self.eviction = None;
+            return self;
+        }
+        let mut eviction = Eviction::new(limits);
+        let now = now_ms();
+        for entry in self.data.iter() {
+            for id in entry.value() {
+                eviction.record(entry.key(), *id, now);
+            }
+        }
+        let victims = eviction.evict();
+        self.drop_entries(&victims, OperatorEvent::Evicted);
+        self.eviction = Some(Mutex::new(eviction));
+        self
+    }
+    
+    /// Bounds of the index (all `None` if unbounded)
+    pub fn limits(&self) -> IndexLimits {
+        self.eviction
+            .as_ref()
+            .map(|eviction| eviction.lock().unwrap().limits)
+            .unwrap_or_default()
+    }
+    
+    /// Entries evicted to stay within the bounds so far
+    pub fn evicted(&self) -> u64 {
+        self.eviction.as_ref().map_or(0, |eviction| eviction.lock().unwrap().evicted)
+    }
+    
+    /// Entries dropped after their TTL so far
+    pub fn expired(&self) -> u64 {
+        self.eviction.as_ref().map_or(0, |eviction| eviction.lock().unwrap().expired)
+    }
+    
+    /// Drop every entry past its TTL; returns how many were dropped
+    ///
+    /// Expired entries are also dropped lazily when looked up.
+    pub fn expire(&self) -> usize {
+        let Some(eviction) = &self.eviction else {
+            return 0;
+        };
+        let mut eviction = eviction.lock().unwrap();
+        let expired = eviction.expire(now_ms());
+        self.drop_entries(&expired, OperatorEvent::Expired);
+        expired.len()
+    }
+    
+    fn push(&self, key: Vec<u8>, value: Id) {
+        let capacity = self.data.capacity();
+        self.data
+            .entry(key)
+            .or_insert_with(Vec::new)
+            .push(value);
+        if self.data.capacity() > capacity {
+            metrics::global().record(OperatorEvent::HashResize, "HashIndex");
         }
     }
     
+    fn remove_from_data(&self, key: &[u8], value: Id) {
+        if let Some(mut entry) = self.data.get_mut(key) {
+            entry.retain(|&id| id != value);
+            if entry.is_empty() {
+                drop(entry);
+                self.data.remove(key);
+            }
+        }
+    }
+    
+    /// Remove untracked entries from the map and count them
+    fn drop_entries(&self, entries: &[(Vec<u8>, Id)], event: OperatorEvent) {
+        for (key, id) in entries {
+            self.remove_from_data(key, *id);
+        }
+        metrics::global().record_n(event, "HashIndex", entries.len() as u64);
+    }
+    
     /// Get statistics about the index
     pub fn stats(&self) -> HashIndexStats {
         let total_keys = self.data.len();
@@ -50,42 +287,96 @@ impl HashIndex {
             },
         }
     }
+    
+    /// Write the index to `path` (to a temp file first, then renamed)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let entries: Vec<(Vec<u8>, Vec<Uuid>)> = self.data
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().iter().map(|id| id.uuid()).collect()))
+            .collect();
+        let bytes = bincode::serialize(&entries)
+            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to serialize hash index: {}", e)))?;
+        
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+    
+    /// Read an index written by [`HashIndex::save`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        let entries: Vec<(Vec<u8>, Vec<Uuid>)> = bincode::deserialize(&bytes)
+            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to deserialize hash index: {}", e)))?;
+        
+        let data = DashMap::with_capacity(entries.len());
+        for (key, ids) in entries {
+            data.insert(key, ids.into_iter().map(Id::from_uuid).collect());
+        }
+        Ok(Self {
+            data: Arc::new(data),
+            eviction: None,
+        })
+    }
 }
 
-impl Default for HashIndex {
+impl<Id: IndexId> Default for HashIndex<Id> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl Index for HashIndex {
-    fn insert(&mut self, key: Vec<u8>, value: NodeId) -> Result<()> {
-        self.data
-            .entry(key)
-            .or_insert_with(Vec::new)
-            .push(value);
+impl<Id: IndexId> Index<Id> for HashIndex<Id> {
+    fn insert(&self, key: Vec<u8>, value: Id) -> Result<()> {
+        let Some(eviction) = &self.eviction else {
+            self.push(key, value);
+            return Ok(());
+        };
+        let mut eviction = eviction.lock().unwrap();
+        if eviction.record(&key, value, now_ms()) {
+            self.push(key, value);
+        }
+        let victims = eviction.evict();
+        self.drop_entries(&victims, OperatorEvent::Evicted);
         Ok(())
     }
     
-    fn remove(&mut self, key: &[u8], value: NodeId) -> Result<()> {
-        if let Some(mut entry) = self.data.get_mut(key) {
-            entry.retain(|&id| id != value);
-            if entry.is_empty() {
-                drop(entry);
-                self.data.remove(key);
-            }
+    fn remove(&self, key: &[u8], value: Id) -> Result<()> {
+        self.remove_from_data(key, value);
+        if let Some(eviction) = &self.eviction {
+            eviction.lock().unwrap().forget(key, value);
         }
         Ok(())
     }
     
-    fn lookup(&self, key: &[u8]) -> Result<Vec<NodeId>> {
-        Ok(self.data
+    fn lookup(&self, key: &[u8]) -> Result<Vec<Id>> {
+        let ids = self.data
             .get(key)
             .map(|entry| entry.value().clone())
-            .unwrap_or_default())
+            .unwrap_or_default();
+        let Some(eviction) = &self.eviction else {
+            return Ok(ids);
+        };
+        
+        let mut eviction = eviction.lock().unwrap();
+        let now = now_ms();
+        let mut live = Vec::with_capacity(ids.len());
+        let mut expired = Vec::new();
+        for id in ids {
+            if eviction.is_expired(key, id, now) {
+                eviction.forget(key, id);
+                expired.push((key.to_vec(), id));
+            } else {
+                eviction.touch(key, id);
+                live.push(id);
+            }
+        }
+        eviction.expired += expired.len() as u64;
+        self.drop_entries(&expired, OperatorEvent::Expired);
+        Ok(live)
     }
     
-    fn range(&self, _start: &[u8], _end: &[u8]) -> Result<Vec<NodeId>> {
+    fn range(&self, _start: &[u8], _end: &[u8]) -> Result<Vec<Id>> {
         // Hash indices don't support range queries efficiently
         Ok(Vec::new())
     }
@@ -97,8 +388,11 @@ impl Index for HashIndex {
             .collect())
     }
     
-    fn clear(&mut self) -> Result<()> {
+    fn clear(&self) -> Result<()> {
         self.data.clear();
+        if let Some(eviction) = &self.eviction {
+            eviction.lock().unwrap().clear();
+        }
         Ok(())
     }
     
@@ -126,7 +420,7 @@ mod tests {
 
     #[test]
     fn test_hash_index_insert_lookup() {
-        let mut index = HashIndex::new();
+        let index = HashIndex::new();
         let node_id = NodeId::new();
         
         index.insert(b"key1".to_vec(), node_id).unwrap();
@@ -138,7 +432,7 @@ mod tests {
 
     #[test]
     fn test_hash_index_multiple_values() {
-        let mut index = HashIndex::new();
+        let index = HashIndex::new();
         let node1 = NodeId::new();
         let node2 = NodeId::new();
         
@@ -153,7 +447,7 @@ mod tests {
 
     #[test]
     fn test_hash_index_remove() {
-        let mut index = HashIndex::new();
+        let index = HashIndex::new();
         let node_id = NodeId::new();
         
         index.insert(b"key1".to_vec(), node_id).unwrap();
@@ -165,7 +459,7 @@ mod tests {
 
     #[test]
     fn test_hash_index_stats() {
-        let mut index = HashIndex::new();
+        let index = HashIndex::new();
         let node1 = NodeId::new();
         let node2 = NodeId::new();
         
@@ -179,9 +473,74 @@ mod tests {
         assert_eq!(stats.max_values_per_key, 2);
     }
 
+    #[test]
+    fn test_hash_index_save_load() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("age.hash");
+        let index = HashIndex::new();
+        let node_id = NodeId::new();
+        index.insert(b"key1".to_vec(), node_id).unwrap();
+        index.save(&path).unwrap();
+        
+        let loaded: HashIndex = HashIndex::load(&path).unwrap();
+        assert_eq!(loaded.lookup(b"key1").unwrap(), vec![node_id]);
+        assert_eq!(loaded.len(), 1);
+    }
+
+    #[test]
+    fn test_hash_index_concurrent_inserts() {
+        let index = Arc::new(HashIndex::new());
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let index = Arc::clone(&index);
+                std::thread::spawn(move || {
+                    for i in 0..250 {
+                        index.insert(format!("key{}", (t * 250 + i) % 100).into_bytes(), NodeId::new()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        
+        assert_eq!(index.len(), 100);
+        assert_eq!(index.stats().total_values, 1000);
+    }
+
+    #[test]
+    fn test_hash_index_limits() {
+        let index = HashIndex::new().with_limits(IndexLimits {
+            max_entries: Some(2),
+            ..Default::default()
+        });
+        let (a, b, c) = (NodeId::new(), NodeId::new(), NodeId::new());
+        index.insert(b"a".to_vec(), a).unwrap();
+        index.insert(b"b".to_vec(), b).unwrap();
+        // Using "a" makes "b" the least recently used entry
+        assert_eq!(index.lookup(b"a").unwrap(), vec![a]);
+        index.insert(b"c".to_vec(), c).unwrap();
+        
+        assert!(index.lookup(b"b").unwrap().is_empty());
+        assert_eq!(index.lookup(b"a").unwrap(), vec![a]);
+        assert_eq!(index.lookup(b"c").unwrap(), vec![c]);
+        assert_eq!(index.evicted(), 1);
+        
+        let expiring = HashIndex::new().with_limits(IndexLimits {
+            entry_ttl_ms: Some(0),
+            ..Default::default()
+        });
+        expiring.insert(b"a".to_vec(), a).unwrap();
+        expiring.insert(b"b".to_vec(), b).unwrap();
+        assert!(expiring.lookup(b"a").unwrap().is_empty());
+        assert_eq!(expiring.expire(), 1);
+        assert_eq!(expiring.len(), 0);
+        assert_eq!(expiring.expired(), 2);
+    }
+
     #[test]
     fn test_hash_index_clear() {
-        let mut index = HashIndex::new();
+        let index = HashIndex::new();
         index.insert(b"key1".to_vec(), NodeId::new()).unwrap();
         
         assert_eq!(index.len(), 1);
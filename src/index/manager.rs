@@ -1,16 +1,61 @@
 //! Index manager for coordinating multiple indices
 //!
-//! Manages all indices and provides query optimization hints
+//! Manages all indices and provides query optimization hints. Writes made
+//! through [`IndexManager::add_node`], [`IndexManager::update_node`] and
+//! [`IndexManager::delete_node`] keep the indexes in step with storage and
+//! enforce unique indexes. Edge indexes cover relationship types and edge
+//! properties and are maintained by [`IndexManager::add_edge`],
+//! [`IndexManager::update_edge`] and [`IndexManager::delete_edge`].
+//!
+//! With [`IndexManager::with_persistence`] the hash and B-tree indexes are
+//! recorded in a manifest in the base directory and reopened on startup.
+//! Hash indexes are saved when created, on [`IndexManager::flush`] and when
+//! the manager is dropped; vector indexes stay in memory.
+//!
+//! [`IndexManager::verify`] compares an index against storage and
+//! [`IndexManager::rebuild`] repopulates it by scanning storage, for use
+//! after bulk imports that bypassed the manager or suspected corruption.
+//!
+//! A property index can be scoped to a label ([`IndexConfig::for_label`]),
+//! holding only nodes that carry the label; unique scoped indexes enforce
+//! uniqueness within the label.
+//!
+//! Every lookup is counted per index; [`IndexManager::list_indices`] reports
+//! the counts, hit ratios and last-use times alongside each index.
+//!
+//! Writes to an index run concurrently, e.g. from parallel importers; only
+//! [`IndexManager::rebuild`] shuts other writers out of the index it
+//! refills.
+//!
+//! Hash indexes used as caches can be bounded by entry count, memory or
+//! entry TTL through [`IndexConfig::limits`]; see [`IndexLimits`].
 
 use crate::error::{DeepGraphError, Result};
-use crate::graph::{NodeId, PropertyValue};
-use crate::index::{property_to_bytes, BTreeIndex, HashIndex, Index};
-use dashmap::DashMap;
-use std::path::PathBuf;
-use std::sync::RwLock;
+use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
+use crate::index::vector::{vector_from_property, DistanceMetric, VectorIndex, VectorMatch};
+use crate::index::{property_to_bytes, BTreeIndex, HashIndex, Index, IndexId, IndexLimits};
+use crate::storage::StorageBackend;
+use dashmap::{DashMap, DashSet};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+/// File in the base directory listing the registered indexes
+const MANIFEST_FILE: &str = "indexes.json";
+
+/// Where a hash index is saved
+fn hash_index_path(base_dir: &Path, name: &str) -> PathBuf {
+    base_dir.join(format!("{}.hash", name))
+}
 
 /// Type of index
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum IndexType {
     /// Hash index for equality lookups
     Hash,
@@ -19,7 +64,7 @@ pub enum IndexType {
 }
 
 /// Configuration for creating an index
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexConfig {
     /// Name of the index
     pub name: String,
@@ -29,6 +74,20 @@ pub struct IndexConfig {
     pub property_key: Option<String>,
     /// Whether this is a label index
     pub is_label_index: bool,
+    /// Whether at most one node may hold each value (property indexes only)
+    #[serde(default)]
+    pub unique: bool,
+    /// Whether the index covers edges (relationship types or edge
+    /// properties) rather than nodes
+    #[serde(default)]
+    pub on_edges: bool,
+    /// Label a property index is restricted to
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Size and TTL bounds (hash indexes only); evicted entries are simply
+    /// missing from lookups
+    #[serde(default)]
+    pub limits: IndexLimits,
 }
 
 impl IndexConfig {
@@ -39,6 +98,10 @@ impl IndexConfig {
             index_type,
             property_key: None,
             is_label_index: true,
+            unique: false,
+            on_edges: false,
+            label: None,
+            limits: IndexLimits::default(),
         }
     }
     
@@ -49,14 +112,415 @@ impl IndexConfig {
             index_type,
             property_key: Some(property_key),
             is_label_index: false,
+            unique: false,
+            on_edges: false,
+            label: None,
+            limits: IndexLimits::default(),
+        }
+    }
+    
+    /// Create a relationship type index configuration
+    ///
+    /// Like label indexes, the index name is the relationship type it covers.
+    pub fn relationship_type_index(name: String, index_type: IndexType) -> Self {
+        Self {
+            on_edges: true,
+            ..Self::label_index(name, index_type)
+        }
+    }
+    
+    /// Create an edge property index configuration
+    pub fn edge_property_index(name: String, index_type: IndexType, property_key: String) -> Self {
+        Self {
+            on_edges: true,
+            ..Self::property_index(name, index_type, property_key)
+        }
+    }
+    
+    /// Reject writes that would give two nodes the same value
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+    
+    /// Only index nodes carrying `label` (property indexes only)
+    pub fn for_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+    
+    /// Evict least recently used entries beyond `max` entries
+    pub fn with_max_entries(mut self, max: usize) -> Self {
+        self.limits.max_entries = Some(max);
+        self
+    }
+    
+    /// Evict least recently used entries beyond roughly `bytes` of keys and IDs
+    pub fn with_max_memory(mut self, bytes: usize) -> Self {
+        self.limits.max_memory_bytes = Some(bytes);
+        self
+    }
+    
+    /// Drop entries `ttl` after they were inserted
+    pub fn with_entry_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.limits.entry_ttl_ms = Some(ttl.as_millis() as u64);
+        self
+    }
+}
+
+/// How often an index has been used
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IndexUsage {
+    /// Lookups, range scans and vector searches served by the index
+    pub lookups: u64,
+    /// Lookups that found at least one entry
+    pub hits: u64,
+    /// When the index was last used, in Unix milliseconds
+    pub last_used_ms: Option<i64>,
+}
+
+impl IndexUsage {
+    /// Fraction of lookups that found something (0 if never used)
+    pub fn hit_ratio(&self) -> f64 {
+        if self.lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.lookups as f64
+        }
+    }
+}
+
+/// Live counters behind [`IndexUsage`]
+#[derive(Default)]
+struct UsageCounters {
+    lookups: AtomicU64,
+    hits: AtomicU64,
+    /// 0 until first use
+    last_used_ms: AtomicI64,
+}
+
+impl UsageCounters {
+    fn snapshot(&self) -> IndexUsage {
+        let last_used_ms = self.last_used_ms.load(Ordering::Relaxed);
+        IndexUsage {
+            lookups: self.lookups.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            last_used_ms: (last_used_ms != 0).then_some(last_used_ms),
+        }
+    }
+}
+
+/// An index as reported by [`IndexManager::list_indices`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexInfo {
+    /// Name of the index
+    pub name: String,
+    /// Hash or B-tree; `None` for vector indexes
+    pub index_type: Option<IndexType>,
+    /// Indexed property, or `None` for label and relationship type indexes
+    pub property_key: Option<String>,
+    /// Label a property index is restricted to
+    pub label: Option<String>,
+    /// Whether the index covers edges
+    pub on_edges: bool,
+    /// Whether the index is unique
+    pub unique: bool,
+    /// Size reported by the index (keys for hash, entries for B-tree,
+    /// vectors for vector indexes)
+    pub size: usize,
+    /// Lookup statistics
+    pub usage: IndexUsage,
+    /// Entries evicted to stay within the index's size limits
+    pub evicted: u64,
+    /// Entries dropped after their TTL
+    pub expired: u64,
+    /// Size and TTL bounds; a bounded index may miss matching entries
+    pub limits: IndexLimits,
+}
+
+/// Differences between an index and the storage it covers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexVerification {
+    /// Name of the verified index
+    pub index_name: String,
+    /// Number of entries storage says the index should hold
+    pub expected: usize,
+    /// Entries missing from the index, as (key, node or edge UUID)
+    pub missing: Vec<(Vec<u8>, Uuid)>,
+    /// Entries in the index that storage does not back
+    pub extra: Vec<(Vec<u8>, Uuid)>,
+}
+
+impl IndexVerification {
+    /// Whether the index matches storage exactly
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+/// Entries an index should hold according to storage
+fn expected_entries(config: &IndexConfig, storage: &dyn StorageBackend) -> Vec<(Vec<u8>, Uuid)> {
+    match (&config.property_key, config.on_edges) {
+        (Some(key), false) => {
+            let nodes: Box<dyn Iterator<Item = Node> + '_> = match &config.label {
+                Some(label) => Box::new(storage.get_nodes_by_label(label).into_iter()),
+                None => storage.iter_nodes(),
+            };
+            nodes
+                .filter_map(|node| node.get_property(key).map(|value| (property_to_bytes(value), *node.id().as_uuid())))
+                .collect()
+        }
+        (Some(key), true) => storage
+            .iter_edges()
+            .filter_map(|edge| edge.get_property(key).map(|value| (property_to_bytes(value), *edge.id().as_uuid())))
+            .collect(),
+        (None, false) => storage
+            .get_nodes_by_label(&config.name)
+            .iter()
+            .map(|node| (config.name.as_bytes().to_vec(), *node.id().as_uuid()))
+            .collect(),
+        (None, true) => storage
+            .get_edges_by_type(&config.name)
+            .iter()
+            .map(|edge| (config.name.as_bytes().to_vec(), *edge.id().as_uuid()))
+            .collect(),
+    }
+}
+
+/// One index entry a node contributes
+#[derive(Clone, Copy)]
+enum Entry<'a> {
+    Label(&'a str),
+    Property(&'a str, &'a PropertyValue),
+    /// A property under one of the node's labels, for label-scoped indexes
+    LabelProperty(&'a str, &'a str, &'a PropertyValue),
+}
+
+/// Entries of `node` that `other` does not have
+///
+/// Label/property pairs are only listed when `scoped` is set, i.e. when
+/// some label-scoped index exists.
+/// Whether `node` contributes `entry`
+fn has_entry(node: &Node, entry: &Entry<'_>) -> bool {
+    match *entry {
+        Entry::Label(label) => node.has_label(label),
+        Entry::Property(key, value) => node.get_property(key) == Some(value),
+        Entry::LabelProperty(label, key, value) => node.has_label(label) && node.get_property(key) == Some(value),
+    }
+}
+
+fn entries_not_in<'a>(node: &'a Node, other: Option<&Node>, scoped: bool) -> Vec<Entry<'a>> {
+    let mut entries: Vec<Entry<'a>> = node
+        .labels()
+        .iter()
+        .filter(|label| !other.is_some_and(|other| other.has_label(label)))
+        .map(|label| Entry::Label(label))
+        .collect();
+    entries.extend(
+        node.properties()
+            .iter()
+            .filter(|(key, value)| other.and_then(|other| other.get_property(key)) != Some(*value))
+            .map(|(key, value)| Entry::Property(key, value)),
+    );
+    if scoped {
+        for label in node.labels() {
+            for (key, value) in node.properties() {
+                let unchanged = other.is_some_and(|other| {
+                    other.has_label(label) && other.get_property(key) == Some(value)
+                });
+                if !unchanged {
+                    entries.push(Entry::LabelProperty(label, key, value));
+                }
+            }
         }
     }
+    entries
+}
+
+/// Lock stripes unique claims are spread over
+const CLAIM_STRIPES: usize = 64;
+
+/// An index of either type plus the locks the manager layers on top
+///
+/// Hash and B-tree indexes synchronize internally, so writers to the same
+/// index run in parallel. `gate` is only taken exclusively while the index
+/// is refilled; unique claims also lock the stripe their key hashes to,
+/// keeping check-then-insert atomic without serializing other keys.
+struct IndexImpl<Id: IndexId = NodeId> {
+    kind: IndexKind<Id>,
+    gate: RwLock<()>,
+    stripes: Vec<Mutex<()>>,
 }
 
-/// Wrapper for different index types
-enum IndexImpl {
-    Hash(RwLock<HashIndex>),
-    BTree(RwLock<BTreeIndex>),
+enum IndexKind<Id: IndexId> {
+    Hash(HashIndex<Id>),
+    BTree(BTreeIndex<Id>),
+}
+
+impl<Id: IndexId> IndexImpl<Id> {
+    fn new(kind: IndexKind<Id>) -> Self {
+        Self {
+            kind,
+            gate: RwLock::new(()),
+            stripes: (0..CLAIM_STRIPES).map(|_| Mutex::new(())).collect(),
+        }
+    }
+    
+    /// Open an index, persisted under `base_dir` if given
+    ///
+    /// A hash index saved there earlier is loaded; otherwise it starts empty.
+    fn create(config: &IndexConfig, base_dir: Option<&Path>) -> Result<Self> {
+        let kind = match config.index_type {
+            IndexType::Hash => {
+                let hash = match base_dir.map(|dir| hash_index_path(dir, &config.name)) {
+                    Some(path) if path.exists() => HashIndex::load(&path)?,
+                    _ => HashIndex::new(),
+                };
+                IndexKind::Hash(hash.with_limits(config.limits))
+            }
+            IndexType::BTree if !config.limits.is_unbounded() => {
+                return Err(DeepGraphError::InvalidOperation(format!(
+                    "Size and TTL limits are only supported on hash indexes ({})",
+                    config.name
+                )));
+            }
+            IndexType::BTree => {
+                let btree = match base_dir {
+                    Some(base_dir) => BTreeIndex::new(&base_dir.join(&config.name), &config.name)?,
+                    None => BTreeIndex::new_temp()?,
+                };
+                IndexKind::BTree(btree)
+            }
+        };
+        Ok(Self::new(kind))
+    }
+    
+    fn index(&self) -> &dyn Index<Id> {
+        match &self.kind {
+            IndexKind::Hash(index) => index,
+            IndexKind::BTree(index) => index,
+        }
+    }
+    
+    /// Save a hash index to its file, or flush a B-tree index
+    fn save(&self, base_dir: &Path, name: &str) -> Result<()> {
+        let _gate = self.gate.read().unwrap();
+        match &self.kind {
+            IndexKind::Hash(index) => index.save(&hash_index_path(base_dir, name)),
+            IndexKind::BTree(index) => index.flush(),
+        }
+    }
+    
+    fn insert(&self, key: Vec<u8>, id: Id) -> Result<()> {
+        let _gate = self.gate.read().unwrap();
+        self.index().insert(key, id)
+    }
+    
+    fn lookup(&self, key: &[u8]) -> Result<Vec<Id>> {
+        let _gate = self.gate.read().unwrap();
+        self.index().lookup(key)
+    }
+    
+    fn remove(&self, key: &[u8], id: Id) -> Result<()> {
+        let _gate = self.gate.read().unwrap();
+        self.index().remove(key, id)
+    }
+    
+    /// All entries, with IDs as UUIDs
+    fn uuid_entries(&self) -> Result<Vec<(Vec<u8>, Uuid)>> {
+        let _gate = self.gate.read().unwrap();
+        let entries = self.index().entries()?;
+        Ok(entries.into_iter().map(|(key, id)| (key, id.uuid())).collect())
+    }
+    
+    /// Replace the contents of the index, shutting out other writers
+    fn refill(&self, entries: &[(Vec<u8>, Uuid)]) -> Result<()> {
+        let _gate = self.gate.write().unwrap();
+        let index = self.index();
+        index.clear()?;
+        for (key, id) in entries {
+            index.insert(key.clone(), Id::from_uuid(*id))?;
+        }
+        Ok(())
+    }
+    
+    fn len(&self) -> usize {
+        self.index().len()
+    }
+    
+    /// (evicted, expired) entry counts; always zero for B-trees
+    fn dropped(&self) -> (u64, u64) {
+        match &self.kind {
+            IndexKind::Hash(index) => (index.evicted(), index.expired()),
+            IndexKind::BTree(_) => (0, 0),
+        }
+    }
+    
+    /// Drop expired entries of a hash index
+    fn expire(&self) -> usize {
+        match &self.kind {
+            IndexKind::Hash(index) => index.expire(),
+            IndexKind::BTree(_) => 0,
+        }
+    }
+    
+    fn range(&self, start: &[u8], end: &[u8]) -> Result<Vec<Id>> {
+        match &self.kind {
+            IndexKind::BTree(index) => {
+                let _gate = self.gate.read().unwrap();
+                index.range(start, end)
+            }
+            IndexKind::Hash(_) => Err(DeepGraphError::StorageError(
+                "Range queries not supported on hash indices".to_string()
+            )),
+        }
+    }
+}
+
+impl IndexImpl {
+    /// Insert an entry, first checking uniqueness under the key's stripe lock
+    ///
+    /// Returns the number of stale holders removed, or `None` if the node
+    /// already held the key and nothing was inserted.
+    fn insert_checked(&self, key: Vec<u8>, node_id: NodeId, unique: Option<&Uniqueness<'_>>) -> Result<Option<u64>> {
+        let _gate = self.gate.read().unwrap();
+        let index = self.index();
+        let Some(unique) = unique else {
+            index.insert(key, node_id)?;
+            return Ok(Some(0));
+        };
+        
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key.hash(&mut hasher);
+        let _stripe = self.stripes[hasher.finish() as usize % CLAIM_STRIPES].lock().unwrap();
+        
+        let mut repaired = 0;
+        for holder in index.lookup(&key)? {
+            if holder == node_id {
+                return Ok(None);
+            }
+            if (unique.is_live)(holder) {
+                return Err(DeepGraphError::ConstraintViolation(format!(
+                    "Node {} already has {} = {:?} (unique index {})",
+                    holder, unique.property, unique.value, unique.index
+                )));
+            }
+            warn!("Unique index '{}' references missing node {}, removing stale entry", unique.index, holder);
+            index.remove(&key, holder)?;
+            repaired += 1;
+        }
+        index.insert(key, node_id)?;
+        Ok(Some(repaired))
+    }
+}
+
+/// What a unique index insert checks against
+struct Uniqueness<'a> {
+    index: &'a str,
+    property: &'a str,
+    value: &'a PropertyValue,
+    /// Whether a node holding the value still exists
+    is_live: &'a dyn Fn(NodeId) -> bool,
 }
 
 /// Index manager
@@ -67,8 +531,32 @@ pub struct IndexManager {
     label_indices: DashMap<String, String>,
     /// Property indices (property key -> index name)
     property_indices: DashMap<String, String>,
+    /// Label-scoped property indices ((label, property key) -> index name)
+    scoped_property_indices: DashMap<(String, String), String>,
+    /// Names of unique indices
+    unique_indices: DashSet<String>,
+    /// Vector indices by name
+    vector_indices: DashMap<String, RwLock<VectorIndex>>,
+    /// Property key -> vector index name
+    vector_properties: DashMap<String, String>,
+    /// Edge indices by name
+    edge_indices: DashMap<String, IndexImpl<EdgeId>>,
+    /// Relationship type indices (type -> index name)
+    edge_type_indices: DashMap<String, String>,
+    /// Edge property indices (property key -> index name)
+    edge_property_indices: DashMap<String, String>,
+    /// Configurations of hash and B-tree indices, as written to the manifest
+    configs: DashMap<String, IndexConfig>,
+    /// Serializes manifest writes
+    manifest_lock: Mutex<()>,
+    /// Lookup counters by index name
+    usage: DashMap<String, UsageCounters>,
     /// Base directory for persistent indices
     base_dir: Option<PathBuf>,
+    /// Number of stale entries removed by read-repair
+    repairs: AtomicU64,
+    /// Nodes holding unique claims ahead of their storage write
+    pending: DashSet<NodeId>,
 }
 
 impl IndexManager {
@@ -78,48 +566,145 @@ impl IndexManager {
             indices: DashMap::new(),
             label_indices: DashMap::new(),
             property_indices: DashMap::new(),
+            scoped_property_indices: DashMap::new(),
+            unique_indices: DashSet::new(),
+            vector_indices: DashMap::new(),
+            vector_properties: DashMap::new(),
+            edge_indices: DashMap::new(),
+            edge_type_indices: DashMap::new(),
+            edge_property_indices: DashMap::new(),
+            configs: DashMap::new(),
+            manifest_lock: Mutex::new(()),
+            usage: DashMap::new(),
             base_dir: None,
+            repairs: AtomicU64::new(0),
+            pending: DashSet::new(),
         }
     }
     
     /// Create an index manager with persistence
+    ///
+    /// Indexes listed in the manifest of `base_dir` are reopened.
     pub fn with_persistence(base_dir: PathBuf) -> Result<Self> {
         std::fs::create_dir_all(&base_dir)
             .map_err(|e| DeepGraphError::IoError(e))?;
         
-        Ok(Self {
+        let manifest = base_dir.join(MANIFEST_FILE);
+        let manager = Self {
             indices: DashMap::new(),
             label_indices: DashMap::new(),
             property_indices: DashMap::new(),
+            scoped_property_indices: DashMap::new(),
+            unique_indices: DashSet::new(),
+            vector_indices: DashMap::new(),
+            vector_properties: DashMap::new(),
+            edge_indices: DashMap::new(),
+            edge_type_indices: DashMap::new(),
+            edge_property_indices: DashMap::new(),
+            configs: DashMap::new(),
+            manifest_lock: Mutex::new(()),
+            usage: DashMap::new(),
             base_dir: Some(base_dir),
-        })
+            repairs: AtomicU64::new(0),
+            pending: DashSet::new(),
+        };
+        
+        if manifest.exists() {
+            let configs: Vec<IndexConfig> = serde_json::from_str(&fs::read_to_string(&manifest)?)?;
+            for config in configs {
+                manager.register(config.clone())?;
+                manager.configs.insert(config.name.clone(), config);
+            }
+            info!("Reopened {} indexes from {}", manager.configs.len(), manifest.display());
+        }
+        Ok(manager)
     }
     
     /// Create an index
+    ///
+    /// With persistence the index is saved and added to the manifest right
+    /// away, so the next [`IndexManager::with_persistence`] reopens it.
     pub fn create_index(&self, config: IndexConfig) -> Result<()> {
-        let index_impl = match config.index_type {
-            IndexType::Hash => {
-                IndexImpl::Hash(RwLock::new(HashIndex::new()))
-            }
-            IndexType::BTree => {
-                let btree = if let Some(base_dir) = &self.base_dir {
-                    let index_path = base_dir.join(&config.name);
-                    BTreeIndex::new(&index_path, &config.name)?
-                } else {
-                    BTreeIndex::new_temp()?
-                };
-                IndexImpl::BTree(RwLock::new(btree))
-            }
-        };
+        let name = config.name.clone();
+        self.register(config.clone())?;
+        self.configs.insert(name.clone(), config);
+        
+        if let Some(base_dir) = &self.base_dir {
+            self.save_index(base_dir, &name)?;
+            self.write_manifest(base_dir)?;
+        }
+        Ok(())
+    }
+    
+    fn save_index(&self, base_dir: &Path, name: &str) -> Result<()> {
+        if let Some(index) = self.indices.get(name) {
+            return index.value().save(base_dir, name);
+        }
+        if let Some(index) = self.edge_indices.get(name) {
+            return index.value().save(base_dir, name);
+        }
+        Ok(())
+    }
+    
+    /// Build an index and add it to the lookup maps
+    fn register(&self, config: IndexConfig) -> Result<()> {
+        if config.unique && config.is_label_index {
+            return Err(DeepGraphError::InvalidOperation(format!(
+                "Label index {} cannot be unique",
+                config.name
+            )));
+        }
+        if config.unique && !config.limits.is_unbounded() {
+            // An evicted entry would let a duplicate value through
+            return Err(DeepGraphError::InvalidOperation(format!(
+                "Unique index {} cannot have size or TTL limits",
+                config.name
+            )));
+        }
+        if config.label.is_some() && (config.is_label_index || config.on_edges) {
+            return Err(DeepGraphError::InvalidOperation(format!(
+                "Only node property indexes can be scoped to a label ({})",
+                config.name
+            )));
+        }
+        if config.on_edges {
+            return self.create_edge_index(config);
+        }
+        let index_impl = IndexImpl::create(&config, self.base_dir.as_deref())?;
         
         // Register the index
         self.indices.insert(config.name.clone(), index_impl);
+        if config.unique {
+            self.unique_indices.insert(config.name.clone());
+        }
         
         // Track label or property index
         if config.is_label_index {
             self.label_indices.insert(config.name.clone(), config.name.clone());
         } else if let Some(prop_key) = config.property_key {
-            self.property_indices.insert(prop_key, config.name);
+            match config.label {
+                Some(label) => self.scoped_property_indices.insert((label, prop_key), config.name),
+                None => self.property_indices.insert(prop_key, config.name),
+            };
+        }
+        
+        Ok(())
+    }
+    
+    fn create_edge_index(&self, config: IndexConfig) -> Result<()> {
+        if config.unique {
+            return Err(DeepGraphError::InvalidOperation(format!(
+                "Edge index {} cannot be unique",
+                config.name
+            )));
+        }
+        let index_impl = IndexImpl::create(&config, self.base_dir.as_deref())?;
+        self.edge_indices.insert(config.name.clone(), index_impl);
+        
+        if config.is_label_index {
+            self.edge_type_indices.insert(config.name.clone(), config.name.clone());
+        } else if let Some(prop_key) = config.property_key {
+            self.edge_property_indices.insert(prop_key, config.name);
         }
         
         Ok(())
@@ -127,6 +712,16 @@ impl IndexManager {
     
     /// Drop an index
     pub fn drop_index(&self, name: &str) -> Result<()> {
+        if self.vector_indices.remove(name).is_some() {
+            self.vector_properties.retain(|_, v| v != name);
+            self.usage.remove(name);
+            return Ok(());
+        }
+        if self.edge_indices.remove(name).is_some() {
+            self.edge_type_indices.retain(|_, v| v != name);
+            self.edge_property_indices.retain(|_, v| v != name);
+            return self.forget(name);
+        }
         self.indices
             .remove(name)
             .ok_or_else(|| DeepGraphError::StorageError(format!("Index {} not found", name)))?;
@@ -134,7 +729,219 @@ impl IndexManager {
         // Remove from tracking maps
         self.label_indices.retain(|_, v| v != name);
         self.property_indices.retain(|_, v| v != name);
+        self.scoped_property_indices.retain(|_, v| v != name);
+        self.unique_indices.remove(name);
+        
+        self.forget(name)
+    }
+    
+    /// Remove a dropped index from the manifest and delete its saved file
+    fn forget(&self, name: &str) -> Result<()> {
+        self.configs.remove(name);
+        self.usage.remove(name);
+        if let Some(base_dir) = &self.base_dir {
+            let path = hash_index_path(base_dir, name);
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            self.write_manifest(base_dir)?;
+        }
+        Ok(())
+    }
+    
+    fn write_manifest(&self, base_dir: &Path) -> Result<()> {
+        let _guard = self.manifest_lock.lock().unwrap();
+        let mut configs: Vec<IndexConfig> = self.configs.iter().map(|entry| entry.value().clone()).collect();
+        configs.sort_by(|a, b| a.name.cmp(&b.name));
         
+        let path = base_dir.join(MANIFEST_FILE);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(&configs)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+    
+    /// Repopulate an index by scanning storage
+    ///
+    /// Returns the number of entries written. A unique index whose values
+    /// are not unique in storage fails with
+    /// [`DeepGraphError::ConstraintViolation`] and is left unchanged. Writes
+    /// made through the manager while the scan runs may be lost; rebuild
+    /// while the graph is quiescent.
+    pub fn rebuild(&self, index_name: &str, storage: &dyn StorageBackend) -> Result<usize> {
+        let config = self.index_config(index_name)?;
+        let entries = expected_entries(&config, storage);
+        
+        if config.unique {
+            let mut seen = HashSet::new();
+            if let Some((key, id)) = entries.iter().find(|(key, _)| !seen.insert(key)) {
+                return Err(DeepGraphError::ConstraintViolation(format!(
+                    "Cannot rebuild unique index {}: node {} shares {} = {:?} with another node",
+                    index_name,
+                    id,
+                    config.property_key.as_deref().unwrap_or_default(),
+                    key
+                )));
+            }
+        }
+        
+        if config.on_edges {
+            self.edge_indices
+                .get(index_name)
+                .ok_or_else(|| DeepGraphError::NotFound(format!("Index {} not found", index_name)))?
+                .refill(&entries)?;
+        } else {
+            self.indices
+                .get(index_name)
+                .ok_or_else(|| DeepGraphError::NotFound(format!("Index {} not found", index_name)))?
+                .refill(&entries)?;
+        }
+        if let Some(base_dir) = &self.base_dir {
+            self.save_index(base_dir, index_name)?;
+        }
+        
+        info!("Rebuilt index {} with {} entries", index_name, entries.len());
+        Ok(entries.len())
+    }
+    
+    /// Compare an index against storage without changing it
+    pub fn verify(&self, index_name: &str, storage: &dyn StorageBackend) -> Result<IndexVerification> {
+        let config = self.index_config(index_name)?;
+        let expected: HashSet<(Vec<u8>, Uuid)> = expected_entries(&config, storage).into_iter().collect();
+        
+        let actual = if config.on_edges {
+            self.edge_indices.get(index_name).map(|index| index.uuid_entries())
+        } else {
+            self.indices.get(index_name).map(|index| index.uuid_entries())
+        };
+        let actual: HashSet<(Vec<u8>, Uuid)> = actual
+            .ok_or_else(|| DeepGraphError::NotFound(format!("Index {} not found", index_name)))??
+            .into_iter()
+            .collect();
+        
+        let mut missing: Vec<_> = expected.difference(&actual).cloned().collect();
+        let mut extra: Vec<_> = actual.difference(&expected).cloned().collect();
+        missing.sort();
+        extra.sort();
+        if !missing.is_empty() || !extra.is_empty() {
+            warn!(
+                "Index {} is inconsistent: {} missing, {} extra entries",
+                index_name,
+                missing.len(),
+                extra.len()
+            );
+        }
+        Ok(IndexVerification {
+            index_name: index_name.to_string(),
+            expected: expected.len(),
+            missing,
+            extra,
+        })
+    }
+    
+    fn index_config(&self, index_name: &str) -> Result<IndexConfig> {
+        if self.vector_indices.contains_key(index_name) {
+            return Err(DeepGraphError::InvalidOperation(format!(
+                "Vector index {} cannot be rebuilt or verified",
+                index_name
+            )));
+        }
+        self.configs
+            .get(index_name)
+            .map(|config| config.value().clone())
+            .ok_or_else(|| DeepGraphError::NotFound(format!("Index {} not found", index_name)))
+    }
+    
+    /// Save hash indices and flush B-tree indices to the base directory
+    ///
+    /// Does nothing without persistence. Also runs when the manager is
+    /// dropped.
+    pub fn flush(&self) -> Result<()> {
+        let Some(base_dir) = &self.base_dir else {
+            return Ok(());
+        };
+        for entry in self.indices.iter() {
+            entry.value().save(base_dir, entry.key())?;
+        }
+        for entry in self.edge_indices.iter() {
+            entry.value().save(base_dir, entry.key())?;
+        }
+        Ok(())
+    }
+    
+    /// Create a vector index over a list-of-numbers property
+    ///
+    /// Nodes written through [`IndexManager::add_node`] and
+    /// [`IndexManager::update_node`] are indexed automatically; vectors can
+    /// also be inserted directly with [`IndexManager::insert_vector`].
+    pub fn create_vector_index(
+        &self,
+        name: &str,
+        property_key: &str,
+        dimensions: usize,
+        metric: DistanceMetric,
+    ) -> Result<()> {
+        if self.indices.contains_key(name) || self.vector_indices.contains_key(name) {
+            return Err(DeepGraphError::InvalidOperation(format!("Index {} already exists", name)));
+        }
+        self.vector_indices
+            .insert(name.to_string(), RwLock::new(VectorIndex::new(dimensions, metric)));
+        self.vector_properties.insert(property_key.to_string(), name.to_string());
+        Ok(())
+    }
+    
+    /// Store a node's vector in a vector index, replacing any previous one
+    pub fn insert_vector(&self, name: &str, node_id: NodeId, vector: Vec<f32>) -> Result<()> {
+        self.vector_index(name)?.write().unwrap().insert(node_id, vector)
+    }
+    
+    /// Remove a node's vector from a vector index
+    pub fn remove_vector(&self, name: &str, node_id: NodeId) -> Result<()> {
+        self.vector_index(name)?.write().unwrap().remove(node_id);
+        Ok(())
+    }
+    
+    /// The `k` nodes closest to `query` in a vector index, closest first
+    pub fn vector_search(&self, name: &str, query: &[f32], k: usize) -> Result<Vec<VectorMatch>> {
+        let result = self.vector_index(name)?.read().unwrap().search(query, k);
+        self.track(name, result)
+    }
+    
+    /// Check if a vector index exists
+    pub fn has_vector_index(&self, name: &str) -> bool {
+        self.vector_indices.contains_key(name)
+    }
+    
+    fn vector_index(&self, name: &str) -> Result<dashmap::mapref::one::Ref<'_, String, RwLock<VectorIndex>>> {
+        self.vector_indices
+            .get(name)
+            .ok_or_else(|| DeepGraphError::NotFound(format!("Vector index {} not found", name)))
+    }
+    
+    /// Index a property value in the vector index on its key, if any
+    fn insert_vector_property(&self, key: &str, value: &PropertyValue, node_id: NodeId) -> Result<()> {
+        let Some(name) = self.vector_properties.get(key) else {
+            return Ok(());
+        };
+        match vector_from_property(value) {
+            Some(vector) => self.insert_vector(name.value(), node_id, vector),
+            None => {
+                warn!("Property {} of node {} is not a numeric list, skipping vector index", key, node_id);
+                Ok(())
+            }
+        }
+    }
+    
+    /// Drop a node's vector if it is still the one stored under `value`
+    fn remove_vector_property(&self, key: &str, value: &PropertyValue, node_id: NodeId) -> Result<()> {
+        let Some(name) = self.vector_properties.get(key) else {
+            return Ok(());
+        };
+        let index = self.vector_index(name.value())?;
+        let mut index = index.write().unwrap();
+        if index.get(node_id).map(|stored| stored.to_vec()) == vector_from_property(value) {
+            index.remove(node_id);
+        }
         Ok(())
     }
     
@@ -142,50 +949,387 @@ impl IndexManager {
     pub fn insert_label(&self, label: &str, node_id: NodeId) -> Result<()> {
         if let Some(index_name) = self.label_indices.get(label) {
             if let Some(index_entry) = self.indices.get(index_name.value()) {
-                match index_entry.value() {
-                    IndexImpl::Hash(index) => {
-                        index.write().unwrap().insert(label.as_bytes().to_vec(), node_id)?;
-                    }
-                    IndexImpl::BTree(index) => {
-                        index.write().unwrap().insert(label.as_bytes().to_vec(), node_id)?;
-                    }
-                }
+                index_entry.value().insert(label.as_bytes().to_vec(), node_id)?;
             }
         }
         Ok(())
     }
     
     /// Insert into property index
+    ///
+    /// Fails with [`DeepGraphError::ConstraintViolation`] if the index is
+    /// unique and another node already holds the value.
     pub fn insert_property(&self, key: &str, value: &PropertyValue, node_id: NodeId) -> Result<()> {
-        if let Some(index_name) = self.property_indices.get(key) {
-            if let Some(index_entry) = self.indices.get(index_name.value()) {
-                let bytes = property_to_bytes(value);
-                
-                match index_entry.value() {
-                    IndexImpl::Hash(index) => {
-                        index.write().unwrap().insert(bytes, node_id)?;
-                    }
-                    IndexImpl::BTree(index) => {
-                        index.write().unwrap().insert(bytes, node_id)?;
-                    }
+        self.insert_property_checked(key, value, node_id, &|_| true).map(|_| ())
+    }
+    
+    fn insert_property_checked(
+        &self,
+        key: &str,
+        value: &PropertyValue,
+        node_id: NodeId,
+        is_live: &dyn Fn(NodeId) -> bool,
+    ) -> Result<bool> {
+        let Some(index_name) = self.property_indices.get(key) else {
+            return Ok(true);
+        };
+        self.claim_value(index_name.value(), key, value, node_id, is_live)
+    }
+    
+    /// Insert into the property index scoped to `label`, if there is one
+    ///
+    /// Unique scoped indexes reject a value another node with the label
+    /// already holds.
+    pub fn insert_label_property(&self, label: &str, key: &str, value: &PropertyValue, node_id: NodeId) -> Result<()> {
+        self.insert_label_property_checked(label, key, value, node_id, &|_| true).map(|_| ())
+    }
+    
+    fn insert_label_property_checked(
+        &self,
+        label: &str,
+        key: &str,
+        value: &PropertyValue,
+        node_id: NodeId,
+        is_live: &dyn Fn(NodeId) -> bool,
+    ) -> Result<bool> {
+        let Some(index_name) = self.scoped_property_indices.get(&(label.to_string(), key.to_string())) else {
+            return Ok(true);
+        };
+        self.claim_value(index_name.value(), key, value, node_id, is_live)
+    }
+    
+    /// Insert a property value into a named index, enforcing uniqueness
+    ///
+    /// Returns false if a unique index already held the value for this node.
+    fn claim_value(
+        &self,
+        index_name: &str,
+        key: &str,
+        value: &PropertyValue,
+        node_id: NodeId,
+        is_live: &dyn Fn(NodeId) -> bool,
+    ) -> Result<bool> {
+        let Some(index_entry) = self.indices.get(index_name) else {
+            return Ok(true);
+        };
+        let unique = self.unique_indices.contains(index_name).then_some(Uniqueness {
+            index: index_name,
+            property: key,
+            value,
+            is_live,
+        });
+        match index_entry.value().insert_checked(property_to_bytes(value), node_id, unique.as_ref())? {
+            Some(repaired) => {
+                self.repairs.fetch_add(repaired, Ordering::Relaxed);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+    
+    /// Whether a property has a unique index
+    pub fn is_unique(&self, key: &str) -> bool {
+        self.property_indices
+            .get(key)
+            .is_some_and(|name| self.unique_indices.contains(name.value()))
+    }
+    
+    /// Add every index entry of a node
+    ///
+    /// Either all entries are added or, when a unique index rejects one,
+    /// none are.
+    pub fn index_node(&self, node: &Node) -> Result<()> {
+        self.insert_entries(node.id(), &entries_not_in(node, None, self.has_scoped_indexes()), &|_| true)
+            .map(|_| ())
+    }
+    
+    /// Remove every index entry of a node
+    pub fn unindex_node(&self, node: &Node) -> Result<()> {
+        self.remove_entries(node.id(), &entries_not_in(node, None, self.has_scoped_indexes()))
+    }
+    
+    /// Add a node to storage and the indexes
+    ///
+    /// The node's unique values are claimed before it is written; if one is
+    /// held by another live node, or by a node another writer is adding,
+    /// nothing is stored and [`DeepGraphError::ConstraintViolation`] is
+    /// returned. Because the claim and the check happen under the index
+    /// lock, two concurrent writers of the same value cannot both succeed.
+    /// Adding a node that already exists updates it.
+    pub fn add_node<S: StorageBackend + ?Sized>(&self, storage: &S, node: Node) -> Result<NodeId> {
+        let old = storage.get_node(node.id()).ok();
+        self.write_node(storage, node, old)
+    }
+    
+    /// Update a node in storage and move its index entries
+    ///
+    /// Checked like [`IndexManager::add_node`]; on a violation the stored
+    /// node is left as it was.
+    pub fn update_node<S: StorageBackend + ?Sized>(&self, storage: &S, node: Node) -> Result<()> {
+        let old = storage.get_node(node.id())?;
+        self.write_node(storage, node, Some(old)).map(|_| ())
+    }
+    
+    /// Claim a node's new entries, write it, then settle the entries
+    fn write_node<S: StorageBackend + ?Sized>(&self, storage: &S, node: Node, old: Option<Node>) -> Result<NodeId> {
+        let id = node.id();
+        self.pending.insert(id);
+        let result = self.write_claimed(storage, node, old.as_ref());
+        self.pending.remove(&id);
+        result.map(|()| id)
+    }
+    
+    fn write_claimed<S: StorageBackend + ?Sized>(&self, storage: &S, node: Node, old: Option<&Node>) -> Result<()> {
+        let id = node.id();
+        let scoped = self.has_scoped_indexes();
+        let is_live = |holder: NodeId| self.pending.contains(&holder) || storage.get_node(holder).is_ok();
+        
+        let claimed = self.insert_entries(id, &entries_not_in(&node, old, scoped), &is_live)?;
+        // Put storage back the way it was, then drop only what this call added
+        let undo = |added: &[Entry<'_>]| -> Result<()> {
+            match old {
+                Some(old) => storage.update_node(old.clone())?,
+                None if storage.get_node(id).is_ok() => storage.delete_node(id)?,
+                None => {}
+            }
+            self.remove_entries(id, added)
+        };
+        
+        let written = match old {
+            Some(_) => storage.update_node(node.clone()),
+            None => storage.add_node(node.clone()).map(|_| ()),
+        };
+        // Index what was stored, including any schema defaults
+        let stored = match written.and_then(|()| storage.get_node(id)) {
+            Ok(stored) => stored,
+            Err(e) => {
+                undo(&claimed)?;
+                return Err(e);
+            }
+        };
+        let late: Vec<Entry<'_>> = entries_not_in(&stored, old, scoped)
+            .into_iter()
+            .filter(|entry| !has_entry(&node, entry))
+            .collect();
+        if let Err(e) = self.insert_entries(id, &late, &is_live) {
+            undo(&claimed)?;
+            return Err(e);
+        }
+        
+        // Claimed values the stored node did not keep
+        let dropped: Vec<Entry<'_>> = entries_not_in(&node, Some(&stored), scoped)
+            .into_iter()
+            .filter(|entry| !old.is_some_and(|old| has_entry(old, entry)))
+            .collect();
+        self.remove_entries(id, &dropped)?;
+        if let Some(old) = old {
+            self.remove_entries(id, &entries_not_in(old, Some(&stored), scoped))?;
+        }
+        Ok(())
+    }
+    
+    /// Delete a node from storage and the indexes
+    ///
+    /// The edges removed along with the node leave the edge indexes too.
+    pub fn delete_node<S: StorageBackend + ?Sized>(&self, storage: &S, id: NodeId) -> Result<()> {
+        let node = storage.get_node(id)?;
+        let mut edges = Vec::new();
+        if !self.edge_indices.is_empty() {
+            edges.extend(storage.get_outgoing_edges(id)?);
+            edges.extend(storage.get_incoming_edges(id)?);
+        }
+        storage.delete_node(id)?;
+        for edge in &edges {
+            self.unindex_edge(edge)?;
+        }
+        self.unindex_node(&node)
+    }
+    
+    /// Insert entries, removing the ones already added if one fails
+    ///
+    /// Returns the entries that were added; values a unique index already
+    /// held for the node are left out, so undoing the call keeps them.
+    fn insert_entries<'a>(
+        &self,
+        node_id: NodeId,
+        entries: &[Entry<'a>],
+        is_live: &dyn Fn(NodeId) -> bool,
+    ) -> Result<Vec<Entry<'a>>> {
+        let mut added = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let result = match *entry {
+                Entry::Label(label) => self.insert_label(label, node_id).map(|()| true),
+                Entry::Property(key, value) => match self.insert_property_checked(key, value, node_id, is_live) {
+                    Ok(true) => match self.insert_vector_property(key, value, node_id) {
+                        Ok(()) => Ok(true),
+                        Err(e) => self.remove_property(key, value, node_id).and(Err(e)),
+                    },
+                    other => other,
+                },
+                Entry::LabelProperty(label, key, value) => {
+                    self.insert_label_property_checked(label, key, value, node_id, is_live)
+                }
+            };
+            match result {
+                Ok(true) => added.push(*entry),
+                Ok(false) => {}
+                Err(e) => {
+                    self.remove_entries(node_id, &added)?;
+                    return Err(e);
                 }
             }
         }
+        Ok(added)
+    }
+    
+    fn remove_entries(&self, node_id: NodeId, entries: &[Entry<'_>]) -> Result<()> {
+        for entry in entries {
+            match entry {
+                Entry::Label(label) => self.remove_label(label, node_id)?,
+                Entry::Property(key, value) => {
+                    self.remove_property(key, value, node_id)?;
+                    self.remove_vector_property(key, value, node_id)?;
+                }
+                Entry::LabelProperty(label, key, value) => self.remove_label_property(label, key, value, node_id)?,
+            }
+        }
+        Ok(())
+    }
+    
+    fn has_scoped_indexes(&self) -> bool {
+        !self.scoped_property_indices.is_empty()
+    }
+    
+    /// Insert into a relationship type index
+    pub fn insert_edge_type(&self, rel_type: &str, edge_id: EdgeId) -> Result<()> {
+        match self.edge_index(&self.edge_type_indices, rel_type) {
+            Some(index) => index.value().insert(rel_type.as_bytes().to_vec(), edge_id),
+            None => Ok(()),
+        }
+    }
+    
+    /// Insert into an edge property index
+    pub fn insert_edge_property(&self, key: &str, value: &PropertyValue, edge_id: EdgeId) -> Result<()> {
+        match self.edge_index(&self.edge_property_indices, key) {
+            Some(index) => index.value().insert(property_to_bytes(value), edge_id),
+            None => Ok(()),
+        }
+    }
+    
+    /// Remove an edge from a relationship type index
+    pub fn remove_edge_type(&self, rel_type: &str, edge_id: EdgeId) -> Result<()> {
+        match self.edge_index(&self.edge_type_indices, rel_type) {
+            Some(index) => index.value().remove(rel_type.as_bytes(), edge_id),
+            None => Ok(()),
+        }
+    }
+    
+    /// Remove an edge from an edge property index
+    pub fn remove_edge_property(&self, key: &str, value: &PropertyValue, edge_id: EdgeId) -> Result<()> {
+        match self.edge_index(&self.edge_property_indices, key) {
+            Some(index) => index.value().remove(&property_to_bytes(value), edge_id),
+            None => Ok(()),
+        }
+    }
+    
+    /// Lookup edges by relationship type
+    pub fn lookup_edge_type(&self, rel_type: &str) -> Result<Vec<EdgeId>> {
+        match self.edge_index(&self.edge_type_indices, rel_type) {
+            Some(index) => self.track(index.key(), index.value().lookup(rel_type.as_bytes())),
+            None => Ok(Vec::new()),
+        }
+    }
+    
+    /// Lookup edges by property value
+    pub fn lookup_edge_property(&self, key: &str, value: &PropertyValue) -> Result<Vec<EdgeId>> {
+        match self.edge_index(&self.edge_property_indices, key) {
+            Some(index) => self.track(index.key(), index.value().lookup(&property_to_bytes(value))),
+            None => Ok(Vec::new()),
+        }
+    }
+    
+    /// Range query on an edge property (only works with B-tree indices)
+    pub fn range_edge_property(
+        &self,
+        key: &str,
+        start: &PropertyValue,
+        end: &PropertyValue,
+    ) -> Result<Vec<EdgeId>> {
+        match self.edge_index(&self.edge_property_indices, key) {
+            Some(index) => self.track(
+                index.key(),
+                index.value().range(&property_to_bytes(start), &property_to_bytes(end)),
+            ),
+            None => Ok(Vec::new()),
+        }
+    }
+    
+    /// Add an edge's relationship type and properties to the edge indexes
+    pub fn index_edge(&self, edge: &Edge) -> Result<()> {
+        self.insert_edge_type(edge.relationship_type(), edge.id())?;
+        for (key, value) in edge.properties() {
+            self.insert_edge_property(key, value, edge.id())?;
+        }
+        Ok(())
+    }
+    
+    /// Remove an edge from the edge indexes
+    pub fn unindex_edge(&self, edge: &Edge) -> Result<()> {
+        self.remove_edge_type(edge.relationship_type(), edge.id())?;
+        for (key, value) in edge.properties() {
+            self.remove_edge_property(key, value, edge.id())?;
+        }
         Ok(())
     }
     
+    /// Add an edge to storage and the edge indexes
+    pub fn add_edge<S: StorageBackend + ?Sized>(&self, storage: &S, edge: Edge) -> Result<EdgeId> {
+        let id = storage.add_edge(edge)?;
+        self.index_edge(&storage.get_edge(id)?)?;
+        Ok(id)
+    }
+    
+    /// Update an edge in storage and move its index entries
+    pub fn update_edge<S: StorageBackend + ?Sized>(&self, storage: &S, edge: Edge) -> Result<()> {
+        let id = edge.id();
+        let old = storage.get_edge(id)?;
+        storage.update_edge(edge)?;
+        self.unindex_edge(&old)?;
+        self.index_edge(&storage.get_edge(id)?)
+    }
+    
+    /// Delete an edge from storage and the edge indexes
+    pub fn delete_edge<S: StorageBackend + ?Sized>(&self, storage: &S, id: EdgeId) -> Result<()> {
+        let edge = storage.get_edge(id)?;
+        storage.delete_edge(id)?;
+        self.unindex_edge(&edge)
+    }
+    
+    /// Check if an index exists for a relationship type
+    pub fn has_edge_type_index(&self, rel_type: &str) -> bool {
+        self.edge_type_indices.contains_key(rel_type)
+    }
+    
+    /// Check if an index exists for an edge property
+    pub fn has_edge_property_index(&self, key: &str) -> bool {
+        self.edge_property_indices.contains_key(key)
+    }
+    
+    fn edge_index(
+        &self,
+        names: &DashMap<String, String>,
+        key: &str,
+    ) -> Option<dashmap::mapref::one::Ref<'_, String, IndexImpl<EdgeId>>> {
+        let name = names.get(key)?;
+        self.edge_indices.get(name.value())
+    }
+    
     /// Lookup by label
     pub fn lookup_label(&self, label: &str) -> Result<Vec<NodeId>> {
         if let Some(index_name) = self.label_indices.get(label) {
             if let Some(index_entry) = self.indices.get(index_name.value()) {
-                return match index_entry.value() {
-                    IndexImpl::Hash(index) => {
-                        index.read().unwrap().lookup(label.as_bytes())
-                    }
-                    IndexImpl::BTree(index) => {
-                        index.read().unwrap().lookup(label.as_bytes())
-                    }
-                };
+                let result = index_entry.value().lookup(label.as_bytes());
+                return self.track(index_name.value(), result);
             }
         }
         Ok(Vec::new())
@@ -197,19 +1341,144 @@ impl IndexManager {
             if let Some(index_entry) = self.indices.get(index_name.value()) {
                 let bytes = property_to_bytes(value);
                 
-                return match index_entry.value() {
-                    IndexImpl::Hash(index) => {
-                        index.read().unwrap().lookup(&bytes)
-                    }
-                    IndexImpl::BTree(index) => {
-                        index.read().unwrap().lookup(&bytes)
-                    }
-                };
+                let result = index_entry.value().lookup(&bytes);
+                return self.track(index_name.value(), result);
             }
         }
         Ok(Vec::new())
     }
     
+    /// Lookup a raw key in a node index by index name
+    pub fn lookup_index(&self, index_name: &str, key: &[u8]) -> Result<Vec<NodeId>> {
+        let index_entry = self
+            .indices
+            .get(index_name)
+            .ok_or_else(|| DeepGraphError::NotFound(format!("Index {} not found", index_name)))?;
+        self.track(index_name, index_entry.value().lookup(key))
+    }
+    
+    /// Count a lookup against an index and pass its result through
+    fn track<T>(&self, index_name: &str, result: Result<Vec<T>>) -> Result<Vec<T>> {
+        if let Ok(found) = &result {
+            let counters = match self.usage.get(index_name) {
+                Some(counters) => counters,
+                None => self.usage.entry(index_name.to_string()).or_default().downgrade(),
+            };
+            counters.lookups.fetch_add(1, Ordering::Relaxed);
+            if !found.is_empty() {
+                counters.hits.fetch_add(1, Ordering::Relaxed);
+            }
+            counters.last_used_ms.store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+        }
+        result
+    }
+    
+    /// Lookup statistics of an index (all zero if it was never used)
+    pub fn usage(&self, index_name: &str) -> IndexUsage {
+        self.usage
+            .get(index_name)
+            .map(|counters| counters.snapshot())
+            .unwrap_or_default()
+    }
+    
+    /// Remove a node from a label index
+    pub fn remove_label(&self, label: &str, node_id: NodeId) -> Result<()> {
+        if let Some(index_name) = self.label_indices.get(label) {
+            if let Some(index_entry) = self.indices.get(index_name.value()) {
+                index_entry.value().remove(label.as_bytes(), node_id)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Remove a node from the property index scoped to `label`
+    pub fn remove_label_property(&self, label: &str, key: &str, value: &PropertyValue, node_id: NodeId) -> Result<()> {
+        if let Some(index_name) = self.scoped_property_indices.get(&(label.to_string(), key.to_string())) {
+            if let Some(index_entry) = self.indices.get(index_name.value()) {
+                index_entry.value().remove(&property_to_bytes(value), node_id)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Lookup nodes with `label` by property value in a label-scoped index
+    pub fn lookup_label_property(&self, label: &str, key: &str, value: &PropertyValue) -> Result<Vec<NodeId>> {
+        match self.scoped_property_indices.get(&(label.to_string(), key.to_string())) {
+            Some(index_name) => self.lookup_index(index_name.value(), &property_to_bytes(value)),
+            None => Ok(Vec::new()),
+        }
+    }
+    
+    /// Check if a label-scoped index exists for a property
+    pub fn has_label_property_index(&self, label: &str, key: &str) -> bool {
+        self.scoped_property_indices.contains_key(&(label.to_string(), key.to_string()))
+    }
+    
+    /// Remove a node from a property index
+    pub fn remove_property(&self, key: &str, value: &PropertyValue, node_id: NodeId) -> Result<()> {
+        if let Some(index_name) = self.property_indices.get(key) {
+            if let Some(index_entry) = self.indices.get(index_name.value()) {
+                index_entry.value().remove(&property_to_bytes(value), node_id)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Lookup by label and load the matching nodes from storage
+    ///
+    /// Entries pointing at nodes that no longer exist are removed from the
+    /// index (read-repair) and counted in [`IndexManager::repair_count`].
+    pub fn resolve_label<S: StorageBackend + ?Sized>(&self, label: &str, storage: &S) -> Result<Vec<Node>> {
+        let index_name = match self.label_indices.get(label) {
+            Some(name) => name.value().clone(),
+            None => return Ok(Vec::new()),
+        };
+        self.resolve(&index_name, label.as_bytes(), storage)
+    }
+    
+    /// Lookup by property value and load the matching nodes from storage
+    ///
+    /// Stale entries are repaired the same way as in [`IndexManager::resolve_label`].
+    pub fn resolve_property<S: StorageBackend + ?Sized>(
+        &self,
+        key: &str,
+        value: &PropertyValue,
+        storage: &S,
+    ) -> Result<Vec<Node>> {
+        let index_name = match self.property_indices.get(key) {
+            Some(name) => name.value().clone(),
+            None => return Ok(Vec::new()),
+        };
+        self.resolve(&index_name, &property_to_bytes(value), storage)
+    }
+    
+    /// Number of stale index entries removed by read-repair
+    pub fn repair_count(&self) -> u64 {
+        self.repairs.load(Ordering::Relaxed)
+    }
+    
+    fn resolve<S: StorageBackend + ?Sized>(&self, index_name: &str, key: &[u8], storage: &S) -> Result<Vec<Node>> {
+        let index_entry = match self.indices.get(index_name) {
+            Some(entry) => entry,
+            None => return Ok(Vec::new()),
+        };
+        let index = index_entry.value();
+        
+        let mut nodes = Vec::new();
+        for node_id in self.track(index_name, index.lookup(key))? {
+            match storage.get_node(node_id) {
+                Ok(node) => nodes.push(node),
+                Err(e) if e.is_not_found() => {
+                    warn!("Index '{}' references missing node {}, removing stale entry", index_name, node_id);
+                    index.remove(key, node_id)?;
+                    self.repairs.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(nodes)
+    }
+    
     /// Range query on a property (only works with B-tree indices)
     pub fn range_property(
         &self,
@@ -219,18 +1488,8 @@ impl IndexManager {
     ) -> Result<Vec<NodeId>> {
         if let Some(index_name) = self.property_indices.get(key) {
             if let Some(index_entry) = self.indices.get(index_name.value()) {
-                match index_entry.value() {
-                    IndexImpl::BTree(index) => {
-                        let start_bytes = property_to_bytes(start);
-                        let end_bytes = property_to_bytes(end);
-                        return index.read().unwrap().range(&start_bytes, &end_bytes);
-                    }
-                    IndexImpl::Hash(_) => {
-                        return Err(DeepGraphError::StorageError(
-                            "Range queries not supported on hash indices".to_string()
-                        ));
-                    }
-                }
+                let result = index_entry.value().range(&property_to_bytes(start), &property_to_bytes(end));
+                return self.track(index_name.value(), result);
             }
         }
         Ok(Vec::new())
@@ -246,17 +1505,71 @@ impl IndexManager {
         self.property_indices.contains_key(key)
     }
     
-    /// Get all index names
-    pub fn list_indices(&self) -> Vec<String> {
-        self.indices
+    /// Get all indices with their usage statistics, sorted by name
+    pub fn list_indices(&self) -> Vec<IndexInfo> {
+        let mut infos: Vec<IndexInfo> = self
+            .configs
             .iter()
-            .map(|entry| entry.key().clone())
-            .collect()
+            .map(|entry| {
+                let config = entry.value();
+                let (size, (evicted, expired)) = match self.indices.get(&config.name) {
+                    Some(index) => (index.value().len(), index.value().dropped()),
+                    None => self.edge_indices.get(&config.name).map_or((0, (0, 0)), |index| {
+                        (index.value().len(), index.value().dropped())
+                    }),
+                };
+                IndexInfo {
+                    name: config.name.clone(),
+                    index_type: Some(config.index_type),
+                    property_key: config.property_key.clone(),
+                    label: config.label.clone(),
+                    on_edges: config.on_edges,
+                    unique: config.unique,
+                    size,
+                    usage: self.usage(&config.name),
+                    evicted,
+                    expired,
+                    limits: config.limits,
+                }
+            })
+            .collect();
+        infos.extend(self.vector_indices.iter().map(|entry| IndexInfo {
+            name: entry.key().clone(),
+            index_type: None,
+            property_key: self
+                .vector_properties
+                .iter()
+                .find(|property| property.value() == entry.key())
+                .map(|property| property.key().clone()),
+            label: None,
+            on_edges: false,
+            unique: false,
+            size: entry.value().read().unwrap().len(),
+            usage: self.usage(entry.key()),
+            evicted: 0,
+            expired: 0,
+            limits: IndexLimits::default(),
+        }));
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+    
+    /// Drop entries past their TTL from every node and edge hash index
+    ///
+    /// Expired entries are skipped by lookups anyway; call this periodically
+    /// to release their memory. Returns how many entries were dropped.
+    pub fn expire_entries(&self) -> usize {
+        let expired = self.indices.iter().map(|entry| entry.value().expire()).sum::<usize>()
+            + self.edge_indices.iter().map(|entry| entry.value().expire()).sum::<usize>();
+        if expired > 0 {
+            info!("Expired {} index entries", expired);
+        }
+        expired
     }
     
     /// Get index count
     pub fn index_count(&self) -> usize {
-        self.indices.len()
+        self.indices.len() + self.vector_indices.len() + self.edge_indices.len()
     }
 }
 
@@ -266,9 +1579,18 @@ impl Default for IndexManager {
     }
 }
 
+impl Drop for IndexManager {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("Failed to save indexes: {}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::GraphStorage;
 
     #[test]
     fn test_create_label_index() {
@@ -378,5 +1700,341 @@ mod tests {
         manager.drop_index("test").unwrap();
         assert_eq!(manager.index_count(), 0);
     }
-}
 
+    #[test]
+    fn test_property_lookup_read_repair() {
+        let manager = IndexManager::new();
+        manager.create_index(IndexConfig::property_index(
+            "age".to_string(),
+            IndexType::Hash,
+            "age".to_string(),
+        )).unwrap();
+        
+        let storage = GraphStorage::new();
+        let value = PropertyValue::Integer(30);
+        let live = storage.add_node(Node::new(vec![])).unwrap();
+        let stale = NodeId::new();
+        manager.insert_property("age", &value, live).unwrap();
+        manager.insert_property("age", &value, stale).unwrap();
+        
+        let nodes = manager.resolve_property("age", &value, &storage).unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].id(), live);
+        assert_eq!(manager.repair_count(), 1);
+        assert_eq!(manager.lookup_property("age", &value).unwrap(), vec![live]);
+    }
+
+    #[test]
+    fn test_unique_index() {
+        let manager = IndexManager::new();
+        manager.create_index(IndexConfig::property_index(
+            "email".to_string(),
+            IndexType::Hash,
+            "email".to_string(),
+        ).unique()).unwrap();
+        assert!(manager.is_unique("email"));
+        assert!(manager.create_index(IndexConfig::label_index("User".to_string(), IndexType::Hash).unique()).is_err());
+        
+        let storage = GraphStorage::new();
+        let user = |email: &str| {
+            let mut node = Node::new(vec!["User".to_string()]);
+            node.set_property("email".to_string(), PropertyValue::String(email.to_string()));
+            node
+        };
+        let ann = manager.add_node(&storage, user("ann@example.com")).unwrap();
+        let err = manager.add_node(&storage, user("ann@example.com")).unwrap_err();
+        assert!(err.is_constraint_violation());
+        assert_eq!(storage.node_count(), 1);
+        
+        // Updating into a taken value is rolled back
+        let bo = manager.add_node(&storage, user("bo@example.com")).unwrap();
+        let mut renamed = storage.get_node(bo).unwrap();
+        renamed.set_property("email".to_string(), PropertyValue::String("ann@example.com".to_string()));
+        assert!(manager.update_node(&storage, renamed).unwrap_err().is_constraint_violation());
+        let bo_email = PropertyValue::String("bo@example.com".to_string());
+        assert_eq!(storage.get_node(bo).unwrap().get_property("email"), Some(&bo_email));
+        assert_eq!(manager.lookup_property("email", &bo_email).unwrap(), vec![bo]);
+        
+        // A deleted holder frees its value
+        manager.delete_node(&storage, ann).unwrap();
+        manager.add_node(&storage, user("ann@example.com")).unwrap();
+    }
+
+    #[test]
+    fn test_edge_indexes() {
+        let manager = IndexManager::new();
+        manager.create_index(IndexConfig::relationship_type_index("KNOWS".to_string(), IndexType::Hash)).unwrap();
+        manager.create_index(IndexConfig::edge_property_index(
+            "weight".to_string(),
+            IndexType::BTree,
+            "weight".to_string(),
+        )).unwrap();
+        assert!(manager.has_edge_type_index("KNOWS"));
+        assert!(manager.has_edge_property_index("weight"));
+        assert!(!manager.has_property_index("weight"));
+        assert_eq!(manager.index_count(), 2);
+        
+        let storage = GraphStorage::new();
+        let alice = storage.add_node(Node::new(vec![])).unwrap();
+        let bob = storage.add_node(Node::new(vec![])).unwrap();
+        let weighted = |weight: i64| {
+            let mut edge = Edge::new(alice, bob, "KNOWS".to_string());
+            edge.set_property("weight".to_string(), PropertyValue::Integer(weight));
+            edge
+        };
+        let light = manager.add_edge(&storage, weighted(10)).unwrap();
+        let heavy = manager.add_edge(&storage, weighted(30)).unwrap();
+        assert_eq!(manager.lookup_edge_type("KNOWS").unwrap().len(), 2);
+        assert_eq!(manager.lookup_edge_property("weight", &PropertyValue::Integer(10)).unwrap(), vec![light]);
+        assert_eq!(
+            manager.range_edge_property("weight", &PropertyValue::Integer(20), &PropertyValue::Integer(40)).unwrap(),
+            vec![heavy]
+        );
+        
+        let mut lighter = storage.get_edge(light).unwrap();
+        lighter.set_property("weight".to_string(), PropertyValue::Integer(5));
+        manager.update_edge(&storage, lighter).unwrap();
+        assert!(manager.lookup_edge_property("weight", &PropertyValue::Integer(10)).unwrap().is_empty());
+        
+        manager.delete_edge(&storage, heavy).unwrap();
+        assert_eq!(manager.lookup_edge_type("KNOWS").unwrap(), vec![light]);
+        
+        // Deleting an endpoint unindexes its edges
+        manager.delete_node(&storage, alice).unwrap();
+        assert!(manager.lookup_edge_type("KNOWS").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_persistent_hash_index() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let storage = GraphStorage::new();
+        let mut node = Node::new(vec!["User".to_string()]);
+        node.set_property("email".to_string(), PropertyValue::String("ann@example.com".to_string()));
+        
+        let ann = {
+            let manager = IndexManager::with_persistence(dir.path().to_path_buf()).unwrap();
+            manager.create_index(IndexConfig::label_index("User".to_string(), IndexType::Hash)).unwrap();
+            manager.create_index(IndexConfig::property_index(
+                "email".to_string(),
+                IndexType::Hash,
+                "email".to_string(),
+            ).unique()).unwrap();
+            manager.create_index(IndexConfig::label_index("Temp".to_string(), IndexType::Hash)).unwrap();
+            manager.drop_index("Temp").unwrap();
+            manager.add_node(&storage, node).unwrap()
+        };
+        assert!(!dir.path().join("Temp.hash").exists());
+        
+        let manager = IndexManager::with_persistence(dir.path().to_path_buf()).unwrap();
+        assert_eq!(manager.index_count(), 2);
+        assert_eq!(manager.lookup_label("User").unwrap(), vec![ann]);
+        assert!(manager.is_unique("email"));
+        let mut duplicate = Node::new(vec!["User".to_string()]);
+        duplicate.set_property("email".to_string(), PropertyValue::String("ann@example.com".to_string()));
+        assert!(manager.add_node(&storage, duplicate).unwrap_err().is_constraint_violation());
+    }
+
+    #[test]
+    fn test_verify_and_rebuild() {
+        let manager = IndexManager::new();
+        manager.create_index(IndexConfig::label_index("Person".to_string(), IndexType::Hash)).unwrap();
+        manager.create_index(IndexConfig::property_index(
+            "age".to_string(),
+            IndexType::BTree,
+            "age".to_string(),
+        )).unwrap();
+        
+        // Bulk-loaded straight into storage, bypassing the manager
+        let storage = GraphStorage::new();
+        let mut node = Node::new(vec!["Person".to_string()]);
+        node.set_property("age".to_string(), PropertyValue::Integer(30));
+        let alice = storage.add_node(node).unwrap();
+        let stale = NodeId::new();
+        manager.insert_label("Person", stale).unwrap();
+        
+        let report = manager.verify("Person", &storage).unwrap();
+        assert!(!report.is_consistent());
+        assert_eq!(report.missing, vec![(b"Person".to_vec(), *alice.as_uuid())]);
+        assert_eq!(report.extra, vec![(b"Person".to_vec(), *stale.as_uuid())]);
+        
+        assert_eq!(manager.rebuild("Person", &storage).unwrap(), 1);
+        assert_eq!(manager.rebuild("age", &storage).unwrap(), 1);
+        assert!(manager.verify("Person", &storage).unwrap().is_consistent());
+        assert!(manager.verify("age", &storage).unwrap().is_consistent());
+        assert_eq!(manager.lookup_property("age", &PropertyValue::Integer(30)).unwrap(), vec![alice]);
+        assert!(manager.rebuild("missing", &storage).unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn test_index_usage() {
+        let manager = IndexManager::new();
+        manager.create_index(IndexConfig::property_index(
+            "age".to_string(),
+            IndexType::Hash,
+            "age".to_string(),
+        )).unwrap();
+        manager.insert_property("age", &PropertyValue::Integer(30), NodeId::new()).unwrap();
+        
+        assert_eq!(manager.list_indices()[0].usage, IndexUsage::default());
+        manager.lookup_property("age", &PropertyValue::Integer(30)).unwrap();
+        manager.lookup_property("age", &PropertyValue::Integer(31)).unwrap();
+        
+        let info = &manager.list_indices()[0];
+        assert_eq!(info.name, "age");
+        assert_eq!(info.property_key.as_deref(), Some("age"));
+        assert_eq!(info.size, 1);
+        assert_eq!(info.usage.lookups, 2);
+        assert_eq!(info.usage.hits, 1);
+        assert_eq!(info.usage.hit_ratio(), 0.5);
+        assert!(info.usage.last_used_ms.is_some());
+    }
+
+    #[test]
+    fn test_label_scoped_index() {
+        let manager = IndexManager::new();
+        manager.create_index(IndexConfig::property_index(
+            "person_name".to_string(),
+            IndexType::Hash,
+            "name".to_string(),
+        ).for_label("Person".to_string()).unique()).unwrap();
+        assert!(manager.has_label_property_index("Person", "name"));
+        assert!(!manager.has_property_index("name"));
+        assert!(manager.create_index(
+            IndexConfig::label_index("Person".to_string(), IndexType::Hash).for_label("Person".to_string())
+        ).is_err());
+        
+        let storage = GraphStorage::new();
+        let named = |label: &str, name: &str| {
+            let mut node = Node::new(vec![label.to_string()]);
+            node.set_property("name".to_string(), PropertyValue::String(name.to_string()));
+            node
+        };
+        let acme = PropertyValue::String("Acme".to_string());
+        let person = manager.add_node(&storage, named("Person", "Acme")).unwrap();
+        // Other labels are neither indexed nor constrained
+        manager.add_node(&storage, named("Company", "Acme")).unwrap();
+        assert_eq!(manager.lookup_label_property("Person", "name", &acme).unwrap(), vec![person]);
+        assert!(manager.add_node(&storage, named("Person", "Acme")).unwrap_err().is_constraint_violation());
+        assert!(manager.verify("person_name", &storage).unwrap().is_consistent());
+        
+        // Losing the label drops the entry
+        let mut relabeled = storage.get_node(person).unwrap();
+        relabeled.remove_label("Person");
+        relabeled.add_label("Company".to_string());
+        manager.update_node(&storage, relabeled).unwrap();
+        assert!(manager.lookup_label_property("Person", "name", &acme).unwrap().is_empty());
+    }
+    #[test]
+    fn test_bounded_index() {
+        let manager = IndexManager::new();
+        manager.create_index(IndexConfig::property_index(
+            "session_token".to_string(),
+            IndexType::Hash,
+            "token".to_string(),
+        ).with_max_entries(2)).unwrap();
+        assert!(manager.create_index(
+            IndexConfig::property_index("age".to_string(), IndexType::BTree, "age".to_string()).with_max_entries(2)
+        ).is_err());
+        assert!(manager.create_index(
+            IndexConfig::property_index("email".to_string(), IndexType::Hash, "email".to_string())
+                .unique()
+                .with_entry_ttl(std::time::Duration::from_secs(60))
+        ).is_err());
+        
+        let token = |n: i64| PropertyValue::Integer(n);
+        let ids: Vec<NodeId> = (0..3).map(|_| NodeId::new()).collect();
+        for (n, id) in ids.iter().enumerate() {
+            manager.insert_property("token", &token(n as i64), *id).unwrap();
+        }
+        assert!(manager.lookup_property("token", &token(0)).unwrap().is_empty());
+        assert_eq!(manager.lookup_property("token", &token(2)).unwrap(), vec![ids[2]]);
+        
+        let info = &manager.list_indices()[0];
+        assert_eq!(info.size, 2);
+        assert_eq!(info.evicted, 1);
+        assert_eq!(manager.expire_entries(), 0);
+    }
+    #[test]
+    fn test_concurrent_unique_claims() {
+        let manager = std::sync::Arc::new(IndexManager::new());
+        manager.create_index(IndexConfig::property_index(
+            "email".to_string(),
+            IndexType::Hash,
+            "email".to_string(),
+        ).unique()).unwrap();
+        
+        let email = PropertyValue::String("a@example.com".to_string());
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let manager = std::sync::Arc::clone(&manager);
+                let email = email.clone();
+                std::thread::spawn(move || manager.insert_property("email", &email, NodeId::new()).is_ok())
+            })
+            .collect();
+        let claimed = handles.into_iter().map(|h| h.join().unwrap()).filter(|ok| *ok).count();
+        
+        assert_eq!(claimed, 1);
+        assert_eq!(manager.lookup_property("email", &email).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_unique_writes() {
+        let manager = std::sync::Arc::new(IndexManager::new());
+        manager.create_index(IndexConfig::property_index(
+            "email".to_string(),
+            IndexType::Hash,
+            "email".to_string(),
+        ).unique()).unwrap();
+        let storage = std::sync::Arc::new(GraphStorage::new());
+        
+        let email = PropertyValue::String("a@example.com".to_string());
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let (manager, storage, email) = (manager.clone(), storage.clone(), email.clone());
+                std::thread::spawn(move || {
+                    let mut node = Node::new(vec!["User".to_string()]);
+                    node.set_property("email".to_string(), email);
+                    manager.add_node(storage.as_ref(), node).is_ok()
+                })
+            })
+            .collect();
+        let written = handles.into_iter().map(|h| h.join().unwrap()).filter(|ok| *ok).count();
+        
+        assert_eq!(written, 1);
+        assert_eq!(storage.node_count(), 1);
+        assert_eq!(manager.lookup_property("email", &email).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rejected_write_keeps_existing_node() {
+        let manager = IndexManager::new();
+        manager.create_index(IndexConfig::property_index(
+            "email".to_string(),
+            IndexType::Hash,
+            "email".to_string(),
+        ).unique()).unwrap();
+        manager.create_index(IndexConfig::property_index(
+            "name".to_string(),
+            IndexType::Hash,
+            "name".to_string(),
+        ).unique()).unwrap();
+        let storage = GraphStorage::new();
+        
+        let mut ann = Node::new(vec!["User".to_string()]);
+        ann.set_property("email".to_string(), "ann@example.com".into());
+        manager.add_node(&storage, ann).unwrap();
+        let mut bo = Node::new(vec!["User".to_string()]);
+        bo.set_property("email".to_string(), "bo@example.com".into());
+        bo.set_property("name".to_string(), "Bo".into());
+        let bo_id = manager.add_node(&storage, bo.clone()).unwrap();
+        
+        // Re-adding an existing node with a taken value neither deletes it
+        // nor drops the entries it already held
+        bo.set_property("email".to_string(), "ann@example.com".into());
+        assert!(manager.add_node(&storage, bo).unwrap_err().is_constraint_violation());
+        let stored = storage.get_node(bo_id).unwrap();
+        assert_eq!(stored.get_property("email"), Some(&"bo@example.com".into()));
+        assert_eq!(manager.lookup_property("name", &"Bo".into()).unwrap(), vec![bo_id]);
+        assert_eq!(manager.lookup_property("email", &"bo@example.com".into()).unwrap(), vec![bo_id]);
+    }
+}
@@ -4,37 +4,89 @@
 //! - Hash indices for equality lookups (O(1))
 //! - B-tree indices for range queries (O(log n))
 //! - Composite indices for multi-column queries
+//! - HNSW vector indices for nearest-neighbor search over embeddings
+//!
+//! Hash and B-tree indices store node IDs by default and edge IDs when
+//! instantiated with [`EdgeId`], so relationship types and edge properties
+//! can be indexed the same way as labels and node properties.
 
 pub mod hash;
 pub mod btree;
 pub mod manager;
+pub mod vector;
 
-pub use hash::HashIndex;
+pub use hash::{HashIndex, IndexLimits};
 pub use btree::BTreeIndex;
-pub use manager::{IndexManager, IndexType, IndexConfig};
+pub use manager::{IndexConfig, IndexInfo, IndexManager, IndexType, IndexUsage, IndexVerification};
+pub use vector::{DistanceMetric, HnswParams, VectorIndex, VectorMatch};
 
 use crate::error::Result;
-use crate::graph::{NodeId, PropertyValue};
+use crate::graph::{EdgeId, NodeId, PropertyValue};
+use uuid::Uuid;
+
+/// Element IDs an index can store
+pub trait IndexId: Copy + Eq + std::hash::Hash + Send + Sync + 'static {
+    /// Rebuild the ID from its UUID
+    fn from_uuid(uuid: Uuid) -> Self;
+    
+    /// Underlying UUID
+    fn uuid(&self) -> Uuid;
+}
+
+impl IndexId for NodeId {
+    fn from_uuid(uuid: Uuid) -> Self {
+        NodeId::from_uuid(uuid)
+    }
+    
+    fn uuid(&self) -> Uuid {
+        *self.as_uuid()
+    }
+}
+
+impl IndexId for EdgeId {
+    fn from_uuid(uuid: Uuid) -> Self {
+        EdgeId::from_uuid(uuid)
+    }
+    
+    fn uuid(&self) -> Uuid {
+        *self.as_uuid()
+    }
+}
 
 /// Trait for index implementations
-pub trait Index: Send + Sync {
+///
+/// Every method takes `&self`: implementations synchronize internally
+/// (sharded maps, lock-free trees), so a shared index can be written from
+/// many threads at once without an outer lock.
+pub trait Index<Id: IndexId = NodeId>: Send + Sync {
     /// Insert a key-value pair into the index
-    fn insert(&mut self, key: Vec<u8>, value: NodeId) -> Result<()>;
+    fn insert(&self, key: Vec<u8>, value: Id) -> Result<()>;
     
     /// Remove a key-value pair from the index
-    fn remove(&mut self, key: &[u8], value: NodeId) -> Result<()>;
+    fn remove(&self, key: &[u8], value: Id) -> Result<()>;
     
     /// Lookup values by exact key
-    fn lookup(&self, key: &[u8]) -> Result<Vec<NodeId>>;
+    fn lookup(&self, key: &[u8]) -> Result<Vec<Id>>;
     
     /// Range query (for indices that support it)
-    fn range(&self, start: &[u8], end: &[u8]) -> Result<Vec<NodeId>>;
+    fn range(&self, start: &[u8], end: &[u8]) -> Result<Vec<Id>>;
     
     /// Get all keys in the index
     fn keys(&self) -> Result<Vec<Vec<u8>>>;
     
+    /// Get every (key, value) pair in the index
+    fn entries(&self) -> Result<Vec<(Vec<u8>, Id)>> {
+        let mut entries = Vec::new();
+        for key in self.keys()? {
+            for id in self.lookup(&key)? {
+                entries.push((key.clone(), id));
+            }
+        }
+        Ok(entries)
+    }
+    
     /// Clear the index
-    fn clear(&mut self) -> Result<()>;
+    fn clear(&self) -> Result<()>;
     
     /// Get index size (number of entries)
     fn len(&self) -> usize;
@@ -0,0 +1,453 @@
+//! Vector similarity index for node embeddings
+//!
+//! [`VectorIndex`] stores one float vector per node (for example a Node2Vec
+//! embedding) and answers approximate k-nearest-neighbor queries with a
+//! hierarchical navigable small world (HNSW) graph: every vector is linked
+//! to its closest neighbors on a random number of layers, and a search
+//! descends greedily from the sparse top layer to the dense bottom one.
+//!
+//! Removed vectors stay in the graph as routing points until they
+//! outnumber the live ones, at which point the graph is rebuilt.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut index = VectorIndex::new(128, DistanceMetric::Cosine);
+//! index.insert(node_id, embedding)?;
+//! let nearest = index.search(&query, 10)?;
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::{NodeId, PropertyValue};
+use log::debug;
+use rand::Rng;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Highest layer a vector can be placed on
+const MAX_LEVEL: usize = 16;
+
+/// How vector distance is measured; smaller is always closer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DistanceMetric {
+    /// `1 - cosine similarity`
+    #[default]
+    Cosine,
+    /// Straight-line distance
+    Euclidean,
+    /// Negated dot product, for vectors normalized by the caller
+    DotProduct,
+}
+
+impl DistanceMetric {
+    /// Distance between two vectors of equal length
+    pub fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            DistanceMetric::Cosine => {
+                let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+                for (x, y) in a.iter().zip(b) {
+                    dot += x * y;
+                    norm_a += x * x;
+                    norm_b += y * y;
+                }
+                if norm_a == 0.0 || norm_b == 0.0 {
+                    1.0
+                } else {
+                    1.0 - dot / (norm_a.sqrt() * norm_b.sqrt())
+                }
+            }
+            DistanceMetric::Euclidean => a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt(),
+            DistanceMetric::DotProduct => -a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>(),
+        }
+    }
+}
+
+/// HNSW construction and search parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HnswParams {
+    /// Links per vector on upper layers (twice as many on the bottom layer)
+    pub m: usize,
+    /// Candidates considered while linking a new vector
+    pub ef_construction: usize,
+    /// Candidates considered per query; raised to `k` when smaller
+    pub ef_search: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 100,
+            ef_search: 50,
+        }
+    }
+}
+
+/// A node returned by a vector search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VectorMatch {
+    pub node_id: NodeId,
+    /// Distance to the query under the index's metric
+    pub distance: f32,
+}
+
+/// Approximate nearest-neighbor index over per-node vectors
+pub struct VectorIndex {
+    dimensions: usize,
+    metric: DistanceMetric,
+    params: HnswParams,
+    /// Slot -> vector
+    vectors: Vec<Vec<f32>>,
+    /// Slot -> node
+    owners: Vec<NodeId>,
+    /// Slot -> whether the vector was removed or replaced
+    removed: Vec<bool>,
+    /// Node -> slot of its live vector
+    slots: HashMap<NodeId, usize>,
+    /// Slot -> layer -> neighbor slots
+    links: Vec<Vec<Vec<usize>>>,
+    entry_point: Option<usize>,
+    top_level: usize,
+}
+
+/// Slot with its distance to a query, ordered by distance
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    slot: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.slot.cmp(&other.slot))
+    }
+}
+
+impl VectorIndex {
+    /// Create an empty index for vectors of `dimensions` floats
+    pub fn new(dimensions: usize, metric: DistanceMetric) -> Self {
+        Self {
+            dimensions,
+            metric,
+            params: HnswParams::default(),
+            vectors: Vec::new(),
+            owners: Vec::new(),
+            removed: Vec::new(),
+            slots: HashMap::new(),
+            links: Vec::new(),
+            entry_point: None,
+            top_level: 0,
+        }
+    }
+
+    /// Set the HNSW parameters
+    pub fn with_params(mut self, params: HnswParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Vector length the index accepts
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Distance metric
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    /// Number of nodes with a vector
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Whether no node has a vector
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Vector stored for a node
+    pub fn get(&self, node_id: NodeId) -> Option<&[f32]> {
+        self.slots.get(&node_id).map(|&slot| self.vectors[slot].as_slice())
+    }
+
+    /// Store a node's vector, replacing any previous one
+    pub fn insert(&mut self, node_id: NodeId, vector: Vec<f32>) -> Result<()> {
+        self.check_vector(&vector)?;
+        if let Some(old) = self.slots.remove(&node_id) {
+            self.removed[old] = true;
+        }
+
+        let slot = self.vectors.len();
+        let level = self.random_level();
+        self.vectors.push(vector);
+        self.owners.push(node_id);
+        self.removed.push(false);
+        self.links.push(vec![Vec::new(); level + 1]);
+        self.slots.insert(node_id, slot);
+
+        let Some(mut entry) = self.entry_point else {
+            self.entry_point = Some(slot);
+            self.top_level = level;
+            return Ok(());
+        };
+
+        let query = self.vectors[slot].clone();
+        for layer in (level + 1..=self.top_level).rev() {
+            entry = self.search_layer(&query, &[entry], 1, layer)[0].slot;
+        }
+
+        let mut entries = vec![entry];
+        for layer in (0..=level.min(self.top_level)).rev() {
+            let found = self.search_layer(&query, &entries, self.params.ef_construction, layer);
+            let neighbors: Vec<usize> = found.iter().map(|c| c.slot).take(self.params.m).collect();
+            let max_links = if layer == 0 { 2 * self.params.m } else { self.params.m };
+            for &neighbor in &neighbors {
+                self.links[neighbor][layer].push(slot);
+                if self.links[neighbor][layer].len() > max_links {
+                    self.prune(neighbor, layer, max_links);
+                }
+            }
+            self.links[slot][layer] = neighbors;
+            entries = found.iter().map(|c| c.slot).collect();
+        }
+
+        if level > self.top_level {
+            self.top_level = level;
+            self.entry_point = Some(slot);
+        }
+        self.compact_if_sparse();
+        Ok(())
+    }
+
+    /// Remove a node's vector; returns whether it had one
+    pub fn remove(&mut self, node_id: NodeId) -> bool {
+        match self.slots.remove(&node_id) {
+            Some(slot) => {
+                self.removed[slot] = true;
+                self.compact_if_sparse();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The `k` nodes whose vectors are closest to `query`, closest first
+    pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<VectorMatch>> {
+        self.check_vector(query)?;
+        let Some(mut entry) = self.entry_point else {
+            return Ok(Vec::new());
+        };
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+
+        for layer in (1..=self.top_level).rev() {
+            entry = self.search_layer(query, &[entry], 1, layer)[0].slot;
+        }
+        // Removed vectors still occupy candidate slots
+        let tombstones = self.vectors.len() - self.slots.len();
+        let ef = self.params.ef_search.max(k) + tombstones;
+        Ok(self
+            .search_layer(query, &[entry], ef, 0)
+            .into_iter()
+            .filter(|c| !self.removed[c.slot])
+            .take(k)
+            .map(|c| VectorMatch {
+                node_id: self.owners[c.slot],
+                distance: c.distance,
+            })
+            .collect())
+    }
+
+    /// Best-first search of one layer, returning up to `ef` slots closest first
+    fn search_layer(&self, query: &[f32], entries: &[usize], ef: usize, layer: usize) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entries.iter().copied().collect();
+        let mut frontier = BinaryHeap::new();
+        let mut nearest = BinaryHeap::new();
+        for &slot in entries {
+            let candidate = Candidate {
+                distance: self.metric.distance(query, &self.vectors[slot]),
+                slot,
+            };
+            frontier.push(Reverse(candidate));
+            nearest.push(candidate);
+        }
+
+        while let Some(Reverse(current)) = frontier.pop() {
+            let furthest = nearest.peek().map_or(f32::INFINITY, |c: &Candidate| c.distance);
+            if current.distance > furthest && nearest.len() >= ef {
+                break;
+            }
+            for &neighbor in &self.links[current.slot][layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let distance = self.metric.distance(query, &self.vectors[neighbor]);
+                let furthest = nearest.peek().map_or(f32::INFINITY, |c: &Candidate| c.distance);
+                if nearest.len() < ef || distance < furthest {
+                    let candidate = Candidate { distance, slot: neighbor };
+                    frontier.push(Reverse(candidate));
+                    nearest.push(candidate);
+                    if nearest.len() > ef {
+                        nearest.pop();
+                    }
+                }
+            }
+        }
+        nearest.into_sorted_vec()
+    }
+
+    /// Keep only the `max_links` closest neighbors of a slot on a layer
+    fn prune(&mut self, slot: usize, layer: usize, max_links: usize) {
+        let base = &self.vectors[slot];
+        let mut scored: Vec<Candidate> = self.links[slot][layer]
+            .iter()
+            .map(|&neighbor| Candidate {
+                distance: self.metric.distance(base, &self.vectors[neighbor]),
+                slot: neighbor,
+            })
+            .collect();
+        scored.sort();
+        scored.truncate(max_links);
+        self.links[slot][layer] = scored.into_iter().map(|c| c.slot).collect();
+    }
+
+    /// Rebuild from the live vectors once removed ones outnumber them
+    fn compact_if_sparse(&mut self) {
+        let tombstones = self.vectors.len() - self.slots.len();
+        if tombstones <= self.slots.len().max(64) {
+            return;
+        }
+        debug!("Rebuilding vector index: {} live, {} removed", self.slots.len(), tombstones);
+        let mut live: Vec<(usize, NodeId)> = self.slots.iter().map(|(&id, &slot)| (slot, id)).collect();
+        live.sort_unstable_by_key(|&(slot, _)| slot);
+        let vectors = std::mem::take(&mut self.vectors);
+        let mut rebuilt = VectorIndex::new(self.dimensions, self.metric).with_params(self.params);
+        for (slot, node_id) in live {
+            // Lengths were checked on the first insert
+            let _ = rebuilt.insert(node_id, vectors[slot].clone());
+        }
+        *self = rebuilt;
+    }
+
+    fn random_level(&self) -> usize {
+        let scale = 1.0 / (self.params.m.max(2) as f64).ln();
+        let uniform: f64 = 1.0 - rand::thread_rng().gen::<f64>();
+        ((-uniform.ln() * scale).floor() as usize).min(MAX_LEVEL)
+    }
+
+    fn check_vector(&self, vector: &[f32]) -> Result<()> {
+        if vector.len() != self.dimensions {
+            return Err(DeepGraphError::InvalidOperation(format!(
+                "Vector has {} dimensions, index expects {}",
+                vector.len(),
+                self.dimensions
+            )));
+        }
+        if vector.iter().any(|x| !x.is_finite()) {
+            return Err(DeepGraphError::InvalidOperation("Vector contains NaN or infinite values".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Read a vector from a list property of numbers
+pub fn vector_from_property(value: &PropertyValue) -> Option<Vec<f32>> {
+    match value {
+        PropertyValue::List(items) => items
+            .iter()
+            .map(|item| match item {
+                PropertyValue::Float(f) => Some(*f as f32),
+                PropertyValue::Integer(i) => Some(*i as f32),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random vectors
+    fn vectors(count: usize, dimensions: usize) -> Vec<Vec<f32>> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        (0..count)
+            .map(|_| {
+                (0..dimensions)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        (state % 10_000) as f32 / 10_000.0 - 0.5
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_knn_matches_brute_force() {
+        let data = vectors(300, 8);
+        let ids: Vec<NodeId> = data.iter().map(|_| NodeId::new()).collect();
+        let mut index = VectorIndex::new(8, DistanceMetric::Euclidean);
+        for (id, vector) in ids.iter().zip(&data) {
+            index.insert(*id, vector.clone()).unwrap();
+        }
+        assert_eq!(index.len(), 300);
+
+        let query = &data[42];
+        let found = index.search(query, 10).unwrap();
+        assert_eq!(found[0].node_id, ids[42]);
+        assert_eq!(found[0].distance, 0.0);
+
+        let mut exact: Vec<(f32, NodeId)> = ids
+            .iter()
+            .zip(&data)
+            .map(|(id, v)| (DistanceMetric::Euclidean.distance(query, v), *id))
+            .collect();
+        exact.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let expected: HashSet<NodeId> = exact.iter().take(10).map(|(_, id)| *id).collect();
+        let recall = found.iter().filter(|m| expected.contains(&m.node_id)).count();
+        assert!(recall >= 9, "recall {}/10", recall);
+
+        assert!(index.search(&[0.0; 3], 1).is_err());
+    }
+
+    #[test]
+    fn test_replace_and_remove() {
+        let mut index = VectorIndex::new(2, DistanceMetric::Cosine);
+        let a = NodeId::new();
+        let b = NodeId::new();
+        index.insert(a, vec![1.0, 0.0]).unwrap();
+        index.insert(b, vec![0.0, 1.0]).unwrap();
+        assert_eq!(index.search(&[1.0, 0.1], 1).unwrap()[0].node_id, a);
+
+        index.insert(a, vec![-1.0, 0.0]).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.search(&[1.0, 0.1], 1).unwrap()[0].node_id, b);
+
+        assert!(index.remove(b));
+        let found = index.search(&[1.0, 0.1], 5).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].node_id, a);
+    }
+}
@@ -11,6 +11,9 @@
 //! - `query`: Query planning and execution
 //! - `wal`: Write-ahead logging for durability
 //! - `mvcc`: Multi-version concurrency control
+//! - `metrics`: Operator-level counters in Prometheus format
+//! - `catalog`: Multiple named graphs in one process
+//! - `database`: [`DeepGraph::open`] builds all subsystems from a config
 
 pub mod graph;
 pub mod storage;
@@ -18,7 +21,12 @@ pub mod parser;
 pub mod transaction;
 pub mod error;
 pub mod config;
+pub mod encryption;
 pub mod import;
+pub mod export;
+pub mod metrics;
+pub mod catalog;
+pub mod database;
 
 // Phase 2 modules
 pub mod persistence;
@@ -35,8 +43,10 @@ pub mod algorithms;
 pub mod python;
 
 pub use error::{DeepGraphError, Result};
-pub use graph::{Node, Edge, Property, PropertyValue, NodeId, EdgeId};
+pub use graph::{Node, Edge, Property, PropertyValue, NodeId, EdgeId, EXPIRES_AT_PROPERTY};
 pub use storage::{GraphStorage, StorageBackend};
 pub use transaction::Transaction;
 pub use config::DeepGraphConfig;
+pub use catalog::{Catalog, Session};
+pub use database::DeepGraph;
 
@@ -0,0 +1,167 @@
+//! Operator-level metrics in Prometheus text format
+//!
+//! Counts internal events per operator, so capacity planning can look at
+//! what the engine is doing rather than only at latency:
+//!
+//! - spills of operator state to disk
+//! - hash-table resizes
+//! - index lookups that fell back to full scans
+//! - transactions retried after a conflict
+//! - nodes and edges removed because their TTL ran out
+//!
+//! Components record into the process-wide [`global`] registry;
+//! [`MetricsRegistry::render_prometheus`] produces the text exposition
+//! format for a scrape endpoint.
+
+use dashmap::DashMap;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+/// Kind of internal event counted per operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OperatorEvent {
+    /// Operator state written to disk because it exceeded memory
+    Spill,
+    /// A hash table grew its capacity
+    HashResize,
+    /// An index lookup was executed as a full scan
+    IndexFallback,
+    /// A transaction was re-run after a conflict
+    TxnRetry,
+    /// An element was removed because it expired
+    Expired,
+    /// A cache entry was evicted to stay within a size limit
+    Evicted,
+    /// An MVCC version no snapshot could see was removed
+    VersionReclaimed,
+}
+
+impl OperatorEvent {
+    /// All events, in exposition order
+    pub const ALL: [OperatorEvent; 7] = [
+        OperatorEvent::Spill,
+        OperatorEvent::HashResize,
+        OperatorEvent::IndexFallback,
+        OperatorEvent::TxnRetry,
+        OperatorEvent::Expired,
+        OperatorEvent::Evicted,
+        OperatorEvent::VersionReclaimed,
+    ];
+
+    /// Prometheus metric name
+    pub fn metric_name(&self) -> &'static str {
+        match self {
+            OperatorEvent::Spill => "deepgraph_operator_spills_total",
+            OperatorEvent::HashResize => "deepgraph_operator_hash_resizes_total",
+            OperatorEvent::IndexFallback => "deepgraph_operator_index_fallbacks_total",
+            OperatorEvent::TxnRetry => "deepgraph_operator_txn_retries_total",
+            OperatorEvent::Expired => "deepgraph_operator_expired_total",
+            OperatorEvent::Evicted => "deepgraph_operator_evictions_total",
+            OperatorEvent::VersionReclaimed => "deepgraph_operator_versions_reclaimed_total",
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self {
+            OperatorEvent::Spill => "Operator state spilled to disk",
+            OperatorEvent::HashResize => "Hash table capacity increases",
+            OperatorEvent::IndexFallback => "Index lookups executed as full scans",
+            OperatorEvent::TxnRetry => "Transactions re-run after a conflict",
+            OperatorEvent::Expired => "Elements removed after their TTL ran out",
+            OperatorEvent::Evicted => "Cache entries evicted to stay within a size limit",
+            OperatorEvent::VersionReclaimed => "MVCC versions removed by vacuum",
+        }
+    }
+}
+
+/// Per-operator event counters
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    counters: DashMap<(OperatorEvent, String), u64>,
+}
+
+impl MetricsRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one event for an operator
+    pub fn record(&self, event: OperatorEvent, operator: &str) {
+        self.record_n(event, operator, 1);
+    }
+
+    /// Count `n` events for an operator
+    pub fn record_n(&self, event: OperatorEvent, operator: &str, n: u64) {
+        if n == 0 {
+            return;
+        }
+        *self.counters.entry((event, operator.to_string())).or_insert(0) += n;
+    }
+
+    /// Current count for an operator
+    pub fn get(&self, event: OperatorEvent, operator: &str) -> u64 {
+        self.counters
+            .get(&(event, operator.to_string()))
+            .map(|count| *count)
+            .unwrap_or(0)
+    }
+
+    /// Snapshot of all counters, ordered by event then operator
+    pub fn snapshot(&self) -> BTreeMap<(OperatorEvent, String), u64> {
+        self.counters
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect()
+    }
+
+    /// Reset all counters
+    pub fn reset(&self) {
+        self.counters.clear();
+    }
+
+    /// Render all counters in the Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let snapshot = self.snapshot();
+        let mut out = String::new();
+        for event in OperatorEvent::ALL {
+            let name = event.metric_name();
+            let _ = writeln!(out, "# HELP {} {}", name, event.help());
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            for ((_, operator), count) in snapshot.iter().filter(|((e, _), _)| *e == event) {
+                let _ = writeln!(out, "{}{{operator=\"{}\"}} {}", name, escape_label(operator), count);
+            }
+        }
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Process-wide registry that engine components record into
+pub fn global() -> &'static MetricsRegistry {
+    static GLOBAL: OnceLock<MetricsRegistry> = OnceLock::new();
+    GLOBAL.get_or_init(MetricsRegistry::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let registry = MetricsRegistry::new();
+        registry.record(OperatorEvent::IndexFallback, "IndexLookup");
+        registry.record_n(OperatorEvent::TxnRetry, "DiskStorage", 3);
+        assert_eq!(registry.get(OperatorEvent::TxnRetry, "DiskStorage"), 3);
+
+        let text = registry.render_prometheus();
+        assert!(text.contains("# TYPE deepgraph_operator_spills_total counter"));
+        assert!(text.contains("deepgraph_operator_index_fallbacks_total{operator=\"IndexLookup\"} 1"));
+        assert!(text.contains("deepgraph_operator_txn_retries_total{operator=\"DiskStorage\"} 3"));
+        assert!(!text.contains("deepgraph_operator_hash_resizes_total{"));
+    }
+}
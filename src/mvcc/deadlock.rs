@@ -4,6 +4,7 @@
 
 use crate::error::{DeepGraphError, Result};
 use crate::mvcc::TransactionId;
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
@@ -30,51 +31,62 @@ impl DeadlockDetector {
     }
     
     /// Request a lock on a resource
+    ///
+    /// Fails immediately if another transaction holds it; use a
+    /// [`LockManager`](crate::mvcc::LockManager) to wait instead.
     pub fn request_lock(
         &self,
         txn_id: TransactionId,
         resource_id: ResourceId,
     ) -> Result<()> {
-        // Check if resource is already locked
-        if let Some(holder) = self.lock_holders.get(&resource_id) {
-            let holder_id = *holder;
-            
-            // If we're the holder, it's a re-entrant lock (OK)
-            if holder_id == txn_id {
+        // Check if resource is already locked, granting it atomically if not
+        let holder_id = match self.lock_holders.entry(resource_id) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => {
+                entry.insert(txn_id);
                 return Ok(());
             }
-            
-            // Add wait-for edge
-            self.wait_for
-                .entry(txn_id)
-                .or_insert_with(HashSet::new)
-                .insert(holder_id);
-            
-            // Check for deadlock
-            if self.has_cycle(txn_id)? {
-                // Remove the wait-for edge we just added
-                if let Some(mut entry) = self.wait_for.get_mut(&txn_id) {
-                    entry.remove(&holder_id);
-                }
-                
-                return Err(DeepGraphError::TransactionError(format!(
-                    "Deadlock detected: transaction {:?} waiting for {:?}",
-                    txn_id, holder_id
-                )));
+        };
+        
+        // If we're the holder, it's a re-entrant lock (OK)
+        if holder_id == txn_id {
+            return Ok(());
+        }
+        
+        // Add wait-for edge, failing if it closes a cycle
+        self.add_wait(txn_id, holder_id)?;
+        
+        // Would need to wait (in real system, this would block)
+        Err(DeepGraphError::TransactionError(format!(
+            "Resource locked by {:?}",
+            holder_id
+        )))
+    }
+    
+    /// Record that `waiter` waits for `holder`
+    ///
+    /// Fails, leaving the wait-for graph unchanged, if the wait would close
+    /// a cycle; the caller should then abort `waiter`.
+    pub fn add_wait(&self, waiter: TransactionId, holder: TransactionId) -> Result<()> {
+        self.wait_for.entry(waiter).or_insert_with(HashSet::new).insert(holder);
+        
+        if self.has_cycle(waiter)? {
+            if let Some(mut entry) = self.wait_for.get_mut(&waiter) {
+                entry.remove(&holder);
             }
-            
-            // Would need to wait (in real system, this would block)
             return Err(DeepGraphError::TransactionError(format!(
-                "Resource locked by {:?}",
-                holder_id
+                "Deadlock detected: transaction {:?} waiting for {:?}",
+                waiter, holder
             )));
         }
-        
-        // Lock is available, grant it
-        self.lock_holders.insert(resource_id, txn_id);
         Ok(())
     }
     
+    /// Forget every wait of `waiter`, once it got its lock or gave up
+    pub fn clear_waits(&self, waiter: TransactionId) {
+        self.wait_for.remove(&waiter);
+    }
+    
     /// Release a lock on a resource
     pub fn release_lock(&self, _txn_id: TransactionId, resource_id: ResourceId) {
         self.lock_holders.remove(&resource_id);
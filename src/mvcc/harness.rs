@@ -0,0 +1,423 @@
+//! Concurrency test harness for the MVCC layer
+//!
+//! Runs randomized concurrent [`Transaction`]s over a small key-value
+//! register, one node per key in [`VersionedStorage`], then checks every
+//! observed read against a sequential model of snapshot isolation. Commits
+//! are checked too: a transaction may not overwrite a key that a concurrent
+//! transaction committed after it began (first committer wins), or that
+//! update is lost.
+//!
+//! Begin and commit are recorded under a single lock so the history has a
+//! total order to replay; reads and writes run fully concurrently.
+//!
+//! [`VersionedStorage`]: crate::mvcc::VersionedStorage
+//!
+//! Only compiled with the `concurrency-testing` feature.
+
+use crate::error::DeepGraphError;
+use crate::graph::{Node, NodeId, PropertyValue};
+use crate::mvcc::TransactionId;
+use crate::storage::GraphStorage;
+use crate::transaction::{Transaction, TransactionManager};
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Workload parameters for a harness run
+#[derive(Debug, Clone)]
+pub struct HarnessConfig {
+    /// Number of concurrent worker threads
+    pub threads: usize,
+    /// Transactions run by each thread
+    pub txns_per_thread: usize,
+    /// Number of distinct keys
+    pub keys: u64,
+    /// Reads/writes per transaction
+    pub ops_per_txn: usize,
+    /// Fraction of operations that are writes
+    pub write_ratio: f64,
+    /// Fraction of transactions that abort voluntarily
+    pub abort_ratio: f64,
+    /// Base RNG seed (each thread derives its own)
+    pub seed: u64,
+}
+
+impl Default for HarnessConfig {
+    fn default() -> Self {
+        Self {
+            threads: 4,
+            txns_per_thread: 200,
+            keys: 8,
+            ops_per_txn: 4,
+            write_ratio: 0.5,
+            abort_ratio: 0.0,
+            seed: 42,
+        }
+    }
+}
+
+impl HarnessConfig {
+    /// Create a config with defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of worker threads
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Set the transactions per thread
+    pub fn with_txns_per_thread(mut self, txns: usize) -> Self {
+        self.txns_per_thread = txns;
+        self
+    }
+
+    /// Set the number of keys (fewer keys means more contention)
+    pub fn with_keys(mut self, keys: u64) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Set the fraction of voluntary aborts
+    pub fn with_abort_ratio(mut self, ratio: f64) -> Self {
+        self.abort_ratio = ratio;
+        self
+    }
+
+    /// Set the RNG seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Kind of snapshot isolation violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// A read returned something other than the snapshot's value
+    StaleRead,
+    /// A commit overwrote a value committed concurrently
+    LostUpdate,
+}
+
+/// A read or commit that disagrees with the sequential model
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    /// What went wrong
+    pub kind: AnomalyKind,
+    /// Transaction that performed the read or commit
+    pub txn: TransactionId,
+    /// Key that was read or overwritten
+    pub key: u64,
+    /// Value the model says the snapshot should contain
+    pub expected: Option<i64>,
+    /// Value actually returned, or the concurrent value a commit overwrote
+    pub observed: Option<i64>,
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self.kind {
+            AnomalyKind::StaleRead => "read",
+            AnomalyKind::LostUpdate => "overwrote",
+        };
+        write!(
+            f,
+            "txn {:?} {} key {}: expected {:?}, observed {:?}",
+            self.txn, action, self.key, self.expected, self.observed
+        )
+    }
+}
+
+/// Outcome of a harness run
+#[derive(Debug, Clone, Default)]
+pub struct HarnessReport {
+    /// Committed transactions
+    pub committed: usize,
+    /// Voluntarily aborted transactions
+    pub aborted: usize,
+    /// Transactions whose commit was rejected as a write-write conflict
+    pub conflicts: usize,
+    /// Reads and commits that violate snapshot isolation
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl HarnessReport {
+    /// Whether every read and commit matched the model
+    pub fn is_consistent(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    Read { key: u64, observed: Option<i64> },
+    Write { key: u64, value: i64 },
+}
+
+#[derive(Debug, Clone)]
+enum Event {
+    Begin(TransactionId),
+    Commit { txn: TransactionId, writes: Vec<(u64, i64)> },
+}
+
+enum Outcome {
+    Committed,
+    Aborted,
+    Conflict,
+}
+
+/// Property of a key's node holding its value
+const VALUE: &str = "value";
+
+/// Key-value register under test
+struct Register {
+    manager: TransactionManager,
+    /// Node storing each key
+    nodes: Vec<NodeId>,
+    /// Totally ordered begin/commit history
+    history: Mutex<Vec<Event>>,
+}
+
+impl Register {
+    fn new(keys: u64) -> Self {
+        let storage = GraphStorage::new();
+        let nodes = (0..keys)
+            .map(|_| storage.add_node(Node::new(vec![])).expect("add_node failed"))
+            .collect();
+        Self {
+            manager: TransactionManager::new(std::sync::Arc::new(storage)),
+            nodes,
+            history: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn begin(&self) -> Transaction {
+        let mut history = self.history.lock();
+        let txn = self
+            .manager
+            .begin_transaction()
+            .expect("begin_transaction failed");
+        history.push(Event::Begin(txn.id()));
+        txn
+    }
+
+    fn read(&self, txn: &Transaction, key: u64) -> Option<i64> {
+        let node = txn.get_node(self.nodes[key as usize]).expect("get_node failed");
+        match node.get_property(VALUE) {
+            Some(PropertyValue::Integer(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn write(&self, txn: &mut Transaction, key: u64, value: i64) {
+        let mut node = txn.get_node(self.nodes[key as usize]).expect("get_node failed");
+        node.set_property(VALUE.to_string(), PropertyValue::Integer(value));
+        txn.update_node(node).expect("update_node failed");
+    }
+
+    /// Commit `txn`, returning false if it lost a write-write conflict
+    fn commit(&self, txn: Transaction, writes: Vec<(u64, i64)>) -> bool {
+        let mut history = self.history.lock();
+        let id = txn.id();
+        match txn.commit() {
+            Ok(()) => {
+                history.push(Event::Commit { txn: id, writes });
+                true
+            }
+            Err(DeepGraphError::TransactionError(message)) if message.starts_with("Serialization failure") => false,
+            Err(e) => panic!("commit of {:?} failed: {}", id, e),
+        }
+    }
+
+    fn run_txn(&self, rng: &mut StdRng, config: &HarnessConfig) -> (TransactionId, Vec<Op>, Outcome) {
+        let mut txn = self.begin();
+        let id = txn.id();
+        let mut ops = Vec::with_capacity(config.ops_per_txn);
+        let mut writes = Vec::new();
+
+        for i in 0..config.ops_per_txn {
+            let key = rng.gen_range(0..config.keys);
+            if rng.gen_bool(config.write_ratio) {
+                // Values are unique per write so reads identify their writer
+                let value = (id.0 * 1000 + i as u64) as i64;
+                self.write(&mut txn, key, value);
+                writes.push((key, value));
+                ops.push(Op::Write { key, value });
+            } else {
+                let observed = self.read(&txn, key);
+                ops.push(Op::Read { key, observed });
+            }
+        }
+
+        if rng.gen_bool(config.abort_ratio) {
+            txn.rollback().expect("rollback failed");
+            return (id, ops, Outcome::Aborted);
+        }
+
+        if self.commit(txn, writes) {
+            (id, ops, Outcome::Committed)
+        } else {
+            (id, ops, Outcome::Conflict)
+        }
+    }
+}
+
+/// Run a randomized concurrent workload and check it against the model
+pub fn run(config: &HarnessConfig) -> HarnessReport {
+    let register = Register::new(config.keys);
+    let mut report = HarnessReport::default();
+    let mut txn_ops: HashMap<TransactionId, Vec<Op>> = HashMap::new();
+
+    let results: Vec<Vec<(TransactionId, Vec<Op>, Outcome)>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..config.threads)
+            .map(|thread| {
+                let register = &register;
+                scope.spawn(move || {
+                    let mut rng = StdRng::seed_from_u64(config.seed.wrapping_add(thread as u64));
+                    (0..config.txns_per_thread)
+                        .map(|_| register.run_txn(&mut rng, config))
+                        .collect()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("harness worker panicked"))
+            .collect()
+    });
+
+    for (txn, ops, outcome) in results.into_iter().flatten() {
+        match outcome {
+            Outcome::Committed => report.committed += 1,
+            Outcome::Aborted => report.aborted += 1,
+            Outcome::Conflict => report.conflicts += 1,
+        }
+        txn_ops.insert(txn, ops);
+    }
+
+    report.anomalies = check(&register.history.lock(), &txn_ops);
+    report
+}
+
+/// Replay the history sequentially and compare every read and commit
+fn check(history: &[Event], txn_ops: &HashMap<TransactionId, Vec<Op>>) -> Vec<Anomaly> {
+    let mut state: HashMap<u64, i64> = HashMap::new();
+    let mut snapshots: HashMap<TransactionId, HashMap<u64, i64>> = HashMap::new();
+    // Position in the history of each begin and of each key's last commit
+    let mut began: HashMap<TransactionId, usize> = HashMap::new();
+    let mut last_commit: HashMap<u64, usize> = HashMap::new();
+    let mut anomalies = Vec::new();
+
+    for (position, event) in history.iter().enumerate() {
+        match event {
+            Event::Begin(txn) => {
+                snapshots.insert(*txn, state.clone());
+                began.insert(*txn, position);
+            }
+            Event::Commit { txn, writes } => {
+                let start = began.get(txn).copied().unwrap_or(0);
+                for (key, _) in writes {
+                    if last_commit.get(key).is_some_and(|committed| *committed > start) {
+                        anomalies.push(Anomaly {
+                            kind: AnomalyKind::LostUpdate,
+                            txn: *txn,
+                            key: *key,
+                            expected: snapshots.get(txn).and_then(|snapshot| snapshot.get(key)).copied(),
+                            observed: state.get(key).copied(),
+                        });
+                    }
+                }
+                for (key, value) in writes {
+                    state.insert(*key, *value);
+                    last_commit.insert(*key, position);
+                }
+            }
+        }
+    }
+
+    for (txn, ops) in txn_ops {
+        let mut local = snapshots.remove(txn).unwrap_or_default();
+        for op in ops {
+            match op {
+                Op::Write { key, value } => {
+                    local.insert(*key, *value);
+                }
+                Op::Read { key, observed } => {
+                    let expected = local.get(key).copied();
+                    if expected != *observed {
+                        anomalies.push(Anomaly {
+                            kind: AnomalyKind::StaleRead,
+                            txn: *txn,
+                            key: *key,
+                            expected,
+                            observed: *observed,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harness_single_thread_is_consistent() {
+        let report = run(&HarnessConfig::new().with_threads(1).with_txns_per_thread(100));
+        assert_eq!(report.committed, 100);
+        assert!(report.is_consistent(), "{:?}", report.anomalies);
+    }
+
+    #[test]
+    fn test_harness_concurrent_snapshot_isolation() {
+        let report = run(&HarnessConfig::new().with_threads(8).with_keys(4));
+        assert!(report.committed > 0);
+        assert!(report.is_consistent(), "{:?}", report.anomalies);
+    }
+
+    #[test]
+    fn test_checker_detects_stale_read() {
+        let (t1, t2) = (TransactionId(1), TransactionId(2));
+        let history = vec![
+            Event::Begin(t1),
+            Event::Commit { txn: t1, writes: vec![(0, 7)] },
+            Event::Begin(t2),
+        ];
+        let mut ops = HashMap::new();
+        ops.insert(t1, vec![Op::Write { key: 0, value: 7 }]);
+        ops.insert(t2, vec![Op::Read { key: 0, observed: None }]);
+
+        let anomalies = check(&history, &ops);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].kind, AnomalyKind::StaleRead);
+        assert_eq!(anomalies[0].expected, Some(7));
+    }
+
+    #[test]
+    fn test_checker_detects_lost_update() {
+        let (t1, t2) = (TransactionId(1), TransactionId(2));
+        let history = vec![
+            Event::Begin(t1),
+            Event::Begin(t2),
+            Event::Commit { txn: t1, writes: vec![(0, 7)] },
+            Event::Commit { txn: t2, writes: vec![(0, 8)] },
+        ];
+        let mut ops = HashMap::new();
+        ops.insert(t1, vec![Op::Write { key: 0, value: 7 }]);
+        ops.insert(t2, vec![Op::Write { key: 0, value: 8 }]);
+
+        let anomalies = check(&history, &ops);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].kind, AnomalyKind::LostUpdate);
+        assert_eq!(anomalies[0].txn, t2);
+        assert_eq!(anomalies[0].observed, Some(7));
+    }
+}
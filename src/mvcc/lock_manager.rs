@@ -0,0 +1,197 @@
+//! Blocking exclusive locks for transactions
+//!
+//! [`LockManager`] grants one transaction at a time a lock on each
+//! [`ResourceId`]. A transaction requesting a held lock waits until the
+//! holder releases it or the timeout passes. Waits are recorded in a
+//! [`DeadlockDetector`]; a request whose wait would close a cycle fails
+//! right away so the caller can abort that transaction and release its
+//! locks, which lets the others proceed.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let locks = LockManager::new().with_timeout(Duration::from_secs(2));
+//! locks.acquire(txn_id, ResourceId(42))?; // blocks while another txn holds it
+//! // ...
+//! locks.release_all(txn_id);
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use crate::mvcc::{DeadlockDetector, ResourceId, TransactionId};
+use log::debug;
+use parking_lot::{Condvar, Mutex};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a request waits for a held lock unless configured otherwise
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Exclusive locks with blocking waits, timeouts and deadlock detection
+pub struct LockManager {
+    /// Holder of each locked resource
+    holders: Mutex<HashMap<ResourceId, TransactionId>>,
+    /// Signalled whenever a lock is released
+    released: Condvar,
+    detector: DeadlockDetector,
+    timeout: Duration,
+}
+
+impl LockManager {
+    /// Create a lock manager waiting up to [`DEFAULT_LOCK_TIMEOUT`]
+    pub fn new() -> Self {
+        Self {
+            holders: Mutex::new(HashMap::new()),
+            released: Condvar::new(),
+            detector: DeadlockDetector::new(),
+            timeout: DEFAULT_LOCK_TIMEOUT,
+        }
+    }
+
+    /// Give up on a held lock after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// How long requests wait
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Lock `resource` for `txn`, waiting while another transaction holds it
+    ///
+    /// Re-acquiring a lock already held succeeds at once. Fails if the
+    /// timeout passes or waiting would deadlock.
+    pub fn acquire(&self, txn: TransactionId, resource: ResourceId) -> Result<()> {
+        let deadline = Instant::now() + self.timeout;
+        let mut holders = self.holders.lock();
+        loop {
+            let holder = match holders.get(&resource) {
+                None => {
+                    holders.insert(resource, txn);
+                    self.detector.clear_waits(txn);
+                    return Ok(());
+                }
+                Some(holder) if *holder == txn => {
+                    self.detector.clear_waits(txn);
+                    return Ok(());
+                }
+                Some(holder) => *holder,
+            };
+
+            // The holder may differ between wakeups; wait only for the current one
+            self.detector.clear_waits(txn);
+            self.detector.add_wait(txn, holder)?;
+            debug!("Transaction {} waiting for lock {:?} held by {}", txn, resource, holder);
+
+            if self.released.wait_until(&mut holders, deadline).timed_out()
+                && holders.get(&resource).is_some_and(|current| *current != txn)
+            {
+                self.detector.clear_waits(txn);
+                return Err(DeepGraphError::TransactionError(format!(
+                    "Timed out after {:?} waiting for lock {:?} held by transaction {}",
+                    self.timeout, resource, holder
+                )));
+            }
+        }
+    }
+
+    /// Lock `resource` only if it is free or already held by `txn`
+    pub fn try_acquire(&self, txn: TransactionId, resource: ResourceId) -> bool {
+        let mut holders = self.holders.lock();
+        *holders.entry(resource).or_insert(txn) == txn
+    }
+
+    /// Release one lock held by `txn`
+    pub fn release(&self, txn: TransactionId, resource: ResourceId) {
+        let mut holders = self.holders.lock();
+        if holders.get(&resource) == Some(&txn) {
+            holders.remove(&resource);
+            self.released.notify_all();
+        }
+    }
+
+    /// Release every lock of `txn`, on commit or abort
+    pub fn release_all(&self, txn: TransactionId) {
+        let mut holders = self.holders.lock();
+        holders.retain(|_, holder| *holder != txn);
+        self.detector.clear_waits(txn);
+        self.released.notify_all();
+    }
+
+    /// Transaction holding `resource`
+    pub fn holder(&self, resource: ResourceId) -> Option<TransactionId> {
+        self.holders.lock().get(&resource).copied()
+    }
+
+    /// Number of locked resources
+    pub fn locked_count(&self) -> usize {
+        self.holders.lock().len()
+    }
+
+    /// Number of transactions currently waiting for a lock
+    pub fn waiting_count(&self) -> usize {
+        self.detector.stats().waiting_transactions
+    }
+}
+
+impl Default for LockManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn wait_until_waiting(locks: &LockManager, count: usize) {
+        while locks.waiting_count() < count {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_waiter_is_woken_on_release() {
+        let locks = Arc::new(LockManager::new());
+        let (a, b) = (TransactionId(1), TransactionId(2));
+        locks.acquire(a, ResourceId(7)).unwrap();
+
+        let waiter = {
+            let locks = locks.clone();
+            std::thread::spawn(move || locks.acquire(b, ResourceId(7)))
+        };
+        wait_until_waiting(&locks, 1);
+        locks.release_all(a);
+
+        waiter.join().unwrap().unwrap();
+        assert_eq!(locks.holder(ResourceId(7)), Some(b));
+        assert_eq!(locks.waiting_count(), 0);
+    }
+
+    #[test]
+    fn test_timeout_and_deadlock() {
+        let locks = Arc::new(LockManager::new().with_timeout(Duration::from_millis(20)));
+        let (a, b) = (TransactionId(1), TransactionId(2));
+        locks.acquire(a, ResourceId(1)).unwrap();
+        locks.acquire(b, ResourceId(2)).unwrap();
+        assert!(locks.acquire(b, ResourceId(1)).is_err());
+        assert!(!locks.try_acquire(b, ResourceId(1)));
+
+        let locks = Arc::new(LockManager::new());
+        locks.acquire(a, ResourceId(1)).unwrap();
+        locks.acquire(b, ResourceId(2)).unwrap();
+        let waiter = {
+            let locks = locks.clone();
+            std::thread::spawn(move || locks.acquire(a, ResourceId(2)))
+        };
+        wait_until_waiting(&locks, 1);
+
+        // b waiting for a would close the cycle: b is refused, not blocked
+        let err = locks.acquire(b, ResourceId(1)).unwrap_err();
+        assert!(err.to_string().contains("Deadlock"));
+        locks.release_all(b);
+        waiter.join().unwrap().unwrap();
+    }
+}
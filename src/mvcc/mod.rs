@@ -6,11 +6,19 @@ pub mod version;
 pub mod snapshot;
 pub mod txn_manager;
 pub mod deadlock;
+pub mod lock_manager;
+pub mod versioned;
+pub mod vacuum;
+#[cfg(feature = "concurrency-testing")]
+pub mod harness;
 
 pub use version::{Version, VersionChain};
 pub use snapshot::Snapshot;
-pub use txn_manager::{TransactionManager, TransactionId, TransactionStatus};
+pub use txn_manager::{ActiveTransaction, TransactionManager, TransactionId, TransactionStatus};
 pub use deadlock::{DeadlockDetector, ResourceId};
+pub use lock_manager::LockManager;
+pub use versioned::VersionedStorage;
+pub use vacuum::{Vacuum, VacuumStats, DEFAULT_VACUUM_INTERVAL};
 
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -8,11 +8,18 @@ use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Transaction ID
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct TransactionId(pub u64);
 
+impl std::fmt::Display for TransactionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Transaction status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TransactionStatus {
@@ -33,6 +40,26 @@ struct TransactionInfo {
     commit_ts: Option<Timestamp>,
     /// Status
     status: TransactionStatus,
+    /// Lowest transaction ID this transaction's snapshot may not see as
+    /// committed: its own, or that of the oldest one active when it began
+    horizon: TransactionId,
+    /// Whether the transaction only reads
+    read_only: bool,
+    /// When the transaction began
+    started_at: Instant,
+}
+
+/// A running transaction, as listed by [`TransactionManager::active_transactions`]
+#[derive(Debug, Clone)]
+pub struct ActiveTransaction {
+    /// Transaction ID
+    pub id: TransactionId,
+    /// Start timestamp of its snapshot
+    pub start_ts: Timestamp,
+    /// Whether the transaction only reads
+    pub read_only: bool,
+    /// Time since the transaction began
+    pub age: Duration,
 }
 
 /// Transaction manager
@@ -51,6 +78,18 @@ impl TransactionManager {
     
     /// Begin a new transaction
     pub fn begin_transaction(&self) -> Result<(TransactionId, Snapshot)> {
+        self.begin(false)
+    }
+    
+    /// Begin a transaction that only reads
+    ///
+    /// It is registered like any other, so its snapshot stays readable
+    /// until it ends, but is listed as read-only.
+    pub fn begin_read_only_transaction(&self) -> Result<(TransactionId, Snapshot)> {
+        self.begin(true)
+    }
+    
+    fn begin(&self, read_only: bool) -> Result<(TransactionId, Snapshot)> {
         let txn_id = next_txn_id();
         let timestamp = current_timestamp();
         
@@ -69,6 +108,9 @@ impl TransactionManager {
             start_ts: timestamp,
             commit_ts: None,
             status: TransactionStatus::Active,
+            horizon: active_txn_ids.iter().copied().min().map_or(txn_id, |oldest| oldest.min(txn_id)),
+            read_only,
+            started_at: Instant::now(),
         };
         
         self.active_txns.insert(txn_id, info);
@@ -123,6 +165,35 @@ impl TransactionManager {
         self.active_txns.len()
     }
     
+    /// Running transactions, oldest first
+    pub fn active_transactions(&self) -> Vec<ActiveTransaction> {
+        let mut active: Vec<ActiveTransaction> = self
+            .active_txns
+            .iter()
+            .map(|entry| ActiveTransaction {
+                id: *entry.key(),
+                start_ts: entry.value().start_ts,
+                read_only: entry.value().read_only,
+                age: entry.value().started_at.elapsed(),
+            })
+            .collect();
+        active.sort_by_key(|txn| txn.id);
+        active
+    }
+    
+    /// Transactions below this ID are finished and visible as committed to
+    /// every active and future snapshot
+    ///
+    /// A version superseded by a committed transaction below the horizon
+    /// can no longer be read and may be garbage collected.
+    pub fn gc_horizon(&self) -> TransactionId {
+        self.active_txns
+            .iter()
+            .map(|entry| entry.value().horizon)
+            .min()
+            .unwrap_or_else(|| TransactionId(current_timestamp()))
+    }
+    
     /// Get oldest active timestamp (for garbage collection)
     pub fn oldest_active_timestamp(&self) -> Option<Timestamp> {
         self.active_txns
@@ -203,6 +274,39 @@ mod tests {
         assert_eq!(manager.active_count(), 0);
     }
 
+    #[test]
+    fn test_active_transactions() {
+        let manager = TransactionManager::new();
+        
+        let (writer, _) = manager.begin_transaction().unwrap();
+        let (reader, _) = manager.begin_read_only_transaction().unwrap();
+        
+        let active = manager.active_transactions();
+        assert_eq!(active.iter().map(|txn| txn.id).collect::<Vec<_>>(), vec![writer, reader]);
+        assert!(!active[0].read_only);
+        assert!(active[1].read_only);
+        assert!(active[0].age >= active[1].age);
+        
+        manager.commit_transaction(writer).unwrap();
+        assert_eq!(manager.active_transactions().len(), 1);
+    }
+
+    #[test]
+    fn test_gc_horizon() {
+        let manager = TransactionManager::new();
+        
+        let (txn1, _) = manager.begin_transaction().unwrap();
+        let (txn2, _) = manager.begin_transaction().unwrap();
+        assert_eq!(manager.gc_horizon(), txn1);
+        
+        // txn2's snapshot still treats txn1 as running
+        manager.commit_transaction(txn1).unwrap();
+        assert_eq!(manager.gc_horizon(), txn1);
+        
+        manager.commit_transaction(txn2).unwrap();
+        assert!(manager.gc_horizon() > txn2);
+    }
+
     #[test]
     fn test_oldest_active_timestamp() {
         let manager = TransactionManager::new();
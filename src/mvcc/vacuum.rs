@@ -0,0 +1,129 @@
+//! Garbage collection of old versions
+//!
+//! Every write through a [`VersionedStorage`] adds a version, so chains
+//! grow without bound unless superseded versions are removed.
+//! [`VersionedStorage::vacuum`] removes every version that no active
+//! snapshot, and no snapshot taken later, can read; [`Vacuum`] runs it on
+//! a background thread at a configurable interval. A long-running
+//! transaction holds back the versions its snapshot can still see until it
+//! finishes.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let storage = Arc::new(VersionedStorage::new(backend, txns));
+//! let vacuum = Vacuum::spawn(storage.clone(), Duration::from_secs(30));
+//! // ...
+//! vacuum.stop();
+//! ```
+
+use crate::mvcc::VersionedStorage;
+use crate::storage::StorageBackend;
+use log::{debug, info};
+use parking_lot::{Condvar, Mutex};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Interval between vacuum runs unless configured otherwise
+pub const DEFAULT_VACUUM_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Versions reclaimed by one vacuum run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VacuumStats {
+    /// Versions removed, including those of dropped chains
+    pub versions_removed: usize,
+    /// Chains dropped because every snapshot sees the backend's value
+    pub chains_removed: usize,
+}
+
+/// Background thread vacuuming a [`VersionedStorage`] periodically
+pub struct Vacuum {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Vacuum {
+    /// Start vacuuming `storage` every `interval`
+    pub fn spawn<S: StorageBackend + 'static>(storage: Arc<VersionedStorage<S>>, interval: Duration) -> Self {
+        info!("Starting MVCC vacuum (interval {:?})", interval);
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let signal = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let (stopped, wake) = &*signal;
+            loop {
+                {
+                    let mut stopped = stopped.lock();
+                    if !*stopped {
+                        wake.wait_for(&mut stopped, interval);
+                    }
+                    if *stopped {
+                        break;
+                    }
+                }
+                storage.vacuum();
+            }
+            debug!("MVCC vacuum stopped");
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Start vacuuming `storage` every [`DEFAULT_VACUUM_INTERVAL`]
+    pub fn spawn_default<S: StorageBackend + 'static>(storage: Arc<VersionedStorage<S>>) -> Self {
+        Self::spawn(storage, DEFAULT_VACUUM_INTERVAL)
+    }
+
+    /// Stop vacuuming and wait for the thread to exit
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let (stopped, wake) = &*self.stop;
+        *stopped.lock() = true;
+        wake.notify_all();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Vacuum {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+    use crate::mvcc::TransactionManager;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_vacuum_thread_bounds_chains() {
+        let storage = Arc::new(VersionedStorage::new(
+            Arc::new(MemoryStorage::new()),
+            Arc::new(TransactionManager::new()),
+        ));
+        let id = storage.add_node(Node::new(vec![])).unwrap();
+        for _ in 0..10 {
+            let node = storage.get_node(id).unwrap();
+            storage.update_node(node).unwrap();
+        }
+        assert!(storage.version_count() > 10);
+
+        let vacuum = Vacuum::spawn(storage.clone(), Duration::from_millis(5));
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while storage.version_count() > 0 && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        vacuum.stop();
+        assert_eq!(storage.version_count(), 0);
+        assert!(storage.get_node(id).is_ok());
+    }
+}
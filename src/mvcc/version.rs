@@ -2,7 +2,7 @@
 //!
 //! Tracks multiple versions of data items
 
-use crate::mvcc::{Timestamp, TransactionId};
+use crate::mvcc::{Snapshot, Timestamp, TransactionId};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use parking_lot::RwLock;
@@ -98,6 +98,33 @@ impl<T: Clone> VersionChain<T> {
         None
     }
     
+    /// Get the version visible to a snapshot, judged by which transactions
+    /// it sees rather than by timestamps
+    pub fn get_visible_to(&self, snapshot: &Snapshot) -> Option<T> {
+        let versions = self.versions.read();
+        versions
+            .iter()
+            .find(|version| snapshot.is_version_visible(version.xmin, version.xmax))
+            .map(|version| version.data.clone())
+    }
+    
+    /// Transaction that created the newest version
+    pub fn latest_writer(&self) -> Option<TransactionId> {
+        self.versions.read().first().map(|version| version.xmin)
+    }
+    
+    /// Remove the versions a transaction created and undo its deletions
+    ///
+    /// Used when the transaction aborts after recording versions.
+    pub fn discard(&self, txn_id: TransactionId) {
+        let mut versions = self.versions.write();
+        versions.retain(|version| version.xmin != txn_id);
+        for version in versions.iter_mut().filter(|version| version.xmax == Some(txn_id)) {
+            version.xmax = None;
+            version.deleted_at = None;
+        }
+    }
+    
     /// Get the latest active version
     pub fn get_latest_active(&self) -> Option<T> {
         let versions = self.versions.read();
@@ -120,6 +147,24 @@ impl<T: Clone> VersionChain<T> {
         }
     }
     
+    /// Remove versions superseded by a transaction below `horizon`,
+    /// returning how many were removed
+    ///
+    /// See [`TransactionManager::gc_horizon`](crate::mvcc::TransactionManager::gc_horizon).
+    pub fn vacuum(&self, horizon: TransactionId) -> usize {
+        let mut versions = self.versions.write();
+        let before = versions.len();
+        versions.retain(|version| version.xmax.map_or(true, |xmax| xmax >= horizon));
+        before - versions.len()
+    }
+    
+    /// Whether the chain is down to one version, created below `horizon`
+    /// and not superseded, so every snapshot sees the same data
+    pub fn is_settled(&self, horizon: TransactionId) -> bool {
+        let versions = self.versions.read();
+        versions.len() == 1 && versions[0].xmin < horizon && versions[0].xmax.is_none()
+    }
+    
     /// Garbage collect old versions
     pub fn gc(&self, min_snapshot_ts: Timestamp) {
         let mut versions = self.versions.write();
@@ -208,6 +253,25 @@ mod tests {
         assert_eq!(chain.get_visible_version(200), None);
     }
 
+    #[test]
+    fn test_snapshot_visibility_and_discard() {
+        let chain: VersionChain<String> = VersionChain::new();
+        chain.add_version(Version::new("v1".to_string(), TransactionId(1), 1));
+        chain.mark_latest_deleted(TransactionId(3), 3);
+        chain.add_version(Version::new("v2".to_string(), TransactionId(3), 3));
+        
+        // Transaction 3 was still running when this snapshot was taken
+        let mut active = std::collections::HashSet::new();
+        active.insert(TransactionId(3));
+        assert_eq!(chain.get_visible_to(&Snapshot::new(4, active)), Some("v1".to_string()));
+        assert_eq!(chain.get_visible_to(&Snapshot::new(4, Default::default())), Some("v2".to_string()));
+        assert_eq!(chain.latest_writer(), Some(TransactionId(3)));
+        
+        chain.discard(TransactionId(3));
+        assert_eq!(chain.version_count(), 1);
+        assert_eq!(chain.get_visible_to(&Snapshot::new(4, Default::default())), Some("v1".to_string()));
+    }
+
     #[test]
     fn test_garbage_collection() {
         let chain: VersionChain<String> = VersionChain::new();
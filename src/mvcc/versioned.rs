@@ -0,0 +1,464 @@
+//! Snapshot reads over a storage backend
+//!
+//! [`VersionedStorage`] keeps a [`VersionChain`] for every node and edge
+//! written through it. A reader holding a [`Snapshot`] gets the version
+//! that was committed when the snapshot was taken, even after later
+//! commits changed the backend. Elements never written through the wrapper
+//! have no chain and are read from the backend directly.
+//!
+//! The first write to an element captures its current value as a base
+//! version visible to every snapshot, then applies the change to the
+//! backend and records the new version under the writing transaction,
+//! which stays invisible until that transaction commits in the
+//! [`TransactionManager`]. Writes through the [`StorageBackend`] methods
+//! each run as their own transaction; [`Transaction`](crate::Transaction)
+//! records all of its writes under one. Chains are only accurate if every
+//! write goes through the wrapper.
+//!
+//! Superseded versions accumulate until [`VersionedStorage::vacuum`]
+//! removes the ones no active snapshot can read any more; see
+//! [`Vacuum`](crate::mvcc::Vacuum) for running it periodically.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let storage = VersionedStorage::new(Arc::new(GraphStorage::new()), Arc::new(TransactionManager::new()));
+//! let id = storage.add_node(node)?;
+//! let (_, snapshot) = storage.transactions().begin_transaction()?;
+//! storage.delete_node(id)?;
+//! assert!(storage.get_node_at(id, &snapshot).is_ok()); // still there for the snapshot
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
+use crate::metrics::{self, OperatorEvent};
+use crate::mvcc::{current_timestamp, Snapshot, TransactionId, TransactionManager, VacuumStats, Version, VersionChain};
+use crate::storage::{CostConstants, GraphStatistics, StorageBackend};
+use dashmap::DashMap;
+use log::{debug, warn};
+use parking_lot::{Mutex, MutexGuard};
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Creator of base versions, which existed before tracking began; every
+/// snapshot sees it as committed
+const BASE_TXN: TransactionId = TransactionId(0);
+
+/// Storage backend wrapper serving reads as of a snapshot
+pub struct VersionedStorage<S: StorageBackend> {
+    inner: Arc<S>,
+    txns: Arc<TransactionManager>,
+    nodes: DashMap<NodeId, VersionChain<Option<Node>>>,
+    edges: DashMap<EdgeId, VersionChain<Option<Edge>>>,
+    /// Held by a writer from tracking to recording, so writes don't interleave
+    writes: Mutex<()>,
+}
+
+impl<S: StorageBackend> VersionedStorage<S> {
+    /// Wrap a backend, registering its writes with `txns`
+    pub fn new(inner: Arc<S>, txns: Arc<TransactionManager>) -> Self {
+        Self {
+            inner,
+            txns,
+            nodes: DashMap::new(),
+            edges: DashMap::new(),
+            writes: Mutex::new(()),
+        }
+    }
+
+    /// The wrapped backend
+    pub fn inner(&self) -> &Arc<S> {
+        &self.inner
+    }
+
+    /// The transaction manager snapshots come from
+    pub fn transactions(&self) -> &Arc<TransactionManager> {
+        &self.txns
+    }
+
+    /// The node as of `snapshot`
+    pub fn get_node_at(&self, id: NodeId, snapshot: &Snapshot) -> Result<Node> {
+        Self::read_at(&self.nodes, &id, snapshot, || self.inner.get_node(id).ok())
+            .ok_or_else(|| DeepGraphError::NodeNotFound(id.to_string()))
+    }
+
+    /// The edge as of `snapshot`
+    pub fn get_edge_at(&self, id: EdgeId, snapshot: &Snapshot) -> Result<Edge> {
+        Self::read_at(&self.edges, &id, snapshot, || self.inner.get_edge(id).ok())
+            .ok_or_else(|| DeepGraphError::EdgeNotFound(id.to_string()))
+    }
+
+    /// Transaction that wrote the newest version of a node, if tracked
+    pub fn node_writer(&self, id: NodeId) -> Option<TransactionId> {
+        self.nodes.get(&id).and_then(|chain| chain.latest_writer())
+    }
+
+    /// Transaction that wrote the newest version of an edge, if tracked
+    pub fn edge_writer(&self, id: EdgeId) -> Option<TransactionId> {
+        self.edges.get(&id).and_then(|chain| chain.latest_writer())
+    }
+
+    /// Versions held across all chains
+    pub fn version_count(&self) -> usize {
+        self.nodes.iter().map(|chain| chain.version_count()).sum::<usize>()
+            + self.edges.iter().map(|chain| chain.version_count()).sum::<usize>()
+    }
+
+    /// Remove versions no active or future snapshot can see
+    ///
+    /// Chains left with a single committed version that matches the
+    /// backend are dropped entirely, so reads of those elements go to the
+    /// backend again. Reclaimed versions are counted under
+    /// [`OperatorEvent::VersionReclaimed`] in the global metrics registry.
+    pub fn vacuum(&self) -> VacuumStats {
+        let _writes = self.write_guard();
+        let horizon = self.txns.gc_horizon();
+        let mut stats = VacuumStats::default();
+        Self::vacuum_chains(&self.nodes, horizon, &mut stats);
+        Self::vacuum_chains(&self.edges, horizon, &mut stats);
+
+        if stats.versions_removed > 0 {
+            metrics::global().record_n(
+                OperatorEvent::VersionReclaimed,
+                "VersionedStorage",
+                stats.versions_removed as u64,
+            );
+            debug!(
+                "Vacuum below {} removed {} versions and {} chains",
+                horizon, stats.versions_removed, stats.chains_removed
+            );
+        }
+        stats
+    }
+
+    fn vacuum_chains<K: Eq + Hash, T: Clone>(
+        chains: &DashMap<K, VersionChain<Option<T>>>,
+        horizon: TransactionId,
+        stats: &mut VacuumStats,
+    ) {
+        chains.retain(|_, chain| {
+            stats.versions_removed += chain.vacuum(horizon);
+            if chain.is_settled(horizon) {
+                stats.versions_removed += 1;
+                stats.chains_removed += 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn read_at<K: Eq + Hash, T: Clone>(
+        chains: &DashMap<K, VersionChain<Option<T>>>,
+        key: &K,
+        snapshot: &Snapshot,
+        current: impl FnOnce() -> Option<T>,
+    ) -> Option<T> {
+        if let Some(chain) = chains.get(key) {
+            return chain.get_visible_to(snapshot).flatten();
+        }
+        let value = current();
+        // A writer may have started tracking the element and changed the
+        // backend since the first check; its chain then has the answer
+        match chains.get(key) {
+            Some(chain) => chain.get_visible_to(snapshot).flatten(),
+            None => value,
+        }
+    }
+
+    /// Exclude other writers until the guard is dropped
+    pub(crate) fn write_guard(&self) -> MutexGuard<'_, ()> {
+        self.writes.lock()
+    }
+
+    /// Start tracking a node before changing it, capturing its base version
+    pub(crate) fn track_node(&self, id: NodeId) {
+        self.nodes
+            .entry(id)
+            .or_insert_with(|| Self::base_chain(self.inner.get_node(id).ok()));
+    }
+
+    /// Start tracking an edge before changing it, capturing its base version
+    pub(crate) fn track_edge(&self, id: EdgeId) {
+        self.edges
+            .entry(id)
+            .or_insert_with(|| Self::base_chain(self.inner.get_edge(id).ok()));
+    }
+
+    fn base_chain<T: Clone>(current: Option<T>) -> VersionChain<Option<T>> {
+        let chain = VersionChain::new();
+        chain.add_version(Version::new(current, BASE_TXN, 0));
+        chain
+    }
+
+    /// Record what `txn` left a tracked node as, reading it from the backend
+    pub(crate) fn record_node(&self, id: NodeId, txn: TransactionId) {
+        let current = self.inner.get_node(id).ok();
+        Self::push(self.nodes.entry(id).or_default().value(), txn, current);
+    }
+
+    /// Record what `txn` left a tracked edge as, reading it from the backend
+    pub(crate) fn record_edge(&self, id: EdgeId, txn: TransactionId) {
+        let current = self.inner.get_edge(id).ok();
+        Self::push(self.edges.entry(id).or_default().value(), txn, current);
+    }
+
+    fn push<T: Clone>(chain: &VersionChain<Option<T>>, txn: TransactionId, data: Option<T>) {
+        let timestamp = current_timestamp();
+        chain.mark_latest_deleted(txn, timestamp);
+        chain.add_version(Version::new(data, txn, timestamp));
+    }
+
+    /// Drop the versions `txn` recorded for a node it failed to commit
+    pub(crate) fn discard_node(&self, id: NodeId, txn: TransactionId) {
+        if let Some(chain) = self.nodes.get(&id) {
+            chain.discard(txn);
+        }
+    }
+
+    /// Drop the versions `txn` recorded for an edge it failed to commit
+    pub(crate) fn discard_edge(&self, id: EdgeId, txn: TransactionId) {
+        if let Some(chain) = self.edges.get(&id) {
+            chain.discard(txn);
+        }
+    }
+
+    /// Edges the backend removes along with a node
+    pub(crate) fn incident_edges(&self, id: NodeId) -> Result<Vec<Edge>> {
+        let mut edges = self.inner.get_outgoing_edges(id)?;
+        edges.extend(
+            self.inner
+                .get_incoming_edges(id)?
+                .into_iter()
+                .filter(|edge| edge.from() != id),
+        );
+        Ok(edges)
+    }
+
+    /// Run one write as its own transaction
+    fn autocommit<T>(&self, write: impl FnOnce(TransactionId) -> Result<T>) -> Result<T> {
+        let (txn, _) = self.txns.begin_transaction()?;
+        let result = {
+            let _writes = self.write_guard();
+            write(txn)
+        };
+        match result {
+            Ok(value) => {
+                self.txns.commit_transaction(txn)?;
+                Ok(value)
+            }
+            Err(e) => {
+                if let Err(abort) = self.txns.abort_transaction(txn) {
+                    warn!("Failed to abort transaction {}: {}", txn, abort);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn write_node(&self, id: NodeId, apply: impl FnOnce(&S) -> Result<()>) -> Result<()> {
+        self.autocommit(|txn| {
+            self.track_node(id);
+            apply(&self.inner)?;
+            self.record_node(id, txn);
+            Ok(())
+        })
+    }
+
+    fn write_edge(&self, id: EdgeId, apply: impl FnOnce(&S) -> Result<()>) -> Result<()> {
+        self.autocommit(|txn| {
+            self.track_edge(id);
+            apply(&self.inner)?;
+            self.record_edge(id, txn);
+            Ok(())
+        })
+    }
+}
+
+impl<S: StorageBackend> StorageBackend for VersionedStorage<S> {
+    fn add_node(&self, node: Node) -> Result<NodeId> {
+        let id = node.id();
+        self.write_node(id, |inner| inner.add_node(node).map(|_| ()))?;
+        Ok(id)
+    }
+
+    fn get_node(&self, id: NodeId) -> Result<Node> {
+        self.inner.get_node(id)
+    }
+
+    fn update_node(&self, node: Node) -> Result<()> {
+        self.write_node(node.id(), |inner| inner.update_node(node))
+    }
+
+    fn delete_node(&self, id: NodeId) -> Result<()> {
+        self.autocommit(|txn| {
+            let edges = self.incident_edges(id)?;
+            self.track_node(id);
+            for edge in &edges {
+                self.track_edge(edge.id());
+            }
+            self.inner.delete_node(id)?;
+            self.record_node(id, txn);
+            for edge in &edges {
+                self.record_edge(edge.id(), txn);
+            }
+            Ok(())
+        })
+    }
+
+    fn add_edge(&self, edge: Edge) -> Result<EdgeId> {
+        let id = edge.id();
+        self.write_edge(id, |inner| inner.add_edge(edge).map(|_| ()))?;
+        Ok(id)
+    }
+
+    fn get_edge(&self, id: EdgeId) -> Result<Edge> {
+        self.inner.get_edge(id)
+    }
+
+    fn update_edge(&self, edge: Edge) -> Result<()> {
+        self.write_edge(edge.id(), |inner| inner.update_edge(edge))
+    }
+
+    fn delete_edge(&self, id: EdgeId) -> Result<()> {
+        self.write_edge(id, |inner| inner.delete_edge(id))
+    }
+
+    fn get_nodes_by_label(&self, label: &str) -> Vec<Node> {
+        self.inner.get_nodes_by_label(label)
+    }
+
+    fn get_all_nodes(&self) -> Vec<Node> {
+        self.inner.get_all_nodes()
+    }
+
+    fn get_all_edges(&self) -> Vec<Edge> {
+        self.inner.get_all_edges()
+    }
+
+    fn get_edges_by_type(&self, relationship_type: &str) -> Vec<Edge> {
+        self.inner.get_edges_by_type(relationship_type)
+    }
+
+    fn get_nodes_by_property(&self, key: &str, value: &PropertyValue) -> Vec<Node> {
+        self.inner.get_nodes_by_property(key, value)
+    }
+
+    fn iter_nodes(&self) -> Box<dyn Iterator<Item = Node> + '_> {
+        self.inner.iter_nodes()
+    }
+
+    fn iter_edges(&self) -> Box<dyn Iterator<Item = Edge> + '_> {
+        self.inner.iter_edges()
+    }
+
+    fn get_outgoing_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
+        self.inner.get_outgoing_edges(node_id)
+    }
+
+    fn get_incoming_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
+        self.inner.get_incoming_edges(node_id)
+    }
+
+    fn node_count(&self) -> usize {
+        self.inner.node_count()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.inner.edge_count()
+    }
+
+    fn cost_constants(&self) -> CostConstants {
+        self.inner.cost_constants()
+    }
+
+    fn statistics(&self) -> Option<&GraphStatistics> {
+        self.inner.statistics()
+    }
+
+    fn degree(&self, node_id: NodeId) -> Result<usize> {
+        self.inner.degree(node_id)
+    }
+    
+    fn flush(&self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_snapshot_reads_survive_later_writes() {
+        let storage = VersionedStorage::new(Arc::new(MemoryStorage::new()), Arc::new(TransactionManager::new()));
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let edge = storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+
+        let (reader, snapshot) = storage.transactions().begin_transaction().unwrap();
+        let mut renamed = storage.get_node(a).unwrap();
+        renamed.set_property("name".to_string(), "Alice".into());
+        storage.update_node(renamed).unwrap();
+        storage.delete_node(b).unwrap();
+        let c = storage.add_node(Node::new(vec![])).unwrap();
+
+        // The snapshot still sees the graph as it was
+        assert!(storage.get_node_at(a, &snapshot).unwrap().get_property("name").is_none());
+        assert!(storage.get_node_at(b, &snapshot).is_ok());
+        assert!(storage.get_edge_at(edge, &snapshot).is_ok());
+        assert!(storage.get_node_at(c, &snapshot).is_err());
+        storage.transactions().commit_transaction(reader).unwrap();
+
+        // A new snapshot sees everything
+        let (_, latest) = storage.transactions().begin_transaction().unwrap();
+        assert!(storage.get_node_at(a, &latest).unwrap().get_property("name").is_some());
+        assert!(storage.get_node_at(b, &latest).is_err());
+        assert!(storage.get_edge_at(edge, &latest).is_err());
+        assert!(storage.get_node_at(c, &latest).is_ok());
+    }
+
+    #[test]
+    fn test_failed_write_records_nothing() {
+        let storage = VersionedStorage::new(Arc::new(MemoryStorage::new()), Arc::new(TransactionManager::new()));
+        assert!(storage.update_node(Node::new(vec![])).is_err());
+        assert!(storage.add_edge(Edge::new(NodeId::new(), NodeId::new(), "KNOWS".to_string())).is_err());
+        // Only the base versions captured before the failed writes remain
+        assert_eq!(storage.version_count(), 2);
+        assert_eq!(storage.transactions().active_count(), 0);
+    }
+
+    #[test]
+    fn test_vacuum_keeps_versions_visible_to_snapshots() {
+        let storage = VersionedStorage::new(Arc::new(MemoryStorage::new()), Arc::new(TransactionManager::new()));
+        let id = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let mut node = storage.get_node(id).unwrap();
+        node.set_property("name".to_string(), "Alice".into());
+        storage.update_node(node.clone()).unwrap();
+
+        let (reader, snapshot) = storage.transactions().begin_transaction().unwrap();
+        node.set_property("name".to_string(), "Bob".into());
+        storage.update_node(node).unwrap();
+
+        // The base and creation versions are unreachable; the reader's is not
+        let stats = storage.vacuum();
+        assert_eq!(stats.versions_removed, 2);
+        assert_eq!(stats.chains_removed, 0);
+        assert_eq!(
+            storage.get_node_at(id, &snapshot).unwrap().get_property("name"),
+            Some(&"Alice".into())
+        );
+
+        // Once the reader is gone the chain collapses into the backend
+        storage.transactions().commit_transaction(reader).unwrap();
+        let stats = storage.vacuum();
+        assert_eq!(stats.chains_removed, 1);
+        assert_eq!(storage.version_count(), 0);
+        let (_, latest) = storage.transactions().begin_transaction().unwrap();
+        assert_eq!(
+            storage.get_node_at(id, &latest).unwrap().get_property("name"),
+            Some(&"Bob".into())
+        );
+    }
+}
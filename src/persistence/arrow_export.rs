@@ -0,0 +1,313 @@
+//! Arrow export of graph data
+//!
+//! Turns the nodes and edges of any [`StorageBackend`] into Arrow
+//! [`RecordBatch`]es, or writes them as Arrow IPC files, so DataFusion,
+//! Polars or pyarrow can take them over without a conversion step. The
+//! columns are those of [`parquet_io`](crate::persistence::parquet_io):
+//! `id`, `labels` or `from_id`/`to_id`/`relationship_type`, and one typed
+//! `prop.<key>` column per property key.
+//!
+//! Query results convert as well, one column per result column, which is
+//! how the Python bindings hand them to pyarrow and pandas.
+//!
+//! All batches of one export share a schema: the table is converted once
+//! and split into zero-copy slices of at most `batch_size` rows.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let batches = node_batches(&storage, DEFAULT_EXPORT_BATCH_SIZE)?;
+//! ctx.register_batch("nodes", arrow::compute::concat_batches(&batches[0].schema(), &batches)?)?;
+//!
+//! export_ipc(Path::new("./export"), &storage)?;
+//! // pyarrow.ipc.open_file("./export/nodes.arrow").read_all()
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use crate::export::property_to_json;
+use crate::graph::PropertyValue;
+use crate::persistence::parquet_io::{edges_to_record_batch, nodes_to_record_batch};
+use crate::query::QueryResult;
+use crate::storage::StorageBackend;
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, RecordBatchOptions, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::{FileWriter, StreamWriter};
+use log::info;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Rows per batch unless configured otherwise
+pub const DEFAULT_EXPORT_BATCH_SIZE: usize = 65_536;
+
+/// IPC file holding the nodes in a directory written by [`export_ipc`]
+pub const NODES_IPC_FILE: &str = "nodes.arrow";
+
+/// IPC file holding the edges in a directory written by [`export_ipc`]
+pub const EDGES_IPC_FILE: &str = "edges.arrow";
+
+fn arrow_error(e: arrow::error::ArrowError) -> DeepGraphError {
+    DeepGraphError::StorageError(format!("Arrow IPC error: {}", e))
+}
+
+/// Split `batch` into slices of at most `batch_size` rows
+///
+/// An empty table still yields one empty batch, which carries the schema.
+fn split(batch: RecordBatch, batch_size: usize) -> Vec<RecordBatch> {
+    let batch_size = batch_size.max(1);
+    if batch.num_rows() <= batch_size {
+        return vec![batch];
+    }
+    (0..batch.num_rows())
+        .step_by(batch_size)
+        .map(|offset| batch.slice(offset, batch_size.min(batch.num_rows() - offset)))
+        .collect()
+}
+
+/// Every node of `storage` as batches of at most `batch_size` rows
+pub fn node_batches<S: StorageBackend + ?Sized>(storage: &S, batch_size: usize) -> Result<Vec<RecordBatch>> {
+    let nodes = storage.get_all_nodes();
+    Ok(split(nodes_to_record_batch(&nodes)?, batch_size))
+}
+
+/// Every edge of `storage` as batches of at most `batch_size` rows
+pub fn edge_batches<S: StorageBackend + ?Sized>(storage: &S, batch_size: usize) -> Result<Vec<RecordBatch>> {
+    let edges = storage.get_all_edges();
+    Ok(split(edges_to_record_batch(&edges)?, batch_size))
+}
+
+/// Write batches sharing one schema to an Arrow IPC file
+pub fn write_ipc(path: &Path, batches: &[RecordBatch]) -> Result<()> {
+    let schema = batches
+        .first()
+        .map(|batch| batch.schema())
+        .ok_or_else(|| DeepGraphError::InvalidOperation("No batches to write".to_string()))?;
+    let mut writer = FileWriter::try_new(File::create(path)?, &schema).map_err(arrow_error)?;
+    for batch in batches {
+        writer.write(batch).map_err(arrow_error)?;
+    }
+    writer.finish().map_err(arrow_error)?;
+    Ok(())
+}
+
+/// Read every batch of an Arrow IPC file
+pub fn read_ipc(path: &Path) -> Result<Vec<RecordBatch>> {
+    FileReader::try_new(File::open(path)?, None)
+        .map_err(arrow_error)?
+        .map(|batch| batch.map_err(arrow_error))
+        .collect()
+}
+
+/// Write batches sharing one schema as an in-memory Arrow IPC stream
+pub fn write_ipc_stream(batches: &[RecordBatch]) -> Result<Vec<u8>> {
+    let schema = batches
+        .first()
+        .map(|batch| batch.schema())
+        .ok_or_else(|| DeepGraphError::InvalidOperation("No batches to write".to_string()))?;
+    let mut writer = StreamWriter::try_new(Vec::new(), &schema).map_err(arrow_error)?;
+    for batch in batches {
+        writer.write(batch).map_err(arrow_error)?;
+    }
+    // Finishes the stream before handing back the buffer
+    writer.into_inner().map_err(arrow_error)
+}
+
+/// Type of a query result column
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ResultColumn {
+    Integer,
+    Float,
+    Boolean,
+    Text,
+}
+
+/// Convert a query result to a record batch
+///
+/// Columns follow `result.columns`, then any other keys the rows hold (the
+/// properties of a returned node) in key order. Integer, float and boolean
+/// columns are typed, integers mixed with floats widen to float, and every
+/// other column holds text, with lists, maps and mixed values as JSON.
+/// Missing values are null.
+pub fn query_result_to_record_batch(result: &QueryResult) -> Result<RecordBatch> {
+    let held = |key: &str| result.rows.iter().any(|row| row.contains_key(key));
+    // A node column such as `n` holds no value of its own, only properties
+    let mut names: Vec<&str> = result
+        .columns
+        .iter()
+        .map(String::as_str)
+        .filter(|column| result.rows.is_empty() || held(column))
+        .collect();
+    let others: BTreeSet<&str> = result
+        .rows
+        .iter()
+        .flat_map(|row| row.keys().map(String::as_str))
+        .filter(|key| !names.contains(key))
+        .collect();
+    names.extend(others);
+
+    let mut fields = Vec::with_capacity(names.len());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(names.len());
+    for name in names {
+        let values: Vec<Option<&PropertyValue>> = result
+            .rows
+            .iter()
+            .map(|row| row.get(name).filter(|value| !matches!(value, PropertyValue::Null)))
+            .collect();
+        let mut kind = None;
+        for value in values.iter().flatten() {
+            let this = match value {
+                PropertyValue::Integer(_) => ResultColumn::Integer,
+                PropertyValue::Float(_) => ResultColumn::Float,
+                PropertyValue::Boolean(_) => ResultColumn::Boolean,
+                _ => ResultColumn::Text,
+            };
+            kind = Some(match (kind, this) {
+                (None, this) => this,
+                (Some(a), b) if a == b => a,
+                (Some(ResultColumn::Integer), ResultColumn::Float) | (Some(ResultColumn::Float), ResultColumn::Integer) => {
+                    ResultColumn::Float
+                }
+                _ => ResultColumn::Text,
+            });
+        }
+        let (data_type, column): (DataType, ArrayRef) = match kind.unwrap_or(ResultColumn::Text) {
+            ResultColumn::Integer => (
+                DataType::Int64,
+                Arc::new(values.iter().map(|v| v.and_then(|v| v.as_integer())).collect::<Int64Array>()),
+            ),
+            ResultColumn::Float => (
+                DataType::Float64,
+                Arc::new(
+                    values
+                        .iter()
+                        .map(|v| match v {
+                            Some(PropertyValue::Integer(i)) => Some(*i as f64),
+                            Some(PropertyValue::Float(f)) => Some(*f),
+                            _ => None,
+                        })
+                        .collect::<Float64Array>(),
+                ),
+            ),
+            ResultColumn::Boolean => (
+                DataType::Boolean,
+                Arc::new(values.iter().map(|v| v.and_then(|v| v.as_boolean())).collect::<BooleanArray>()),
+            ),
+            ResultColumn::Text => (
+                DataType::Utf8,
+                Arc::new(
+                    values
+                        .iter()
+                        .map(|v| {
+                            v.map(|v| match v {
+                                PropertyValue::String(s) => s.clone(),
+                                v => property_to_json(v).to_string(),
+                            })
+                        })
+                        .collect::<StringArray>(),
+                ),
+            ),
+        };
+        fields.push(Field::new(name, data_type, true));
+        columns.push(column);
+    }
+
+    let options = RecordBatchOptions::new().with_row_count(Some(result.rows.len()));
+    RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), columns, &options).map_err(arrow_error)
+}
+
+/// Write the nodes and edges of `storage` as IPC files in `dir`, returning
+/// the number of nodes and edges written
+pub fn export_ipc<S: StorageBackend + ?Sized>(dir: &Path, storage: &S) -> Result<(usize, usize)> {
+    std::fs::create_dir_all(dir)?;
+    let nodes = node_batches(storage, DEFAULT_EXPORT_BATCH_SIZE)?;
+    let edges = edge_batches(storage, DEFAULT_EXPORT_BATCH_SIZE)?;
+    write_ipc(&dir.join(NODES_IPC_FILE), &nodes)?;
+    write_ipc(&dir.join(EDGES_IPC_FILE), &edges)?;
+    let counts = (
+        nodes.iter().map(RecordBatch::num_rows).sum(),
+        edges.iter().map(RecordBatch::num_rows).sum(),
+    );
+    info!("Exported {} nodes and {} edges as Arrow IPC to {}", counts.0, counts.1, dir.display());
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node};
+    use crate::persistence::parquet_io::record_batch_to_nodes;
+    use crate::storage::MemoryStorage;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_batches_share_schema() {
+        let storage = MemoryStorage::new();
+        for i in 0..5 {
+            let mut node = Node::new(vec!["Person".to_string()]);
+            if i % 2 == 0 {
+                node.set_property("age".to_string(), (i as i64).into());
+            }
+            storage.add_node(node).unwrap();
+        }
+        let batches = node_batches(&storage, 2).unwrap();
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).collect::<Vec<_>>(), vec![2, 2, 1]);
+        assert!(batches.iter().all(|batch| batch.schema() == batches[0].schema()));
+        assert!(batches[0].schema().field_with_name("prop.age").is_ok());
+
+        let empty = edge_batches(&storage, 2).unwrap();
+        assert_eq!(empty.len(), 1);
+        assert_eq!(empty[0].num_rows(), 0);
+    }
+
+    #[test]
+    fn test_ipc_round_trip() {
+        let dir = tempdir().unwrap();
+        let storage = MemoryStorage::new();
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+
+        assert_eq!(export_ipc(dir.path(), &storage).unwrap(), (2, 1));
+        let batches = read_ipc(&dir.path().join(NODES_IPC_FILE)).unwrap();
+        let nodes: Vec<Node> = batches
+            .iter()
+            .flat_map(|batch| record_batch_to_nodes(batch).unwrap())
+            .collect();
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes.iter().any(|node| node.id() == a));
+        assert_eq!(read_ipc(&dir.path().join(EDGES_IPC_FILE)).unwrap()[0].num_rows(), 1);
+    }
+
+    #[test]
+    fn test_query_result_to_record_batch() {
+        use arrow::array::Array;
+        use std::collections::HashMap;
+
+        let row = |pairs: Vec<(&str, PropertyValue)>| -> HashMap<String, PropertyValue> {
+            pairs.into_iter().map(|(key, value)| (key.to_string(), value)).collect()
+        };
+        let result = QueryResult {
+            columns: vec!["score".to_string(), "n".to_string()],
+            rows: vec![
+                row(vec![("score", PropertyValue::Integer(1)), ("name", PropertyValue::String("Ada".to_string()))]),
+                row(vec![("score", PropertyValue::Float(2.5)), ("tags", PropertyValue::List(vec![PropertyValue::Integer(1)]))]),
+            ],
+            row_count: 2,
+            execution_time_ms: 0,
+        };
+
+        let batch = query_result_to_record_batch(&result).unwrap();
+        let names: Vec<_> = batch.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, vec!["score", "name", "tags"]);
+        assert_eq!(batch.column(0).data_type(), &DataType::Float64);
+        let tags = batch.column(2).as_any().downcast_ref::<StringArray>().unwrap();
+        assert!(tags.is_null(0));
+        assert_eq!(tags.value(1), "[1]");
+
+        let ipc = write_ipc_stream(&[batch]).unwrap();
+        let batches = crate::import::arrow::read_ipc_stream(ipc.as_slice()).unwrap();
+        assert_eq!(batches[0].num_rows(), 2);
+    }
+}
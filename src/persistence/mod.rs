@@ -1,13 +1,31 @@
 //! Persistence layer for durable storage
 //!
 //! Provides save/load functionality using Parquet format for efficient
-//! storage and fast loading of graph data.
+//! storage and fast loading of graph data, and Arrow export for handing the
+//! graph to other columnar tools.
 
+pub mod archive;
+pub mod arrow_export;
 pub mod parquet_io;
+pub mod replication;
+pub mod scheduler;
 pub mod snapshot;
+pub mod store;
 
-pub use parquet_io::{ParquetWriter, ParquetReader};
+pub use archive::{export_graph, import_graph, ArchiveEntry, ArchiveReader, ArchiveWriter};
+pub use arrow_export::{
+    edge_batches, export_ipc, node_batches, query_result_to_record_batch, read_ipc, write_ipc, write_ipc_stream,
+};
+pub use parquet_io::{
+    edges_to_record_batch, nodes_to_record_batch, record_batch_to_edges, record_batch_to_nodes, ParquetReader,
+    ParquetWriter,
+};
+pub use replication::{ReplicationDelta, SnapshotManifest};
+pub use scheduler::{take_snapshot, SnapshotSchedule, SnapshotScheduler};
 pub use snapshot::{Snapshot, SnapshotManager};
+#[cfg(any(feature = "s3", feature = "gcs"))]
+pub use store::ObjectSnapshotStore;
+pub use store::{download_dir, upload_dir, LocalSnapshotStore, SnapshotStore};
 
 use crate::error::Result;
 use std::path::Path;
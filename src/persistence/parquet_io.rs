@@ -2,14 +2,63 @@
 //!
 //! Provides efficient serialization and deserialization of graph data
 //! using Apache Parquet format.
+//!
+//! Nodes and edges are stored one row each, with an `id` column, `labels`
+//! (nodes) or `from_id`, `to_id` and `relationship_type` (edges), and one
+//! `prop.<key>` column per property key. A key whose values are all
+//! integers, floats, booleans or strings gets a typed column; any other
+//! key, including one holding lists, maps, explicit nulls or mixed types,
+//! gets a JSON-encoded string column so every [`PropertyValue`] variant
+//! round-trips exactly. A null cell means the element lacks the property.
+//!
+//! Columns are matched by name, so files written with a different set of
+//! property keys read fine: missing property columns are absent
+//! properties, and unknown non-property columns are ignored.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! ParquetWriter::new().write_graph(Path::new("./export"), &storage)?;
+//! let restored = MemoryStorage::new();
+//! ParquetReader::read_graph(Path::new("./export"), &restored)?;
+//! ```
 
+use crate::config::StorageConfig;
 use crate::error::{DeepGraphError, Result};
-use arrow::array::RecordBatch;
+use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
+use crate::storage::{RecordCompression, StorageBackend};
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, BooleanBuilder, FixedSizeBinaryArray, FixedSizeBinaryBuilder,
+    Float64Array, Float64Builder, Int64Array, Int64Builder, ListArray, ListBuilder, RecordBatch,
+    StringArray, StringBuilder,
+};
+use arrow::datatypes::{DataType, Field, Schema};
+use log::info;
+use parquet::basic::{Compression, ZstdLevel};
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use parquet::arrow::ArrowWriter;
 use parquet::file::properties::WriterProperties;
+use parquet::file::reader::ChunkReader;
+use std::collections::{BTreeSet, HashMap};
 use std::fs::File;
+use std::io::Write;
 use std::path::Path;
+use std::sync::Arc;
+
+/// File holding the nodes of a graph written by [`ParquetWriter::write_graph`]
+pub const NODES_FILE: &str = "nodes.parquet";
+
+/// File holding the edges of a graph written by [`ParquetWriter::write_graph`]
+pub const EDGES_FILE: &str = "edges.parquet";
+
+/// Prefix of the column holding each property key
+pub const PROPERTY_COLUMN_PREFIX: &str = "prop.";
+
+/// Field metadata key naming how a property column is encoded
+const ENCODING_KEY: &str = "deepgraph.encoding";
+
+/// [`ENCODING_KEY`] value of JSON-encoded property columns
+const JSON_ENCODING: &str = "json";
 
 /// Parquet writer for graph data
 pub struct ParquetWriter {
@@ -26,6 +75,25 @@ impl ParquetWriter {
         Self { properties }
     }
     
+    /// Create a writer following the compression settings of a storage config
+    ///
+    /// Uses zstd at `compression_level` when compression is enabled, and
+    /// Snappy otherwise.
+    pub fn from_config(config: &StorageConfig) -> Result<Self> {
+        let compression = match RecordCompression::from_config(config) {
+            Some(settings) => {
+                let level = ZstdLevel::try_new(settings.level)
+                    .map_err(|e| DeepGraphError::InvalidOperation(format!("Invalid zstd level: {}", e)))?;
+                Compression::ZSTD(level)
+            }
+            None => Compression::SNAPPY,
+        };
+        let properties = WriterProperties::builder()
+            .set_compression(compression)
+            .build();
+        Ok(Self { properties })
+    }
+    
     /// Create a new writer with custom properties
     pub fn with_properties(properties: WriterProperties) -> Self {
         Self { properties }
@@ -43,10 +111,22 @@ impl ParquetWriter {
         
         let file = File::create(path)
             .map_err(|e| DeepGraphError::IoError(e))?;
-        
+        self.write_batches_to(file, batches)
+    }
+    
+    /// Encode record batches as Parquet in memory
+    pub fn write_batches_to_bytes(&self, batches: &[RecordBatch]) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        if !batches.is_empty() {
+            self.write_batches_to(&mut buffer, batches)?;
+        }
+        Ok(buffer)
+    }
+    
+    fn write_batches_to<W: Write + Send>(&self, sink: W, batches: &[RecordBatch]) -> Result<()> {
         let schema = batches[0].schema();
         let mut writer = ArrowWriter::try_new(
-            file,
+            sink,
             schema,
             Some(self.properties.clone()),
         ).map_err(|e| DeepGraphError::StorageError(format!("Failed to create writer: {}", e)))?;
@@ -61,6 +141,38 @@ impl ParquetWriter {
         
         Ok(())
     }
+    
+    /// Write nodes to a Parquet file
+    pub fn write_nodes(&self, path: &Path, nodes: &[Node]) -> Result<()> {
+        self.write_batches(path, &[nodes_to_record_batch(nodes)?])
+    }
+    
+    /// Write edges to a Parquet file
+    pub fn write_edges(&self, path: &Path, edges: &[Edge]) -> Result<()> {
+        self.write_batches(path, &[edges_to_record_batch(edges)?])
+    }
+    
+    /// Encode nodes as a Parquet file in memory
+    pub fn write_nodes_to_bytes(&self, nodes: &[Node]) -> Result<Vec<u8>> {
+        self.write_batches_to_bytes(&[nodes_to_record_batch(nodes)?])
+    }
+    
+    /// Encode edges as a Parquet file in memory
+    pub fn write_edges_to_bytes(&self, edges: &[Edge]) -> Result<Vec<u8>> {
+        self.write_batches_to_bytes(&[edges_to_record_batch(edges)?])
+    }
+    
+    /// Write every node and edge of `storage` to [`NODES_FILE`] and
+    /// [`EDGES_FILE`] in `dir`, creating it if needed
+    pub fn write_graph<S: StorageBackend + ?Sized>(&self, dir: &Path, storage: &S) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let nodes = storage.get_all_nodes();
+        let edges = storage.get_all_edges();
+        self.write_nodes(&dir.join(NODES_FILE), &nodes)?;
+        self.write_edges(&dir.join(EDGES_FILE), &edges)?;
+        info!("Wrote {} nodes and {} edges to {}", nodes.len(), edges.len(), dir.display());
+        Ok(())
+    }
 }
 
 impl Default for ParquetWriter {
@@ -77,8 +189,17 @@ impl ParquetReader {
     pub fn read_batches(path: &Path) -> Result<Vec<RecordBatch>> {
         let file = File::open(path)
             .map_err(|e| DeepGraphError::IoError(e))?;
-        
-        let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        Self::read_batches_from(file)
+    }
+    
+    /// Read record batches from Parquet data held in memory, such as a
+    /// decrypted file
+    pub fn read_batches_from_bytes(data: Vec<u8>) -> Result<Vec<RecordBatch>> {
+        Self::read_batches_from(bytes::Bytes::from(data))
+    }
+    
+    fn read_batches_from<T: ChunkReader + 'static>(input: T) -> Result<Vec<RecordBatch>> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(input)
             .map_err(|e| DeepGraphError::StorageError(format!("Failed to create reader: {}", e)))?;
         
         let reader = builder.build()
@@ -105,6 +226,334 @@ impl ParquetReader {
         let metadata = builder.metadata();
         Ok(metadata.file_metadata().clone())
     }
+    
+    /// Read nodes from a Parquet file written by [`ParquetWriter::write_nodes`]
+    pub fn read_nodes(path: &Path) -> Result<Vec<Node>> {
+        let mut nodes = Vec::new();
+        for batch in Self::read_batches(path)? {
+            nodes.extend(record_batch_to_nodes(&batch)?);
+        }
+        Ok(nodes)
+    }
+    
+    /// Read edges from a Parquet file written by [`ParquetWriter::write_edges`]
+    pub fn read_edges(path: &Path) -> Result<Vec<Edge>> {
+        let mut edges = Vec::new();
+        for batch in Self::read_batches(path)? {
+            edges.extend(record_batch_to_edges(&batch)?);
+        }
+        Ok(edges)
+    }
+    
+    /// Read nodes from Parquet data held in memory
+    pub fn read_nodes_from_bytes(data: Vec<u8>) -> Result<Vec<Node>> {
+        let mut nodes = Vec::new();
+        for batch in Self::read_batches_from_bytes(data)? {
+            nodes.extend(record_batch_to_nodes(&batch)?);
+        }
+        Ok(nodes)
+    }
+    
+    /// Read edges from Parquet data held in memory
+    pub fn read_edges_from_bytes(data: Vec<u8>) -> Result<Vec<Edge>> {
+        let mut edges = Vec::new();
+        for batch in Self::read_batches_from_bytes(data)? {
+            edges.extend(record_batch_to_edges(&batch)?);
+        }
+        Ok(edges)
+    }
+    
+    /// Load a graph written by [`ParquetWriter::write_graph`] into `storage`,
+    /// returning the number of nodes and edges added
+    pub fn read_graph<S: StorageBackend + ?Sized>(dir: &Path, storage: &S) -> Result<(usize, usize)> {
+        let nodes = Self::read_nodes(&dir.join(NODES_FILE))?;
+        let edges = Self::read_edges(&dir.join(EDGES_FILE))?;
+        let counts = (nodes.len(), edges.len());
+        for node in nodes {
+            storage.add_node(node)?;
+        }
+        for edge in edges {
+            storage.add_edge(edge)?;
+        }
+        info!("Read {} nodes and {} edges from {}", counts.0, counts.1, dir.display());
+        Ok(counts)
+    }
+}
+
+/// Build a record batch holding one row per node
+pub fn nodes_to_record_batch(nodes: &[Node]) -> Result<RecordBatch> {
+    let mut labels = ListBuilder::new(StringBuilder::new());
+    for node in nodes {
+        for label in node.labels() {
+            labels.values().append_value(label);
+        }
+        labels.append(true);
+    }
+    let labels = labels.finish();
+
+    let mut fields = vec![
+        Field::new("id", DataType::FixedSizeBinary(16), false),
+        Field::new("labels", labels.data_type().clone(), false),
+    ];
+    let mut columns: Vec<ArrayRef> = vec![
+        id_column(nodes.iter().map(|node| *node.id().as_uuid()))?,
+        Arc::new(labels),
+    ];
+    let properties: Vec<&HashMap<String, PropertyValue>> = nodes.iter().map(|node| node.properties()).collect();
+    property_columns(&properties, &mut fields, &mut columns)?;
+    new_batch(fields, columns)
+}
+
+/// Build a record batch holding one row per edge
+pub fn edges_to_record_batch(edges: &[Edge]) -> Result<RecordBatch> {
+    let mut relationship_types = StringBuilder::new();
+    for edge in edges {
+        relationship_types.append_value(edge.relationship_type());
+    }
+
+    let mut fields = vec![
+        Field::new("id", DataType::FixedSizeBinary(16), false),
+        Field::new("from_id", DataType::FixedSizeBinary(16), false),
+        Field::new("to_id", DataType::FixedSizeBinary(16), false),
+        Field::new("relationship_type", DataType::Utf8, false),
+    ];
+    let mut columns: Vec<ArrayRef> = vec![
+        id_column(edges.iter().map(|edge| *edge.id().as_uuid()))?,
+        id_column(edges.iter().map(|edge| *edge.from().as_uuid()))?,
+        id_column(edges.iter().map(|edge| *edge.to().as_uuid()))?,
+        Arc::new(relationship_types.finish()),
+    ];
+    let properties: Vec<&HashMap<String, PropertyValue>> = edges.iter().map(|edge| edge.properties()).collect();
+    property_columns(&properties, &mut fields, &mut columns)?;
+    new_batch(fields, columns)
+}
+
+/// Read the nodes of a record batch built by [`nodes_to_record_batch`]
+pub fn record_batch_to_nodes(batch: &RecordBatch) -> Result<Vec<Node>> {
+    let ids = uuid_column(batch, "id")?;
+    let labels = column::<ListArray>(batch, "labels")?;
+    let properties = PropertyColumn::all(batch)?;
+
+    (0..batch.num_rows())
+        .map(|row| {
+            let label_values = labels.value(row);
+            let label_values = label_values
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| DeepGraphError::StorageError("Invalid labels data".to_string()))?;
+            let node_labels = (0..label_values.len()).map(|i| label_values.value(i).to_string()).collect();
+            let mut node = Node::with_id(NodeId::from_uuid(uuid_at(ids, row)?), node_labels);
+            for (key, column) in &properties {
+                if let Some(value) = column.value(row)? {
+                    node.set_property(key.clone(), value);
+                }
+            }
+            Ok(node)
+        })
+        .collect()
+}
+
+/// Read the edges of a record batch built by [`edges_to_record_batch`]
+pub fn record_batch_to_edges(batch: &RecordBatch) -> Result<Vec<Edge>> {
+    let ids = uuid_column(batch, "id")?;
+    let from = uuid_column(batch, "from_id")?;
+    let to = uuid_column(batch, "to_id")?;
+    let relationship_types = column::<StringArray>(batch, "relationship_type")?;
+    let properties = PropertyColumn::all(batch)?;
+
+    (0..batch.num_rows())
+        .map(|row| {
+            let mut edge = Edge::with_id(
+                EdgeId::from_uuid(uuid_at(ids, row)?),
+                NodeId::from_uuid(uuid_at(from, row)?),
+                NodeId::from_uuid(uuid_at(to, row)?),
+                relationship_types.value(row).to_string(),
+            );
+            for (key, column) in &properties {
+                if let Some(value) = column.value(row)? {
+                    edge.set_property(key.clone(), value);
+                }
+            }
+            Ok(edge)
+        })
+        .collect()
+}
+
+fn new_batch(fields: Vec<Field>, columns: Vec<ArrayRef>) -> Result<RecordBatch> {
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| DeepGraphError::StorageError(format!("Failed to create batch: {}", e)))
+}
+
+fn id_column(ids: impl Iterator<Item = uuid::Uuid>) -> Result<ArrayRef> {
+    let mut builder = FixedSizeBinaryBuilder::new(16);
+    for id in ids {
+        builder
+            .append_value(id.as_bytes())
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to append ID: {}", e)))?;
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// How a property key's values are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Integer,
+    Float,
+    Boolean,
+    String,
+    Json,
+}
+
+impl ColumnKind {
+    fn of(value: &PropertyValue) -> Self {
+        match value {
+            PropertyValue::Integer(_) => ColumnKind::Integer,
+            PropertyValue::Float(_) => ColumnKind::Float,
+            PropertyValue::Boolean(_) => ColumnKind::Boolean,
+            PropertyValue::String(_) => ColumnKind::String,
+            PropertyValue::Null | PropertyValue::List(_) | PropertyValue::Map(_) => ColumnKind::Json,
+        }
+    }
+}
+
+/// Append a column per property key, in key order
+fn property_columns(
+    rows: &[&HashMap<String, PropertyValue>],
+    fields: &mut Vec<Field>,
+    columns: &mut Vec<ArrayRef>,
+) -> Result<()> {
+    let keys: BTreeSet<&String> = rows.iter().flat_map(|properties| properties.keys()).collect();
+    for key in keys {
+        let values: Vec<Option<&PropertyValue>> = rows.iter().map(|properties| properties.get(key)).collect();
+        let mut kinds = values.iter().flatten().map(|value| ColumnKind::of(value));
+        let first = kinds.next().unwrap_or(ColumnKind::Json);
+        let kind = if kinds.all(|kind| kind == first) { first } else { ColumnKind::Json };
+
+        let name = format!("{}{}", PROPERTY_COLUMN_PREFIX, key);
+        let (field, column): (Field, ArrayRef) = match kind {
+            ColumnKind::Integer => {
+                let mut builder = Int64Builder::with_capacity(values.len());
+                values.iter().for_each(|value| builder.append_option(value.and_then(|v| v.as_integer())));
+                (Field::new(name, DataType::Int64, true), Arc::new(builder.finish()))
+            }
+            ColumnKind::Float => {
+                let mut builder = Float64Builder::with_capacity(values.len());
+                values.iter().for_each(|value| builder.append_option(value.and_then(|v| v.as_float())));
+                (Field::new(name, DataType::Float64, true), Arc::new(builder.finish()))
+            }
+            ColumnKind::Boolean => {
+                let mut builder = BooleanBuilder::with_capacity(values.len());
+                values.iter().for_each(|value| builder.append_option(value.and_then(|v| v.as_boolean())));
+                (Field::new(name, DataType::Boolean, true), Arc::new(builder.finish()))
+            }
+            ColumnKind::String => {
+                let mut builder = StringBuilder::new();
+                values.iter().for_each(|value| builder.append_option(value.and_then(|v| v.as_string())));
+                (Field::new(name, DataType::Utf8, true), Arc::new(builder.finish()))
+            }
+            ColumnKind::Json => {
+                let mut builder = StringBuilder::new();
+                for value in &values {
+                    match value {
+                        Some(value) => builder.append_value(
+                            serde_json::to_string(value).map_err(|e| DeepGraphError::SerializationError(e.to_string()))?,
+                        ),
+                        None => builder.append_null(),
+                    }
+                }
+                let metadata = HashMap::from([(ENCODING_KEY.to_string(), JSON_ENCODING.to_string())]);
+                (
+                    Field::new(name, DataType::Utf8, true).with_metadata(metadata),
+                    Arc::new(builder.finish()),
+                )
+            }
+        };
+        fields.push(field);
+        columns.push(column);
+    }
+    Ok(())
+}
+
+/// A property column of a batch being read
+enum PropertyColumn<'a> {
+    Integer(&'a Int64Array),
+    Float(&'a Float64Array),
+    Boolean(&'a BooleanArray),
+    String(&'a StringArray),
+    Json(&'a StringArray),
+}
+
+impl<'a> PropertyColumn<'a> {
+    /// Every `prop.` column of `batch`, with its property key
+    fn all(batch: &'a RecordBatch) -> Result<Vec<(String, Self)>> {
+        let schema = batch.schema();
+        let mut properties = Vec::new();
+        for (index, field) in schema.fields().iter().enumerate() {
+            let Some(key) = field.name().strip_prefix(PROPERTY_COLUMN_PREFIX) else {
+                continue;
+            };
+            let array = batch.column(index).as_any();
+            let invalid = || {
+                DeepGraphError::StorageError(format!("Invalid data in property column {}", field.name()))
+            };
+            let column = match field.data_type() {
+                DataType::Int64 => Self::Integer(array.downcast_ref().ok_or_else(invalid)?),
+                DataType::Float64 => Self::Float(array.downcast_ref().ok_or_else(invalid)?),
+                DataType::Boolean => Self::Boolean(array.downcast_ref().ok_or_else(invalid)?),
+                DataType::Utf8 if field.metadata().get(ENCODING_KEY).map(String::as_str) == Some(JSON_ENCODING) => {
+                    Self::Json(array.downcast_ref().ok_or_else(invalid)?)
+                }
+                DataType::Utf8 => Self::String(array.downcast_ref().ok_or_else(invalid)?),
+                other => {
+                    return Err(DeepGraphError::StorageError(format!(
+                        "Unsupported type {} for property column {}",
+                        other,
+                        field.name()
+                    )))
+                }
+            };
+            properties.push((key.to_string(), column));
+        }
+        Ok(properties)
+    }
+
+    /// The property at `row`, or `None` if the element lacks it
+    fn value(&self, row: usize) -> Result<Option<PropertyValue>> {
+        let is_null = match self {
+            Self::Integer(array) => array.is_null(row),
+            Self::Float(array) => array.is_null(row),
+            Self::Boolean(array) => array.is_null(row),
+            Self::String(array) | Self::Json(array) => array.is_null(row),
+        };
+        if is_null {
+            return Ok(None);
+        }
+        Ok(Some(match self {
+            Self::Integer(array) => PropertyValue::Integer(array.value(row)),
+            Self::Float(array) => PropertyValue::Float(array.value(row)),
+            Self::Boolean(array) => PropertyValue::Boolean(array.value(row)),
+            Self::String(array) => PropertyValue::String(array.value(row).to_string()),
+            Self::Json(array) => serde_json::from_str(array.value(row))
+                .map_err(|e| DeepGraphError::SerializationError(e.to_string()))?,
+        }))
+    }
+}
+
+fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> Result<&'a T> {
+    batch
+        .column_by_name(name)
+        .ok_or_else(|| DeepGraphError::StorageError(format!("Missing column {}", name)))?
+        .as_any()
+        .downcast_ref::<T>()
+        .ok_or_else(|| DeepGraphError::StorageError(format!("Invalid {} column", name)))
+}
+
+fn uuid_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a FixedSizeBinaryArray> {
+    column::<FixedSizeBinaryArray>(batch, name)
+}
+
+fn uuid_at(array: &FixedSizeBinaryArray, row: usize) -> Result<uuid::Uuid> {
+    uuid::Uuid::from_slice(array.value(row)).map_err(|e| DeepGraphError::StorageError(format!("Invalid ID: {}", e)))
 }
 
 #[cfg(test)]
@@ -147,5 +596,78 @@ mod tests {
         assert_eq!(batches.len(), 1);
         assert_eq!(batches[0].num_rows(), 3);
     }
+
+    #[test]
+    fn test_graph_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = crate::storage::MemoryStorage::new();
+
+        let mut alice = Node::new(vec!["Person".to_string(), "Admin".to_string()]);
+        alice.set_property("name".to_string(), "Alice".into());
+        alice.set_property("age".to_string(), PropertyValue::Integer(30));
+        alice.set_property("score".to_string(), PropertyValue::Float(0.5));
+        alice.set_property("active".to_string(), PropertyValue::Boolean(true));
+        alice.set_property("nickname".to_string(), PropertyValue::Null);
+        alice.set_property(
+            "tags".to_string(),
+            PropertyValue::List(vec![PropertyValue::Integer(1), "two".into()]),
+        );
+        alice.set_property(
+            "address".to_string(),
+            PropertyValue::Map(HashMap::from([("city".to_string(), "Paris".into())])),
+        );
+        // Mixed types under one key fall back to JSON and keep their variants
+        let mut bob = Node::new(vec![]);
+        bob.set_property("score".to_string(), PropertyValue::Integer(2));
+        let mut knows = Edge::new(alice.id(), bob.id(), "KNOWS".to_string());
+        knows.set_property("since".to_string(), PropertyValue::Integer(2020));
+        knows.set_property("weights".to_string(), PropertyValue::List(vec![PropertyValue::Float(1.5)]));
+
+        storage.add_node(alice.clone()).unwrap();
+        storage.add_node(bob.clone()).unwrap();
+        storage.add_edge(knows.clone()).unwrap();
+        ParquetWriter::new().write_graph(dir.path(), &storage).unwrap();
+
+        let restored = crate::storage::MemoryStorage::new();
+        assert_eq!(ParquetReader::read_graph(dir.path(), &restored).unwrap(), (2, 1));
+        let read_alice = restored.get_node(alice.id()).unwrap();
+        assert_eq!(read_alice.labels(), alice.labels());
+        assert_eq!(read_alice.properties(), alice.properties());
+        assert_eq!(restored.get_node(bob.id()).unwrap().properties(), bob.properties());
+        let read_knows = restored.get_edge(knows.id()).unwrap();
+        assert_eq!(read_knows.relationship_type(), "KNOWS");
+        assert_eq!((read_knows.from(), read_knows.to()), (alice.id(), bob.id()));
+        assert_eq!(read_knows.properties(), knows.properties());
+    }
+
+    #[test]
+    fn test_schema_evolution() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = ParquetWriter::new();
+
+        let mut old = Node::new(vec!["Person".to_string()]);
+        old.set_property("name".to_string(), "Alice".into());
+        writer.write_nodes(&dir.path().join("old.parquet"), &[old.clone()]).unwrap();
+
+        let mut new = Node::new(vec!["Person".to_string()]);
+        new.set_property("name".to_string(), "Bob".into());
+        new.set_property("email".to_string(), "bob@example.com".into());
+        writer.write_nodes(&dir.path().join("new.parquet"), &[new.clone()]).unwrap();
+
+        // Each file reads with its own columns
+        let nodes = ParquetReader::read_nodes(&dir.path().join("old.parquet")).unwrap();
+        assert_eq!(nodes[0].properties(), old.properties());
+        let nodes = ParquetReader::read_nodes(&dir.path().join("new.parquet")).unwrap();
+        assert_eq!(nodes[0].properties(), new.properties());
+
+        // Unknown non-property columns are ignored
+        let batch = nodes_to_record_batch(&[old.clone()]).unwrap();
+        let mut fields: Vec<Field> = batch.schema().fields().iter().map(|f| f.as_ref().clone()).collect();
+        let mut columns = batch.columns().to_vec();
+        fields.push(Field::new("ingested_by", DataType::Int32, false));
+        columns.push(Arc::new(Int32Array::from(vec![7])));
+        let extended = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap();
+        assert_eq!(record_batch_to_nodes(&extended).unwrap()[0].properties(), old.properties());
+    }
 }
 
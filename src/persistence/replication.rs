@@ -0,0 +1,363 @@
+//! Snapshot-diff based replication seeding
+//!
+//! A replica describes its latest snapshot with a [`SnapshotManifest`]: one
+//! content fingerprint per node and edge, plus the WAL position the snapshot
+//! was taken at. The primary compares the manifest with its current state and
+//! ships a [`ReplicationDelta`] holding only the changed entities and the
+//! committed WAL tail, so re-seeding a lagging replica does not transfer the
+//! whole graph.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // Primary
+//! let lsn = wal.current_lsn();
+//! let delta = ReplicationDelta::compute(&replica_manifest, &storage, lsn)?
+//!     .with_wal_tail(&WALRecovery::new(wal_config), lsn)?;
+//!
+//! // Replica
+//! delta.apply(&replica_storage)?;
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::{Edge, EdgeId, Node, NodeId};
+use crate::storage::{GraphOp, StorageBackend};
+use crate::wal::log::LSN;
+use crate::wal::{WALEntry, WALOperation, WALRecovery};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Content fingerprints of every node and edge in a snapshot
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// WAL position the snapshot reflects
+    pub lsn: LSN,
+    /// Node ID -> fingerprint
+    pub nodes: HashMap<NodeId, u64>,
+    /// Edge ID -> fingerprint
+    pub edges: HashMap<EdgeId, u64>,
+}
+
+impl SnapshotManifest {
+    /// Fingerprint the current contents of a storage backend
+    pub fn capture<S: StorageBackend + ?Sized>(storage: &S, lsn: LSN) -> Result<Self> {
+        let mut manifest = Self {
+            lsn,
+            ..Self::default()
+        };
+        for node in storage.iter_nodes() {
+            manifest.nodes.insert(node.id(), node_fingerprint(&node)?);
+        }
+        for edge in storage.iter_edges() {
+            manifest.edges.insert(edge.id(), edge_fingerprint(&edge)?);
+        }
+        Ok(manifest)
+    }
+
+    /// Save as JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| DeepGraphError::SerializationError(e.to_string()))?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Load from JSON
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|e| DeepGraphError::SerializationError(e.to_string()))
+    }
+}
+
+/// Changes that bring a replica's snapshot up to date with the primary
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplicationDelta {
+    /// LSN of the replica snapshot the delta was computed against
+    pub base_lsn: LSN,
+    /// LSN of the primary state the delta reflects
+    pub target_lsn: LSN,
+    /// Nodes that are new or changed on the primary
+    pub upserted_nodes: Vec<Node>,
+    /// Nodes the replica has but the primary no longer does
+    pub deleted_nodes: Vec<NodeId>,
+    /// Edges that are new or changed on the primary
+    pub upserted_edges: Vec<Edge>,
+    /// Edges the replica has but the primary no longer does
+    pub deleted_edges: Vec<EdgeId>,
+    /// Committed WAL entries logged while the delta was being computed
+    pub wal_tail: Vec<WALEntry>,
+}
+
+impl ReplicationDelta {
+    /// Diff a replica manifest against the primary's current state
+    ///
+    /// `target_lsn` should be read from the primary WAL before the storage
+    /// is scanned, so writes racing with the scan are covered by the tail.
+    pub fn compute<S: StorageBackend + ?Sized>(
+        replica: &SnapshotManifest,
+        primary: &S,
+        target_lsn: LSN,
+    ) -> Result<Self> {
+        let mut delta = Self {
+            base_lsn: replica.lsn,
+            target_lsn,
+            ..Self::default()
+        };
+        let mut seen_nodes = HashSet::new();
+        let mut seen_edges = HashSet::new();
+
+        for node in primary.iter_nodes() {
+            seen_nodes.insert(node.id());
+            if replica.nodes.get(&node.id()) != Some(&node_fingerprint(&node)?) {
+                delta.upserted_nodes.push(node);
+            }
+        }
+        for edge in primary.iter_edges() {
+            seen_edges.insert(edge.id());
+            if replica.edges.get(&edge.id()) != Some(&edge_fingerprint(&edge)?) {
+                delta.upserted_edges.push(edge);
+            }
+        }
+
+        delta.deleted_nodes = replica
+            .nodes
+            .keys()
+            .filter(|id| !seen_nodes.contains(id))
+            .copied()
+            .collect();
+        delta.deleted_edges = replica
+            .edges
+            .keys()
+            .filter(|id| !seen_edges.contains(id))
+            .copied()
+            .collect();
+
+        debug!(
+            "Replication delta: {} nodes, {} edges changed; {} nodes, {} edges removed",
+            delta.upserted_nodes.len(),
+            delta.upserted_edges.len(),
+            delta.deleted_nodes.len(),
+            delta.deleted_edges.len()
+        );
+        Ok(delta)
+    }
+
+    /// Attach committed WAL entries logged at or after `from_lsn`
+    pub fn with_wal_tail(mut self, recovery: &WALRecovery, from_lsn: LSN) -> Result<Self> {
+        self.wal_tail = recovery.committed_tail(from_lsn)?;
+        Ok(self)
+    }
+
+    /// Number of entities and log entries shipped
+    pub fn len(&self) -> usize {
+        self.upserted_nodes.len()
+            + self.deleted_nodes.len()
+            + self.upserted_edges.len()
+            + self.deleted_edges.len()
+            + self.wal_tail.len()
+    }
+
+    /// Whether the replica is already up to date
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Apply the delta, then replay the WAL tail, on a replica
+    ///
+    /// Every step is idempotent, so a delta can be re-applied after an
+    /// interrupted seed. The tail is replayed on top of the primary's
+    /// current state, so an edge whose endpoint is already gone is skipped:
+    /// the tail deletes that endpoint, and the edge with it, later on.
+    pub fn apply<S: StorageBackend + ?Sized>(&self, replica: &S) -> Result<()> {
+        info!(
+            "Applying replication delta ({} changes, LSN {} -> {})",
+            self.len(),
+            self.base_lsn,
+            self.target_lsn
+        );
+
+        for id in &self.deleted_edges {
+            ignore_missing(replica.delete_edge(*id))?;
+        }
+        for id in &self.deleted_nodes {
+            ignore_missing(replica.delete_node(*id))?;
+        }
+        for node in &self.upserted_nodes {
+            upsert_node(replica, node.clone())?;
+        }
+        for edge in &self.upserted_edges {
+            upsert_edge(replica, edge.clone())?;
+        }
+
+        for entry in &self.wal_tail {
+            match &entry.operation {
+                WALOperation::InsertNode { node } | WALOperation::UpdateNode { node } => {
+                    upsert_node(replica, node.clone())?
+                }
+                WALOperation::InsertEdge { edge } | WALOperation::UpdateEdge { edge } => {
+                    replay_edge(replica, edge.clone())?
+                }
+                WALOperation::DeleteNode { id } => ignore_missing(replica.delete_node(*id))?,
+                WALOperation::DeleteEdge { id } => ignore_missing(replica.delete_edge(*id))?,
+                WALOperation::Batch { ops } => {
+                    for op in ops {
+                        match op {
+                            GraphOp::AddNode(node) | GraphOp::UpdateNode(node) => upsert_node(replica, node.clone())?,
+                            GraphOp::AddEdge(edge) | GraphOp::UpdateEdge(edge) => replay_edge(replica, edge.clone())?,
+                            GraphOp::DeleteNode(id) => ignore_missing(replica.delete_node(*id))?,
+                            GraphOp::DeleteEdge(id) => ignore_missing(replica.delete_edge(*id))?,
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn upsert_node<S: StorageBackend + ?Sized>(storage: &S, node: Node) -> Result<()> {
+    match storage.get_node(node.id()) {
+        Ok(_) => storage.update_node(node),
+        Err(e) if e.is_not_found() => storage.add_node(node).map(|_| ()),
+        Err(e) => Err(e),
+    }
+}
+
+fn upsert_edge<S: StorageBackend + ?Sized>(storage: &S, edge: Edge) -> Result<()> {
+    match storage.get_edge(edge.id()) {
+        Ok(_) => storage.update_edge(edge),
+        Err(e) if e.is_not_found() => storage.add_edge(edge).map(|_| ()),
+        Err(e) => Err(e),
+    }
+}
+
+/// [`upsert_edge`] for a WAL-tail edge, skipped if an endpoint is missing
+fn replay_edge<S: StorageBackend + ?Sized>(storage: &S, edge: Edge) -> Result<()> {
+    for endpoint in [edge.from(), edge.to()] {
+        match storage.get_node(endpoint) {
+            Ok(_) => {}
+            Err(e) if e.is_not_found() => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+    upsert_edge(storage, edge)
+}
+
+fn ignore_missing(result: Result<()>) -> Result<()> {
+    match result {
+        Err(e) if e.is_not_found() => Ok(()),
+        other => other,
+    }
+}
+
+/// Fingerprint of a node's labels and properties
+///
+/// Properties are hashed in key order, so the value is stable across
+/// processes regardless of `HashMap` iteration order.
+pub fn node_fingerprint(node: &Node) -> Result<u64> {
+    let mut labels: Vec<&String> = node.labels().iter().collect();
+    labels.sort();
+    let properties: BTreeMap<_, _> = node.properties().iter().collect();
+    let canonical = serde_json::to_vec(&(node.id(), labels, properties))?;
+    Ok(fnv1a(&canonical))
+}
+
+/// Fingerprint of an edge's endpoints, type and properties
+pub fn edge_fingerprint(edge: &Edge) -> Result<u64> {
+    let properties: BTreeMap<_, _> = edge.properties().iter().collect();
+    let canonical = serde_json::to_vec(&(
+        edge.id(),
+        edge.from(),
+        edge.to(),
+        edge.relationship_type(),
+        properties,
+    ))?;
+    Ok(fnv1a(&canonical))
+}
+
+/// 64-bit FNV-1a, chosen over `DefaultHasher` because it is stable across
+/// Rust releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::PropertyValue;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_delta_ships_only_changes() {
+        let primary = MemoryStorage::new();
+        let a = primary.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = primary.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let c = primary.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        primary.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+
+        // Seed the replica with a full copy, then let the primary move on
+        let replica = MemoryStorage::new();
+        ReplicationDelta::compute(&SnapshotManifest::default(), &primary, 0)
+            .unwrap()
+            .apply(&replica)
+            .unwrap();
+        let manifest = SnapshotManifest::capture(&replica, 0).unwrap();
+
+        let mut changed = primary.get_node(a).unwrap();
+        changed.set_property("name".to_string(), PropertyValue::String("Alice".to_string()));
+        primary.update_node(changed).unwrap();
+        primary.delete_node(c).unwrap();
+        primary.add_edge(Edge::new(b, a, "KNOWS".to_string())).unwrap();
+
+        let delta = ReplicationDelta::compute(&manifest, &primary, 1).unwrap();
+        assert_eq!(delta.upserted_nodes.len(), 1);
+        assert_eq!(delta.deleted_nodes, vec![c]);
+        assert_eq!(delta.upserted_edges.len(), 1);
+        assert!(delta.deleted_edges.is_empty());
+
+        delta.apply(&replica).unwrap();
+        let caught_up = SnapshotManifest::capture(&replica, 1).unwrap();
+        assert!(ReplicationDelta::compute(&caught_up, &primary, 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tail_edges_to_deleted_nodes_are_skipped() {
+        let primary = MemoryStorage::new();
+        let a = primary.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = primary.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let replica = MemoryStorage::new();
+        ReplicationDelta::compute(&SnapshotManifest::default(), &primary, 0)
+            .unwrap()
+            .apply(&replica)
+            .unwrap();
+        let manifest = SnapshotManifest::capture(&replica, 0).unwrap();
+
+        // The tail inserts edges to `b`, then deletes `b`; the diff already
+        // reflects the deletion
+        let knows = Edge::new(a, b, "KNOWS".to_string());
+        let likes = Edge::new(b, a, "LIKES".to_string());
+        primary.add_edge(knows.clone()).unwrap();
+        primary.add_edge(likes.clone()).unwrap();
+        primary.delete_node(b).unwrap();
+        let entry = |lsn, operation| WALEntry { lsn, txn_id: lsn, operation, timestamp: 0 };
+
+        let mut delta = ReplicationDelta::compute(&manifest, &primary, 3).unwrap();
+        delta.wal_tail = vec![
+            entry(1, WALOperation::InsertEdge { edge: knows }),
+            entry(2, WALOperation::Batch { ops: vec![GraphOp::AddEdge(likes)] }),
+            entry(3, WALOperation::DeleteNode { id: b }),
+        ];
+        delta.apply(&replica).unwrap();
+
+        assert!(replica.get_node(b).is_err());
+        assert_eq!(replica.edge_count(), 0);
+        let caught_up = SnapshotManifest::capture(&replica, 3).unwrap();
+        assert!(ReplicationDelta::compute(&caught_up, &primary, 3).unwrap().is_empty());
+    }
+}
@@ -1,11 +1,29 @@
 //! Snapshot management for point-in-time backups
 //!
 //! Provides functionality to create, manage, and restore from snapshots.
+//! [`SnapshotScheduler`](crate::persistence::SnapshotScheduler) takes them
+//! automatically, [`SnapshotStore`]s hold copies off-host, and
+//! [`archive`](crate::persistence::archive) streams them as single files.
+//! A manager given an [`EncryptionKey`] encrypts the node and edge files of
+//! the snapshots it writes; the metadata stays readable so snapshots can be
+//! listed without the key.
 
+use crate::encryption::EncryptionKey;
 use crate::error::{DeepGraphError, Result};
+use crate::persistence::archive::{ArchiveEntry, ArchiveReader, ArchiveWriter};
+use crate::graph::{Edge, Node};
+use crate::persistence::parquet_io::{ParquetReader, ParquetWriter};
+use crate::persistence::store::{download_dir, upload_dir, SnapshotStore};
+use crate::storage::StorageBackend;
+use crate::wal::log::LSN;
+use log::info;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Name of the metadata file in a snapshot directory
+const METADATA_FILE: &str = "metadata.json";
 
 /// Metadata for a snapshot
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +40,13 @@ pub struct Snapshot {
     pub edge_count: usize,
     /// Optional description
     pub description: Option<String>,
+    /// WAL checkpoint the snapshot includes every entry before; replay
+    /// the log from here to catch up
+    #[serde(default)]
+    pub wal_lsn: Option<LSN>,
+    /// Whether the node and edge files are encrypted
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 impl Snapshot {
@@ -39,6 +64,8 @@ impl Snapshot {
             node_count,
             edge_count,
             description: None,
+            wal_lsn: None,
+            encrypted: false,
         }
     }
     
@@ -48,6 +75,12 @@ impl Snapshot {
         self
     }
     
+    /// Record the WAL checkpoint the snapshot was taken at
+    pub fn with_wal_lsn(mut self, lsn: LSN) -> Self {
+        self.wal_lsn = Some(lsn);
+        self
+    }
+    
     /// Get the path to the nodes file
     pub fn nodes_file(&self) -> PathBuf {
         self.path.join("nodes.parquet")
@@ -60,7 +93,7 @@ impl Snapshot {
     
     /// Get the path to the metadata file
     pub fn metadata_file(&self) -> PathBuf {
-        self.path.join("metadata.json")
+        self.path.join(METADATA_FILE)
     }
     
     /// Save snapshot metadata to disk
@@ -77,7 +110,7 @@ impl Snapshot {
     
     /// Load snapshot metadata from disk
     pub fn load_metadata(path: &Path) -> Result<Self> {
-        let metadata_path = path.join("metadata.json");
+        let metadata_path = path.join(METADATA_FILE);
         let json = fs::read_to_string(&metadata_path)
             .map_err(|e| DeepGraphError::IoError(e))?;
         
@@ -92,6 +125,8 @@ impl Snapshot {
 pub struct SnapshotManager {
     /// Base directory for snapshots
     base_dir: PathBuf,
+    /// Key for the node and edge files of new snapshots
+    encryption: Option<EncryptionKey>,
 }
 
 impl SnapshotManager {
@@ -103,7 +138,63 @@ impl SnapshotManager {
                 .map_err(|e| DeepGraphError::IoError(e))?;
         }
         
-        Ok(Self { base_dir })
+        Ok(Self {
+            base_dir,
+            encryption: None,
+        })
+    }
+    
+    /// Encrypt the files of new snapshots with `key`, and decrypt
+    /// encrypted snapshots with it
+    pub fn with_encryption(mut self, key: EncryptionKey) -> Self {
+        self.encryption = Some(key);
+        self
+    }
+    
+    /// Key for encrypted snapshots, failing if none was given
+    fn key_for(&self, snapshot: &Snapshot) -> Result<&EncryptionKey> {
+        self.encryption.as_ref().ok_or_else(|| {
+            DeepGraphError::InvalidOperation(format!("Snapshot {} is encrypted but no key is configured", snapshot.id))
+        })
+    }
+    
+    /// Read the nodes of a snapshot, decrypting them if needed
+    pub fn read_nodes(&self, snapshot: &Snapshot) -> Result<Vec<Node>> {
+        if snapshot.encrypted {
+            let data = self.key_for(snapshot)?.decrypt_file(&snapshot.nodes_file())?;
+            ParquetReader::read_nodes_from_bytes(data)
+        } else {
+            ParquetReader::read_nodes(&snapshot.nodes_file())
+        }
+    }
+    
+    /// Read the edges of a snapshot, decrypting them if needed
+    pub fn read_edges(&self, snapshot: &Snapshot) -> Result<Vec<Edge>> {
+        if snapshot.encrypted {
+            let data = self.key_for(snapshot)?.decrypt_file(&snapshot.edges_file())?;
+            ParquetReader::read_edges_from_bytes(data)
+        } else {
+            ParquetReader::read_edges(&snapshot.edges_file())
+        }
+    }
+    
+    /// Write the node and edge files of `snapshot`, encrypted if a key is
+    /// configured
+    fn write_files(&self, snapshot: &mut Snapshot, nodes: &[Node], edges: &[Edge]) -> Result<()> {
+        let writer = ParquetWriter::new();
+        match &self.encryption {
+            // Encode in memory so no plaintext copy is ever written
+            Some(key) => {
+                key.encrypt_to_file(&snapshot.nodes_file(), &writer.write_nodes_to_bytes(nodes)?)?;
+                key.encrypt_to_file(&snapshot.edges_file(), &writer.write_edges_to_bytes(edges)?)?;
+            }
+            None => {
+                writer.write_nodes(&snapshot.nodes_file(), nodes)?;
+                writer.write_edges(&snapshot.edges_file(), edges)?;
+            }
+        }
+        snapshot.encrypted = self.encryption.is_some();
+        Ok(())
     }
     
     /// Create a new snapshot directory
@@ -137,12 +228,40 @@ impl SnapshotManager {
             }
         }
         
-        // Sort by timestamp (newest first)
-        snapshots.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        // Sort by timestamp (newest first), then by ID for snapshots taken
+        // within the same second
+        snapshots.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
         
         Ok(snapshots)
     }
     
+    /// Write every node and edge of `storage` to a new snapshot
+    ///
+    /// The ID is derived from the current time, so IDs sort in creation
+    /// order. Pass the WAL checkpoint taken just before, if any, so the
+    /// log can be replayed on top of the snapshot.
+    pub fn create_snapshot<S: StorageBackend + ?Sized>(&self, storage: &S, wal_lsn: Option<LSN>) -> Result<Snapshot> {
+        let now = chrono::Utc::now();
+        let mut snapshot_id = format!("snapshot-{}", now.format("%Y%m%dT%H%M%S%.3fZ"));
+        let mut suffix = 1;
+        while self.base_dir.join(&snapshot_id).exists() {
+            suffix += 1;
+            snapshot_id = format!("snapshot-{}-{}", now.format("%Y%m%dT%H%M%S%.3fZ"), suffix);
+        }
+        let path = self.create_snapshot_dir(&snapshot_id)?;
+        
+        let nodes = storage.get_all_nodes();
+        let edges = storage.get_all_edges();
+        let mut snapshot = Snapshot::new(snapshot_id, path, nodes.len(), edges.len());
+        self.write_files(&mut snapshot, &nodes, &edges)?;
+        if let Some(lsn) = wal_lsn {
+            snapshot = snapshot.with_wal_lsn(lsn);
+        }
+        // Written last: a snapshot without metadata is not listed
+        snapshot.save_metadata()?;
+        Ok(snapshot)
+    }
+    
     /// Get a specific snapshot by ID
     pub fn get_snapshot(&self, snapshot_id: &str) -> Result<Snapshot> {
         let snapshot_dir = self.base_dir.join(snapshot_id);
@@ -185,6 +304,120 @@ impl SnapshotManager {
         
         Ok(to_delete)
     }
+    
+    /// Copy a snapshot to `store`, under its ID
+    ///
+    /// The metadata is uploaded last, so the snapshot is only listed in the
+    /// store once all of its files are there.
+    pub fn upload_snapshot(&self, snapshot_id: &str, store: &dyn SnapshotStore) -> Result<()> {
+        let snapshot = self.get_snapshot(snapshot_id)?;
+        let files = upload_dir(store, &snapshot.path, snapshot_id, &[METADATA_FILE])?;
+        info!("Uploaded snapshot {} ({} files)", snapshot_id, files);
+        Ok(())
+    }
+    
+    /// IDs of the complete snapshots in `store`, newest first for IDs
+    /// generated by [`SnapshotManager::create_snapshot`]
+    pub fn list_remote_snapshots(&self, store: &dyn SnapshotStore) -> Result<Vec<String>> {
+        let mut ids: Vec<String> = store
+            .list("")?
+            .into_iter()
+            .filter_map(|key| {
+                let (id, file) = key.split_once('/')?;
+                (file == METADATA_FILE).then(|| id.to_string())
+            })
+            .collect();
+        ids.sort_by(|a, b| b.cmp(a));
+        Ok(ids)
+    }
+    
+    /// Copy a snapshot from `store` into this manager's directory
+    ///
+    /// Fails if a snapshot with the same ID exists locally.
+    pub fn download_snapshot(&self, snapshot_id: &str, store: &dyn SnapshotStore) -> Result<Snapshot> {
+        let path = self.base_dir.join(snapshot_id);
+        if path.exists() {
+            return Err(DeepGraphError::InvalidOperation(format!(
+                "Snapshot {} already exists locally",
+                snapshot_id
+            )));
+        }
+        // Without the metadata the download would never be listed
+        store.get(&format!("{}/{}", snapshot_id, METADATA_FILE))?;
+        
+        let files = download_dir(store, snapshot_id, &path, &[METADATA_FILE])?;
+        let mut snapshot = Snapshot::load_metadata(&path)?;
+        snapshot.path = path;
+        snapshot.save_metadata()?;
+        info!("Downloaded snapshot {} ({} files)", snapshot_id, files);
+        Ok(snapshot)
+    }
+    
+    /// Write a snapshot as a single-file archive to `writer`
+    pub fn export_snapshot<W: Write>(&self, snapshot_id: &str, writer: W) -> Result<Snapshot> {
+        let mut snapshot = self.get_snapshot(snapshot_id)?;
+        let mut archive = ArchiveWriter::new(writer)?;
+        for node in self.read_nodes(&snapshot)? {
+            archive.write_node(node)?;
+        }
+        for edge in self.read_edges(&snapshot)? {
+            archive.write_edge(edge)?;
+        }
+        // The local directory means nothing to the receiver, and the
+        // archive itself is not encrypted
+        snapshot.path = PathBuf::new();
+        snapshot.encrypted = false;
+        let (snapshot, _) = archive.finish(snapshot)?;
+        info!("Exported snapshot {} as an archive", snapshot_id);
+        Ok(snapshot)
+    }
+    
+    /// Unpack a single-file archive from `reader` into a new snapshot
+    ///
+    /// The snapshot keeps the archived ID; fails if it exists locally.
+    pub fn import_snapshot<R: Read>(&self, reader: R) -> Result<Snapshot> {
+        let (mut nodes, mut edges) = (Vec::new(), Vec::new());
+        for entry in ArchiveReader::new(reader)? {
+            match entry? {
+                ArchiveEntry::Nodes(batch) => nodes.extend(batch),
+                ArchiveEntry::Edges(batch) => edges.extend(batch),
+                ArchiveEntry::Metadata(mut snapshot) => {
+                    let mut components = Path::new(&snapshot.id).components();
+                    if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+                        return Err(DeepGraphError::InvalidOperation(format!(
+                            "Invalid snapshot ID '{}' in archive",
+                            snapshot.id
+                        )));
+                    }
+                    if self.base_dir.join(&snapshot.id).exists() {
+                        return Err(DeepGraphError::InvalidOperation(format!(
+                            "Snapshot {} already exists locally",
+                            snapshot.id
+                        )));
+                    }
+                    snapshot.path = self.create_snapshot_dir(&snapshot.id)?;
+                    snapshot.node_count = nodes.len();
+                    snapshot.edge_count = edges.len();
+                    self.write_files(&mut snapshot, &nodes, &edges)?;
+                    snapshot.save_metadata()?;
+                    info!("Imported snapshot {} from an archive", snapshot.id);
+                    return Ok(snapshot);
+                }
+            }
+        }
+        Err(DeepGraphError::StorageError("Snapshot archive is truncated".to_string()))
+    }
+    
+    /// Delete a snapshot from `store`
+    pub fn delete_remote_snapshot(&self, snapshot_id: &str, store: &dyn SnapshotStore) -> Result<()> {
+        let metadata = format!("{}/{}", snapshot_id, METADATA_FILE);
+        // Metadata first, so a partly deleted snapshot is no longer listed
+        store.delete(&metadata)?;
+        for key in store.list(&format!("{}/", snapshot_id))? {
+            store.delete(&key)?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -279,5 +512,77 @@ mod tests {
         let snapshots = manager.list_snapshots().unwrap();
         assert_eq!(snapshots.len(), 2);
     }
+    
+    #[test]
+    fn test_snapshot_upload_download() {
+        use crate::persistence::{LocalSnapshotStore, ParquetReader};
+        use crate::storage::MemoryStorage;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let storage = MemoryStorage::new();
+        storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let store = LocalSnapshotStore::new(temp_dir.path().join("remote")).unwrap();
+        
+        let primary = SnapshotManager::new(temp_dir.path().join("primary")).unwrap();
+        let snapshot = primary.create_snapshot(&storage, None).unwrap();
+        primary.upload_snapshot(&snapshot.id, &store).unwrap();
+        assert_eq!(primary.list_remote_snapshots(&store).unwrap(), vec![snapshot.id.clone()]);
+        
+        let replica = SnapshotManager::new(temp_dir.path().join("replica")).unwrap();
+        let restored = replica.download_snapshot(&snapshot.id, &store).unwrap();
+        assert_eq!(restored.path, temp_dir.path().join("replica").join(&snapshot.id));
+        assert_eq!(ParquetReader::read_nodes(&restored.nodes_file()).unwrap().len(), 1);
+        assert!(replica.download_snapshot(&snapshot.id, &store).is_err());
+        
+        primary.delete_remote_snapshot(&snapshot.id, &store).unwrap();
+        assert!(store.list("").unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_snapshot_archive_export_import() {
+        use crate::storage::MemoryStorage;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let storage = MemoryStorage::new();
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+        
+        let primary = SnapshotManager::new(temp_dir.path().join("primary")).unwrap();
+        let snapshot = primary.create_snapshot(&storage, Some(7)).unwrap();
+        let mut archive = Vec::new();
+        primary.export_snapshot(&snapshot.id, &mut archive).unwrap();
+        
+        let replica = SnapshotManager::new(temp_dir.path().join("replica")).unwrap();
+        let imported = replica.import_snapshot(archive.as_slice()).unwrap();
+        assert_eq!(imported.id, snapshot.id);
+        assert_eq!((imported.node_count, imported.edge_count, imported.wal_lsn), (2, 1, Some(7)));
+        assert_eq!(replica.list_snapshots().unwrap().len(), 1);
+        assert!(replica.import_snapshot(archive.as_slice()).is_err());
+    }
+    
+    #[test]
+    fn test_encrypted_snapshot() {
+        use crate::storage::MemoryStorage;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let storage = MemoryStorage::new();
+        storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let key = EncryptionKey::from_hex(&EncryptionKey::generate_hex()).unwrap();
+        let manager = SnapshotManager::new(temp_dir.path().to_path_buf()).unwrap().with_encryption(key);
+        
+        let snapshot = manager.create_snapshot(&storage, None).unwrap();
+        assert!(snapshot.encrypted);
+        assert!(ParquetReader::read_nodes(&snapshot.nodes_file()).is_err());
+        let raw = fs::read(snapshot.nodes_file()).unwrap();
+        assert!(!raw.windows(4).any(|w| w == b"PAR1"));
+        assert!(!snapshot.nodes_file().with_extension("tmp").exists());
+        assert_eq!(manager.read_nodes(&snapshot).unwrap().len(), 1);
+        
+        // Listing works without the key, reading does not
+        let keyless = SnapshotManager::new(temp_dir.path().to_path_buf()).unwrap();
+        let listed = keyless.list_snapshots().unwrap();
+        assert!(listed[0].encrypted);
+        assert!(keyless.read_nodes(&listed[0]).is_err());
+    }
 }
-
@@ -0,0 +1,358 @@
+//! Snapshot storage targets
+//!
+//! A [`SnapshotStore`] holds snapshot and backup files under `/`-separated
+//! keys, so they can be kept off-host. [`LocalSnapshotStore`] keeps them in
+//! a directory, for example a mounted network share;
+//! [`ObjectSnapshotStore`] keeps them in an S3-compatible bucket (feature
+//! `s3`) or a Google Cloud Storage bucket (feature `gcs`).
+//!
+//! [`SnapshotManager::upload_snapshot`](crate::persistence::SnapshotManager::upload_snapshot)
+//! and [`SnapshotManager::download_snapshot`](crate::persistence::SnapshotManager::download_snapshot)
+//! ship snapshots to and from a store; [`upload_dir`] and [`download_dir`]
+//! do the same for any directory, such as a
+//! [`DiskStorage`](crate::storage::DiskStorage) backup.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let store = ObjectSnapshotStore::s3("deepgraph-backups")?.with_prefix("prod");
+//! let snapshot = manager.create_snapshot(&storage, None)?;
+//! manager.upload_snapshot(&snapshot.id, &store)?;
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use log::debug;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Key-value store for snapshot files
+///
+/// Keys are relative, `/`-separated paths. Implementations must be safe to
+/// share between threads; every method blocks until the operation is done.
+pub trait SnapshotStore: Send + Sync {
+    /// Store `data` under `key`, replacing any previous value
+    fn put(&self, key: &str, data: &[u8]) -> Result<()>;
+
+    /// Read the value under `key`, failing with
+    /// [`DeepGraphError::NotFound`] if there is none
+    fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+    /// Every key starting with `prefix`, sorted
+    fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Remove the value under `key`; missing keys are ignored
+    fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Join `prefix` and `key` with a single `/`
+pub(crate) fn join_key(prefix: &str, key: &str) -> String {
+    match prefix.trim_end_matches('/') {
+        "" => key.to_string(),
+        prefix => format!("{}/{}", prefix, key),
+    }
+}
+
+/// Upload every file below `dir` to `store` under `prefix`
+///
+/// Files named in `last` are uploaded after the others, so a reader
+/// polling for them sees a complete copy. Returns the number of files.
+pub fn upload_dir(store: &dyn SnapshotStore, dir: &Path, prefix: &str, last: &[&str]) -> Result<usize> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort_by_key(|key| (last.contains(&key.as_str()), key.clone()));
+    for key in &files {
+        let data = fs::read(dir.join(key))?;
+        store.put(&join_key(prefix, key), &data)?;
+    }
+    debug!("Uploaded {} files from {:?} to '{}'", files.len(), dir, prefix);
+    Ok(files.len())
+}
+
+/// Download every key under `prefix` from `store` into `dir`
+///
+/// Keys named in `last` are written after the others. Returns the number
+/// of files.
+pub fn download_dir(store: &dyn SnapshotStore, prefix: &str, dir: &Path, last: &[&str]) -> Result<usize> {
+    let root = join_key(prefix, "");
+    let mut keys: Vec<String> = store
+        .list(&root)?
+        .into_iter()
+        .filter_map(|key| key.strip_prefix(&root).map(str::to_string))
+        .collect();
+    keys.sort_by_key(|key| (last.contains(&key.as_str()), key.clone()));
+    for key in &keys {
+        let path = dir.join(relative_path(key)?);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, store.get(&join_key(prefix, key))?)?;
+    }
+    debug!("Downloaded {} files from '{}' to {:?}", keys.len(), prefix, dir);
+    Ok(keys.len())
+}
+
+/// Keys of every file below `dir`, relative to `root`
+fn collect_files(root: &Path, dir: &Path, keys: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, keys)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<_> = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+            keys.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+/// Turn a key into a relative path, rejecting keys that escape the root
+fn relative_path(key: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(key);
+    if key.is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(DeepGraphError::InvalidOperation(format!("Invalid snapshot store key '{}'", key)));
+    }
+    Ok(path)
+}
+
+/// Snapshot store in a local directory
+pub struct LocalSnapshotStore {
+    root: PathBuf,
+}
+
+impl LocalSnapshotStore {
+    /// Store files below `root`, creating it if needed
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Directory the files are stored in
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl SnapshotStore for LocalSnapshotStore {
+    fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        let path = self.root.join(relative_path(key)?);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write beside the target and rename, so readers never see a
+        // partially written file
+        let mut staging = path.clone().into_os_string();
+        staging.push(".partial");
+        fs::write(&staging, data)?;
+        fs::rename(&staging, &path)?;
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let path = self.root.join(relative_path(key)?);
+        fs::read(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DeepGraphError::NotFound(format!("Snapshot store key '{}'", key)),
+            _ => DeepGraphError::IoError(e),
+        })
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        collect_files(&self.root, &self.root, &mut keys)?;
+        keys.retain(|key| key.starts_with(prefix) && !key.ends_with(".partial"));
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.root.join(relative_path(key)?)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(any(feature = "s3", feature = "gcs"))]
+pub use object::ObjectSnapshotStore;
+
+#[cfg(any(feature = "s3", feature = "gcs"))]
+mod object {
+    use super::*;
+    use object_store::path::Path as ObjectPath;
+    use object_store::{ObjectStore, PutPayload};
+    use std::sync::Arc;
+    use tokio::runtime::Runtime;
+
+    fn store_error(e: object_store::Error) -> DeepGraphError {
+        match e {
+            object_store::Error::NotFound { path, .. } => {
+                DeepGraphError::NotFound(format!("Snapshot store key '{}'", path))
+            }
+            e => DeepGraphError::StorageError(format!("Object store error: {}", e)),
+        }
+    }
+
+    /// Snapshot store in an object storage bucket
+    ///
+    /// Calls block on a private runtime, so they must not be made from
+    /// within an async context.
+    pub struct ObjectSnapshotStore {
+        store: Arc<dyn ObjectStore>,
+        prefix: String,
+        runtime: Runtime,
+    }
+
+    impl ObjectSnapshotStore {
+        /// Store files in `store`, which may be any `object_store` backend
+        pub fn new(store: Arc<dyn ObjectStore>) -> Result<Self> {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(DeepGraphError::IoError)?;
+            Ok(Self {
+                store,
+                prefix: String::new(),
+                runtime,
+            })
+        }
+
+        /// Store files in an S3 bucket
+        ///
+        /// Credentials and region come from the usual `AWS_*` environment
+        /// variables; set `AWS_ENDPOINT` for S3-compatible services.
+        #[cfg(feature = "s3")]
+        pub fn s3(bucket: &str) -> Result<Self> {
+            let store = object_store::aws::AmazonS3Builder::from_env()
+                .with_bucket_name(bucket)
+                .build()
+                .map_err(store_error)?;
+            Self::new(Arc::new(store))
+        }
+
+        /// Store files in a bucket of the S3-compatible service at `endpoint`
+        #[cfg(feature = "s3")]
+        pub fn s3_compatible(bucket: &str, endpoint: &str) -> Result<Self> {
+            let store = object_store::aws::AmazonS3Builder::from_env()
+                .with_bucket_name(bucket)
+                .with_endpoint(endpoint)
+                .with_allow_http(endpoint.starts_with("http://"))
+                .build()
+                .map_err(store_error)?;
+            Self::new(Arc::new(store))
+        }
+
+        /// Store files in a Google Cloud Storage bucket
+        ///
+        /// Credentials come from `GOOGLE_SERVICE_ACCOUNT` or the other
+        /// `GOOGLE_*` environment variables.
+        #[cfg(feature = "gcs")]
+        pub fn gcs(bucket: &str) -> Result<Self> {
+            let store = object_store::gcp::GoogleCloudStorageBuilder::from_env()
+                .with_bucket_name(bucket)
+                .build()
+                .map_err(store_error)?;
+            Self::new(Arc::new(store))
+        }
+
+        /// Keep every key below `prefix` in the bucket
+        pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+            self.prefix = prefix.into().trim_matches('/').to_string();
+            self
+        }
+
+        fn location(&self, key: &str) -> Result<ObjectPath> {
+            relative_path(key)?;
+            Ok(ObjectPath::from(join_key(&self.prefix, key)))
+        }
+    }
+
+    impl SnapshotStore for ObjectSnapshotStore {
+        fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+            let location = self.location(key)?;
+            self.runtime
+                .block_on(self.store.put(&location, PutPayload::from(data.to_vec())))
+                .map_err(store_error)?;
+            Ok(())
+        }
+
+        fn get(&self, key: &str) -> Result<Vec<u8>> {
+            let location = self.location(key)?;
+            self.runtime.block_on(async {
+                let object = self.store.get(&location).await.map_err(store_error)?;
+                let bytes = object.bytes().await.map_err(store_error)?;
+                Ok(bytes.to_vec())
+            })
+        }
+
+        fn list(&self, prefix: &str) -> Result<Vec<String>> {
+            let root = join_key(&self.prefix, "");
+            let mut keys = Vec::new();
+            let mut pending = vec![(!self.prefix.is_empty()).then(|| ObjectPath::from(self.prefix.as_str()))];
+            // Walk one level at a time, which needs no stream handling
+            while let Some(dir) = pending.pop() {
+                let listing = self
+                    .runtime
+                    .block_on(self.store.list_with_delimiter(dir.as_ref()))
+                    .map_err(store_error)?;
+                pending.extend(listing.common_prefixes.into_iter().map(Some));
+                keys.extend(listing.objects.into_iter().filter_map(|object| {
+                    let key = object.location.as_ref().strip_prefix(root.as_str())?.to_string();
+                    key.starts_with(prefix).then_some(key)
+                }));
+            }
+            keys.sort();
+            Ok(keys)
+        }
+
+        fn delete(&self, key: &str) -> Result<()> {
+            let location = self.location(key)?;
+            match self.runtime.block_on(self.store.delete(&location)) {
+                Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+                Err(e) => Err(store_error(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_local_store() {
+        let dir = tempdir().unwrap();
+        let store = LocalSnapshotStore::new(dir.path().join("store")).unwrap();
+        store.put("snap-1/nodes.parquet", b"nodes").unwrap();
+        store.put("snap-1/metadata.json", b"{}").unwrap();
+        store.put("snap-2/metadata.json", b"{}").unwrap();
+
+        assert_eq!(store.get("snap-1/nodes.parquet").unwrap(), b"nodes");
+        assert!(matches!(store.get("snap-3/metadata.json"), Err(DeepGraphError::NotFound(_))));
+        assert!(store.get("../outside").is_err());
+        assert_eq!(
+            store.list("snap-1/").unwrap(),
+            vec!["snap-1/metadata.json", "snap-1/nodes.parquet"]
+        );
+
+        store.delete("snap-1/nodes.parquet").unwrap();
+        store.delete("snap-1/nodes.parquet").unwrap();
+        assert_eq!(store.list("").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_dir_round_trip() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("backup");
+        fs::create_dir_all(source.join("blobs")).unwrap();
+        fs::write(source.join("conf"), b"config").unwrap();
+        fs::write(source.join("blobs/1"), b"blob").unwrap();
+
+        let store = LocalSnapshotStore::new(dir.path().join("store")).unwrap();
+        assert_eq!(upload_dir(&store, &source, "backups/b1", &["conf"]).unwrap(), 2);
+        let target = dir.path().join("restored");
+        assert_eq!(download_dir(&store, "backups/b1", &target, &[]).unwrap(), 2);
+        assert_eq!(fs::read(target.join("blobs/1")).unwrap(), b"blob");
+        assert_eq!(fs::read(target.join("conf")).unwrap(), b"config");
+    }
+}
@@ -10,7 +10,9 @@ use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::graph::{Node, Edge, PropertyValue, NodeId, EdgeId};
-use crate::storage::{GraphStorage, StorageBackend};
+use crate::config::DeepGraphConfig;
+use crate::database::DeepGraph;
+use crate::storage::{CostConstants, GraphOp, GraphStatistics, GraphStorage, StorageBackend};
 use crate::mvcc::{TransactionManager, txn_manager::TransactionId, current_timestamp};
 use crate::index::{IndexManager, IndexConfig, IndexType};
 use crate::wal::{WAL, WALConfig, WALRecovery};
@@ -48,30 +50,455 @@ fn py_to_property_value(obj: &Bound<'_, PyAny>) -> PyResult<PropertyValue> {
         Ok(PropertyValue::Null)
     } else if let Ok(s) = obj.extract::<String>() {
         Ok(PropertyValue::String(s))
+    } else if obj.is_instance_of::<pyo3::types::PyBool>() {
+        // Checked before integers, since Python bools are ints
+        Ok(PropertyValue::Boolean(obj.extract::<bool>()?))
     } else if let Ok(i) = obj.extract::<i64>() {
         Ok(PropertyValue::Integer(i))
     } else if let Ok(f) = obj.extract::<f64>() {
         Ok(PropertyValue::Float(f))
-    } else if let Ok(b) = obj.extract::<bool>() {
-        Ok(PropertyValue::Boolean(b))
+    } else if let Ok(list) = obj.downcast::<pyo3::types::PyList>() {
+        Ok(PropertyValue::List(list.iter().map(|item| py_to_property_value(&item)).collect::<PyResult<_>>()?))
+    } else if let Ok(tuple) = obj.downcast::<pyo3::types::PyTuple>() {
+        // Tuples come back as lists
+        Ok(PropertyValue::List(tuple.iter().map(|item| py_to_property_value(&item)).collect::<PyResult<_>>()?))
+    } else if let Ok(dict) = obj.downcast::<pyo3::types::PyDict>() {
+        let mut map = HashMap::new();
+        for (key, value) in dict.iter() {
+            let key = key.extract::<String>().map_err(|_| {
+                PyValueError::new_err(format!("Property map keys must be strings, got {}", type_name(&key)))
+            })?;
+            map.insert(key, py_to_property_value(&value)?);
+        }
+        Ok(PropertyValue::Map(map))
     } else {
-        Err(PyValueError::new_err("Unsupported property value type"))
+        Err(PyValueError::new_err(format!("Unsupported property value type: {}", type_name(obj))))
+    }
+}
+
+/// Python type name of `obj`, for error messages
+fn type_name(obj: &Bound<'_, PyAny>) -> String {
+    obj.get_type().name().map(|name| name.to_string()).unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Parse, plan and execute a Cypher query against `storage`
+fn run_cypher<S: StorageBackend>(
+    storage: Arc<S>,
+    query: &str,
+    parameters: HashMap<String, PropertyValue>,
+) -> PyResult<crate::query::QueryResult> {
+    use crate::query::{ast::Statement, rewrite::prepare, QueryExecutor};
+
+    let statement = CypherParser::parse(query)
+        .map_err(|e| PyRuntimeError::new_err(format!("Parse error: {}", e)))?;
+    let Statement::Query(query_ast) = prepare(&statement, &parameters)
+        .map_err(|e| PyRuntimeError::new_err(format!("Preparation error: {}", e)))?;
+    let planner = QueryPlanner::new();
+    let logical_plan = planner.logical_plan(&query_ast)
+        .map_err(|e| PyRuntimeError::new_err(format!("Planning error: {}", e)))?;
+    let physical_plan = planner.physical_plan(&logical_plan)
+        .map_err(|e| PyRuntimeError::new_err(format!("Physical planning error: {}", e)))?;
+    QueryExecutor::new(storage)
+        .with_parameters(parameters)
+        .execute(&physical_plan)
+        .map_err(|e| PyRuntimeError::new_err(format!("Execution error: {}", e)))
+}
+
+/// Convert a query result to a dictionary of columns, rows (as dictionaries),
+/// row count and execution time
+fn query_result_to_py(py: Python, result: crate::query::QueryResult) -> PyResult<PyObject> {
+    let result_dict = pyo3::types::PyDict::new_bound(py);
+    result_dict.set_item("columns", result.columns)?;
+    result_dict.set_item("row_count", result.row_count)?;
+    result_dict.set_item("execution_time_ms", result.execution_time_ms)?;
+
+    let rows = pyo3::types::PyList::empty_bound(py);
+    for row in result.rows {
+        let row_dict = pyo3::types::PyDict::new_bound(py);
+        for (key, value) in row {
+            row_dict.set_item(key, property_value_to_py(py, &value)?)?;
+        }
+        rows.append(row_dict)?;
+    }
+    result_dict.set_item("rows", rows)?;
+
+    Ok(result_dict.to_object(py))
+}
+
+/// Record batches of a pyarrow Table or RecordBatch, or a pandas DataFrame
+///
+/// The data crosses over as an Arrow IPC stream written by pyarrow, which
+/// avoids tying the bindings to the pyo3 version of arrow's `pyarrow`
+/// feature at the cost of one copy.
+fn arrow_batches(py: Python, data: &Bound<'_, PyAny>) -> PyResult<Vec<arrow::array::RecordBatch>> {
+    let pa = py.import_bound("pyarrow")?;
+    let table_type = pa.getattr("Table")?;
+    let table = if data.is_instance(&table_type)? {
+        data.clone()
+    } else if data.is_instance(&pa.getattr("RecordBatch")?)? {
+        table_type.call_method1("from_batches", (vec![data.clone()],))?
+    } else if data.hasattr("to_records")? {
+        let kwargs = pyo3::types::PyDict::new_bound(py);
+        kwargs.set_item("preserve_index", false)?;
+        table_type.call_method("from_pandas", (data,), Some(&kwargs))?
+    } else {
+        return Err(PyValueError::new_err("Expected a pyarrow Table or RecordBatch, or a pandas DataFrame"));
+    };
+
+    let sink = pa.getattr("BufferOutputStream")?.call0()?;
+    let writer = py.import_bound("pyarrow.ipc")?.call_method1("new_stream", (&sink, table.getattr("schema")?))?;
+    writer.call_method1("write_table", (&table,))?;
+    writer.call_method0("close")?;
+    let ipc: Vec<u8> = sink.call_method0("getvalue")?.call_method0("to_pybytes")?.extract()?;
+    crate::import::arrow::read_ipc_stream(ipc.as_slice())
+        .map_err(|e| PyValueError::new_err(format!("Invalid Arrow data: {}", e)))
+}
+
+/// Dictionary with 'id', 'labels' and 'properties' keys for a node
+fn node_to_py(py: Python, node: &Node) -> PyResult<PyObject> {
+    let dict = pyo3::types::PyDict::new_bound(py);
+    dict.set_item("id", node.id().to_string())?;
+    dict.set_item("labels", node.labels().to_vec())?;
+    let props = pyo3::types::PyDict::new_bound(py);
+    for (key, value) in node.properties() {
+        props.set_item(key, property_value_to_py(py, value)?)?;
+    }
+    dict.set_item("properties", props)?;
+    Ok(dict.to_object(py))
+}
+
+/// Dictionary with 'id', 'from', 'to', 'label' and 'properties' keys for
+/// an edge
+fn edge_to_py(py: Python, edge: &Edge) -> PyResult<PyObject> {
+    let dict = pyo3::types::PyDict::new_bound(py);
+    dict.set_item("id", edge.id().to_string())?;
+    dict.set_item("from", edge.from().to_string())?;
+    dict.set_item("to", edge.to().to_string())?;
+    dict.set_item("label", edge.relationship_type())?;
+    let props = pyo3::types::PyDict::new_bound(py);
+    for (key, value) in edge.properties() {
+        props.set_item(key, property_value_to_py(py, value)?)?;
+    }
+    dict.set_item("properties", props)?;
+    Ok(dict.to_object(py))
+}
+
+/// A node from a record passed to `add_nodes`
+fn record_to_node(record: &Bound<'_, pyo3::types::PyDict>) -> PyResult<Node> {
+    let labels: Vec<String> = match record.get_item("labels")? {
+        Some(labels) => labels.extract()?,
+        None => Vec::new(),
+    };
+    let mut node = Node::new(labels);
+    for (key, value) in record_properties(record)? {
+        node.set_property(key, value);
+    }
+    Ok(node)
+}
+
+/// An edge from a record passed to `add_edges`
+fn record_to_edge(record: &Bound<'_, pyo3::types::PyDict>) -> PyResult<Edge> {
+    let label: String = record
+        .get_item("label")?
+        .ok_or_else(|| PyValueError::new_err("Missing label"))?
+        .extract()?;
+    let mut edge = Edge::new(record_node_id(record, "from_id")?, record_node_id(record, "to_id")?, label);
+    for (key, value) in record_properties(record)? {
+        edge.set_property(key, value);
+    }
+    Ok(edge)
+}
+
+/// The `properties` entry of a record passed to `add_nodes` or `add_edges`
+fn record_properties(record: &Bound<'_, pyo3::types::PyDict>) -> PyResult<HashMap<String, PropertyValue>> {
+    let mut properties = HashMap::new();
+    if let Some(entries) = record.get_item("properties")? {
+        for (key, value) in entries.downcast::<pyo3::types::PyDict>()?.iter() {
+            properties.insert(key.extract()?, py_to_property_value(&value)?);
+        }
+    }
+    Ok(properties)
+}
+
+/// A node ID field of a record passed to `add_edges`
+fn record_node_id(record: &Bound<'_, pyo3::types::PyDict>, field: &str) -> PyResult<NodeId> {
+    let id: String = record
+        .get_item(field)?
+        .ok_or_else(|| PyValueError::new_err(format!("Missing {}", field)))?
+        .extract()?;
+    let uuid = Uuid::parse_str(&id).map_err(|e| PyValueError::new_err(format!("Invalid {}: {}", field, e)))?;
+    Ok(NodeId::from_uuid(uuid))
+}
+
+/// Dictionary of import statistics
+fn import_stats_to_py(py: Python, stats: crate::import::ImportStats) -> PyResult<PyObject> {
+    let dict = pyo3::types::PyDict::new_bound(py);
+    dict.set_item("nodes_imported", stats.nodes_imported)?;
+    dict.set_item("edges_imported", stats.edges_imported)?;
+    dict.set_item("duplicates", stats.duplicates)?;
+    dict.set_item("duration_ms", stats.duration_ms)?;
+    dict.set_item("errors", stats.errors)?;
+    dict.set_item("node_id_map", stats.node_id_map)?;
+    Ok(dict.to_object(py))
+}
+
+/// Dictionary of export statistics, summed over the files of one export
+fn export_stats_to_py(py: Python, stats: &[crate::export::ExportStats]) -> PyResult<PyObject> {
+    let dict = pyo3::types::PyDict::new_bound(py);
+    dict.set_item("nodes_exported", stats.iter().map(|s| s.nodes_exported).sum::<usize>())?;
+    dict.set_item("edges_exported", stats.iter().map(|s| s.edges_exported).sum::<usize>())?;
+    dict.set_item("duration_ms", stats.iter().map(|s| s.duration_ms).sum::<u64>())?;
+    Ok(dict.to_object(py))
+}
+
+/// A pyarrow Table holding a query result, crossing over as an Arrow IPC
+/// stream the same way [`arrow_batches`] brings tables in
+fn query_result_to_arrow(py: Python, result: &crate::query::QueryResult) -> PyResult<PyObject> {
+    use crate::persistence::arrow_export::{query_result_to_record_batch, write_ipc_stream};
+
+    let ipc = query_result_to_record_batch(result)
+        .and_then(|batch| write_ipc_stream(&[batch]))
+        .map_err(|e| PyRuntimeError::new_err(format!("Arrow conversion error: {}", e)))?;
+    let buffer = py.import_bound("pyarrow")?.call_method1("py_buffer", (pyo3::types::PyBytes::new_bound(py, &ipc),))?;
+    let table = py.import_bound("pyarrow.ipc")?.call_method1("open_stream", (buffer,))?.call_method0("read_all")?;
+    Ok(table.unbind())
+}
+
+/// Storage behind a `PyGraphStorage`: a plain in-memory graph, or the
+/// backend of a database opened from configuration
+#[derive(Clone)]
+struct Backend {
+    storage: Arc<dyn StorageBackend>,
+    /// Database the backend belongs to, for flushing its WAL and indexes
+    database: Option<Arc<DeepGraph>>,
+}
+
+impl Backend {
+    fn memory(storage: GraphStorage) -> Self {
+        Backend {
+            storage: Arc::new(storage),
+            database: None,
+        }
+    }
+
+    fn database(database: DeepGraph) -> Self {
+        Backend {
+            storage: database.storage(),
+            database: Some(Arc::new(database)),
+        }
+    }
+
+    /// Delete every node, and with them every edge, through the backend so
+    /// the deletes are logged like any other write
+    fn clear(&self) -> crate::error::Result<()> {
+        let ids: Vec<NodeId> = self.storage.iter_nodes().map(|node| node.id()).collect();
+        for id in ids {
+            self.storage.delete_node(id)?;
+        }
+        Ok(())
+    }
+}
+
+impl StorageBackend for Backend {
+    fn add_node(&self, node: Node) -> crate::error::Result<NodeId> {
+        self.storage.add_node(node)
+    }
+
+    fn get_node(&self, id: NodeId) -> crate::error::Result<Node> {
+        self.storage.get_node(id)
+    }
+
+    fn update_node(&self, node: Node) -> crate::error::Result<()> {
+        self.storage.update_node(node)
+    }
+
+    fn delete_node(&self, id: NodeId) -> crate::error::Result<()> {
+        self.storage.delete_node(id)
+    }
+
+    fn add_edge(&self, edge: Edge) -> crate::error::Result<EdgeId> {
+        self.storage.add_edge(edge)
+    }
+
+    fn get_edge(&self, id: EdgeId) -> crate::error::Result<Edge> {
+        self.storage.get_edge(id)
+    }
+
+    fn update_edge(&self, edge: Edge) -> crate::error::Result<()> {
+        self.storage.update_edge(edge)
+    }
+
+    fn delete_edge(&self, id: EdgeId) -> crate::error::Result<()> {
+        self.storage.delete_edge(id)
+    }
+
+    fn get_nodes_by_label(&self, label: &str) -> Vec<Node> {
+        self.storage.get_nodes_by_label(label)
+    }
+
+    fn get_all_nodes(&self) -> Vec<Node> {
+        self.storage.get_all_nodes()
+    }
+
+    fn get_all_edges(&self) -> Vec<Edge> {
+        self.storage.get_all_edges()
+    }
+
+    fn get_edges_by_type(&self, relationship_type: &str) -> Vec<Edge> {
+        self.storage.get_edges_by_type(relationship_type)
+    }
+
+    fn get_nodes_by_property(&self, key: &str, value: &PropertyValue) -> Vec<Node> {
+        self.storage.get_nodes_by_property(key, value)
+    }
+
+    fn iter_nodes(&self) -> Box<dyn Iterator<Item = Node> + '_> {
+        self.storage.iter_nodes()
+    }
+
+    fn iter_edges(&self) -> Box<dyn Iterator<Item = Edge> + '_> {
+        self.storage.iter_edges()
+    }
+
+    fn get_outgoing_edges(&self, node_id: NodeId) -> crate::error::Result<Vec<Edge>> {
+        self.storage.get_outgoing_edges(node_id)
+    }
+
+    fn get_incoming_edges(&self, node_id: NodeId) -> crate::error::Result<Vec<Edge>> {
+        self.storage.get_incoming_edges(node_id)
+    }
+
+    fn node_count(&self) -> usize {
+        self.storage.node_count()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.storage.edge_count()
+    }
+
+    fn cost_constants(&self) -> CostConstants {
+        self.storage.cost_constants()
+    }
+
+    fn statistics(&self) -> Option<&GraphStatistics> {
+        self.storage.statistics()
+    }
+
+    fn degree(&self, node_id: NodeId) -> crate::error::Result<usize> {
+        self.storage.degree(node_id)
+    }
+
+    fn apply_batch(&self, ops: Vec<GraphOp>) -> crate::error::Result<()> {
+        self.storage.apply_batch(ops)
+    }
+
+    fn flush(&self) -> crate::error::Result<()> {
+        self.storage.flush()
     }
 }
 
 /// Python wrapper for GraphStorage
 #[pyclass]
 pub struct PyGraphStorage {
-    storage: Arc<RwLock<GraphStorage>>,
+    storage: Arc<RwLock<Backend>>,
+}
+
+impl PyGraphStorage {
+    fn from_backend(backend: Backend) -> Self {
+        PyGraphStorage {
+            storage: Arc::new(RwLock::new(backend)),
+        }
+    }
+
+    fn open_database(config: DeepGraphConfig) -> PyResult<Self> {
+        let database = DeepGraph::open(config)
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to open database: {}", e)))?;
+        Ok(Self::from_backend(Backend::database(database)))
+    }
+
+    /// Apply `ops` as one batch with the GIL released
+    fn apply_ops(&self, py: Python, ops: Vec<GraphOp>) -> crate::error::Result<()> {
+        let storage = self.storage.read()
+            .map_err(|e| crate::error::DeepGraphError::StorageError(format!("Lock error: {}", e)))?
+            .clone();
+        py.allow_threads(move || storage.apply_batch(ops))
+    }
+
+    fn run_query(
+        &self,
+        py: Python,
+        query: &str,
+        params: Option<HashMap<String, PyObject>>,
+    ) -> PyResult<crate::query::QueryResult> {
+        let mut parameters = HashMap::new();
+        for (key, value) in params.unwrap_or_default() {
+            parameters.insert(key, py_to_property_value(value.bind(py))?);
+        }
+
+        // Clones share the underlying backend, so the lock is only held briefly
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?
+            .clone();
+        run_cypher(Arc::new(storage), query, parameters)
+    }
 }
 
 #[pymethods]
 impl PyGraphStorage {
-    /// Create a new graph storage
+    /// Create a new in-memory graph storage
     #[new]
     fn new() -> Self {
-        PyGraphStorage {
-            storage: Arc::new(RwLock::new(GraphStorage::new())),
+        Self::from_backend(Backend::memory(GraphStorage::new()))
+    }
+
+    /// Open a persistent graph in a directory, creating it if needed
+    ///
+    /// The graph is kept in disk storage under the directory; writes go to
+    /// a write-ahead log in it before they are applied, and indexes are
+    /// saved alongside.
+    ///
+    /// Args:
+    ///     path: Database directory
+    ///
+    /// Example:
+    ///     storage = GraphStorage.open("./mydb")
+    ///     storage.add_node(["Person"], {"name": "Alice"})
+    ///     storage.flush()
+    #[staticmethod]
+    fn open(path: String) -> PyResult<Self> {
+        let mut config = DeepGraphConfig::default();
+        config.storage.storage_type = "disk".to_string();
+        config.storage.disk_path = std::path::Path::new(&path).join("graph").to_string_lossy().into_owned();
+        config.storage.data_dir = path;
+        Self::open_database(config)
+    }
+
+    /// Open the graph described by a TOML configuration file
+    ///
+    /// `storage.storage_type` selects the backend ("memory", "columnar" or
+    /// "disk"), and the WAL, indexes, cache and encryption are set up as
+    /// configured. In-memory backends are recovered from their snapshots
+    /// and WAL. DEEPGRAPH_* environment variables override the file.
+    ///
+    /// Args:
+    ///     toml_path: Path to the configuration file
+    #[staticmethod]
+    fn open_with_config(toml_path: String) -> PyResult<Self> {
+        let config = DeepGraphConfig::from_file_with_env(toml_path)
+            .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?;
+        Self::open_database(config)
+    }
+
+    /// Flush the write-ahead log and indexes of an opened database
+    ///
+    /// Does nothing for in-memory storage created with GraphStorage().
+    fn flush(&self) -> PyResult<()> {
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
+        match &storage.database {
+            Some(database) => database.flush()
+                .map_err(|e| PyRuntimeError::new_err(format!("Failed to flush: {}", e))),
+            None => Ok(()),
         }
     }
 
@@ -79,7 +506,9 @@ impl PyGraphStorage {
     /// 
     /// Args:
     ///     labels: List of string labels for the node
-    ///     properties: Dictionary of properties (key-value pairs)
+    ///     properties: Dictionary of properties (key-value pairs); values may
+    ///         be None, str, int, float, bool, or lists, tuples and dicts of
+    ///         these, nested to any depth
     /// 
     /// Returns:
     ///     Node ID as a string
@@ -103,6 +532,67 @@ impl PyGraphStorage {
         })
     }
 
+    /// Add many nodes in one batch
+    ///
+    /// The records are converted in a single pass, then inserted together
+    /// with the GIL released, which is much faster than calling add_node
+    /// per node. If any node is rejected, none are added.
+    ///
+    /// Args:
+    ///     nodes: List of dictionaries with optional "labels" (list of
+    ///         strings) and "properties" (dictionary) entries
+    ///
+    /// Returns:
+    ///     List of node IDs as strings, in input order
+    ///
+    /// Example:
+    ///     ids = storage.add_nodes([
+    ///         {"labels": ["Person"], "properties": {"name": "Alice"}},
+    ///         {"labels": ["Person"], "properties": {"name": "Bob"}},
+    ///     ])
+    fn add_nodes(&self, py: Python, nodes: Vec<Bound<'_, pyo3::types::PyDict>>) -> PyResult<Vec<String>> {
+        let mut ids = Vec::with_capacity(nodes.len());
+        let mut ops = Vec::with_capacity(nodes.len());
+        for (index, record) in nodes.iter().enumerate() {
+            let node = record_to_node(record).map_err(|e| PyValueError::new_err(format!("Node {}: {}", index, e)))?;
+            ids.push(node.id().to_string());
+            ops.push(GraphOp::AddNode(node));
+        }
+
+        self.apply_ops(py, ops)
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to add nodes: {}", e)))?;
+        Ok(ids)
+    }
+
+    /// Add many edges in one batch
+    ///
+    /// Like add_nodes: converted in one pass and inserted together with the
+    /// GIL released. If any edge is rejected, for example because an
+    /// endpoint does not exist, none are added.
+    ///
+    /// Args:
+    ///     edges: List of dictionaries with "from_id", "to_id" and "label"
+    ///         entries and an optional "properties" dictionary
+    ///
+    /// Returns:
+    ///     List of edge IDs as strings, in input order
+    ///
+    /// Example:
+    ///     storage.add_edges([{"from_id": ids[0], "to_id": ids[1], "label": "KNOWS"}])
+    fn add_edges(&self, py: Python, edges: Vec<Bound<'_, pyo3::types::PyDict>>) -> PyResult<Vec<String>> {
+        let mut ids = Vec::with_capacity(edges.len());
+        let mut ops = Vec::with_capacity(edges.len());
+        for (index, record) in edges.iter().enumerate() {
+            let edge = record_to_edge(record).map_err(|e| PyValueError::new_err(format!("Edge {}: {}", index, e)))?;
+            ids.push(edge.id().to_string());
+            ops.push(GraphOp::AddEdge(edge));
+        }
+
+        self.apply_ops(py, ops)
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to add edges: {}", e)))?;
+        Ok(ids)
+    }
+
     /// Add an edge between two nodes
     /// 
     /// Args:
@@ -453,6 +943,100 @@ impl PyGraphStorage {
         Ok(edges.iter().map(|edge| edge.id().to_string()).collect())
     }
 
+    /// Iterate over nodes lazily
+    ///
+    /// Unlike get_all_nodes, only node IDs are collected up front; each node
+    /// dictionary is built when the loop reaches it.
+    ///
+    /// Args:
+    ///     label: Only visit nodes with this label (all nodes if None)
+    ///
+    /// Returns:
+    ///     Iterator of node dictionaries
+    ///
+    /// Example:
+    ///     for node in storage.nodes(label="Person"):
+    ///         print(node['properties']['name'])
+    #[pyo3(signature = (label=None))]
+    fn nodes(&self, label: Option<String>) -> PyResult<PyNodeIterator> {
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?
+            .clone();
+        let ids: Vec<NodeId> = match label {
+            Some(label) => storage.get_nodes_by_label(&label).iter().map(|node| node.id()).collect(),
+            None => storage.iter_nodes().map(|node| node.id()).collect(),
+        };
+        Ok(PyNodeIterator { storage, ids: ids.into_iter() })
+    }
+
+    /// Iterate over edges lazily
+    ///
+    /// Args:
+    ///     relationship_type: Only visit edges of this type (all edges if None)
+    ///
+    /// Returns:
+    ///     Iterator of edge dictionaries
+    #[pyo3(signature = (relationship_type=None))]
+    fn edges(&self, relationship_type: Option<String>) -> PyResult<PyEdgeIterator> {
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?
+            .clone();
+        let ids: Vec<EdgeId> = storage
+            .iter_edges()
+            .filter(|edge| relationship_type.as_deref().map_or(true, |t| edge.relationship_type() == t))
+            .map(|edge| edge.id())
+            .collect();
+        Ok(PyEdgeIterator { storage, ids: ids.into_iter() })
+    }
+
+    /// Iterate over the neighbors of a node lazily
+    ///
+    /// Each neighbor is visited once, however many edges lead to it.
+    ///
+    /// Args:
+    ///     node_id: Node ID as a string
+    ///     direction: "out" (targets of outgoing edges), "in" (sources of
+    ///         incoming edges) or "both"
+    ///
+    /// Returns:
+    ///     Iterator of node dictionaries
+    ///
+    /// Example:
+    ///     for friend in storage.neighbors(alice, direction="out"):
+    ///         print(friend['properties']['name'])
+    #[pyo3(signature = (node_id, direction="out".to_string()))]
+    fn neighbors(&self, node_id: String, direction: String) -> PyResult<PyNodeIterator> {
+        let uuid = Uuid::parse_str(&node_id)
+            .map_err(|e| PyValueError::new_err(format!("Invalid node_id: {}", e)))?;
+        let nid = NodeId::from_uuid(uuid);
+        let (outgoing, incoming) = match direction.as_str() {
+            "out" => (true, false),
+            "in" => (false, true),
+            "both" => (true, true),
+            _ => return Err(PyValueError::new_err(format!(
+                "Invalid direction '{}': expected 'out', 'in' or 'both'", direction
+            ))),
+        };
+
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?
+            .clone();
+        let mut ids = Vec::new();
+        if outgoing {
+            let edges = storage.get_outgoing_edges(nid)
+                .map_err(|e| PyRuntimeError::new_err(format!("Failed to get outgoing edges: {}", e)))?;
+            ids.extend(edges.iter().map(|edge| edge.to()));
+        }
+        if incoming {
+            let edges = storage.get_incoming_edges(nid)
+                .map_err(|e| PyRuntimeError::new_err(format!("Failed to get incoming edges: {}", e)))?;
+            ids.extend(edges.iter().map(|edge| edge.from()));
+        }
+        let mut seen = std::collections::HashSet::new();
+        ids.retain(|id| seen.insert(*id));
+        Ok(PyNodeIterator { storage, ids: ids.into_iter() })
+    }
+
     /// Get all nodes in the graph
     /// 
     /// Returns:
@@ -462,23 +1046,7 @@ impl PyGraphStorage {
             let storage = self.storage.read()
                 .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
             
-            let nodes = storage.get_all_nodes();
-            let mut result = Vec::new();
-            
-            for node in nodes {
-                let dict = pyo3::types::PyDict::new_bound(py);
-                dict.set_item("id", node.id().to_string())?;
-                dict.set_item("labels", node.labels().to_vec())?;
-                
-                let props = pyo3::types::PyDict::new_bound(py);
-                for (key, value) in node.properties() {
-                    props.set_item(key, property_value_to_py(py, value)?)?;
-                }
-                dict.set_item("properties", props)?;
-                result.push(dict.to_object(py));
-            }
-            
-            Ok(result)
+            storage.get_all_nodes().iter().map(|node| node_to_py(py, node)).collect()
         })
     }
 
@@ -499,48 +1067,53 @@ impl PyGraphStorage {
     ///     for row in result['rows']:
     ///         print(row['name'], row['age'])
     fn execute_cypher(&self, py: Python, query: String) -> PyResult<PyObject> {
-        use crate::query::{CypherParser, QueryPlanner, QueryExecutor, ast::Statement};
-        
-        // Parse the query
-        let ast = CypherParser::parse(&query)
-            .map_err(|e| PyRuntimeError::new_err(format!("Parse error: {}", e)))?;
-        
-        // Extract the query from the statement
-        let Statement::Query(query_ast) = ast;
-        
-        // Create planner and generate execution plan
-        let planner = QueryPlanner::new();
-        let logical_plan = planner.logical_plan(&query_ast)
-            .map_err(|e| PyRuntimeError::new_err(format!("Planning error: {}", e)))?;
-        let physical_plan = planner.physical_plan(&logical_plan)
-            .map_err(|e| PyRuntimeError::new_err(format!("Physical planning error: {}", e)))?;
-        
-        // Execute the query
-        let storage_guard = self.storage.read()
-            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
-        
-        let executor = QueryExecutor::new(std::sync::Arc::new(storage_guard.clone()));
-        let result = executor.execute(&physical_plan)
-            .map_err(|e| PyRuntimeError::new_err(format!("Execution error: {}", e)))?;
-        
-        // Convert result to Python dictionary
-        let result_dict = pyo3::types::PyDict::new_bound(py);
-        result_dict.set_item("columns", result.columns)?;
-        result_dict.set_item("row_count", result.row_count)?;
-        result_dict.set_item("execution_time_ms", result.execution_time_ms)?;
-        
-        // Convert rows to Python list of dictionaries
-        let rows = pyo3::types::PyList::empty_bound(py);
-        for row in result.rows {
-            let row_dict = pyo3::types::PyDict::new_bound(py);
-            for (key, value) in row {
-                row_dict.set_item(key, property_value_to_py(py, &value)?)?;
-            }
-            rows.append(row_dict)?;
-        }
-        result_dict.set_item("rows", rows)?;
-        
-        Ok(result_dict.to_object(py))
+        self.execute(py, query, None)
+    }
+
+    /// Execute a Cypher query with parameters
+    ///
+    /// Args:
+    ///     query: Cypher query string, referring to parameters as $name
+    ///     params: Optional dictionary of parameter values (None, str, int,
+    ///         float, bool, or lists and dicts of these)
+    ///
+    /// Returns:
+    ///     Dictionary with:
+    ///         - columns: List of column names
+    ///         - rows: List of row dictionaries
+    ///         - row_count: Number of rows returned
+    ///         - execution_time_ms: Execution time in milliseconds
+    ///
+    /// Example:
+    ///     result = storage.execute("MATCH (n:Person) WHERE n.age > $min_age RETURN n", {"min_age": 25})
+    ///     for row in result['rows']:
+    ///         print(row['name'], row['age'])
+    #[pyo3(signature = (query, params=None))]
+    fn execute(&self, py: Python, query: String, params: Option<HashMap<String, PyObject>>) -> PyResult<PyObject> {
+        let result = self.run_query(py, &query, params)?;
+        query_result_to_py(py, result)
+    }
+
+    /// Execute a Cypher query, returning the result as a pyarrow Table
+    ///
+    /// Columns are typed (int64, double, bool or string; lists, maps and
+    /// mixed values as JSON strings), and a returned node contributes one
+    /// column per property plus `_node_id`. Requires pyarrow.
+    ///
+    /// Args:
+    ///     query: Cypher query string, referring to parameters as $name
+    ///     params: Optional dictionary of parameter values
+    ///
+    /// Returns:
+    ///     pyarrow.Table, convertible with .to_pandas()
+    ///
+    /// Example:
+    ///     df = storage.execute_df("MATCH (n:Person) RETURN n").to_pandas()
+    ///     print(df[['name', 'age']].describe())
+    #[pyo3(signature = (query, params=None))]
+    fn execute_df(&self, py: Python, query: String, params: Option<HashMap<String, PyObject>>) -> PyResult<PyObject> {
+        let result = self.run_query(py, &query, params)?;
+        query_result_to_arrow(py, &result)
     }
 
     /// Import nodes from a CSV file
@@ -683,6 +1256,206 @@ impl PyGraphStorage {
         Ok(dict.to_object(py))
     }
 
+    /// Import nodes from a pyarrow Table or RecordBatch, or a pandas DataFrame
+    ///
+    /// Columns are matched as for CSV files (`id`, `labels`); the other
+    /// columns become properties typed after their Arrow type.
+    ///
+    /// Args:
+    ///     data: pyarrow.Table, pyarrow.RecordBatch or pandas.DataFrame
+    ///
+    /// Returns:
+    ///     Dictionary with import statistics
+    ///
+    /// Example:
+    ///     stats = storage.import_nodes_arrow(df)
+    ///     print(f"Imported {stats['nodes_imported']} nodes")
+    fn import_nodes_arrow(&self, py: Python, data: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        use crate::import::ArrowImporter;
+        
+        let batches = arrow_batches(py, data)?;
+        let importer = ArrowImporter::new();
+        let storage_guard = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
+        
+        let stats = importer.import_nodes(&*storage_guard, &batches)
+            .map_err(|e| PyRuntimeError::new_err(format!("Import error: {}", e)))?;
+        
+        // Convert stats to Python dict
+        let dict = pyo3::types::PyDict::new_bound(py);
+        dict.set_item("nodes_imported", stats.nodes_imported)?;
+        dict.set_item("edges_imported", stats.edges_imported)?;
+        dict.set_item("duration_ms", stats.duration_ms)?;
+        dict.set_item("errors", stats.errors)?;
+        dict.set_item("node_id_map", stats.node_id_map)?;
+        
+        Ok(dict.to_object(py))
+    }
+
+    /// Import edges from a pyarrow Table or RecordBatch, or a pandas DataFrame
+    ///
+    /// Args:
+    ///     data: Table with `from`, `to` and `type` columns plus properties
+    ///     node_id_map: Dictionary mapping external IDs to internal IDs
+    ///
+    /// Returns:
+    ///     Dictionary with import statistics
+    ///
+    /// Example:
+    ///     node_stats = storage.import_nodes_arrow(nodes_df)
+    ///     edge_stats = storage.import_edges_arrow(edges_df, node_stats['node_id_map'])
+    fn import_edges_arrow(
+        &self,
+        py: Python,
+        data: &Bound<'_, PyAny>,
+        node_id_map: HashMap<String, String>,
+    ) -> PyResult<PyObject> {
+        use crate::import::ArrowImporter;
+        
+        let batches = arrow_batches(py, data)?;
+        let importer = ArrowImporter::new();
+        let storage_guard = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
+        
+        let stats = importer.import_edges(&*storage_guard, &batches, &node_id_map)
+            .map_err(|e| PyRuntimeError::new_err(format!("Import error: {}", e)))?;
+        
+        // Convert stats to Python dict
+        let dict = pyo3::types::PyDict::new_bound(py);
+        dict.set_item("nodes_imported", stats.nodes_imported)?;
+        dict.set_item("edges_imported", stats.edges_imported)?;
+        dict.set_item("duration_ms", stats.duration_ms)?;
+        dict.set_item("errors", stats.errors)?;
+        
+        Ok(dict.to_object(py))
+    }
+
+    /// Import a graph from CSV files
+    ///
+    /// Nodes are imported first; edges then refer to them by the IDs in the
+    /// nodes file.
+    ///
+    /// Args:
+    ///     nodes_path: Path to the nodes CSV file
+    ///     edges_path: Optional path to the edges CSV file
+    ///
+    /// Returns:
+    ///     Dictionary with import statistics (nodes_imported, edges_imported,
+    ///     duplicates, duration_ms, errors, node_id_map)
+    ///
+    /// Example:
+    ///     stats = storage.import_csv("nodes.csv", "edges.csv")
+    #[pyo3(signature = (nodes_path, edges_path=None))]
+    fn import_csv(&self, py: Python, nodes_path: String, edges_path: Option<String>) -> PyResult<PyObject> {
+        use crate::import::CsvImporter;
+
+        let importer = CsvImporter::new();
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
+        let mut stats = importer.import_nodes(&*storage, &nodes_path)
+            .map_err(|e| PyRuntimeError::new_err(format!("Import error: {}", e)))?;
+        if let Some(edges_path) = edges_path {
+            let edges = importer.import_edges(&*storage, &edges_path, &stats.node_id_map)
+                .map_err(|e| PyRuntimeError::new_err(format!("Import error: {}", e)))?;
+            stats.duration_ms += edges.duration_ms;
+            stats.merge(edges);
+        }
+        import_stats_to_py(py, stats)
+    }
+
+    /// Import a graph from JSON files
+    ///
+    /// Like import_csv, for JSON arrays or JSON Lines files.
+    ///
+    /// Args:
+    ///     nodes_path: Path to the nodes JSON file
+    ///     edges_path: Optional path to the edges JSON file
+    ///
+    /// Returns:
+    ///     Dictionary with import statistics
+    #[pyo3(signature = (nodes_path, edges_path=None))]
+    fn import_json(&self, py: Python, nodes_path: String, edges_path: Option<String>) -> PyResult<PyObject> {
+        use crate::import::JsonImporter;
+
+        let importer = JsonImporter::new();
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
+        let mut stats = importer.import_nodes(&*storage, &nodes_path)
+            .map_err(|e| PyRuntimeError::new_err(format!("Import error: {}", e)))?;
+        if let Some(edges_path) = edges_path {
+            let edges = importer.import_edges(&*storage, &edges_path, &stats.node_id_map)
+                .map_err(|e| PyRuntimeError::new_err(format!("Import error: {}", e)))?;
+            stats.duration_ms += edges.duration_ms;
+            stats.merge(edges);
+        }
+        import_stats_to_py(py, stats)
+    }
+
+    /// Export the graph as CSV files readable by import_csv
+    ///
+    /// Args:
+    ///     nodes_path: Path of the nodes CSV file to write
+    ///     edges_path: Path of the edges CSV file to write
+    ///
+    /// Returns:
+    ///     Dictionary with nodes_exported, edges_exported and duration_ms
+    fn export_csv(&self, py: Python, nodes_path: String, edges_path: String) -> PyResult<PyObject> {
+        let exporter = crate::export::CsvExporter::new();
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
+        let export_error = |e: crate::error::DeepGraphError| PyRuntimeError::new_err(format!("Export error: {}", e));
+        let nodes = exporter.export_nodes(&*storage, &nodes_path).map_err(export_error)?;
+        let edges = exporter.export_edges(&*storage, &edges_path).map_err(export_error)?;
+        export_stats_to_py(py, &[nodes, edges])
+    }
+
+    /// Export the graph as JSON files readable by import_json
+    ///
+    /// Args:
+    ///     nodes_path: Path of the nodes JSON file to write
+    ///     edges_path: Path of the edges JSON file to write
+    ///
+    /// Returns:
+    ///     Dictionary with nodes_exported, edges_exported and duration_ms
+    fn export_json(&self, py: Python, nodes_path: String, edges_path: String) -> PyResult<PyObject> {
+        let exporter = crate::export::JsonExporter::new();
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
+        let export_error = |e: crate::error::DeepGraphError| PyRuntimeError::new_err(format!("Export error: {}", e));
+        let nodes = exporter.export_nodes(&*storage, &nodes_path).map_err(export_error)?;
+        let edges = exporter.export_edges(&*storage, &edges_path).map_err(export_error)?;
+        export_stats_to_py(py, &[nodes, edges])
+    }
+
+    /// Export the graph to a single file
+    ///
+    /// Args:
+    ///     path: Path of the file to write
+    ///     format: "cypher" (a CREATE script), "dot" (Graphviz) or "gexf"
+    ///         (Gephi)
+    ///
+    /// Returns:
+    ///     Dictionary with nodes_exported, edges_exported and duration_ms
+    ///
+    /// Example:
+    ///     storage.export("graph.gexf", "gexf")
+    fn export(&self, py: Python, path: String, format: String) -> PyResult<PyObject> {
+        use crate::export::{CypherExporter, DotExporter, GexfExporter};
+
+        let storage = self.storage.read()
+            .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
+        let stats = match format.as_str() {
+            "cypher" => CypherExporter::new().export(&*storage, &path),
+            "dot" => DotExporter::new().export(&*storage, &path),
+            "gexf" => GexfExporter::new().export(&*storage, &path),
+            _ => return Err(PyValueError::new_err(format!(
+                "Unknown export format '{}': expected 'cypher', 'dot' or 'gexf'", format
+            ))),
+        }
+        .map_err(|e| PyRuntimeError::new_err(format!("Export error: {}", e)))?;
+        export_stats_to_py(py, &[stats])
+    }
+
     /// Get all edges in the graph
     /// 
     /// Returns:
@@ -692,25 +1465,7 @@ impl PyGraphStorage {
             let storage = self.storage.read()
                 .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
             
-            let edges = storage.get_all_edges();
-            let mut result = Vec::new();
-            
-            for edge in edges {
-                let dict = pyo3::types::PyDict::new_bound(py);
-                dict.set_item("id", edge.id().to_string())?;
-                dict.set_item("from", edge.from().to_string())?;
-                dict.set_item("to", edge.to().to_string())?;
-                dict.set_item("label", edge.relationship_type())?;
-                
-                let props = pyo3::types::PyDict::new_bound(py);
-                for (key, value) in edge.properties() {
-                    props.set_item(key, property_value_to_py(py, value)?)?;
-                }
-                dict.set_item("properties", props)?;
-                result.push(dict.to_object(py));
-            }
-            
-            Ok(result)
+            storage.get_all_edges().iter().map(|edge| edge_to_py(py, edge)).collect()
         })
     }
 
@@ -718,8 +1473,74 @@ impl PyGraphStorage {
     fn clear(&self) -> PyResult<()> {
         let storage = self.storage.write()
             .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
-        storage.clear();
-        Ok(())
+        storage.clear()
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to clear: {}", e)))
+    }
+}
+
+/// Lazy iterator over nodes, returned by `GraphStorage.nodes()` and
+/// `GraphStorage.neighbors()`
+///
+/// Holds only the IDs of the nodes to visit; each node is read from storage
+/// when the iteration reaches it, and nodes deleted in the meantime are
+/// skipped.
+#[pyclass]
+pub struct PyNodeIterator {
+    storage: Backend,
+    ids: std::vec::IntoIter<NodeId>,
+}
+
+#[pymethods]
+impl PyNodeIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<PyObject>> {
+        for id in self.ids.by_ref() {
+            match self.storage.get_node(id) {
+                Ok(node) => return node_to_py(py, &node).map(Some),
+                Err(e) if e.is_not_found() => continue,
+                Err(e) => return Err(PyRuntimeError::new_err(e.to_string())),
+            }
+        }
+        Ok(None)
+    }
+
+    fn __length_hint__(&self) -> usize {
+        self.ids.len()
+    }
+}
+
+/// Lazy iterator over edges, returned by `GraphStorage.edges()`
+///
+/// Like the node iterator, it holds only edge IDs and skips edges deleted
+/// during iteration.
+#[pyclass]
+pub struct PyEdgeIterator {
+    storage: Backend,
+    ids: std::vec::IntoIter<EdgeId>,
+}
+
+#[pymethods]
+impl PyEdgeIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<PyObject>> {
+        for id in self.ids.by_ref() {
+            match self.storage.get_edge(id) {
+                Ok(edge) => return edge_to_py(py, &edge).map(Some),
+                Err(e) if e.is_not_found() => continue,
+                Err(e) => return Err(PyRuntimeError::new_err(e.to_string())),
+            }
+        }
+        Ok(None)
+    }
+
+    fn __length_hint__(&self) -> usize {
+        self.ids.len()
     }
 }
 
@@ -809,6 +1630,10 @@ impl PyIndexManager {
             index_type: IndexType::Hash,
             is_label_index: true,
             property_key: None,
+            unique: false,
+            on_edges: false,
+            label: None,
+            limits: Default::default(),
         };
         
         manager.create_index(config)
@@ -829,6 +1654,10 @@ impl PyIndexManager {
             index_type: IndexType::BTree,
             is_label_index: false,
             property_key: Some(property_key),
+            unique: false,
+            on_edges: false,
+            label: None,
+            limits: Default::default(),
         };
         
         manager.create_index(config)
@@ -995,7 +1824,7 @@ impl PyQueryPlanner {
 #[pyclass]
 pub struct PyQueryExecutor {
     #[allow(dead_code)]
-    storage: Arc<RwLock<GraphStorage>>,
+    storage: Arc<RwLock<Backend>>,
 }
 
 #[pymethods]
@@ -1142,7 +1971,7 @@ fn py_bfs(py: Python, storage: &PyGraphStorage, start_node: String, max_depth: O
     let storage_lock = storage.storage.read()
         .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
     
-    let result = bfs(&storage_lock, node_id, max_depth)
+    let result = bfs(&*storage_lock, node_id, max_depth)
         .map_err(|e| PyRuntimeError::new_err(format!("BFS failed: {}", e)))?;
     
     let dict = pyo3::types::PyDict::new_bound(py);
@@ -1173,7 +2002,7 @@ fn py_dfs(py: Python, storage: &PyGraphStorage, start_node: String) -> PyResult<
     let storage_lock = storage.storage.read()
         .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
     
-    let result = dfs(&storage_lock, node_id)
+    let result = dfs(&*storage_lock, node_id)
         .map_err(|e| PyRuntimeError::new_err(format!("DFS failed: {}", e)))?;
     
     let dict = pyo3::types::PyDict::new_bound(py);
@@ -1204,7 +2033,7 @@ fn py_dijkstra(py: Python, storage: &PyGraphStorage, source: String, weight_prop
         .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
     
     let weight_prop_ref = weight_property.as_ref().map(|s| s.as_str());
-    let result = dijkstra(&storage_lock, source_id, weight_prop_ref)
+    let result = dijkstra(&*storage_lock, source_id, weight_prop_ref)
         .map_err(|e| PyRuntimeError::new_err(format!("Dijkstra failed: {}", e)))?;
     
     let dict = pyo3::types::PyDict::new_bound(py);
@@ -1232,7 +2061,7 @@ fn py_connected_components(py: Python, storage: &PyGraphStorage) -> PyResult<PyO
     let storage_lock = storage.storage.read()
         .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
     
-    let result = connected_components(&storage_lock)
+    let result = connected_components(&*storage_lock)
         .map_err(|e| PyRuntimeError::new_err(format!("Connected components failed: {}", e)))?;
     
     let dict = pyo3::types::PyDict::new_bound(py);
@@ -1259,7 +2088,7 @@ fn py_pagerank(py: Python, storage: &PyGraphStorage, damping_factor: f64, max_it
     let storage_lock = storage.storage.read()
         .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
     
-    let result = pagerank(&storage_lock, damping_factor, max_iterations, tolerance)
+    let result = pagerank(&*storage_lock, damping_factor, max_iterations, tolerance)
         .map_err(|e| PyRuntimeError::new_err(format!("PageRank failed: {}", e)))?;
     
     let dict = pyo3::types::PyDict::new_bound(py);
@@ -1281,7 +2110,7 @@ fn py_triangle_count(py: Python, storage: &PyGraphStorage) -> PyResult<PyObject>
     let storage_lock = storage.storage.read()
         .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
     
-    let result = triangle_count(&storage_lock)
+    let result = triangle_count(&*storage_lock)
         .map_err(|e| PyRuntimeError::new_err(format!("Triangle counting failed: {}", e)))?;
     
     let dict = pyo3::types::PyDict::new_bound(py);
@@ -1309,7 +2138,7 @@ fn py_louvain(py: Python, storage: &PyGraphStorage, max_iterations: usize, min_i
     let storage_lock = storage.storage.read()
         .map_err(|e| PyRuntimeError::new_err(format!("Lock error: {}", e)))?;
     
-    let result = louvain(&storage_lock, max_iterations, min_improvement)
+    let result = louvain(&*storage_lock, max_iterations, min_improvement)
         .map_err(|e| PyRuntimeError::new_err(format!("Louvain failed: {}", e)))?;
     
     let dict = pyo3::types::PyDict::new_bound(py);
@@ -1348,7 +2177,7 @@ fn py_node2vec(
         seed,
     };
     
-    let result = node2vec(&storage_lock, config)
+    let result = node2vec(&*storage_lock, config)
         .map_err(|e| PyRuntimeError::new_err(format!("Node2Vec failed: {}", e)))?;
     
     let dict = pyo3::types::PyDict::new_bound(py);
@@ -1469,12 +2298,13 @@ impl PyDiskStorage {
     /// Returns:
     ///     Query result dictionary
     fn execute_cypher(&self, py: Python, query: String) -> PyResult<PyObject> {
-        use crate::query::{CypherParser, QueryPlanner, QueryExecutor, ast::Statement};
+        use crate::query::{CypherParser, QueryPlanner, QueryExecutor, ast::Statement, rewrite::prepare};
         
         // Parse query
         let ast = CypherParser::parse(&query)
             .map_err(|e| PyRuntimeError::new_err(format!("Parse error: {}", e)))?;
-        let Statement::Query(query_ast) = ast;
+        let Statement::Query(query_ast) = prepare(&ast, &HashMap::new())
+            .map_err(|e| PyRuntimeError::new_err(format!("Preparation error: {}", e)))?;
         
         // Plan query
         let planner = QueryPlanner::new();
@@ -1575,19 +2405,126 @@ impl PyDiskStorage {
             dict.set_item("node_count", stats.node_count)?;
             dict.set_item("edge_count", stats.edge_count)?;
             dict.set_item("size_on_disk_bytes", stats.size_on_disk_bytes)?;
+            dict.set_item("index_repairs", stats.index_repairs)?;
             
             Ok(dict.to_object(py))
         })
     }
 }
 
+/// Python wrapper for a catalog of named in-memory graphs
+#[pyclass]
+pub struct PyCatalog {
+    catalog: Arc<crate::catalog::Catalog<GraphStorage>>,
+}
+
+impl PyCatalog {
+    fn handle(storage: Arc<GraphStorage>) -> PyGraphStorage {
+        // MemoryStorage clones share their maps, so the handle sees the same graph
+        PyGraphStorage::from_backend(Backend::memory((*storage).clone()))
+    }
+}
+
+#[pymethods]
+impl PyCatalog {
+    /// Create an empty catalog
+    #[new]
+    fn new() -> Self {
+        PyCatalog {
+            catalog: Arc::new(crate::catalog::Catalog::new()),
+        }
+    }
+
+    /// Create an empty graph and return a handle to it
+    ///
+    /// Example:
+    ///     tenant = catalog.create_graph("tenant_a")
+    ///     tenant.add_node(["Person"], {"name": "Ann"})
+    fn create_graph(&self, name: String) -> PyResult<PyGraphStorage> {
+        let storage = self.catalog.create_graph(&name, Arc::new(GraphStorage::new()))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self::handle(storage))
+    }
+
+    /// Handle to an existing graph
+    fn graph(&self, name: String) -> PyResult<PyGraphStorage> {
+        let storage = self.catalog.graph(&name)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self::handle(storage))
+    }
+
+    /// Remove a graph from the catalog
+    fn drop_graph(&self, name: String) -> PyResult<()> {
+        self.catalog.drop_graph(&name)
+            .map(|_| ())
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Names of all graphs
+    fn list_graphs(&self) -> Vec<String> {
+        self.catalog.graph_names()
+    }
+
+    /// Name of the graph new sessions start in, if any
+    fn default_graph(&self) -> Option<String> {
+        self.catalog.default_graph()
+    }
+
+    /// Open a session, with its own current graph, to run queries in
+    ///
+    /// Example:
+    ///     session = catalog.session()
+    ///     session.execute_cypher("USE tenant_a")
+    fn session(&self) -> PyCatalogSession {
+        PyCatalogSession {
+            session: self.catalog.session(),
+        }
+    }
+}
+
+/// One connection to a catalog, returned by `Catalog.session()`
+///
+/// `USE graph` only changes the current graph of this session.
+#[pyclass]
+pub struct PyCatalogSession {
+    session: crate::catalog::Session<GraphStorage>,
+}
+
+#[pymethods]
+impl PyCatalogSession {
+    /// Select the graph that queries without USE run against
+    fn use_graph(&mut self, name: String) -> PyResult<()> {
+        self.session.use_graph(&name)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Name of the current graph, if any
+    fn current_graph(&self) -> Option<String> {
+        self.session.current_graph().map(str::to_string)
+    }
+
+    /// Execute a Cypher query, honoring `USE graph` and `FROM GRAPH`
+    ///
+    /// Example:
+    ///     result = session.execute_cypher("USE tenant_a MATCH (n:Person) RETURN n")
+    fn execute_cypher(&mut self, py: Python, query: String) -> PyResult<PyObject> {
+        let result = self.session.execute(&query)
+            .map_err(|e| PyRuntimeError::new_err(format!("Execution error: {}", e)))?;
+        query_result_to_py(py, result)
+    }
+}
+
 /// DeepGraph Python module
 #[pymodule]
 fn deepgraph(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Core classes
     m.add_class::<PyGraphStorage>()?;
     m.add_class::<PyDiskStorage>()?;
+    m.add_class::<PyCatalog>()?;
+    m.add_class::<PyCatalogSession>()?;
     m.add_class::<PyTransactionManager>()?;
+    m.add_class::<PyNodeIterator>()?;
+    m.add_class::<PyEdgeIterator>()?;
     
     // Index management
     m.add_class::<PyIndexManager>()?;
@@ -17,6 +17,9 @@ pub enum Statement {
 pub enum Query {
     Read(ReadQuery),
     Write(WriteQuery),
+    Call(CallClause),
+    Federated(FederatedQuery),
+    Use(UseQuery),
 }
 
 /// Read query (MATCH)
@@ -27,6 +30,33 @@ pub struct ReadQuery {
     pub return_clause: ReturnClause,
 }
 
+/// Query whose MATCH clauses each run against a named graph
+/// (FROM GRAPH a MATCH ... FROM GRAPH b MATCH ... WHERE ... RETURN ...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FederatedQuery {
+    pub parts: Vec<GraphMatch>,
+    pub where_clause: Option<WhereClause>,
+    pub return_clause: ReturnClause,
+}
+
+/// `USE graph [query]`
+///
+/// Selects a named graph for the inner query or, without one, for the rest
+/// of the session. The planner plans the inner query; resolving the graph
+/// is up to the caller (see `catalog::Session`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UseQuery {
+    pub graph: String,
+    pub query: Option<Box<Query>>,
+}
+
+/// MATCH clause bound to a named graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphMatch {
+    pub graph: String,
+    pub match_clause: MatchClause,
+}
+
 /// Write query (CREATE, DELETE, SET, MERGE)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WriteQuery {
@@ -40,6 +70,24 @@ pub enum WriteQuery {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MatchClause {
     pub patterns: Vec<Pattern>,
+    #[serde(default)]
+    pub hints: Vec<IndexHint>,
+}
+
+/// Planner hint following the MATCH patterns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum IndexHint {
+    /// `USING INDEX n:Label(property)`: find `n` through the index on `property`
+    Index {
+        variable: String,
+        label: String,
+        property: String,
+    },
+    /// `USING SCAN n:Label`: scan the label instead of using an index for `n`
+    Scan {
+        variable: String,
+        label: String,
+    },
 }
 
 /// Pattern for graph matching
@@ -109,6 +157,13 @@ pub struct OrderItem {
     pub ascending: bool,
 }
 
+/// CALL clause (built-in procedure invocation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallClause {
+    pub procedure: String,
+    pub args: Vec<Expression>,
+}
+
 /// CREATE clause
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateClause {
@@ -3,9 +3,11 @@
 //! Executes optimized query plans against the storage engine
 
 use crate::error::Result;
-use crate::graph::PropertyValue;
-use crate::query::planner::PhysicalPlan;
-use crate::storage::StorageBackend;
+use crate::graph::{Edge, EdgeId, Node, PropertyValue};
+use crate::index::IndexManager;
+use crate::query::planner::{EdgeLookup, PhysicalPlan};
+use crate::query::procedures;
+use crate::storage::{ExternalIdRegistry, StorageBackend};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -49,12 +51,41 @@ impl QueryResult {
 pub struct QueryExecutor<S: StorageBackend> {
     /// Storage backend
     storage: Arc<S>,
+    /// Values bound to `$name` parameters
+    parameters: HashMap<String, PropertyValue>,
+    /// Registry consulted by `external(...)`
+    id_registry: Option<Arc<ExternalIdRegistry>>,
+    /// Indexes available to procedures such as `vector.search`
+    indexes: Option<Arc<IndexManager>>,
 }
 
 impl<S: StorageBackend> QueryExecutor<S> {
     /// Create a new executor
     pub fn new(storage: Arc<S>) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            parameters: HashMap::new(),
+            id_registry: None,
+            indexes: None,
+        }
+    }
+    
+    /// Bind values for `$name` parameters referenced by the plan
+    pub fn with_parameters(mut self, parameters: HashMap<String, PropertyValue>) -> Self {
+        self.parameters = parameters;
+        self
+    }
+    
+    /// Resolve `external('...')` calls through an external ID registry
+    pub fn with_id_registry(mut self, registry: Arc<ExternalIdRegistry>) -> Self {
+        self.id_registry = Some(registry);
+        self
+    }
+    
+    /// Make an index manager available to procedures
+    pub fn with_index_manager(mut self, indexes: Arc<IndexManager>) -> Self {
+        self.indexes = Some(indexes);
+        self
     }
     
     /// Execute a physical plan
@@ -63,12 +94,27 @@ impl<S: StorageBackend> QueryExecutor<S> {
         
         let mut result = match plan {
             PhysicalPlan::Scan { label } => self.execute_scan(label.as_deref())?,
+            PhysicalPlan::HashIndexScan { index_name, key, label } => {
+                self.execute_index_scan(index_name, key, label.as_deref())?
+            }
+            PhysicalPlan::EdgeScan { rel_type } => self.execute_edge_scan(rel_type.as_deref()),
+            PhysicalPlan::EdgeIndexScan { lookup, rel_type } => {
+                self.execute_edge_index_scan(lookup, rel_type.as_deref())?
+            }
             PhysicalPlan::Filter { source, predicate } => {
                 self.execute_filter(source, predicate)?
             }
             PhysicalPlan::Project { source, columns } => {
                 self.execute_project(source, columns)?
             }
+            PhysicalPlan::ProcedureCall { procedure, args } => {
+                let empty = HashMap::new();
+                let values = args
+                    .iter()
+                    .map(|arg| self.evaluate_value(arg, &empty))
+                    .collect::<Result<Vec<_>>>()?;
+                procedures::call_with_indexes(self.storage.as_ref(), self.indexes.as_deref(), procedure, &values)?
+            }
             _ => QueryResult::empty(),
         };
         
@@ -78,13 +124,95 @@ impl<S: StorageBackend> QueryExecutor<S> {
     
     /// Execute a scan operation
     fn execute_scan(&self, label: Option<&str>) -> Result<QueryResult> {
-        let nodes = if let Some(label) = label {
-            self.storage.get_nodes_by_label(label)
+        let nodes: Box<dyn Iterator<Item = Node> + '_> = if let Some(label) = label {
+            Box::new(self.storage.get_nodes_by_label(label).into_iter())
         } else {
-            // Full scan - now implemented!
-            self.storage.get_all_nodes()
+            // Full scan, streamed so nodes are not collected twice
+            self.storage.iter_nodes()
+        };
+        Ok(Self::node_rows(nodes))
+    }
+    
+    /// Execute an equality lookup through an index
+    fn execute_index_scan(&self, index_name: &str, key: &[u8], label: Option<&str>) -> Result<QueryResult> {
+        let indexes = self.indexes.as_ref().ok_or_else(|| {
+            crate::error::DeepGraphError::InvalidOperation(format!("Index scan on {} needs an index manager", index_name))
+        })?;
+        let mut nodes = Vec::new();
+        for node_id in indexes.lookup_index(index_name, key)? {
+            match self.storage.get_node(node_id) {
+                Ok(node) if label.map_or(true, |label| node.has_label(label)) => nodes.push(node),
+                Ok(_) => {}
+                // Stale entry; the index manager repairs these on resolve
+                Err(e) if e.is_not_found() => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Self::node_rows(nodes.into_iter()))
+    }
+    
+    /// Execute a scan over edges
+    fn execute_edge_scan(&self, rel_type: Option<&str>) -> QueryResult {
+        let edges: Box<dyn Iterator<Item = Edge> + '_> = match rel_type {
+            Some(rel_type) => Box::new(self.storage.get_edges_by_type(rel_type).into_iter()),
+            None => self.storage.iter_edges(),
+        };
+        Self::edge_rows(edges)
+    }
+    
+    /// Execute a lookup through an edge index
+    fn execute_edge_index_scan(&self, lookup: &EdgeLookup, rel_type: Option<&str>) -> Result<QueryResult> {
+        let indexes = self.indexes.as_ref().ok_or_else(|| {
+            crate::error::DeepGraphError::InvalidOperation("Edge index scan needs an index manager".to_string())
+        })?;
+        let ids: Vec<EdgeId> = match lookup {
+            EdgeLookup::Type(rel_type) => indexes.lookup_edge_type(rel_type)?,
+            EdgeLookup::Property { property, value } => indexes.lookup_edge_property(property, value)?,
+            EdgeLookup::Range { property, start, end, end_inclusive } => {
+                // The index range excludes its end
+                let mut ids = indexes.range_edge_property(property, start, end)?;
+                if *end_inclusive {
+                    ids.extend(indexes.lookup_edge_property(property, end)?);
+                }
+                ids
+            }
         };
+        let mut edges = Vec::new();
+        for edge_id in ids {
+            match self.storage.get_edge(edge_id) {
+                Ok(edge) if rel_type.map_or(true, |rel_type| edge.relationship_type() == rel_type) => edges.push(edge),
+                Ok(_) => {}
+                Err(e) if e.is_not_found() => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Self::edge_rows(edges.into_iter()))
+    }
+    
+    /// One row per edge: its ID and endpoints plus its properties
+    fn edge_rows(edges: impl Iterator<Item = Edge>) -> QueryResult {
+        let mut columns = vec!["_edge_id".to_string(), "_source_id".to_string(), "_target_id".to_string()];
+        let rows: Vec<HashMap<String, PropertyValue>> = edges
+            .map(|edge| {
+                let mut row = HashMap::new();
+                row.insert("_edge_id".to_string(), PropertyValue::String(edge.id().to_string()));
+                row.insert("_source_id".to_string(), PropertyValue::String(edge.from().to_string()));
+                row.insert("_target_id".to_string(), PropertyValue::String(edge.to().to_string()));
+                for (key, value) in edge.properties().iter() {
+                    row.insert(key.clone(), value.clone());
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+                row
+            })
+            .collect();
         
+        QueryResult::with_data(columns, rows)
+    }
+    
+    /// One row per node: its ID plus its properties
+    fn node_rows(nodes: impl Iterator<Item = Node>) -> QueryResult {
         // Convert nodes to result rows with properties
         let mut columns = vec!["_node_id".to_string()];
         let rows: Vec<HashMap<String, PropertyValue>> = nodes
@@ -109,7 +237,7 @@ impl<S: StorageBackend> QueryExecutor<S> {
             })
             .collect();
         
-        Ok(QueryResult::with_data(columns, rows))
+        QueryResult::with_data(columns, rows)
     }
     
     /// Execute a filter operation
@@ -132,7 +260,7 @@ impl<S: StorageBackend> QueryExecutor<S> {
     }
     
     /// Evaluate a predicate expression on a row
-    fn evaluate_predicate(
+    pub(crate) fn evaluate_predicate(
         &self,
         expr: &crate::query::ast::Expression,
         row: &HashMap<String, PropertyValue>,
@@ -201,7 +329,7 @@ impl<S: StorageBackend> QueryExecutor<S> {
     }
     
     /// Evaluate an expression to a value
-    fn evaluate_value(
+    pub(crate) fn evaluate_value(
         &self,
         expr: &crate::query::ast::Expression,
         row: &HashMap<String, PropertyValue>,
@@ -211,6 +339,14 @@ impl<S: StorageBackend> QueryExecutor<S> {
         match expr {
             Expression::Literal(val) => Ok(val.clone()),
             
+            Expression::Parameter(name) => {
+                self.parameters.get(name)
+                    .cloned()
+                    .ok_or_else(|| crate::error::DeepGraphError::InvalidOperation(
+                        format!("Missing parameter: ${}", name)
+                    ))
+            }
+            
             Expression::Variable(name) => {
                 // Look up variable in row
                 row.get(name)
@@ -223,8 +359,10 @@ impl<S: StorageBackend> QueryExecutor<S> {
             Expression::Property(base, prop) => {
                 // For property access like n.age, evaluate base then get property
                 if let Expression::Variable(var_name) = base.as_ref() {
-                    // Look up property directly in row (we flattened it in scan)
-                    row.get(prop)
+                    // Rows joining several variables qualify keys as `var.prop`;
+                    // single-variable scans flatten properties into the row
+                    row.get(&format!("{}.{}", var_name, prop))
+                        .or_else(|| row.get(prop))
                         .cloned()
                         .ok_or_else(|| crate::error::DeepGraphError::InvalidOperation(
                             format!("Property not found: {}.{}", var_name, prop)
@@ -269,12 +407,57 @@ impl<S: StorageBackend> QueryExecutor<S> {
                 }
             }
             
+            Expression::FunctionCall { name, args, .. } => self.call_function(name, args, row),
+            
             _ => Err(crate::error::DeepGraphError::InvalidOperation(
                 format!("Expression evaluation not yet implemented: {:?}", expr)
             )),
         }
     }
     
+    /// Evaluate a scalar function call
+    ///
+    /// - `id(n)` - internal ID of the node bound to the row
+    /// - `external(key)` - internal ID registered for an external key, or
+    ///   null when the key is unknown
+    fn call_function(
+        &self,
+        name: &str,
+        args: &[crate::query::ast::Expression],
+        row: &HashMap<String, PropertyValue>,
+    ) -> Result<PropertyValue> {
+        match (name.to_ascii_lowercase().as_str(), args) {
+            ("id", [arg]) => {
+                let qualified = match arg {
+                    crate::query::ast::Expression::Variable(var) => row.get(&format!("{}._node_id", var)),
+                    _ => None,
+                };
+                qualified.or_else(|| row.get("_node_id"))
+                    .cloned()
+                    .ok_or_else(|| crate::error::DeepGraphError::InvalidOperation(
+                        "id() requires a node".to_string()
+                    ))
+            }
+            ("external", [arg]) => {
+                let registry = self.id_registry.as_ref()
+                    .ok_or_else(|| crate::error::DeepGraphError::InvalidOperation(
+                        "external() requires an external ID registry".to_string()
+                    ))?;
+                match self.evaluate_value(arg, row)? {
+                    PropertyValue::String(key) => Ok(registry.get(&key)?
+                        .map(|id| PropertyValue::String(id.to_string()))
+                        .unwrap_or(PropertyValue::Null)),
+                    other => Err(crate::error::DeepGraphError::InvalidOperation(
+                        format!("external() expects a string, got {:?}", other)
+                    )),
+                }
+            }
+            _ => Err(crate::error::DeepGraphError::InvalidOperation(
+                format!("Unknown function {}/{}", name, args.len())
+            )),
+        }
+    }
+    
     /// Compare two property values
     fn compare_values(&self, left: &PropertyValue, right: &PropertyValue) -> Result<i32> {
         match (left, right) {
@@ -464,5 +647,185 @@ mod tests {
         let result = executor.execute(&plan).unwrap();
         assert_eq!(result.row_count, 1);
     }
-}
 
+    #[test]
+    fn test_filter_with_parameter() {
+        use crate::query::ast::Expression;
+        
+        let storage = Arc::new(MemoryStorage::new());
+        for age in [25, 35, 45] {
+            let mut node = crate::graph::Node::new(vec!["Person".to_string()]);
+            node.set_property("age".to_string(), PropertyValue::Integer(age));
+            storage.add_node(node).unwrap();
+        }
+        
+        let plan = PhysicalPlan::Filter {
+            source: Box::new(PhysicalPlan::Scan { label: Some("Person".to_string()) }),
+            predicate: Expression::Gt(
+                Box::new(Expression::Property(Box::new(Expression::variable("n")), "age".to_string())),
+                Box::new(Expression::Parameter("min_age".to_string())),
+            ),
+        };
+        
+        // Plans reaching the executor unprepared skip rows whose parameter is unbound
+        let unbound = QueryExecutor::new(storage.clone());
+        assert_eq!(unbound.execute(&plan).unwrap().row_count, 0);
+        
+        let mut params = HashMap::new();
+        params.insert("min_age".to_string(), PropertyValue::Integer(30));
+        let executor = QueryExecutor::new(storage).with_parameters(params);
+        assert_eq!(executor.execute(&plan).unwrap().row_count, 2);
+    }
+    
+    #[test]
+    fn test_filter_by_external_id() {
+        use crate::query::ast::Expression;
+        
+        let storage = Arc::new(MemoryStorage::new());
+        let registry = Arc::new(ExternalIdRegistry::temporary().unwrap());
+        for key in ["user:41", "user:42"] {
+            let node_id = storage.add_node(crate::graph::Node::new(vec!["User".to_string()])).unwrap();
+            registry.insert(key, node_id).unwrap();
+        }
+        
+        // WHERE id(n) = external('user:42')
+        let plan = PhysicalPlan::Filter {
+            source: Box::new(PhysicalPlan::Scan { label: None }),
+            predicate: Expression::Eq(
+                Box::new(Expression::FunctionCall {
+                    name: "id".to_string(),
+                    args: vec![Expression::variable("n")],
+                    distinct: false,
+                }),
+                Box::new(Expression::FunctionCall {
+                    name: "external".to_string(),
+                    args: vec![Expression::literal(PropertyValue::String("user:42".to_string()))],
+                    distinct: false,
+                }),
+            ),
+        };
+        
+        let executor = QueryExecutor::new(storage).with_id_registry(registry.clone());
+        let result = executor.execute(&plan).unwrap();
+        assert_eq!(result.row_count, 1);
+        assert_eq!(
+            result.rows[0]["_node_id"],
+            PropertyValue::String(registry.get("user:42").unwrap().unwrap().to_string())
+        );
+    }
+
+    #[test]
+    fn test_index_hints() {
+        use crate::index::{IndexConfig, IndexType};
+        use crate::query::ast::Statement;
+        use crate::query::planner::PlannerStats;
+        use crate::query::{CypherParser, QueryPlanner};
+
+        let storage = Arc::new(MemoryStorage::new());
+        let indexes = Arc::new(IndexManager::new());
+        indexes.create_index(IndexConfig::property_index(
+            "person_name".to_string(),
+            IndexType::Hash,
+            "name".to_string(),
+        )).unwrap();
+        for name in ["Alice", "Bob"] {
+            let mut node = Node::new(vec!["Person".to_string()]);
+            node.set_property("name".to_string(), PropertyValue::String(name.to_string()));
+            indexes.add_node(storage.as_ref(), node).unwrap();
+        }
+
+        let planner = QueryPlanner::with_stats(PlannerStats::for_backend(storage.as_ref()).with_indexes(&indexes));
+        let plan = |query: &str| {
+            let Statement::Query(query) = CypherParser::parse(query)?;
+            planner.physical_plan(&planner.logical_plan(&query)?)
+        };
+        let executor = QueryExecutor::new(storage.clone()).with_index_manager(indexes.clone());
+
+        let forced = plan("MATCH (n:Person) USING INDEX n:Person(name) WHERE n.name = 'Alice' RETURN n").unwrap();
+        let PhysicalPlan::Project { source, .. } = &forced else { panic!("expected projection") };
+        let PhysicalPlan::Filter { source, .. } = source.as_ref() else { panic!("expected filter") };
+        assert!(matches!(source.as_ref(), PhysicalPlan::HashIndexScan { .. }));
+        assert_eq!(executor.execute(&forced).unwrap().row_count, 1);
+        assert_eq!(indexes.usage("person_name").lookups, 1);
+
+        let scanned = plan("MATCH (n:Person) USING SCAN n:Person WHERE n.name = 'Alice' RETURN n").unwrap();
+        assert_eq!(executor.execute(&scanned).unwrap().row_count, 1);
+        assert_eq!(indexes.usage("person_name").lookups, 1);
+
+        assert!(plan("MATCH (n:Person) USING INDEX n:Person(age) WHERE n.age = 3 RETURN n").is_err());
+        assert!(plan("MATCH (n:Person) USING INDEX n:Person(name) RETURN n").is_err());
+    }
+
+    #[test]
+    fn test_edge_index_plans() {
+        use crate::graph::Edge;
+        use crate::index::{IndexConfig, IndexType};
+        use crate::query::ast::{Expression, Query, Statement};
+        use crate::query::planner::PlannerStats;
+        use crate::query::{CypherParser, QueryPlanner};
+
+        let storage = Arc::new(MemoryStorage::new());
+        let indexes = Arc::new(IndexManager::new());
+        indexes.create_index(IndexConfig::relationship_type_index("LIKES".to_string(), IndexType::Hash)).unwrap();
+        indexes.create_index(IndexConfig::edge_property_index(
+            "knows_since".to_string(),
+            IndexType::BTree,
+            "since".to_string(),
+        )).unwrap();
+        let a = storage.add_node(Node::new(vec![])).unwrap();
+        let b = storage.add_node(Node::new(vec![])).unwrap();
+        for (rel_type, since) in [("KNOWS", "2019"), ("KNOWS", "2020"), ("KNOWS", "2021"), ("KNOWS", "2022"), ("LIKES", "2020")] {
+            let mut edge = Edge::new(a, b, rel_type.to_string());
+            edge.set_property("since".to_string(), PropertyValue::String(since.to_string()));
+            indexes.add_edge(storage.as_ref(), edge).unwrap();
+        }
+
+        let planner = QueryPlanner::with_stats(PlannerStats::for_backend(storage.as_ref()).with_indexes(&indexes));
+        let parse = |query: &str| {
+            let Statement::Query(query) = CypherParser::parse(query).unwrap();
+            query
+        };
+        let plan_query = |query: &Query| planner.physical_plan(&planner.logical_plan(query).unwrap()).unwrap();
+        let plan = |query: &str| plan_query(&parse(query));
+        let source = |plan: &PhysicalPlan| {
+            let PhysicalPlan::Project { source, .. } = plan else { panic!("expected projection") };
+            match source.as_ref() {
+                PhysicalPlan::Filter { source, .. } => source.as_ref().clone(),
+                other => other.clone(),
+            }
+        };
+        let executor = QueryExecutor::new(storage.clone()).with_index_manager(indexes.clone());
+
+        let equal = plan("MATCH (a)-[r:KNOWS]->(b) WHERE r.since = '2020' RETURN r");
+        assert!(matches!(source(&equal), PhysicalPlan::EdgeIndexScan { lookup: EdgeLookup::Property { .. }, .. }));
+        assert_eq!(executor.execute(&equal).unwrap().row_count, 1);
+        assert_eq!(indexes.usage("knows_since").lookups, 1);
+
+        // AND is not parsed yet, so the upper bound is added by hand
+        let mut query = parse("MATCH (a)-[r:KNOWS]->(b) WHERE r.since > '2019' RETURN r.since");
+        let Query::Read(read) = &mut query else { panic!("expected a read query") };
+        let condition = &mut read.where_clause.as_mut().unwrap().condition;
+        *condition = Expression::And(
+            Box::new(condition.clone()),
+            Box::new(Expression::Le(
+                Box::new(Expression::property(Expression::variable("r"), "since")),
+                Box::new(Expression::literal(PropertyValue::String("2021".to_string()))),
+            )),
+        );
+        let range = plan_query(&query);
+        assert!(matches!(source(&range), PhysicalPlan::EdgeIndexScan { lookup: EdgeLookup::Range { .. }, .. }));
+        let rows = executor.execute(&range).unwrap().rows;
+        let mut since: Vec<_> = rows.iter().map(|row| row["since"].clone()).collect();
+        since.sort_by_key(|value| format!("{:?}", value));
+        assert_eq!(since, vec![PropertyValue::String("2020".to_string()), PropertyValue::String("2021".to_string())]);
+
+        let by_type = plan("MATCH (a)-[r:LIKES]->(b) RETURN r");
+        assert!(matches!(source(&by_type), PhysicalPlan::EdgeIndexScan { lookup: EdgeLookup::Type(_), .. }));
+        assert_eq!(executor.execute(&by_type).unwrap().row_count, 1);
+        assert_eq!(indexes.usage("LIKES").lookups, 1);
+
+        // The endpoints are needed, so the edges alone cannot answer it
+        let joined = plan("MATCH (a)-[r:KNOWS]->(b) WHERE r.since = '2020' RETURN a");
+        assert!(matches!(source(&joined), PhysicalPlan::Scan { .. }));
+    }
+}
@@ -0,0 +1,414 @@
+//! Federated query execution across named graphs
+//!
+//! Runs queries of the form
+//!
+//! ```text
+//! FROM GRAPH hr MATCH (e:Employee)
+//! FROM GRAPH crm MATCH (c:Contact)
+//! WHERE e.email = c.email
+//! RETURN e.name, c.company
+//! ```
+//!
+//! Each `FROM GRAPH` part scans its own graph; the parts are then joined
+//! with a nested-loop join. WHERE conjuncts are applied as soon as every
+//! variable they mention is bound, so a selective condition on one graph
+//! prunes rows before the next graph is joined in.
+//!
+//! Joined rows qualify every column with its variable (`e.name`,
+//! `e._node_id`) and record the source graph in `e._graph`.
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::PropertyValue;
+use crate::query::ast::{Expression, FederatedQuery, PatternElement};
+use crate::query::executor::{QueryExecutor, QueryResult};
+use crate::query::planner::PhysicalPlan;
+use crate::storage::StorageBackend;
+use log::debug;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+type Row = HashMap<String, PropertyValue>;
+
+/// Executes [`FederatedQuery`]s against a set of named graphs
+pub struct FederatedExecutor<S: StorageBackend> {
+    graphs: HashMap<String, Arc<S>>,
+    parameters: HashMap<String, PropertyValue>,
+}
+
+impl<S: StorageBackend> FederatedExecutor<S> {
+    /// Create an executor with no graphs registered
+    pub fn new() -> Self {
+        Self {
+            graphs: HashMap::new(),
+            parameters: HashMap::new(),
+        }
+    }
+
+    /// Register a graph under the name used in `FROM GRAPH name`
+    pub fn with_graph(mut self, name: impl Into<String>, storage: Arc<S>) -> Self {
+        self.graphs.insert(name.into(), storage);
+        self
+    }
+
+    /// Bind values for `$name` parameters referenced by the query
+    pub fn with_parameters(mut self, parameters: HashMap<String, PropertyValue>) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Execute a federated query
+    pub fn execute(&self, query: &FederatedQuery) -> Result<QueryResult> {
+        let start = std::time::Instant::now();
+        check_return(&query.return_clause)?;
+
+        let mut pending: Vec<Expression> = Vec::new();
+        if let Some(where_clause) = &query.where_clause {
+            split_conjuncts(&where_clause.condition, &mut pending);
+        }
+
+        let mut bound: HashSet<String> = HashSet::new();
+        let mut rows: Vec<Row> = vec![Row::new()];
+        let mut evaluator = None;
+
+        for part in &query.parts {
+            let storage = self.graphs.get(&part.graph).ok_or_else(|| {
+                DeepGraphError::InvalidOperation(format!("Unknown graph: {}", part.graph))
+            })?;
+            let (variable, label) = first_node(part)?;
+            if !bound.insert(variable.clone()) {
+                return Err(DeepGraphError::InvalidOperation(format!(
+                    "Variable {} is bound by more than one graph",
+                    variable
+                )));
+            }
+
+            let executor = QueryExecutor::new(storage.clone()).with_parameters(self.parameters.clone());
+            let scanned = executor.execute(&PhysicalPlan::Scan { label })?;
+            let qualified: Vec<Row> = scanned
+                .rows
+                .into_iter()
+                .map(|row| qualify(&variable, &part.graph, row))
+                .collect();
+
+            let mut joined = Vec::with_capacity(rows.len().saturating_mul(qualified.len()));
+            for left in &rows {
+                for right in &qualified {
+                    let mut row = left.clone();
+                    row.extend(right.iter().map(|(k, v)| (k.clone(), v.clone())));
+                    joined.push(row);
+                }
+            }
+
+            // Apply every condition that is now fully bound
+            let (ready, rest): (Vec<_>, Vec<_>) = pending
+                .into_iter()
+                .partition(|expr| referenced_variables(expr).is_subset(&bound));
+            pending = rest;
+            if !ready.is_empty() {
+                joined = filter_rows(&executor, &ready, joined)?;
+            }
+            debug!("Joined graph {} as {}: {} rows", part.graph, variable, joined.len());
+
+            rows = joined;
+            evaluator = Some(executor);
+        }
+
+        let evaluator = match evaluator {
+            Some(evaluator) => evaluator,
+            None => return Ok(QueryResult::empty()),
+        };
+
+        // Conditions on variables no part binds can never be satisfied
+        if !pending.is_empty() {
+            rows = filter_rows(&evaluator, &pending, rows)?;
+        }
+
+        if let Some(limit) = query.return_clause.limit {
+            rows.truncate(limit.max(0) as usize);
+        }
+
+        let mut result = project(&evaluator, &query.return_clause.items, rows)?;
+        result.execution_time_ms = start.elapsed().as_millis() as u64;
+        Ok(result)
+    }
+}
+
+impl<S: StorageBackend> Default for FederatedExecutor<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Variable and label of a part's node pattern
+///
+/// Each part joins exactly one labelled node scan; anything the scan
+/// would silently drop is rejected instead.
+fn first_node(part: &crate::query::ast::GraphMatch) -> Result<(String, Option<String>)> {
+    let unsupported = |what: &str| {
+        DeepGraphError::InvalidOperation(format!(
+            "Federated queries do not support {} (graph {})",
+            what, part.graph
+        ))
+    };
+
+    let node = match part.match_clause.patterns.as_slice() {
+        [] => None,
+        [pattern] => match pattern.elements.as_slice() {
+            [] => None,
+            [PatternElement::Node(node)] => Some(node),
+            elements if elements.iter().any(|e| matches!(e, PatternElement::Relationship(_))) => {
+                return Err(unsupported("relationship patterns"));
+            }
+            _ => return Err(unsupported("more than one node per graph")),
+        },
+        _ => return Err(unsupported("more than one node per graph")),
+    }
+    .ok_or_else(|| {
+        DeepGraphError::InvalidOperation(format!("Graph {} has no node pattern", part.graph))
+    })?;
+
+    if !node.properties.is_empty() {
+        return Err(unsupported("inline property maps"));
+    }
+    if node.labels.len() > 1 {
+        return Err(unsupported("more than one label per node"));
+    }
+    let variable = node.variable.clone().ok_or_else(|| {
+        DeepGraphError::InvalidOperation(format!(
+            "Node pattern in graph {} needs a variable to be joined",
+            part.graph
+        ))
+    })?;
+    Ok((variable, node.labels.first().cloned()))
+}
+
+/// Reject RETURN modifiers the join does not implement
+fn check_return(clause: &crate::query::ast::ReturnClause) -> Result<()> {
+    if clause.distinct {
+        return Err(DeepGraphError::InvalidOperation(
+            "Federated queries do not support RETURN DISTINCT".to_string(),
+        ));
+    }
+    if clause.order_by.is_some() {
+        return Err(DeepGraphError::InvalidOperation(
+            "Federated queries do not support ORDER BY".to_string(),
+        ));
+    }
+    for item in &clause.items {
+        if item.alias.is_none() && column_name(&item.expression).is_none() {
+            return Err(DeepGraphError::InvalidOperation(
+                "Computed RETURN items in federated queries need an alias (AS name)".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Keep the rows every condition holds for, surfacing evaluation errors
+fn filter_rows<S: StorageBackend>(
+    evaluator: &QueryExecutor<S>,
+    conditions: &[Expression],
+    rows: Vec<Row>,
+) -> Result<Vec<Row>> {
+    let mut kept = Vec::with_capacity(rows.len());
+    'rows: for row in rows {
+        for expr in conditions {
+            if !evaluator.evaluate_predicate(expr, &row)? {
+                continue 'rows;
+            }
+        }
+        kept.push(row);
+    }
+    Ok(kept)
+}
+
+/// Prefix every column of a scanned row with its variable
+fn qualify(variable: &str, graph: &str, row: Row) -> Row {
+    let mut qualified: Row = row
+        .into_iter()
+        .map(|(key, value)| (format!("{}.{}", variable, key), value))
+        .collect();
+    qualified.insert(format!("{}._graph", variable), PropertyValue::String(graph.to_string()));
+    qualified
+}
+
+fn split_conjuncts(expr: &Expression, out: &mut Vec<Expression>) {
+    match expr {
+        Expression::And(left, right) => {
+            split_conjuncts(left, out);
+            split_conjuncts(right, out);
+        }
+        other => out.push(other.clone()),
+    }
+}
+
+fn referenced_variables(expr: &Expression) -> HashSet<String> {
+    fn walk(expr: &Expression, out: &mut HashSet<String>) {
+        use Expression::*;
+        match expr {
+            Variable(name) => {
+                out.insert(name.clone());
+            }
+            Property(base, _) | Not(base) | Neg(base) => walk(base, out),
+            And(l, r) | Or(l, r) | Eq(l, r) | Ne(l, r) | Lt(l, r) | Le(l, r) | Gt(l, r)
+            | Ge(l, r) | Add(l, r) | Sub(l, r) | Mul(l, r) | Div(l, r) | Mod(l, r) => {
+                walk(l, out);
+                walk(r, out);
+            }
+            FunctionCall { args, .. } => args.iter().for_each(|arg| walk(arg, out)),
+            Literal(_) | Parameter(_) => {}
+        }
+    }
+    let mut out = HashSet::new();
+    walk(expr, &mut out);
+    out
+}
+
+/// Evaluate RETURN items over joined rows
+///
+/// A bare variable expands to all of its qualified columns.
+fn project<S: StorageBackend>(
+    evaluator: &QueryExecutor<S>,
+    items: &[crate::query::ast::ReturnItem],
+    rows: Vec<Row>,
+) -> Result<QueryResult> {
+    let mut columns: Vec<String> = Vec::new();
+    let mut projected = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let mut out = Row::new();
+        for item in items {
+            match &item.expression {
+                Expression::Variable(var) => {
+                    let prefix = format!("{}.", var);
+                    for (key, value) in row.iter().filter(|(key, _)| key.starts_with(&prefix)) {
+                        out.insert(key.clone(), value.clone());
+                    }
+                }
+                expr => {
+                    // check_return guarantees one of the two
+                    let column = item.alias.clone().or_else(|| column_name(expr)).unwrap_or_default();
+                    let value = evaluator.evaluate_value(expr, &row)?;
+                    out.insert(column, value);
+                }
+            }
+        }
+        let mut keys: Vec<&String> = out.keys().collect();
+        keys.sort();
+        for key in keys {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+        projected.push(out);
+    }
+
+    Ok(QueryResult::with_data(columns, projected))
+}
+
+/// Column name for an unaliased RETURN item, if it has a natural one
+fn column_name(expr: &Expression) -> Option<String> {
+    match expr {
+        Expression::Property(base, prop) => match base.as_ref() {
+            Expression::Variable(var) => Some(format!("{}.{}", var, prop)),
+            _ => Some(prop.clone()),
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+    use crate::query::ast::{Query, Statement};
+    use crate::query::CypherParser;
+    use crate::storage::MemoryStorage;
+
+    fn person(storage: &MemoryStorage, label: &str, email: &str, extra: (&str, &str)) {
+        let mut node = Node::new(vec![label.to_string()]);
+        node.set_property("email".to_string(), email.into());
+        node.set_property(extra.0.to_string(), extra.1.into());
+        storage.add_node(node).unwrap();
+    }
+
+    fn federated(text: &str) -> FederatedQuery {
+        match CypherParser::parse(text).unwrap() {
+            Statement::Query(Query::Federated(query)) => query,
+            other => panic!("expected a federated query, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_constructs_are_rejected() {
+        let hr = Arc::new(MemoryStorage::new());
+        person(&hr, "Employee", "ann@example.com", ("name", "Ann"));
+        let crm = Arc::new(MemoryStorage::new());
+        person(&crm, "Contact", "ann@example.com", ("company", "Acme"));
+        let executor = FederatedExecutor::new().with_graph("hr", hr).with_graph("crm", crm);
+
+        for text in [
+            "FROM GRAPH hr MATCH (e:Employee)-[:KNOWS]->(f) FROM GRAPH crm MATCH (c:Contact) RETURN e.name",
+            "FROM GRAPH hr MATCH (e:Employee), (f:Employee) FROM GRAPH crm MATCH (c:Contact) RETURN e.name",
+            "FROM GRAPH hr MATCH (e:Employee {name: 'Ann'}) FROM GRAPH crm MATCH (c:Contact) RETURN e.name",
+            "FROM GRAPH hr MATCH (e:Employee) FROM GRAPH crm MATCH (c:Contact) RETURN id(e)",
+        ] {
+            let err = executor.execute(&federated(text)).expect_err(text);
+            assert!(matches!(err, DeepGraphError::InvalidOperation(_)), "{}: {:?}", text, err);
+        }
+
+        let mut query = federated("FROM GRAPH hr MATCH (e:Employee) FROM GRAPH crm MATCH (c:Contact) RETURN e.name");
+        query.return_clause.distinct = true;
+        assert!(matches!(executor.execute(&query), Err(DeepGraphError::InvalidOperation(_))));
+        query.return_clause.distinct = false;
+        query.return_clause.order_by = Some(vec![crate::query::ast::OrderItem {
+            expression: Expression::Variable("e".to_string()),
+            ascending: true,
+        }]);
+        assert!(matches!(executor.execute(&query), Err(DeepGraphError::InvalidOperation(_))));
+
+        // Predicate errors surface instead of silently dropping rows
+        let query = federated(
+            "FROM GRAPH hr MATCH (e:Employee) FROM GRAPH crm MATCH (c:Contact) \
+             WHERE e.email = $email RETURN e.name",
+        );
+        assert!(executor.execute(&query).is_err());
+
+        let query = federated(
+            "FROM GRAPH hr MATCH (e:Employee) FROM GRAPH crm MATCH (c:Contact) \
+             WHERE e.email = c.email RETURN id(e) AS employee, c.company",
+        );
+        let result = executor.execute(&query).unwrap();
+        assert_eq!(result.row_count, 1);
+        assert!(result.columns.contains(&"employee".to_string()));
+    }
+
+    #[test]
+    fn test_join_across_graphs() {
+        let hr = Arc::new(MemoryStorage::new());
+        person(&hr, "Employee", "ann@example.com", ("name", "Ann"));
+        person(&hr, "Employee", "bo@example.com", ("name", "Bo"));
+        let crm = Arc::new(MemoryStorage::new());
+        person(&crm, "Contact", "ann@example.com", ("company", "Acme"));
+        person(&crm, "Contact", "cy@example.com", ("company", "Initech"));
+
+        let query = match CypherParser::parse(
+            "FROM GRAPH hr MATCH (e:Employee) FROM GRAPH crm MATCH (c:Contact) \
+             WHERE e.email = c.email RETURN e.name, c.company",
+        )
+        .unwrap()
+        {
+            Statement::Query(Query::Federated(query)) => query,
+            other => panic!("expected a federated query, got {:?}", other),
+        };
+
+        let executor = FederatedExecutor::new().with_graph("hr", hr).with_graph("crm", crm.clone());
+        let result = executor.execute(&query).unwrap();
+        assert_eq!(result.row_count, 1);
+        assert_eq!(result.rows[0]["e.name"], PropertyValue::String("Ann".to_string()));
+        assert_eq!(result.rows[0]["c.company"], PropertyValue::String("Acme".to_string()));
+
+        let missing = FederatedExecutor::new().with_graph("crm", crm);
+        assert!(missing.execute(&query).is_err());
+    }
+}
@@ -7,9 +7,17 @@ pub mod ast;
 pub mod parser;
 pub mod planner;
 pub mod executor;
+pub mod federation;
+pub mod rewrite;
+pub mod procedures;
+pub mod visualization;
+#[cfg(feature = "sql")]
+pub mod sql;
 
 pub use ast::{Statement, Query, Pattern, Expression};
 pub use parser::CypherParser;
 pub use planner::{QueryPlanner, LogicalPlan, PhysicalPlan};
 pub use executor::{QueryExecutor, QueryResult};
+pub use federation::FederatedExecutor;
+pub use visualization::GraphView;
 
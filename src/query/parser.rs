@@ -63,6 +63,9 @@ fn build_query(pair: Pair<Rule>) -> Result<Query> {
         match inner.as_rule() {
             Rule::read_query => return Ok(Query::Read(build_read_query(inner)?)),
             Rule::write_query => return Ok(Query::Write(build_write_query(inner)?)),
+            Rule::call_query => return Ok(Query::Call(build_call_query(inner)?)),
+            Rule::federated_query => return Ok(Query::Federated(build_federated_query(inner)?)),
+            Rule::use_query => return Ok(Query::Use(build_use_query(inner)?)),
             _ => {}
         }
     }
@@ -93,17 +96,122 @@ fn build_read_query(pair: Pair<Rule>) -> Result<ReadQuery> {
     })
 }
 
+/// Build UseQuery from parse tree (USE g [query])
+fn build_use_query(pair: Pair<Rule>) -> Result<UseQuery> {
+    let mut graph = None;
+    let mut query = None;
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::identifier => graph = Some(inner.as_str().to_string()),
+            Rule::query => query = Some(Box::new(build_query(inner)?)),
+            _ => {}
+        }
+    }
+    Ok(UseQuery {
+        graph: graph.ok_or_else(|| DeepGraphError::ParserError("Missing graph name".to_string()))?,
+        query,
+    })
+}
+
+/// Build FederatedQuery from parse tree (FROM GRAPH g MATCH ... RETURN ...)
+fn build_federated_query(pair: Pair<Rule>) -> Result<FederatedQuery> {
+    let mut parts = Vec::new();
+    let mut where_clause = None;
+    let mut return_clause = None;
+    
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::graph_match => {
+                let mut graph = None;
+                let mut match_clause = None;
+                for part in inner.into_inner() {
+                    match part.as_rule() {
+                        Rule::identifier => graph = Some(part.as_str().to_string()),
+                        Rule::match_clause => match_clause = Some(build_match_clause(part)?),
+                        _ => {}
+                    }
+                }
+                parts.push(GraphMatch {
+                    graph: graph
+                        .ok_or_else(|| DeepGraphError::ParserError("Missing graph name".to_string()))?,
+                    match_clause: match_clause
+                        .ok_or_else(|| DeepGraphError::ParserError("Missing MATCH clause".to_string()))?,
+                });
+            }
+            Rule::where_clause => where_clause = Some(build_where_clause(inner)?),
+            Rule::return_clause => return_clause = Some(build_return_clause(inner)?),
+            _ => {}
+        }
+    }
+    
+    Ok(FederatedQuery {
+        parts,
+        where_clause,
+        return_clause: return_clause
+            .ok_or_else(|| DeepGraphError::ParserError("Missing RETURN clause".to_string()))?,
+    })
+}
+
+/// Build CallClause from parse tree (CALL db.proc(args))
+fn build_call_query(pair: Pair<Rule>) -> Result<CallClause> {
+    let mut procedure = None;
+    let mut args = Vec::new();
+    
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::procedure_name => procedure = Some(inner.as_str().to_string()),
+            Rule::expression => args.push(build_expression(inner)?),
+            _ => {}
+        }
+    }
+    
+    Ok(CallClause {
+        procedure: procedure
+            .ok_or_else(|| DeepGraphError::ParserError("Missing procedure name".to_string()))?,
+        args,
+    })
+}
+
 /// Build MatchClause from parse tree
 fn build_match_clause(pair: Pair<Rule>) -> Result<MatchClause> {
     let mut patterns = Vec::new();
+    let mut hints = Vec::new();
     
     for inner in pair.into_inner() {
-        if inner.as_rule() == Rule::pattern {
-            patterns.push(build_pattern(inner)?);
+        match inner.as_rule() {
+            Rule::pattern => patterns.push(build_pattern(inner)?),
+            Rule::index_hint => hints.push(build_index_hint(inner)?),
+            _ => {}
         }
     }
     
-    Ok(MatchClause { patterns })
+    Ok(MatchClause { patterns, hints })
+}
+
+/// Build IndexHint from parse tree
+fn build_index_hint(pair: Pair<Rule>) -> Result<IndexHint> {
+    let hint = pair
+        .into_inner()
+        .next()
+        .ok_or_else(|| DeepGraphError::ParserError("Empty USING hint".to_string()))?;
+    let rule = hint.as_rule();
+    
+    let mut variable = String::new();
+    let mut label = String::new();
+    let mut property = String::new();
+    for inner in hint.into_inner() {
+        match inner.as_rule() {
+            Rule::variable => variable = inner.as_str().to_string(),
+            Rule::label => label = inner.as_str().to_string(),
+            Rule::property_key => property = inner.as_str().to_string(),
+            _ => {}
+        }
+    }
+    
+    Ok(match rule {
+        Rule::index_hint_index => IndexHint::Index { variable, label, property },
+        _ => IndexHint::Scan { variable, label },
+    })
 }
 
 /// Build Pattern from parse tree
@@ -693,5 +801,18 @@ mod tests {
             assert!(create.patterns.len() > 0);
         }
     }
-}
 
+    #[test]
+    fn test_parse_call() {
+        let result = CypherParser::parse("CALL db.degreeDistribution('Person');");
+        
+        if let Ok(Statement::Query(Query::Call(call))) = result {
+            assert_eq!(call.procedure, "db.degreeDistribution");
+            assert_eq!(call.args.len(), 1);
+        } else {
+            panic!("Expected CallClause, got {:?}", result);
+        }
+        
+        assert!(CypherParser::validate("CALL db.schema.relCounts()").is_ok());
+    }
+}
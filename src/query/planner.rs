@@ -1,10 +1,25 @@
 //! Query planner for optimization
 //!
 //! Transforms AST into optimized execution plans
+//!
+//! An equality predicate on an indexed property of the scanned node turns
+//! the label scan into an index lookup when that is estimated to be
+//! cheaper. An index scoped to a label is only used when the node pattern
+//! has that label. `USING INDEX n:Label(property)` forces the lookup and
+//! `USING SCAN n:Label` rules it out.
+//!
+//! A `()-[r:TYPE]->()` pattern whose relationship is the only variable the
+//! query refers to is answered from the edges alone. Such a scan becomes a
+//! lookup through an edge property index for `r.key = literal` or a range
+//! between two string literals, or through a relationship type index.
 
-use crate::error::Result;
+use crate::error::{DeepGraphError, Result};
+use crate::graph::PropertyValue;
+use crate::index::{property_to_bytes, IndexManager, IndexType};
+use crate::metrics::{self, OperatorEvent};
 use crate::query::ast::*;
-use std::collections::HashMap;
+use crate::storage::{CostConstants, GraphStats, StorageBackend};
+use std::collections::{HashMap, HashSet};
 
 /// Logical query plan (high-level operations)
 #[derive(Debug, Clone)]
@@ -20,7 +35,20 @@ pub enum LogicalPlan {
         variable: String,
         label: String,
         property: String,
-        value: String,
+        value: PropertyValue,
+    },
+    
+    /// Scan all edges, or those of one relationship type
+    EdgeScan {
+        variable: String,
+        rel_type: Option<String>,
+    },
+    
+    /// Edge lookup through a relationship type or edge property index
+    EdgeIndexLookup {
+        variable: String,
+        rel_type: Option<String>,
+        lookup: EdgeLookup,
     },
     
     /// Filter operation
@@ -46,6 +74,12 @@ pub enum LogicalPlan {
         source: Box<LogicalPlan>,
         count: i64,
     },
+    
+    /// Invoke a built-in procedure
+    ProcedureCall {
+        procedure: String,
+        args: Vec<Expression>,
+    },
 }
 
 /// Physical query plan (execution details)
@@ -56,10 +90,24 @@ pub enum PhysicalPlan {
         label: Option<String>,
     },
     
-    /// Use hash index
+    /// Equality lookup through a hash or B-tree index
     HashIndexScan {
         index_name: String,
         key: Vec<u8>,
+        /// Label the matched nodes must carry
+        label: Option<String>,
+    },
+    
+    /// Scan edges from storage
+    EdgeScan {
+        rel_type: Option<String>,
+    },
+    
+    /// Find edges through an edge index
+    EdgeIndexScan {
+        lookup: EdgeLookup,
+        /// Relationship type the matched edges must have
+        rel_type: Option<String>,
     },
     
     /// Use B-tree index with range
@@ -80,6 +128,34 @@ pub enum PhysicalPlan {
         source: Box<PhysicalPlan>,
         columns: Vec<String>,
     },
+    
+    /// Invoke a built-in procedure
+    ProcedureCall {
+        procedure: String,
+        args: Vec<Expression>,
+    },
+}
+
+/// How an edge index lookup finds its edges
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdgeLookup {
+    /// Every edge of a relationship type
+    Type(String),
+    /// Edges whose `property` equals `value`
+    Property {
+        property: String,
+        value: PropertyValue,
+    },
+    /// Edges whose `property` lies between `start` and `end`
+    ///
+    /// Only planned for strings: the index orders keys by their byte
+    /// encoding, which matches value order for strings alone.
+    Range {
+        property: String,
+        start: PropertyValue,
+        end: PropertyValue,
+        end_inclusive: bool,
+    },
 }
 
 /// Query planner
@@ -97,6 +173,129 @@ pub struct PlannerStats {
     pub edge_count: usize,
     /// Available indices
     pub indices: HashMap<String, IndexStats>,
+    /// Available edge indices; relationship type indexes have no property
+    pub edge_indices: HashMap<String, IndexStats>,
+    /// Nodes per label, when known
+    pub label_counts: HashMap<String, u64>,
+    /// Edges per relationship type, when known
+    pub rel_type_counts: HashMap<String, u64>,
+    /// I/O and CPU cost constants of the target backend
+    pub cost: CostConstants,
+}
+
+impl PlannerStats {
+    /// Collect counts and cost constants from a storage backend
+    pub fn for_backend<S: StorageBackend + ?Sized>(storage: &S) -> Self {
+        Self {
+            node_count: storage.node_count(),
+            edge_count: storage.edge_count(),
+            indices: HashMap::new(),
+            edge_indices: HashMap::new(),
+            label_counts: storage
+                .statistics()
+                .map(|stats| stats.label_counts())
+                .unwrap_or_default(),
+            rel_type_counts: storage
+                .statistics()
+                .map(|stats| stats.rel_type_counts())
+                .unwrap_or_default(),
+            cost: storage.cost_constants(),
+        }
+    }
+    
+    /// Record the node property and edge indexes of an index manager
+    ///
+    /// Indexes with size or TTL limits are left out: entries they evicted
+    /// would silently drop rows from a lookup.
+    pub fn with_indexes(mut self, indexes: &IndexManager) -> Self {
+        for info in indexes.list_indices() {
+            let Some(index_type) = info.index_type else {
+                continue;
+            };
+            if !info.limits.is_unbounded() || (info.property_key.is_none() && !info.on_edges) {
+                continue;
+            }
+            let index_type = match index_type {
+                IndexType::Hash => "hash",
+                IndexType::BTree => "btree",
+            };
+            let stats = IndexStats {
+                index_type: index_type.to_string(),
+                entry_count: info.size,
+                property_key: info.property_key,
+                label: info.label,
+            };
+            if info.on_edges {
+                self.edge_indices.insert(info.name, stats);
+            } else {
+                self.indices.insert(info.name, stats);
+            }
+        }
+        self
+    }
+    
+    /// Name of an index usable for `property` of a node with `labels`
+    ///
+    /// An index scoped to one of the labels is preferred over an unscoped
+    /// one; indexes scoped to other labels are never returned.
+    pub fn index_on(&self, labels: &[String], property: &str) -> Option<&str> {
+        let candidates = || {
+            self.indices
+                .iter()
+                .filter(move |(_, stats)| stats.property_key.as_deref() == Some(property))
+        };
+        candidates()
+            .find(|(_, stats)| stats.label.as_ref().is_some_and(|label| labels.contains(label)))
+            .or_else(|| candidates().find(|(_, stats)| stats.label.is_none()))
+            .map(|(name, _)| name.as_str())
+    }
+    
+    /// Index on an edge property, if there is one
+    pub fn edge_index_on(&self, property: &str) -> Option<&IndexStats> {
+        self.edge_indices
+            .values()
+            .find(|stats| stats.property_key.as_deref() == Some(property))
+    }
+    
+    /// Whether edges of `rel_type` can be found through a type index
+    ///
+    /// Relationship type indexes are named after the type they cover.
+    pub fn has_rel_type_index(&self, rel_type: &str) -> bool {
+        self.edge_indices
+            .get(rel_type)
+            .is_some_and(|stats| stats.property_key.is_none())
+    }
+    
+    /// Take counts from a [`GraphStats`] summary
+    pub fn with_graph_stats(mut self, stats: &GraphStats) -> Self {
+        self.node_count = stats.node_count;
+        self.edge_count = stats.edge_count;
+        self.label_counts = stats.label_counts.clone();
+        self.rel_type_counts = stats.rel_type_counts.clone();
+        self
+    }
+    
+    /// Estimated number of nodes a label scan returns
+    ///
+    /// Uses the rarest label when several are given; falls back to the
+    /// total node count when a label has no recorded count.
+    pub fn label_cardinality(&self, labels: &[String]) -> usize {
+        labels
+            .iter()
+            .map(|label| match self.label_counts.get(label) {
+                Some(count) => *count as usize,
+                None => self.node_count,
+            })
+            .min()
+            .unwrap_or(self.node_count)
+    }
+    
+    /// Estimated number of edges of a relationship type
+    pub fn rel_type_cardinality(&self, rel_type: Option<&str>) -> usize {
+        rel_type
+            .and_then(|rel_type| self.rel_type_counts.get(rel_type))
+            .map_or(self.edge_count, |count| *count as usize)
+    }
 }
 
 /// Index statistics
@@ -106,6 +305,10 @@ pub struct IndexStats {
     pub index_type: String,
     /// Number of entries
     pub entry_count: usize,
+    /// Indexed node property, if a property index
+    pub property_key: Option<String>,
+    /// Label the index is restricted to
+    pub label: Option<String>,
 }
 
 impl QueryPlanner {
@@ -132,15 +335,37 @@ impl QueryPlanner {
                     labels: vec![],
                 })
             }
+            Query::Call(call) => Ok(LogicalPlan::ProcedureCall {
+                procedure: call.procedure.clone(),
+                args: call.args.clone(),
+            }),
+            Query::Federated(_) => Err(crate::error::DeepGraphError::InvalidOperation(
+                "Federated queries span several graphs; run them with FederatedExecutor".to_string()
+            )),
+            Query::Use(use_query) => match &use_query.query {
+                Some(query) => self.logical_plan(query),
+                None => Err(crate::error::DeepGraphError::InvalidOperation(
+                    format!("USE {} selects a graph but has no query to plan", use_query.graph)
+                )),
+            },
         }
     }
     
     /// Plan a read query
     fn plan_read_query(&self, query: &ReadQuery) -> Result<LogicalPlan> {
-        // Start with node scan
-        let mut plan = self.plan_match(&query.match_clause)?;
+        // Start with node scan, or an edge scan for edge-only patterns
+        let mut plan = match edge_pattern(query) {
+            Some((variable, rel_type)) => LogicalPlan::EdgeScan { variable, rel_type },
+            None => self.plan_match(&query.match_clause)?,
+        };
+        let condition = query.where_clause.as_ref().map(|where_clause| &where_clause.condition);
+        if let Some(lookup) = self.plan_index_lookup(&plan, &query.match_clause.hints, condition)? {
+            plan = lookup;
+        } else if let Some(lookup) = self.plan_edge_index_lookup(&plan, condition) {
+            plan = lookup;
+        }
         
-        // Add filter if WHERE exists
+        // Add filter if WHERE exists (the lookup only narrows the scan)
         if let Some(where_clause) = &query.where_clause {
             plan = LogicalPlan::Filter {
                 source: Box::new(plan),
@@ -195,6 +420,108 @@ impl QueryPlanner {
         })
     }
     
+    /// Replace a node scan with an index lookup when hinted or cheaper
+    fn plan_index_lookup(
+        &self,
+        scan: &LogicalPlan,
+        hints: &[IndexHint],
+        condition: Option<&Expression>,
+    ) -> Result<Option<LogicalPlan>> {
+        let LogicalPlan::NodeScan { variable, labels } = scan else {
+            return Ok(None);
+        };
+        let mut equalities = Vec::new();
+        if let Some(condition) = condition {
+            collect_equalities(variable, condition, &mut equalities);
+        }
+        
+        for hint in hints {
+            match hint {
+                IndexHint::Scan { variable: hinted, .. } if hinted == variable => return Ok(None),
+                IndexHint::Index { variable: hinted, label, property } if hinted == variable => {
+                    if self.stats.index_on(std::slice::from_ref(label), property).is_none() {
+                        return Err(DeepGraphError::InvalidOperation(format!(
+                            "USING INDEX {}:{}({}): no index on {} usable for :{}",
+                            variable, label, property, property, label
+                        )));
+                    }
+                    let value = equalities
+                        .iter()
+                        .find(|(key, _)| key == property)
+                        .map(|(_, value)| value.clone())
+                        .ok_or_else(|| DeepGraphError::InvalidOperation(format!(
+                            "USING INDEX {}:{}({}) needs a predicate {}.{} = <literal>",
+                            variable, label, property, variable, property
+                        )))?;
+                    return Ok(Some(LogicalPlan::IndexLookup {
+                        variable: variable.clone(),
+                        label: label.clone(),
+                        property: property.clone(),
+                        value,
+                    }));
+                }
+                _ => {}
+            }
+        }
+        
+        let scan_cost = self.estimate_cost(scan);
+        for (property, value) in equalities {
+            if self.stats.index_on(labels, &property).is_none() {
+                continue;
+            }
+            let lookup = LogicalPlan::IndexLookup {
+                variable: variable.clone(),
+                label: labels.first().cloned().unwrap_or_default(),
+                property,
+                value,
+            };
+            if self.estimate_cost(&lookup) < scan_cost {
+                return Ok(Some(lookup));
+            }
+        }
+        Ok(None)
+    }
+    
+    /// Replace an edge scan with an edge index lookup when cheaper
+    ///
+    /// Edge property predicates are tried before the relationship type, as
+    /// they usually match fewer edges.
+    fn plan_edge_index_lookup(&self, scan: &LogicalPlan, condition: Option<&Expression>) -> Option<LogicalPlan> {
+        let LogicalPlan::EdgeScan { variable, rel_type } = scan else {
+            return None;
+        };
+        let mut lookups = Vec::new();
+        if let Some(condition) = condition {
+            let mut equalities = Vec::new();
+            collect_equalities(variable, condition, &mut equalities);
+            lookups.extend(
+                equalities
+                    .into_iter()
+                    .filter(|(property, _)| self.stats.edge_index_on(property).is_some())
+                    .map(|(property, value)| EdgeLookup::Property { property, value }),
+            );
+            lookups.extend(
+                string_ranges(variable, condition)
+                    .into_iter()
+                    .filter(|lookup| matches!(lookup, EdgeLookup::Range { property, .. }
+                        if self.stats.edge_index_on(property).is_some_and(|stats| stats.index_type == "btree"))),
+            );
+        }
+        if let Some(rel_type) = rel_type.as_ref().filter(|rel_type| self.stats.has_rel_type_index(rel_type)) {
+            lookups.push(EdgeLookup::Type(rel_type.clone()));
+        }
+        
+        let scan_cost = self.estimate_cost(scan);
+        lookups
+            .into_iter()
+            .map(|lookup| LogicalPlan::EdgeIndexLookup {
+                variable: variable.clone(),
+                rel_type: rel_type.clone(),
+                lookup,
+            })
+            .find(|lookup| self.estimate_cost(lookup) < scan_cost)
+    }
+    
     /// Optimize logical plan into physical plan
     pub fn physical_plan(&self, logical: &LogicalPlan) -> Result<PhysicalPlan> {
         match logical {
@@ -204,6 +531,25 @@ impl QueryPlanner {
                 Ok(PhysicalPlan::Scan { label })
             }
             
+            LogicalPlan::IndexLookup { label, property, value, .. } => match self.stats.index_on(std::slice::from_ref(label), property) {
+                Some(index_name) => Ok(PhysicalPlan::HashIndexScan {
+                    index_name: index_name.to_string(),
+                    key: property_to_bytes(value),
+                    label: (!label.is_empty()).then(|| label.clone()),
+                }),
+                None => {
+                    metrics::global().record(OperatorEvent::IndexFallback, "IndexLookup");
+                    Ok(PhysicalPlan::Scan { label: (!label.is_empty()).then(|| label.clone()) })
+                }
+            },
+            
+            LogicalPlan::EdgeScan { rel_type, .. } => Ok(PhysicalPlan::EdgeScan { rel_type: rel_type.clone() }),
+            
+            LogicalPlan::EdgeIndexLookup { rel_type, lookup, .. } => Ok(PhysicalPlan::EdgeIndexScan {
+                lookup: lookup.clone(),
+                rel_type: rel_type.clone(),
+            }),
+            
             LogicalPlan::Filter { source, condition } => {
                 let source_plan = self.physical_plan(source)?;
                 Ok(PhysicalPlan::Filter {
@@ -237,8 +583,18 @@ impl QueryPlanner {
                 self.physical_plan(source)
             }
             
+            LogicalPlan::ProcedureCall { procedure, args } => Ok(PhysicalPlan::ProcedureCall {
+                procedure: procedure.clone(),
+                args: args.clone(),
+            }),
+            
             _ => {
                 // Fallback to simple scan
+                let operator = match logical {
+                    LogicalPlan::Join { .. } => "Join",
+                    _ => "Unknown",
+                };
+                metrics::global().record(OperatorEvent::IndexFallback, operator);
                 Ok(PhysicalPlan::Scan { label: None })
             }
         }
@@ -246,20 +602,35 @@ impl QueryPlanner {
     
     /// Estimate cost of a logical plan
     pub fn estimate_cost(&self, plan: &LogicalPlan) -> f64 {
+        let cost = &self.stats.cost;
         match plan {
-            LogicalPlan::NodeScan { .. } => {
-                // Full scan cost = node count
-                self.stats.node_count as f64
+            LogicalPlan::NodeScan { labels, .. } => {
+                // Scan cost = nodes read * sequential read cost
+                self.stats.label_cardinality(labels) as f64 * cost.sequential_read
             }
             
             LogicalPlan::IndexLookup { .. } => {
-                // Index lookup cost = O(log n)
-                (self.stats.node_count as f64).log2()
+                // Index lookup cost = O(log n) probes + fetching the match
+                (self.stats.node_count as f64).log2().max(1.0) * cost.index_probe + cost.random_read
+            }
+            
+            LogicalPlan::EdgeScan { .. } => self.stats.edge_count as f64 * cost.sequential_read,
+            
+            LogicalPlan::EdgeIndexLookup { rel_type, lookup, .. } => {
+                let probes = (self.stats.edge_count as f64).log2().max(1.0) * cost.index_probe;
+                // Equality is assumed to match one edge and a range a third of
+                // the edges of the type
+                let fetched = match lookup {
+                    EdgeLookup::Type(rel_type) => self.stats.rel_type_cardinality(Some(rel_type)) as f64,
+                    EdgeLookup::Property { .. } => 1.0,
+                    EdgeLookup::Range { .. } => self.stats.rel_type_cardinality(rel_type.as_deref()) as f64 / 3.0,
+                };
+                probes + fetched * cost.random_read
             }
             
             LogicalPlan::Filter { source, .. } => {
                 // Filter cost = source cost + evaluation
-                self.estimate_cost(source) + self.stats.node_count as f64 * 0.1
+                self.estimate_cost(source) + self.stats.node_count as f64 * cost.cpu_tuple
             }
             
             LogicalPlan::Project { source, .. } => {
@@ -276,10 +647,153 @@ impl QueryPlanner {
                 // Join cost = product of inputs
                 self.estimate_cost(left) * self.estimate_cost(right)
             }
+            
+            LogicalPlan::ProcedureCall { .. } => {
+                // Procedures answer from maintained statistics
+                1.0
+            }
         }
     }
 }
 
+/// Variable and type of the relationship in a lone `()-[r]->()` pattern
+/// when the query refers to no other variable
+///
+/// Labels or properties on either end, or an undirected relationship,
+/// need the nodes as well and rule the edge-only plan out.
+fn edge_pattern(query: &ReadQuery) -> Option<(String, Option<String>)> {
+    let [pattern] = query.match_clause.patterns.as_slice() else {
+        return None;
+    };
+    let [PatternElement::Node(from), PatternElement::Relationship(rel), PatternElement::Node(to)] =
+        pattern.elements.as_slice()
+    else {
+        return None;
+    };
+    let bare = |node: &NodePattern| node.labels.is_empty() && node.properties.is_empty();
+    if !bare(from) || !bare(to) || !rel.properties.is_empty() || rel.direction == Direction::Both {
+        return None;
+    }
+    let variable = rel.variable.clone()?;
+    
+    let mut referenced = HashSet::new();
+    if let Some(where_clause) = &query.where_clause {
+        collect_variables(&where_clause.condition, &mut referenced);
+    }
+    for item in &query.return_clause.items {
+        collect_variables(&item.expression, &mut referenced);
+    }
+    for item in query.return_clause.order_by.iter().flatten() {
+        collect_variables(&item.expression, &mut referenced);
+    }
+    referenced
+        .iter()
+        .all(|name| *name == variable)
+        .then(|| (variable, rel.rel_type.clone()))
+}
+
+/// Collect the variables an expression refers to
+fn collect_variables(expr: &Expression, out: &mut HashSet<String>) {
+    use Expression::*;
+    match expr {
+        Variable(name) => {
+            out.insert(name.clone());
+        }
+        Property(base, _) | Not(base) | Neg(base) => collect_variables(base, out),
+        And(l, r) | Or(l, r) | Eq(l, r) | Ne(l, r) | Lt(l, r) | Le(l, r) | Gt(l, r)
+        | Ge(l, r) | Add(l, r) | Sub(l, r) | Mul(l, r) | Div(l, r) | Mod(l, r) => {
+            collect_variables(l, out);
+            collect_variables(r, out);
+        }
+        FunctionCall { args, .. } => args.iter().for_each(|arg| collect_variables(arg, out)),
+        Literal(_) | Parameter(_) => {}
+    }
+}
+
+/// Bounds found for one property by [`string_ranges`]
+#[derive(Default)]
+struct StringBounds {
+    start: Option<String>,
+    /// Upper bound and whether it is inclusive
+    end: Option<(String, bool)>,
+}
+
+/// Ranges on string properties of `variable` bounded on both sides by
+/// conjuncts of a condition
+fn string_ranges(variable: &str, condition: &Expression) -> Vec<EdgeLookup> {
+    let mut bounds: HashMap<String, StringBounds> = HashMap::new();
+    let mut conjuncts = vec![condition];
+    while let Some(conjunct) = conjuncts.pop() {
+        let (left, right, lower, inclusive) = match conjunct {
+            Expression::And(left, right) => {
+                conjuncts.push(left);
+                conjuncts.push(right);
+                continue;
+            }
+            Expression::Gt(left, right) => (left, right, true, false),
+            Expression::Ge(left, right) => (left, right, true, true),
+            Expression::Lt(left, right) => (left, right, false, false),
+            Expression::Le(left, right) => (left, right, false, true),
+            _ => continue,
+        };
+        // `literal < r.key` bounds the property from the other side
+        let (target, key, value, lower) = match (left.as_ref(), right.as_ref()) {
+            (Expression::Property(target, key), Expression::Literal(PropertyValue::String(value))) => {
+                (target, key, value, lower)
+            }
+            (Expression::Literal(PropertyValue::String(value)), Expression::Property(target, key)) => {
+                (target, key, value, !lower)
+            }
+            _ => continue,
+        };
+        if !matches!(target.as_ref(), Expression::Variable(name) if name == variable) {
+            continue;
+        }
+        // Any bound will do: the filter above the lookup checks them all
+        let entry = bounds.entry(key.clone()).or_default();
+        if lower {
+            entry.start = Some(value.clone());
+        } else {
+            entry.end = Some((value.clone(), inclusive));
+        }
+    }
+    bounds
+        .into_iter()
+        .filter_map(|(property, bounds)| match bounds {
+            StringBounds { start: Some(start), end: Some((end, end_inclusive)) } => Some(EdgeLookup::Range {
+                property,
+                start: PropertyValue::String(start),
+                end: PropertyValue::String(end),
+                end_inclusive,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Collect `variable.key = literal` conjuncts of a condition
+fn collect_equalities(variable: &str, condition: &Expression, out: &mut Vec<(String, PropertyValue)>) {
+    match condition {
+        Expression::And(left, right) => {
+            collect_equalities(variable, left, out);
+            collect_equalities(variable, right, out);
+        }
+        Expression::Eq(left, right) => {
+            let pair = match (left.as_ref(), right.as_ref()) {
+                (Expression::Property(target, key), Expression::Literal(value))
+                | (Expression::Literal(value), Expression::Property(target, key)) => Some((target, key, value)),
+                _ => None,
+            };
+            if let Some((target, key, value)) = pair {
+                if matches!(target.as_ref(), Expression::Variable(name) if name == variable) {
+                    out.push((key.clone(), value.clone()));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 impl Default for QueryPlanner {
     fn default() -> Self {
         Self::new()
@@ -321,11 +835,100 @@ mod tests {
             variable: "n".to_string(),
             label: "Person".to_string(),
             property: "name".to_string(),
-            value: "Alice".to_string(),
+            value: PropertyValue::String("Alice".to_string()),
         };
         
         let cost = planner.estimate_cost(&plan);
         assert!(cost < 100.0); // Much cheaper than full scan
     }
-}
 
+    #[test]
+    fn test_backend_cost_constants() {
+        let memory_stats = PlannerStats {
+            node_count: 1000,
+            ..Default::default()
+        };
+        let mut disk_stats = memory_stats.clone();
+        disk_stats.cost = CostConstants::disk();
+        
+        let scan = LogicalPlan::NodeScan {
+            variable: "n".to_string(),
+            labels: vec![],
+        };
+        let lookup = LogicalPlan::IndexLookup {
+            variable: "n".to_string(),
+            label: "Person".to_string(),
+            property: "name".to_string(),
+            value: PropertyValue::String("Alice".to_string()),
+        };
+        
+        let memory = QueryPlanner::with_stats(memory_stats);
+        let disk = QueryPlanner::with_stats(disk_stats);
+        assert!(disk.estimate_cost(&scan) > memory.estimate_cost(&scan));
+        assert!(disk.estimate_cost(&lookup) > memory.estimate_cost(&lookup));
+        assert!(disk.estimate_cost(&lookup) < disk.estimate_cost(&scan));
+    }
+
+    #[test]
+    fn test_label_scan_uses_label_counts() {
+        let mut graph_stats = GraphStats::default();
+        graph_stats.node_count = 1000;
+        graph_stats.label_counts.insert("Person".to_string(), 10);
+        let planner = QueryPlanner::with_stats(PlannerStats::default().with_graph_stats(&graph_stats));
+        
+        let scan = |label: &str| LogicalPlan::NodeScan {
+            variable: "n".to_string(),
+            labels: vec![label.to_string()],
+        };
+        assert_eq!(planner.estimate_cost(&scan("Person")), 10.0);
+        assert_eq!(planner.estimate_cost(&scan("Unknown")), 1000.0);
+    }
+
+    #[test]
+    fn test_label_scoped_index_choice() {
+        let mut stats = PlannerStats::default();
+        let scoped = |label: Option<&str>| IndexStats {
+            index_type: "hash".to_string(),
+            entry_count: 10,
+            property_key: Some("name".to_string()),
+            label: label.map(str::to_string),
+        };
+        stats.indices.insert("person_name".to_string(), scoped(Some("Person")));
+        
+        let person = vec!["Person".to_string()];
+        let company = vec!["Company".to_string()];
+        assert_eq!(stats.index_on(&person, "name"), Some("person_name"));
+        assert_eq!(stats.index_on(&company, "name"), None);
+        
+        stats.indices.insert("any_name".to_string(), scoped(None));
+        assert_eq!(stats.index_on(&person, "name"), Some("person_name"));
+        assert_eq!(stats.index_on(&company, "name"), Some("any_name"));
+    }
+
+    #[test]
+    fn test_bounded_indexes_are_not_planned() {
+        use crate::index::IndexConfig;
+
+        let indexes = IndexManager::new();
+        indexes
+            .create_index(IndexConfig::property_index("by_name".to_string(), IndexType::Hash, "name".to_string()))
+            .unwrap();
+        indexes
+            .create_index(
+                IndexConfig::property_index("by_email".to_string(), IndexType::Hash, "email".to_string())
+                    .with_max_entries(100),
+            )
+            .unwrap();
+        indexes
+            .create_index(
+                IndexConfig::property_index("by_city".to_string(), IndexType::Hash, "city".to_string())
+                    .with_entry_ttl(std::time::Duration::from_secs(60)),
+            )
+            .unwrap();
+
+        let stats = PlannerStats::default().with_indexes(&indexes);
+        assert_eq!(stats.index_on(&[], "name"), Some("by_name"));
+        assert_eq!(stats.index_on(&[], "email"), None);
+        assert_eq!(stats.index_on(&[], "city"), None);
+    }
+}
@@ -0,0 +1,289 @@
+//! Built-in procedures invoked with `CALL`
+//!
+//! Procedures answer summary questions from the storage statistics instead
+//! of scanning the graph:
+//!
+//! - `CALL db.schema.relCounts()` - edge count per relationship type
+//! - `CALL db.degreeDistribution(label)` - histogram of node degrees,
+//!   optionally restricted to one label
+//! - `CALL vector.search(index, query, k)` - the `k` nodes nearest to a
+//!   query vector in a vector index (needs an [`IndexManager`])
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::PropertyValue;
+use crate::index::vector::vector_from_property;
+use crate::index::IndexManager;
+use crate::query::executor::QueryResult;
+use crate::storage::StorageBackend;
+use log::debug;
+use std::collections::{BTreeMap, HashMap};
+
+/// Run a procedure by its fully qualified name
+pub fn call<S: StorageBackend + ?Sized>(
+    storage: &S,
+    name: &str,
+    args: &[PropertyValue],
+) -> Result<QueryResult> {
+    call_with_indexes(storage, None, name, args)
+}
+
+/// Run a procedure, giving index procedures access to an index manager
+pub fn call_with_indexes<S: StorageBackend + ?Sized>(
+    storage: &S,
+    indexes: Option<&IndexManager>,
+    name: &str,
+    args: &[PropertyValue],
+) -> Result<QueryResult> {
+    match name {
+        "vector.search" => {
+            let indexes = indexes.ok_or_else(|| {
+                DeepGraphError::InvalidOperation(format!("{} needs an index manager", name))
+            })?;
+            match args {
+                [PropertyValue::String(index), query, PropertyValue::Integer(k)] if *k >= 0 => {
+                    let query = vector_from_property(query).ok_or_else(|| {
+                        DeepGraphError::InvalidOperation(format!("{} expects a list of numbers as the query", name))
+                    })?;
+                    vector_search(indexes, index, &query, *k as usize)
+                }
+                _ => Err(DeepGraphError::InvalidOperation(format!(
+                    "{} expects (index name, query vector, k), got {:?}",
+                    name, args
+                ))),
+            }
+        }
+        "db.schema.relCounts" => {
+            expect_args(name, args, 0)?;
+            rel_counts(storage)
+        }
+        "db.degreeDistribution" => {
+            let label = match args {
+                [] | [PropertyValue::Null] => None,
+                [PropertyValue::String(label)] => Some(label.as_str()),
+                _ => {
+                    return Err(DeepGraphError::InvalidOperation(format!(
+                        "{} expects an optional label string, got {:?}",
+                        name, args
+                    )))
+                }
+            };
+            degree_distribution(storage, label)
+        }
+        _ => Err(DeepGraphError::InvalidOperation(format!(
+            "Unknown procedure: {}",
+            name
+        ))),
+    }
+}
+
+/// Edge count per relationship type, one row per type
+pub fn rel_counts<S: StorageBackend + ?Sized>(storage: &S) -> Result<QueryResult> {
+    let counts: BTreeMap<String, u64> = match storage.statistics() {
+        Some(stats) => stats.rel_type_counts().into_iter().collect(),
+        None => {
+            debug!("Backend keeps no statistics, counting relationships by scan");
+            let mut counts = BTreeMap::new();
+            for edge in storage.iter_edges() {
+                *counts.entry(edge.relationship_type().to_string()).or_insert(0) += 1;
+            }
+            counts
+        }
+    };
+
+    let rows = counts
+        .into_iter()
+        .map(|(rel_type, count)| {
+            let mut row = HashMap::new();
+            row.insert("relationshipType".to_string(), PropertyValue::String(rel_type));
+            row.insert("count".to_string(), PropertyValue::Integer(count as i64));
+            row
+        })
+        .collect();
+
+    Ok(QueryResult::with_data(
+        vec!["relationshipType".to_string(), "count".to_string()],
+        rows,
+    ))
+}
+
+/// Histogram of node degrees (in + out), ordered by degree
+///
+/// Without a label the histogram comes straight from the statistics; with
+/// one, the label's nodes are looked up and their degrees read from the
+/// statistics.
+pub fn degree_distribution<S: StorageBackend + ?Sized>(
+    storage: &S,
+    label: Option<&str>,
+) -> Result<QueryResult> {
+    let histogram: BTreeMap<usize, u64> = match (storage.statistics(), label) {
+        (Some(stats), None) => stats.degree_counts().into_iter().collect(),
+        (stats, label) => {
+            let nodes = match label {
+                Some(label) => storage.get_nodes_by_label(label),
+                None => {
+                    debug!("Backend keeps no statistics, computing degrees by scan");
+                    storage.get_all_nodes()
+                }
+            };
+            let mut histogram = BTreeMap::new();
+            for node in &nodes {
+                let degree = match stats.and_then(|stats| stats.degree(node.id())) {
+                    Some(degree) => degree,
+                    None => storage.degree(node.id())?,
+                };
+                *histogram.entry(degree).or_insert(0) += 1;
+            }
+            histogram
+        }
+    };
+
+    let rows = histogram
+        .into_iter()
+        .map(|(degree, count)| {
+            let mut row = HashMap::new();
+            row.insert("degree".to_string(), PropertyValue::Integer(degree as i64));
+            row.insert("count".to_string(), PropertyValue::Integer(count as i64));
+            row
+        })
+        .collect();
+
+    Ok(QueryResult::with_data(
+        vec!["degree".to_string(), "count".to_string()],
+        rows,
+    ))
+}
+
+/// Nearest nodes to a query vector, one row per node, closest first
+pub fn vector_search(indexes: &IndexManager, index: &str, query: &[f32], k: usize) -> Result<QueryResult> {
+    let rows = indexes
+        .vector_search(index, query, k)?
+        .into_iter()
+        .map(|found| {
+            let mut row = HashMap::new();
+            row.insert("nodeId".to_string(), PropertyValue::String(found.node_id.to_string()));
+            row.insert("distance".to_string(), PropertyValue::Float(found.distance as f64));
+            row
+        })
+        .collect();
+
+    Ok(QueryResult::with_data(
+        vec!["nodeId".to_string(), "distance".to_string()],
+        rows,
+    ))
+}
+
+fn expect_args(name: &str, args: &[PropertyValue], count: usize) -> Result<()> {
+    if args.len() != count {
+        return Err(DeepGraphError::InvalidOperation(format!(
+            "{} expects {} argument(s), got {}",
+            name,
+            count,
+            args.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node};
+    use crate::query::{CypherParser, QueryExecutor, QueryPlanner};
+    use crate::query::ast::Statement;
+    use crate::storage::MemoryStorage;
+    use std::sync::Arc;
+
+    fn sample_graph() -> Arc<MemoryStorage> {
+        let storage = Arc::new(MemoryStorage::new());
+        let alice = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let bob = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let acme = storage.add_node(Node::new(vec!["Company".to_string()])).unwrap();
+        storage.add_edge(Edge::new(alice, bob, "KNOWS".to_string())).unwrap();
+        storage.add_edge(Edge::new(alice, acme, "WORKS_AT".to_string())).unwrap();
+        storage.add_edge(Edge::new(bob, acme, "WORKS_AT".to_string())).unwrap();
+        storage
+    }
+
+    fn run(storage: Arc<MemoryStorage>, query: &str) -> QueryResult {
+        let Statement::Query(query) = CypherParser::parse(query).unwrap();
+        let planner = QueryPlanner::new();
+        let plan = planner.physical_plan(&planner.logical_plan(&query).unwrap()).unwrap();
+        QueryExecutor::new(storage).execute(&plan).unwrap()
+    }
+
+    fn int(row: &HashMap<String, PropertyValue>, key: &str) -> i64 {
+        row[key].as_integer().unwrap()
+    }
+
+    #[test]
+    fn test_rel_counts() {
+        let result = run(sample_graph(), "CALL db.schema.relCounts()");
+        assert_eq!(result.row_count, 2);
+        assert_eq!(result.rows[0]["relationshipType"], PropertyValue::String("KNOWS".to_string()));
+        assert_eq!(int(&result.rows[0], "count"), 1);
+        assert_eq!(int(&result.rows[1], "count"), 2);
+    }
+
+    #[test]
+    fn test_degree_distribution_by_label() {
+        let storage = sample_graph();
+
+        // Both people have degree 2 (one KNOWS + one WORKS_AT)
+        let people = run(storage.clone(), "CALL db.degreeDistribution('Person')");
+        assert_eq!(people.row_count, 1);
+        assert_eq!(int(&people.rows[0], "degree"), 2);
+        assert_eq!(int(&people.rows[0], "count"), 2);
+
+        // Unfiltered, the histogram is read from the statistics
+        let histogram = |storage: &Arc<MemoryStorage>| -> Vec<(i64, i64)> {
+            run(storage.clone(), "CALL db.degreeDistribution()")
+                .rows
+                .iter()
+                .map(|row| (int(row, "degree"), int(row, "count")))
+                .collect()
+        };
+        assert_eq!(histogram(&storage), vec![(2, 3)]);
+        let bob = storage.get_nodes_by_label("Person")[0].id();
+        storage.delete_node(bob).unwrap();
+        assert_eq!(histogram(&storage), vec![(1, 2)]);
+
+        assert!(call(storage.as_ref(), "db.unknown", &[]).is_err());
+    }
+
+    #[test]
+    fn test_vector_search_procedure() {
+        use crate::index::DistanceMetric;
+
+        let storage = Arc::new(MemoryStorage::new());
+        let indexes = Arc::new(IndexManager::new());
+        indexes.create_vector_index("embeddings", "embedding", 2, DistanceMetric::Euclidean).unwrap();
+        let embedded = |x: f64, y: f64| {
+            let mut node = Node::new(vec!["Doc".to_string()]);
+            node.set_property(
+                "embedding".to_string(),
+                PropertyValue::List(vec![PropertyValue::Float(x), PropertyValue::Float(y)]),
+            );
+            node
+        };
+        let near = indexes.add_node(storage.as_ref(), embedded(1.0, 1.0)).unwrap();
+        indexes.add_node(storage.as_ref(), embedded(5.0, 5.0)).unwrap();
+
+        let Statement::Query(query) = CypherParser::parse("CALL vector.search('embeddings', $q, 1)").unwrap();
+        let planner = QueryPlanner::new();
+        let plan = planner.physical_plan(&planner.logical_plan(&query).unwrap()).unwrap();
+        let mut parameters = HashMap::new();
+        parameters.insert(
+            "q".to_string(),
+            PropertyValue::List(vec![PropertyValue::Float(0.9), PropertyValue::Integer(1)]),
+        );
+        let executor = QueryExecutor::new(storage.clone()).with_parameters(parameters);
+        assert!(executor.execute(&plan).is_err());
+
+        let result = executor.with_index_manager(indexes.clone()).execute(&plan).unwrap();
+        assert_eq!(result.row_count, 1);
+        assert_eq!(result.rows[0]["nodeId"], PropertyValue::String(near.to_string()));
+
+        indexes.delete_node(storage.as_ref(), near).unwrap();
+        assert_ne!(indexes.vector_search("embeddings", &[0.9, 1.0], 1).unwrap()[0].node_id, near);
+    }
+}
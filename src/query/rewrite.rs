@@ -0,0 +1,356 @@
+//! Pre-planning rewrites
+//!
+//! Folds constant sub-expressions and collects the `$name` parameters that
+//! remain, so the planner sees the simplest form of each predicate.
+//!
+//! [`prepare`] runs the pass on every query before it is planned, and
+//! rejects queries referencing a `$name` the caller left unbound.
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::PropertyValue;
+use crate::query::ast::*;
+use std::collections::{BTreeSet, HashMap};
+
+/// A statement after rewriting, ready for planning
+#[derive(Debug, Clone)]
+pub struct NormalizedQuery {
+    /// The rewritten statement
+    pub statement: Statement,
+    /// `$name` parameters of the original statement, which the caller binds
+    pub required_parameters: BTreeSet<String>,
+}
+
+/// Fold constants and collect the parameters the statement references
+pub fn normalize(statement: &Statement) -> NormalizedQuery {
+    let mut rewriter = Rewriter {
+        required: BTreeSet::new(),
+    };
+    let statement = rewriter.statement(statement);
+
+    NormalizedQuery {
+        statement,
+        required_parameters: rewriter.required,
+    }
+}
+
+/// Normalize a parsed statement for planning
+///
+/// Fails with "Missing parameter" when the statement references a `$name`
+/// that `parameters` does not bind, rather than letting the predicate
+/// silently match nothing.
+pub fn prepare(statement: &Statement, parameters: &HashMap<String, PropertyValue>) -> Result<Statement> {
+    let normalized = normalize(statement);
+    match normalized.required_parameters.iter().find(|name| !parameters.contains_key(*name)) {
+        Some(name) => Err(DeepGraphError::InvalidOperation(format!("Missing parameter: ${}", name))),
+        None => Ok(normalized.statement),
+    }
+}
+
+/// Fold constant sub-expressions of an expression
+///
+/// Operations that would fail at runtime (division by zero, overflow,
+/// incompatible types) are left unfolded so the error surfaces as before.
+pub fn fold_constants(expr: &Expression) -> Expression {
+    use Expression::*;
+
+    match expr {
+        Add(l, r) | Sub(l, r) | Mul(l, r) | Div(l, r) | Mod(l, r) => {
+            let (l, r) = (fold_constants(l), fold_constants(r));
+            if let (Literal(a), Literal(b)) = (&l, &r) {
+                if let Some(value) = fold_arithmetic(expr, a, b) {
+                    return Literal(value);
+                }
+            }
+            rebuild_binary(expr, l, r)
+        }
+        Eq(l, r) | Ne(l, r) | Lt(l, r) | Le(l, r) | Gt(l, r) | Ge(l, r) => {
+            let (l, r) = (fold_constants(l), fold_constants(r));
+            if let (Literal(a), Literal(b)) = (&l, &r) {
+                match expr {
+                    Eq(..) => return Literal(PropertyValue::Boolean(a == b)),
+                    Ne(..) => return Literal(PropertyValue::Boolean(a != b)),
+                    _ => {}
+                }
+            }
+            rebuild_binary(expr, l, r)
+        }
+        And(l, r) => match (fold_constants(l), fold_constants(r)) {
+            (Literal(PropertyValue::Boolean(false)), _) | (_, Literal(PropertyValue::Boolean(false))) => {
+                Literal(PropertyValue::Boolean(false))
+            }
+            (Literal(PropertyValue::Boolean(true)), other) | (other, Literal(PropertyValue::Boolean(true))) => other,
+            (l, r) => And(Box::new(l), Box::new(r)),
+        },
+        Or(l, r) => match (fold_constants(l), fold_constants(r)) {
+            (Literal(PropertyValue::Boolean(true)), _) | (_, Literal(PropertyValue::Boolean(true))) => {
+                Literal(PropertyValue::Boolean(true))
+            }
+            (Literal(PropertyValue::Boolean(false)), other) | (other, Literal(PropertyValue::Boolean(false))) => other,
+            (l, r) => Or(Box::new(l), Box::new(r)),
+        },
+        Not(inner) => match fold_constants(inner) {
+            Literal(PropertyValue::Boolean(b)) => Literal(PropertyValue::Boolean(!b)),
+            other => Not(Box::new(other)),
+        },
+        Neg(inner) => match fold_constants(inner) {
+            Literal(PropertyValue::Integer(i)) if i != i64::MIN => Literal(PropertyValue::Integer(-i)),
+            Literal(PropertyValue::Float(f)) => Literal(PropertyValue::Float(-f)),
+            other => Neg(Box::new(other)),
+        },
+        Property(base, key) => Property(Box::new(fold_constants(base)), key.clone()),
+        FunctionCall { name, args, distinct } => FunctionCall {
+            name: name.clone(),
+            args: args.iter().map(fold_constants).collect(),
+            distinct: *distinct,
+        },
+        Literal(_) | Variable(_) | Parameter(_) => expr.clone(),
+    }
+}
+
+/// Rebuild a binary expression of the same kind with new operands
+fn rebuild_binary(expr: &Expression, l: Expression, r: Expression) -> Expression {
+    use Expression::*;
+
+    let (l, r) = (Box::new(l), Box::new(r));
+    match expr {
+        And(..) => And(l, r),
+        Or(..) => Or(l, r),
+        Add(..) => Add(l, r),
+        Sub(..) => Sub(l, r),
+        Mul(..) => Mul(l, r),
+        Div(..) => Div(l, r),
+        Mod(..) => Mod(l, r),
+        Eq(..) => Eq(l, r),
+        Ne(..) => Ne(l, r),
+        Lt(..) => Lt(l, r),
+        Le(..) => Le(l, r),
+        Gt(..) => Gt(l, r),
+        Ge(..) => Ge(l, r),
+        _ => unreachable!("rebuild_binary called on non-binary expression"),
+    }
+}
+
+/// Evaluate an arithmetic operation on two literals, if it is safe to do so
+fn fold_arithmetic(op: &Expression, a: &PropertyValue, b: &PropertyValue) -> Option<PropertyValue> {
+    use Expression::*;
+    use PropertyValue::{Float, Integer};
+
+    match (a, b) {
+        (Integer(x), Integer(y)) => match op {
+            Add(..) => x.checked_add(*y).map(Integer),
+            Sub(..) => x.checked_sub(*y).map(Integer),
+            Mul(..) => x.checked_mul(*y).map(Integer),
+            Div(..) => x.checked_div(*y).map(Integer),
+            Mod(..) => x.checked_rem(*y).map(Integer),
+            _ => None,
+        },
+        (Integer(_), Float(_)) | (Float(_), Integer(_)) | (Float(_), Float(_)) => {
+            let x = a.as_float().or_else(|| a.as_integer().map(|i| i as f64))?;
+            let y = b.as_float().or_else(|| b.as_integer().map(|i| i as f64))?;
+            match op {
+                Add(..) => Some(Float(x + y)),
+                Sub(..) => Some(Float(x - y)),
+                Mul(..) => Some(Float(x * y)),
+                Div(..) if y != 0.0 => Some(Float(x / y)),
+                _ => None,
+            }
+        }
+        (PropertyValue::String(x), PropertyValue::String(y)) => match op {
+            Add(..) => Some(PropertyValue::String(format!("{}{}", x, y))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+struct Rewriter {
+    required: BTreeSet<String>,
+}
+
+impl Rewriter {
+    fn statement(&mut self, statement: &Statement) -> Statement {
+        let Statement::Query(query) = statement;
+        Statement::Query(self.query(query))
+    }
+
+    fn query(&mut self, query: &Query) -> Query {
+        match query {
+            Query::Read(read) => Query::Read(ReadQuery {
+                match_clause: MatchClause {
+                    patterns: read.match_clause.patterns.iter().map(|p| self.pattern(p)).collect(),
+                    hints: read.match_clause.hints.clone(),
+                },
+                where_clause: read.where_clause.as_ref().map(|w| WhereClause {
+                    condition: self.fold(&w.condition),
+                }),
+                // RETURN items name result columns, so they are folded but
+                // never parameterized
+                return_clause: ReturnClause {
+                    items: read
+                        .return_clause
+                        .items
+                        .iter()
+                        .map(|item| ReturnItem {
+                            expression: self.fold(&item.expression),
+                            alias: item.alias.clone(),
+                        })
+                        .collect(),
+                    ..read.return_clause.clone()
+                },
+            }),
+            Query::Write(write) => Query::Write(match write {
+                WriteQuery::Create(c) => WriteQuery::Create(CreateClause {
+                    patterns: c.patterns.iter().map(|p| self.pattern(p)).collect(),
+                }),
+                WriteQuery::Delete(d) => WriteQuery::Delete(DeleteClause {
+                    expressions: d.expressions.iter().map(|e| self.fold(e)).collect(),
+                }),
+                WriteQuery::Set(s) => WriteQuery::Set(SetClause {
+                    items: s
+                        .items
+                        .iter()
+                        .map(|item| SetItem {
+                            variable: item.variable.clone(),
+                            property: item.property.clone(),
+                            value: self.fold(&item.value),
+                        })
+                        .collect(),
+                }),
+                WriteQuery::Merge(m) => WriteQuery::Merge(MergeClause {
+                    pattern: self.pattern(&m.pattern),
+                }),
+            }),
+            Query::Call(call) => Query::Call(CallClause {
+                procedure: call.procedure.clone(),
+                args: call.args.iter().map(|arg| self.fold(arg)).collect(),
+            }),
+            Query::Use(use_query) => Query::Use(UseQuery {
+                graph: use_query.graph.clone(),
+                query: use_query.query.as_ref().map(|query| Box::new(self.query(query))),
+            }),
+            Query::Federated(federated) => Query::Federated(FederatedQuery {
+                parts: federated
+                    .parts
+                    .iter()
+                    .map(|part| GraphMatch {
+                        graph: part.graph.clone(),
+                        match_clause: MatchClause {
+                            patterns: part.match_clause.patterns.iter().map(|p| self.pattern(p)).collect(),
+                            hints: part.match_clause.hints.clone(),
+                        },
+                    })
+                    .collect(),
+                where_clause: federated.where_clause.as_ref().map(|w| WhereClause {
+                    condition: self.fold(&w.condition),
+                }),
+                return_clause: ReturnClause {
+                    items: federated
+                        .return_clause
+                        .items
+                        .iter()
+                        .map(|item| ReturnItem {
+                            expression: self.fold(&item.expression),
+                            alias: item.alias.clone(),
+                        })
+                        .collect(),
+                    ..federated.return_clause.clone()
+                },
+            }),
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) -> Pattern {
+        Pattern {
+            elements: pattern
+                .elements
+                .iter()
+                .map(|element| match element {
+                    PatternElement::Node(node) => PatternElement::Node(NodePattern {
+                        properties: self.properties(&node.properties),
+                        ..node.clone()
+                    }),
+                    PatternElement::Relationship(rel) => PatternElement::Relationship(RelationshipPattern {
+                        properties: self.properties(&rel.properties),
+                        ..rel.clone()
+                    }),
+                })
+                .collect(),
+        }
+    }
+
+    fn properties(&mut self, properties: &HashMap<String, Expression>) -> HashMap<String, Expression> {
+        properties
+            .iter()
+            .map(|(key, value)| (key.clone(), self.fold(value)))
+            .collect()
+    }
+
+    /// Fold constants, noting the parameters that remain
+    fn fold(&mut self, expr: &Expression) -> Expression {
+        let folded = fold_constants(expr);
+        self.note_parameters(&folded);
+        folded
+    }
+
+    fn note_parameters(&mut self, expr: &Expression) {
+        use Expression::*;
+
+        match expr {
+            Parameter(name) => {
+                self.required.insert(name.clone());
+            }
+            And(l, r) | Or(l, r) | Eq(l, r) | Ne(l, r) | Lt(l, r) | Le(l, r) | Gt(l, r) | Ge(l, r)
+            | Add(l, r) | Sub(l, r) | Mul(l, r) | Div(l, r) | Mod(l, r) => {
+                self.note_parameters(l);
+                self.note_parameters(r);
+            }
+            Property(inner, _) | Not(inner) | Neg(inner) => self.note_parameters(inner),
+            FunctionCall { args, .. } => args.iter().for_each(|arg| self.note_parameters(arg)),
+            Literal(_) | Variable(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::CypherParser;
+
+    #[test]
+    fn test_fold_arithmetic_and_logic() {
+        let expr = Expression::Add(
+            Box::new(Expression::Literal(PropertyValue::Integer(20))),
+            Box::new(Expression::Mul(
+                Box::new(Expression::Literal(PropertyValue::Integer(2))),
+                Box::new(Expression::Literal(PropertyValue::Integer(5))),
+            )),
+        );
+        assert_eq!(fold_constants(&expr), Expression::Literal(PropertyValue::Integer(30)));
+
+        let gt = Expression::Gt(
+            Box::new(Expression::property(Expression::variable("n"), "age")),
+            Box::new(Expression::Literal(PropertyValue::Integer(1))),
+        );
+        let and = Expression::And(Box::new(Expression::Literal(PropertyValue::Boolean(true))), Box::new(gt.clone()));
+        assert_eq!(fold_constants(&and), gt);
+
+        // Division by zero is left for the executor to report
+        let div = Expression::Div(
+            Box::new(Expression::Literal(PropertyValue::Integer(1))),
+            Box::new(Expression::Literal(PropertyValue::Integer(0))),
+        );
+        assert_eq!(fold_constants(&div), div);
+    }
+
+    #[test]
+    fn test_prepare_requires_bound_parameters() {
+        let statement = CypherParser::parse("MATCH (n:Person) WHERE n.age > $min_age RETURN n").unwrap();
+
+        let err = prepare(&statement, &HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("Missing parameter: $min_age"));
+
+        let mut parameters = HashMap::new();
+        parameters.insert("min_age".to_string(), PropertyValue::Integer(30));
+        assert!(prepare(&statement, &parameters).is_ok());
+    }
+}
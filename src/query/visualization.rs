@@ -0,0 +1,190 @@
+//! Subgraph JSON for graph visualization libraries
+//!
+//! Turns the nodes returned by a query into a [`GraphView`]: those nodes plus
+//! the edges between them. A view renders as D3 force-layout JSON
+//! (`{"nodes": [...], "links": [...]}`) or Cytoscape.js elements
+//! (`{"elements": {"nodes": [{"data": ...}], "edges": [{"data": ...}]}}`).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let result = executor.execute(&plan)?;
+//! let view = GraphView::from_result(storage.as_ref(), &result)?;
+//! let json = view.to_cytoscape_json();
+//! ```
+
+use crate::error::Result;
+use crate::graph::{Edge, Node, NodeId, PropertyValue};
+use crate::query::executor::QueryResult;
+use crate::storage::StorageBackend;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Nodes and edges of a query result, ready to render
+#[derive(Debug, Clone, Default)]
+pub struct GraphView {
+    /// Nodes in result order
+    pub nodes: Vec<Node>,
+    /// Edges whose endpoints are both in `nodes`
+    pub edges: Vec<Edge>,
+}
+
+impl GraphView {
+    /// Build the subgraph induced by the nodes of a query result
+    ///
+    /// Nodes are identified by the `_node_id` column that node scans emit;
+    /// rows without it (aggregates, procedure output) contribute nothing.
+    pub fn from_result<S: StorageBackend + ?Sized>(storage: &S, result: &QueryResult) -> Result<Self> {
+        Self::from_rows(storage, &result.rows)
+    }
+
+    /// Build the subgraph induced by the `_node_id` values of result rows
+    pub fn from_rows<S: StorageBackend + ?Sized>(
+        storage: &S,
+        rows: &[HashMap<String, PropertyValue>],
+    ) -> Result<Self> {
+        let mut seen = HashSet::new();
+        let ids: Vec<NodeId> = rows
+            .iter()
+            .filter_map(|row| match row.get("_node_id") {
+                Some(PropertyValue::String(id)) => Uuid::parse_str(id).ok().map(NodeId::from_uuid),
+                _ => None,
+            })
+            .filter(|id| seen.insert(*id))
+            .collect();
+        Self::from_nodes(storage, &ids)
+    }
+
+    /// Build the subgraph induced by a set of nodes
+    ///
+    /// IDs of nodes that no longer exist are skipped.
+    pub fn from_nodes<S: StorageBackend + ?Sized>(storage: &S, ids: &[NodeId]) -> Result<Self> {
+        let mut view = Self::default();
+        for id in ids {
+            match storage.get_node(*id) {
+                Ok(node) => view.nodes.push(node),
+                Err(e) if e.is_not_found() => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let members: HashSet<NodeId> = view.nodes.iter().map(|n| n.id()).collect();
+        for node in &view.nodes {
+            for edge in storage.get_outgoing_edges(node.id())? {
+                if members.contains(&edge.to()) {
+                    view.edges.push(edge);
+                }
+            }
+        }
+        Ok(view)
+    }
+
+    /// D3 force-layout JSON: `{"nodes": [...], "links": [...]}`
+    ///
+    /// Properties are flattened into each object next to `id`, `labels`
+    /// and `type`; a property with one of those names is dropped.
+    pub fn to_d3_json(&self) -> Value {
+        let nodes: Vec<Value> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let mut obj = properties_json(node.properties().iter());
+                obj.insert("id".to_string(), json!(node.id().to_string()));
+                obj.insert("labels".to_string(), json!(node.labels()));
+                Value::Object(obj)
+            })
+            .collect();
+        let links: Vec<Value> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                let mut obj = properties_json(edge.properties().iter());
+                obj.insert("id".to_string(), json!(edge.id().to_string()));
+                obj.insert("source".to_string(), json!(edge.from().to_string()));
+                obj.insert("target".to_string(), json!(edge.to().to_string()));
+                obj.insert("type".to_string(), json!(edge.relationship_type()));
+                Value::Object(obj)
+            })
+            .collect();
+        json!({ "nodes": nodes, "links": links })
+    }
+
+    /// Cytoscape.js elements JSON
+    ///
+    /// `data.label` holds the first node label or the relationship type, so
+    /// stylesheets can use `label: data(label)` directly.
+    pub fn to_cytoscape_json(&self) -> Value {
+        let nodes: Vec<Value> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                json!({
+                    "data": {
+                        "id": node.id().to_string(),
+                        "label": node.labels().first(),
+                        "labels": node.labels(),
+                        "properties": properties_json(node.properties().iter()),
+                    }
+                })
+            })
+            .collect();
+        let edges: Vec<Value> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                json!({
+                    "data": {
+                        "id": edge.id().to_string(),
+                        "source": edge.from().to_string(),
+                        "target": edge.to().to_string(),
+                        "label": edge.relationship_type(),
+                        "properties": properties_json(edge.properties().iter()),
+                    }
+                })
+            })
+            .collect();
+        json!({ "elements": { "nodes": nodes, "edges": edges } })
+    }
+}
+
+fn properties_json<'a>(properties: impl Iterator<Item = (&'a String, &'a PropertyValue)>) -> Map<String, Value> {
+    properties.map(|(key, value)| (key.clone(), value.to_json())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::executor::QueryExecutor;
+    use crate::query::planner::PhysicalPlan;
+    use crate::storage::MemoryStorage;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_view_from_result() {
+        let storage = Arc::new(MemoryStorage::new());
+        let mut alice = Node::new(vec!["Person".to_string()]);
+        alice.set_property("name".to_string(), PropertyValue::String("Alice".to_string()));
+        let alice = storage.add_node(alice).unwrap();
+        let bob = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let acme = storage.add_node(Node::new(vec!["Company".to_string()])).unwrap();
+        storage.add_edge(Edge::new(alice, bob, "KNOWS".to_string())).unwrap();
+        storage.add_edge(Edge::new(alice, acme, "WORKS_AT".to_string())).unwrap();
+
+        let plan = PhysicalPlan::Scan { label: Some("Person".to_string()) };
+        let result = QueryExecutor::new(storage.clone()).execute(&plan).unwrap();
+        let view = GraphView::from_result(storage.as_ref(), &result).unwrap();
+
+        // The company is outside the result, so WORKS_AT is left out
+        assert_eq!(view.nodes.len(), 2);
+        assert_eq!(view.edges.len(), 1);
+
+        let d3 = view.to_d3_json();
+        assert_eq!(d3["links"][0]["source"], json!(alice.to_string()));
+        assert_eq!(d3["links"][0]["type"], json!("KNOWS"));
+
+        let cy = view.to_cytoscape_json();
+        assert_eq!(cy["elements"]["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(cy["elements"]["edges"][0]["data"]["target"], json!(bob.to_string()));
+    }
+}
@@ -0,0 +1,328 @@
+//! Atomic batches of graph operations
+//!
+//! [`StorageBackend::apply_batch`] applies a list of [`GraphOp`]s as one
+//! unit without an interactive transaction. The batch is validated first:
+//! every node or edge an operation updates or deletes must exist, and
+//! every edge must connect nodes that exist, counting the effect of the
+//! operations before it. A batch that fails validation changes nothing.
+//! Operations are then applied in order; if the backend rejects one, for
+//! example because it breaks a schema constraint, the ones already applied
+//! are undone before the error is returned.
+//!
+//! That is all the default implementation promises: concurrent readers may
+//! observe a partly applied batch. [`MemoryStorage`](crate::storage::MemoryStorage)
+//! holds other operations off while it applies a batch and
+//! [`DiskStorage`](crate::storage::DiskStorage) runs it as a single sled
+//! transaction, so on those backends a batch is seen whole or not at all.
+//!
+//! [`DurableStorage`](crate::storage::DurableStorage) logs a batch as a
+//! single WAL record, so recovery replays all of it or none of it.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let alice = Node::new(vec!["Person".to_string()]);
+//! let bob = Node::new(vec!["Person".to_string()]);
+//! let knows = Edge::new(alice.id(), bob.id(), "KNOWS".to_string());
+//! storage.apply_batch(vec![
+//!     GraphOp::AddNode(alice),
+//!     GraphOp::AddNode(bob),
+//!     GraphOp::AddEdge(knows),
+//! ])?;
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::{Edge, EdgeId, Node, NodeId};
+use crate::storage::StorageBackend;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One operation of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GraphOp {
+    /// Add a node, replacing any node with the same ID
+    AddNode(Node),
+    /// Replace an existing node
+    UpdateNode(Node),
+    /// Delete a node and its edges
+    DeleteNode(NodeId),
+    /// Add an edge between existing nodes
+    AddEdge(Edge),
+    /// Replace an existing edge
+    UpdateEdge(Edge),
+    /// Delete an edge
+    DeleteEdge(EdgeId),
+}
+
+impl GraphOp {
+    /// Apply the operation to `storage` on its own
+    pub fn apply<S: StorageBackend + ?Sized>(self, storage: &S) -> Result<()> {
+        match self {
+            GraphOp::AddNode(node) => storage.add_node(node).map(|_| ()),
+            GraphOp::UpdateNode(node) => storage.update_node(node),
+            GraphOp::DeleteNode(id) => storage.delete_node(id),
+            GraphOp::AddEdge(edge) => storage.add_edge(edge).map(|_| ()),
+            GraphOp::UpdateEdge(edge) => storage.update_edge(edge),
+            GraphOp::DeleteEdge(id) => storage.delete_edge(id),
+        }
+    }
+}
+
+/// How to reverse one applied operation
+enum Undo {
+    /// Put back this node, or delete it if it did not exist
+    Node(NodeId, Option<Node>),
+    /// Put back this edge, or delete it if it did not exist
+    Edge(EdgeId, Option<Edge>),
+    /// Restore a deleted node together with the edges deleted with it
+    DeletedNode(Node, Vec<Edge>),
+}
+
+/// Whether the nodes and edges a batch touches exist, as of each operation
+struct Overlay<'a, S: StorageBackend + ?Sized> {
+    storage: &'a S,
+    nodes: HashMap<NodeId, bool>,
+    /// Endpoints of each touched edge; `None` if deleted
+    edges: HashMap<EdgeId, Option<(NodeId, NodeId)>>,
+}
+
+impl<'a, S: StorageBackend + ?Sized> Overlay<'a, S> {
+    fn node_exists(&self, id: NodeId) -> bool {
+        match self.nodes.get(&id) {
+            Some(exists) => *exists,
+            None => self.storage.get_node(id).is_ok(),
+        }
+    }
+
+    fn edge_exists(&self, id: EdgeId) -> bool {
+        match self.edges.get(&id) {
+            Some(endpoints) => endpoints.is_some(),
+            None => self.storage.get_edge(id).is_ok(),
+        }
+    }
+
+    fn require_node(&self, id: NodeId) -> Result<()> {
+        if self.node_exists(id) {
+            Ok(())
+        } else {
+            Err(DeepGraphError::NodeNotFound(id.to_string()))
+        }
+    }
+
+    fn require_edge(&self, id: EdgeId) -> Result<()> {
+        if self.edge_exists(id) {
+            Ok(())
+        } else {
+            Err(DeepGraphError::EdgeNotFound(id.to_string()))
+        }
+    }
+
+    fn check(&mut self, op: &GraphOp) -> Result<()> {
+        match op {
+            GraphOp::AddNode(node) => {
+                self.nodes.insert(node.id(), true);
+            }
+            GraphOp::UpdateNode(node) => self.require_node(node.id())?,
+            GraphOp::DeleteNode(id) => {
+                self.require_node(*id)?;
+                self.nodes.insert(*id, false);
+                // The backend removes the node's edges along with it; a node
+                // staged earlier in the batch has none stored yet
+                let stored = self
+                    .storage
+                    .get_outgoing_edges(*id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .chain(self.storage.get_incoming_edges(*id).unwrap_or_default())
+                    .map(|edge| edge.id());
+                let staged = self
+                    .edges
+                    .iter()
+                    .filter(|(_, endpoints)| endpoints.is_some_and(|(from, to)| from == *id || to == *id))
+                    .map(|(edge_id, _)| *edge_id);
+                let incident: Vec<EdgeId> = stored.chain(staged).collect();
+                for edge_id in incident {
+                    self.edges.insert(edge_id, None);
+                }
+            }
+            GraphOp::AddEdge(edge) => {
+                self.require_node(edge.from())?;
+                self.require_node(edge.to())?;
+                self.edges.insert(edge.id(), Some((edge.from(), edge.to())));
+            }
+            GraphOp::UpdateEdge(edge) => {
+                self.require_edge(edge.id())?;
+                self.require_node(edge.from())?;
+                self.require_node(edge.to())?;
+                self.edges.insert(edge.id(), Some((edge.from(), edge.to())));
+            }
+            GraphOp::DeleteEdge(id) => {
+                self.require_edge(*id)?;
+                self.edges.insert(*id, None);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check a batch's preconditions against `storage` without changing it
+///
+/// Fails with [`DeepGraphError::InvalidOperation`] naming the first
+/// operation whose node or edge would not exist when it runs.
+pub fn validate_batch<S: StorageBackend + ?Sized>(storage: &S, ops: &[GraphOp]) -> Result<()> {
+    let mut overlay = Overlay {
+        storage,
+        nodes: HashMap::new(),
+        edges: HashMap::new(),
+    };
+    for (index, op) in ops.iter().enumerate() {
+        overlay.check(op).map_err(|e| {
+            DeepGraphError::InvalidOperation(format!("Batch rejected at operation {}: {}", index, e))
+        })?;
+    }
+    Ok(())
+}
+
+/// Validate a batch, then apply it, undoing it if the backend rejects part
+pub fn apply_batch<S: StorageBackend + ?Sized>(storage: &S, ops: Vec<GraphOp>) -> Result<()> {
+    validate_batch(storage, &ops)?;
+    apply_validated(storage, ops)
+}
+
+/// Apply an already validated batch, undoing it if the backend rejects part
+pub(crate) fn apply_validated<S: StorageBackend + ?Sized>(storage: &S, ops: Vec<GraphOp>) -> Result<()> {
+    debug!("Applying batch of {} operations", ops.len());
+    let mut undo = Vec::with_capacity(ops.len());
+    for op in ops {
+        if let Err(e) = apply_one(storage, op, &mut undo) {
+            warn!("Batch failed, undoing {} operations: {}", undo.len(), e);
+            revert(storage, undo);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+fn apply_one<S: StorageBackend + ?Sized>(storage: &S, op: GraphOp, undo: &mut Vec<Undo>) -> Result<()> {
+    match op {
+        GraphOp::AddNode(node) => {
+            let id = node.id();
+            let before = storage.get_node(id).ok();
+            storage.add_node(node)?;
+            undo.push(Undo::Node(id, before));
+        }
+        GraphOp::UpdateNode(node) => {
+            let id = node.id();
+            let before = storage.get_node(id).ok();
+            storage.update_node(node)?;
+            undo.push(Undo::Node(id, before));
+        }
+        GraphOp::DeleteNode(id) => {
+            let node = storage.get_node(id)?;
+            let mut edges = storage.get_outgoing_edges(id)?;
+            edges.extend(
+                storage
+                    .get_incoming_edges(id)?
+                    .into_iter()
+                    .filter(|edge| edge.from() != id),
+            );
+            storage.delete_node(id)?;
+            undo.push(Undo::DeletedNode(node, edges));
+        }
+        GraphOp::AddEdge(edge) => {
+            let id = edge.id();
+            let before = storage.get_edge(id).ok();
+            storage.add_edge(edge)?;
+            undo.push(Undo::Edge(id, before));
+        }
+        GraphOp::UpdateEdge(edge) => {
+            let id = edge.id();
+            let before = storage.get_edge(id).ok();
+            storage.update_edge(edge)?;
+            undo.push(Undo::Edge(id, before));
+        }
+        GraphOp::DeleteEdge(id) => {
+            let before = storage.get_edge(id)?;
+            storage.delete_edge(id)?;
+            undo.push(Undo::Edge(id, Some(before)));
+        }
+    }
+    Ok(())
+}
+
+/// Reverse applied operations, newest first
+fn revert<S: StorageBackend + ?Sized>(storage: &S, undo: Vec<Undo>) {
+    for step in undo.into_iter().rev() {
+        let result = match step {
+            Undo::Node(_, Some(node)) => storage.add_node(node).map(|_| ()),
+            Undo::Node(id, None) => storage.delete_node(id),
+            Undo::Edge(_, Some(edge)) => match storage.get_edge(edge.id()) {
+                Ok(_) => storage.update_edge(edge),
+                Err(_) => storage.add_edge(edge).map(|_| ()),
+            },
+            Undo::Edge(id, None) => storage.delete_edge(id),
+            Undo::DeletedNode(node, edges) => storage.add_node(node).and_then(|_| {
+                edges
+                    .into_iter()
+                    .try_for_each(|edge| storage.add_edge(edge).map(|_| ()))
+            }),
+        };
+        if let Err(e) = result {
+            warn!("Failed to undo batch operation: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_batch_applies_in_order() {
+        let storage = MemoryStorage::new();
+        let a = Node::new(vec!["Person".to_string()]);
+        let b = Node::new(vec!["Person".to_string()]);
+        let knows = Edge::new(a.id(), b.id(), "KNOWS".to_string());
+        let (a_id, b_id, knows_id) = (a.id(), b.id(), knows.id());
+        apply_batch(
+            &storage,
+            vec![GraphOp::AddNode(a), GraphOp::AddNode(b), GraphOp::AddEdge(knows)],
+        )
+        .unwrap();
+        assert_eq!(storage.get_outgoing_edges(a_id).unwrap().len(), 1);
+
+        // The edge is gone once its node is deleted, so updating it fails
+        let result = apply_batch(
+            &storage,
+            vec![
+                GraphOp::DeleteNode(b_id),
+                GraphOp::UpdateEdge(storage.get_edge(knows_id).unwrap()),
+            ],
+        );
+        assert!(matches!(result, Err(DeepGraphError::InvalidOperation(_))));
+        assert!(storage.get_node(b_id).is_ok());
+        assert_eq!(storage.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_rejected_batch_is_undone() {
+        let storage = MemoryStorage::new();
+        let existing = storage.add_node(Node::new(vec![])).unwrap();
+        let mut renamed = storage.get_node(existing).unwrap();
+        renamed.set_property("name".to_string(), "Alice".into());
+
+        // Skipping validation, the backend rejects updating a missing node
+        // only after the first two operations were applied
+        let ops = vec![
+            GraphOp::UpdateNode(renamed),
+            GraphOp::AddNode(Node::new(vec![])),
+            GraphOp::UpdateNode(Node::new(vec![])),
+        ];
+        assert!(validate_batch(&storage, &ops).is_err());
+        assert!(apply_validated(&storage, ops).is_err());
+        assert_eq!(storage.node_count(), 1);
+        assert!(storage.get_node(existing).unwrap().get_property("name").is_none());
+    }
+}
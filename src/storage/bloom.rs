@@ -0,0 +1,212 @@
+//! Bloom filters for negative lookups
+//!
+//! A [`BloomFilter`] answers "definitely absent" or "possibly present" for
+//! byte keys with a handful of bit probes. [`DiskStorage`](crate::storage::DiskStorage)
+//! keeps one over node IDs and one over labels, so lookups of missing nodes
+//! and labels return without reading sled.
+//!
+//! Filters only grow: a removed key stays "possibly present", which costs a
+//! read but never gives a wrong answer. Bits are atomics, so inserts and
+//! probes need no lock.
+
+use crate::error::{DeepGraphError, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Sizing of a bloom filter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BloomConfig {
+    /// Target probability that an absent key is reported present
+    pub false_positive_rate: f64,
+    /// Keys the filter is sized for; a fuller filter is rebuilt larger
+    pub expected_items: usize,
+}
+
+impl BloomConfig {
+    /// Filters with the given false-positive rate, sized for 100k keys
+    pub fn new(false_positive_rate: f64) -> Self {
+        Self {
+            false_positive_rate,
+            expected_items: 100_000,
+        }
+    }
+
+    /// Size filters for at least `expected_items` keys
+    pub fn with_expected_items(mut self, expected_items: usize) -> Self {
+        self.expected_items = expected_items;
+        self
+    }
+}
+
+impl Default for BloomConfig {
+    fn default() -> Self {
+        Self::new(0.01)
+    }
+}
+
+/// Serialized form of a [`BloomFilter`]
+#[derive(Serialize, Deserialize)]
+struct BloomSnapshot {
+    bits: Vec<u64>,
+    num_hashes: u32,
+    capacity: usize,
+    items: usize,
+}
+
+/// Fixed-size bloom filter over byte keys
+pub struct BloomFilter {
+    bits: Vec<AtomicU64>,
+    /// Number of bits (a multiple of 64)
+    num_bits: u64,
+    /// Probes per key
+    num_hashes: u32,
+    /// Keys the filter was sized for
+    capacity: usize,
+    /// Inserts that set at least one new bit (approximate distinct keys)
+    items: AtomicUsize,
+}
+
+impl BloomFilter {
+    /// Create a filter holding `capacity` keys at `false_positive_rate`
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1);
+        let rate = false_positive_rate.clamp(1e-9, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+        let wanted_bits = (-(capacity as f64) * rate.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let words = wanted_bits.div_ceil(64) as usize;
+        let num_bits = words as u64 * 64;
+        let num_hashes = (num_bits as f64 / capacity as f64 * ln2).round().clamp(1.0, 32.0) as u32;
+        Self {
+            bits: (0..words).map(|_| AtomicU64::new(0)).collect(),
+            num_bits,
+            num_hashes,
+            capacity,
+            items: AtomicUsize::new(0),
+        }
+    }
+
+    /// Bit positions of a key (double hashing)
+    fn probes(&self, key: &[u8]) -> impl Iterator<Item = u64> + '_ {
+        let h1 = fnv1a(key);
+        let h2 = splitmix64(h1) | 1;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+
+    /// Add a key
+    pub fn insert(&self, key: &[u8]) {
+        let mut fresh = false;
+        for bit in self.probes(key) {
+            let mask = 1u64 << (bit % 64);
+            let previous = self.bits[(bit / 64) as usize].fetch_or(mask, Ordering::Relaxed);
+            fresh |= previous & mask == 0;
+        }
+        if fresh {
+            self.items.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether the key may have been inserted; `false` means it never was
+    pub fn may_contain(&self, key: &[u8]) -> bool {
+        self.probes(key).all(|bit| {
+            self.bits[(bit / 64) as usize].load(Ordering::Relaxed) & (1u64 << (bit % 64)) != 0
+        })
+    }
+
+    /// Approximate number of distinct keys inserted
+    pub fn len(&self) -> usize {
+        self.items.load(Ordering::Relaxed)
+    }
+
+    /// Whether nothing was inserted
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Keys the filter was sized for
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Whether more keys than planned were inserted, raising the false-positive rate
+    pub fn is_full(&self) -> bool {
+        self.len() > self.capacity
+    }
+
+    /// Size of the bit array in bytes
+    pub fn size_bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// Serialize the filter
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let snapshot = BloomSnapshot {
+            bits: self.bits.iter().map(|word| word.load(Ordering::Relaxed)).collect(),
+            num_hashes: self.num_hashes,
+            capacity: self.capacity,
+            items: self.len(),
+        };
+        bincode::serialize(&snapshot)
+            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to serialize bloom filter: {}", e)))
+    }
+
+    /// Deserialize a filter written by [`BloomFilter::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let snapshot: BloomSnapshot = bincode::deserialize(bytes)
+            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to deserialize bloom filter: {}", e)))?;
+        if snapshot.bits.is_empty() || snapshot.num_hashes == 0 {
+            return Err(DeepGraphError::SerializationError("Empty bloom filter".to_string()));
+        }
+        Ok(Self {
+            num_bits: snapshot.bits.len() as u64 * 64,
+            bits: snapshot.bits.into_iter().map(AtomicU64::new).collect(),
+            num_hashes: snapshot.num_hashes,
+            capacity: snapshot.capacity,
+            items: AtomicUsize::new(snapshot.items),
+        })
+    }
+}
+
+/// 64-bit FNV-1a; stable across Rust releases, unlike `DefaultHasher`,
+/// so saved filters stay valid
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Finalizer of SplitMix64, deriving the second probe hash
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_false_negatives_and_bounded_false_positives() {
+        let filter = BloomFilter::new(1_000, 0.01);
+        for i in 0..1_000u32 {
+            filter.insert(&i.to_le_bytes());
+        }
+        assert!((0..1_000u32).all(|i| filter.may_contain(&i.to_le_bytes())));
+        assert!(!filter.is_full());
+
+        let false_positives = (1_000..11_000u32).filter(|i| filter.may_contain(&i.to_le_bytes())).count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let filter = BloomFilter::new(100, 0.01);
+        filter.insert(b"Person");
+        let loaded = BloomFilter::from_bytes(&filter.to_bytes().unwrap()).unwrap();
+        assert!(loaded.may_contain(b"Person"));
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.capacity(), 100);
+        assert!(BloomFilter::from_bytes(b"junk").is_err());
+    }
+}
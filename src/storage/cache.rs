@@ -0,0 +1,556 @@
+//! LRU read cache in front of a storage backend
+//!
+//! [`CachedStorage`] keeps recently read nodes and edges in memory, bounded by
+//! an approximate byte budget, so repeated point reads against a disk backend
+//! do not hit Sled every time. Writes go straight to the wrapped backend and
+//! invalidate the affected entries; scans are always served by the backend.
+//!
+//! A miss notes the key's generation before reading the backend and only
+//! caches the result if no write invalidated the key in the meantime, so a
+//! slow read racing a write cannot put the old value back into the cache.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let config = DeepGraphConfig::default();
+//! let storage = CachedStorage::from_config(DiskStorage::new(&config.storage.disk_path)?, &config.storage);
+//! let node = storage.get_node(id)?; // miss, read from disk
+//! let node = storage.get_node(id)?; // hit
+//! println!("hit ratio: {:.2}", storage.cache_stats().hit_ratio());
+//! ```
+
+use crate::config::StorageConfig;
+use crate::error::Result;
+use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
+use crate::storage::{CostConstants, GraphOp, GraphStatistics, StorageBackend};
+use log::debug;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Fixed per-entry overhead added to the serialized size of a cached value
+const ENTRY_OVERHEAD: usize = 64;
+
+/// Stripes key generations are spread over; keys sharing a stripe only
+/// cost each other a cache fill, never a stale read
+const GENERATION_STRIPES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CacheKey {
+    Node(NodeId),
+    Edge(EdgeId),
+}
+
+#[derive(Debug, Clone)]
+enum CachedValue {
+    Node(Node),
+    Edge(Edge),
+}
+
+struct CacheEntry {
+    value: CachedValue,
+    size: usize,
+    tick: u64,
+}
+
+/// Byte-bounded LRU map; recency is tracked by a monotonically increasing tick
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<CacheKey, CacheEntry>,
+    recency: BTreeMap<u64, CacheKey>,
+    bytes: usize,
+    next_tick: u64,
+    /// Invalidation counts per key stripe, allocated on first invalidation
+    generations: Vec<u64>,
+}
+
+impl Lru {
+    fn stripe(key: &CacheKey) -> usize {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key.hash(&mut hasher);
+        hasher.finish() as usize % GENERATION_STRIPES
+    }
+
+    /// Generation of `key`, bumped whenever it is invalidated
+    fn generation(&self, key: &CacheKey) -> u64 {
+        self.generations.get(Self::stripe(key)).copied().unwrap_or(0)
+    }
+
+    /// Drop `key` and make reads of it still in flight stale
+    fn invalidate(&mut self, key: &CacheKey) {
+        if self.generations.is_empty() {
+            self.generations = vec![0; GENERATION_STRIPES];
+        }
+        let generation = &mut self.generations[Self::stripe(key)];
+        *generation = generation.wrapping_add(1);
+        self.remove(key);
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<CachedValue> {
+        let tick = self.next_tick;
+        let entry = self.entries.get_mut(key)?;
+        self.recency.remove(&entry.tick);
+        entry.tick = tick;
+        self.recency.insert(tick, *key);
+        self.next_tick += 1;
+        Some(entry.value.clone())
+    }
+
+    /// Insert a value, returning how many entries were evicted to fit it
+    fn insert(&mut self, key: CacheKey, value: CachedValue, size: usize, capacity: usize) -> u64 {
+        self.remove(&key);
+        if size > capacity {
+            return 0;
+        }
+
+        let mut evicted = 0;
+        while self.bytes + size > capacity {
+            let oldest = match self.recency.keys().next() {
+                Some(tick) => *tick,
+                None => break,
+            };
+            if let Some(victim) = self.recency.remove(&oldest) {
+                if let Some(entry) = self.entries.remove(&victim) {
+                    self.bytes -= entry.size;
+                    evicted += 1;
+                }
+            }
+        }
+
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        self.recency.insert(tick, key);
+        self.entries.insert(key, CacheEntry { value, size, tick });
+        self.bytes += size;
+        evicted
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.tick);
+            self.bytes -= entry.size;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.bytes = 0;
+    }
+}
+
+/// Cache effectiveness counters
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CacheStats {
+    /// Reads served from the cache
+    pub hits: u64,
+    /// Reads that went to the backend
+    pub misses: u64,
+    /// Entries dropped to stay within the byte budget
+    pub evictions: u64,
+    /// Entries currently cached
+    pub entries: usize,
+    /// Approximate bytes currently cached
+    pub bytes: usize,
+    /// Byte budget
+    pub capacity_bytes: usize,
+}
+
+impl CacheStats {
+    /// Fraction of reads served from the cache (0.0 when nothing was read)
+    pub fn hit_ratio(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Storage backend wrapper with an LRU cache for node and edge point reads
+pub struct CachedStorage<S: StorageBackend> {
+    inner: S,
+    cache: Mutex<Lru>,
+    capacity_bytes: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl<S: StorageBackend> CachedStorage<S> {
+    /// Wrap a backend with a cache of at most `capacity_bytes`
+    ///
+    /// A capacity of zero disables caching; every read goes to the backend.
+    pub fn new(inner: S, capacity_bytes: usize) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(Lru::default()),
+            capacity_bytes,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Wrap a backend using `enable_cache` and `cache_size_mb` from the config
+    pub fn from_config(inner: S, config: &StorageConfig) -> Self {
+        let capacity = if config.enable_cache {
+            config.cache_size_mb.saturating_mul(1024 * 1024)
+        } else {
+            0
+        };
+        debug!("Storage cache capacity: {} bytes", capacity);
+        Self::new(inner, capacity)
+    }
+
+    /// The wrapped backend
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Unwrap, dropping the cache
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Whether reads are cached at all
+    pub fn is_enabled(&self) -> bool {
+        self.capacity_bytes > 0
+    }
+
+    /// Current cache counters
+    pub fn cache_stats(&self) -> CacheStats {
+        let cache = self.cache.lock();
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: cache.entries.len(),
+            bytes: cache.bytes,
+            capacity_bytes: self.capacity_bytes,
+        }
+    }
+
+    /// Drop every cached entry; counters are kept
+    pub fn clear_cache(&self) {
+        self.cache.lock().clear();
+    }
+
+    /// Cached value of `key`, plus the key's generation to pass to
+    /// [`store`](Self::store) after a miss
+    fn lookup(&self, key: CacheKey) -> (Option<CachedValue>, u64) {
+        if !self.is_enabled() {
+            return (None, 0);
+        }
+        let mut cache = self.cache.lock();
+        let found = cache.get(&key);
+        match found {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+        (found, cache.generation(&key))
+    }
+
+    /// Cache a value read from the backend, unless `key` was invalidated
+    /// since `generation` was taken
+    fn store(&self, key: CacheKey, value: CachedValue, generation: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        let size = match &value {
+            CachedValue::Node(node) => bincode::serialized_size(node),
+            CachedValue::Edge(edge) => bincode::serialized_size(edge),
+        }
+        .map(|size| size as usize)
+        .unwrap_or(0)
+            + ENTRY_OVERHEAD;
+        let mut cache = self.cache.lock();
+        if cache.generation(&key) != generation {
+            return;
+        }
+        let evicted = cache.insert(key, value, size, self.capacity_bytes);
+        self.evictions.fetch_add(evicted, Ordering::Relaxed);
+    }
+
+    fn invalidate(&self, key: CacheKey) {
+        if self.is_enabled() {
+            self.cache.lock().invalidate(&key);
+        }
+    }
+}
+
+impl<S: StorageBackend> StorageBackend for CachedStorage<S> {
+    fn add_node(&self, node: Node) -> Result<NodeId> {
+        // A node may be re-added under an ID that was cached before deletion
+        let id = node.id();
+        let result = self.inner.add_node(node);
+        self.invalidate(CacheKey::Node(id));
+        result
+    }
+
+    fn get_node(&self, id: NodeId) -> Result<Node> {
+        let (cached, generation) = self.lookup(CacheKey::Node(id));
+        if let Some(CachedValue::Node(node)) = cached {
+            return Ok(node);
+        }
+        let node = self.inner.get_node(id)?;
+        self.store(CacheKey::Node(id), CachedValue::Node(node.clone()), generation);
+        Ok(node)
+    }
+
+    fn update_node(&self, node: Node) -> Result<()> {
+        let id = node.id();
+        let result = self.inner.update_node(node);
+        self.invalidate(CacheKey::Node(id));
+        result
+    }
+
+    fn delete_node(&self, id: NodeId) -> Result<()> {
+        // The backend cascades to incident edges, so drop those as well
+        if self.is_enabled() {
+            let incident: Vec<EdgeId> = self
+                .inner
+                .get_outgoing_edges(id)
+                .into_iter()
+                .chain(self.inner.get_incoming_edges(id))
+                .flatten()
+                .map(|edge| edge.id())
+                .collect();
+            let result = self.inner.delete_node(id);
+            let mut cache = self.cache.lock();
+            cache.invalidate(&CacheKey::Node(id));
+            for edge_id in incident {
+                cache.invalidate(&CacheKey::Edge(edge_id));
+            }
+            return result;
+        }
+        self.inner.delete_node(id)
+    }
+
+    fn add_edge(&self, edge: Edge) -> Result<EdgeId> {
+        let id = edge.id();
+        let result = self.inner.add_edge(edge);
+        self.invalidate(CacheKey::Edge(id));
+        result
+    }
+
+    fn get_edge(&self, id: EdgeId) -> Result<Edge> {
+        let (cached, generation) = self.lookup(CacheKey::Edge(id));
+        if let Some(CachedValue::Edge(edge)) = cached {
+            return Ok(edge);
+        }
+        let edge = self.inner.get_edge(id)?;
+        self.store(CacheKey::Edge(id), CachedValue::Edge(edge.clone()), generation);
+        Ok(edge)
+    }
+
+    fn update_edge(&self, edge: Edge) -> Result<()> {
+        let id = edge.id();
+        let result = self.inner.update_edge(edge);
+        self.invalidate(CacheKey::Edge(id));
+        result
+    }
+
+    fn delete_edge(&self, id: EdgeId) -> Result<()> {
+        let result = self.inner.delete_edge(id);
+        self.invalidate(CacheKey::Edge(id));
+        result
+    }
+
+    fn get_nodes_by_label(&self, label: &str) -> Vec<Node> {
+        self.inner.get_nodes_by_label(label)
+    }
+
+    fn get_all_nodes(&self) -> Vec<Node> {
+        self.inner.get_all_nodes()
+    }
+
+    fn get_all_edges(&self) -> Vec<Edge> {
+        self.inner.get_all_edges()
+    }
+
+    fn get_edges_by_type(&self, relationship_type: &str) -> Vec<Edge> {
+        self.inner.get_edges_by_type(relationship_type)
+    }
+
+    fn get_nodes_by_property(&self, key: &str, value: &PropertyValue) -> Vec<Node> {
+        self.inner.get_nodes_by_property(key, value)
+    }
+
+    fn iter_nodes(&self) -> Box<dyn Iterator<Item = Node> + '_> {
+        self.inner.iter_nodes()
+    }
+
+    fn iter_edges(&self) -> Box<dyn Iterator<Item = Edge> + '_> {
+        self.inner.iter_edges()
+    }
+
+    fn get_outgoing_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
+        self.inner.get_outgoing_edges(node_id)
+    }
+
+    fn get_incoming_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
+        self.inner.get_incoming_edges(node_id)
+    }
+
+    fn node_count(&self) -> usize {
+        self.inner.node_count()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.inner.edge_count()
+    }
+
+    fn cost_constants(&self) -> CostConstants {
+        self.inner.cost_constants()
+    }
+
+    fn statistics(&self) -> Option<&GraphStatistics> {
+        self.inner.statistics()
+    }
+
+    fn degree(&self, node_id: NodeId) -> Result<usize> {
+        self.inner.degree(node_id)
+    }
+    
+    fn flush(&self) -> Result<()> {
+        self.inner.flush()
+    }
+    
+    fn apply_batch(&self, ops: Vec<GraphOp>) -> Result<()> {
+        if !self.is_enabled() {
+            return self.inner.apply_batch(ops);
+        }
+        // Collect what the batch touches before it runs, including the
+        // edges a node deletion cascades to
+        let mut touched = Vec::with_capacity(ops.len());
+        for op in &ops {
+            match op {
+                GraphOp::AddNode(node) | GraphOp::UpdateNode(node) => touched.push(CacheKey::Node(node.id())),
+                GraphOp::DeleteNode(id) => {
+                    touched.push(CacheKey::Node(*id));
+                    touched.extend(
+                        self.inner
+                            .get_outgoing_edges(*id)
+                            .into_iter()
+                            .chain(self.inner.get_incoming_edges(*id))
+                            .flatten()
+                            .map(|edge| CacheKey::Edge(edge.id())),
+                    );
+                }
+                GraphOp::AddEdge(edge) | GraphOp::UpdateEdge(edge) => touched.push(CacheKey::Edge(edge.id())),
+                GraphOp::DeleteEdge(id) => touched.push(CacheKey::Edge(*id)),
+            }
+        }
+        // The backend applies the batch as one unit, e.g. a single sled transaction
+        let result = self.inner.apply_batch(ops);
+        let mut cache = self.cache.lock();
+        for key in &touched {
+            cache.invalidate(key);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_hits_misses_and_invalidation() {
+        let storage = CachedStorage::new(MemoryStorage::new(), 1024 * 1024);
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let edge = storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+
+        storage.get_node(a).unwrap();
+        storage.get_node(a).unwrap();
+        storage.get_edge(edge).unwrap();
+        let stats = storage.cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 2));
+
+        // Updates are visible through the cache
+        let mut node = storage.get_node(a).unwrap();
+        node.set_property("name".to_string(), PropertyValue::String("Alice".to_string()));
+        storage.update_node(node).unwrap();
+        assert!(storage.get_node(a).unwrap().get_property("name").is_some());
+
+        // Deleting a node also drops its cached edges
+        storage.delete_node(b).unwrap();
+        assert!(storage.get_edge(edge).is_err());
+        assert!(storage.get_node(b).is_err());
+    }
+
+    #[test]
+    fn test_batches_reach_the_backend_and_invalidate() {
+        let storage = CachedStorage::new(MemoryStorage::new(), 1024 * 1024);
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let edge = storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+        storage.get_node(a).unwrap();
+        storage.get_edge(edge).unwrap();
+
+        let mut node = storage.get_node(a).unwrap();
+        node.set_property("name".to_string(), PropertyValue::String("Alice".to_string()));
+        storage
+            .apply_batch(vec![GraphOp::UpdateNode(node), GraphOp::DeleteNode(b)])
+            .unwrap();
+        assert!(storage.get_node(a).unwrap().get_property("name").is_some());
+        assert!(storage.get_edge(edge).is_err());
+
+        // A failing batch leaves nothing behind, in the backend or the cache
+        let c = Node::new(vec![]);
+        let c_id = c.id();
+        assert!(storage.apply_batch(vec![GraphOp::AddNode(c), GraphOp::DeleteNode(b)]).is_err());
+        assert!(storage.get_node(c_id).is_err());
+    }
+
+    #[test]
+    fn test_read_racing_a_write_is_not_cached() {
+        let storage = CachedStorage::new(MemoryStorage::new(), 1024 * 1024);
+        let id = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+
+        // A miss reads the old node, then a write lands before it is cached
+        let (cached, generation) = storage.lookup(CacheKey::Node(id));
+        assert!(cached.is_none());
+        let stale = storage.inner().get_node(id).unwrap();
+        let mut node = stale.clone();
+        node.set_property("name".to_string(), PropertyValue::String("Alice".to_string()));
+        storage.update_node(node).unwrap();
+        storage.store(CacheKey::Node(id), CachedValue::Node(stale), generation);
+
+        assert_eq!(storage.cache_stats().entries, 0);
+        assert!(storage.get_node(id).unwrap().get_property("name").is_some());
+    }
+
+    #[test]
+    fn test_lru_eviction_and_disabled_cache() {
+        let backend = MemoryStorage::new();
+        let ids: Vec<NodeId> = (0..10)
+            .map(|_| backend.add_node(Node::new(vec!["Person".to_string()])).unwrap())
+            .collect();
+        let storage = CachedStorage::new(backend, 3 * (ENTRY_OVERHEAD + 64));
+        for id in &ids {
+            storage.get_node(*id).unwrap();
+        }
+        let stats = storage.cache_stats();
+        assert!(stats.evictions > 0);
+        assert!(stats.bytes <= stats.capacity_bytes);
+
+        // The most recent read survives, the first one was evicted
+        storage.get_node(ids[9]).unwrap();
+        assert_eq!(storage.cache_stats().hits, 1);
+
+        let config = StorageConfig {
+            enable_cache: false,
+            ..StorageConfig::default()
+        };
+        let disabled = CachedStorage::from_config(MemoryStorage::new(), &config);
+        let id = disabled.add_node(Node::new(vec![])).unwrap();
+        disabled.get_node(id).unwrap();
+        assert_eq!(disabled.cache_stats(), CacheStats::default());
+    }
+}
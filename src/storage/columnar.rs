@@ -6,12 +6,13 @@
 use crate::error::{DeepGraphError, Result};
 use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
 use crate::storage::schema::{edge_schema, node_schema};
-use crate::storage::StorageBackend;
+use crate::storage::{CostConstants, Pressure, StorageBackend, ThrottleConfig};
 
 use arrow::array::{
-    Array, ArrayRef, FixedSizeBinaryArray, FixedSizeBinaryBuilder,
+    Array, ArrayRef, BooleanArray, FixedSizeBinaryArray, FixedSizeBinaryBuilder,
     ListArray, RecordBatch, StringBuilder, StringArray,
 };
+use arrow::compute::{concat_batches, filter_record_batch};
 use arrow::buffer::OffsetBuffer;
 use arrow::datatypes::Schema;
 use dashmap::DashMap;
@@ -19,17 +20,83 @@ use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Default number of rows per flushed node batch
+pub const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// Batch index marking a row that still lives in the pending buffer
+const PENDING_BATCH: usize = usize::MAX;
+
+/// Per-batch deletion vector marking rows that are no longer live
+#[derive(Debug, Clone)]
+struct DeletionVector {
+    deleted: Vec<bool>,
+    count: usize,
+}
+
+impl DeletionVector {
+    fn new(rows: usize) -> Self {
+        Self {
+            deleted: vec![false; rows],
+            count: 0,
+        }
+    }
+
+    /// Tombstone a row, returning false if it was already dead
+    fn mark(&mut self, row: usize) -> bool {
+        match self.deleted.get_mut(row) {
+            Some(flag) if !*flag => {
+                *flag = true;
+                self.count += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Selection mask of the live rows
+    fn live_mask(&self) -> BooleanArray {
+        BooleanArray::from(self.deleted.iter().map(|d| !d).collect::<Vec<bool>>())
+    }
+}
+
+/// Outcome of a [`ColumnarStorage::vacuum`] run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VacuumStats {
+    /// Tombstoned node rows physically removed
+    pub node_rows_reclaimed: usize,
+    /// Tombstoned edge rows physically removed
+    pub edge_rows_reclaimed: usize,
+    /// Batches dropped because every row was dead
+    pub batches_removed: usize,
+}
+
 /// Columnar storage using Apache Arrow
 ///
 /// Stores nodes and edges in columnar format for efficient querying
 /// and analytical workloads. Provides better compression and cache locality
 /// compared to row-based storage.
+///
+/// New nodes are buffered and written out as one `RecordBatch` once
+/// `batch_size` rows have accumulated (or on [`ColumnarStorage::flush`]).
+/// [`ColumnarStorage::compact`] merges small batches and drops superseded rows.
+///
+/// Batches are immutable, so updates and deletes tombstone the old row in a
+/// per-batch deletion vector. Scans skip tombstoned rows and
+/// [`ColumnarStorage::vacuum`] rewrites batches to reclaim their space.
+/// Locks are always taken in the order pending -> batches -> tombstones.
 pub struct ColumnarStorage {
     /// Node batches (columnar format)
     node_batches: RwLock<Vec<RecordBatch>>,
+    /// Deletion vectors, one per node batch
+    node_tombstones: RwLock<Vec<DeletionVector>>,
+    /// Nodes buffered until a full batch is flushed
+    pending_nodes: RwLock<Vec<Node>>,
+    /// Rows per flushed node batch
+    batch_size: usize,
     /// Edge batches (columnar format)
-    #[allow(dead_code)] // TODO: Implement edge serialization in Phase 2
     edge_batches: RwLock<Vec<RecordBatch>>,
+    /// Deletion vectors, one per edge batch
+    edge_tombstones: RwLock<Vec<DeletionVector>>,
     /// In-memory index for fast lookups (node_id -> batch_index, row_index)
     node_index: DashMap<NodeId, (usize, usize)>,
     /// In-memory index for fast lookups (edge_id -> batch_index, row_index)
@@ -41,8 +108,9 @@ pub struct ColumnarStorage {
     /// Node schema
     node_schema: Arc<Schema>,
     /// Edge schema
-    #[allow(dead_code)] // TODO: Implement edge serialization in Phase 2
     edge_schema: Arc<Schema>,
+    /// Limits on tombstoned rows awaiting a vacuum
+    vacuum_backlog: Option<ThrottleConfig>,
 }
 
 impl ColumnarStorage {
@@ -50,55 +118,99 @@ impl ColumnarStorage {
     pub fn new() -> Self {
         Self {
             node_batches: RwLock::new(Vec::new()),
+            node_tombstones: RwLock::new(Vec::new()),
+            pending_nodes: RwLock::new(Vec::new()),
+            batch_size: DEFAULT_BATCH_SIZE,
             edge_batches: RwLock::new(Vec::new()),
+            edge_tombstones: RwLock::new(Vec::new()),
             node_index: DashMap::new(),
             edge_index: DashMap::new(),
             outgoing_edges: DashMap::new(),
             incoming_edges: DashMap::new(),
             node_schema: node_schema(),
             edge_schema: edge_schema(),
+            vacuum_backlog: None,
         }
     }
 
-    /// Serialize a node to Arrow format and add to batch
-    fn serialize_node(&self, node: &Node) -> Result<()> {
-        let id = node.id();
-        let id_bytes = id.as_uuid().as_bytes().to_vec();
-        
-        // Build Arrow arrays
-        let mut id_builder = FixedSizeBinaryBuilder::new(16);
-        id_builder.append_value(&id_bytes).map_err(|e| {
-            DeepGraphError::StorageError(format!("Failed to append ID: {}", e))
-        })?;
-        
-        // Build labels list with correct schema
+    /// Set the number of rows per flushed node batch
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Reject updates and deletes once too many tombstoned rows await a vacuum
+    ///
+    /// Limits count dead rows; `max_wait` is unused since only an explicit
+    /// [`ColumnarStorage::vacuum`] drains the backlog.
+    pub fn with_vacuum_backlog_limit(mut self, limits: ThrottleConfig) -> Self {
+        self.vacuum_backlog = Some(limits);
+        self
+    }
+
+    /// Backpressure level of the vacuum backlog
+    pub fn pressure(&self) -> Pressure {
+        let limits = match self.vacuum_backlog {
+            Some(limits) => limits,
+            None => return Pressure::Normal,
+        };
+        let dead = self.dead_row_count() as u64;
+        if dead >= limits.hard_limit {
+            Pressure::Saturated
+        } else if dead >= limits.soft_limit {
+            Pressure::Elevated
+        } else {
+            Pressure::Normal
+        }
+    }
+
+    /// Fail with `Throttled` before a write that would add to a full backlog
+    fn check_backpressure(&self) -> Result<()> {
+        if self.pressure() == Pressure::Saturated {
+            return Err(DeepGraphError::Throttled(format!(
+                "{} tombstoned rows awaiting vacuum",
+                self.dead_row_count()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Build a record batch holding one row per node
+    fn build_node_batch(&self, nodes: &[Node]) -> Result<RecordBatch> {
+        let mut id_builder = FixedSizeBinaryBuilder::with_capacity(nodes.len(), 16);
         let mut string_builder = StringBuilder::new();
-        for label in node.labels() {
-            string_builder.append_value(label);
+        let mut props_builder = StringBuilder::new();
+        let mut created_builder = arrow::array::Int64Builder::with_capacity(nodes.len());
+        let mut updated_builder = arrow::array::Int64Builder::with_capacity(nodes.len());
+        let now = chrono::Utc::now().timestamp();
+        
+        for node in nodes {
+            id_builder.append_value(node.id().as_uuid().as_bytes()).map_err(|e| {
+                DeepGraphError::StorageError(format!("Failed to append ID: {}", e))
+            })?;
+            
+            for label in node.labels() {
+                string_builder.append_value(label);
+            }
+            
+            let props_json = serde_json::to_string(node.properties())
+                .map_err(|e| DeepGraphError::SerializationError(e.to_string()))?;
+            props_builder.append_value(&props_json);
+            
+            created_builder.append_value(now);
+            updated_builder.append_value(now);
         }
-        let string_array = string_builder.finish();
         
+        // Build labels list with correct schema
         let field = arrow::datatypes::Field::new("item", arrow::datatypes::DataType::Utf8, false);
         let labels_array = ListArray::new(
             Arc::new(field),
-            OffsetBuffer::from_lengths(vec![node.labels().len()]),
-            Arc::new(string_array),
+            OffsetBuffer::from_lengths(nodes.iter().map(|n| n.labels().len())),
+            Arc::new(string_builder.finish()),
             None,
         );
         
-        let mut props_builder = StringBuilder::new();
-        let props_json = serde_json::to_string(node.properties())
-            .map_err(|e| DeepGraphError::SerializationError(e.to_string()))?;
-        props_builder.append_value(&props_json);
-        
-        let mut created_builder = arrow::array::Int64Builder::new();
-        created_builder.append_value(chrono::Utc::now().timestamp());
-        
-        let mut updated_builder = arrow::array::Int64Builder::new();
-        updated_builder.append_value(chrono::Utc::now().timestamp());
-        
-        // Create record batch
-        let batch = RecordBatch::try_new(
+        RecordBatch::try_new(
             self.node_schema.clone(),
             vec![
                 Arc::new(id_builder.finish()) as ArrayRef,
@@ -107,26 +219,237 @@ impl ColumnarStorage {
                 Arc::new(created_builder.finish()) as ArrayRef,
                 Arc::new(updated_builder.finish()) as ArrayRef,
             ],
-        ).map_err(|e| DeepGraphError::StorageError(format!("Failed to create batch: {}", e)))?;
+        ).map_err(|e| DeepGraphError::StorageError(format!("Failed to create batch: {}", e)))
+    }
+
+    /// Append a node to the pending buffer, flushing once a batch is full
+    fn buffer_node(&self, pending: &mut Vec<Node>, node: Node) -> Result<()> {
+        let id = node.id();
+        pending.push(node);
+        self.node_index.insert(id, (PENDING_BATCH, pending.len() - 1));
+        
+        if pending.len() >= self.batch_size {
+            self.flush_pending(pending)?;
+        }
+        Ok(())
+    }
+
+    /// Write buffered rows out as one batch
+    ///
+    /// Rows that were superseded or deleted while buffered are dropped.
+    fn flush_pending(&self, pending: &mut Vec<Node>) -> Result<()> {
+        let live: Vec<Node> = pending
+            .drain(..)
+            .enumerate()
+            .filter(|(row, node)| {
+                self.node_index
+                    .get(&node.id())
+                    .is_some_and(|loc| *loc == (PENDING_BATCH, *row))
+            })
+            .map(|(_, node)| node)
+            .collect();
         
-        // Add to batches
+        if live.is_empty() {
+            return Ok(());
+        }
+        
+        let batch = self.build_node_batch(&live)?;
         let mut batches = self.node_batches.write();
         let batch_idx = batches.len();
-        let row_idx = 0;
         batches.push(batch);
+        self.node_tombstones.write().push(DeletionVector::new(live.len()));
         
-        // Update index
-        self.node_index.insert(id, (batch_idx, row_idx));
+        for (row_idx, node) in live.iter().enumerate() {
+            self.node_index.insert(node.id(), (batch_idx, row_idx));
+        }
         
         Ok(())
     }
 
-    /// Deserialize a node from Arrow format
-    fn deserialize_node(&self, batch_idx: usize, row_idx: usize) -> Result<Node> {
-        let batches = self.node_batches.read();
+    /// Flush buffered nodes into a record batch
+    pub fn flush(&self) -> Result<()> {
+        let mut pending = self.pending_nodes.write();
+        self.flush_pending(&mut pending)
+    }
+
+    /// Merge small node batches into `batch_size`-row batches
+    ///
+    /// Superseded and deleted rows are dropped in the process. Returns the
+    /// number of batches removed.
+    pub fn compact(&self) -> Result<usize> {
+        let mut pending = self.pending_nodes.write();
+        self.flush_pending(&mut pending)?;
+        
+        let mut batches = self.node_batches.write();
+        let mut tombstones = self.node_tombstones.write();
+        let before = batches.len();
+        let live = Self::live_batches(&batches, &tombstones)?;
+        
+        let merged = if live.is_empty() {
+            Vec::new()
+        } else {
+            let all = concat_batches(&self.node_schema, &live)
+                .map_err(|e| DeepGraphError::StorageError(format!("Failed to merge batches: {}", e)))?;
+            (0..all.num_rows())
+                .step_by(self.batch_size)
+                .map(|offset| all.slice(offset, self.batch_size.min(all.num_rows() - offset)))
+                .collect::<Vec<_>>()
+        };
+        
+        Self::reindex(&merged, &self.node_index, NodeId::from_uuid)?;
+        *tombstones = merged.iter().map(|b| DeletionVector::new(b.num_rows())).collect();
+        *batches = merged;
+        Ok(before.saturating_sub(batches.len()))
+    }
+
+    /// Rewrite batches that contain tombstoned rows, reclaiming their space
+    ///
+    /// Unlike [`ColumnarStorage::compact`], batches are not merged, so a
+    /// vacuum only touches batches that actually have dead rows.
+    pub fn vacuum(&self) -> Result<VacuumStats> {
+        let mut stats = VacuumStats::default();
+        
+        {
+            let mut pending = self.pending_nodes.write();
+            self.flush_pending(&mut pending)?;
+            let mut batches = self.node_batches.write();
+            let mut tombstones = self.node_tombstones.write();
+            let (rows, removed) = Self::vacuum_batches(&mut batches, &mut tombstones)?;
+            Self::reindex(&batches, &self.node_index, NodeId::from_uuid)?;
+            stats.node_rows_reclaimed = rows;
+            stats.batches_removed += removed;
+        }
+        
+        {
+            let mut batches = self.edge_batches.write();
+            let mut tombstones = self.edge_tombstones.write();
+            let (rows, removed) = Self::vacuum_batches(&mut batches, &mut tombstones)?;
+            Self::reindex(&batches, &self.edge_index, EdgeId::from_uuid)?;
+            stats.edge_rows_reclaimed = rows;
+            stats.batches_removed += removed;
+        }
+        
+        Ok(stats)
+    }
+
+    /// Number of tombstoned rows not yet reclaimed by a vacuum
+    pub fn dead_row_count(&self) -> usize {
+        let nodes: usize = self.node_tombstones.read().iter().map(|t| t.count).sum();
+        let edges: usize = self.edge_tombstones.read().iter().map(|t| t.count).sum();
+        nodes + edges
+    }
+
+    /// Filter dead rows out of each batch, returning (rows reclaimed, batches removed)
+    fn vacuum_batches(
+        batches: &mut Vec<RecordBatch>,
+        tombstones: &mut Vec<DeletionVector>,
+    ) -> Result<(usize, usize)> {
+        let before = batches.len();
+        let mut reclaimed = 0;
+        let mut kept_batches = Vec::with_capacity(batches.len());
+        let mut kept_tombstones = Vec::with_capacity(tombstones.len());
+        
+        for (batch, deletes) in batches.drain(..).zip(tombstones.drain(..)) {
+            reclaimed += deletes.count;
+            if deletes.count == 0 {
+                kept_batches.push(batch);
+                kept_tombstones.push(deletes);
+                continue;
+            }
+            if deletes.count == batch.num_rows() {
+                continue;
+            }
+            let rewritten = filter_record_batch(&batch, &deletes.live_mask())
+                .map_err(|e| DeepGraphError::StorageError(format!("Failed to rewrite batch: {}", e)))?;
+            kept_tombstones.push(DeletionVector::new(rewritten.num_rows()));
+            kept_batches.push(rewritten);
+        }
+        
+        *batches = kept_batches;
+        *tombstones = kept_tombstones;
+        Ok((reclaimed, before - batches.len()))
+    }
+
+    /// Point an index at the rows of `batches`, using the id in column 0
+    fn reindex<K>(
+        batches: &[RecordBatch],
+        index: &DashMap<K, (usize, usize)>,
+        make_id: impl Fn(uuid::Uuid) -> K,
+    ) -> Result<()>
+    where
+        K: std::hash::Hash + Eq,
+    {
+        for (batch_idx, batch) in batches.iter().enumerate() {
+            let ids = batch.column(0)
+                .as_any()
+                .downcast_ref::<FixedSizeBinaryArray>()
+                .ok_or_else(|| DeepGraphError::StorageError("Invalid ID column".to_string()))?;
+            for row_idx in 0..ids.len() {
+                let uuid = uuid::Uuid::from_slice(ids.value(row_idx))
+                    .map_err(|e| DeepGraphError::StorageError(format!("Invalid ID: {}", e)))?;
+                index.insert(make_id(uuid), (batch_idx, row_idx));
+            }
+        }
+        Ok(())
+    }
+
+    /// Tombstone the flushed row a node index entry pointed at
+    ///
+    /// Rows still in the pending buffer need no tombstone; they are dropped
+    /// when the buffer is flushed.
+    fn tombstone_node(&self, location: (usize, usize)) {
+        let (batch_idx, row_idx) = location;
+        if batch_idx == PENDING_BATCH {
+            return;
+        }
+        if let Some(deletes) = self.node_tombstones.write().get_mut(batch_idx) {
+            deletes.mark(row_idx);
+        }
+    }
+
+    /// Tombstone the row an edge index entry pointed at
+    ///
+    /// Callers hold the edge batch lock so the location cannot be moved by
+    /// a concurrent vacuum.
+    fn tombstone_edge(&self, location: (usize, usize)) {
+        let (batch_idx, row_idx) = location;
+        if let Some(deletes) = self.edge_tombstones.write().get_mut(batch_idx) {
+            deletes.mark(row_idx);
+        }
+    }
+
+    /// Number of node record batches (excluding buffered rows)
+    pub fn node_batch_count(&self) -> usize {
+        self.node_batches.read().len()
+    }
+
+    /// Load a node from either the pending buffer or a flushed batch
+    fn load_node(pending: &[Node], batches: &[RecordBatch], location: (usize, usize)) -> Result<Node> {
+        let (batch_idx, row_idx) = location;
+        if batch_idx == PENDING_BATCH {
+            return pending.get(row_idx)
+                .cloned()
+                .ok_or_else(|| DeepGraphError::StorageError("Pending row not found".to_string()));
+        }
+        
         let batch = batches.get(batch_idx)
             .ok_or_else(|| DeepGraphError::StorageError("Batch not found".to_string()))?;
-        
+        Self::deserialize_node(batch, row_idx)
+    }
+
+    /// Load every node matching a predicate
+    fn scan_nodes(&self, predicate: impl Fn(&Node) -> bool) -> Vec<Node> {
+        let pending = self.pending_nodes.read();
+        let batches = self.node_batches.read();
+        self.node_index
+            .iter()
+            .filter_map(|entry| Self::load_node(&pending, &batches, *entry.value()).ok())
+            .filter(|node| predicate(node))
+            .collect()
+    }
+
+    /// Deserialize a node from Arrow format
+    fn deserialize_node(batch: &RecordBatch, row_idx: usize) -> Result<Node> {
         // Extract ID
         let id_array = batch.column(0)
             .as_any()
@@ -169,6 +492,150 @@ impl ColumnarStorage {
         
         Ok(node)
     }
+
+    /// Serialize an edge to Arrow format and add to batch
+    fn serialize_edge(&self, edge: &Edge) -> Result<()> {
+        let id = edge.id();
+        
+        let mut id_builder = FixedSizeBinaryBuilder::new(16);
+        let mut from_builder = FixedSizeBinaryBuilder::new(16);
+        let mut to_builder = FixedSizeBinaryBuilder::new(16);
+        for (builder, bytes) in [
+            (&mut id_builder, id.as_uuid().as_bytes()),
+            (&mut from_builder, edge.from().as_uuid().as_bytes()),
+            (&mut to_builder, edge.to().as_uuid().as_bytes()),
+        ] {
+            builder.append_value(bytes).map_err(|e| {
+                DeepGraphError::StorageError(format!("Failed to append ID: {}", e))
+            })?;
+        }
+        
+        let mut type_builder = StringBuilder::new();
+        type_builder.append_value(edge.relationship_type());
+        
+        let mut props_builder = StringBuilder::new();
+        let props_json = serde_json::to_string(edge.properties())
+            .map_err(|e| DeepGraphError::SerializationError(e.to_string()))?;
+        props_builder.append_value(&props_json);
+        
+        let mut created_builder = arrow::array::Int64Builder::new();
+        created_builder.append_value(chrono::Utc::now().timestamp());
+        
+        let mut updated_builder = arrow::array::Int64Builder::new();
+        updated_builder.append_value(chrono::Utc::now().timestamp());
+        
+        let batch = RecordBatch::try_new(
+            self.edge_schema.clone(),
+            vec![
+                Arc::new(id_builder.finish()) as ArrayRef,
+                Arc::new(from_builder.finish()) as ArrayRef,
+                Arc::new(to_builder.finish()) as ArrayRef,
+                Arc::new(type_builder.finish()) as ArrayRef,
+                Arc::new(props_builder.finish()) as ArrayRef,
+                Arc::new(created_builder.finish()) as ArrayRef,
+                Arc::new(updated_builder.finish()) as ArrayRef,
+            ],
+        ).map_err(|e| DeepGraphError::StorageError(format!("Failed to create batch: {}", e)))?;
+        
+        let mut batches = self.edge_batches.write();
+        let batch_idx = batches.len();
+        batches.push(batch);
+        self.edge_tombstones.write().push(DeletionVector::new(1));
+        if let Some(old) = self.edge_index.insert(id, (batch_idx, 0)) {
+            self.tombstone_edge(old);
+        }
+        
+        Ok(())
+    }
+
+    /// Deserialize an edge from Arrow format
+    fn deserialize_edge(batches: &[RecordBatch], location: (usize, usize)) -> Result<Edge> {
+        let (batch_idx, row_idx) = location;
+        let batch = batches.get(batch_idx)
+            .ok_or_else(|| DeepGraphError::StorageError("Batch not found".to_string()))?;
+        
+        let uuid_at = |col: usize| -> Result<uuid::Uuid> {
+            let array = batch.column(col)
+                .as_any()
+                .downcast_ref::<FixedSizeBinaryArray>()
+                .ok_or_else(|| DeepGraphError::StorageError("Invalid ID column".to_string()))?;
+            uuid::Uuid::from_slice(array.value(row_idx))
+                .map_err(|e| DeepGraphError::InvalidEdgeId(e.to_string()))
+        };
+        let string_at = |col: usize| -> Result<String> {
+            let array = batch.column(col)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| DeepGraphError::StorageError("Invalid string column".to_string()))?;
+            Ok(array.value(row_idx).to_string())
+        };
+        
+        let id = EdgeId::from_uuid(uuid_at(0)?);
+        let from = NodeId::from_uuid(uuid_at(1)?);
+        let to = NodeId::from_uuid(uuid_at(2)?);
+        let relationship_type = string_at(3)?;
+        let properties: HashMap<String, PropertyValue> = serde_json::from_str(&string_at(4)?)
+            .map_err(|e| DeepGraphError::SerializationError(e.to_string()))?;
+        
+        let mut edge = Edge::with_id(id, from, to, relationship_type);
+        for (key, value) in properties {
+            edge.set_property(key, value);
+        }
+        
+        Ok(edge)
+    }
+
+    /// Keep only the rows of `batches` that are not tombstoned
+    fn live_batches(
+        batches: &[RecordBatch],
+        tombstones: &[DeletionVector],
+    ) -> Result<Vec<RecordBatch>> {
+        batches
+            .iter()
+            .zip(tombstones)
+            .filter(|(batch, deletes)| deletes.count < batch.num_rows())
+            .map(|(batch, deletes)| {
+                if deletes.count == 0 {
+                    return Ok(batch.clone());
+                }
+                filter_record_batch(batch, &deletes.live_mask())
+                    .map_err(|e| DeepGraphError::StorageError(format!("Failed to filter batch: {}", e)))
+            })
+            .collect()
+    }
+
+    /// Snapshot of all live node rows as Arrow batches
+    ///
+    /// Rows superseded by updates or removed by deletes are filtered out.
+    pub fn node_batches(&self) -> Result<Vec<RecordBatch>> {
+        let mut pending = self.pending_nodes.write();
+        self.flush_pending(&mut pending)?;
+        let batches = self.node_batches.read();
+        Self::live_batches(&batches, &self.node_tombstones.read())
+    }
+
+    /// Snapshot of all live edge rows as Arrow batches
+    pub fn edge_batches(&self) -> Result<Vec<RecordBatch>> {
+        let batches = self.edge_batches.read();
+        Self::live_batches(&batches, &self.edge_tombstones.read())
+    }
+
+    /// Arrow schema of the node batches
+    pub fn node_schema(&self) -> Arc<Schema> {
+        self.node_schema.clone()
+    }
+
+    /// Arrow schema of the edge batches
+    pub fn edge_schema(&self) -> Arc<Schema> {
+        self.edge_schema.clone()
+    }
+
+    /// Remove an edge ID from a node's adjacency list
+    fn unlink_edge(adjacency: &DashMap<NodeId, Vec<EdgeId>>, node_id: NodeId, edge_id: EdgeId) {
+        if let Some(mut ids) = adjacency.get_mut(&node_id) {
+            ids.retain(|&id| id != edge_id);
+        }
+    }
 }
 
 impl Default for ColumnarStorage {
@@ -180,46 +647,57 @@ impl Default for ColumnarStorage {
 impl StorageBackend for ColumnarStorage {
     fn add_node(&self, node: Node) -> Result<NodeId> {
         let id = node.id();
-        self.serialize_node(&node)?;
+        let mut pending = self.pending_nodes.write();
+        self.buffer_node(&mut pending, node)?;
         Ok(id)
     }
     
     fn get_node(&self, id: NodeId) -> Result<Node> {
-        let (batch_idx, row_idx) = self.node_index
+        let pending = self.pending_nodes.read();
+        let batches = self.node_batches.read();
+        let location = self.node_index
             .get(&id)
             .map(|entry| *entry.value())
             .ok_or_else(|| DeepGraphError::NodeNotFound(id.to_string()))?;
         
-        self.deserialize_node(batch_idx, row_idx)
+        Self::load_node(&pending, &batches, location)
     }
     
     fn update_node(&self, node: Node) -> Result<()> {
         let id = node.id();
-        if !self.node_index.contains_key(&id) {
-            return Err(DeepGraphError::NodeNotFound(id.to_string()));
-        }
+        self.check_backpressure()?;
+        let mut pending = self.pending_nodes.write();
+        let old = self.node_index
+            .get(&id)
+            .map(|entry| *entry.value())
+            .ok_or_else(|| DeepGraphError::NodeNotFound(id.to_string()))?;
         
-        // For now, simple implementation: mark old as deleted and add new
-        // TODO: Implement in-place update or better versioning
-        self.serialize_node(&node)?;
+        // Re-append the node; the index now points at the new row and the
+        // old one is tombstoned until the next vacuum
+        self.buffer_node(&mut pending, node)?;
+        self.tombstone_node(old);
         Ok(())
     }
     
     fn delete_node(&self, id: NodeId) -> Result<()> {
-        self.node_index
+        self.check_backpressure()?;
+        // Serialize with flush/compaction, which rewrite index entries
+        let _pending = self.pending_nodes.write();
+        let (_, location) = self.node_index
             .remove(&id)
             .ok_or_else(|| DeepGraphError::NodeNotFound(id.to_string()))?;
+        self.tombstone_node(location);
         
-        // Remove associated edges
-        if let Some((_, edge_ids)) = self.outgoing_edges.remove(&id) {
-            for edge_id in edge_ids {
-                self.edge_index.remove(&edge_id);
-            }
-        }
-        
-        if let Some((_, edge_ids)) = self.incoming_edges.remove(&id) {
-            for edge_id in edge_ids {
-                self.edge_index.remove(&edge_id);
+        // Remove associated edges; holding the batch lock keeps edge
+        // locations stable against a concurrent vacuum
+        let _edge_batches = self.edge_batches.read();
+        for adjacency in [&self.outgoing_edges, &self.incoming_edges] {
+            if let Some((_, edge_ids)) = adjacency.remove(&id) {
+                for edge_id in edge_ids {
+                    if let Some((_, location)) = self.edge_index.remove(&edge_id) {
+                        self.tombstone_edge(location);
+                    }
+                }
             }
         }
         
@@ -239,8 +717,7 @@ impl StorageBackend for ColumnarStorage {
             return Err(DeepGraphError::NodeNotFound(to.to_string()));
         }
         
-        // TODO: Serialize edge to Arrow format (similar to nodes)
-        // For now, use placeholder
+        self.serialize_edge(&edge)?;
         
         // Update indices
         self.outgoing_edges
@@ -257,46 +734,58 @@ impl StorageBackend for ColumnarStorage {
     }
     
     fn get_edge(&self, id: EdgeId) -> Result<Edge> {
-        // TODO: Implement edge deserialization
-        Err(DeepGraphError::EdgeNotFound(id.to_string()))
+        let batches = self.edge_batches.read();
+        let location = self.edge_index
+            .get(&id)
+            .map(|entry| *entry.value())
+            .ok_or_else(|| DeepGraphError::EdgeNotFound(id.to_string()))?;
+        
+        Self::deserialize_edge(&batches, location)
     }
     
-    fn update_edge(&self, _edge: Edge) -> Result<()> {
-        // TODO: Implement edge update
+    fn update_edge(&self, edge: Edge) -> Result<()> {
+        let id = edge.id();
+        self.check_backpressure()?;
+        if !self.edge_index.contains_key(&id) {
+            return Err(DeepGraphError::EdgeNotFound(id.to_string()));
+        }
+        
+        self.serialize_edge(&edge)?;
         Ok(())
     }
     
     fn delete_edge(&self, id: EdgeId) -> Result<()> {
-        self.edge_index
+        self.check_backpressure()?;
+        let batches = self.edge_batches.read();
+        let (_, location) = self.edge_index
             .remove(&id)
             .ok_or_else(|| DeepGraphError::EdgeNotFound(id.to_string()))?;
+        let edge = Self::deserialize_edge(&batches, location)?;
+        self.tombstone_edge(location);
+        drop(batches);
+        
+        Self::unlink_edge(&self.outgoing_edges, edge.from(), id);
+        Self::unlink_edge(&self.incoming_edges, edge.to(), id);
         Ok(())
     }
     
     fn get_nodes_by_label(&self, label: &str) -> Vec<Node> {
         // TODO: Use label index for efficiency
-        let mut nodes = Vec::new();
-        for entry in self.node_index.iter() {
-            let (batch_idx, row_idx) = *entry.value();
-            if let Ok(node) = self.deserialize_node(batch_idx, row_idx) {
-                if node.has_label(label) {
-                    nodes.push(node);
-                }
-            }
-        }
-        nodes
+        self.scan_nodes(|node| node.has_label(label))
     }
     
     fn get_all_nodes(&self) -> Vec<Node> {
         // Get all nodes (full scan)
-        let mut nodes = Vec::new();
-        for entry in self.node_index.iter() {
-            let (batch_idx, row_idx) = *entry.value();
-            if let Ok(node) = self.deserialize_node(batch_idx, row_idx) {
-                nodes.push(node);
-            }
-        }
-        nodes
+        self.scan_nodes(|_| true)
+    }
+    
+    fn get_all_edges(&self) -> Vec<Edge> {
+        self.iter_edges().collect()
+    }
+    
+    fn iter_edges(&self) -> Box<dyn Iterator<Item = Edge> + '_> {
+        let ids: Vec<EdgeId> = self.edge_index.iter().map(|entry| *entry.key()).collect();
+        Box::new(ids.into_iter().filter_map(move |id| self.get_edge(id).ok()))
     }
     
     fn get_outgoing_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
@@ -304,8 +793,10 @@ impl StorageBackend for ColumnarStorage {
             return Err(DeepGraphError::NodeNotFound(node_id.to_string()));
         }
         
-        // TODO: Deserialize edges
-        Ok(Vec::new())
+        Ok(self.outgoing_edges
+            .get(&node_id)
+            .map(|ids| ids.iter().filter_map(|&id| self.get_edge(id).ok()).collect())
+            .unwrap_or_default())
     }
     
     fn get_incoming_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
@@ -313,8 +804,10 @@ impl StorageBackend for ColumnarStorage {
             return Err(DeepGraphError::NodeNotFound(node_id.to_string()));
         }
         
-        // TODO: Deserialize edges
-        Ok(Vec::new())
+        Ok(self.incoming_edges
+            .get(&node_id)
+            .map(|ids| ids.iter().filter_map(|&id| self.get_edge(id).ok()).collect())
+            .unwrap_or_default())
     }
     
     fn node_count(&self) -> usize {
@@ -324,6 +817,10 @@ impl StorageBackend for ColumnarStorage {
     fn edge_count(&self) -> usize {
         self.edge_index.len()
     }
+    
+    fn cost_constants(&self) -> CostConstants {
+        CostConstants::columnar()
+    }
 }
 
 #[cfg(test)]
@@ -349,5 +846,93 @@ mod tests {
         assert_eq!(retrieved.id(), id);
         assert_eq!(retrieved.labels(), node.labels());
     }
-}
 
+    #[test]
+    fn test_edges_and_live_batches() {
+        let storage = ColumnarStorage::new();
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        
+        let mut edge = Edge::new(a, b, "KNOWS".to_string());
+        edge.set_property("since".to_string(), PropertyValue::Integer(2020));
+        let edge_id = storage.add_edge(edge).unwrap();
+        
+        let retrieved = storage.get_edge(edge_id).unwrap();
+        assert_eq!(retrieved.relationship_type(), "KNOWS");
+        assert_eq!(retrieved.get_property("since"), Some(&PropertyValue::Integer(2020)));
+        assert_eq!(storage.get_outgoing_edges(a).unwrap().len(), 1);
+        assert_eq!(storage.get_incoming_edges(b).unwrap().len(), 1);
+        
+        // An update supersedes the old row, a delete hides it
+        let mut updated = storage.get_node(a).unwrap();
+        updated.set_property("name".to_string(), "Alice".into());
+        storage.update_node(updated).unwrap();
+        storage.delete_node(b).unwrap();
+        
+        let rows: usize = storage.node_batches().unwrap().iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 1);
+    }
+
+    #[test]
+    fn test_batched_append_and_compaction() {
+        let storage = ColumnarStorage::new().with_batch_size(4);
+        let ids: Vec<NodeId> = (0..10)
+            .map(|_| storage.add_node(Node::new(vec!["Person".to_string()])).unwrap())
+            .collect();
+        
+        // Two full batches flushed, two rows still buffered but readable
+        assert_eq!(storage.node_batch_count(), 2);
+        assert_eq!(storage.get_node(ids[9]).unwrap().id(), ids[9]);
+        assert_eq!(storage.get_nodes_by_label("Person").len(), 10);
+        
+        // Updates append rows; compaction merges and drops the stale ones
+        for id in &ids[..3] {
+            let mut node = storage.get_node(*id).unwrap();
+            node.set_property("seen".to_string(), PropertyValue::Boolean(true));
+            storage.update_node(node).unwrap();
+        }
+        storage.delete_node(ids[5]).unwrap();
+        storage.compact().unwrap();
+        
+        assert_eq!(storage.node_batch_count(), 3);
+        assert_eq!(storage.node_count(), 9);
+        assert_eq!(
+            storage.get_node(ids[0]).unwrap().get_property("seen"),
+            Some(&PropertyValue::Boolean(true))
+        );
+        assert_eq!(storage.get_all_nodes().len(), 9);
+    }
+
+    #[test]
+    fn test_tombstones_and_vacuum() {
+        let storage = ColumnarStorage::new().with_batch_size(2);
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let c = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let d = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let ab = storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+        storage.add_edge(Edge::new(c, d, "KNOWS".to_string())).unwrap();
+        
+        // Updating and deleting flushed rows tombstones them in place
+        let mut node = storage.get_node(c).unwrap();
+        node.set_property("age".to_string(), PropertyValue::Integer(30));
+        storage.update_node(node).unwrap();
+        storage.delete_node(a).unwrap();
+        storage.delete_node(b).unwrap();
+        assert_eq!(storage.dead_row_count(), 4);
+        assert!(storage.get_edge(ab).is_err());
+        
+        let stats = storage.vacuum().unwrap();
+        assert_eq!(stats.node_rows_reclaimed, 3);
+        assert_eq!(stats.edge_rows_reclaimed, 1);
+        assert_eq!(storage.dead_row_count(), 0);
+        
+        // Surviving rows are still reachable through the rebuilt indexes
+        assert_eq!(storage.get_all_nodes().len(), 2);
+        assert_eq!(
+            storage.get_node(c).unwrap().get_property("age"),
+            Some(&PropertyValue::Integer(30))
+        );
+        assert_eq!(storage.get_outgoing_edges(c).unwrap().len(), 1);
+    }
+}
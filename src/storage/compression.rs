@@ -0,0 +1,98 @@
+//! zstd compression of large stored records
+//!
+//! Nodes and edges with long text properties serialize to large bincode
+//! blobs. [`RecordCompression`] compresses blobs at or above a size
+//! threshold; smaller records, which gain little, are stored as is.
+//! Compressed blobs start with the zstd frame magic, which no bincode
+//! encoded node or edge can begin with (their first bytes are the ID
+//! length), so [`decompress`] recognizes them without a format flag and
+//! records written before compression was enabled still read back.
+
+use crate::config::StorageConfig;
+use crate::error::{DeepGraphError, Result};
+use std::borrow::Cow;
+
+/// First four bytes of every zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// When and how hard to compress stored records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordCompression {
+    /// Records smaller than this are stored uncompressed
+    pub threshold_bytes: usize,
+    /// zstd level (1-22)
+    pub level: i32,
+}
+
+impl RecordCompression {
+    /// Compress records of at least `threshold_bytes` at zstd level 3
+    pub fn new(threshold_bytes: usize) -> Self {
+        Self {
+            threshold_bytes,
+            level: 3,
+        }
+    }
+
+    /// Set the zstd level
+    pub fn with_level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Settings from `compression_threshold_bytes`/`compression_level`, or
+    /// `None` when the threshold is zero
+    pub fn from_config(config: &StorageConfig) -> Option<Self> {
+        (config.compression_threshold_bytes > 0)
+            .then(|| Self::new(config.compression_threshold_bytes).with_level(config.compression_level))
+    }
+
+    /// Compress a serialized record if it is large enough to be worth it
+    ///
+    /// The record is returned unchanged when it is under the threshold or
+    /// does not shrink.
+    pub fn compress(&self, record: Vec<u8>) -> Result<Vec<u8>> {
+        if record.len() < self.threshold_bytes {
+            return Ok(record);
+        }
+        let compressed = zstd::bulk::compress(&record, self.level)
+            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to compress record: {}", e)))?;
+        Ok(if compressed.len() < record.len() { compressed } else { record })
+    }
+}
+
+/// Whether a stored record was written compressed
+pub fn is_compressed(record: &[u8]) -> bool {
+    record.starts_with(&ZSTD_MAGIC)
+}
+
+/// Undo [`RecordCompression::compress`]; uncompressed records are borrowed as is
+pub fn decompress(record: &[u8]) -> Result<Cow<'_, [u8]>> {
+    if !is_compressed(record) {
+        return Ok(Cow::Borrowed(record));
+    }
+    zstd::stream::decode_all(record)
+        .map(Cow::Owned)
+        .map_err(|e| DeepGraphError::SerializationError(format!("Failed to decompress record: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Node, PropertyValue};
+
+    #[test]
+    fn test_compress_round_trip() {
+        let compression = RecordCompression::new(256);
+        let mut node = Node::new(vec!["Doc".to_string()]);
+        let small = bincode::serialize(&node).unwrap();
+        assert!(!is_compressed(&small));
+        assert_eq!(compression.compress(small.clone()).unwrap(), small);
+
+        node.set_property("body".to_string(), PropertyValue::String("lorem ipsum ".repeat(200)));
+        let large = bincode::serialize(&node).unwrap();
+        let stored = compression.compress(large.clone()).unwrap();
+        assert!(is_compressed(&stored));
+        assert!(stored.len() < large.len() / 4);
+        assert_eq!(decompress(&stored).unwrap().as_ref(), large.as_slice());
+    }
+}
@@ -0,0 +1,111 @@
+//! Dense `u64` internal IDs
+//!
+//! Public node and edge IDs are UUIDs, which are 16 bytes each and hash
+//! poorly into compact structures. [`DenseIdMap`] hands out sequential
+//! `u64` IDs per backend and keeps the mapping in both directions, so
+//! adjacency lists and secondary indexes can store 8-byte dense IDs and
+//! translate back only at the API boundary.
+//!
+//! Dense IDs are never reused: removing a key leaves a gap, so a stale
+//! dense ID held by an index simply fails to resolve.
+
+use dashmap::DashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Internal sequential identifier
+pub type DenseId = u64;
+
+/// Bidirectional map between public IDs and sequential [`DenseId`]s
+#[derive(Debug)]
+pub struct DenseIdMap<K: Eq + Hash> {
+    forward: DashMap<K, DenseId>,
+    reverse: DashMap<DenseId, K>,
+    next: AtomicU64,
+}
+
+impl<K: Copy + Eq + Hash> DenseIdMap<K> {
+    /// Create an empty map; the first assigned ID is `0`
+    pub fn new() -> Self {
+        Self {
+            forward: DashMap::new(),
+            reverse: DashMap::new(),
+            next: AtomicU64::new(0),
+        }
+    }
+
+    /// Dense ID of `key`, assigning the next free one if it has none
+    pub fn get_or_assign(&self, key: K) -> DenseId {
+        if let Some(dense) = self.forward.get(&key) {
+            return *dense;
+        }
+        *self.forward.entry(key).or_insert_with(|| {
+            let dense = self.next.fetch_add(1, Ordering::Relaxed);
+            self.reverse.insert(dense, key);
+            dense
+        })
+    }
+
+    /// Dense ID of `key`, if one was assigned
+    pub fn dense(&self, key: &K) -> Option<DenseId> {
+        self.forward.get(key).map(|dense| *dense)
+    }
+
+    /// Public ID a dense ID was assigned to, if it is still mapped
+    pub fn resolve(&self, dense: DenseId) -> Option<K> {
+        self.reverse.get(&dense).map(|key| *key)
+    }
+
+    /// Drop the mapping for `key`, returning its dense ID
+    pub fn remove(&self, key: &K) -> Option<DenseId> {
+        let (_, dense) = self.forward.remove(key)?;
+        self.reverse.remove(&dense);
+        Some(dense)
+    }
+
+    /// Number of mapped keys
+    pub fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    /// Whether no keys are mapped
+    pub fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    /// Remove every mapping; IDs handed out later continue the sequence
+    pub fn clear(&self) {
+        self.forward.clear();
+        self.reverse.clear();
+    }
+}
+
+impl<K: Copy + Eq + Hash> Default for DenseIdMap<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::NodeId;
+
+    #[test]
+    fn test_assign_resolve_remove() {
+        let map = DenseIdMap::new();
+        let a = NodeId::new();
+        let b = NodeId::new();
+
+        assert_eq!(map.get_or_assign(a), 0);
+        assert_eq!(map.get_or_assign(b), 1);
+        assert_eq!(map.get_or_assign(a), 0);
+        assert_eq!(map.resolve(1), Some(b));
+
+        assert_eq!(map.remove(&a), Some(0));
+        assert_eq!(map.resolve(0), None);
+        // Removed IDs are not handed out again
+        assert_eq!(map.get_or_assign(a), 2);
+        assert_eq!(map.len(), 2);
+    }
+}
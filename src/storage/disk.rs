@@ -2,12 +2,114 @@
 //!
 //! This module provides a disk-first storage backend that can handle
 //! graphs larger than RAM with ACID guarantees and crash recovery.
+//!
+//! Bloom filters over node IDs and labels (see [`BloomConfig`]) let lookups
+//! of missing nodes and labels skip sled. They are saved in the database on
+//! [`DiskStorage::flush`]; the saved copy is deleted before the next write,
+//! so after a crash the filters are rebuilt instead of trusted.
 
 use crate::error::{DeepGraphError, Result};
 use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
+use crate::metrics::{self, OperatorEvent};
+use crate::storage::bloom::{BloomConfig, BloomFilter};
+use crate::storage::compression::{self, RecordCompression};
+use crate::storage::schema::SchemaRegistry;
 use log::{debug, info, warn};
-use sled::{Db, Tree};
+use parking_lot::{Mutex, RwLock};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sled::transaction::{ConflictableTransactionError, ConflictableTransactionResult, TransactionError, TransactionalTree};
+use sled::{Batch, Db, Transactional, Tree};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Key in the default tree recording that the property index is populated
+const PROPERTY_INDEX_MARKER: &[u8] = b"__property_index_v2";
+
+/// Key in the default tree recording that adjacency is stored as sub-keys
+const ADJACENCY_SUBKEYS_MARKER: &[u8] = b"__adjacency_subkeys_v1";
+
+/// Key in the default tree of a backup holding the WAL LSN it corresponds to
+const BACKUP_WAL_LSN_KEY: &[u8] = b"__backup_wal_lsn";
+
+/// Key in the default tree holding the bloom filters saved by the last flush
+const BLOOM_FILTERS_KEY: &[u8] = b"__bloom_filters_v1";
+
+/// Bloom filters over node IDs and labels
+struct NodeFilters {
+    /// False-positive rate the filters were sized for
+    rate: f64,
+    nodes: BloomFilter,
+    labels: BloomFilter,
+}
+
+impl NodeFilters {
+    fn note_node(&self, node: &Node) {
+        self.nodes.insert(node.id().as_bytes());
+        for label in node.labels() {
+            self.labels.insert(label.as_bytes());
+        }
+    }
+    
+    fn is_full(&self) -> bool {
+        self.nodes.is_full() || self.labels.is_full()
+    }
+}
+
+/// When DiskStorage flushes mutations to disk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DurabilityMode {
+    /// Flush after every mutation (safest, slowest)
+    #[default]
+    SyncPerOp,
+    /// Flush at most once per interval; a crash may lose the last interval
+    Periodic(Duration),
+    /// Only flush on an explicit [`DiskStorage::flush`] (fastest, for bulk loads)
+    Manual,
+}
+
+/// A group of node and edge inserts applied atomically with a single flush
+///
+/// See [`DiskStorage::write_batch`].
+#[derive(Debug, Clone, Default)]
+pub struct DiskWriteBatch {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+impl DiskWriteBatch {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stage a node insert
+    pub fn add_node(&mut self, node: Node) -> NodeId {
+        let id = node.id();
+        self.nodes.push(node);
+        id
+    }
+
+    /// Stage an edge insert; its endpoints may be staged in the same batch
+    pub fn add_edge(&mut self, edge: Edge) -> EdgeId {
+        let id = edge.id();
+        self.edges.push(edge);
+        id
+    }
+
+    /// Number of staged operations
+    pub fn len(&self) -> usize {
+        self.nodes.len() + self.edges.len()
+    }
+
+    /// Whether nothing is staged
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+}
 
 /// Disk-based storage using Sled embedded database
 ///
@@ -36,15 +138,34 @@ pub struct DiskStorage {
     edges: Tree,
     /// Tree for label index (Label → Vec<NodeId>)
     label_index: Tree,
-    /// Tree for outgoing edges (NodeId → Vec<EdgeId>)
+    /// Tree for outgoing edges (NodeId || EdgeId → empty)
     outgoing_edges: Tree,
-    /// Tree for incoming edges (NodeId → Vec<EdgeId>)
+    /// Tree for incoming edges (NodeId || EdgeId → empty)
     incoming_edges: Tree,
-    /// Tree for property index (PropertyKey → Vec<NodeId>)
-    #[allow(dead_code)] // Will be used for property queries in the future
+    /// Tree for property index ((PropertyKey, Value) → Vec<NodeId>)
     property_index: Tree,
     /// Tree for edge type index (EdgeType → Vec<EdgeId>)
     edge_type_index: Tree,
+    /// Number of stale index entries removed by read-repair
+    index_repairs: AtomicU64,
+    /// When mutations are flushed to disk
+    durability: DurabilityMode,
+    /// Time of the last flush, for periodic durability
+    last_flush: Mutex<Instant>,
+    /// Property schema that node writes are validated against
+    schema: Option<Arc<SchemaRegistry>>,
+    /// Held shared by every write and exclusively while a backup is taken
+    backup_gate: RwLock<()>,
+    /// Compression of large node and edge records
+    compression: Option<RecordCompression>,
+    /// Sizing of the bloom filters
+    bloom_config: BloomConfig,
+    /// Bloom filters; `None` if they could not be built
+    filters: RwLock<Option<NodeFilters>>,
+    /// Whether the filters saved under [`BLOOM_FILTERS_KEY`] are current
+    filters_saved: AtomicBool,
+    /// Lookups answered by a bloom filter without reading sled
+    bloom_skips: AtomicU64,
 }
 
 impl DiskStorage {
@@ -91,7 +212,7 @@ impl DiskStorage {
         info!("  Nodes: {}", nodes.len());
         info!("  Edges: {}", edges.len());
         
-        Ok(Self {
+        let storage = Self {
             db,
             nodes,
             edges,
@@ -100,7 +221,242 @@ impl DiskStorage {
             incoming_edges,
             property_index,
             edge_type_index,
-        })
+            index_repairs: AtomicU64::new(0),
+            durability: DurabilityMode::default(),
+            last_flush: Mutex::new(Instant::now()),
+            schema: None,
+            backup_gate: RwLock::new(()),
+            compression: None,
+            bloom_config: BloomConfig::default(),
+            filters: RwLock::new(None),
+            filters_saved: AtomicBool::new(false),
+            bloom_skips: AtomicU64::new(0),
+        };
+        
+        // Databases written before the property index existed need it built once
+        let indexed = storage.db.contains_key(PROPERTY_INDEX_MARKER)
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to read property index marker: {}", e)))?;
+        if !indexed {
+            storage.rebuild_property_index()?;
+        }
+        
+        // Older databases kept adjacency as one serialized Vec per node
+        let subkeys = storage.db.contains_key(ADJACENCY_SUBKEYS_MARKER)
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to read adjacency marker: {}", e)))?;
+        if !subkeys {
+            storage.rebuild_adjacency()?;
+        }
+        
+        storage.open_filters();
+        Ok(storage)
+    }
+    
+    /// Set when mutations are flushed to disk
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let storage = DiskStorage::new("./data/bulk")?
+    ///     .with_durability(DurabilityMode::Manual);
+    /// ```
+    pub fn with_durability(mut self, durability: DurabilityMode) -> Self {
+        self.durability = durability;
+        self
+    }
+    
+    /// Current durability mode
+    pub fn durability(&self) -> DurabilityMode {
+        self.durability
+    }
+    
+    /// Compress large node and edge records on write
+    ///
+    /// Records are decompressed transparently on read, whether or not
+    /// compression is enabled, so it can be turned on for an existing
+    /// database.
+    pub fn with_compression(mut self, compression: RecordCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+    
+    /// Size the bloom filters for a false-positive rate and key count
+    ///
+    /// Filters loaded with a different rate or smaller capacity are rebuilt.
+    pub fn with_bloom_filters(mut self, config: BloomConfig) -> Self {
+        self.bloom_config = config;
+        let stale = self.filters.read().as_ref().map_or(true, |filters| {
+            filters.rate != config.false_positive_rate || filters.nodes.capacity() < config.expected_items
+        });
+        if stale {
+            self.refresh_filters();
+        }
+        self
+    }
+    
+    /// Sizing of the bloom filters
+    pub fn bloom_config(&self) -> BloomConfig {
+        self.bloom_config
+    }
+    
+    /// Whether a node may exist, without reading sled if the filter rules it out
+    pub fn contains_node(&self, id: NodeId) -> Result<bool> {
+        if self.ruled_out(|filters| &filters.nodes, id.as_bytes()) {
+            return Ok(false);
+        }
+        self.nodes.contains_key(id.as_bytes())
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to check node: {}", e)))
+    }
+    
+    /// Rebuild the bloom filters from the stored keys
+    ///
+    /// Writes wait while the trees are scanned. Happens automatically when a
+    /// filter holds more keys than it was sized for.
+    pub fn rebuild_bloom_filters(&self) -> Result<()> {
+        let _paused = self.backup_gate.write();
+        let filters = self.build_filters()?;
+        *self.filters.write() = Some(filters);
+        Ok(())
+    }
+    
+    /// Whether the filter chosen by `filter` proves `key` absent
+    fn ruled_out(&self, filter: impl Fn(&NodeFilters) -> &BloomFilter, key: &[u8]) -> bool {
+        let absent = self.filters.read().as_ref().is_some_and(|filters| !filter(filters).may_contain(key));
+        if absent {
+            self.bloom_skips.fetch_add(1, Ordering::Relaxed);
+        }
+        absent
+    }
+    
+    /// Record a node being written in the filters
+    ///
+    /// Called inside transactions, before the write becomes visible;
+    /// repeating it on a transaction retry is harmless.
+    fn note_node(&self, node: &Node) {
+        if let Some(filters) = self.filters.read().as_ref() {
+            filters.note_node(node);
+        }
+    }
+    
+    /// Scan node IDs and labels into new filters
+    fn build_filters(&self) -> Result<NodeFilters> {
+        let config = self.bloom_config;
+        let filters = NodeFilters {
+            rate: config.false_positive_rate,
+            nodes: BloomFilter::new(config.expected_items.max(self.nodes.len() * 2), config.false_positive_rate),
+            labels: BloomFilter::new(1024.max(self.label_index.len() * 2), config.false_positive_rate),
+        };
+        for key in self.nodes.iter().keys() {
+            let key = key.map_err(|e| DeepGraphError::StorageError(format!("Failed to scan nodes: {}", e)))?;
+            filters.nodes.insert(&key);
+        }
+        for key in self.label_index.iter().keys() {
+            let key = key.map_err(|e| DeepGraphError::StorageError(format!("Failed to scan labels: {}", e)))?;
+            filters.labels.insert(&key);
+        }
+        info!(
+            "Built bloom filters: {} node IDs ({} bytes), {} labels",
+            filters.nodes.len(),
+            filters.nodes.size_bytes(),
+            filters.labels.len()
+        );
+        Ok(filters)
+    }
+    
+    /// Load the filters saved by the last flush, or build them
+    fn open_filters(&self) {
+        let saved = match self.db.get(BLOOM_FILTERS_KEY) {
+            Ok(saved) => saved,
+            Err(e) => {
+                warn!("Failed to read saved bloom filters: {}", e);
+                None
+            }
+        };
+        // Whether used or not, a saved copy must go before the next write
+        self.filters_saved.store(saved.is_some(), Ordering::Release);
+        let loaded = saved.and_then(|bytes| {
+            let (rate, nodes, labels) = bincode::deserialize::<(f64, Vec<u8>, Vec<u8>)>(&bytes).ok()?;
+            if rate != self.bloom_config.false_positive_rate {
+                return None;
+            }
+            Some(NodeFilters {
+                rate,
+                nodes: BloomFilter::from_bytes(&nodes).ok()?,
+                labels: BloomFilter::from_bytes(&labels).ok()?,
+            })
+        });
+        match loaded {
+            Some(filters) => {
+                debug!("Loaded saved bloom filters");
+                *self.filters.write() = Some(filters);
+            }
+            None => self.refresh_filters(),
+        }
+    }
+    
+    /// Rebuild the filters, turning them off if that fails
+    fn refresh_filters(&self) {
+        if let Err(e) = self.rebuild_bloom_filters() {
+            warn!("Disabling bloom filters: {}", e);
+            *self.filters.write() = None;
+        }
+    }
+    
+    /// Rebuild the filters larger once a write filled them up
+    fn grow_filters_if_full(&self) {
+        let full = self.filters.read().as_ref().is_some_and(NodeFilters::is_full);
+        if full {
+            info!("Bloom filters are full, rebuilding");
+            self.refresh_filters();
+        }
+    }
+    
+    /// Save the filters into the default tree; callers hold the backup gate
+    /// exclusively so no write can slip in between
+    fn save_filters(&self) -> Result<()> {
+        let filters = self.filters.read();
+        let Some(filters) = filters.as_ref() else {
+            return Ok(());
+        };
+        let bytes = bincode::serialize(&(
+            filters.rate,
+            filters.nodes.to_bytes()?,
+            filters.labels.to_bytes()?,
+        ))
+        .map_err(|e| DeepGraphError::SerializationError(format!("Failed to serialize bloom filters: {}", e)))?;
+        self.db.insert(BLOOM_FILTERS_KEY, bytes)
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to save bloom filters: {}", e)))?;
+        self.filters_saved.store(true, Ordering::Release);
+        Ok(())
+    }
+    
+    /// Drop the saved filters before the first write after a save
+    ///
+    /// sled persists writes in order, so no state in which the write is
+    /// durable still holds the outdated filters.
+    fn invalidate_saved_filters(&self) -> Result<()> {
+        if self.filters_saved.swap(false, Ordering::AcqRel) {
+            self.db.remove(BLOOM_FILTERS_KEY)
+                .map_err(|e| DeepGraphError::StorageError(format!("Failed to drop saved bloom filters: {}", e)))?;
+        }
+        Ok(())
+    }
+    
+    /// Validate node writes against a schema registry
+    pub fn with_schema(mut self, schema: Arc<SchemaRegistry>) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+    
+    /// Schema registry node writes are validated against, if any
+    pub fn schema(&self) -> Option<&Arc<SchemaRegistry>> {
+        self.schema.as_ref()
+    }
+    
+    fn conform(&self, node: Node) -> Result<Node> {
+        match &self.schema {
+            Some(schema) => schema.conform(node),
+            None => Ok(node),
+        }
     }
     
     /// Flush all pending writes to disk
@@ -108,18 +464,387 @@ impl DiskStorage {
     /// Ensures all data is persisted. Called automatically on important operations,
     /// but can be called manually for explicit durability.
     pub fn flush(&self) -> Result<()> {
+        let _paused = self.backup_gate.write();
+        self.save_filters()?;
+        self.flush_db()
+    }
+    
+    /// Flush the database without saving the bloom filters
+    fn flush_db(&self) -> Result<()> {
         debug!("Flushing disk storage");
         self.db.flush()
             .map_err(|e| DeepGraphError::StorageError(format!("Failed to flush: {}", e)))?;
+        *self.last_flush.lock() = Instant::now();
         Ok(())
     }
     
+    /// Flush after a mutation if the durability mode asks for it
+    fn flush_after_write(&self) -> Result<()> {
+        match self.durability {
+            DurabilityMode::SyncPerOp => self.flush_db(),
+            DurabilityMode::Periodic(interval) => {
+                let due = self.last_flush.lock().elapsed() >= interval;
+                if due {
+                    self.flush_db()?;
+                }
+                Ok(())
+            }
+            DurabilityMode::Manual => Ok(()),
+        }
+    }
+    
+    /// Apply a batch of inserts atomically across all trees
+    ///
+    /// Node and edge records go through one `sled::Batch` per tree and the
+    /// index entries are merged per key, all inside a single transaction,
+    /// followed by at most one flush. Either every staged write becomes
+    /// visible or none does.
+    pub fn write_batch(&self, batch: DiskWriteBatch) -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        debug!("Applying write batch of {} operations", batch.len());
+        let mut batch = batch;
+        batch.nodes = batch.nodes.into_iter().map(|node| self.conform(node)).collect::<Result<_>>()?;
+        
+        let mut node_records = Batch::default();
+        let mut labels: HashMap<&str, Vec<NodeId>> = HashMap::new();
+        let mut postings: HashMap<Vec<u8>, Vec<NodeId>> = HashMap::new();
+        let staged_nodes: HashSet<NodeId> = batch.nodes.iter().map(|n| n.id()).collect();
+        for node in &batch.nodes {
+            node_records.insert(&node.id().as_bytes()[..], self.serialize_node(node)?);
+            for label in node.labels() {
+                labels.entry(label.as_str()).or_default().push(node.id());
+            }
+            for key in Self::property_keys(node)? {
+                postings.entry(key).or_default().push(node.id());
+            }
+        }
+        
+        let mut edge_records = Batch::default();
+        let mut outgoing = Batch::default();
+        let mut incoming = Batch::default();
+        let mut edge_types: HashMap<&str, Vec<EdgeId>> = HashMap::new();
+        for edge in &batch.edges {
+            edge_records.insert(&edge.id().as_bytes()[..], self.serialize_edge(edge)?);
+            outgoing.insert(&Self::adjacency_key(edge.from(), edge.id())[..], &b""[..]);
+            incoming.insert(&Self::adjacency_key(edge.to(), edge.id())[..], &b""[..]);
+            edge_types.entry(edge.relationship_type()).or_default().push(edge.id());
+        }
+        
+        self.transact(|tx| {
+            // Edge endpoints must exist already or be staged in this batch
+            for edge in &batch.edges {
+                for endpoint in [edge.from(), edge.to()] {
+                    if !staged_nodes.contains(&endpoint) {
+                        tx.require_node(endpoint)?;
+                    }
+                }
+            }
+            
+            for node in &batch.nodes {
+                self.note_node(node);
+            }
+            tx.nodes.apply_batch(&node_records)?;
+            tx.edges.apply_batch(&edge_records)?;
+            for (label, ids) in &labels {
+                Self::merge_ids(tx.labels, label.as_bytes(), ids)?;
+            }
+            for (key, ids) in &postings {
+                Self::merge_ids(tx.properties, key, ids)?;
+            }
+            tx.outgoing.apply_batch(&outgoing)?;
+            tx.incoming.apply_batch(&incoming)?;
+            for (edge_type, ids) in &edge_types {
+                Self::merge_ids(tx.edge_types, edge_type.as_bytes(), ids)?;
+            }
+            Ok(())
+        })?;
+        
+        self.grow_filters_if_full();
+        self.flush_after_write()
+    }
+    
+    /// Run a closure atomically across all graph trees
+    ///
+    /// sled may run the closure more than once on conflict, so it must not
+    /// have side effects outside the transaction.
+    fn transact<T>(
+        &self,
+        f: impl Fn(&GraphTx<'_>) -> ConflictableTransactionResult<T, DeepGraphError>,
+    ) -> Result<T> {
+        let _gate = self.backup_gate.read();
+        self.invalidate_saved_filters()?;
+        let attempts = AtomicU64::new(0);
+        let result = (
+            &self.nodes,
+            &self.edges,
+            &self.label_index,
+            &self.property_index,
+            &self.outgoing_edges,
+            &self.incoming_edges,
+            &self.edge_type_index,
+        )
+            .transaction(|(nodes, edges, labels, properties, outgoing, incoming, edge_types)| {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                f(&GraphTx {
+                    nodes,
+                    edges,
+                    labels,
+                    properties,
+                    outgoing,
+                    incoming,
+                    edge_types,
+                })
+            })
+            .map_err(|e| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(e) => {
+                    DeepGraphError::StorageError(format!("Transaction failed: {}", e))
+                }
+            });
+        
+        // sled re-runs the closure on every conflict
+        let retries = attempts.load(Ordering::Relaxed).saturating_sub(1);
+        metrics::global().record_n(OperatorEvent::TxnRetry, "DiskStorage", retries);
+        result
+    }
+    
+    /// Append IDs to a serialized ID list inside a transaction
+    fn merge_ids<T>(
+        tree: &TransactionalTree,
+        key: &[u8],
+        new_ids: &[T],
+    ) -> ConflictableTransactionResult<(), DeepGraphError>
+    where
+        T: Serialize + DeserializeOwned + PartialEq + Copy,
+    {
+        let abort = |e: bincode::Error| {
+            ConflictableTransactionError::Abort(DeepGraphError::SerializationError(
+                format!("Failed to merge index entry: {}", e),
+            ))
+        };
+        let mut ids: Vec<T> = match tree.get(key)? {
+            Some(bytes) => bincode::deserialize(&bytes).map_err(abort)?,
+            None => Vec::new(),
+        };
+        for id in new_ids {
+            if !ids.contains(id) {
+                ids.push(*id);
+            }
+        }
+        tree.insert(key, bincode::serialize(&ids).map_err(abort)?)?;
+        Ok(())
+    }
+    
+    /// Remove IDs from a serialized ID list inside a transaction
+    ///
+    /// The key is dropped once its list is empty.
+    fn remove_ids<T>(
+        tree: &TransactionalTree,
+        key: &[u8],
+        stale: &[T],
+    ) -> ConflictableTransactionResult<(), DeepGraphError>
+    where
+        T: Serialize + DeserializeOwned + PartialEq,
+    {
+        let abort = |e: bincode::Error| {
+            ConflictableTransactionError::Abort(DeepGraphError::SerializationError(
+                format!("Failed to update index entry: {}", e),
+            ))
+        };
+        let mut ids: Vec<T> = match tree.get(key)? {
+            Some(bytes) => bincode::deserialize(&bytes).map_err(abort)?,
+            None => return Ok(()),
+        };
+        ids.retain(|id| !stale.contains(id));
+        if ids.is_empty() {
+            tree.remove(key)?;
+        } else {
+            tree.insert(key, bincode::serialize(&ids).map_err(abort)?)?;
+        }
+        Ok(())
+    }
+    
+    /// Insert a node and its index entries inside a transaction
+    fn add_node_tx(
+        &self,
+        tx: &GraphTx<'_>,
+        node: &Node,
+        bytes: &[u8],
+        postings: &[Vec<u8>],
+    ) -> ConflictableTransactionResult<(), DeepGraphError> {
+        let id = node.id();
+        self.note_node(node);
+        tx.nodes.insert(&id.as_bytes()[..], bytes)?;
+        for label in node.labels() {
+            Self::merge_ids(tx.labels, label.as_bytes(), &[id])?;
+        }
+        for key in postings {
+            Self::merge_ids(tx.properties, key, &[id])?;
+        }
+        Ok(())
+    }
+    
+    /// Replace a node and move its index entries inside a transaction
+    fn update_node_tx(
+        &self,
+        tx: &GraphTx<'_>,
+        node: &Node,
+        bytes: &[u8],
+    ) -> ConflictableTransactionResult<(), DeepGraphError> {
+        let id = node.id();
+        let old_node = match tx.nodes.get(id.as_bytes())? {
+            Some(old) => self.deserialize_node(&old).map_err(ConflictableTransactionError::Abort)?,
+            None => {
+                return Err(ConflictableTransactionError::Abort(DeepGraphError::NotFound(
+                    format!("Node {} not found", id),
+                )))
+            }
+        };
+        
+        self.note_node(node);
+        
+        // Move the node between label index entries
+        for label in old_node.labels() {
+            if !node.has_label(label) {
+                Self::remove_ids(tx.labels, label.as_bytes(), &[id])?;
+            }
+        }
+        for label in node.labels() {
+            Self::merge_ids(tx.labels, label.as_bytes(), &[id])?;
+        }
+        
+        // Move the node between property postings whose value changed
+        for (key, value) in old_node.properties() {
+            if node.get_property(key) != Some(value) {
+                let stale = Self::property_key(key, value).map_err(ConflictableTransactionError::Abort)?;
+                Self::remove_ids(tx.properties, &stale, &[id])?;
+            }
+        }
+        for (key, value) in node.properties() {
+            if old_node.get_property(key) != Some(value) {
+                let posting = Self::property_key(key, value).map_err(ConflictableTransactionError::Abort)?;
+                Self::merge_ids(tx.properties, &posting, &[id])?;
+            }
+        }
+        
+        tx.nodes.insert(&id.as_bytes()[..], bytes)?;
+        Ok(())
+    }
+    
+    /// Remove a node, its index entries and `incident` edges inside a transaction
+    ///
+    /// Transactional trees cannot be scanned, so the caller collects the
+    /// incident edges beforehand.
+    fn delete_node_tx(
+        &self,
+        tx: &GraphTx<'_>,
+        id: NodeId,
+        incident: &[EdgeId],
+    ) -> ConflictableTransactionResult<(), DeepGraphError> {
+        let node = match tx.nodes.remove(&id.as_bytes()[..])? {
+            Some(bytes) => self.deserialize_node(&bytes).map_err(ConflictableTransactionError::Abort)?,
+            None => {
+                return Err(ConflictableTransactionError::Abort(DeepGraphError::NotFound(
+                    format!("Node {} not found", id),
+                )))
+            }
+        };
+        
+        for label in node.labels() {
+            Self::remove_ids(tx.labels, label.as_bytes(), &[id])?;
+        }
+        for key in Self::property_keys(&node).map_err(ConflictableTransactionError::Abort)? {
+            Self::remove_ids(tx.properties, &key, &[id])?;
+        }
+        
+        // Remove all edges connected to this node
+        for &edge_id in incident {
+            self.delete_edge_tx(tx, edge_id)?;
+        }
+        Ok(())
+    }
+    
+    /// Delete edges still attached to a deleted node
+    ///
+    /// An edge added between the scan for incident edges and the commit is
+    /// still attached; no new ones can appear now that the node is gone.
+    fn delete_stragglers(&self, id: NodeId) -> Result<()> {
+        let mut stragglers = self.get_outgoing_edge_ids(id)?;
+        stragglers.extend(self.get_incoming_edge_ids(id)?);
+        for edge_id in stragglers {
+            self.transact(|tx| self.delete_edge_tx(tx, edge_id))?;
+        }
+        Ok(())
+    }
+    
+    /// Insert an edge between existing nodes inside a transaction
+    fn add_edge_tx(
+        &self,
+        tx: &GraphTx<'_>,
+        edge: &Edge,
+        bytes: &[u8],
+    ) -> ConflictableTransactionResult<(), DeepGraphError> {
+        let id = edge.id();
+        tx.require_node(edge.from())?;
+        tx.require_node(edge.to())?;
+        
+        tx.edges.insert(&id.as_bytes()[..], bytes)?;
+        tx.outgoing.insert(&Self::adjacency_key(edge.from(), id)[..], &b""[..])?;
+        tx.incoming.insert(&Self::adjacency_key(edge.to(), id)[..], &b""[..])?;
+        Self::merge_ids(tx.edge_types, edge.relationship_type().as_bytes(), &[id])?;
+        Ok(())
+    }
+    
+    /// Replace an edge inside a transaction
+    fn update_edge_tx(
+        &self,
+        tx: &GraphTx<'_>,
+        edge: &Edge,
+        bytes: &[u8],
+    ) -> ConflictableTransactionResult<(), DeepGraphError> {
+        let id = edge.id();
+        let old_edge = match tx.edges.get(id.as_bytes())? {
+            Some(old) => self.deserialize_edge(&old).map_err(ConflictableTransactionError::Abort)?,
+            None => {
+                return Err(ConflictableTransactionError::Abort(DeepGraphError::NotFound(
+                    format!("Edge {} not found", id),
+                )))
+            }
+        };
+        
+        if old_edge.relationship_type() != edge.relationship_type() {
+            Self::remove_ids(tx.edge_types, old_edge.relationship_type().as_bytes(), &[id])?;
+            Self::merge_ids(tx.edge_types, edge.relationship_type().as_bytes(), &[id])?;
+        }
+        
+        tx.edges.insert(&id.as_bytes()[..], bytes)?;
+        Ok(())
+    }
+    
+    /// Remove an edge and its index entries inside a transaction
+    ///
+    /// Returns false if the edge did not exist.
+    fn delete_edge_tx(&self, tx: &GraphTx<'_>, id: EdgeId) -> ConflictableTransactionResult<bool, DeepGraphError> {
+        let edge = match tx.edges.remove(&id.as_bytes()[..])? {
+            Some(bytes) => self.deserialize_edge(&bytes).map_err(ConflictableTransactionError::Abort)?,
+            None => return Ok(false),
+        };
+        tx.outgoing.remove(Self::adjacency_key(edge.from(), id).as_slice())?;
+        tx.incoming.remove(Self::adjacency_key(edge.to(), id).as_slice())?;
+        Self::remove_ids(tx.edge_types, edge.relationship_type().as_bytes(), &[id])?;
+        Ok(true)
+    }
+    
     /// Get database statistics
     pub fn stats(&self) -> DiskStorageStats {
         DiskStorageStats {
             node_count: self.nodes.len(),
             edge_count: self.edges.len(),
             size_on_disk_bytes: self.db.size_on_disk().unwrap_or(0),
+            index_repairs: self.index_repairs.load(Ordering::Relaxed),
+            bloom_skips: self.bloom_skips.load(Ordering::Relaxed),
         }
     }
     
@@ -127,28 +852,38 @@ impl DiskStorage {
     
     /// Serialize a node to bytes
     fn serialize_node(&self, node: &Node) -> Result<Vec<u8>> {
-        bincode::serialize(node)
-            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to serialize node: {}", e)))
+        let bytes = bincode::serialize(node)
+            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to serialize node: {}", e)))?;
+        self.compress(bytes)
     }
     
     /// Deserialize a node from bytes
     fn deserialize_node(&self, bytes: &[u8]) -> Result<Node> {
-        bincode::deserialize(bytes)
+        bincode::deserialize(&compression::decompress(bytes)?)
             .map_err(|e| DeepGraphError::SerializationError(format!("Failed to deserialize node: {}", e)))
     }
     
     /// Serialize an edge to bytes
     fn serialize_edge(&self, edge: &Edge) -> Result<Vec<u8>> {
-        bincode::serialize(edge)
-            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to serialize edge: {}", e)))
+        let bytes = bincode::serialize(edge)
+            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to serialize edge: {}", e)))?;
+        self.compress(bytes)
     }
     
     /// Deserialize an edge from bytes
     fn deserialize_edge(&self, bytes: &[u8]) -> Result<Edge> {
-        bincode::deserialize(bytes)
+        bincode::deserialize(&compression::decompress(bytes)?)
             .map_err(|e| DeepGraphError::SerializationError(format!("Failed to deserialize edge: {}", e)))
     }
     
+    /// Compress a serialized record if compression is enabled
+    fn compress(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        match &self.compression {
+            Some(compression) => compression.compress(bytes),
+            None => Ok(bytes),
+        }
+    }
+    
     /// Serialize a vector of NodeIds
     fn serialize_node_ids(&self, ids: &[NodeId]) -> Result<Vec<u8>> {
         bincode::serialize(ids)
@@ -161,12 +896,6 @@ impl DiskStorage {
             .map_err(|e| DeepGraphError::SerializationError(format!("Failed to deserialize node IDs: {}", e)))
     }
     
-    /// Serialize a vector of EdgeIds
-    fn serialize_edge_ids(&self, ids: &[EdgeId]) -> Result<Vec<u8>> {
-        bincode::serialize(ids)
-            .map_err(|e| DeepGraphError::SerializationError(format!("Failed to serialize edge IDs: {}", e)))
-    }
-    
     /// Deserialize a vector of EdgeIds
     fn deserialize_edge_ids(&self, bytes: &[u8]) -> Result<Vec<EdgeId>> {
         bincode::deserialize(bytes)
@@ -175,15 +904,53 @@ impl DiskStorage {
     
     // --- Helper methods for index management ---
     
-    /// Add a node to the label index
-    fn add_to_label_index(&self, label: &str, node_id: NodeId) -> Result<()> {
-        let mut ids = self.get_nodes_for_label(label)?;
-        if !ids.contains(&node_id) {
-            ids.push(node_id);
-            let bytes = self.serialize_node_ids(&ids)?;
-            self.label_index.insert(label.as_bytes(), bytes)
-                .map_err(|e| DeepGraphError::StorageError(format!("Failed to update label index: {}", e)))?;
+    /// Property index key: length-prefixed property name, then the encoded value
+    ///
+    /// The length prefix keeps `("ab", "c")` and `("a", "bc")` apart and lets
+    /// all postings of one property be scanned by prefix.
+    fn property_key(key: &str, value: &PropertyValue) -> Result<Vec<u8>> {
+        let mut bytes = Self::property_prefix(key);
+        bytes.extend_from_slice(&value.index_key());
+        Ok(bytes)
+    }
+    
+    /// Key prefix shared by every value of a property
+    fn property_prefix(key: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + key.len());
+        bytes.extend_from_slice(&(key.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(key.as_bytes());
+        bytes
+    }
+    
+    /// Property index keys of every property on a node
+    fn property_keys(node: &Node) -> Result<Vec<Vec<u8>>> {
+        node.properties()
+            .iter()
+            .map(|(key, value)| Self::property_key(key, value))
+            .collect()
+    }
+    
+    /// Rebuild the property index from the node records
+    pub fn rebuild_property_index(&self) -> Result<()> {
+        info!("Rebuilding property index over {} nodes", self.nodes.len());
+        self.property_index.clear()
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to clear property index: {}", e)))?;
+        
+        let mut postings: HashMap<Vec<u8>, Vec<NodeId>> = HashMap::new();
+        for node in self.get_all_nodes() {
+            for key in Self::property_keys(&node)? {
+                postings.entry(key).or_default().push(node.id());
+            }
+        }
+        
+        let mut batch = Batch::default();
+        for (key, ids) in postings {
+            batch.insert(key, self.serialize_node_ids(&ids)?);
         }
+        self.property_index.apply_batch(batch)
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to write property index: {}", e)))?;
+        self.db.insert(PROPERTY_INDEX_MARKER, &[1u8][..])
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to write property index marker: {}", e)))?;
         Ok(())
     }
     
@@ -199,6 +966,9 @@ impl DiskStorage {
     
     /// Get all nodes with a specific label
     fn get_nodes_for_label(&self, label: &str) -> Result<Vec<NodeId>> {
+        if self.ruled_out(|filters| &filters.labels, label.as_bytes()) {
+            return Ok(Vec::new());
+        }
         match self.label_index.get(label.as_bytes())
             .map_err(|e| DeepGraphError::StorageError(format!("Failed to read label index: {}", e)))? {
             Some(bytes) => self.deserialize_node_ids(&bytes),
@@ -206,77 +976,85 @@ impl DiskStorage {
         }
     }
     
-    /// Add an edge to outgoing edges index
-    fn add_to_outgoing_edges(&self, node_id: NodeId, edge_id: EdgeId) -> Result<()> {
-        let mut edges = self.get_outgoing_edge_ids(node_id)?;
-        if !edges.contains(&edge_id) {
-            edges.push(edge_id);
-            let bytes = self.serialize_edge_ids(&edges)?;
-            self.outgoing_edges.insert(node_id.as_bytes(), bytes)
-                .map_err(|e| DeepGraphError::StorageError(format!("Failed to update outgoing edges: {}", e)))?;
+    /// Remove a label index entry that points at a node which no longer exists
+    ///
+    /// Mutations are transactional, but databases written by older versions
+    /// may still carry entries left behind by an interrupted multi-tree update.
+    /// Rather than silently dropping the node from results on every lookup,
+    /// the stale entry is removed so the index heals itself.
+    fn repair_label_index(&self, label: &str, node_id: NodeId) {
+        warn!("Label index '{}' references missing node {}, removing stale entry", label, node_id);
+        match self.remove_from_label_index(label, node_id) {
+            Ok(()) => {
+                self.index_repairs.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => warn!("Failed to repair label index '{}': {}", label, e),
         }
-        Ok(())
     }
     
-    /// Add an edge to incoming edges index
-    fn add_to_incoming_edges(&self, node_id: NodeId, edge_id: EdgeId) -> Result<()> {
-        let mut edges = self.get_incoming_edge_ids(node_id)?;
-        if !edges.contains(&edge_id) {
-            edges.push(edge_id);
-            let bytes = self.serialize_edge_ids(&edges)?;
-            self.incoming_edges.insert(node_id.as_bytes(), bytes)
-                .map_err(|e| DeepGraphError::StorageError(format!("Failed to update incoming edges: {}", e)))?;
-        }
-        Ok(())
+    /// Adjacency key: the node ID followed by the edge ID
+    ///
+    /// Each edge is its own entry, so inserting or removing one never
+    /// rewrites the rest of a node's adjacency, and a prefix scan over the
+    /// node ID lists its edges.
+    fn adjacency_key(node_id: NodeId, edge_id: EdgeId) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        key[..16].copy_from_slice(node_id.as_bytes());
+        key[16..].copy_from_slice(edge_id.as_bytes());
+        key
+    }
+    
+    /// Edge IDs under a node's prefix in an adjacency tree
+    fn scan_adjacency(tree: &Tree, node_id: NodeId) -> Result<Vec<EdgeId>> {
+        tree.scan_prefix(node_id.as_bytes())
+            .keys()
+            .map(|key| {
+                let key = key.map_err(|e| DeepGraphError::StorageError(format!("Failed to scan adjacency: {}", e)))?;
+                let edge_bytes: [u8; 16] = key[16..].try_into().map_err(|_| {
+                    DeepGraphError::StorageError(format!("Malformed adjacency key of {} bytes", key.len()))
+                })?;
+                Ok(EdgeId::from_uuid(uuid::Uuid::from_bytes(edge_bytes)))
+            })
+            .collect()
     }
     
     /// Get outgoing edge IDs for a node
     fn get_outgoing_edge_ids(&self, node_id: NodeId) -> Result<Vec<EdgeId>> {
-        match self.outgoing_edges.get(node_id.as_bytes())
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to read outgoing edges: {}", e)))? {
-            Some(bytes) => self.deserialize_edge_ids(&bytes),
-            None => Ok(Vec::new()),
-        }
+        Self::scan_adjacency(&self.outgoing_edges, node_id)
     }
     
     /// Get incoming edge IDs for a node
     fn get_incoming_edge_ids(&self, node_id: NodeId) -> Result<Vec<EdgeId>> {
-        match self.incoming_edges.get(node_id.as_bytes())
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to read incoming edges: {}", e)))? {
-            Some(bytes) => self.deserialize_edge_ids(&bytes),
-            None => Ok(Vec::new()),
-        }
+        Self::scan_adjacency(&self.incoming_edges, node_id)
     }
     
-    /// Remove an edge from outgoing edges index
-    fn remove_from_outgoing_edges(&self, node_id: NodeId, edge_id: EdgeId) -> Result<()> {
-        let mut edges = self.get_outgoing_edge_ids(node_id)?;
-        edges.retain(|&id| id != edge_id);
-        let bytes = self.serialize_edge_ids(&edges)?;
-        self.outgoing_edges.insert(node_id.as_bytes(), bytes)
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to update outgoing edges: {}", e)))?;
-        Ok(())
-    }
-    
-    /// Remove an edge from incoming edges index
-    fn remove_from_incoming_edges(&self, node_id: NodeId, edge_id: EdgeId) -> Result<()> {
-        let mut edges = self.get_incoming_edge_ids(node_id)?;
-        edges.retain(|&id| id != edge_id);
-        let bytes = self.serialize_edge_ids(&edges)?;
-        self.incoming_edges.insert(node_id.as_bytes(), bytes)
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to update incoming edges: {}", e)))?;
-        Ok(())
-    }
-    
-    /// Add an edge to the edge type index
-    fn add_to_edge_type_index(&self, edge_type: &str, edge_id: EdgeId) -> Result<()> {
-        let mut ids = self.get_edges_for_type(edge_type)?;
-        if !ids.contains(&edge_id) {
-            ids.push(edge_id);
-            let bytes = self.serialize_edge_ids(&ids)?;
-            self.edge_type_index.insert(edge_type.as_bytes(), bytes)
-                .map_err(|e| DeepGraphError::StorageError(format!("Failed to update edge type index: {}", e)))?;
+    /// Rebuild both adjacency trees from the edges tree
+    ///
+    /// Converts databases written with one serialized edge list per node to
+    /// one sub-key per edge.
+    fn rebuild_adjacency(&self) -> Result<()> {
+        info!("Rebuilding adjacency over {} edges", self.edges.len());
+        let clear = |tree: &Tree| {
+            tree.clear()
+                .map_err(|e| DeepGraphError::StorageError(format!("Failed to clear adjacency: {}", e)))
+        };
+        clear(&self.outgoing_edges)?;
+        clear(&self.incoming_edges)?;
+        
+        let mut outgoing = Batch::default();
+        let mut incoming = Batch::default();
+        for edge in self.iter_edges() {
+            outgoing.insert(&Self::adjacency_key(edge.from(), edge.id())[..], &b""[..]);
+            incoming.insert(&Self::adjacency_key(edge.to(), edge.id())[..], &b""[..]);
         }
+        let write = |tree: &Tree, batch: Batch| {
+            tree.apply_batch(batch)
+                .map_err(|e| DeepGraphError::StorageError(format!("Failed to write adjacency: {}", e)))
+        };
+        write(&self.outgoing_edges, outgoing)?;
+        write(&self.incoming_edges, incoming)?;
+        self.db.insert(ADJACENCY_SUBKEYS_MARKER, &[1u8][..])
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to write adjacency marker: {}", e)))?;
         Ok(())
     }
     
@@ -290,35 +1068,67 @@ impl DiskStorage {
     }
 }
 
+/// Transactional views of every DiskStorage tree
+struct GraphTx<'a> {
+    nodes: &'a TransactionalTree,
+    edges: &'a TransactionalTree,
+    labels: &'a TransactionalTree,
+    properties: &'a TransactionalTree,
+    outgoing: &'a TransactionalTree,
+    incoming: &'a TransactionalTree,
+    edge_types: &'a TransactionalTree,
+}
+
+impl GraphTx<'_> {
+    /// Abort with `NotFound` unless the node exists
+    fn require_node(&self, id: NodeId) -> ConflictableTransactionResult<(), DeepGraphError> {
+        if self.nodes.get(id.as_bytes())?.is_none() {
+            return Err(ConflictableTransactionError::Abort(DeepGraphError::NotFound(
+                format!("Node {} not found", id),
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A batch operation with its records serialized ahead of the transaction
+enum PreparedOp {
+    AddNode { node: Node, bytes: Vec<u8>, postings: Vec<Vec<u8>> },
+    UpdateNode { node: Node, bytes: Vec<u8> },
+    DeleteNode { id: NodeId, incident: Vec<EdgeId> },
+    AddEdge { edge: Edge, bytes: Vec<u8> },
+    UpdateEdge { edge: Edge, bytes: Vec<u8> },
+    DeleteEdge(EdgeId),
+}
+
 /// Statistics about disk storage
 #[derive(Debug, Clone)]
 pub struct DiskStorageStats {
     pub node_count: usize,
     pub edge_count: usize,
     pub size_on_disk_bytes: u64,
+    /// Stale index entries removed by read-repair since the storage was opened
+    pub index_repairs: u64,
+    /// Lookups of missing nodes or labels answered by a bloom filter
+    pub bloom_skips: u64,
 }
 
 // --- Implement StorageBackend trait ---
 
-use crate::storage::StorageBackend;
+use crate::storage::{batch, CostConstants, GraphOp, StorageBackend};
 
 impl StorageBackend for DiskStorage {
     fn add_node(&self, node: Node) -> Result<NodeId> {
+        let node = self.conform(node)?;
         let id = node.id();
         debug!("Adding node {} to disk storage", id);
         
-        // Serialize and store node
         let bytes = self.serialize_node(&node)?;
-        self.nodes.insert(id.as_bytes(), bytes)
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to insert node: {}", e)))?;
+        let postings = Self::property_keys(&node)?;
+        self.transact(|tx| self.add_node_tx(tx, &node, &bytes, &postings))?;
         
-        // Update label indices
-        for label in node.labels() {
-            self.add_to_label_index(label, id)?;
-        }
-        
-        // Flush to ensure durability
-        self.flush()?;
+        self.grow_filters_if_full();
+        self.flush_after_write()?;
         
         debug!("Node {} added successfully", id);
         Ok(id)
@@ -327,6 +1137,9 @@ impl StorageBackend for DiskStorage {
     fn get_node(&self, id: NodeId) -> Result<Node> {
         debug!("Getting node {} from disk storage", id);
         
+        if self.ruled_out(|filters| &filters.nodes, id.as_bytes()) {
+            return Err(DeepGraphError::NotFound(format!("Node {} not found", id)));
+        }
         match self.nodes.get(id.as_bytes())
             .map_err(|e| DeepGraphError::StorageError(format!("Failed to get node: {}", e)))? {
             Some(bytes) => {
@@ -341,34 +1154,15 @@ impl StorageBackend for DiskStorage {
     }
     
     fn update_node(&self, node: Node) -> Result<()> {
+        let node = self.conform(node)?;
         let id = node.id();
         debug!("Updating node {} in disk storage", id);
         
-        // Check if node exists
-        if !self.nodes.contains_key(id.as_bytes())
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to check node existence: {}", e)))? {
-            return Err(DeepGraphError::NotFound(format!("Node {} not found", id)));
-        }
-        
-        // Get old node to update indices
-        let old_node = self.get_node(id)?;
-        
-        // Remove old labels from index
-        for label in old_node.labels() {
-            self.remove_from_label_index(label, id)?;
-        }
-        
-        // Add new labels to index
-        for label in node.labels() {
-            self.add_to_label_index(label, id)?;
-        }
-        
-        // Update node
         let bytes = self.serialize_node(&node)?;
-        self.nodes.insert(id.as_bytes(), bytes)
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to update node: {}", e)))?;
+        self.transact(|tx| self.update_node_tx(tx, &node, &bytes))?;
         
-        self.flush()?;
+        self.grow_filters_if_full();
+        self.flush_after_write()?;
         
         debug!("Node {} updated successfully", id);
         Ok(())
@@ -377,30 +1171,14 @@ impl StorageBackend for DiskStorage {
     fn delete_node(&self, id: NodeId) -> Result<()> {
         debug!("Deleting node {} from disk storage", id);
         
-        // Get node to update indices
-        let node = self.get_node(id)?;
-        
-        // Remove from label indices
-        for label in node.labels() {
-            self.remove_from_label_index(label, id)?;
-        }
-        
-        // Remove all edges connected to this node
-        let outgoing = self.get_outgoing_edge_ids(id)?;
-        for edge_id in outgoing {
-            self.delete_edge(edge_id)?;
-        }
-        
-        let incoming = self.get_incoming_edge_ids(id)?;
-        for edge_id in incoming {
-            self.delete_edge(edge_id)?;
-        }
+        // Transactional trees cannot be scanned, so collect the incident
+        // edges first and delete them together with the node
+        let mut incident = self.get_outgoing_edge_ids(id)?;
+        incident.extend(self.get_incoming_edge_ids(id)?);
+        self.transact(|tx| self.delete_node_tx(tx, id, &incident))?;
+        self.delete_stragglers(id)?;
         
-        // Remove node
-        self.nodes.remove(id.as_bytes())
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to delete node: {}", e)))?;
-        
-        self.flush()?;
+        self.flush_after_write()?;
         
         debug!("Node {} deleted successfully", id);
         Ok(())
@@ -410,23 +1188,10 @@ impl StorageBackend for DiskStorage {
         let id = edge.id();
         debug!("Adding edge {} to disk storage", id);
         
-        // Verify nodes exist
-        let _from = self.get_node(edge.from())?;
-        let _to = self.get_node(edge.to())?;
-        
-        // Serialize and store edge
         let bytes = self.serialize_edge(&edge)?;
-        self.edges.insert(id.as_bytes(), bytes)
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to insert edge: {}", e)))?;
-        
-        // Update adjacency indices
-        self.add_to_outgoing_edges(edge.from(), id)?;
-        self.add_to_incoming_edges(edge.to(), id)?;
+        self.transact(|tx| self.add_edge_tx(tx, &edge, &bytes))?;
         
-        // Update edge type index
-        self.add_to_edge_type_index(edge.relationship_type(), id)?;
-        
-        self.flush()?;
+        self.flush_after_write()?;
         
         debug!("Edge {} added successfully", id);
         Ok(id)
@@ -452,18 +1217,10 @@ impl StorageBackend for DiskStorage {
         let id = edge.id();
         debug!("Updating edge {} in disk storage", id);
         
-        // Check if edge exists
-        if !self.edges.contains_key(id.as_bytes())
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to check edge existence: {}", e)))? {
-            return Err(DeepGraphError::NotFound(format!("Edge {} not found", id)));
-        }
-        
-        // Update edge
         let bytes = self.serialize_edge(&edge)?;
-        self.edges.insert(id.as_bytes(), bytes)
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to update edge: {}", e)))?;
+        self.transact(|tx| self.update_edge_tx(tx, &edge, &bytes))?;
         
-        self.flush()?;
+        self.flush_after_write()?;
         
         debug!("Edge {} updated successfully", id);
         Ok(())
@@ -472,18 +1229,13 @@ impl StorageBackend for DiskStorage {
     fn delete_edge(&self, id: EdgeId) -> Result<()> {
         debug!("Deleting edge {} from disk storage", id);
         
-        // Get edge to update indices
-        let edge = self.get_edge(id)?;
-        
-        // Remove from adjacency indices
-        self.remove_from_outgoing_edges(edge.from(), id)?;
-        self.remove_from_incoming_edges(edge.to(), id)?;
-        
-        // Remove edge
-        self.edges.remove(id.as_bytes())
-            .map_err(|e| DeepGraphError::StorageError(format!("Failed to delete edge: {}", e)))?;
+        let deleted = self.transact(|tx| self.delete_edge_tx(tx, id))?;
+        if !deleted {
+            warn!("Edge {} not found", id);
+            return Err(DeepGraphError::NotFound(format!("Edge {} not found", id)));
+        }
         
-        self.flush()?;
+        self.flush_after_write()?;
         
         debug!("Edge {} deleted successfully", id);
         Ok(())
@@ -494,9 +1246,15 @@ impl StorageBackend for DiskStorage {
         
         match self.get_nodes_for_label(label) {
             Ok(ids) => {
-                ids.into_iter()
-                    .filter_map(|id| self.get_node(id).ok())
-                    .collect()
+                let mut nodes = Vec::with_capacity(ids.len());
+                for id in ids {
+                    match self.get_node(id) {
+                        Ok(node) => nodes.push(node),
+                        Err(e) if e.is_not_found() => self.repair_label_index(label, id),
+                        Err(e) => warn!("Failed to read node {}: {}", id, e),
+                    }
+                }
+                nodes
             }
             Err(e) => {
                 warn!("Failed to get nodes by label: {}", e);
@@ -507,21 +1265,40 @@ impl StorageBackend for DiskStorage {
     
     fn get_all_nodes(&self) -> Vec<Node> {
         debug!("Getting all nodes from disk storage");
-        
-        self.nodes
-            .iter()
-            .filter_map(|result| {
-                match result {
-                    Ok((_key, value)) => {
-                        self.deserialize_node(&value).ok()
-                    }
-                    Err(e) => {
-                        warn!("Failed to iterate node: {}", e);
-                        None
-                    }
-                }
-            })
-            .collect()
+        self.iter_nodes().collect()
+    }
+    
+    fn get_all_edges(&self) -> Vec<Edge> {
+        debug!("Getting all edges from disk storage");
+        self.iter_edges().collect()
+    }
+    
+    fn get_edges_by_type(&self, relationship_type: &str) -> Vec<Edge> {
+        DiskStorage::get_edges_by_type(self, relationship_type)
+    }
+    
+    fn get_nodes_by_property(&self, key: &str, value: &PropertyValue) -> Vec<Node> {
+        DiskStorage::get_nodes_by_property(self, key, value)
+    }
+    
+    fn iter_nodes(&self) -> Box<dyn Iterator<Item = Node> + '_> {
+        Box::new(self.nodes.iter().filter_map(move |result| match result {
+            Ok((_key, value)) => self.deserialize_node(&value).ok(),
+            Err(e) => {
+                warn!("Failed to iterate node: {}", e);
+                None
+            }
+        }))
+    }
+    
+    fn iter_edges(&self) -> Box<dyn Iterator<Item = Edge> + '_> {
+        Box::new(self.edges.iter().filter_map(move |result| match result {
+            Ok((_key, value)) => self.deserialize_edge(&value).ok(),
+            Err(e) => {
+                warn!("Failed to iterate edge: {}", e);
+                None
+            }
+        }))
     }
     
     fn get_outgoing_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
@@ -555,17 +1332,115 @@ impl StorageBackend for DiskStorage {
     fn edge_count(&self) -> usize {
         self.edges.len()
     }
+    
+    fn cost_constants(&self) -> CostConstants {
+        CostConstants::disk()
+    }
+    
+    fn flush(&self) -> Result<()> {
+        DiskStorage::flush(self)
+    }
+    
+    /// Apply a batch as a single sled transaction
+    ///
+    /// Records are serialized up front and every operation runs in one
+    /// transaction, so readers never see part of a batch and a rejected
+    /// batch leaves nothing to undo.
+    fn apply_batch(&self, ops: Vec<GraphOp>) -> Result<()> {
+        batch::validate_batch(self, &ops)?;
+        debug!("Applying batch of {} operations in one transaction", ops.len());
+        
+        let mut prepared = Vec::with_capacity(ops.len());
+        let mut added_edges: Vec<&Edge> = Vec::new();
+        let mut deleted_nodes = Vec::new();
+        for op in &ops {
+            prepared.push(match op {
+                GraphOp::AddNode(node) => {
+                    let node = self.conform(node.clone())?;
+                    let bytes = self.serialize_node(&node)?;
+                    let postings = Self::property_keys(&node)?;
+                    PreparedOp::AddNode { node, bytes, postings }
+                }
+                GraphOp::UpdateNode(node) => {
+                    let node = self.conform(node.clone())?;
+                    let bytes = self.serialize_node(&node)?;
+                    PreparedOp::UpdateNode { node, bytes }
+                }
+                GraphOp::DeleteNode(id) => {
+                    // Edges added earlier in the batch are not on disk yet
+                    let mut incident = self.get_outgoing_edge_ids(*id)?;
+                    incident.extend(self.get_incoming_edge_ids(*id)?);
+                    incident.extend(
+                        added_edges
+                            .iter()
+                            .filter(|edge| edge.from() == *id || edge.to() == *id)
+                            .map(|edge| edge.id()),
+                    );
+                    deleted_nodes.push(*id);
+                    PreparedOp::DeleteNode { id: *id, incident }
+                }
+                GraphOp::AddEdge(edge) => {
+                    added_edges.push(edge);
+                    PreparedOp::AddEdge { edge: edge.clone(), bytes: self.serialize_edge(edge)? }
+                }
+                GraphOp::UpdateEdge(edge) => {
+                    PreparedOp::UpdateEdge { edge: edge.clone(), bytes: self.serialize_edge(edge)? }
+                }
+                GraphOp::DeleteEdge(id) => PreparedOp::DeleteEdge(*id),
+            });
+        }
+        
+        self.transact(|tx| {
+            for op in &prepared {
+                match op {
+                    PreparedOp::AddNode { node, bytes, postings } => self.add_node_tx(tx, node, bytes, postings)?,
+                    PreparedOp::UpdateNode { node, bytes } => self.update_node_tx(tx, node, bytes)?,
+                    PreparedOp::DeleteNode { id, incident } => self.delete_node_tx(tx, *id, incident)?,
+                    PreparedOp::AddEdge { edge, bytes } => self.add_edge_tx(tx, edge, bytes)?,
+                    PreparedOp::UpdateEdge { edge, bytes } => self.update_edge_tx(tx, edge, bytes)?,
+                    PreparedOp::DeleteEdge(id) => {
+                        if !self.delete_edge_tx(tx, *id)? {
+                            return Err(ConflictableTransactionError::Abort(DeepGraphError::NotFound(
+                                format!("Edge {} not found", id),
+                            )));
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        
+        for id in deleted_nodes {
+            self.delete_stragglers(id)?;
+        }
+        self.grow_filters_if_full();
+        self.flush_after_write()
+    }
 }
 
 // Additional helper methods specific to DiskStorage
 
 impl DiskStorage {
-    /// Get all nodes with a specific property value
+    /// Get all nodes with a specific property value, using the property index
     pub fn get_nodes_by_property(&self, key: &str, value: &PropertyValue) -> Vec<Node> {
-        self.get_all_nodes()
-            .into_iter()
-            .filter(|node| node.get_property(key) == Some(value))
-            .collect()
+        let ids = Self::property_key(key, value).and_then(|index_key| {
+            match self.property_index.get(index_key)
+                .map_err(|e| DeepGraphError::StorageError(format!("Failed to read property index: {}", e)))? {
+                Some(bytes) => self.deserialize_node_ids(&bytes),
+                None => Ok(Vec::new()),
+            }
+        });
+        
+        match ids {
+            Ok(ids) => ids.into_iter()
+                .filter_map(|id| self.get_node(id).ok())
+                .filter(|node| node.get_property(key) == Some(value))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to get nodes by property: {}", e);
+                Vec::new()
+            }
+        }
     }
     
     /// Get all edges of a specific type
@@ -602,7 +1477,8 @@ impl DiskStorage {
         
         // Serialize all nodes
         for node in self.get_all_nodes() {
-            let bytes = self.serialize_node(&node)?;
+            let bytes = bincode::serialize(&node)
+                .map_err(|e| DeepGraphError::SerializationError(format!("Failed to serialize node: {}", e)))?;
             snapshot.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
             snapshot.extend_from_slice(&bytes);
         }
@@ -614,6 +1490,7 @@ impl DiskStorage {
         // Serialize all edges
         for result in self.edges.iter() {
             if let Ok((_key, value)) = result {
+                let value = compression::decompress(&value)?;
                 snapshot.extend_from_slice(&(value.len() as u64).to_le_bytes());
                 snapshot.extend_from_slice(&value);
             }
@@ -622,6 +1499,84 @@ impl DiskStorage {
         info!("Snapshot created: {} bytes", snapshot.len());
         Ok(snapshot)
     }
+    
+    /// Copy the database to `path` as a consistent point-in-time backup
+    ///
+    /// Writes wait while the trees are exported, so the copy holds exactly
+    /// the mutations committed before the call. The backup is a plain sled
+    /// directory: open it directly with [`DiskStorage::new`] or copy it to a
+    /// new location with [`DiskStorage::restore_from`]. `path` must not
+    /// exist or must be an empty directory.
+    pub fn backup_to(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.write_backup(path.as_ref(), None)
+    }
+    
+    /// Back up to `path`, recording the WAL LSN the copy corresponds to
+    ///
+    /// After restoring, replay the WAL from the LSN returned by
+    /// [`DiskStorage::backup_wal_lsn`] to catch up with later writes.
+    pub fn backup_to_with_wal_lsn(&self, path: impl AsRef<Path>, lsn: u64) -> Result<()> {
+        self.write_backup(path.as_ref(), Some(lsn))
+    }
+    
+    fn write_backup(&self, path: &Path, wal_lsn: Option<u64>) -> Result<()> {
+        info!("Backing up disk storage to {:?}", path);
+        let _paused = self.backup_gate.write();
+        self.save_filters()?;
+        self.flush_db()?;
+        
+        let copy = Self::copy_db(&self.db, path)?;
+        match wal_lsn {
+            Some(lsn) => copy.insert(BACKUP_WAL_LSN_KEY, &lsn.to_be_bytes()[..]).map(|_| ()),
+            None => copy.remove(BACKUP_WAL_LSN_KEY).map(|_| ()),
+        }
+        .map_err(|e| DeepGraphError::StorageError(format!("Failed to write backup LSN marker: {}", e)))?;
+        copy.flush()
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to flush backup: {}", e)))?;
+        
+        info!("Backup complete: {} nodes, {} edges", self.nodes.len(), self.edges.len());
+        Ok(())
+    }
+    
+    /// Restore a backup into a new database at `target` and open it
+    ///
+    /// The backup itself is left untouched. `target` must not exist or must
+    /// be an empty directory.
+    pub fn restore_from(backup: impl AsRef<Path>, target: impl AsRef<Path>) -> Result<Self> {
+        info!("Restoring backup {:?} to {:?}", backup.as_ref(), target.as_ref());
+        if !backup.as_ref().is_dir() {
+            return Err(DeepGraphError::NotFound(format!("Backup {:?} not found", backup.as_ref())));
+        }
+        {
+            let source = sled::open(backup.as_ref())
+                .map_err(|e| DeepGraphError::StorageError(format!("Failed to open backup: {}", e)))?;
+            let copy = Self::copy_db(&source, target.as_ref())?;
+            copy.flush()
+                .map_err(|e| DeepGraphError::StorageError(format!("Failed to flush restored database: {}", e)))?;
+        }
+        Self::new(target)
+    }
+    
+    /// WAL LSN recorded when this database was written as a backup
+    pub fn backup_wal_lsn(&self) -> Result<Option<u64>> {
+        let marker = self.db.get(BACKUP_WAL_LSN_KEY)
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to read backup LSN marker: {}", e)))?;
+        Ok(marker.and_then(|bytes| <[u8; 8]>::try_from(bytes.as_ref()).ok()).map(u64::from_be_bytes))
+    }
+    
+    /// Export every tree of `source` into a fresh database at `target`
+    fn copy_db(source: &Db, target: &Path) -> Result<Db> {
+        if target.exists() && target.read_dir()?.next().is_some() {
+            return Err(DeepGraphError::InvalidOperation(format!(
+                "Backup target {:?} is not empty",
+                target
+            )));
+        }
+        let copy = sled::open(target)
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to create backup database: {}", e)))?;
+        copy.import(source.export());
+        Ok(copy)
+    }
 }
 
 #[cfg(test)]
@@ -635,6 +1590,25 @@ mod tests {
         (storage, temp_dir)
     }
     
+    /// Open `path` again after dropping a storage on it
+    ///
+    /// sled's flusher thread can hold the directory lock for a moment after
+    /// the drop, so lock failures are retried with backoff.
+    fn reopen(path: &Path) -> DiskStorage {
+        let mut delay = std::time::Duration::from_millis(10);
+        for _ in 0..8 {
+            match DiskStorage::new(path) {
+                Ok(storage) => return storage,
+                Err(e) if e.to_string().contains("could not acquire lock") => {
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                }
+                Err(e) => panic!("Failed to reopen {}: {}", path.display(), e),
+            }
+        }
+        DiskStorage::new(path).unwrap()
+    }
+    
     #[test]
     fn test_create_storage() {
         let (_storage, _temp_dir) = create_test_storage();
@@ -719,6 +1693,27 @@ mod tests {
         assert_eq!(companies.len(), 1);
     }
     
+    #[test]
+    fn test_label_index_read_repair() {
+        let (storage, _temp_dir) = create_test_storage();
+        
+        let id1 = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let id2 = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        
+        // Simulate drift: the node is gone but the index entry survived
+        storage.nodes.remove(id2.as_bytes()).unwrap();
+        
+        let people = storage.get_nodes_by_label("Person");
+        assert_eq!(people.len(), 1);
+        assert_eq!(people[0].id(), id1);
+        assert_eq!(storage.stats().index_repairs, 1);
+        
+        // The stale entry is gone, so a second lookup needs no repair
+        assert_eq!(storage.get_nodes_for_label("Person").unwrap(), vec![id1]);
+        storage.get_nodes_by_label("Person");
+        assert_eq!(storage.stats().index_repairs, 1);
+    }
+    
     #[test]
     fn test_edge_operations() {
         let (storage, _temp_dir) = create_test_storage();
@@ -761,4 +1756,238 @@ mod tests {
         
         assert_eq!(storage.edge_count(), 1);
     }
+
+    #[test]
+    fn test_write_batch_is_atomic() {
+        let (storage, _temp_dir) = create_test_storage();
+        let storage = storage.with_durability(DurabilityMode::Manual);
+        
+        let mut batch = DiskWriteBatch::new();
+        let a = batch.add_node(Node::new(vec!["Person".to_string()]));
+        let b = batch.add_node(Node::new(vec!["Person".to_string()]));
+        batch.add_edge(Edge::new(a, b, "KNOWS".to_string()));
+        storage.write_batch(batch).unwrap();
+        
+        assert_eq!(storage.get_nodes_by_label("Person").len(), 2);
+        assert_eq!(storage.get_outgoing_edges(a).unwrap().len(), 1);
+        assert_eq!(storage.get_edges_by_type("KNOWS").len(), 1);
+        
+        // An edge to a missing node aborts the whole batch
+        let mut batch = DiskWriteBatch::new();
+        batch.add_node(Node::new(vec!["Person".to_string()]));
+        batch.add_edge(Edge::new(a, NodeId::new(), "KNOWS".to_string()));
+        assert!(storage.write_batch(batch).is_err());
+        assert_eq!(storage.node_count(), 2);
+        
+        storage.flush().unwrap();
+    }
+
+    #[test]
+    fn test_apply_batch_runs_in_one_transaction() {
+        let (storage, _temp_dir) = create_test_storage();
+        let a = Node::new(vec!["Person".to_string()]);
+        let b = Node::new(vec!["Person".to_string()]);
+        let knows = Edge::new(a.id(), b.id(), "KNOWS".to_string());
+        let (a_id, b_id) = (a.id(), b.id());
+        
+        // Deleting a node also removes an edge added earlier in the batch
+        storage
+            .apply_batch(vec![GraphOp::AddNode(a), GraphOp::AddNode(b), GraphOp::AddEdge(knows), GraphOp::DeleteNode(b_id)])
+            .unwrap();
+        assert!(storage.get_node(a_id).is_ok());
+        assert!(storage.get_node(b_id).is_err());
+        assert_eq!(storage.edge_count(), 0);
+        assert!(storage.get_edges_by_type("KNOWS").is_empty());
+        
+        // A failing batch leaves nothing behind
+        let c = Node::new(vec!["Person".to_string()]);
+        let c_id = c.id();
+        let dangling = Edge::new(a_id, NodeId::new(), "KNOWS".to_string());
+        assert!(storage.apply_batch(vec![GraphOp::AddNode(c), GraphOp::AddEdge(dangling)]).is_err());
+        assert!(storage.get_node(c_id).is_err());
+        assert_eq!(storage.get_nodes_by_label("Person").len(), 1);
+    }
+
+    #[test]
+    fn test_mutations_keep_indexes_consistent() {
+        let (storage, _temp_dir) = create_test_storage();
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let edge_id = storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+        
+        // An edge to a missing node leaves no trace in any tree
+        assert!(storage.add_edge(Edge::new(a, NodeId::new(), "KNOWS".to_string())).is_err());
+        assert_eq!(storage.edge_count(), 1);
+        assert_eq!(storage.get_edges_by_type("KNOWS").len(), 1);
+        
+        // Relabeling moves the node between label index entries
+        let mut node = storage.get_node(a).unwrap();
+        node.remove_label("Person");
+        node.add_label("Admin".to_string());
+        storage.update_node(node).unwrap();
+        assert_eq!(storage.get_nodes_by_label("Person").len(), 1);
+        assert_eq!(storage.get_nodes_by_label("Admin").len(), 1);
+        
+        // Deleting a node drops its edges from every index
+        storage.delete_node(b).unwrap();
+        assert!(storage.get_edge(edge_id).is_err());
+        assert!(storage.get_outgoing_edges(a).unwrap().is_empty());
+        assert!(storage.get_edges_for_type("KNOWS").unwrap().is_empty());
+        assert_eq!(storage.stats().index_repairs, 0);
+    }
+
+    #[test]
+    fn test_property_index() {
+        let (storage, temp_dir) = create_test_storage();
+        let mut alice = Node::new(vec!["Person".to_string()]);
+        alice.set_property("city".to_string(), PropertyValue::String("NYC".to_string()));
+        let alice_id = storage.add_node(alice).unwrap();
+        let mut bob = Node::new(vec!["Person".to_string()]);
+        bob.set_property("city".to_string(), PropertyValue::String("NYC".to_string()));
+        let bob_id = storage.add_node(bob).unwrap();
+        
+        let nyc = PropertyValue::String("NYC".to_string());
+        let sf = PropertyValue::String("SF".to_string());
+        assert_eq!(storage.get_nodes_by_property("city", &nyc).len(), 2);
+        
+        // Updates move the posting, deletes drop it
+        let mut moved = storage.get_node(alice_id).unwrap();
+        moved.set_property("city".to_string(), sf.clone());
+        storage.update_node(moved).unwrap();
+        storage.delete_node(bob_id).unwrap();
+        assert!(storage.get_nodes_by_property("city", &nyc).is_empty());
+        assert_eq!(storage.get_nodes_by_property("city", &sf)[0].id(), alice_id);
+        
+        // A database without the index marker is reindexed on open
+        storage.property_index.clear().unwrap();
+        storage.db.remove(PROPERTY_INDEX_MARKER).unwrap();
+        storage.flush().unwrap();
+        drop(storage);
+        let reopened = reopen(temp_dir.path());
+        assert_eq!(reopened.get_nodes_by_property("city", &sf).len(), 1);
+    }
+    
+    #[test]
+    fn test_scans_through_trait_object() {
+        let (storage, _temp_dir) = create_test_storage();
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+        storage.add_edge(Edge::new(b, a, "LIKES".to_string())).unwrap();
+        
+        let backend: &dyn StorageBackend = &storage;
+        assert_eq!(backend.iter_nodes().count(), 2);
+        assert_eq!(backend.iter_edges().count(), 2);
+        assert_eq!(backend.get_all_edges().len(), 2);
+        let knows = backend.get_edges_by_type("KNOWS");
+        assert_eq!(knows.len(), 1);
+        assert_eq!(knows[0].from(), a);
+    }
+    
+    #[test]
+    fn test_backup_and_restore() {
+        let (storage, _temp_dir) = create_test_storage();
+        let alice = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let bob = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let knows = storage.add_edge(Edge::new(alice, bob, "KNOWS".to_string())).unwrap();
+        
+        let backups = TempDir::new().unwrap();
+        let backup = backups.path().join("backup");
+        storage.backup_to_with_wal_lsn(&backup, 42).unwrap();
+        storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        assert!(storage.backup_to(&backup).is_err());
+        
+        let restored = DiskStorage::restore_from(&backup, backups.path().join("restored")).unwrap();
+        assert_eq!(restored.node_count(), 2);
+        assert_eq!(restored.get_edge(knows).unwrap().to(), bob);
+        assert_eq!(restored.get_nodes_by_label("Person").len(), 2);
+        assert_eq!(restored.backup_wal_lsn().unwrap(), Some(42));
+        assert_eq!(storage.backup_wal_lsn().unwrap(), None);
+    }
+    
+    #[test]
+    fn test_adjacency_subkeys() {
+        let temp_dir = TempDir::new().unwrap();
+        let (hub, spokes) = {
+            let storage = DiskStorage::new(temp_dir.path()).unwrap();
+            let hub = storage.add_node(Node::new(vec!["Hub".to_string()])).unwrap();
+            let mut spokes = Vec::new();
+            for _ in 0..50 {
+                let spoke = storage.add_node(Node::new(vec!["Spoke".to_string()])).unwrap();
+                spokes.push(storage.add_edge(Edge::new(hub, spoke, "LINK".to_string())).unwrap());
+            }
+            storage.delete_edge(spokes.pop().unwrap()).unwrap();
+            assert_eq!(storage.get_outgoing_edges(hub).unwrap().len(), 49);
+            assert_eq!(storage.outgoing_edges.len(), 49);
+            
+            // Rewrite adjacency in the old one-list-per-node layout
+            storage.outgoing_edges.clear().unwrap();
+            storage.incoming_edges.clear().unwrap();
+            storage.outgoing_edges.insert(hub.as_bytes(), bincode::serialize(&spokes).unwrap()).unwrap();
+            storage.db.remove(ADJACENCY_SUBKEYS_MARKER).unwrap();
+            storage.flush().unwrap();
+            (hub, spokes)
+        };
+        
+        // Reopening converts the old layout from the edges tree
+        let storage = reopen(temp_dir.path());
+        assert_eq!(storage.get_outgoing_edges(hub).unwrap().len(), spokes.len());
+        let spoke = storage.get_edge(spokes[0]).unwrap().to();
+        assert_eq!(storage.get_incoming_edges(spoke).unwrap().len(), 1);
+        
+        storage.delete_node(hub).unwrap();
+        assert_eq!(storage.edge_count(), 0);
+        assert!(storage.get_incoming_edges(spoke).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_compressed_records() {
+        let temp_dir = TempDir::new().unwrap();
+        let body = PropertyValue::String("lorem ipsum ".repeat(500));
+        let plain = {
+            let storage = DiskStorage::new(temp_dir.path()).unwrap();
+            storage.add_node(Node::new(vec!["Doc".to_string()])).unwrap()
+        };
+        
+        // Enabling compression on an existing database keeps old records readable
+        let storage = reopen(temp_dir.path()).with_compression(RecordCompression::new(1024));
+        let mut doc = Node::new(vec!["Doc".to_string()]);
+        doc.set_property("body".to_string(), body.clone());
+        let doc = storage.add_node(doc).unwrap();
+        
+        let raw = storage.nodes.get(doc.as_bytes()).unwrap().unwrap();
+        assert!(compression::is_compressed(&raw));
+        assert!(raw.len() < 1024);
+        assert_eq!(storage.get_node(doc).unwrap().get_property("body"), Some(&body));
+        assert!(!compression::is_compressed(&storage.nodes.get(plain.as_bytes()).unwrap().unwrap()));
+        assert!(storage.get_node(plain).is_ok());
+        assert_eq!(storage.get_nodes_by_property("body", &body).len(), 1);
+    }
+    #[test]
+    fn test_bloom_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("bloom.db");
+        let id = {
+            let storage = DiskStorage::new(&path).unwrap()
+                .with_bloom_filters(BloomConfig::new(0.001).with_expected_items(1_000));
+            let id = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+            
+            assert!(storage.contains_node(id).unwrap());
+            assert!(!storage.contains_node(NodeId::new()).unwrap());
+            assert!(storage.get_node(NodeId::new()).unwrap_err().is_not_found());
+            assert!(storage.get_nodes_by_label("Company").is_empty());
+            assert!(storage.stats().bloom_skips >= 3);
+            
+            storage.flush().unwrap();
+            id
+        };
+        
+        // Saved filters are reused and dropped by the next write
+        let storage = DiskStorage::new(&path).unwrap();
+        assert!(storage.db.contains_key(BLOOM_FILTERS_KEY).unwrap());
+        assert_eq!(storage.get_node(id).unwrap().id(), id);
+        storage.add_node(Node::new(vec!["Company".to_string()])).unwrap();
+        assert!(!storage.db.contains_key(BLOOM_FILTERS_KEY).unwrap());
+        assert_eq!(storage.get_nodes_by_label("Company").len(), 1);
+    }
 }
@@ -0,0 +1,385 @@
+//! Write-ahead logging in front of a storage backend
+//!
+//! [`DurableStorage`] appends every mutation to the [`WAL`] before applying
+//! it to the wrapped backend, so [`WALRecovery`](crate::wal::WALRecovery)
+//! can rebuild the graph after a crash. Each mutation is its own WAL
+//! transaction: the operation, then `CommitTxn` once the backend accepted
+//! it, or `AbortTxn` if the backend rejected it, so recovery never replays a
+//! failed write. A batch from [`StorageBackend::apply_batch`] is validated
+//! against the backend and logged as a single record. With
+//! [`WALConfig::sync_on_write`](crate::wal::WALConfig) set, a write returns
+//! only once its commit record is synced to disk. Reads go straight to the
+//! backend.
+//!
+//! For a backend that persists its own writes when flushed, such as
+//! [`DiskStorage`](crate::storage::DiskStorage), the log is only needed until
+//! then: built [`with_truncate_on_flush`](DurableStorage::with_truncate_on_flush),
+//! [`flush`](StorageBackend::flush) checkpoints the WAL, flushes the backend
+//! and removes the segments it made redundant, so the log does not grow
+//! without bound.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let wal = Arc::new(WAL::new(WALConfig::new().with_dir("./data/wal"))?);
+//! let storage = DurableStorage::new(MemoryStorage::new(), Some(wal));
+//! storage.add_node(Node::new(vec!["Person".to_string()]))?; // logged, then applied
+//! ```
+
+use crate::error::Result;
+use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
+use crate::storage::{batch, CostConstants, GraphOp, GraphStatistics, StorageBackend};
+use crate::wal::log::LSN;
+use crate::wal::{WALOperation, WAL};
+use log::{debug, warn};
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// Storage backend wrapper that logs mutations to a WAL before applying them
+pub struct DurableStorage<S: StorageBackend> {
+    inner: S,
+    /// `None` when the WAL is disabled; writes then pass straight through
+    wal: Option<Arc<WAL>>,
+    /// Held from append to apply, so the log records writes in the order
+    /// the backend applied them and replay reproduces the same state
+    write_order: Mutex<()>,
+    /// Truncate the WAL once the backend has flushed
+    truncate_on_flush: bool,
+}
+
+impl<S: StorageBackend> DurableStorage<S> {
+    /// Wrap a backend, logging to `wal` if given
+    pub fn new(inner: S, wal: Option<Arc<WAL>>) -> Self {
+        Self {
+            inner,
+            wal,
+            write_order: Mutex::new(()),
+            truncate_on_flush: false,
+        }
+    }
+
+    /// Truncate the WAL on every [`flush`](StorageBackend::flush)
+    ///
+    /// Only for backends whose flush makes every applied write durable;
+    /// an in-memory backend is rebuilt from the log, which must be kept.
+    pub fn with_truncate_on_flush(mut self) -> Self {
+        self.truncate_on_flush = true;
+        self
+    }
+
+    /// The wrapped backend
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Unwrap, keeping the WAL open for other users
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// The WAL writes are logged to, if enabled
+    pub fn wal(&self) -> Option<&Arc<WAL>> {
+        self.wal.as_ref()
+    }
+
+    /// Write a WAL checkpoint once every logged write has been applied
+    ///
+    /// Waits for the write in progress, if any, so every entry before the
+    /// returned LSN is reflected in the backend and no transaction spans
+    /// it. `None` when the WAL is disabled.
+    pub fn checkpoint(&self) -> Result<Option<LSN>> {
+        let Some(wal) = &self.wal else {
+            return Ok(None);
+        };
+        let _order = self.write_order.lock();
+        wal.checkpoint().map(Some)
+    }
+
+    /// Write a WAL checkpoint and read the backend at it
+    ///
+    /// Like [`checkpoint`](Self::checkpoint), but writes stay blocked until
+    /// `read` returns, so it sees exactly the state at the returned LSN: no
+    /// write can land both in what `read` captures and in the log after
+    /// the checkpoint, where recovery would replay it a second time. The
+    /// LSN is `None` when the WAL is disabled; writes are not held back
+    /// then.
+    pub fn checkpoint_with<T>(&self, read: impl FnOnce(Option<LSN>, &S) -> Result<T>) -> Result<T> {
+        let _order = self.write_order.lock();
+        let lsn = self.wal.as_ref().map(|wal| wal.checkpoint()).transpose()?;
+        read(lsn, &self.inner)
+    }
+
+    /// Log `operation`, apply it, then commit or abort it in the log
+    fn logged<T>(&self, operation: WALOperation, apply: impl FnOnce(&S) -> Result<T>) -> Result<T> {
+        if self.wal.is_none() {
+            return apply(&self.inner);
+        }
+        let _order = self.write_order.lock();
+        self.log_and_apply(operation, apply)
+    }
+
+    /// [`logged`](Self::logged) for callers already holding `write_order`
+    fn log_and_apply<T>(&self, operation: WALOperation, apply: impl FnOnce(&S) -> Result<T>) -> Result<T> {
+        let Some(wal) = &self.wal else {
+            return apply(&self.inner);
+        };
+        let txn_id = wal.allocate_txn_id();
+        wal.append(txn_id, operation)?;
+        match apply(&self.inner) {
+            Ok(value) => {
+                wal.append(txn_id, WALOperation::CommitTxn)?;
+                if wal.config().sync_on_write {
+                    wal.flush()?;
+                }
+                Ok(value)
+            }
+            Err(e) => {
+                // Without a commit record recovery skips the write anyway
+                if let Err(abort) = wal.append(txn_id, WALOperation::AbortTxn) {
+                    warn!("Failed to log abort of WAL transaction {}: {}", txn_id, abort);
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<S: StorageBackend> StorageBackend for DurableStorage<S> {
+    fn add_node(&self, node: Node) -> Result<NodeId> {
+        self.logged(WALOperation::InsertNode { node: node.clone() }, |inner| inner.add_node(node))
+    }
+
+    fn get_node(&self, id: NodeId) -> Result<Node> {
+        self.inner.get_node(id)
+    }
+
+    fn update_node(&self, node: Node) -> Result<()> {
+        self.logged(WALOperation::UpdateNode { node: node.clone() }, |inner| inner.update_node(node))
+    }
+
+    fn delete_node(&self, id: NodeId) -> Result<()> {
+        self.logged(WALOperation::DeleteNode { id }, |inner| inner.delete_node(id))
+    }
+
+    fn add_edge(&self, edge: Edge) -> Result<EdgeId> {
+        self.logged(WALOperation::InsertEdge { edge: edge.clone() }, |inner| inner.add_edge(edge))
+    }
+
+    fn get_edge(&self, id: EdgeId) -> Result<Edge> {
+        self.inner.get_edge(id)
+    }
+
+    fn update_edge(&self, edge: Edge) -> Result<()> {
+        self.logged(WALOperation::UpdateEdge { edge: edge.clone() }, |inner| inner.update_edge(edge))
+    }
+
+    fn delete_edge(&self, id: EdgeId) -> Result<()> {
+        self.logged(WALOperation::DeleteEdge { id }, |inner| inner.delete_edge(id))
+    }
+
+    fn get_nodes_by_label(&self, label: &str) -> Vec<Node> {
+        self.inner.get_nodes_by_label(label)
+    }
+
+    fn get_all_nodes(&self) -> Vec<Node> {
+        self.inner.get_all_nodes()
+    }
+
+    fn get_all_edges(&self) -> Vec<Edge> {
+        self.inner.get_all_edges()
+    }
+
+    fn get_edges_by_type(&self, relationship_type: &str) -> Vec<Edge> {
+        self.inner.get_edges_by_type(relationship_type)
+    }
+
+    fn get_nodes_by_property(&self, key: &str, value: &PropertyValue) -> Vec<Node> {
+        self.inner.get_nodes_by_property(key, value)
+    }
+
+    fn iter_nodes(&self) -> Box<dyn Iterator<Item = Node> + '_> {
+        self.inner.iter_nodes()
+    }
+
+    fn iter_edges(&self) -> Box<dyn Iterator<Item = Edge> + '_> {
+        self.inner.iter_edges()
+    }
+
+    fn get_outgoing_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
+        self.inner.get_outgoing_edges(node_id)
+    }
+
+    fn get_incoming_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
+        self.inner.get_incoming_edges(node_id)
+    }
+
+    fn node_count(&self) -> usize {
+        self.inner.node_count()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.inner.edge_count()
+    }
+
+    fn cost_constants(&self) -> CostConstants {
+        self.inner.cost_constants()
+    }
+
+    fn statistics(&self) -> Option<&GraphStatistics> {
+        self.inner.statistics()
+    }
+
+    fn degree(&self, node_id: NodeId) -> Result<usize> {
+        self.inner.degree(node_id)
+    }
+
+    fn apply_batch(&self, ops: Vec<GraphOp>) -> Result<()> {
+        if self.wal.is_none() {
+            return self.inner.apply_batch(ops);
+        }
+        // Validate under the write lock so nothing changes before applying
+        let _order = self.write_order.lock();
+        batch::validate_batch(&self.inner, &ops)?;
+        self.log_and_apply(WALOperation::Batch { ops: ops.clone() }, |inner| inner.apply_batch(ops))
+    }
+
+    fn flush(&self) -> Result<()> {
+        let Some(wal) = &self.wal else {
+            return self.inner.flush();
+        };
+        if !self.truncate_on_flush {
+            wal.flush()?;
+            return self.inner.flush();
+        }
+        // Everything logged before the checkpoint is applied, and durable
+        // once the backend has flushed
+        let _order = self.write_order.lock();
+        let lsn = wal.checkpoint()?;
+        wal.flush()?;
+        self.inner.flush()?;
+        let truncation = wal.truncate(lsn)?;
+        debug!("Flushed backend and truncated WAL before LSN {}: {:?}", lsn, truncation);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use crate::wal::{WALConfig, WALRecovery};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_writes_are_logged_and_recovered() {
+        let dir = tempdir().unwrap();
+        let config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_sync(false);
+        let wal = Arc::new(WAL::new(config.clone()).unwrap());
+        let storage = DurableStorage::new(MemoryStorage::new(), Some(wal.clone()));
+
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+        storage.delete_node(b).unwrap();
+        // Rejected by the backend: logged as aborted, never replayed
+        assert!(storage.update_node(Node::new(vec![])).is_err());
+        wal.flush().unwrap();
+        drop(storage);
+        drop(wal);
+
+        let recovered = MemoryStorage::new();
+        WALRecovery::new(config).recover(&recovered).unwrap();
+        assert_eq!(recovered.node_count(), 1);
+        assert!(recovered.get_node(a).is_ok());
+        assert_eq!(recovered.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_batch_is_one_record() {
+        let dir = tempdir().unwrap();
+        let config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_sync(false);
+        let wal = Arc::new(WAL::new(config.clone()).unwrap());
+        let storage = DurableStorage::new(MemoryStorage::new(), Some(wal.clone()));
+
+        let a = Node::new(vec![]);
+        let b = Node::new(vec![]);
+        let edge = Edge::new(a.id(), b.id(), "KNOWS".to_string());
+        let (a_id, b_id) = (a.id(), b.id());
+        storage
+            .apply_batch(vec![GraphOp::AddNode(a), GraphOp::AddNode(b), GraphOp::AddEdge(edge)])
+            .unwrap();
+        // Fails validation: nothing is logged
+        assert!(storage.apply_batch(vec![GraphOp::DeleteNode(NodeId::new())]).is_err());
+        wal.flush().unwrap();
+
+        // One batch record plus its commit
+        let entries: Vec<_> = crate::wal::WALReader::new(config.clone()).map(|e| e.unwrap()).collect();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(&entries[0].operation, WALOperation::Batch { ops } if ops.len() == 3));
+
+        let recovered = MemoryStorage::new();
+        WALRecovery::new(config).recover(&recovered).unwrap();
+        assert_eq!(recovered.get_outgoing_edges(a_id).unwrap().len(), 1);
+        assert!(recovered.get_node(b_id).is_ok());
+    }
+
+    #[test]
+    fn test_checkpoint_with_holds_back_writes() {
+        let dir = tempdir().unwrap();
+        let config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_sync(false);
+        let wal = Arc::new(WAL::new(config).unwrap());
+        let storage = DurableStorage::new(MemoryStorage::new(), Some(wal));
+
+        std::thread::scope(|scope| {
+            let lsn = storage
+                .checkpoint_with(|lsn, inner| {
+                    scope.spawn(|| storage.add_node(Node::new(vec![])).unwrap());
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                    assert_eq!(inner.node_count(), 0);
+                    Ok(lsn)
+                })
+                .unwrap();
+            assert!(lsn.is_some());
+        });
+        assert_eq!(storage.node_count(), 1);
+    }
+
+    #[test]
+    fn test_flush_truncates_wal_of_disk_backend() {
+        use crate::storage::DiskStorage;
+
+        let dir = tempdir().unwrap();
+        let mut config = WALConfig::new()
+            .with_dir(dir.path().join("wal").to_string_lossy().to_string())
+            .with_sync(true);
+        config.checkpoint_threshold = 4;
+        let wal = Arc::new(WAL::new(config.clone()).unwrap());
+        let storage = DurableStorage::new(DiskStorage::new(dir.path().join("db")).unwrap(), Some(wal))
+            .with_truncate_on_flush();
+
+        let ids: Vec<NodeId> = (0..20)
+            .map(|_| storage.add_node(Node::new(vec![])).unwrap())
+            .collect();
+        let segments = || WALRecovery::new(config.clone()).find_segments().unwrap().len();
+        let before = segments();
+        assert!(before > 2);
+
+        storage.flush().unwrap();
+        assert!(segments() < before);
+        assert!(ids.iter().all(|id| storage.get_node(*id).is_ok()));
+    }
+
+    #[test]
+    fn test_without_wal() {
+        let storage = DurableStorage::new(MemoryStorage::new(), None);
+        let id = storage.add_node(Node::new(vec![])).unwrap();
+        assert!(storage.get_node(id).is_ok());
+        assert!(storage.wal().is_none());
+    }
+}
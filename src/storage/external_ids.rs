@@ -0,0 +1,119 @@
+//! Persistent external-ID registry
+//!
+//! Maps identifiers from source systems (`"user:42"`) to the [`NodeId`]s they
+//! were imported as. Importers consult it so re-importing a record updates
+//! the existing node instead of creating a duplicate, and queries resolve
+//! references with `external('user:42')`.
+
+use crate::error::{DeepGraphError, Result};
+use crate::graph::NodeId;
+use log::info;
+use std::path::Path;
+use uuid::Uuid;
+
+/// External ID -> [`NodeId`] mapping backed by a Sled tree
+pub struct ExternalIdRegistry {
+    /// Keeps the database open for the lifetime of the registry
+    db: sled::Db,
+    ids: sled::Tree,
+}
+
+impl ExternalIdRegistry {
+    /// Open (or create) a registry stored at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        info!("Opening external ID registry at {:?}", path.as_ref());
+        let db = sled::open(path.as_ref())
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to open external ID registry: {}", e)))?;
+        Self::from_db(db)
+    }
+
+    /// Create a registry that is discarded when dropped
+    pub fn temporary() -> Result<Self> {
+        let db = sled::Config::new()
+            .temporary(true)
+            .open()
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to open external ID registry: {}", e)))?;
+        Self::from_db(db)
+    }
+
+    fn from_db(db: sled::Db) -> Result<Self> {
+        let ids = db.open_tree("external_ids")
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to open external_ids tree: {}", e)))?;
+        Ok(Self { db, ids })
+    }
+
+    /// Look up the node an external ID was imported as
+    pub fn get(&self, external_id: &str) -> Result<Option<NodeId>> {
+        let value = self.ids.get(external_id.as_bytes())
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to read external ID: {}", e)))?;
+        value.map(|bytes| Self::decode(&bytes)).transpose()
+    }
+
+    /// Map an external ID to a node, returning the previous mapping
+    pub fn insert(&self, external_id: &str, node_id: NodeId) -> Result<Option<NodeId>> {
+        let previous = self.ids.insert(external_id.as_bytes(), &node_id.as_bytes()[..])
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to write external ID: {}", e)))?;
+        previous.map(|bytes| Self::decode(&bytes)).transpose()
+    }
+
+    /// Forget an external ID
+    pub fn remove(&self, external_id: &str) -> Result<Option<NodeId>> {
+        let previous = self.ids.remove(external_id.as_bytes())
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to remove external ID: {}", e)))?;
+        previous.map(|bytes| Self::decode(&bytes)).transpose()
+    }
+
+    /// Number of registered external IDs
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether no external IDs are registered
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Flush the registry to disk
+    pub fn flush(&self) -> Result<()> {
+        self.db.flush()
+            .map_err(|e| DeepGraphError::StorageError(format!("Failed to flush external ID registry: {}", e)))?;
+        Ok(())
+    }
+
+    fn decode(bytes: &[u8]) -> Result<NodeId> {
+        Uuid::from_slice(bytes)
+            .map(NodeId::from_uuid)
+            .map_err(|e| DeepGraphError::StorageError(format!("Corrupt external ID entry: {}", e)))
+    }
+}
+
+impl std::fmt::Debug for ExternalIdRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalIdRegistry")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_registry_persists_across_reopen() {
+        let dir = tempdir().unwrap();
+        let node_id = NodeId::new();
+        {
+            let registry = ExternalIdRegistry::open(dir.path()).unwrap();
+            assert_eq!(registry.insert("user:42", node_id).unwrap(), None);
+            registry.flush().unwrap();
+        }
+
+        let registry = ExternalIdRegistry::open(dir.path()).unwrap();
+        assert_eq!(registry.get("user:42").unwrap(), Some(node_id));
+        assert_eq!(registry.get("user:43").unwrap(), None);
+        assert_eq!(registry.remove("user:42").unwrap(), Some(node_id));
+        assert!(registry.is_empty());
+    }
+}
@@ -5,11 +5,32 @@
 
 use crate::error::{DeepGraphError, Result};
 use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
+use crate::storage::batch::{self, GraphOp};
+use crate::storage::dense_ids::{DenseId, DenseIdMap};
+use crate::storage::schema::SchemaRegistry;
+use crate::storage::stats::GraphStatistics;
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use log::{debug, info, warn};
-use std::collections::HashMap;
+use parking_lot::{RwLock, RwLockReadGuard};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
+/// Property index key: property name and encoded value
+type PropertyKey = (String, Vec<u8>);
+
+/// Source of per-thread tokens; 0 means "no thread"
+static NEXT_THREAD_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD_TOKEN: u64 = NEXT_THREAD_TOKEN.fetch_add(1, Ordering::Relaxed);
+}
+
+fn thread_token() -> u64 {
+    THREAD_TOKEN.with(|token| *token)
+}
+
 /// In-memory graph storage engine
 ///
 /// Uses concurrent hash maps (DashMap) for thread-safe operations.
@@ -18,16 +39,49 @@ use std::sync::Arc;
 /// - Edges by ID
 /// - Outgoing edges by source node
 /// - Incoming edges by target node
+/// - Nodes by label
+/// - Nodes by property value
+///
+/// Adjacency lists and the label/property indexes hold dense `u64` IDs
+/// rather than UUIDs; they are translated at the API boundary.
+///
+/// [`MemoryStorage::apply_batch`] holds every other reader and writer off
+/// while it runs, so a batch is never seen half applied.
 #[derive(Debug, Clone)]
 pub struct MemoryStorage {
     /// Store nodes by ID
     nodes: Arc<DashMap<NodeId, Node>>,
     /// Store edges by ID
     edges: Arc<DashMap<EdgeId, Edge>>,
-    /// Index: source node -> outgoing edges
-    outgoing_edges: Arc<DashMap<NodeId, Vec<EdgeId>>>,
-    /// Index: target node -> incoming edges
-    incoming_edges: Arc<DashMap<NodeId, Vec<EdgeId>>>,
+    /// Node ID <-> dense ID
+    node_ids: Arc<DenseIdMap<NodeId>>,
+    /// Edge ID <-> dense ID
+    edge_ids: Arc<DenseIdMap<EdgeId>>,
+    /// Index: source node -> outgoing edges (dense IDs)
+    outgoing_edges: Arc<DashMap<DenseId, Vec<DenseId>>>,
+    /// Index: target node -> incoming edges (dense IDs)
+    incoming_edges: Arc<DashMap<DenseId, Vec<DenseId>>>,
+    /// Index: label -> nodes carrying it (dense IDs)
+    label_index: Arc<DashMap<String, HashSet<DenseId>>>,
+    /// Index: (property key, encoded value) -> nodes holding it (dense IDs)
+    property_index: Arc<DashMap<PropertyKey, HashSet<DenseId>>>,
+    /// Label and relationship type counts
+    stats: Arc<GraphStatistics>,
+    /// Property schema that node writes are validated against
+    schema: Option<Arc<SchemaRegistry>>,
+    /// Shared by single operations, held exclusively by a batch
+    batch_gate: Arc<RwLock<()>>,
+    /// Token of the thread applying a batch, 0 if none
+    batch_owner: Arc<AtomicU64>,
+}
+
+/// Clears the batch owner when the batch finishes, even by panicking
+struct BatchOwner<'a>(&'a AtomicU64);
+
+impl Drop for BatchOwner<'_> {
+    fn drop(&mut self) {
+        self.0.store(0, Ordering::SeqCst);
+    }
 }
 
 impl MemoryStorage {
@@ -37,32 +91,207 @@ impl MemoryStorage {
         Self {
             nodes: Arc::new(DashMap::new()),
             edges: Arc::new(DashMap::new()),
+            node_ids: Arc::new(DenseIdMap::new()),
+            edge_ids: Arc::new(DenseIdMap::new()),
             outgoing_edges: Arc::new(DashMap::new()),
             incoming_edges: Arc::new(DashMap::new()),
+            label_index: Arc::new(DashMap::new()),
+            property_index: Arc::new(DashMap::new()),
+            stats: Arc::new(GraphStatistics::new()),
+            schema: None,
+            batch_gate: Arc::new(RwLock::new(())),
+            batch_owner: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Validate node writes against a schema registry
+    pub fn with_schema(mut self, schema: Arc<SchemaRegistry>) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Schema registry node writes are validated against, if any
+    pub fn schema(&self) -> Option<&Arc<SchemaRegistry>> {
+        self.schema.as_ref()
+    }
+
+    /// Wait out a batch being applied by another thread
+    ///
+    /// The thread applying the batch already holds the gate exclusively, so
+    /// the operations it performs pass straight through.
+    fn shared(&self) -> Option<RwLockReadGuard<'_, ()>> {
+        if self.batch_owner.load(Ordering::SeqCst) == thread_token() {
+            return None;
+        }
+        Some(self.batch_gate.read_recursive())
+    }
+
+    /// Validate and apply `ops` as one unit, invisible until complete
+    ///
+    /// Other threads' reads and writes wait until the batch has been applied
+    /// or undone.
+    pub fn apply_batch(&self, ops: Vec<GraphOp>) -> Result<()> {
+        let _exclusive = self.batch_gate.write();
+        self.batch_owner.store(thread_token(), Ordering::SeqCst);
+        let _owner = BatchOwner(&self.batch_owner);
+        batch::apply_batch(self, ops)
+    }
+
+    fn conform(&self, node: Node) -> Result<Node> {
+        match &self.schema {
+            Some(schema) => schema.conform(node),
+            None => Ok(node),
         }
     }
 
     /// Get the number of nodes in the graph
     pub fn node_count(&self) -> usize {
+        let _shared = self.shared();
         self.nodes.len()
     }
 
     /// Get the number of edges in the graph
     pub fn edge_count(&self) -> usize {
+        let _shared = self.shared();
         self.edges.len()
     }
 
+    /// Label and relationship type counts and node degrees, maintained on every write
+    pub fn statistics(&self) -> &GraphStatistics {
+        &self.stats
+    }
+
+    /// Dense internal ID of a node, if it is stored
+    pub fn dense_node_id(&self, id: NodeId) -> Option<DenseId> {
+        self.node_ids.dense(&id)
+    }
+
+    /// Node ID a dense internal ID stands for
+    pub fn node_id_for_dense(&self, dense: DenseId) -> Option<NodeId> {
+        self.node_ids.resolve(dense)
+    }
+
+    /// Number of edges touching a node, without materializing them
+    pub fn degree(&self, node_id: NodeId) -> Result<usize> {
+        let _shared = self.shared();
+        let dense = self.existing_dense_id(node_id)?;
+        let count = |index: &DashMap<DenseId, Vec<DenseId>>| {
+            index
+                .get(&dense)
+                .map(|ids| ids.iter().filter(|id| self.edge_ids.resolve(**id).is_some()).count())
+                .unwrap_or(0)
+        };
+        Ok(count(&self.outgoing_edges) + count(&self.incoming_edges))
+    }
+
+    /// Dense ID of a node that must exist
+    fn existing_dense_id(&self, node_id: NodeId) -> Result<DenseId> {
+        match self.node_ids.dense(&node_id) {
+            Some(dense) if self.nodes.contains_key(&node_id) => Ok(dense),
+            _ => Err(DeepGraphError::NodeNotFound(node_id.to_string())),
+        }
+    }
+
+    /// Resolve a dense adjacency list to the edges that still exist
+    fn resolve_edges(&self, dense_ids: Vec<DenseId>) -> Vec<Edge> {
+        dense_ids
+            .into_iter()
+            .filter_map(|dense| self.edge_ids.resolve(dense))
+            .filter_map(|id| self.edges.get(&id).map(|e| e.value().clone()))
+            .collect()
+    }
+
+    /// Move a node between label and property index entries
+    ///
+    /// Only entries that differ between the old and new version are touched,
+    /// so a label or property kept across an update is never briefly missing.
+    fn reindex_node(&self, old: Option<&Node>, new: Option<&Node>) {
+        let id = match old.or(new) {
+            Some(node) => self.node_ids.get_or_assign(node.id()),
+            None => return,
+        };
+        let has_label = |node: Option<&Node>, label: &str| node.is_some_and(|n| n.has_label(label));
+        let has_value = |node: Option<&Node>, key: &str, value: &PropertyValue| {
+            node.and_then(|n| n.get_property(key)) == Some(value)
+        };
+
+        if let Some(old) = old {
+            for label in old.labels() {
+                if !has_label(new, label) {
+                    Self::remove_posting(&self.label_index, label.clone(), id);
+                }
+            }
+            for (key, value) in old.properties() {
+                if !has_value(new, key, value) {
+                    Self::remove_posting(&self.property_index, (key.clone(), value.index_key()), id);
+                }
+            }
+        }
+        if let Some(new) = new {
+            for label in new.labels() {
+                if !has_label(old, label) {
+                    self.label_index.entry(label.clone()).or_default().insert(id);
+                }
+            }
+            for (key, value) in new.properties() {
+                if !has_value(old, key, value) {
+                    self.property_index
+                        .entry((key.clone(), value.index_key()))
+                        .or_default()
+                        .insert(id);
+                }
+            }
+        }
+    }
+
+    fn remove_posting<K: Eq + std::hash::Hash>(index: &DashMap<K, HashSet<DenseId>>, key: K, id: DenseId) {
+        let now_empty = match index.get_mut(&key) {
+            Some(mut ids) => {
+                ids.remove(&id);
+                ids.is_empty()
+            }
+            None => false,
+        };
+        if now_empty {
+            index.remove_if(&key, |_, ids| ids.is_empty());
+        }
+    }
+
+    /// Fetch indexed nodes, re-checking each against the predicate in case a
+    /// concurrent write changed it after the index was read
+    fn fetch_indexed(&self, ids: Vec<DenseId>, matches: impl Fn(&Node) -> bool) -> Vec<Node> {
+        ids.into_iter()
+            .filter_map(|dense| self.node_ids.resolve(dense))
+            .filter_map(|id| self.nodes.get(&id).map(|entry| entry.value().clone()))
+            .filter(|node| matches(node))
+            .collect()
+    }
+
     /// Add a node to the storage
     pub fn add_node(&self, node: Node) -> Result<NodeId> {
+        let _shared = self.shared();
+        let node = self.conform(node)?;
         let id = node.id();
         debug!("Adding node {} with labels {:?}", id, node.labels());
-        self.nodes.insert(id, node);
+        match self.nodes.entry(id) {
+            Entry::Occupied(mut entry) => {
+                self.stats.node_updated(entry.get(), &node);
+                self.reindex_node(Some(entry.get()), Some(&node));
+                entry.insert(node);
+            }
+            Entry::Vacant(entry) => {
+                self.stats.node_added(&node);
+                self.reindex_node(None, Some(&node));
+                entry.insert(node);
+            }
+        }
         info!("Node {} added successfully", id);
         Ok(id)
     }
 
     /// Get a node by ID
     pub fn get_node(&self, id: NodeId) -> Result<Node> {
+        let _shared = self.shared();
         debug!("Retrieving node {}", id);
         self.nodes
             .get(&id)
@@ -75,10 +304,14 @@ impl MemoryStorage {
 
     /// Update a node
     pub fn update_node(&self, node: Node) -> Result<()> {
+        let _shared = self.shared();
+        let node = self.conform(node)?;
         let id = node.id();
         debug!("Updating node {}", id);
-        if self.nodes.contains_key(&id) {
-            self.nodes.insert(id, node);
+        if let Some(mut entry) = self.nodes.get_mut(&id) {
+            self.stats.node_updated(entry.value(), &node);
+            self.reindex_node(Some(entry.value()), Some(&node));
+            *entry = node;
             info!("Node {} updated successfully", id);
             Ok(())
         } else {
@@ -89,31 +322,31 @@ impl MemoryStorage {
 
     /// Delete a node and all connected edges
     pub fn delete_node(&self, id: NodeId) -> Result<()> {
+        let _shared = self.shared();
         info!("Deleting node {} and all connected edges", id);
         
-        // Count edges for logging
-        let outgoing_count = self.outgoing_edges.get(&id).map(|e| e.len()).unwrap_or(0);
-        let incoming_count = self.incoming_edges.get(&id).map(|e| e.len()).unwrap_or(0);
-        
         // Remove the node
-        self.nodes
+        let (_, node) = self.nodes
             .remove(&id)
             .ok_or_else(|| {
                 warn!("Cannot delete node {}: not found", id);
                 DeepGraphError::NodeNotFound(id.to_string())
             })?;
-
-        // Remove all outgoing edges
-        if let Some((_, edge_ids)) = self.outgoing_edges.remove(&id) {
-            for edge_id in edge_ids {
-                self.edges.remove(&edge_id);
+        self.stats.node_removed(&node);
+        self.reindex_node(Some(&node), None);
+        let dense = self.node_ids.remove(&id);
+
+        // Remove all outgoing and incoming edges
+        let mut outgoing_count = 0;
+        let mut incoming_count = 0;
+        if let Some(dense) = dense {
+            if let Some((_, edge_ids)) = self.outgoing_edges.remove(&dense) {
+                outgoing_count = edge_ids.len();
+                self.remove_edges(edge_ids);
             }
-        }
-
-        // Remove all incoming edges
-        if let Some((_, edge_ids)) = self.incoming_edges.remove(&id) {
-            for edge_id in edge_ids {
-                self.edges.remove(&edge_id);
+            if let Some((_, edge_ids)) = self.incoming_edges.remove(&dense) {
+                incoming_count = edge_ids.len();
+                self.remove_edges(edge_ids);
             }
         }
 
@@ -122,28 +355,41 @@ impl MemoryStorage {
         Ok(())
     }
 
-    /// Get all nodes with a specific label
+    /// Get all nodes with a specific label, via the label index
     pub fn get_nodes_by_label(&self, label: &str) -> Vec<Node> {
-        self.nodes
-            .iter()
-            .filter(|entry| entry.value().has_label(label))
-            .map(|entry| entry.value().clone())
-            .collect()
+        let _shared = self.shared();
+        let ids: Vec<DenseId> = self
+            .label_index
+            .get(label)
+            .map(|ids| ids.iter().copied().collect())
+            .unwrap_or_default();
+        self.fetch_indexed(ids, |node| node.has_label(label))
     }
 
-    /// Get all nodes with a specific property
+    /// Get all nodes with a specific property value, via the property index
     pub fn get_nodes_by_property(&self, key: &str, value: &PropertyValue) -> Vec<Node> {
-        self.nodes
-            .iter()
-            .filter(|entry| {
-                entry
-                    .value()
-                    .get_property(key)
-                    .map(|v| v == value)
-                    .unwrap_or(false)
-            })
-            .map(|entry| entry.value().clone())
-            .collect()
+        let _shared = self.shared();
+        let ids: Vec<DenseId> = self
+            .property_index
+            .get(&(key.to_string(), value.index_key()))
+            .map(|ids| ids.iter().copied().collect())
+            .unwrap_or_default();
+        self.fetch_indexed(ids, |node| node.get_property(key) == Some(value))
+    }
+
+    /// Drop edges by dense ID, leaving the other endpoint's adjacency entry to
+    /// be skipped on read
+    fn remove_edges(&self, dense_ids: Vec<DenseId>) {
+        for dense in dense_ids {
+            let edge_id = match self.edge_ids.resolve(dense) {
+                Some(edge_id) => edge_id,
+                None => continue,
+            };
+            self.edge_ids.remove(&edge_id);
+            if let Some((_, edge)) = self.edges.remove(&edge_id) {
+                self.stats.edge_removed(&edge);
+            }
+        }
     }
 
     /// Add an edge with just IDs and relationship type (helper method)
@@ -163,6 +409,7 @@ impl MemoryStorage {
 
     /// Add an edge to the storage
     pub fn add_edge(&self, edge: Edge) -> Result<EdgeId> {
+        let _shared = self.shared();
         let id = edge.id();
         let from = edge.from();
         let to = edge.to();
@@ -180,19 +427,21 @@ impl MemoryStorage {
         }
 
         // Add edge to storage
-        self.edges.insert(id, edge);
+        self.stats.edge_added(&edge);
+        if let Some(old) = self.edges.insert(id, edge) {
+            self.stats.edge_removed(&old);
+        }
 
-        // Update outgoing edges index
+        // Update adjacency indexes
+        let dense = self.edge_ids.get_or_assign(id);
         self.outgoing_edges
-            .entry(from)
+            .entry(self.node_ids.get_or_assign(from))
             .or_insert_with(Vec::new)
-            .push(id);
-
-        // Update incoming edges index
+            .push(dense);
         self.incoming_edges
-            .entry(to)
+            .entry(self.node_ids.get_or_assign(to))
             .or_insert_with(Vec::new)
-            .push(id);
+            .push(dense);
 
         info!("Edge {} added successfully", id);
         Ok(id)
@@ -200,6 +449,7 @@ impl MemoryStorage {
 
     /// Get an edge by ID
     pub fn get_edge(&self, id: EdgeId) -> Result<Edge> {
+        let _shared = self.shared();
         self.edges
             .get(&id)
             .map(|entry| entry.value().clone())
@@ -208,9 +458,12 @@ impl MemoryStorage {
 
     /// Update an edge
     pub fn update_edge(&self, edge: Edge) -> Result<()> {
+        let _shared = self.shared();
         let id = edge.id();
-        if self.edges.contains_key(&id) {
-            self.edges.insert(id, edge);
+        if let Some(mut entry) = self.edges.get_mut(&id) {
+            self.stats.edge_removed(entry.value());
+            self.stats.edge_added(&edge);
+            *entry = edge;
             Ok(())
         } else {
             Err(DeepGraphError::EdgeNotFound(id.to_string()))
@@ -219,6 +472,7 @@ impl MemoryStorage {
 
     /// Delete an edge
     pub fn delete_edge(&self, id: EdgeId) -> Result<()> {
+        let _shared = self.shared();
         info!("Deleting edge {}", id);
         
         let edge = self
@@ -229,17 +483,22 @@ impl MemoryStorage {
                 DeepGraphError::EdgeNotFound(id.to_string())
             })?;
 
-        let from = edge.1.from();
-        let to = edge.1.to();
+        self.stats.edge_removed(&edge.1);
+        let dense = match self.edge_ids.remove(&id) {
+            Some(dense) => dense,
+            None => return Ok(()),
+        };
 
-        // Remove from outgoing edges index
-        if let Some(mut edges) = self.outgoing_edges.get_mut(&from) {
-            edges.retain(|&eid| eid != id);
+        // Remove from adjacency indexes
+        if let Some(from) = self.node_ids.dense(&edge.1.from()) {
+            if let Some(mut edges) = self.outgoing_edges.get_mut(&from) {
+                edges.retain(|&eid| eid != dense);
+            }
         }
-
-        // Remove from incoming edges index
-        if let Some(mut edges) = self.incoming_edges.get_mut(&to) {
-            edges.retain(|&eid| eid != id);
+        if let Some(to) = self.node_ids.dense(&edge.1.to()) {
+            if let Some(mut edges) = self.incoming_edges.get_mut(&to) {
+                edges.retain(|&eid| eid != dense);
+            }
         }
 
         info!("Edge {} deleted successfully", id);
@@ -248,42 +507,31 @@ impl MemoryStorage {
 
     /// Get all outgoing edges from a node
     pub fn get_outgoing_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
-        if !self.nodes.contains_key(&node_id) {
-            return Err(DeepGraphError::NodeNotFound(node_id.to_string()));
-        }
-
+        let _shared = self.shared();
+        let dense = self.existing_dense_id(node_id)?;
         let edge_ids = self
             .outgoing_edges
-            .get(&node_id)
+            .get(&dense)
             .map(|entry| entry.value().clone())
             .unwrap_or_default();
-
-        Ok(edge_ids
-            .into_iter()
-            .filter_map(|id| self.edges.get(&id).map(|e| e.value().clone()))
-            .collect())
+        Ok(self.resolve_edges(edge_ids))
     }
 
     /// Get all incoming edges to a node
     pub fn get_incoming_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
-        if !self.nodes.contains_key(&node_id) {
-            return Err(DeepGraphError::NodeNotFound(node_id.to_string()));
-        }
-
+        let _shared = self.shared();
+        let dense = self.existing_dense_id(node_id)?;
         let edge_ids = self
             .incoming_edges
-            .get(&node_id)
+            .get(&dense)
             .map(|entry| entry.value().clone())
             .unwrap_or_default();
-
-        Ok(edge_ids
-            .into_iter()
-            .filter_map(|id| self.edges.get(&id).map(|e| e.value().clone()))
-            .collect())
+        Ok(self.resolve_edges(edge_ids))
     }
 
     /// Get all edges of a specific type
     pub fn get_edges_by_type(&self, relationship_type: &str) -> Vec<Edge> {
+        let _shared = self.shared();
         self.edges
             .iter()
             .filter(|entry| entry.value().relationship_type() == relationship_type)
@@ -293,6 +541,7 @@ impl MemoryStorage {
 
     /// Get all nodes in the graph
     pub fn get_all_nodes(&self) -> Vec<Node> {
+        let _shared = self.shared();
         self.nodes
             .iter()
             .map(|entry| entry.value().clone())
@@ -301,18 +550,53 @@ impl MemoryStorage {
 
     /// Get all edges in the graph
     pub fn get_all_edges(&self) -> Vec<Edge> {
+        let _shared = self.shared();
         self.edges
             .iter()
             .map(|entry| entry.value().clone())
             .collect()
     }
 
+    /// Iterate over all nodes
+    ///
+    /// Only the IDs are collected up front; each node is cloned when the
+    /// iterator reaches it, so no shard lock is held between items and the
+    /// storage may be written to while iterating.
+    pub fn iter_nodes(&self) -> impl Iterator<Item = Node> + '_ {
+        let ids: Vec<NodeId> = {
+            let _shared = self.shared();
+            self.nodes.iter().map(|entry| *entry.key()).collect()
+        };
+        ids.into_iter().filter_map(move |id| {
+            let _shared = self.shared();
+            self.nodes.get(&id).map(|node| node.value().clone())
+        })
+    }
+
+    /// Iterate over all edges, cloning each edge lazily like [`Self::iter_nodes`]
+    pub fn iter_edges(&self) -> impl Iterator<Item = Edge> + '_ {
+        let ids: Vec<EdgeId> = {
+            let _shared = self.shared();
+            self.edges.iter().map(|entry| *entry.key()).collect()
+        };
+        ids.into_iter().filter_map(move |id| {
+            let _shared = self.shared();
+            self.edges.get(&id).map(|edge| edge.value().clone())
+        })
+    }
+
     /// Clear all data from storage
     pub fn clear(&self) {
+        let _shared = self.shared();
         self.nodes.clear();
         self.edges.clear();
+        self.node_ids.clear();
+        self.edge_ids.clear();
         self.outgoing_edges.clear();
         self.incoming_edges.clear();
+        self.label_index.clear();
+        self.property_index.clear();
+        self.stats.clear();
     }
 }
 
@@ -445,5 +729,94 @@ mod tests {
         let age_30 = storage.get_nodes_by_property("age", &PropertyValue::Integer(30));
         assert_eq!(age_30.len(), 2);
     }
-}
 
+    #[test]
+    fn test_indexes_follow_updates() {
+        let storage = MemoryStorage::new();
+        let mut node = Node::new(vec!["Person".to_string()]);
+        node.set_property("city".to_string(), "NYC".into());
+        let id = storage.add_node(node).unwrap();
+        assert_eq!(storage.get_nodes_by_property("city", &"NYC".into()).len(), 1);
+
+        let mut node = storage.get_node(id).unwrap();
+        node.remove_label("Person");
+        node.add_label("Admin".to_string());
+        node.set_property("city".to_string(), "SF".into());
+        storage.update_node(node).unwrap();
+        assert!(storage.get_nodes_by_label("Person").is_empty());
+        assert_eq!(storage.get_nodes_by_label("Admin").len(), 1);
+        assert!(storage.get_nodes_by_property("city", &"NYC".into()).is_empty());
+        assert_eq!(storage.get_nodes_by_property("city", &"SF".into()).len(), 1);
+
+        storage.delete_node(id).unwrap();
+        assert!(storage.get_nodes_by_label("Admin").is_empty());
+        assert!(storage.label_index.is_empty());
+        assert!(storage.property_index.is_empty());
+    }
+
+    #[test]
+    fn test_dense_ids_back_adjacency() {
+        let storage = MemoryStorage::new();
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let edge = storage.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+
+        let dense_a = storage.dense_node_id(a).unwrap();
+        assert_eq!(storage.node_id_for_dense(dense_a), Some(a));
+        assert_eq!(storage.get_outgoing_edges(a).unwrap()[0].id(), edge);
+        assert_eq!(storage.degree(b).unwrap(), 1);
+
+        // Deleting a node retires its dense ID and the edges it held
+        storage.delete_node(b).unwrap();
+        assert_eq!(storage.dense_node_id(b), None);
+        assert!(storage.get_outgoing_edges(a).unwrap().is_empty());
+        assert_eq!(storage.degree(a).unwrap(), 0);
+
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        assert_ne!(storage.dense_node_id(b), Some(dense_a));
+        assert!(storage.get_incoming_edges(b).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_batch_is_never_seen_half_applied() {
+        let storage = MemoryStorage::new();
+        let ops: Vec<GraphOp> = (0..500)
+            .map(|_| GraphOp::AddNode(Node::new(vec!["Person".to_string()])))
+            .collect();
+
+        let reader = {
+            let storage = storage.clone();
+            std::thread::spawn(move || {
+                for _ in 0..2000 {
+                    let seen = storage.get_nodes_by_label("Person").len();
+                    assert!(seen == 0 || seen == 500, "saw {} of 500 nodes", seen);
+                }
+            })
+        };
+        storage.apply_batch(ops).unwrap();
+        reader.join().unwrap();
+        assert_eq!(storage.node_count(), 500);
+    }
+
+    #[test]
+    fn test_schema_validates_writes() {
+        use crate::storage::schema::{LabelSchema, PropertyDefinition, PropertyType};
+
+        let schema = SchemaRegistry::new().with_label(
+            LabelSchema::new("Person").with_property(PropertyDefinition::new("age", PropertyType::Integer).required()),
+        );
+        let storage = MemoryStorage::new().with_schema(Arc::new(schema));
+
+        assert!(storage.add_node(Node::new(vec!["Person".to_string()])).is_err());
+        assert_eq!(storage.node_count(), 0);
+
+        let mut node = Node::new(vec!["Person".to_string()]);
+        node.set_property("age".to_string(), PropertyValue::Float(30.0));
+        let id = storage.add_node(node).unwrap();
+        assert_eq!(storage.get_node(id).unwrap().get_property("age"), Some(&PropertyValue::Integer(30)));
+
+        let mut node = storage.get_node(id).unwrap();
+        node.set_property("age".to_string(), PropertyValue::String("unknown".to_string()));
+        assert!(storage.update_node(node).is_err());
+    }
+}
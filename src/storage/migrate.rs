@@ -0,0 +1,130 @@
+//! Copying a graph between storage backends
+//!
+//! [`migrate`] streams every node and then every edge from one backend into
+//! another, keeping their IDs, so a graph built in [`MemoryStorage`] can be
+//! moved to [`DiskStorage`] (or back) without an export/import round-trip.
+//! The source stays readable and writable while the copy runs; elements
+//! added after their scan started are not copied.
+//!
+//! [`MemoryStorage`]: crate::storage::MemoryStorage
+//! [`DiskStorage`]: crate::storage::DiskStorage
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let disk = DiskStorage::new("./data/graph.db")?;
+//! let progress = migrate_with_progress(&memory, &disk, 10_000, |p| {
+//!     println!("{}/{} nodes, {}/{} edges", p.nodes_copied, p.total_nodes, p.edges_copied, p.total_edges);
+//! })?;
+//! ```
+
+use crate::error::Result;
+use crate::storage::StorageBackend;
+use log::{info, warn};
+
+/// How far a migration has got
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MigrationProgress {
+    /// Nodes written to the destination
+    pub nodes_copied: usize,
+    /// Edges written to the destination
+    pub edges_copied: usize,
+    /// Edges skipped because an endpoint was deleted during the copy
+    pub edges_skipped: usize,
+    /// Nodes in the source when the migration started
+    pub total_nodes: usize,
+    /// Edges in the source when the migration started
+    pub total_edges: usize,
+}
+
+/// Copy all nodes and edges from `src` into `dst`, preserving IDs
+pub fn migrate(src: &dyn StorageBackend, dst: &dyn StorageBackend) -> Result<MigrationProgress> {
+    migrate_with_progress(src, dst, 0, |_| {})
+}
+
+/// Copy all nodes and edges, calling `on_progress` every `every` elements
+///
+/// `on_progress` also runs once after the last element. An `every` of zero
+/// reports only the final progress. The first failed write aborts the
+/// migration; elements already copied stay in `dst`.
+pub fn migrate_with_progress(
+    src: &dyn StorageBackend,
+    dst: &dyn StorageBackend,
+    every: usize,
+    mut on_progress: impl FnMut(&MigrationProgress),
+) -> Result<MigrationProgress> {
+    let mut progress = MigrationProgress {
+        total_nodes: src.node_count(),
+        total_edges: src.edge_count(),
+        ..Default::default()
+    };
+    info!(
+        "Migrating {} nodes and {} edges",
+        progress.total_nodes, progress.total_edges
+    );
+    let mut report = |progress: &MigrationProgress| {
+        let done = progress.nodes_copied + progress.edges_copied + progress.edges_skipped;
+        if every > 0 && done % every == 0 {
+            on_progress(progress);
+        }
+    };
+
+    for node in src.iter_nodes() {
+        dst.add_node(node)?;
+        progress.nodes_copied += 1;
+        report(&progress);
+    }
+
+    for edge in src.iter_edges() {
+        let id = edge.id();
+        match dst.add_edge(edge) {
+            Ok(_) => progress.edges_copied += 1,
+            // Endpoint deleted in the source after the node scan passed it
+            Err(e) if e.is_not_found() => {
+                warn!("Skipping edge {} during migration: {}", id, e);
+                progress.edges_skipped += 1;
+            }
+            Err(e) => return Err(e),
+        }
+        report(&progress);
+    }
+
+    on_progress(&progress);
+    info!(
+        "Migration complete: {} nodes, {} edges ({} skipped)",
+        progress.nodes_copied, progress.edges_copied, progress.edges_skipped
+    );
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node, PropertyValue};
+    use crate::storage::{DiskStorage, MemoryStorage};
+
+    #[test]
+    fn test_migrate_memory_to_disk() {
+        let memory = MemoryStorage::new();
+        let mut alice = Node::new(vec!["Person".to_string()]);
+        alice.set_property("name".to_string(), PropertyValue::String("Alice".to_string()));
+        let alice = memory.add_node(alice).unwrap();
+        let bob = memory.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let knows = memory.add_edge(Edge::new(alice, bob, "KNOWS".to_string())).unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let disk = DiskStorage::new(temp_dir.path()).unwrap();
+        let mut reports = Vec::new();
+        let progress = migrate_with_progress(&memory, &disk, 1, |p| reports.push(*p)).unwrap();
+
+        assert_eq!(progress.nodes_copied, 2);
+        assert_eq!(progress.edges_copied, 1);
+        assert_eq!(reports.len(), 4);
+        assert_eq!(reports.last(), Some(&progress));
+        assert_eq!(
+            disk.get_node(alice).unwrap().get_property("name"),
+            Some(&PropertyValue::String("Alice".to_string()))
+        );
+        assert_eq!(disk.get_outgoing_edges(alice).unwrap()[0].id(), knows);
+    }
+}
@@ -5,18 +5,100 @@
 //! - Columnar Arrow storage (Phase 2)
 //! - Persistent Parquet storage (Phase 2)
 //! - Disk-based Sled storage (Phase 4)
+//! - LRU read cache over any backend
+//! - Write-ahead logging in front of any backend
+//! - Atomic batches of operations on any backend
+//! - zstd compression of large disk records
+//! - Bloom filters for negative lookups on disk storage
 
 pub mod memory;
+pub mod batch;
+pub mod bloom;
+pub mod cache;
 pub mod columnar;
+pub mod compression;
+pub mod dense_ids;
 pub mod disk;
+pub mod durable;
+pub mod external_ids;
+pub mod migrate;
 pub mod schema;
+pub mod stats;
+pub mod throttle;
+pub mod ttl;
 
 pub use memory::MemoryStorage;
-pub use columnar::ColumnarStorage;
-pub use disk::DiskStorage;
+pub use batch::{validate_batch, GraphOp};
+pub use bloom::{BloomConfig, BloomFilter};
+pub use cache::{CacheStats, CachedStorage};
+pub use columnar::{ColumnarStorage, VacuumStats};
+pub use compression::RecordCompression;
+pub use dense_ids::{DenseId, DenseIdMap};
+pub use disk::{DiskStorage, DiskWriteBatch, DurabilityMode};
+pub use durable::DurableStorage;
+pub use external_ids::ExternalIdRegistry;
+pub use migrate::{migrate, migrate_with_progress, MigrationProgress};
+pub use schema::{LabelSchema, PropertyDefinition, PropertyType, SchemaMode, SchemaRegistry, SchemaViolation};
+pub use stats::{DegreeSummary, GraphStatistics, GraphStats, PropertyKeyStats};
+pub use throttle::{Pressure, ThrottleConfig, WriteThrottle};
+pub use ttl::{sweep_expired, SweepStats, TtlSweeper};
 
 use crate::error::Result;
-use crate::graph::{Edge, EdgeId, Node, NodeId};
+use crate::graph::{Edge, EdgeId, Node, NodeId, PropertyValue};
+
+/// Relative I/O and CPU costs of a storage backend, used by the query planner
+///
+/// Units are arbitrary but comparable across backends: reading one node
+/// sequentially from memory costs `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostConstants {
+    /// Cost of reading one record during a sequential scan
+    pub sequential_read: f64,
+    /// Cost of fetching one record by ID
+    pub random_read: f64,
+    /// Cost of descending one level of an index
+    pub index_probe: f64,
+    /// Cost of evaluating a predicate against one record
+    pub cpu_tuple: f64,
+}
+
+impl CostConstants {
+    /// Costs for the in-memory hash map backend
+    pub fn memory() -> Self {
+        Self {
+            sequential_read: 1.0,
+            random_read: 1.0,
+            index_probe: 1.0,
+            cpu_tuple: 0.1,
+        }
+    }
+    
+    /// Costs for the sled-backed disk backend, where point reads may hit disk
+    pub fn disk() -> Self {
+        Self {
+            sequential_read: 4.0,
+            random_read: 40.0,
+            index_probe: 4.0,
+            cpu_tuple: 0.1,
+        }
+    }
+    
+    /// Costs for the Arrow columnar backend, which favours scans
+    pub fn columnar() -> Self {
+        Self {
+            sequential_read: 0.5,
+            random_read: 2.0,
+            index_probe: 1.0,
+            cpu_tuple: 0.1,
+        }
+    }
+}
+
+impl Default for CostConstants {
+    fn default() -> Self {
+        Self::memory()
+    }
+}
 
 /// Trait for storage backends
 pub trait StorageBackend: Send + Sync {
@@ -50,6 +132,46 @@ pub trait StorageBackend: Send + Sync {
     /// Get all nodes (for full scan - MATCH (n))
     fn get_all_nodes(&self) -> Vec<Node>;
     
+    /// Get all edges
+    ///
+    /// The default collects the outgoing edges of every node; backends with
+    /// an edge table should scan it directly.
+    fn get_all_edges(&self) -> Vec<Edge> {
+        self.get_all_nodes()
+            .iter()
+            .filter_map(|node| self.get_outgoing_edges(node.id()).ok())
+            .flatten()
+            .collect()
+    }
+    
+    /// Get all edges of a specific relationship type
+    fn get_edges_by_type(&self, relationship_type: &str) -> Vec<Edge> {
+        self.iter_edges()
+            .filter(|edge| edge.relationship_type() == relationship_type)
+            .collect()
+    }
+    
+    /// Get all nodes whose property `key` equals `value`
+    fn get_nodes_by_property(&self, key: &str, value: &PropertyValue) -> Vec<Node> {
+        self.iter_nodes()
+            .filter(|node| node.get_property(key) == Some(value))
+            .collect()
+    }
+    
+    /// Iterate over all nodes without materializing them up front
+    ///
+    /// Nodes added or removed while iterating may or may not be observed.
+    fn iter_nodes(&self) -> Box<dyn Iterator<Item = Node> + '_> {
+        Box::new(self.get_all_nodes().into_iter())
+    }
+    
+    /// Iterate over all edges without materializing them up front
+    ///
+    /// Edges added or removed while iterating may or may not be observed.
+    fn iter_edges(&self) -> Box<dyn Iterator<Item = Edge> + '_> {
+        Box::new(self.get_all_edges().into_iter())
+    }
+    
     /// Get outgoing edges from a node
     fn get_outgoing_edges(&self, node_id: NodeId) -> Result<Vec<Edge>>;
     
@@ -61,6 +183,47 @@ pub trait StorageBackend: Send + Sync {
     
     /// Get edge count
     fn edge_count(&self) -> usize;
+    
+    /// Cost constants the query planner should use for this backend
+    fn cost_constants(&self) -> CostConstants {
+        CostConstants::memory()
+    }
+    
+    /// Incrementally maintained label/relationship counts and degrees, if the backend keeps them
+    fn statistics(&self) -> Option<&GraphStatistics> {
+        None
+    }
+    
+    /// Number of edges touching a node (in either direction)
+    fn degree(&self, node_id: NodeId) -> Result<usize> {
+        Ok(self.get_outgoing_edges(node_id)?.len() + self.get_incoming_edges(node_id)?.len())
+    }
+    
+    /// Label and type counts, degree distribution and property cardinalities
+    ///
+    /// Scans the whole graph; prefer [`StorageBackend::statistics`] for
+    /// label and type counts alone.
+    fn graph_stats(&self) -> GraphStats {
+        GraphStats::collect(self)
+    }
+    
+    /// Apply `ops` as one unit after checking their preconditions
+    ///
+    /// See the [`batch`] module. The default applies the operations one by
+    /// one and undoes them on failure; it does not stop concurrent readers
+    /// and writers from interleaving with the batch. [`MemoryStorage`] and
+    /// [`DiskStorage`] override it to apply the batch in isolation.
+    fn apply_batch(&self, ops: Vec<GraphOp>) -> Result<()> {
+        batch::apply_batch(self, ops)
+    }
+    
+    /// Make every write so far durable
+    ///
+    /// The default does nothing, which suits backends that do not buffer
+    /// writes, such as [`MemoryStorage`].
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Re-export the default storage type for backward compatibility
@@ -108,6 +271,26 @@ impl StorageBackend for MemoryStorage {
         MemoryStorage::get_all_nodes(self)
     }
     
+    fn get_all_edges(&self) -> Vec<Edge> {
+        MemoryStorage::get_all_edges(self)
+    }
+    
+    fn get_edges_by_type(&self, relationship_type: &str) -> Vec<Edge> {
+        MemoryStorage::get_edges_by_type(self, relationship_type)
+    }
+    
+    fn get_nodes_by_property(&self, key: &str, value: &PropertyValue) -> Vec<Node> {
+        MemoryStorage::get_nodes_by_property(self, key, value)
+    }
+    
+    fn iter_nodes(&self) -> Box<dyn Iterator<Item = Node> + '_> {
+        Box::new(MemoryStorage::iter_nodes(self))
+    }
+    
+    fn iter_edges(&self) -> Box<dyn Iterator<Item = Edge> + '_> {
+        Box::new(MemoryStorage::iter_edges(self))
+    }
+    
     fn get_outgoing_edges(&self, node_id: NodeId) -> Result<Vec<Edge>> {
         MemoryStorage::get_outgoing_edges(self, node_id)
     }
@@ -123,5 +306,17 @@ impl StorageBackend for MemoryStorage {
     fn edge_count(&self) -> usize {
         MemoryStorage::edge_count(self)
     }
+    
+    fn statistics(&self) -> Option<&GraphStatistics> {
+        Some(MemoryStorage::statistics(self))
+    }
+    
+    fn degree(&self, node_id: NodeId) -> Result<usize> {
+        MemoryStorage::degree(self, node_id)
+    }
+    
+    fn apply_batch(&self, ops: Vec<GraphOp>) -> Result<()> {
+        MemoryStorage::apply_batch(self, ops)
+    }
 }
 
@@ -1,8 +1,15 @@
 //! Arrow schema definitions for graph storage
 //!
-//! Defines the columnar layout for nodes and edges using Apache Arrow
+//! Defines the columnar layout for nodes and edges using Apache Arrow, and
+//! the optional [`SchemaRegistry`] that types node properties per label.
 
+use crate::error::{DeepGraphError, Result};
+use crate::graph::{Node, PropertyValue};
 use arrow::datatypes::{DataType, Field, Schema};
+use log::warn;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 
 /// Get the Arrow schema for node storage
@@ -66,6 +73,295 @@ pub fn property_index_schema() -> Arc<Schema> {
     ]))
 }
 
+/// Declared type of a node property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    List,
+    Map,
+    /// Any value is accepted
+    Any,
+}
+
+impl PropertyType {
+    /// Whether a value already has this type
+    pub fn matches(&self, value: &PropertyValue) -> bool {
+        matches!(
+            (self, value),
+            (PropertyType::Any, _)
+                | (PropertyType::String, PropertyValue::String(_))
+                | (PropertyType::Integer, PropertyValue::Integer(_))
+                | (PropertyType::Float, PropertyValue::Float(_))
+                | (PropertyType::Boolean, PropertyValue::Boolean(_))
+                | (PropertyType::List, PropertyValue::List(_))
+                | (PropertyType::Map, PropertyValue::Map(_))
+        )
+    }
+
+    /// Convert a value to this type when that loses no information
+    ///
+    /// Integers widen to floats, whole floats narrow to integers, and
+    /// strings holding a number or `true`/`false` are parsed; scalars
+    /// convert to strings.
+    pub fn coerce(&self, value: &PropertyValue) -> Option<PropertyValue> {
+        if self.matches(value) {
+            return Some(value.clone());
+        }
+        match (self, value) {
+            (PropertyType::Float, PropertyValue::Integer(i)) => Some(PropertyValue::Float(*i as f64)),
+            (PropertyType::Integer, PropertyValue::Float(f)) if f.fract() == 0.0 && f.is_finite() => {
+                Some(PropertyValue::Integer(*f as i64))
+            }
+            (PropertyType::Integer, PropertyValue::String(s)) => s.trim().parse().ok().map(PropertyValue::Integer),
+            (PropertyType::Float, PropertyValue::String(s)) => s.trim().parse().ok().map(PropertyValue::Float),
+            (PropertyType::Boolean, PropertyValue::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+                "true" => Some(PropertyValue::Boolean(true)),
+                "false" => Some(PropertyValue::Boolean(false)),
+                _ => None,
+            },
+            (PropertyType::String, PropertyValue::Integer(i)) => Some(PropertyValue::String(i.to_string())),
+            (PropertyType::String, PropertyValue::Float(f)) => Some(PropertyValue::String(f.to_string())),
+            (PropertyType::String, PropertyValue::Boolean(b)) => Some(PropertyValue::String(b.to_string())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PropertyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PropertyType::String => "String",
+            PropertyType::Integer => "Integer",
+            PropertyType::Float => "Float",
+            PropertyType::Boolean => "Boolean",
+            PropertyType::List => "List",
+            PropertyType::Map => "Map",
+            PropertyType::Any => "Any",
+        };
+        f.write_str(name)
+    }
+}
+
+fn value_type_name(value: &PropertyValue) -> &'static str {
+    match value {
+        PropertyValue::String(_) => "String",
+        PropertyValue::Integer(_) => "Integer",
+        PropertyValue::Float(_) => "Float",
+        PropertyValue::Boolean(_) => "Boolean",
+        PropertyValue::Null => "Null",
+        PropertyValue::List(_) => "List",
+        PropertyValue::Map(_) => "Map",
+    }
+}
+
+/// Declaration of one property of a label
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyDefinition {
+    /// Property key
+    pub name: String,
+    /// Expected type
+    pub property_type: PropertyType,
+    /// Whether nodes with the label must carry the property
+    pub required: bool,
+    /// Value filled in when the property is missing
+    pub default: Option<PropertyValue>,
+}
+
+impl PropertyDefinition {
+    /// Declare an optional property of the given type
+    pub fn new(name: impl Into<String>, property_type: PropertyType) -> Self {
+        Self {
+            name: name.into(),
+            property_type,
+            required: false,
+            default: None,
+        }
+    }
+
+    /// Require the property to be present
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Fill in `value` when the property is missing
+    pub fn with_default(mut self, value: PropertyValue) -> Self {
+        self.default = Some(value);
+        self
+    }
+}
+
+/// Property declarations for one node label
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelSchema {
+    /// Label the declarations apply to
+    pub label: String,
+    /// Declared properties by key; undeclared properties are allowed
+    pub properties: HashMap<String, PropertyDefinition>,
+}
+
+impl LabelSchema {
+    /// Start a schema for a label with no declared properties
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            properties: HashMap::new(),
+        }
+    }
+
+    /// Declare a property
+    pub fn with_property(mut self, definition: PropertyDefinition) -> Self {
+        self.properties.insert(definition.name.clone(), definition);
+        self
+    }
+}
+
+/// How a [`SchemaRegistry`] treats nodes that do not conform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaMode {
+    /// Reject the write
+    #[default]
+    Strict,
+    /// Log a warning and store the node as given
+    Advisory,
+}
+
+/// A property that does not conform to its label's schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// Label whose schema was violated
+    pub label: String,
+    /// Offending property key
+    pub property: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}: {}", self.label, self.property, self.message)
+    }
+}
+
+/// Per-label property declarations that writes are validated against
+///
+/// Labels without a registered schema are unconstrained. Storage backends
+/// configured with a registry call [`SchemaRegistry::conform`] from
+/// `add_node` and `update_node`.
+#[derive(Debug, Default)]
+pub struct SchemaRegistry {
+    labels: RwLock<HashMap<String, LabelSchema>>,
+    mode: SchemaMode,
+}
+
+impl SchemaRegistry {
+    /// Create an empty strict registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how non-conforming nodes are handled
+    pub fn with_mode(mut self, mode: SchemaMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Register a label schema
+    pub fn with_label(self, schema: LabelSchema) -> Self {
+        self.register(schema);
+        self
+    }
+
+    /// Register a label schema, returning the one it replaced
+    pub fn register(&self, schema: LabelSchema) -> Option<LabelSchema> {
+        self.labels.write().insert(schema.label.clone(), schema)
+    }
+
+    /// Remove a label schema
+    pub fn unregister(&self, label: &str) -> Option<LabelSchema> {
+        self.labels.write().remove(label)
+    }
+
+    /// Schema registered for a label
+    pub fn get(&self, label: &str) -> Option<LabelSchema> {
+        self.labels.read().get(label).cloned()
+    }
+
+    /// How non-conforming nodes are handled
+    pub fn mode(&self) -> SchemaMode {
+        self.mode
+    }
+
+    /// Fill defaults and coerce values in place, returning what could not be fixed
+    pub fn apply(&self, node: &mut Node) -> Vec<SchemaViolation> {
+        let labels = self.labels.read();
+        let mut violations = Vec::new();
+        let node_labels: Vec<String> = node.labels().to_vec();
+
+        for schema in node_labels.iter().filter_map(|label| labels.get(label)) {
+            for definition in schema.properties.values() {
+                let violation = |message: String| SchemaViolation {
+                    label: schema.label.clone(),
+                    property: definition.name.clone(),
+                    message,
+                };
+                match node.get_property(&definition.name) {
+                    None | Some(PropertyValue::Null) => {
+                        if let Some(default) = &definition.default {
+                            node.set_property(definition.name.clone(), default.clone());
+                        } else if definition.required {
+                            violations.push(violation("required property is missing".to_string()));
+                        }
+                    }
+                    Some(value) if definition.property_type.matches(value) => {}
+                    Some(value) => match definition.property_type.coerce(value) {
+                        Some(coerced) => node.set_property(definition.name.clone(), coerced),
+                        None => violations.push(violation(format!(
+                            "expected {}, got {}",
+                            definition.property_type,
+                            value_type_name(value)
+                        ))),
+                    },
+                }
+            }
+        }
+        violations
+    }
+
+    /// Validate a node about to be written
+    ///
+    /// Returns the node with defaults filled and values coerced. In strict
+    /// mode any remaining violation fails the write; in advisory mode it is
+    /// logged and the node is returned as given.
+    pub fn conform(&self, node: Node) -> Result<Node> {
+        let mut conformed = node.clone();
+        let violations = self.apply(&mut conformed);
+        if violations.is_empty() {
+            return Ok(conformed);
+        }
+
+        let summary = violations
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join("; ");
+        match self.mode {
+            SchemaMode::Strict => Err(DeepGraphError::InvalidOperation(format!(
+                "Node {} violates schema: {}",
+                node.id(),
+                summary
+            ))),
+            SchemaMode::Advisory => {
+                warn!("Node {} violates schema: {}", node.id(), summary);
+                Ok(node)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,5 +384,45 @@ mod tests {
         assert_eq!(schema.field(2).name(), "to_id");
         assert_eq!(schema.field(3).name(), "relationship_type");
     }
-}
 
+    fn person_schema(mode: SchemaMode) -> SchemaRegistry {
+        SchemaRegistry::new().with_mode(mode).with_label(
+            LabelSchema::new("Person")
+                .with_property(PropertyDefinition::new("name", PropertyType::String).required())
+                .with_property(PropertyDefinition::new("age", PropertyType::Integer))
+                .with_property(
+                    PropertyDefinition::new("active", PropertyType::Boolean)
+                        .with_default(PropertyValue::Boolean(true)),
+                ),
+        )
+    }
+
+    #[test]
+    fn test_schema_defaults_and_coercion() {
+        let registry = person_schema(SchemaMode::Strict);
+        let mut node = Node::new(vec!["Person".to_string()]);
+        node.set_property("name".to_string(), PropertyValue::String("Ann".to_string()));
+        node.set_property("age".to_string(), PropertyValue::String("42".to_string()));
+
+        let node = registry.conform(node).unwrap();
+        assert_eq!(node.get_property("age"), Some(&PropertyValue::Integer(42)));
+        assert_eq!(node.get_property("active"), Some(&PropertyValue::Boolean(true)));
+
+        // Unregistered labels are unconstrained
+        assert!(registry.conform(Node::new(vec!["Company".to_string()])).is_ok());
+    }
+
+    #[test]
+    fn test_schema_strict_and_advisory() {
+        let mut node = Node::new(vec!["Person".to_string()]);
+        node.set_property("age".to_string(), PropertyValue::String("old".to_string()));
+
+        let err = person_schema(SchemaMode::Strict).conform(node.clone()).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("Person.name: required property is missing"));
+        assert!(message.contains("Person.age: expected Integer, got String"));
+
+        let kept = person_schema(SchemaMode::Advisory).conform(node).unwrap();
+        assert_eq!(kept.get_property("age"), Some(&PropertyValue::String("old".to_string())));
+    }
+}
@@ -0,0 +1,349 @@
+//! Incrementally maintained graph statistics
+//!
+//! Storage backends update these counters on every write, so summary
+//! questions ("how many KNOWS edges are there?", "how many nodes have
+//! degree 3?") are answered without scanning the graph. [`GraphStats`] is the fuller point-in-time summary
+//! returned by [`StorageBackend::graph_stats`].
+
+use crate::graph::{Edge, Node, NodeId};
+use crate::storage::StorageBackend;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Per-label node counts, per-type relationship counts and node degrees
+#[derive(Debug, Default)]
+pub struct GraphStatistics {
+    /// Label -> number of nodes carrying it
+    label_counts: DashMap<String, u64>,
+    /// Relationship type -> number of edges
+    rel_type_counts: DashMap<String, u64>,
+    /// Node -> number of edges touching it (a self-loop counts twice)
+    degrees: DashMap<NodeId, usize>,
+    /// Degree -> number of nodes with that degree
+    degree_counts: DashMap<usize, u64>,
+}
+
+impl GraphStatistics {
+    /// Create empty statistics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an inserted node, which starts out with no edges
+    pub fn node_added(&self, node: &Node) {
+        self.labels_added(node);
+        if let Entry::Vacant(entry) = self.degrees.entry(node.id()) {
+            entry.insert(0);
+            *self.degree_counts.entry(0).or_insert(0) += 1;
+        }
+    }
+
+    /// Record a removed node
+    pub fn node_removed(&self, node: &Node) {
+        for label in node.labels() {
+            Self::decrement(&self.label_counts, label);
+        }
+        if let Some((_, degree)) = self.degrees.remove(&node.id()) {
+            Self::decrement(&self.degree_counts, &degree);
+        }
+    }
+
+    /// Record a node being replaced by a new version; its edges stay
+    pub fn node_updated(&self, old: &Node, new: &Node) {
+        for label in old.labels() {
+            Self::decrement(&self.label_counts, label);
+        }
+        self.labels_added(new);
+    }
+
+    /// Record an inserted edge
+    pub fn edge_added(&self, edge: &Edge) {
+        *self
+            .rel_type_counts
+            .entry(edge.relationship_type().to_string())
+            .or_insert(0) += 1;
+        self.shift_degree(edge.from(), 1);
+        self.shift_degree(edge.to(), 1);
+    }
+
+    /// Record a removed edge
+    pub fn edge_removed(&self, edge: &Edge) {
+        Self::decrement(&self.rel_type_counts, edge.relationship_type());
+        self.shift_degree(edge.from(), -1);
+        self.shift_degree(edge.to(), -1);
+    }
+
+    /// Number of nodes with a label
+    pub fn label_count(&self, label: &str) -> u64 {
+        self.label_counts.get(label).map(|c| *c).unwrap_or(0)
+    }
+
+    /// Number of edges of a relationship type
+    pub fn rel_type_count(&self, rel_type: &str) -> u64 {
+        self.rel_type_counts.get(rel_type).map(|c| *c).unwrap_or(0)
+    }
+
+    /// Number of edges touching a node, if the node was recorded
+    pub fn degree(&self, id: NodeId) -> Option<usize> {
+        self.degrees.get(&id).map(|degree| *degree)
+    }
+
+    /// Snapshot of the degree histogram: degree -> number of nodes
+    pub fn degree_counts(&self) -> HashMap<usize, u64> {
+        self.degree_counts
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect()
+    }
+
+    /// Snapshot of all label counts
+    pub fn label_counts(&self) -> HashMap<String, u64> {
+        self.label_counts
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect()
+    }
+
+    /// Snapshot of all relationship type counts
+    pub fn rel_type_counts(&self) -> HashMap<String, u64> {
+        self.rel_type_counts
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect()
+    }
+
+    /// Reset all counters
+    pub fn clear(&self) {
+        self.label_counts.clear();
+        self.rel_type_counts.clear();
+        self.degrees.clear();
+        self.degree_counts.clear();
+    }
+
+    fn labels_added(&self, node: &Node) {
+        for label in node.labels() {
+            *self.label_counts.entry(label.clone()).or_insert(0) += 1;
+        }
+    }
+
+    /// Move a node to another degree bucket; endpoints never recorded as
+    /// nodes (e.g. already deleted) are ignored
+    fn shift_degree(&self, id: NodeId, delta: isize) {
+        if let Some(mut degree) = self.degrees.get_mut(&id) {
+            let old = *degree;
+            *degree = old.saturating_add_signed(delta);
+            Self::decrement(&self.degree_counts, &old);
+            *self.degree_counts.entry(*degree).or_insert(0) += 1;
+        }
+    }
+
+    fn decrement<K, Q>(counts: &DashMap<K, u64>, key: &Q)
+    where
+        K: Eq + Hash + Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let now_zero = match counts.get_mut(key) {
+            Some(mut count) => {
+                *count = count.saturating_sub(1);
+                *count == 0
+            }
+            None => false,
+        };
+        if now_zero {
+            counts.remove_if(key, |_, count| *count == 0);
+        }
+    }
+}
+
+/// Distribution of node degrees (incoming plus outgoing edges)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DegreeSummary {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub median: usize,
+    /// 99th percentile
+    pub p99: usize,
+}
+
+impl DegreeSummary {
+    /// Summarize a list of degrees
+    pub fn from_degrees(mut degrees: Vec<usize>) -> Self {
+        if degrees.is_empty() {
+            return Self::default();
+        }
+        degrees.sort_unstable();
+        let n = degrees.len();
+        let percentile = |p: f64| degrees[((n - 1) as f64 * p).round() as usize];
+        Self {
+            min: degrees[0],
+            max: degrees[n - 1],
+            mean: degrees.iter().sum::<usize>() as f64 / n as f64,
+            median: percentile(0.5),
+            p99: percentile(0.99),
+        }
+    }
+}
+
+/// How a node property key is used
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PropertyKeyStats {
+    /// Nodes carrying the key
+    pub node_count: u64,
+    /// Distinct values stored under the key
+    pub distinct_values: u64,
+}
+
+impl PropertyKeyStats {
+    /// Share of the nodes carrying the key that have a unique value, in `0..=1`
+    pub fn selectivity(&self) -> f64 {
+        if self.node_count == 0 {
+            0.0
+        } else {
+            self.distinct_values as f64 / self.node_count as f64
+        }
+    }
+}
+
+/// Point-in-time summary of a graph's shape
+///
+/// Built by one scan over all nodes and edges; used for planner estimates
+/// and monitoring.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GraphStats {
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// Label -> number of nodes carrying it
+    pub label_counts: HashMap<String, u64>,
+    /// Relationship type -> number of edges
+    pub rel_type_counts: HashMap<String, u64>,
+    pub degree: DegreeSummary,
+    /// Node property key -> usage and cardinality
+    pub property_keys: HashMap<String, PropertyKeyStats>,
+}
+
+impl GraphStats {
+    /// Scan a backend and summarize it
+    pub fn collect<S: StorageBackend + ?Sized>(storage: &S) -> Self {
+        let mut stats = Self::default();
+        let mut degrees: HashMap<NodeId, usize> = HashMap::new();
+        let mut distinct: HashMap<String, HashSet<Vec<u8>>> = HashMap::new();
+
+        for node in storage.iter_nodes() {
+            stats.node_count += 1;
+            degrees.insert(node.id(), 0);
+            for label in node.labels() {
+                *stats.label_counts.entry(label.clone()).or_insert(0) += 1;
+            }
+            for (key, value) in node.properties() {
+                stats.property_keys.entry(key.clone()).or_default().node_count += 1;
+                // Property values are not hashable (floats); compare their index keys
+                distinct.entry(key.clone()).or_default().insert(value.index_key());
+            }
+        }
+        for (key, values) in distinct {
+            if let Some(key_stats) = stats.property_keys.get_mut(&key) {
+                key_stats.distinct_values = values.len() as u64;
+            }
+        }
+
+        for edge in storage.iter_edges() {
+            stats.edge_count += 1;
+            *stats
+                .rel_type_counts
+                .entry(edge.relationship_type().to_string())
+                .or_insert(0) += 1;
+            for endpoint in [edge.from(), edge.to()] {
+                if let Some(degree) = degrees.get_mut(&endpoint) {
+                    *degree += 1;
+                }
+            }
+        }
+
+        stats.degree = DegreeSummary::from_degrees(degrees.into_values().collect());
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::NodeId;
+
+    #[test]
+    fn test_counts_track_writes() {
+        let stats = GraphStatistics::new();
+        let person = Node::new(vec!["Person".to_string()]);
+        let edge = Edge::new(NodeId::new(), NodeId::new(), "KNOWS".to_string());
+
+        stats.node_added(&person);
+        stats.edge_added(&edge);
+        stats.edge_added(&edge);
+        assert_eq!(stats.label_count("Person"), 1);
+        assert_eq!(stats.rel_type_count("KNOWS"), 2);
+
+        stats.edge_removed(&edge);
+        stats.edge_removed(&edge);
+        stats.node_removed(&person);
+        assert!(stats.rel_type_counts().is_empty());
+        assert_eq!(stats.label_count("Person"), 0);
+    }
+
+    #[test]
+    fn test_degree_histogram_tracks_writes() {
+        let stats = GraphStatistics::new();
+        let (a, b) = (Node::new(vec![]), Node::new(vec![]));
+        stats.node_added(&a);
+        stats.node_added(&b);
+        let knows = Edge::new(a.id(), b.id(), "KNOWS".to_string());
+        let self_loop = Edge::new(a.id(), a.id(), "KNOWS".to_string());
+        stats.edge_added(&knows);
+        stats.edge_added(&self_loop);
+        assert_eq!(stats.degree(a.id()), Some(3));
+        assert_eq!(stats.degree_counts(), HashMap::from([(1, 1), (3, 1)]));
+
+        // Relabeling keeps the degree; deleting a node drops its bucket
+        let mut person = b.clone();
+        person.add_label("Person".to_string());
+        stats.node_updated(&b, &person);
+        stats.node_removed(&a);
+        stats.edge_removed(&knows);
+        stats.edge_removed(&self_loop);
+        assert_eq!(stats.degree(b.id()), Some(0));
+        assert_eq!(stats.label_count("Person"), 1);
+        assert_eq!(stats.degree_counts(), HashMap::from([(0, 1)]));
+    }
+
+    #[test]
+    fn test_graph_stats_collect() {
+        use crate::graph::PropertyValue;
+        use crate::storage::MemoryStorage;
+
+        let storage = MemoryStorage::new();
+        let mut ids = Vec::new();
+        for (city, score) in [("Paris", 0.0), ("Paris", -0.0), ("Rome", 1.5)] {
+            let mut node = Node::new(vec!["Person".to_string()]);
+            node.set_property("city".to_string(), PropertyValue::String(city.to_string()));
+            node.set_property("score".to_string(), PropertyValue::Float(score));
+            ids.push(storage.add_node(node).unwrap());
+        }
+        storage.add_node(Node::new(vec!["Company".to_string()])).unwrap();
+        storage.add_edge(Edge::new(ids[0], ids[1], "KNOWS".to_string())).unwrap();
+        storage.add_edge(Edge::new(ids[0], ids[2], "KNOWS".to_string())).unwrap();
+
+        let stats = storage.graph_stats();
+        assert_eq!(stats.node_count, 4);
+        assert_eq!(stats.label_counts["Person"], 3);
+        assert_eq!(stats.rel_type_counts["KNOWS"], 2);
+        assert_eq!(stats.property_keys["city"], PropertyKeyStats { node_count: 3, distinct_values: 2 });
+        // 0.0 and -0.0 are equal values
+        assert_eq!(stats.property_keys["score"], PropertyKeyStats { node_count: 3, distinct_values: 2 });
+        assert_eq!(stats.degree.min, 0);
+        assert_eq!(stats.degree.max, 2);
+        assert_eq!(stats.degree.mean, 1.0);
+    }
+}
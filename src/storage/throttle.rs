@@ -0,0 +1,215 @@
+//! Write throttling and backpressure
+//!
+//! A [`WriteThrottle`] tracks work that writers have produced but a
+//! background step (WAL flush, columnar vacuum) has not yet absorbed. Once
+//! the outstanding amount crosses the hard limit, writers either wait for it
+//! to drain or get [`DeepGraphError::Throttled`], instead of memory usage and
+//! commit latency growing without bound.
+
+use crate::error::{DeepGraphError, Result};
+use parking_lot::{Condvar, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Limits for a [`WriteThrottle`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThrottleConfig {
+    /// Outstanding units above which [`Pressure::Elevated`] is reported
+    pub soft_limit: u64,
+    /// Outstanding units writers may not exceed
+    pub hard_limit: u64,
+    /// How long a blocking writer waits for the backlog to drain
+    pub max_wait: Duration,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            soft_limit: 8 * 1024 * 1024,
+            hard_limit: 32 * 1024 * 1024,
+            max_wait: Duration::from_secs(1),
+        }
+    }
+}
+
+impl ThrottleConfig {
+    /// Create a config with defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the soft and hard limits
+    pub fn with_limits(mut self, soft_limit: u64, hard_limit: u64) -> Self {
+        self.soft_limit = soft_limit.min(hard_limit);
+        self.hard_limit = hard_limit;
+        self
+    }
+
+    /// Set how long blocking writers wait before giving up
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+}
+
+/// Backpressure signal exposed to writers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Pressure {
+    /// Below the soft limit
+    Normal,
+    /// Between the soft and hard limits; writers should slow down
+    Elevated,
+    /// At the hard limit; new writes wait or are rejected
+    Saturated,
+}
+
+/// Bounded counter of outstanding write work
+#[derive(Debug)]
+pub struct WriteThrottle {
+    config: ThrottleConfig,
+    outstanding: Mutex<u64>,
+    drained: Condvar,
+    throttled: AtomicU64,
+}
+
+impl WriteThrottle {
+    /// Create a throttle with the given limits
+    pub fn new(config: ThrottleConfig) -> Self {
+        Self {
+            config,
+            outstanding: Mutex::new(0),
+            drained: Condvar::new(),
+            throttled: AtomicU64::new(0),
+        }
+    }
+
+    /// Current backpressure level
+    pub fn pressure(&self) -> Pressure {
+        let outstanding = *self.outstanding.lock();
+        if outstanding >= self.config.hard_limit {
+            Pressure::Saturated
+        } else if outstanding >= self.config.soft_limit {
+            Pressure::Elevated
+        } else {
+            Pressure::Normal
+        }
+    }
+
+    /// Units produced but not yet released
+    pub fn outstanding(&self) -> u64 {
+        *self.outstanding.lock()
+    }
+
+    /// Number of writes that were rejected
+    pub fn throttled_count(&self) -> u64 {
+        self.throttled.load(Ordering::Relaxed)
+    }
+
+    /// Whether reserving `units` now would have to wait
+    pub fn would_wait(&self, units: u64) -> bool {
+        Self::would_block(*self.outstanding.lock(), units, self.config.hard_limit)
+    }
+
+    /// Reserve `units` without waiting, failing with `Throttled` when full
+    pub fn try_acquire(&self, units: u64) -> Result<()> {
+        let mut outstanding = self.outstanding.lock();
+        if Self::would_block(*outstanding, units, self.config.hard_limit) {
+            return Err(self.reject(*outstanding));
+        }
+        *outstanding += units;
+        Ok(())
+    }
+
+    /// Reserve `units`, waiting up to `max_wait` for the backlog to drain
+    pub fn acquire(&self, units: u64) -> Result<()> {
+        let deadline = Instant::now() + self.config.max_wait;
+        let mut outstanding = self.outstanding.lock();
+        while Self::would_block(*outstanding, units, self.config.hard_limit) {
+            if self.drained.wait_until(&mut outstanding, deadline).timed_out()
+                && Self::would_block(*outstanding, units, self.config.hard_limit)
+            {
+                return Err(self.reject(*outstanding));
+            }
+        }
+        *outstanding += units;
+        Ok(())
+    }
+
+    /// Async variant of [`WriteThrottle::acquire`] that yields instead of blocking a thread
+    pub async fn acquire_async(&self, units: u64) -> Result<()> {
+        let deadline = Instant::now() + self.config.max_wait;
+        loop {
+            match self.try_acquire(units) {
+                Err(e) if e.is_throttled() && Instant::now() < deadline => {
+                    // try_acquire counted a rejection; only the final one should count
+                    self.throttled.fetch_sub(1, Ordering::Relaxed);
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Mark `units` as absorbed by the background step and wake waiters
+    pub fn release(&self, units: u64) {
+        let mut outstanding = self.outstanding.lock();
+        *outstanding = outstanding.saturating_sub(units);
+        self.drained.notify_all();
+    }
+
+    /// An empty backlog always admits one request, however large, so an
+    /// oversized write cannot block forever
+    fn would_block(outstanding: u64, units: u64, hard_limit: u64) -> bool {
+        outstanding > 0 && outstanding + units > hard_limit
+    }
+
+    fn reject(&self, outstanding: u64) -> DeepGraphError {
+        self.throttled.fetch_add(1, Ordering::Relaxed);
+        DeepGraphError::Throttled(format!(
+            "{} units outstanding, hard limit {}",
+            outstanding, self.config.hard_limit
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_try_acquire_rejects_at_hard_limit() {
+        let throttle = WriteThrottle::new(ThrottleConfig::new().with_limits(5, 10));
+        throttle.try_acquire(6).unwrap();
+        assert_eq!(throttle.pressure(), Pressure::Elevated);
+
+        let err = throttle.try_acquire(6).unwrap_err();
+        assert!(err.is_throttled());
+        assert_eq!(throttle.throttled_count(), 1);
+
+        throttle.release(6);
+        assert_eq!(throttle.pressure(), Pressure::Normal);
+        throttle.try_acquire(6).unwrap();
+    }
+
+    #[test]
+    fn test_acquire_waits_for_release() {
+        let throttle = Arc::new(WriteThrottle::new(
+            ThrottleConfig::new()
+                .with_limits(10, 10)
+                .with_max_wait(Duration::from_secs(5)),
+        ));
+        throttle.acquire(10).unwrap();
+
+        let releaser = {
+            let throttle = throttle.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                throttle.release(10);
+            })
+        };
+        throttle.acquire(5).unwrap();
+        releaser.join().unwrap();
+        assert_eq!(throttle.outstanding(), 5);
+    }
+}
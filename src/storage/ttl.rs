@@ -0,0 +1,191 @@
+//! Expiration of nodes and edges
+//!
+//! Elements carry an optional expiry time in the reserved
+//! [`EXPIRES_AT_PROPERTY`](crate::graph::EXPIRES_AT_PROPERTY) (see [`Node::set_ttl`](crate::graph::Node::set_ttl)).
+//! [`sweep_expired`] deletes every element whose time has passed, and a
+//! [`TtlSweeper`] runs it periodically on a background thread. Removals are
+//! counted under [`OperatorEvent::Expired`] in the global metrics registry.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut session = Node::new(vec!["Session".to_string()]);
+//! session.set_ttl(Duration::from_secs(30 * 60));
+//! storage.add_node(session)?;
+//!
+//! let sweeper = TtlSweeper::spawn(storage.clone(), Duration::from_secs(60));
+//! // ...
+//! sweeper.stop();
+//! ```
+
+use crate::error::Result;
+use crate::metrics::{self, OperatorEvent};
+use crate::storage::StorageBackend;
+use log::{debug, info, warn};
+use parking_lot::{Condvar, Mutex};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Elements removed by one sweep
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepStats {
+    /// Expired nodes deleted, with their edges
+    pub nodes_expired: usize,
+    /// Expired edges deleted
+    pub edges_expired: usize,
+}
+
+/// Current time in Unix milliseconds
+pub fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Delete every node and edge that has expired as of `now_ms`
+///
+/// Edges are swept first so an edge is not counted twice when its node
+/// expires too. Elements deleted concurrently are skipped.
+pub fn sweep_expired<S: StorageBackend + ?Sized>(storage: &S, now_ms: i64) -> Result<SweepStats> {
+    let mut stats = SweepStats::default();
+
+    let expired_edges: Vec<_> = storage
+        .iter_edges()
+        .filter(|edge| edge.is_expired_at(now_ms))
+        .map(|edge| edge.id())
+        .collect();
+    for id in expired_edges {
+        match storage.delete_edge(id) {
+            Ok(()) => stats.edges_expired += 1,
+            Err(e) if e.is_not_found() => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let expired_nodes: Vec<_> = storage
+        .iter_nodes()
+        .filter(|node| node.is_expired_at(now_ms))
+        .map(|node| node.id())
+        .collect();
+    for id in expired_nodes {
+        match storage.delete_node(id) {
+            Ok(()) => stats.nodes_expired += 1,
+            Err(e) if e.is_not_found() => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let registry = metrics::global();
+    registry.record_n(OperatorEvent::Expired, "NodeExpiry", stats.nodes_expired as u64);
+    registry.record_n(OperatorEvent::Expired, "EdgeExpiry", stats.edges_expired as u64);
+    if stats != SweepStats::default() {
+        debug!(
+            "Expired {} nodes and {} edges",
+            stats.nodes_expired, stats.edges_expired
+        );
+    }
+    Ok(stats)
+}
+
+/// Background thread that sweeps expired elements at a fixed interval
+pub struct TtlSweeper {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl TtlSweeper {
+    /// Start sweeping `storage` every `interval`
+    pub fn spawn<S: StorageBackend + 'static>(storage: Arc<S>, interval: Duration) -> Self {
+        info!("Starting TTL sweeper (interval {:?})", interval);
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let signal = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let (stopped, wake) = &*signal;
+            loop {
+                {
+                    let mut stopped = stopped.lock();
+                    if !*stopped {
+                        wake.wait_for(&mut stopped, interval);
+                    }
+                    if *stopped {
+                        break;
+                    }
+                }
+                if let Err(e) = sweep_expired(storage.as_ref(), now_millis()) {
+                    warn!("TTL sweep failed: {}", e);
+                }
+            }
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop the sweeper and wait for the thread to exit
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let (stopped, wake) = &*self.stop;
+        *stopped.lock() = true;
+        wake.notify_all();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for TtlSweeper {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node};
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_sweep_expired() {
+        let storage = MemoryStorage::new();
+        let keep = storage.add_node(Node::new(vec!["User".to_string()])).unwrap();
+        let mut session = Node::new(vec!["Session".to_string()]);
+        session.set_expires_at(1_000);
+        let session = storage.add_node(session).unwrap();
+        let other = storage.add_node(Node::new(vec!["User".to_string()])).unwrap();
+
+        storage.add_edge(Edge::new(keep, session, "OWNS".to_string())).unwrap();
+        let mut cached = Edge::new(keep, other, "SEEN".to_string());
+        cached.set_expires_at(2_000);
+        storage.add_edge(cached).unwrap();
+
+        assert_eq!(sweep_expired(&storage, 500).unwrap(), SweepStats::default());
+        let stats = sweep_expired(&storage, 1_500).unwrap();
+        assert_eq!(stats, SweepStats { nodes_expired: 1, edges_expired: 0 });
+        assert_eq!(storage.node_count(), 2);
+        assert_eq!(storage.edge_count(), 1);
+
+        let stats = sweep_expired(&storage, 2_000).unwrap();
+        assert_eq!(stats.edges_expired, 1);
+        assert_eq!(storage.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_background_sweeper() {
+        let storage = Arc::new(MemoryStorage::new());
+        let mut node = Node::new(vec!["Session".to_string()]);
+        node.set_ttl(Duration::from_millis(0));
+        storage.add_node(node).unwrap();
+
+        let sweeper = TtlSweeper::spawn(storage.clone(), Duration::from_millis(5));
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while storage.node_count() > 0 && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        sweeper.stop();
+        assert_eq!(storage.node_count(), 0);
+    }
+}
@@ -1,36 +1,44 @@
-//! Transaction management framework (placeholder)
+//! Transaction management framework
 //!
-//! This module will eventually provide full ACID transaction support.
-//! For Phase 1, we provide a placeholder structure to demonstrate the framework.
+//! A [`Transaction`] buffers its writes and applies them to storage at
+//! commit, undoing the applied ones if storage rejects any, so a
+//! transaction takes effect completely or not at all.
+//!
+//! Transactions are registered with the MVCC
+//! [`TransactionManager`](crate::mvcc::TransactionManager): beginning one
+//! takes a [`Snapshot`], and committing or rolling back ends it there.
+//! Storage is accessed through a [`VersionedStorage`], so reads return the
+//! version committed as of the snapshot, and a commit that would overwrite
+//! a change the snapshot cannot see is rejected (first committer wins).
+//!
+//! A read-only transaction, begun with
+//! [`TransactionManager::begin_read_only_transaction`], rejects writes and
+//! never takes the commit lock, so long analytics queries can hold a
+//! stable snapshot without blocking writers. Its snapshot stays readable
+//! until it ends; [`mvcc::TransactionManager::active_transactions`] lists
+//! the transactions currently holding one, with their ages.
+//!
+//! [`TransactionLimits`] cap how many writes a transaction may buffer, how
+//! large its buffer may grow and how long it may run. A transaction that
+//! exceeds a limit is aborted and the offending call fails, protecting the
+//! server from runaway clients.
+//!
+//! Callbacks registered with [`TransactionManager::add_listener`] are told
+//! when a transaction begins, commits or aborts, with a [`ChangeSummary`]
+//! of what it wrote, for cache invalidation or audit logging.
 
 use crate::error::{DeepGraphError, Result};
 use crate::graph::{Edge, EdgeId, Node, NodeId};
+use crate::mvcc::{self, Snapshot, VersionedStorage};
 use crate::storage::GraphStorage;
+use log::{debug, warn};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use uuid::Uuid;
-
-/// Transaction ID
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct TransactionId(Uuid);
-
-impl TransactionId {
-    /// Create a new transaction ID
-    pub fn new() -> Self {
-        Self(Uuid::new_v4())
-    }
-}
-
-impl Default for TransactionId {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+use std::time::{Duration, Instant};
 
-impl std::fmt::Display for TransactionId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
-    }
-}
+pub use crate::mvcc::TransactionId;
 
 /// Transaction state
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -62,15 +70,198 @@ pub enum IsolationLevel {
     Serializable,
 }
 
-/// A database transaction (placeholder)
+/// Resource limits for a transaction; unlimited by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionLimits {
+    /// Most writes a transaction may buffer
+    pub max_writes: Option<usize>,
+    /// Longest a transaction may run, from begin to commit
+    pub max_duration: Option<Duration>,
+    /// Largest serialized size of the buffered writes, in bytes
+    pub max_buffer_bytes: Option<usize>,
+}
+
+impl TransactionLimits {
+    /// No limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Abort transactions that buffer more than `max_writes` writes
+    pub fn with_max_writes(mut self, max_writes: usize) -> Self {
+        self.max_writes = Some(max_writes);
+        self
+    }
+
+    /// Abort transactions running longer than `max_duration`
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Abort transactions whose buffered writes exceed `max_buffer_bytes`
+    pub fn with_max_buffer_bytes(mut self, max_buffer_bytes: usize) -> Self {
+        self.max_buffer_bytes = Some(max_buffer_bytes);
+        self
+    }
+}
+
+/// Elements a transaction wrote
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSummary {
+    /// Nodes added or updated
+    pub nodes_written: Vec<NodeId>,
+    /// Nodes deleted
+    pub nodes_deleted: Vec<NodeId>,
+    /// Edges added or updated
+    pub edges_written: Vec<EdgeId>,
+    /// Edges deleted, including those removed with a deleted node
+    pub edges_deleted: Vec<EdgeId>,
+}
+
+impl ChangeSummary {
+    /// Number of elements written or deleted
+    pub fn len(&self) -> usize {
+        self.nodes_written.len() + self.nodes_deleted.len() + self.edges_written.len() + self.edges_deleted.len()
+    }
+
+    /// Whether the transaction wrote nothing
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A transaction lifecycle event passed to listeners
+#[derive(Debug, Clone)]
+pub enum TransactionEvent {
+    /// A transaction began
+    Begin {
+        /// Transaction ID
+        id: TransactionId,
+        /// Whether it is read-only
+        read_only: bool,
+    },
+    /// A transaction committed; its changes are visible in storage
+    Commit {
+        /// Transaction ID
+        id: TransactionId,
+        /// What it wrote
+        changes: ChangeSummary,
+    },
+    /// A transaction ended without applying anything, whether rolled back,
+    /// rejected at commit, aborted on a limit or dropped
+    Abort {
+        /// Transaction ID
+        id: TransactionId,
+        /// What it had buffered
+        changes: ChangeSummary,
+    },
+}
+
+impl TransactionEvent {
+    /// The transaction the event concerns
+    pub fn transaction_id(&self) -> TransactionId {
+        match self {
+            TransactionEvent::Begin { id, .. }
+            | TransactionEvent::Commit { id, .. }
+            | TransactionEvent::Abort { id, .. } => *id,
+        }
+    }
+}
+
+/// Handle for removing a listener
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+type Listener = Arc<dyn Fn(&TransactionEvent) + Send + Sync>;
+
+/// Listeners shared by a manager and the transactions it begins
+#[derive(Default)]
+struct Listeners {
+    next_id: AtomicU64,
+    listeners: RwLock<Vec<(ListenerId, Listener)>>,
+}
+
+impl Listeners {
+    /// Call every listener, outside the lock so they may register others
+    fn notify(&self, event: TransactionEvent) {
+        let listeners: Vec<Listener> = self.listeners.read().iter().map(|(_, listener)| listener.clone()).collect();
+        for listener in listeners {
+            listener(&event);
+        }
+    }
+}
+
+/// A write buffered by a transaction, replayed in order at commit
+#[derive(Debug, Clone)]
+enum Write {
+    PutNode(Node),
+    DeleteNode(NodeId),
+    PutEdge(Edge),
+    DeleteEdge(EdgeId),
+}
+
+impl Write {
+    /// Approximate memory held by the write
+    fn size(&self) -> usize {
+        let payload = match self {
+            Write::PutNode(node) => bincode::serialized_size(node).unwrap_or(0),
+            Write::PutEdge(edge) => bincode::serialized_size(edge).unwrap_or(0),
+            Write::DeleteNode(_) | Write::DeleteEdge(_) => 0,
+        };
+        std::mem::size_of::<Write>() + payload as usize
+    }
+}
+
+/// How to reverse one applied write if a later one fails
+enum Undo {
+    /// Put back this node, or delete it if it did not exist
+    Node(NodeId, Option<Node>),
+    /// Put back this edge, or delete it if it did not exist
+    Edge(EdgeId, Option<Edge>),
+    /// Restore a deleted node together with the edges deleted with it
+    DeletedNode(Node, Vec<Edge>),
+}
+
+/// An overlay entry as it was before a write replaced it; `None` if absent
+enum OverlayUndo {
+    Node(NodeId, Option<Option<Node>>),
+    Edge(EdgeId, Option<Option<Edge>>),
+}
+
+/// Position of the write buffer when a savepoint was taken
+struct Savepoint {
+    name: String,
+    writes: usize,
+    buffer_bytes: usize,
+    overlay_undo: usize,
+}
+
+/// A node or edge, as tracked for conflict detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Element {
+    Node(NodeId),
+    Edge(EdgeId),
+}
+
+impl std::fmt::Display for Element {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Element::Node(id) => write!(f, "node {}", id),
+            Element::Edge(id) => write!(f, "edge {}", id),
+        }
+    }
+}
+
+/// A database transaction
 ///
-/// In Phase 1, this is a simple wrapper around the storage engine.
-/// Future phases will implement proper ACID guarantees with:
-/// - Write-ahead logging (WAL)
-/// - Multi-version concurrency control (MVCC)
-/// - Proper isolation levels
-/// - Deadlock detection
-#[derive(Debug)]
+/// Writes are buffered in the transaction and only reach storage on
+/// [`commit`](Transaction::commit); [`rollback`](Transaction::rollback), or
+/// dropping the transaction, discards them. Reads see the transaction's own
+/// writes on top of the snapshot. Commit is all or nothing: if storage rejects a
+/// write, the writes already applied are undone before the error is
+/// returned. The isolation level is recorded only; every transaction runs
+/// under snapshot isolation.
 pub struct Transaction {
     /// Transaction ID
     id: TransactionId,
@@ -78,24 +269,94 @@ pub struct Transaction {
     state: TransactionState,
     /// Isolation level
     isolation_level: IsolationLevel,
-    /// Reference to the storage engine
-    storage: Arc<GraphStorage>,
+    /// Committed state visible to this transaction
+    snapshot: Snapshot,
+    /// Storage, read as of `snapshot`
+    storage: Arc<VersionedStorage<GraphStorage>>,
+    /// Buffered writes in the order they were made
+    writes: Vec<Write>,
+    /// Latest buffered version of each written node; `None` if deleted
+    nodes: HashMap<NodeId, Option<Node>>,
+    /// Latest buffered version of each written edge; `None` if deleted
+    edges: HashMap<EdgeId, Option<Edge>>,
+    /// Previous overlay entries, newest last, for rolling back to a savepoint
+    overlay_undo: Vec<OverlayUndo>,
+    /// Open savepoints, oldest first
+    savepoints: Vec<Savepoint>,
+    /// Whether writes are rejected
+    read_only: bool,
+    /// Limits enforced on writes and at commit
+    limits: TransactionLimits,
+    /// When the transaction began
+    started_at: Instant,
+    /// Approximate size of `writes`
+    buffer_bytes: usize,
+    /// Told about begin, commit and abort
+    listeners: Arc<Listeners>,
+}
+
+impl std::fmt::Debug for Transaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transaction")
+            .field("id", &self.id)
+            .field("state", &self.state)
+            .field("isolation_level", &self.isolation_level)
+            .field("read_only", &self.read_only)
+            .field("pending_writes", &self.writes.len())
+            .finish()
+    }
 }
 
 impl Transaction {
-    /// Begin a new transaction
-    pub fn begin(storage: Arc<GraphStorage>) -> Self {
+    /// Begin a transaction on its own
+    ///
+    /// It is isolated from nothing else; begin transactions through a
+    /// shared [`TransactionManager`] to detect conflicts between them.
+    pub fn begin(storage: Arc<GraphStorage>) -> Result<Self> {
         Self::begin_with_isolation(storage, IsolationLevel::ReadCommitted)
     }
 
-    /// Begin a new transaction with a specific isolation level
-    pub fn begin_with_isolation(storage: Arc<GraphStorage>, isolation_level: IsolationLevel) -> Self {
-        Self {
-            id: TransactionId::new(),
+    /// Begin a transaction on its own with a specific isolation level
+    pub fn begin_with_isolation(storage: Arc<GraphStorage>, isolation_level: IsolationLevel) -> Result<Self> {
+        TransactionManager::new(storage).begin_transaction_with_isolation(isolation_level)
+    }
+
+    /// Begin a read-only transaction on its own
+    pub fn begin_read_only(storage: Arc<GraphStorage>) -> Result<Self> {
+        TransactionManager::new(storage).begin_read_only_transaction()
+    }
+
+    fn start(manager: &TransactionManager, isolation_level: IsolationLevel, read_only: bool) -> Result<Self> {
+        let storage = Arc::clone(&manager.storage);
+        let (id, snapshot) = if read_only {
+            storage.transactions().begin_read_only_transaction()?
+        } else {
+            storage.transactions().begin_transaction()?
+        };
+        debug!(
+            "Began {}transaction {} at snapshot {}",
+            if read_only { "read-only " } else { "" },
+            id,
+            snapshot.timestamp
+        );
+        manager.listeners.notify(TransactionEvent::Begin { id, read_only });
+        Ok(Self {
+            id,
             state: TransactionState::Active,
             isolation_level,
+            snapshot,
             storage,
-        }
+            writes: Vec::new(),
+            nodes: HashMap::new(),
+            edges: HashMap::new(),
+            overlay_undo: Vec::new(),
+            savepoints: Vec::new(),
+            read_only,
+            limits: manager.limits,
+            started_at: Instant::now(),
+            buffer_bytes: 0,
+            listeners: Arc::clone(&manager.listeners),
+        })
     }
 
     /// Get the transaction ID
@@ -113,75 +374,285 @@ impl Transaction {
         self.isolation_level
     }
 
+    /// The snapshot the transaction reads from
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
     /// Check if the transaction is active
     pub fn is_active(&self) -> bool {
         self.state == TransactionState::Active
     }
 
+    /// Whether the transaction rejects writes
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// The limits the transaction runs under
+    pub fn limits(&self) -> TransactionLimits {
+        self.limits
+    }
+
+    /// Time since the transaction began
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Approximate memory held by buffered writes, in bytes
+    pub fn buffer_bytes(&self) -> usize {
+        self.buffer_bytes
+    }
+
+    /// Number of buffered writes not yet committed
+    pub fn pending_writes(&self) -> usize {
+        self.writes.len()
+    }
+
     /// Add a node within this transaction
     pub fn add_node(&mut self, node: Node) -> Result<NodeId> {
-        self.ensure_active()?;
-        self.storage.add_node(node)
+        self.ensure_writable()?;
+        let id = node.id();
+        self.set_node(id, Some(node.clone()));
+        self.push_write(Write::PutNode(node))?;
+        Ok(id)
     }
 
     /// Get a node within this transaction
     pub fn get_node(&self, id: NodeId) -> Result<Node> {
         self.ensure_active()?;
-        self.storage.get_node(id)
+        match self.nodes.get(&id) {
+            Some(Some(node)) => Ok(node.clone()),
+            Some(None) => Err(DeepGraphError::NodeNotFound(id.to_string())),
+            None => self.storage.get_node_at(id, &self.snapshot),
+        }
     }
 
     /// Update a node within this transaction
     pub fn update_node(&mut self, node: Node) -> Result<()> {
-        self.ensure_active()?;
-        self.storage.update_node(node)
+        self.ensure_writable()?;
+        self.get_node(node.id())?;
+        self.set_node(node.id(), Some(node.clone()));
+        self.push_write(Write::PutNode(node))?;
+        Ok(())
     }
 
     /// Delete a node within this transaction
+    ///
+    /// Its edges disappear from the transaction's view as well, as they
+    /// will from storage at commit.
     pub fn delete_node(&mut self, id: NodeId) -> Result<()> {
-        self.ensure_active()?;
-        self.storage.delete_node(id)
+        self.ensure_writable()?;
+        self.get_node(id)?;
+        let mut incident: Vec<EdgeId> = self
+            .storage
+            .incident_edges(id)
+            .unwrap_or_default()
+            .iter()
+            .map(|edge| edge.id())
+            .collect();
+        incident.extend(self.edges.iter().filter_map(|(edge_id, edge)| {
+            edge.as_ref()
+                .filter(|edge| edge.from() == id || edge.to() == id)
+                .map(|_| *edge_id)
+        }));
+        for edge_id in incident {
+            self.set_edge(edge_id, None);
+        }
+        self.set_node(id, None);
+        self.push_write(Write::DeleteNode(id))?;
+        Ok(())
     }
 
     /// Add an edge within this transaction
     pub fn add_edge(&mut self, edge: Edge) -> Result<EdgeId> {
-        self.ensure_active()?;
-        self.storage.add_edge(edge)
+        self.ensure_writable()?;
+        self.get_node(edge.from())?;
+        self.get_node(edge.to())?;
+        let id = edge.id();
+        self.set_edge(id, Some(edge.clone()));
+        self.push_write(Write::PutEdge(edge))?;
+        Ok(id)
     }
 
     /// Get an edge within this transaction
     pub fn get_edge(&self, id: EdgeId) -> Result<Edge> {
         self.ensure_active()?;
-        self.storage.get_edge(id)
+        match self.edges.get(&id) {
+            Some(Some(edge)) => Ok(edge.clone()),
+            Some(None) => Err(DeepGraphError::EdgeNotFound(id.to_string())),
+            None => self.storage.get_edge_at(id, &self.snapshot),
+        }
     }
 
     /// Update an edge within this transaction
     pub fn update_edge(&mut self, edge: Edge) -> Result<()> {
-        self.ensure_active()?;
-        self.storage.update_edge(edge)
+        self.ensure_writable()?;
+        self.get_edge(edge.id())?;
+        self.set_edge(edge.id(), Some(edge.clone()));
+        self.push_write(Write::PutEdge(edge))?;
+        Ok(())
     }
 
     /// Delete an edge within this transaction
     pub fn delete_edge(&mut self, id: EdgeId) -> Result<()> {
+        self.ensure_writable()?;
+        self.get_edge(id)?;
+        self.set_edge(id, None);
+        self.push_write(Write::DeleteEdge(id))?;
+        Ok(())
+    }
+
+    /// Mark the current point so later writes can be undone with
+    /// [`rollback_to`](Transaction::rollback_to)
+    ///
+    /// Reusing a name shadows the earlier savepoint until this one is
+    /// released or rolled past.
+    pub fn savepoint(&mut self, name: impl Into<String>) -> Result<()> {
         self.ensure_active()?;
-        self.storage.delete_edge(id)
+        let name = name.into();
+        debug!("Transaction {} savepoint '{}' at {} writes", self.id, name, self.writes.len());
+        self.savepoints.push(Savepoint {
+            name,
+            writes: self.writes.len(),
+            buffer_bytes: self.buffer_bytes,
+            overlay_undo: self.overlay_undo.len(),
+        });
+        Ok(())
+    }
+
+    /// Discard the writes made since savepoint `name`
+    ///
+    /// The savepoint stays, so the transaction can roll back to it again;
+    /// savepoints taken after it are removed.
+    pub fn rollback_to(&mut self, name: &str) -> Result<()> {
+        self.ensure_active()?;
+        let index = self.savepoint_index(name)?;
+        let savepoint = &self.savepoints[index];
+        let (writes, buffer_bytes, mark) = (savepoint.writes, savepoint.buffer_bytes, savepoint.overlay_undo);
+        debug!(
+            "Transaction {} rolling back to savepoint '{}' ({} writes discarded)",
+            self.id,
+            name,
+            self.writes.len() - writes
+        );
+        self.writes.truncate(writes);
+        self.buffer_bytes = buffer_bytes;
+        for entry in self.overlay_undo.drain(mark..).rev() {
+            match entry {
+                OverlayUndo::Node(id, Some(previous)) => {
+                    self.nodes.insert(id, previous);
+                }
+                OverlayUndo::Node(id, None) => {
+                    self.nodes.remove(&id);
+                }
+                OverlayUndo::Edge(id, Some(previous)) => {
+                    self.edges.insert(id, previous);
+                }
+                OverlayUndo::Edge(id, None) => {
+                    self.edges.remove(&id);
+                }
+            }
+        }
+        self.savepoints.truncate(index + 1);
+        Ok(())
+    }
+
+    /// Forget savepoint `name` and those taken after it, keeping their writes
+    pub fn release_savepoint(&mut self, name: &str) -> Result<()> {
+        self.ensure_active()?;
+        let index = self.savepoint_index(name)?;
+        self.savepoints.truncate(index);
+        Ok(())
+    }
+
+    fn savepoint_index(&self, name: &str) -> Result<usize> {
+        self.savepoints
+            .iter()
+            .rposition(|savepoint| savepoint.name == name)
+            .ok_or_else(|| DeepGraphError::TransactionError(format!("No savepoint named '{}'", name)))
+    }
+
+    fn set_node(&mut self, id: NodeId, node: Option<Node>) {
+        let previous = self.nodes.insert(id, node);
+        self.overlay_undo.push(OverlayUndo::Node(id, previous));
     }
 
-    /// Commit the transaction (placeholder)
+    fn set_edge(&mut self, id: EdgeId, edge: Option<Edge>) {
+        let previous = self.edges.insert(id, edge);
+        self.overlay_undo.push(OverlayUndo::Edge(id, previous));
+    }
+
+    /// Commit the transaction, applying its writes to storage
     ///
-    /// In Phase 1, this simply marks the transaction as committed.
-    /// Future phases will implement proper commit protocol with WAL.
+    /// Fails with a serialization error, leaving storage untouched, if a
+    /// transaction this one cannot see committed a write to an element this
+    /// one writes. If storage rejects a write, the writes applied before it
+    /// are undone. Either way the transaction ends up
+    /// [`Aborted`](TransactionState::Aborted).
     pub fn commit(mut self) -> Result<()> {
         self.ensure_active()?;
+        self.enforce_limits()?;
         self.state = TransactionState::Committing;
-        // TODO: Implement proper commit logic with WAL
+        if self.read_only {
+            debug!("Committing read-only transaction {}", self.id);
+            self.storage.transactions().commit_transaction(self.id)?;
+            self.state = TransactionState::Committed;
+            self.listeners.notify(TransactionEvent::Commit {
+                id: self.id,
+                changes: ChangeSummary::default(),
+            });
+            return Ok(());
+        }
+        debug!("Committing transaction {} ({} writes)", self.id, self.writes.len());
+
+        let storage = Arc::clone(&self.storage);
+        let _writes = storage.write_guard();
+        let conflict = self
+            .nodes
+            .keys()
+            .map(|id| (Element::Node(*id), storage.node_writer(*id)))
+            .chain(self.edges.keys().map(|id| (Element::Edge(*id), storage.edge_writer(*id))))
+            .find_map(|(element, writer)| {
+                writer
+                    .filter(|writer| *writer != self.id && !self.snapshot.is_txn_visible(*writer))
+                    .map(|writer| (element, writer))
+            });
+        if let Some((element, writer)) = conflict {
+            self.abort();
+            return Err(DeepGraphError::TransactionError(format!(
+                "Serialization failure: {} was changed by transaction {} after transaction {} began",
+                element, writer, self.id
+            )));
+        }
+
+        let mut undo = Vec::with_capacity(self.writes.len());
+        let mut touched = Vec::new();
+        for write in std::mem::take(&mut self.writes) {
+            if let Err(e) = self.apply(write, &mut undo, &mut touched) {
+                warn!("Transaction {} failed to commit, undoing {} writes: {}", self.id, undo.len(), e);
+                self.undo(undo);
+                for element in touched {
+                    match element {
+                        Element::Node(id) => storage.discard_node(id, self.id),
+                        Element::Edge(id) => storage.discard_edge(id, self.id),
+                    }
+                }
+                self.abort();
+                return Err(e);
+            }
+        }
+
+        storage.transactions().commit_transaction(self.id)?;
         self.state = TransactionState::Committed;
+        self.listeners.notify(TransactionEvent::Commit {
+            id: self.id,
+            changes: self.change_summary(),
+        });
         Ok(())
     }
 
-    /// Rollback the transaction (placeholder)
-    ///
-    /// In Phase 1, this simply marks the transaction as rolled back.
-    /// Future phases will implement proper rollback with undo operations.
+    /// Roll back the transaction, discarding its buffered writes
     pub fn rollback(mut self) -> Result<()> {
         if self.state == TransactionState::Committed {
             return Err(DeepGraphError::TransactionError(
@@ -189,11 +660,189 @@ impl Transaction {
             ));
         }
         self.state = TransactionState::RollingBack;
-        // TODO: Implement proper rollback logic with undo operations
+        debug!("Rolling back transaction {} ({} writes discarded)", self.id, self.writes.len());
+        let changes = self.change_summary();
+        self.discard_buffer();
+        self.storage.transactions().abort_transaction(self.id)?;
         self.state = TransactionState::RolledBack;
+        self.listeners.notify(TransactionEvent::Abort { id: self.id, changes });
+        Ok(())
+    }
+
+    /// Drop every buffered write and savepoint
+    fn discard_buffer(&mut self) {
+        self.writes.clear();
+        self.nodes.clear();
+        self.edges.clear();
+        self.overlay_undo.clear();
+        self.savepoints.clear();
+        self.buffer_bytes = 0;
+    }
+
+    /// Buffer a write, aborting the transaction if that exceeds a limit
+    fn push_write(&mut self, write: Write) -> Result<()> {
+        self.buffer_bytes += write.size();
+        self.writes.push(write);
+        self.enforce_limits()
+    }
+
+    /// The first limit the transaction exceeds, as an error
+    fn check_limits(&self) -> Result<()> {
+        let exceeded = |what: String| {
+            Err(DeepGraphError::TransactionError(format!(
+                "Transaction {} aborted: {}",
+                self.id, what
+            )))
+        };
+        if let Some(max) = self.limits.max_duration {
+            let elapsed = self.elapsed();
+            if elapsed > max {
+                return exceeded(format!("ran for {:?}, longer than the limit of {:?}", elapsed, max));
+            }
+        }
+        if let Some(max) = self.limits.max_writes {
+            if self.writes.len() > max {
+                return exceeded(format!("exceeded the limit of {} writes", max));
+            }
+        }
+        if let Some(max) = self.limits.max_buffer_bytes {
+            if self.buffer_bytes > max {
+                return exceeded(format!(
+                    "write buffer of {} bytes exceeded the limit of {} bytes",
+                    self.buffer_bytes, max
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Abort the transaction if it exceeds a limit
+    fn enforce_limits(&mut self) -> Result<()> {
+        if let Err(e) = self.check_limits() {
+            warn!("{}", e);
+            self.abort();
+            self.discard_buffer();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// End the transaction in the MVCC manager without applying anything
+    fn abort(&mut self) {
+        self.state = TransactionState::Aborted;
+        if let Err(e) = self.storage.transactions().abort_transaction(self.id) {
+            warn!("Failed to abort transaction {}: {}", self.id, e);
+        }
+        self.listeners.notify(TransactionEvent::Abort {
+            id: self.id,
+            changes: self.change_summary(),
+        });
+    }
+
+    /// What the transaction has written so far, from its overlay
+    fn change_summary(&self) -> ChangeSummary {
+        let mut summary = ChangeSummary::default();
+        for (id, node) in &self.nodes {
+            match node {
+                Some(_) => summary.nodes_written.push(*id),
+                None => summary.nodes_deleted.push(*id),
+            }
+        }
+        for (id, edge) in &self.edges {
+            match edge {
+                Some(_) => summary.edges_written.push(*id),
+                None => summary.edges_deleted.push(*id),
+            }
+        }
+        summary
+    }
+
+    /// Apply one write to storage and record the new versions, noting how
+    /// to reverse it and which elements got versions
+    fn apply(&self, write: Write, undo: &mut Vec<Undo>, touched: &mut Vec<Element>) -> Result<()> {
+        let storage = self.storage.inner();
+        match write {
+            Write::PutNode(node) => {
+                let id = node.id();
+                self.storage.track_node(id);
+                let before = storage.get_node(id).ok();
+                if before.is_some() {
+                    storage.update_node(node)?;
+                } else {
+                    storage.add_node(node)?;
+                }
+                undo.push(Undo::Node(id, before));
+                self.storage.record_node(id, self.id);
+                touched.push(Element::Node(id));
+            }
+            Write::DeleteNode(id) => {
+                let before = storage.get_node(id)?;
+                let edges = self.storage.incident_edges(id)?;
+                self.storage.track_node(id);
+                for edge in &edges {
+                    self.storage.track_edge(edge.id());
+                }
+                storage.delete_node(id)?;
+                self.storage.record_node(id, self.id);
+                touched.push(Element::Node(id));
+                for edge in &edges {
+                    self.storage.record_edge(edge.id(), self.id);
+                    touched.push(Element::Edge(edge.id()));
+                }
+                undo.push(Undo::DeletedNode(before, edges));
+            }
+            Write::PutEdge(edge) => {
+                let id = edge.id();
+                self.storage.track_edge(id);
+                let before = storage.get_edge(id).ok();
+                if before.is_some() {
+                    storage.update_edge(edge)?;
+                } else {
+                    storage.add_edge(edge)?;
+                }
+                undo.push(Undo::Edge(id, before));
+                self.storage.record_edge(id, self.id);
+                touched.push(Element::Edge(id));
+            }
+            Write::DeleteEdge(id) => {
+                let before = storage.get_edge(id)?;
+                self.storage.track_edge(id);
+                storage.delete_edge(id)?;
+                undo.push(Undo::Edge(id, Some(before)));
+                self.storage.record_edge(id, self.id);
+                touched.push(Element::Edge(id));
+            }
+        }
         Ok(())
     }
 
+    /// Reverse applied writes, newest first
+    fn undo(&self, undo: Vec<Undo>) {
+        let storage = self.storage.inner();
+        for step in undo.into_iter().rev() {
+            let result = match step {
+                Undo::Node(_, Some(before)) => storage.update_node(before),
+                Undo::Node(id, None) => storage.delete_node(id),
+                Undo::Edge(id, Some(before)) => {
+                    if storage.get_edge(id).is_ok() {
+                        storage.update_edge(before)
+                    } else {
+                        storage.add_edge(before).map(|_| ())
+                    }
+                }
+                Undo::Edge(id, None) => storage.delete_edge(id),
+                Undo::DeletedNode(node, edges) => storage.add_node(node).and_then(|_| {
+                    edges
+                        .into_iter()
+                        .try_for_each(|edge| storage.add_edge(edge).map(|_| ()))
+                }),
+            };
+            if let Err(e) = result {
+                warn!("Failed to undo a write of transaction {}: {}", self.id, e);
+            }
+        }
+    }
+
     /// Ensure the transaction is active
     fn ensure_active(&self) -> Result<()> {
         if !self.is_active() {
@@ -204,33 +853,118 @@ impl Transaction {
         }
         Ok(())
     }
+
+    /// Ensure the transaction is active and may write
+    fn ensure_writable(&mut self) -> Result<()> {
+        self.ensure_active()?;
+        self.enforce_limits()?;
+        if self.read_only {
+            return Err(DeepGraphError::TransactionError(format!(
+                "Transaction {} is read-only",
+                self.id
+            )));
+        }
+        Ok(())
+    }
 }
 
-/// Transaction manager (placeholder)
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if matches!(self.state, TransactionState::Active | TransactionState::Committing) {
+            debug!("Transaction {} dropped without commit, discarding its writes", self.id);
+            self.abort();
+        }
+    }
+}
+
+/// Begins transactions that are isolated from each other
 ///
-/// Manages multiple concurrent transactions. In Phase 1, this is a simple
-/// wrapper. Future phases will implement:
-/// - Deadlock detection
-/// - Transaction coordination
-/// - Conflict resolution
+/// Cheap to clone; clones share the versioned storage and MVCC manager.
+#[derive(Clone)]
 pub struct TransactionManager {
-    storage: Arc<GraphStorage>,
+    storage: Arc<VersionedStorage<GraphStorage>>,
+    limits: TransactionLimits,
+    listeners: Arc<Listeners>,
 }
 
 impl TransactionManager {
-    /// Create a new transaction manager
+    /// Create a transaction manager with its own MVCC manager
     pub fn new(storage: Arc<GraphStorage>) -> Self {
-        Self { storage }
+        Self::with_mvcc(storage, Arc::new(mvcc::TransactionManager::new()))
+    }
+
+    /// Create a transaction manager registering transactions with `mvcc`
+    pub fn with_mvcc(storage: Arc<GraphStorage>, mvcc: Arc<mvcc::TransactionManager>) -> Self {
+        Self::with_versioned_storage(Arc::new(VersionedStorage::new(storage, mvcc)))
+    }
+
+    /// Create a transaction manager over versioned storage that other
+    /// writers share
+    pub fn with_versioned_storage(storage: Arc<VersionedStorage<GraphStorage>>) -> Self {
+        Self {
+            storage,
+            limits: TransactionLimits::default(),
+            listeners: Arc::new(Listeners::default()),
+        }
+    }
+
+    /// Enforce `limits` on every transaction begun from now on
+    pub fn with_limits(mut self, limits: TransactionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// The limits transactions are begun with
+    pub fn limits(&self) -> TransactionLimits {
+        self.limits
+    }
+
+    /// The versioned storage transactions read and write
+    pub fn storage(&self) -> &Arc<VersionedStorage<GraphStorage>> {
+        &self.storage
+    }
+
+    /// The MVCC manager transactions are registered with
+    pub fn mvcc(&self) -> &Arc<mvcc::TransactionManager> {
+        self.storage.transactions()
     }
 
     /// Begin a new transaction
-    pub fn begin_transaction(&self) -> Transaction {
-        Transaction::begin(Arc::clone(&self.storage))
+    pub fn begin_transaction(&self) -> Result<Transaction> {
+        self.begin_transaction_with_isolation(IsolationLevel::ReadCommitted)
     }
 
     /// Begin a transaction with a specific isolation level
-    pub fn begin_transaction_with_isolation(&self, isolation_level: IsolationLevel) -> Transaction {
-        Transaction::begin_with_isolation(Arc::clone(&self.storage), isolation_level)
+    pub fn begin_transaction_with_isolation(&self, isolation_level: IsolationLevel) -> Result<Transaction> {
+        Transaction::start(self, isolation_level, false)
+    }
+
+    /// Begin a read-only transaction
+    ///
+    /// It reads one stable snapshot for as long as it runs and never
+    /// blocks or conflicts with writers. Versions it can see are kept
+    /// from the vacuum until it ends, so end long-running ones promptly.
+    pub fn begin_read_only_transaction(&self) -> Result<Transaction> {
+        Transaction::start(self, IsolationLevel::RepeatableRead, true)
+    }
+
+    /// Call `listener` whenever a transaction begun through this manager,
+    /// or a clone of it, begins, commits or aborts
+    ///
+    /// Listeners run synchronously on the thread ending the transaction,
+    /// after its outcome is final, so they should return quickly.
+    pub fn add_listener(&self, listener: impl Fn(&TransactionEvent) + Send + Sync + 'static) -> ListenerId {
+        let id = ListenerId(self.listeners.next_id.fetch_add(1, Ordering::Relaxed));
+        self.listeners.listeners.write().push((id, Arc::new(listener)));
+        id
+    }
+
+    /// Stop calling a listener; returns whether it was registered
+    pub fn remove_listener(&self, id: ListenerId) -> bool {
+        let mut listeners = self.listeners.listeners.write();
+        let before = listeners.len();
+        listeners.retain(|(listener_id, _)| *listener_id != id);
+        listeners.len() != before
     }
 }
 
@@ -241,7 +975,7 @@ mod tests {
     #[test]
     fn test_transaction_lifecycle() {
         let storage = Arc::new(GraphStorage::new());
-        let tx = Transaction::begin(storage);
+        let tx = Transaction::begin(storage).unwrap();
 
         assert_eq!(tx.state(), TransactionState::Active);
         assert!(tx.is_active());
@@ -252,7 +986,7 @@ mod tests {
     #[test]
     fn test_transaction_operations() {
         let storage = Arc::new(GraphStorage::new());
-        let mut tx = Transaction::begin(Arc::clone(&storage));
+        let mut tx = Transaction::begin(Arc::clone(&storage)).unwrap();
 
         let node = Node::new(vec!["Person".to_string()]);
         let node_id = tx.add_node(node).unwrap();
@@ -266,15 +1000,171 @@ mod tests {
     #[test]
     fn test_transaction_rollback() {
         let storage = Arc::new(GraphStorage::new());
-        let tx = Transaction::begin(storage);
+        let mut tx = Transaction::begin(Arc::clone(&storage)).unwrap();
+        let node_id = tx.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        assert_eq!(tx.pending_writes(), 1);
+        assert_eq!(storage.node_count(), 0);
 
         assert!(tx.rollback().is_ok());
+        assert!(storage.get_node(node_id).is_err());
+    }
+
+    #[test]
+    fn test_read_your_writes_and_commit() {
+        let storage = Arc::new(GraphStorage::new());
+        let existing = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let mut tx = Transaction::begin(Arc::clone(&storage)).unwrap();
+
+        let a = tx.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let edge = tx.add_edge(Edge::new(existing, a, "KNOWS".to_string())).unwrap();
+        assert!(tx.get_edge(edge).is_ok());
+        tx.delete_node(existing).unwrap();
+        assert!(tx.get_node(existing).is_err());
+        assert!(tx.get_edge(edge).is_err());
+        // Nothing reaches storage before commit
+        assert!(storage.get_node(a).is_err());
+        assert!(storage.get_node(existing).is_ok());
+
+        tx.commit().unwrap();
+        assert!(storage.get_node(a).is_ok());
+        assert!(storage.get_node(existing).is_err());
+        assert_eq!(storage.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_failed_commit_undoes_applied_writes() {
+        let storage = Arc::new(GraphStorage::new());
+        let a = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let b = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let mut tx = Transaction::begin(Arc::clone(&storage)).unwrap();
+
+        let mut renamed = storage.get_node(a).unwrap();
+        renamed.set_property("name".to_string(), "Alice".into());
+        tx.update_node(renamed).unwrap();
+        tx.add_edge(Edge::new(a, b, "KNOWS".to_string())).unwrap();
+        // Removed behind the transaction's back: the edge insert fails at commit
+        storage.delete_node(b).unwrap();
+
+        assert!(tx.commit().is_err());
+        assert!(storage.get_node(a).unwrap().get_property("name").is_none());
+        assert_eq!(storage.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_read_only_transaction() {
+        let manager = TransactionManager::new(Arc::new(GraphStorage::new()));
+        let mut writer = manager.begin_transaction().unwrap();
+        let id = writer.add_node(Node::new(vec![])).unwrap();
+        writer.commit().unwrap();
+
+        let mut reader = manager.begin_read_only_transaction().unwrap();
+        assert!(reader.is_read_only());
+        assert!(reader.add_node(Node::new(vec![])).is_err());
+        assert!(reader.delete_node(id).is_err());
+
+        // A writer committing meanwhile neither blocks nor changes the reader
+        let mut writer = manager.begin_transaction().unwrap();
+        writer.delete_node(id).unwrap();
+        writer.commit().unwrap();
+        assert!(reader.get_node(id).is_ok());
+
+        let active = manager.mvcc().active_transactions();
+        assert_eq!(active.len(), 1);
+        assert!(active[0].read_only);
+        reader.commit().unwrap();
+        assert!(manager.mvcc().active_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_limits_abort_transaction() {
+        let storage = Arc::new(GraphStorage::new());
+        let manager = TransactionManager::new(Arc::clone(&storage))
+            .with_limits(TransactionLimits::new().with_max_writes(2));
+        let mut tx = manager.begin_transaction().unwrap();
+        tx.add_node(Node::new(vec![])).unwrap();
+        tx.add_node(Node::new(vec![])).unwrap();
+        let err = tx.add_node(Node::new(vec![])).unwrap_err();
+        assert!(err.to_string().contains("limit of 2 writes"));
+        assert_eq!(tx.state(), TransactionState::Aborted);
+        assert!(tx.commit().is_err());
+        assert_eq!(storage.node_count(), 0);
+        assert_eq!(manager.mvcc().active_count(), 0);
+
+        let manager = manager.with_limits(TransactionLimits::new().with_max_buffer_bytes(64));
+        let mut tx = manager.begin_transaction().unwrap();
+        let mut node = Node::new(vec![]);
+        node.set_property("bio".to_string(), "x".repeat(100).into());
+        assert!(tx.add_node(node).is_err());
+
+        let manager = manager.with_limits(TransactionLimits::new().with_max_duration(Duration::from_millis(1)));
+        let mut tx = manager.begin_transaction().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(tx.add_node(Node::new(vec![])).is_err());
+        assert!(!tx.is_active());
+    }
+
+    #[test]
+    fn test_listeners() {
+        let manager = TransactionManager::new(Arc::new(GraphStorage::new()));
+        let events = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let listener = manager.add_listener(move |event| recorded.lock().push(event.clone()));
+
+        let mut tx = manager.begin_transaction().unwrap();
+        let committed = tx.id();
+        let node = tx.add_node(Node::new(vec![])).unwrap();
+        tx.commit().unwrap();
+
+        let mut tx = manager.begin_transaction().unwrap();
+        let dropped = tx.id();
+        tx.delete_node(node).unwrap();
+        drop(tx);
+
+        {
+            let events = events.lock();
+            assert_eq!(events.len(), 4);
+            assert!(matches!(&events[0], TransactionEvent::Begin { id, read_only: false } if *id == committed));
+            assert!(matches!(&events[1], TransactionEvent::Commit { id, changes }
+                if *id == committed && changes.nodes_written == vec![node]));
+            assert!(matches!(&events[3], TransactionEvent::Abort { id, changes }
+                if *id == dropped && changes.nodes_deleted == vec![node]));
+        }
+
+        assert!(manager.remove_listener(listener));
+        manager.begin_transaction().unwrap().commit().unwrap();
+        assert_eq!(events.lock().len(), 4);
+    }
+
+    #[test]
+    fn test_savepoints() {
+        let storage = Arc::new(GraphStorage::new());
+        let mut tx = Transaction::begin(Arc::clone(&storage)).unwrap();
+
+        let kept = tx.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        tx.savepoint("batch").unwrap();
+        let dropped = tx.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        tx.delete_node(kept).unwrap();
+        tx.savepoint("inner").unwrap();
+
+        tx.rollback_to("batch").unwrap();
+        assert!(tx.get_node(kept).is_ok());
+        assert!(tx.get_node(dropped).is_err());
+        assert!(tx.rollback_to("inner").is_err());
+        // Still usable after rolling back
+        tx.rollback_to("batch").unwrap();
+        tx.release_savepoint("batch").unwrap();
+        assert!(tx.rollback_to("batch").is_err());
+        assert_eq!(tx.pending_writes(), 1);
+
+        tx.commit().unwrap();
+        assert!(storage.get_node(kept).is_ok());
+        assert!(storage.get_node(dropped).is_err());
     }
 
     #[test]
     fn test_cannot_rollback_committed() {
         let storage = Arc::new(GraphStorage::new());
-        let tx = Transaction::begin(storage);
+        let tx = Transaction::begin(storage).unwrap();
         
         tx.commit().unwrap();
         // Cannot create another tx from the consumed one
@@ -286,21 +1176,52 @@ mod tests {
         let storage = Arc::new(GraphStorage::new());
         let manager = TransactionManager::new(storage);
 
-        let mut tx1 = manager.begin_transaction();
-        let mut tx2 = manager.begin_transaction();
+        let mut tx1 = manager.begin_transaction().unwrap();
+        let mut tx2 = manager.begin_transaction().unwrap();
+        assert_eq!(manager.mvcc().active_count(), 2);
 
-        let node = Node::new(vec!["Person".to_string()]);
-        let _id1 = tx1.add_node(node.clone()).unwrap();
-        let _id2 = tx2.add_node(node).unwrap();
+        let _id1 = tx1.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+        let _id2 = tx2.add_node(Node::new(vec!["Person".to_string()])).unwrap();
 
         tx1.commit().unwrap();
         tx2.commit().unwrap();
+        assert_eq!(manager.mvcc().active_count(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_conflicts() {
+        let storage = Arc::new(GraphStorage::new());
+        let manager = TransactionManager::new(Arc::clone(&storage));
+        let id = storage.add_node(Node::new(vec!["Person".to_string()])).unwrap();
+
+        let mut writer = manager.begin_transaction().unwrap();
+        let mut reader = manager.begin_transaction().unwrap();
+        let mut node = writer.get_node(id).unwrap();
+        node.set_property("name".to_string(), "Alice".into());
+        writer.update_node(node.clone()).unwrap();
+        writer.commit().unwrap();
+
+        // The change committed after `reader` began: it still reads the
+        // old version, and may not overwrite the new one
+        assert!(reader.get_node(id).unwrap().get_property("name").is_none());
+        reader.update_node(node).unwrap();
+        assert!(reader.commit().is_err());
+
+        let mut later = manager.begin_transaction().unwrap();
+        assert!(later.get_node(id).unwrap().get_property("name").is_some());
+        later.delete_node(id).unwrap();
+        later.commit().unwrap();
+
+        // Dropping an open transaction ends it in the MVCC manager
+        let dropped = manager.begin_transaction().unwrap();
+        drop(dropped);
+        assert_eq!(manager.mvcc().active_count(), 0);
     }
 
     #[test]
     fn test_isolation_levels() {
         let storage = Arc::new(GraphStorage::new());
-        let tx = Transaction::begin_with_isolation(storage, IsolationLevel::Serializable);
+        let tx = Transaction::begin_with_isolation(storage, IsolationLevel::Serializable).unwrap();
 
         assert_eq!(tx.isolation_level(), IsolationLevel::Serializable);
     }
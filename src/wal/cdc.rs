@@ -0,0 +1,300 @@
+//! Change data capture on top of the WAL
+//!
+//! A [`ChangeFeed`] tails the log with a [`WALReader`] on a background
+//! thread and publishes the changes of every committed transaction to its
+//! subscribers, in commit order. Operations are held back until their
+//! transaction's `CommitTxn` is read and dropped on `AbortTxn`, so
+//! subscribers never see a write that recovery would not replay.
+//! [`ChangeCollector`] does the buffering and can be fed entries directly.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let feed = ChangeFeed::spawn(wal_config, checkpoint_lsn, Duration::from_millis(50));
+//! let changes = feed.subscribe();
+//! for change in changes.iter() {
+//!     mirror.apply(change.event);
+//! }
+//! ```
+
+use crate::graph::{Edge, EdgeId, Node, NodeId};
+use crate::storage::GraphOp;
+use crate::wal::log::LSN;
+use crate::wal::{WALConfig, WALEntry, WALOperation, WALReader};
+use log::{debug, info, warn};
+use parking_lot::{Condvar, Mutex};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// A committed change to the graph
+#[derive(Debug, Clone)]
+pub enum ChangeEvent {
+    /// Node added
+    NodeCreated(Node),
+    /// Node replaced by this version
+    NodeUpdated(Node),
+    /// Node removed
+    NodeDeleted(NodeId),
+    /// Edge added
+    EdgeCreated(Edge),
+    /// Edge replaced by this version
+    EdgeUpdated(Edge),
+    /// Edge removed
+    EdgeDeleted(EdgeId),
+}
+
+impl ChangeEvent {
+    /// The change a logged operation describes; `None` for control records
+    pub fn from_operation(operation: WALOperation) -> Option<Self> {
+        match operation {
+            WALOperation::InsertNode { node } => Some(Self::NodeCreated(node)),
+            WALOperation::UpdateNode { node } => Some(Self::NodeUpdated(node)),
+            WALOperation::DeleteNode { id } => Some(Self::NodeDeleted(id)),
+            WALOperation::InsertEdge { edge } => Some(Self::EdgeCreated(edge)),
+            WALOperation::UpdateEdge { edge } => Some(Self::EdgeUpdated(edge)),
+            WALOperation::DeleteEdge { id } => Some(Self::EdgeDeleted(id)),
+            _ => None,
+        }
+    }
+
+    /// The change a batched operation describes
+    pub fn from_op(op: GraphOp) -> Self {
+        match op {
+            GraphOp::AddNode(node) => Self::NodeCreated(node),
+            GraphOp::UpdateNode(node) => Self::NodeUpdated(node),
+            GraphOp::DeleteNode(id) => Self::NodeDeleted(id),
+            GraphOp::AddEdge(edge) => Self::EdgeCreated(edge),
+            GraphOp::UpdateEdge(edge) => Self::EdgeUpdated(edge),
+            GraphOp::DeleteEdge(id) => Self::EdgeDeleted(id),
+        }
+    }
+}
+
+/// A change with its position in the log
+#[derive(Debug, Clone)]
+pub struct Change {
+    /// LSN of the operation
+    pub lsn: LSN,
+    /// Transaction that made the change
+    pub txn_id: u64,
+    /// Seconds since the Unix epoch when the operation was logged
+    pub timestamp: u64,
+    /// What changed
+    pub event: ChangeEvent,
+}
+
+/// Buffers logged operations until their transaction commits
+#[derive(Debug, Default)]
+pub struct ChangeCollector {
+    pending: HashMap<u64, Vec<Change>>,
+}
+
+impl ChangeCollector {
+    /// Create an empty collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next log entry; returns the transaction's changes on commit
+    pub fn push(&mut self, entry: WALEntry) -> Vec<Change> {
+        match entry.operation {
+            WALOperation::CommitTxn => self.pending.remove(&entry.txn_id).unwrap_or_default(),
+            WALOperation::AbortTxn => {
+                self.pending.remove(&entry.txn_id);
+                Vec::new()
+            }
+            WALOperation::Batch { ops } => {
+                let events = ops.into_iter().map(ChangeEvent::from_op);
+                self.buffer(entry.lsn, entry.txn_id, entry.timestamp, events);
+                Vec::new()
+            }
+            operation => {
+                let events = ChangeEvent::from_operation(operation);
+                self.buffer(entry.lsn, entry.txn_id, entry.timestamp, events);
+                Vec::new()
+            }
+        }
+    }
+
+    fn buffer(&mut self, lsn: LSN, txn_id: u64, timestamp: u64, events: impl IntoIterator<Item = ChangeEvent>) {
+        let changes: Vec<Change> = events
+            .into_iter()
+            .map(|event| Change {
+                lsn,
+                txn_id,
+                timestamp,
+                event,
+            })
+            .collect();
+        if !changes.is_empty() {
+            self.pending.entry(txn_id).or_default().extend(changes);
+        }
+    }
+
+    /// Transactions seen but neither committed nor aborted yet
+    pub fn open_transactions(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// Background thread publishing committed changes from the WAL
+pub struct ChangeFeed {
+    subscribers: Arc<Mutex<Vec<Sender<Change>>>>,
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ChangeFeed {
+    /// Tail the log in `config.wal_dir` from `from_lsn`, polling every `poll_interval`
+    ///
+    /// Start at a transaction boundary, such as a checkpoint LSN: a
+    /// transaction whose first operations precede `from_lsn` is published
+    /// without them.
+    pub fn spawn(config: WALConfig, from_lsn: LSN, poll_interval: Duration) -> Self {
+        info!("Starting change feed on {} from LSN {}", config.wal_dir, from_lsn);
+        let subscribers: Arc<Mutex<Vec<Sender<Change>>>> = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+
+        let targets = subscribers.clone();
+        let signal = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let mut reader = WALReader::new(config).from_lsn(from_lsn);
+            let mut collector = ChangeCollector::new();
+            let (stopped, wake) = &*signal;
+            loop {
+                loop {
+                    match reader.next_entry() {
+                        Ok(Some(entry)) => {
+                            let changes = collector.push(entry);
+                            if !changes.is_empty() {
+                                Self::publish(&targets, changes);
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            warn!("Change feed failed to read the WAL: {}", e);
+                            break;
+                        }
+                    }
+                }
+
+                let mut stopped = stopped.lock();
+                if !*stopped {
+                    wake.wait_for(&mut stopped, poll_interval);
+                }
+                if *stopped {
+                    break;
+                }
+            }
+            debug!("Change feed stopped");
+        });
+
+        Self {
+            subscribers,
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Receive every change committed from now on
+    ///
+    /// Dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> Receiver<Change> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().push(sender);
+        receiver
+    }
+
+    /// Number of live subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.lock().len()
+    }
+
+    /// Stop tailing and wait for the thread to exit
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn publish(subscribers: &Mutex<Vec<Sender<Change>>>, changes: Vec<Change>) {
+        let mut subscribers = subscribers.lock();
+        subscribers.retain(|subscriber| changes.iter().all(|change| subscriber.send(change.clone()).is_ok()));
+    }
+
+    fn shutdown(&mut self) {
+        let (stopped, wake) = &*self.stop;
+        *stopped.lock() = true;
+        wake.notify_all();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ChangeFeed {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wal::WAL;
+    use tempfile::tempdir;
+
+    fn entry(lsn: LSN, txn_id: u64, operation: WALOperation) -> WALEntry {
+        WALEntry {
+            lsn,
+            txn_id,
+            operation,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_collector_releases_on_commit() {
+        let mut collector = ChangeCollector::new();
+        let node = Node::new(vec![]);
+        assert!(collector.push(entry(0, 1, WALOperation::InsertNode { node: node.clone() })).is_empty());
+        assert!(collector.push(entry(1, 2, WALOperation::DeleteNode { id: node.id() })).is_empty());
+        assert!(collector.push(entry(2, 2, WALOperation::AbortTxn)).is_empty());
+        assert_eq!(collector.open_transactions(), 1);
+
+        let changes = collector.push(entry(3, 1, WALOperation::CommitTxn));
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(&changes[0].event, ChangeEvent::NodeCreated(created) if created.id() == node.id()));
+        assert_eq!(collector.open_transactions(), 0);
+    }
+
+    #[test]
+    fn test_feed_publishes_committed_changes() {
+        let dir = tempdir().unwrap();
+        let config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_sync(false);
+        let wal = WAL::new(config.clone()).unwrap();
+        let feed = ChangeFeed::spawn(config, 0, Duration::from_millis(5));
+        let changes = feed.subscribe();
+
+        let node = Node::new(vec!["Person".to_string()]);
+        wal.append(1, WALOperation::InsertNode { node: node.clone() }).unwrap();
+        wal.append(1, WALOperation::CommitTxn).unwrap();
+        wal.append(2, WALOperation::DeleteNode { id: node.id() }).unwrap();
+        wal.append(2, WALOperation::AbortTxn).unwrap();
+        wal.append(3, WALOperation::UpdateNode { node: node.clone() }).unwrap();
+        wal.append(3, WALOperation::CommitTxn).unwrap();
+        wal.flush().unwrap();
+
+        let first = changes.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(first.event, ChangeEvent::NodeCreated(_)));
+        let second = changes.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(second.event, ChangeEvent::NodeUpdated(_)));
+        assert_eq!(second.txn_id, 3);
+
+        drop(changes);
+        feed.stop();
+    }
+}
@@ -1,18 +1,22 @@
 //! Write-Ahead Log implementation
 //!
 //! Logs all mutations before applying them to storage
+//!
+//! [`WAL::truncate`] removes segments whose entries storage has absorbed.
 
 use crate::error::{DeepGraphError, Result};
 use crate::graph::{Edge, EdgeId, Node, NodeId};
-use crate::wal::WALConfig;
+use crate::storage::{GraphOp, Pressure, WriteThrottle};
+use crate::wal::{WALConfig, WALRecovery};
 use log::{debug, info, trace};
 use serde::{Deserialize, Serialize};
-use std::fs::{File, OpenOptions};
+use std::fs::{self, File, OpenOptions};
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use parking_lot::RwLock;
+use uuid::Uuid;
 
 /// Log Sequence Number - monotonically increasing
 pub type LSN = u64;
@@ -29,6 +33,14 @@ pub struct WAL {
     segment_number: Arc<AtomicU64>,
     /// Entries written in current segment
     entries_in_segment: Arc<AtomicU64>,
+    /// Backpressure on bytes appended but not yet flushed
+    throttle: Option<WriteThrottle>,
+    /// Bytes appended since the last flush
+    unflushed_bytes: AtomicU64,
+    /// Bytes appended since the log was opened
+    appended_bytes: AtomicU64,
+    /// Next transaction ID handed out by [`WAL::allocate_txn_id`]
+    next_txn_id: AtomicU64,
 }
 
 /// WAL entry representing a single operation
@@ -44,6 +56,17 @@ pub struct WALEntry {
     pub timestamp: u64,
 }
 
+/// Segments removed by [`WAL::truncate`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WALTruncation {
+    /// Segments deleted
+    pub deleted: usize,
+    /// Segments moved to the archive directory
+    pub archived: usize,
+    /// Bytes no longer held in the WAL directory
+    pub bytes_released: u64,
+}
+
 /// Operations that can be logged
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WALOperation {
@@ -76,6 +99,31 @@ pub enum WALOperation {
     
     /// Checkpoint marker
     Checkpoint,
+    
+    /// Progress of a transactional import batch
+    ///
+    /// Logged inside the batch's own transaction, so the batch and the
+    /// position an interrupted import resumes from commit together.
+    ImportBatch {
+        run_id: Uuid,
+        batch: u64,
+        records: u64,
+        node_ids: Vec<(String, NodeId)>,
+    },
+    
+    /// Operations applied as one unit by
+    /// [`StorageBackend::apply_batch`](crate::storage::StorageBackend::apply_batch)
+    Batch { ops: Vec<GraphOp> },
+}
+
+/// Throttle units reserved by an append, released again if the append
+/// fails before handing them to the next flush
+struct Reservation<'a>(&'a WriteThrottle, u64);
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        self.0.release(self.1);
+    }
 }
 
 impl WAL {
@@ -90,12 +138,27 @@ impl WAL {
         // Create WAL directory
         std::fs::create_dir_all(&config.wal_dir)?;
         
+        let throttle = match config.throttle {
+            Some(throttle) if !config.sync_on_write => Some(WriteThrottle::new(throttle)),
+            _ => None,
+        };
+        
+        // Continue after segments left by earlier runs
+        let (next_segment, next_lsn, next_txn_id) = WALRecovery::new(config.clone()).resume_point()?;
+        if next_segment > 0 {
+            info!("Resuming WAL at segment {}, LSN {}", next_segment, next_lsn);
+        }
+        
         let wal = Self {
             config,
             current_segment: Arc::new(RwLock::new(None)),
-            current_lsn: Arc::new(AtomicU64::new(0)),
-            segment_number: Arc::new(AtomicU64::new(0)),
+            current_lsn: Arc::new(AtomicU64::new(next_lsn)),
+            segment_number: Arc::new(AtomicU64::new(next_segment)),
             entries_in_segment: Arc::new(AtomicU64::new(0)),
+            throttle,
+            unflushed_bytes: AtomicU64::new(0),
+            appended_bytes: AtomicU64::new(0),
+            next_txn_id: AtomicU64::new(next_txn_id),
         };
         
         // Open first segment
@@ -107,6 +170,23 @@ impl WAL {
     
     /// Append an entry to the log
     pub fn append(&self, txn_id: u64, operation: WALOperation) -> Result<LSN> {
+        // Wait for flushes to catch up before buffering more. This happens
+        // before an LSN is assigned so a rejected append leaves no gap, and
+        // never under the segment lock, which flush needs. A full backlog is
+        // flushed here, as there may be no background flusher to drain it.
+        let entry_bytes = bincode::serialized_size(&operation)
+            .map_err(|e| DeepGraphError::StorageError(format!("WAL serialize error: {}", e)))?;
+        let reservation = match &self.throttle {
+            Some(throttle) => {
+                if throttle.would_wait(entry_bytes) {
+                    self.flush()?;
+                }
+                throttle.acquire(entry_bytes)?;
+                Some(Reservation(throttle, entry_bytes))
+            }
+            None => None,
+        };
+        
         // Get next LSN
         let lsn = self.current_lsn.fetch_add(1, Ordering::SeqCst);
         
@@ -123,9 +203,8 @@ impl WAL {
                 .as_secs(),
         };
         
-        // Serialize entry
-        let serialized = bincode::serialize(&entry)
-            .map_err(|e| DeepGraphError::StorageError(format!("WAL serialize error: {}", e)))?;
+        // Serialize entry, encrypting it if configured
+        let serialized = self.config.encode_entry(&entry)?;
         
         trace!("WAL entry serialized: {} bytes", serialized.len());
         
@@ -135,11 +214,16 @@ impl WAL {
             let len = serialized.len() as u32;
             writer.write_all(&len.to_le_bytes())?;
             writer.write_all(&serialized)?;
+            self.appended_bytes.fetch_add(4 + serialized.len() as u64, Ordering::SeqCst);
             
             // Sync if configured
             if self.config.sync_on_write {
                 writer.flush()?;
                 trace!("WAL entry synced to disk at LSN {}", lsn);
+            } else {
+                // The next flush releases these units
+                self.unflushed_bytes.fetch_add(entry_bytes, Ordering::SeqCst);
+                std::mem::forget(reservation);
             }
         }
         
@@ -162,15 +246,25 @@ impl WAL {
         
         info!("Rotating WAL to new segment: {:?} (segment #{})", segment_path, segment_num);
         
+        // Flush the old segment before its successor appears, so a
+        // `WALReader` that sees the new file knows the old one is complete
+        let mut current = self.current_segment.write();
+        if let Some(ref mut writer) = *current {
+            self.flush_segment(writer)?;
+        }
+        
         let file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&segment_path)?;
         
-        let writer = BufWriter::new(file);
+        *current = Some(BufWriter::new(file));
+        drop(current);
         
-        let mut current = self.current_segment.write();
-        *current = Some(writer);
+        let flushed = self.unflushed_bytes.swap(0, Ordering::SeqCst);
+        if let Some(throttle) = &self.throttle {
+            throttle.release(flushed);
+        }
         
         self.entries_in_segment.store(0, Ordering::SeqCst);
         
@@ -178,27 +272,72 @@ impl WAL {
         Ok(())
     }
     
+    /// Write out a segment's buffer, syncing the file if configured
+    fn flush_segment(&self, writer: &mut BufWriter<File>) -> Result<()> {
+        writer.flush()?;
+        if self.config.sync_on_write {
+            writer.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+    
     /// Get path for a segment
     fn segment_path(&self, segment: u64) -> PathBuf {
         Path::new(&self.config.wal_dir).join(format!("wal-{:08}.log", segment))
     }
     
     /// Force sync to disk
+    ///
+    /// With `sync_on_write` set this waits for the device (`fdatasync`);
+    /// otherwise buffered entries are only handed to the OS.
     pub fn flush(&self) -> Result<()> {
         debug!("Flushing WAL to disk");
         let mut segment = self.current_segment.write();
         if let Some(ref mut writer) = *segment {
-            writer.flush()?;
+            self.flush_segment(writer)?;
+        }
+        let flushed = self.unflushed_bytes.swap(0, Ordering::SeqCst);
+        if let Some(throttle) = &self.throttle {
+            throttle.release(flushed);
         }
         trace!("WAL flushed successfully");
         Ok(())
     }
     
+    /// Configuration the log was opened with
+    pub fn config(&self) -> &WALConfig {
+        &self.config
+    }
+    
+    /// Backpressure level of unflushed appends
+    ///
+    /// Always [`Pressure::Normal`] when no throttle is configured.
+    pub fn pressure(&self) -> Pressure {
+        self.throttle
+            .as_ref()
+            .map_or(Pressure::Normal, |throttle| throttle.pressure())
+    }
+    
+    /// Bytes appended since the log was opened, including length prefixes
+    ///
+    /// Compare two readings to tell how much the log grew in between.
+    pub fn appended_bytes(&self) -> u64 {
+        self.appended_bytes.load(Ordering::SeqCst)
+    }
+    
     /// Get current LSN
     pub fn current_lsn(&self) -> LSN {
         self.current_lsn.load(Ordering::SeqCst)
     }
     
+    /// Hand out a transaction ID not used anywhere in this log
+    ///
+    /// IDs continue after the highest one found when the log was opened, so
+    /// recovery never mistakes a new transaction for one of an earlier run.
+    pub fn allocate_txn_id(&self) -> u64 {
+        self.next_txn_id.fetch_add(1, Ordering::SeqCst)
+    }
+    
     /// Write checkpoint marker
     pub fn checkpoint(&self) -> Result<LSN> {
         info!("Writing WAL checkpoint");
@@ -206,11 +345,97 @@ impl WAL {
         info!("WAL checkpoint written at LSN {}", lsn);
         Ok(lsn)
     }
+    
+    /// Remove segments made redundant by `applied_lsn`
+    ///
+    /// Call once every entry before `applied_lsn` is reflected in storage or
+    /// a snapshot, typically with the LSN returned by [`WAL::checkpoint`]
+    /// after flushing storage. Segments holding only earlier entries are
+    /// deleted or archived unless [`WALConfig::retention`] keeps them; the
+    /// segment being written is never touched. `applied_lsn` must not fall
+    /// inside an open transaction, whose earlier entries would be lost.
+    pub fn truncate(&self, applied_lsn: LSN) -> Result<WALTruncation> {
+        let recovery = WALRecovery::new(self.config.clone());
+        let segments = recovery.find_segments()?;
+        let current = self.segment_path(self.segment_number.load(Ordering::SeqCst).saturating_sub(1));
+        
+        // A segment only holds entries before the first LSN of the next
+        // non-empty segment
+        let mut applied = Vec::new();
+        let mut next_start: Option<LSN> = None;
+        for path in segments.iter().rev() {
+            if Path::new(path) != current && next_start.is_some_and(|next| next <= applied_lsn) {
+                applied.push(path.clone());
+            }
+            if let Some(start) = recovery.first_lsn(path)? {
+                next_start = Some(start);
+            }
+        }
+        
+        // Newest first; once one segment goes, all older ones go too, so
+        // the segments left behind stay contiguous
+        let retention = &self.config.retention;
+        let now = std::time::SystemTime::now();
+        let mut truncation = WALTruncation::default();
+        let (mut kept, mut kept_bytes, mut removing) = (0usize, 0u64, false);
+        for path in &applied {
+            let metadata = fs::metadata(path)?;
+            let size = metadata.len();
+            let age = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .unwrap_or_default();
+            removing = removing
+                || retention.max_segments.is_some_and(|max| kept >= max)
+                || retention.max_age.is_some_and(|max| age > max)
+                || retention.max_total_bytes.is_some_and(|max| kept_bytes + size > max);
+            if !removing {
+                kept += 1;
+                kept_bytes += size;
+                continue;
+            }
+            
+            match &retention.archive_dir {
+                Some(archive_dir) => {
+                    Self::archive_segment(Path::new(path), Path::new(archive_dir))?;
+                    truncation.archived += 1;
+                }
+                None => {
+                    fs::remove_file(path)?;
+                    truncation.deleted += 1;
+                }
+            }
+            truncation.bytes_released += size;
+        }
+        
+        if truncation.deleted + truncation.archived > 0 {
+            info!(
+                "WAL truncated before LSN {}: {} segments deleted, {} archived, {} bytes released",
+                applied_lsn, truncation.deleted, truncation.archived, truncation.bytes_released
+            );
+        }
+        Ok(truncation)
+    }
+    
+    /// Move a segment into the archive directory
+    fn archive_segment(path: &Path, archive_dir: &Path) -> Result<()> {
+        fs::create_dir_all(archive_dir)?;
+        let target = archive_dir.join(path.file_name().unwrap_or_default());
+        // Renaming fails across file systems; copy instead
+        if fs::rename(path, &target).is_err() {
+            fs::copy(path, &target)?;
+            fs::remove_file(path)?;
+        }
+        debug!("Archived WAL segment {:?} to {:?}", path, target);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::wal::WALRetention;
     use tempfile::tempdir;
 
     #[test]
@@ -289,5 +514,112 @@ mod tests {
         
         assert!(wal.flush().is_ok());
     }
-}
 
+    #[test]
+    fn test_throttle_unflushed_appends() {
+        use crate::storage::ThrottleConfig;
+        use std::time::Duration;
+        
+        let dir = tempdir().unwrap();
+        let config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_sync(false)
+            .with_throttle(
+                ThrottleConfig::new()
+                    .with_limits(1, 1)
+                    .with_max_wait(Duration::from_millis(10)),
+            );
+        
+        let wal = WAL::new(config).unwrap();
+        wal.append(1, WALOperation::BeginTxn).unwrap();
+        assert_eq!(wal.pressure(), Pressure::Saturated);
+        
+        // With no background flusher, the blocked append drains the backlog itself
+        wal.append(1, WALOperation::CommitTxn).unwrap();
+        assert_eq!(wal.current_lsn(), 2);
+        
+        wal.flush().unwrap();
+        assert_eq!(wal.pressure(), Pressure::Normal);
+        
+        // Units of an append that fails are handed back
+        let throttle = wal.throttle.as_ref().unwrap();
+        throttle.acquire(1).unwrap();
+        drop(Reservation(throttle, 1));
+        assert_eq!(throttle.outstanding(), 0);
+    }
+    #[test]
+    fn test_reopen_continues_log() {
+        let dir = tempdir().unwrap();
+        let config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_sync(false);
+        
+        let wal = WAL::new(config.clone()).unwrap();
+        let txn = wal.allocate_txn_id();
+        wal.append(txn, WALOperation::BeginTxn).unwrap();
+        wal.append(txn, WALOperation::CommitTxn).unwrap();
+        drop(wal);
+        
+        let reopened = WAL::new(config).unwrap();
+        assert_eq!(reopened.current_lsn(), 2);
+        assert!(reopened.allocate_txn_id() > txn);
+        assert!(dir.path().join("wal-00000001.log").exists());
+    }
+    #[test]
+    fn test_truncate_after_checkpoint() {
+        let dir = tempdir().unwrap();
+        let archive = tempdir().unwrap();
+        let mut config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_sync(false)
+            .with_retention(WALRetention::default()
+                .with_max_segments(1)
+                .with_archive_dir(archive.path().to_string_lossy().to_string()));
+        config.checkpoint_threshold = 2;
+        
+        let wal = WAL::new(config.clone()).unwrap();
+        for txn in 1..=6 {
+            wal.append(txn, WALOperation::InsertNode { node: Node::new(vec![]) }).unwrap();
+            wal.append(txn, WALOperation::CommitTxn).unwrap();
+        }
+        let lsn = wal.checkpoint().unwrap();
+        wal.append(7, WALOperation::InsertNode { node: Node::new(vec![]) }).unwrap();
+        wal.append(7, WALOperation::CommitTxn).unwrap();
+        wal.flush().unwrap();
+        let before = WALRecovery::new(config.clone()).find_segments().unwrap().len();
+        
+        let truncation = wal.truncate(lsn).unwrap();
+        assert!(truncation.archived > 0);
+        assert_eq!(truncation.deleted, 0);
+        assert_eq!(fs::read_dir(archive.path()).unwrap().count(), truncation.archived);
+        let after = WALRecovery::new(config.clone()).find_segments().unwrap().len();
+        assert_eq!(after, before - truncation.archived);
+        
+        // Entries from the checkpoint on are still there
+        let tail = WALRecovery::new(config).committed_tail(lsn).unwrap();
+        assert_eq!(tail.len(), 1);
+    }
+    
+    #[test]
+    fn test_encrypted_entries() {
+        use crate::encryption::EncryptionKey;
+        
+        let dir = tempdir().unwrap();
+        let key = EncryptionKey::from_hex(&EncryptionKey::generate_hex()).unwrap();
+        let config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_encryption(key);
+        let wal = WAL::new(config.clone()).unwrap();
+        wal.append(7, WALOperation::InsertNode { node: Node::new(vec!["SecretLabel".to_string()]) }).unwrap();
+        wal.append(7, WALOperation::CommitTxn).unwrap();
+        wal.flush().unwrap();
+        
+        let segment = fs::read(dir.path().join("wal-00000000.log")).unwrap();
+        assert!(!segment.windows(11).any(|window| window == b"SecretLabel"));
+        assert_eq!(WALRecovery::new(config.clone()).committed_tail(0).unwrap().len(), 1);
+        
+        // Without the key the segments cannot be read
+        let plain = WALConfig { encryption: None, ..config };
+        assert!(WALRecovery::new(plain).committed_tail(0).is_err());
+    }
+}
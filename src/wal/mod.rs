@@ -1,12 +1,94 @@
 //! Write-Ahead Logging (WAL) for durability
 //!
 //! Implements ACID guarantees through write-ahead logging
+//!
+//! Segments accumulate until [`WAL::truncate`] is told which LSN storage
+//! (or a snapshot) has fully absorbed; older segments are then deleted or
+//! archived as allowed by [`WALRetention`]. [`WALReader`] iterates the log
+//! across segments for tools and consumers that tail it, and [`WALWriter`]
+//! appends from a background thread so callers need not wait on flushes.
+//! [`ChangeFeed`] publishes committed changes to subscribers for CDC.
+//! With [`WALConfig::encryption`] set, every entry is sealed with
+//! AES-256-GCM before it reaches a segment.
 
+pub mod cdc;
 pub mod log;
+pub mod reader;
 pub mod recovery;
+pub mod writer;
 
-pub use log::{WAL, WALEntry, WALOperation};
+pub use cdc::{Change, ChangeCollector, ChangeEvent, ChangeFeed};
+pub use log::{WALEntry, WALOperation, WALTruncation, WAL};
+pub use reader::WALReader;
 pub use recovery::WALRecovery;
+pub use writer::{AppendHandle, WALWriter};
+
+use crate::encryption::EncryptionKey;
+use crate::error::{DeepGraphError, Result};
+use crate::storage::ThrottleConfig;
+use std::time::Duration;
+
+/// Which fully applied segments [`WAL::truncate`] keeps
+///
+/// A segment whose entries are all applied is removed as soon as it
+/// exceeds any of the limits; unset limits never remove anything. The
+/// default removes every applied segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WALRetention {
+    /// Keep at most this many applied segments, newest first
+    pub max_segments: Option<usize>,
+    /// Remove applied segments last written longer ago than this
+    pub max_age: Option<Duration>,
+    /// Keep applied segments, newest first, up to this many bytes in total
+    pub max_total_bytes: Option<u64>,
+    /// Move removed segments here instead of deleting them
+    pub archive_dir: Option<String>,
+}
+
+impl Default for WALRetention {
+    fn default() -> Self {
+        Self {
+            max_segments: Some(0),
+            max_age: None,
+            max_total_bytes: None,
+            archive_dir: None,
+        }
+    }
+}
+
+impl WALRetention {
+    /// Never remove segments
+    pub fn keep_all() -> Self {
+        Self {
+            max_segments: None,
+            ..Self::default()
+        }
+    }
+    
+    /// Keep at most `count` applied segments
+    pub fn with_max_segments(mut self, count: usize) -> Self {
+        self.max_segments = Some(count);
+        self
+    }
+    
+    /// Remove applied segments older than `age`
+    pub fn with_max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+    
+    /// Keep at most `bytes` of applied segments
+    pub fn with_max_total_bytes(mut self, bytes: u64) -> Self {
+        self.max_total_bytes = Some(bytes);
+        self
+    }
+    
+    /// Archive removed segments into `dir`
+    pub fn with_archive_dir(mut self, dir: impl Into<String>) -> Self {
+        self.archive_dir = Some(dir.into());
+        self
+    }
+}
 
 /// WAL configuration
 #[derive(Debug, Clone)]
@@ -19,6 +101,16 @@ pub struct WALConfig {
     pub sync_on_write: bool,
     /// Auto-checkpoint after N entries (default: 1000)
     pub checkpoint_threshold: usize,
+    /// Bound on bytes appended but not yet flushed (default: unbounded)
+    ///
+    /// Only applies when `sync_on_write` is false.
+    pub throttle: Option<ThrottleConfig>,
+    /// Which applied segments truncation keeps
+    pub retention: WALRetention,
+    /// Encrypt entries with this key (default: plaintext)
+    ///
+    /// Segments written with a key can only be read with the same key.
+    pub encryption: Option<EncryptionKey>,
 }
 
 impl Default for WALConfig {
@@ -28,6 +120,9 @@ impl Default for WALConfig {
             segment_size: 64 * 1024 * 1024, // 64MB
             sync_on_write: true,
             checkpoint_threshold: 1000,
+            throttle: None,
+            retention: WALRetention::default(),
+            encryption: None,
         }
     }
 }
@@ -55,5 +150,47 @@ impl WALConfig {
         self.sync_on_write = sync;
         self
     }
+    
+    /// Apply backpressure once this many unflushed bytes are outstanding
+    pub fn with_throttle(mut self, throttle: ThrottleConfig) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+    
+    /// Set which applied segments truncation keeps
+    pub fn with_retention(mut self, retention: WALRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+    
+    /// Encrypt entries with `key`
+    pub fn with_encryption(mut self, key: EncryptionKey) -> Self {
+        self.encryption = Some(key);
+        self
+    }
+    
+    /// Serialize an entry as stored in a segment, without length prefix
+    pub(crate) fn encode_entry(&self, entry: &WALEntry) -> Result<Vec<u8>> {
+        let serialized = bincode::serialize(entry)
+            .map_err(|e| DeepGraphError::StorageError(format!("WAL serialize error: {}", e)))?;
+        match &self.encryption {
+            Some(key) => key.encrypt(&serialized),
+            None => Ok(serialized),
+        }
+    }
+    
+    /// Deserialize an entry stored by [`WALConfig::encode_entry`]
+    pub(crate) fn decode_entry(&self, bytes: &[u8]) -> Result<WALEntry> {
+        let decrypted;
+        let serialized = match &self.encryption {
+            Some(key) => {
+                decrypted = key.decrypt(bytes)?;
+                &decrypted[..]
+            }
+            None => bytes,
+        };
+        bincode::deserialize(serialized)
+            .map_err(|e| DeepGraphError::StorageError(format!("Deserialize error: {}", e)))
+    }
 }
 
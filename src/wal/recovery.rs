@@ -2,8 +2,9 @@
 //!
 //! Replays log entries to restore database state
 
-use crate::error::{DeepGraphError, Result};
+use crate::error::Result;
 use crate::storage::StorageBackend;
+use crate::wal::log::LSN;
 use crate::wal::{WALConfig, WALEntry, WALOperation};
 use log::{info, debug, warn};
 use std::collections::HashSet;
@@ -24,7 +25,14 @@ impl WALRecovery {
     
     /// Recover database from WAL
     pub fn recover<S: StorageBackend>(&self, storage: &S) -> Result<u64> {
-        info!("Starting WAL recovery from directory: {}", self.config.wal_dir);
+        self.recover_from(storage, 0)
+    }
+    
+    /// Replay committed operations logged at or after `from_lsn`
+    ///
+    /// Use the LSN recorded with a snapshot to catch up after loading it.
+    pub fn recover_from<S: StorageBackend>(&self, storage: &S, from_lsn: LSN) -> Result<u64> {
+        info!("Starting WAL recovery from directory: {} (LSN {})", self.config.wal_dir, from_lsn);
         
         // Find all WAL segments
         let segments = self.find_segments()?;
@@ -59,7 +67,7 @@ impl WALRecovery {
             let entries = self.read_segment(segment_path)?;
             for entry in entries {
                 // Only replay operations from committed transactions
-                if committed_txns.contains(&entry.txn_id) {
+                if entry.lsn >= from_lsn && committed_txns.contains(&entry.txn_id) {
                     self.replay_entry(storage, &entry)?;
                     recovered += 1;
                 }
@@ -70,8 +78,110 @@ impl WALRecovery {
         Ok(recovered)
     }
     
+    /// All logged entries in log order, committed or not
+    pub fn entries(&self) -> Result<Vec<WALEntry>> {
+        let mut entries = Vec::new();
+        for segment_path in &self.find_segments()? {
+            entries.extend(self.read_segment(segment_path)?);
+        }
+        Ok(entries)
+    }
+    
+    /// Data operations of committed transactions logged at or after `from_lsn`
+    ///
+    /// Used to ship the log tail to a replica; control records (begin,
+    /// commit, checkpoint, import progress) are omitted.
+    pub fn committed_tail(&self, from_lsn: LSN) -> Result<Vec<WALEntry>> {
+        let mut entries = self.entries()?;
+        
+        let committed: HashSet<u64> = entries
+            .iter()
+            .filter(|entry| matches!(entry.operation, WALOperation::CommitTxn))
+            .map(|entry| entry.txn_id)
+            .collect();
+        
+        entries.retain(|entry| {
+            entry.lsn >= from_lsn
+                && committed.contains(&entry.txn_id)
+                && !matches!(
+                    entry.operation,
+                    WALOperation::BeginTxn
+                        | WALOperation::CommitTxn
+                        | WALOperation::AbortTxn
+                        | WALOperation::Checkpoint
+                        | WALOperation::ImportBatch { .. }
+                )
+        });
+        entries.sort_by_key(|entry| entry.lsn);
+        Ok(entries)
+    }
+    
+    /// Where a reopened log continues
+    ///
+    /// Returns the segment number, LSN and transaction ID that follow
+    /// everything already in the directory, so a new [`WAL`](crate::wal::WAL)
+    /// appends after earlier runs instead of interleaving with them.
+    pub(crate) fn resume_point(&self) -> Result<(u64, LSN, u64)> {
+        let segments = self.find_segments()?;
+        let next_segment = segments
+            .iter()
+            .filter_map(|path| {
+                let stem = Path::new(path).file_stem()?.to_str()?;
+                stem.strip_prefix("wal-")?.parse::<u64>().ok()
+            })
+            .max()
+            .map_or(0, |last| last + 1);
+        
+        let mut next_lsn = 0;
+        let mut next_txn_id = 1;
+        for segment_path in &segments {
+            for entry in self.read_segment(segment_path)? {
+                next_lsn = next_lsn.max(entry.lsn + 1);
+                next_txn_id = next_txn_id.max(entry.txn_id + 1);
+            }
+        }
+        Ok((next_segment, next_lsn, next_txn_id))
+    }
+    
+    /// LSN of the first entry in a segment, or `None` if it is empty
+    pub(crate) fn first_lsn(&self, path: &str) -> Result<Option<LSN>> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut len_bytes = [0u8; 4];
+        match reader.read_exact(&mut len_bytes) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let mut entry_bytes = vec![0u8; u32::from_le_bytes(len_bytes) as usize];
+        match reader.read_exact(&mut entry_bytes) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let entry = self.config.decode_entry(&entry_bytes)?;
+        Ok(Some(entry.lsn))
+    }
+    
+    /// LSN of the oldest entry still in the log, or `None` if it is empty
+    ///
+    /// Above zero once [`WAL::truncate`](crate::wal::WAL::truncate) removed
+    /// segments; entries before it can only come from a snapshot.
+    pub fn first_available_lsn(&self) -> Result<Option<LSN>> {
+        for segment in self.find_segments()? {
+            if let Some(lsn) = self.first_lsn(&segment)? {
+                return Ok(Some(lsn));
+            }
+        }
+        Ok(None)
+    }
+    
+    /// Decode an entry as stored in this log's segments
+    pub(crate) fn decode_entry(&self, bytes: &[u8]) -> Result<WALEntry> {
+        self.config.decode_entry(bytes)
+    }
+    
     /// Find all WAL segment files
-    fn find_segments(&self) -> Result<Vec<String>> {
+    pub(crate) fn find_segments(&self) -> Result<Vec<String>> {
         let wal_path = Path::new(&self.config.wal_dir);
         
         if !wal_path.exists() {
@@ -111,13 +221,20 @@ impl WALRecovery {
                 Ok(_) => {
                     let len = u32::from_le_bytes(len_bytes) as usize;
                     
-                    // Read entry data
+                    // Read entry data; a crash mid-append leaves a torn
+                    // final entry, which was never committed
                     let mut entry_bytes = vec![0u8; len];
-                    reader.read_exact(&mut entry_bytes)?;
+                    match reader.read_exact(&mut entry_bytes) {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                            warn!("Ignoring torn entry at the end of WAL segment {}", path);
+                            break;
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
                     
                     // Deserialize
-                    let entry: WALEntry = bincode::deserialize(&entry_bytes)
-                        .map_err(|e| DeepGraphError::StorageError(format!("Deserialize error: {}", e)))?;
+                    let entry = self.config.decode_entry(&entry_bytes)?;
                     
                     entries.push(entry);
                 }
@@ -150,6 +267,11 @@ impl WALRecovery {
             WALOperation::DeleteEdge { id } => {
                 storage.delete_edge(*id)?;
             }
+            WALOperation::Batch { ops } => {
+                for op in ops {
+                    op.clone().apply(storage)?;
+                }
+            }
             _ => {
                 // Skip control operations (BeginTxn, CommitTxn, etc.)
             }
@@ -0,0 +1,262 @@
+//! Asynchronous WAL appends
+//!
+//! [`WALWriter`] moves appends onto a dedicated thread. Callers enqueue an
+//! operation and get an [`AppendHandle`] back immediately; the handle
+//! resolves with the entry's LSN once the writer has flushed it, either by
+//! blocking in [`AppendHandle::wait`] or by awaiting it as a future. The
+//! writer drains whatever is queued, appends it and flushes once for the
+//! whole batch, so many concurrent commits share one flush (group commit)
+//! and query threads never wait on disk themselves.
+//!
+//! The queue is bounded: [`WALWriter::append`] blocks while it is full and
+//! [`WALWriter::try_append`] fails with `Throttled` instead. Entries are on
+//! disk once their handle resolves only if the [`WAL`] has `sync_on_write`
+//! set; the batch then shares a single `fdatasync`. Without it the batch is
+//! only handed to the OS.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let wal = Arc::new(WAL::new(WALConfig::new().with_sync(true))?);
+//! let writer = WALWriter::spawn(wal, 1024);
+//! let handle = writer.append(txn_id, WALOperation::CommitTxn)?;
+//! let lsn = handle.await?; // durable from here on
+//! ```
+
+use crate::error::{DeepGraphError, Result};
+use crate::wal::log::LSN;
+use crate::wal::{WALOperation, WAL};
+use log::{debug, info, warn};
+use parking_lot::{Condvar, Mutex};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+
+/// Outcome of one queued append, shared by the writer and the handle
+#[derive(Default)]
+struct Completion {
+    state: Mutex<CompletionState>,
+    done: Condvar,
+}
+
+#[derive(Default)]
+struct CompletionState {
+    result: Option<Result<LSN>>,
+    /// Task to wake when the handle is awaited rather than waited on
+    waker: Option<Waker>,
+}
+
+impl Completion {
+    fn complete(&self, result: Result<LSN>) {
+        let mut state = self.state.lock();
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.done.notify_all();
+    }
+}
+
+/// An entry waiting for the writer thread
+struct QueuedAppend {
+    txn_id: u64,
+    operation: WALOperation,
+    completion: Arc<Completion>,
+}
+
+/// Resolves with an entry's LSN once it is flushed to the log
+///
+/// Usable as a future or through the blocking [`AppendHandle::wait`].
+#[must_use = "the append may not be durable until the handle resolves"]
+pub struct AppendHandle {
+    completion: Arc<Completion>,
+}
+
+impl AppendHandle {
+    /// Whether the append has finished, successfully or not
+    pub fn is_done(&self) -> bool {
+        self.completion.state.lock().result.is_some()
+    }
+
+    /// Block until the entry is durable, returning its LSN
+    pub fn wait(self) -> Result<LSN> {
+        let mut state = self.completion.state.lock();
+        loop {
+            if let Some(result) = state.result.take() {
+                return result;
+            }
+            self.completion.done.wait(&mut state);
+        }
+    }
+}
+
+impl Future for AppendHandle {
+    type Output = Result<LSN>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.completion.state.lock();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Dedicated thread appending to a [`WAL`] from a bounded queue
+pub struct WALWriter {
+    /// Dropped on shutdown, which lets the thread drain and exit
+    queue: Option<SyncSender<QueuedAppend>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl WALWriter {
+    /// Start a writer for `wal` holding at most `queue_capacity` pending appends
+    pub fn spawn(wal: Arc<WAL>, queue_capacity: usize) -> Self {
+        info!("Starting WAL writer (queue capacity {})", queue_capacity);
+        let (queue, pending) = mpsc::sync_channel(queue_capacity.max(1));
+        let handle = std::thread::spawn(move || Self::run(&wal, pending));
+        Self {
+            queue: Some(queue),
+            handle: Some(handle),
+        }
+    }
+
+    /// Enqueue an append, blocking while the queue is full
+    pub fn append(&self, txn_id: u64, operation: WALOperation) -> Result<AppendHandle> {
+        let (append, handle) = Self::queued(txn_id, operation);
+        self.sender()?.send(append).map_err(|_| Self::stopped())?;
+        Ok(handle)
+    }
+
+    /// Enqueue an append, failing with `Throttled` if the queue is full
+    pub fn try_append(&self, txn_id: u64, operation: WALOperation) -> Result<AppendHandle> {
+        let (append, handle) = Self::queued(txn_id, operation);
+        match self.sender()?.try_send(append) {
+            Ok(()) => Ok(handle),
+            Err(TrySendError::Full(_)) => Err(DeepGraphError::Throttled(
+                "WAL writer queue is full".to_string(),
+            )),
+            Err(TrySendError::Disconnected(_)) => Err(Self::stopped()),
+        }
+    }
+
+    /// Flush everything queued so far and stop the thread
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn queued(txn_id: u64, operation: WALOperation) -> (QueuedAppend, AppendHandle) {
+        let completion = Arc::new(Completion::default());
+        let handle = AppendHandle {
+            completion: completion.clone(),
+        };
+        (
+            QueuedAppend {
+                txn_id,
+                operation,
+                completion,
+            },
+            handle,
+        )
+    }
+
+    fn sender(&self) -> Result<&SyncSender<QueuedAppend>> {
+        self.queue.as_ref().ok_or_else(Self::stopped)
+    }
+
+    fn stopped() -> DeepGraphError {
+        DeepGraphError::StorageError("WAL writer has stopped".to_string())
+    }
+
+    /// Writer loop: take a batch, append it, flush once, then resolve it
+    fn run(wal: &WAL, pending: Receiver<QueuedAppend>) {
+        while let Ok(first) = pending.recv() {
+            let mut batch = vec![first];
+            batch.extend(pending.try_iter());
+
+            let count = batch.len();
+            let appended: Vec<(Arc<Completion>, Result<LSN>)> = batch
+                .into_iter()
+                .map(|append| (append.completion, wal.append(append.txn_id, append.operation)))
+                .collect();
+
+            let flushed = wal.flush();
+            match &flushed {
+                Ok(()) => debug!("WAL writer flushed a batch of {} entries", count),
+                Err(e) => warn!("WAL writer failed to flush a batch of {}: {}", count, e),
+            }
+
+            for (completion, result) in appended {
+                let result = match (&flushed, result) {
+                    (Err(e), Ok(_)) => Err(DeepGraphError::StorageError(format!("Failed to flush WAL: {}", e))),
+                    (_, result) => result,
+                };
+                completion.complete(result);
+            }
+        }
+        debug!("WAL writer stopped");
+    }
+
+    fn stop(&mut self) {
+        self.queue = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for WALWriter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+    use crate::wal::{WALConfig, WALReader};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_appends_resolve_once_flushed() {
+        let dir = tempdir().unwrap();
+        let config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_sync(false);
+        let wal = Arc::new(WAL::new(config.clone()).unwrap());
+        let writer = WALWriter::spawn(wal, 4);
+
+        let handles: Vec<AppendHandle> = (1..=10)
+            .map(|txn| writer.append(txn, WALOperation::InsertNode { node: Node::new(vec![]) }).unwrap())
+            .collect();
+        let mut lsns: Vec<LSN> = handles.into_iter().map(|handle| handle.wait().unwrap()).collect();
+        lsns.sort_unstable();
+        assert_eq!(lsns, (0..10).collect::<Vec<_>>());
+
+        // Resolved means flushed: a reader already sees every entry
+        assert_eq!(WALReader::new(config).count(), 10);
+
+        writer.shutdown();
+    }
+
+    #[tokio::test]
+    async fn test_await_append() {
+        let dir = tempdir().unwrap();
+        let config = WALConfig::new()
+            .with_dir(dir.path().to_string_lossy().to_string())
+            .with_sync(false);
+        let writer = WALWriter::spawn(Arc::new(WAL::new(config).unwrap()), 1);
+
+        let lsn = writer.append(1, WALOperation::CommitTxn).unwrap().await.unwrap();
+        assert_eq!(lsn, 0);
+        let handle = writer.try_append(2, WALOperation::CommitTxn).unwrap();
+        assert_eq!(handle.await.unwrap(), 1);
+    }
+}
@@ -167,6 +167,56 @@ def test_type_inference():
         import shutil
         shutil.rmtree(temp_dir)
 
+def test_import_export_round_trip():
+    print("\n=== Testing Import/Export Round Trip ===")
+    
+    temp_dir = tempfile.mkdtemp()
+    
+    try:
+        nodes_file = os.path.join(temp_dir, "nodes.csv")
+        edges_file = os.path.join(temp_dir, "edges.csv")
+        with open(nodes_file, 'w') as f:
+            f.write("id,labels,name\n")
+            f.write("1,Person,Alice\n")
+            f.write("2,Person,Bob\n")
+        with open(edges_file, 'w') as f:
+            f.write("from,to,type,since\n")
+            f.write("1,2,KNOWS,2020\n")
+        
+        storage = deepgraph.GraphStorage()
+        stats = storage.import_csv(nodes_file, edges_file)
+        print(f"  ✅ Imported {stats['nodes_imported']} nodes and {stats['edges_imported']} edges")
+        assert stats['nodes_imported'] == 2
+        assert stats['edges_imported'] == 1
+        assert set(stats['node_id_map']) == {"1", "2"}
+        
+        out_nodes = os.path.join(temp_dir, "out_nodes.json")
+        out_edges = os.path.join(temp_dir, "out_edges.json")
+        exported = storage.export_json(out_nodes, out_edges)
+        assert exported['nodes_exported'] == 2
+        assert exported['edges_exported'] == 1
+        
+        copy = deepgraph.GraphStorage()
+        stats = copy.import_json(out_nodes, out_edges)
+        assert stats['nodes_imported'] == 2
+        assert stats['edges_imported'] == 1
+        
+        gexf = os.path.join(temp_dir, "graph.gexf")
+        assert storage.export(gexf, "gexf")['nodes_exported'] == 2
+        assert os.path.exists(gexf)
+        
+        print("\n✅ Import/Export Round Trip Test Passed!\n")
+        return True
+        
+    except Exception as e:
+        print(f"\n❌ Import/Export Round Trip Test Failed: {e}\n")
+        import traceback
+        traceback.print_exc()
+        return False
+    finally:
+        import shutil
+        shutil.rmtree(temp_dir)
+
 def main():
     print("\n" + "="*50)
     print("DeepGraph Import Python Bindings Test Suite")
@@ -178,6 +228,7 @@ def main():
     results.append(("CSV Import", test_csv_import()))
     results.append(("JSON Import", test_json_import()))
     results.append(("Type Inference", test_type_inference()))
+    results.append(("Import/Export Round Trip", test_import_export_round_trip()))
     
     # Summary
     print("\n" + "="*50)
@@ -163,7 +163,7 @@ fn test_update_operations() {
 #[test]
 fn test_transaction_operations() {
     let storage = Arc::new(GraphStorage::new());
-    let mut tx = Transaction::begin(storage);
+    let mut tx = Transaction::begin(storage).unwrap();
     
     let mut node = Node::new(vec!["Person".to_string()]);
     node.set_property("name".to_string(), "Alice".into());